@@ -1,14 +1,17 @@
-use crate::{eth::subscription::SubscriptionId, types::ReorgOptions};
+use crate::{
+    eth::subscription::SubscriptionId,
+    types::{
+        BlockAction, BridgeEvent, BridgeTemplate, DebugTracingOptions, ExpectedStateDiff,
+        ProtocolLimits, ReorgOptions, RichReceiptOptions, ScheduleOptions, TokenRef,
+    },
+};
 use alloy_primitives::{Address, Bytes, TxHash, B256, B64, U256};
 use alloy_rpc_types::{
     anvil::{Forking, MineOptions},
     pubsub::{Params as SubscriptionParams, SubscriptionKind},
     request::TransactionRequest,
     state::StateOverride,
-    trace::{
-        filter::TraceFilter,
-        geth::{GethDebugTracingCallOptions, GethDebugTracingOptions},
-    },
+    trace::{filter::TraceFilter, geth::GethDebugTracingCallOptions},
     BlockId, BlockNumberOrTag as BlockNumber, Filter, Index,
 };
 use alloy_serde::WithOtherFields;
@@ -293,10 +296,7 @@ pub enum EthRequest {
 
     /// geth's `debug_traceTransaction`  endpoint
     #[cfg_attr(feature = "serde", serde(rename = "debug_traceTransaction"))]
-    DebugTraceTransaction(
-        B256,
-        #[cfg_attr(feature = "serde", serde(default))] GethDebugTracingOptions,
-    ),
+    DebugTraceTransaction(B256, #[cfg_attr(feature = "serde", serde(default))] DebugTracingOptions),
 
     /// geth's `debug_traceCall`  endpoint
     #[cfg_attr(feature = "serde", serde(rename = "debug_traceCall"))]
@@ -471,6 +471,40 @@ pub enum EthRequest {
         B256,
     ),
 
+    /// Sets the ERC-20 token balance of an account
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_dealERC20"))]
+    DealErc20(
+        TokenRef,
+        Address,
+        #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_number"))] U256,
+    ),
+
+    /// Transfers an ERC-721 token to an account, from its current owner if it has one
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_dealERC721"))]
+    DealErc721(
+        TokenRef,
+        Address,
+        #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_number"))] U256,
+    ),
+
+    /// Sets the storage slot of the balances mapping of a token, used by `anvil_dealERC20` and
+    /// `anvil_dealERC721` instead of probing the token's storage
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_setTokenBalanceSlot"))]
+    SetTokenBalanceSlot(
+        TokenRef,
+        #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_number"))] U256,
+    ),
+
+    /// Emits the event of a bridge transfer from the bridge contract, and mints or burns the
+    /// bridged funds like the bridge would
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_simulateBridgeEvent", with = "sequence"))]
+    SimulateBridgeEvent(BridgeEvent),
+
+    /// Registers a bridge template used by `anvil_simulateBridgeEvent`, replacing any template
+    /// with the same name
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_setBridgeTemplate"))]
+    SetBridgeTemplate(String, BridgeTemplate),
+
     /// Sets the coinbase address
     #[cfg_attr(
         feature = "serde",
@@ -493,6 +527,10 @@ pub enum EthRequest {
     )]
     SetLogging(bool),
 
+    /// Sets the levels logged to the log file, using the `RUST_LOG` syntax
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_setLogFilter", with = "sequence"))]
+    SetLogFilter(String),
+
     /// Set the minimum gas price for the node
     #[cfg_attr(
         feature = "serde",
@@ -540,6 +578,59 @@ pub enum EthRequest {
     )]
     LoadState(Bytes),
 
+    /// Serializes all accounts and storage into the compact binary bulk dump format
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "anvil_exportStateBinary", with = "empty_params")
+    )]
+    ExportStateBinary(()),
+
+    /// Saves the runtime settings of the node, such as impersonated accounts, the mining mode and
+    /// the fees, as a named profile
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_saveProfile", with = "sequence"))]
+    SaveProfile(String),
+
+    /// Restores the runtime settings previously saved with `anvil_saveProfile`
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_loadProfile", with = "sequence"))]
+    LoadProfile(String),
+
+    /// Sets the protocol-level limits, such as the maximum code size and the calldata gas cost
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_setLimits", with = "sequence"))]
+    SetLimits(ProtocolLimits),
+
+    /// Registers the storage values and balance changes expected in the next block, replacing
+    /// previous expectations
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_expectStateDiff", with = "sequence"))]
+    ExpectStateDiff(ExpectedStateDiff),
+
+    /// Checks the expectations registered with `anvil_expectStateDiff` against the mined block
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_checkStateDiff", with = "empty_params"))]
+    CheckStateDiff(()),
+
+    /// Schedules a signed transaction to be included in a future block, at a block number or
+    /// timestamp
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_scheduleTx"))]
+    ScheduleTx(Bytes, ScheduleOptions),
+
+    /// Registers a call the miner executes every given number of blocks, returns its id
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_addBlockAction", with = "sequence"))]
+    AddBlockAction(BlockAction),
+
+    /// Removes a call registered with `anvil_addBlockAction`
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_removeBlockAction", with = "sequence"))]
+    RemoveBlockAction(u64),
+
+    /// Mines a number of blocks from the transactions in the pool, split according to a gas target
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_mineBatch"))]
+    MineBatch(
+        /// Number of blocks to mine
+        #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_number"))]
+        U256,
+        /// The gas target of each block, defaults to the configured batch gas target
+        #[cfg_attr(feature = "serde", serde(default, deserialize_with = "deserialize_number_opt"))]
+        Option<U256>,
+    ),
+
     /// Retrieves the Anvil node configuration params
     #[cfg_attr(feature = "serde", serde(rename = "anvil_nodeInfo", with = "empty_params"))]
     NodeInfo(()),
@@ -781,6 +872,11 @@ pub enum EthRequest {
     #[cfg_attr(feature = "serde", serde(rename = "anvil_rollback", with = "sequence"))]
     Rollback(Option<u64>),
 
+    /// Returns the receipt of a transaction with the touched state, the created and destroyed
+    /// contracts, the decoded logs and the gas used by call depth
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_getRichReceipt"))]
+    GetRichReceipt(B256, #[cfg_attr(feature = "serde", serde(default))] Option<RichReceiptOptions>),
+
     /// Wallet
     #[cfg_attr(feature = "serde", serde(rename = "wallet_getCapabilities", with = "empty_params"))]
     WalletGetCapabilities(()),
@@ -836,6 +932,7 @@ pub enum EthRpcCall {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloy_primitives::{address, I256};
 
     #[test]
     fn test_web3_client_version() {
@@ -1216,6 +1313,17 @@ fn test_serde_custom_logging() {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_custom_log_filter() {
+        let s = r#"{"method": "anvil_setLogFilter", "params": ["info,backend=debug"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::SetLogFilter(filter) => assert_eq!(filter, "info,backend=debug"),
+            _ => unreachable!(),
+        }
+    }
+
     #[test]
     fn test_serde_custom_min_gas_price() {
         let s = r#"{"method": "anvil_setMinGasPrice", "params": ["0x0"]}"#;
@@ -1258,6 +1366,219 @@ fn test_serde_custom_dump_state() {
         }
     }
 
+    #[test]
+    fn test_serde_custom_set_limits() {
+        let s = r#"{"method": "anvil_setLimits", "params": [{"codeSizeLimit": 49152, "calldataNonzeroByteGas": 8}] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::SetLimits(limits) => assert_eq!(
+                limits,
+                ProtocolLimits {
+                    code_size_limit: Some(49152),
+                    calldata_nonzero_byte_gas: Some(8),
+                    ..Default::default()
+                }
+            ),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_serde_custom_expect_state_diff() {
+        let s = r#"{"method": "anvil_expectStateDiff", "params": [{
+            "storage": [{
+                "address": "0x6B175474E89094C44Da98b954EedeAC495271d0F",
+                "slot": "0x0000000000000000000000000000000000000000000000000000000000000002",
+                "value": "0x00000000000000000000000000000000000000000000000000000000000003e8"
+            }],
+            "balances": [{"address": "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045", "delta": "-1000"}]
+        }] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::ExpectStateDiff(diff) => {
+                assert_eq!(diff.storage.len(), 1);
+                assert_eq!(diff.storage[0].value, B256::from(U256::from(1000)));
+                assert_eq!(diff.balances[0].delta, I256::try_from(-1000).unwrap());
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "anvil_checkStateDiff", "params": [] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
+    #[test]
+    fn test_serde_custom_schedule() {
+        let s = r#"{"method": "anvil_scheduleTx", "params": ["0x02f8", {"atBlock": 10}] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::ScheduleTx(tx, options) => {
+                assert_eq!(tx, Bytes::from_static(&[0x02, 0xf8]));
+                assert_eq!(options, ScheduleOptions { at_block: Some(10), at_timestamp: None });
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "anvil_scheduleTx", "params": ["0x02f8", {"at_timestamp": 100}] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::ScheduleTx(_, options) => {
+                assert_eq!(options.at_timestamp, Some(100));
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "anvil_addBlockAction", "params": [{
+            "from": "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+            "to": "0x6B175474E89094C44Da98b954EedeAC495271d0F",
+            "data": "0x4585e33b",
+            "every": 5
+        }] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::AddBlockAction(action) => {
+                assert_eq!(action.every, 5);
+                assert_eq!(action.value, U256::ZERO);
+                assert_eq!(action.start_block, None);
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "anvil_removeBlockAction", "params": [1] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::RemoveBlockAction(id) => assert_eq!(id, 1),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_serde_custom_mine_batch() {
+        let s = r#"{"method": "anvil_mineBatch", "params": ["0x3"] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::MineBatch(blocks, gas_target) => {
+                assert_eq!(blocks, U256::from(3));
+                assert!(gas_target.is_none());
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "anvil_mineBatch", "params": [2, 100000] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::MineBatch(blocks, gas_target) => {
+                assert_eq!(blocks, U256::from(2));
+                assert_eq!(gas_target, Some(U256::from(100_000)));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_serde_custom_deal_tokens() {
+        let s = r#"{"method": "anvil_dealERC20", "params": ["0x6B175474E89094C44Da98b954EedeAC495271d0F", "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045", "0x3e8"] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::DealErc20(token, to, amount) => {
+                assert_eq!(
+                    token,
+                    TokenRef::Address(address!("6B175474E89094C44Da98b954EedeAC495271d0F"))
+                );
+                assert_eq!(to, address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045"));
+                assert_eq!(amount, U256::from(1000));
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "anvil_dealERC721", "params": ["NOUN", "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045", 154] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::DealErc721(token, _, id) => {
+                assert_eq!(token, TokenRef::Symbol("NOUN".to_string()));
+                assert_eq!(id, U256::from(154));
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "anvil_setTokenBalanceSlot", "params": ["DAI", "0x2"] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::SetTokenBalanceSlot(token, slot) => {
+                assert_eq!(token, TokenRef::Symbol("DAI".to_string()));
+                assert_eq!(slot, U256::from(2));
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "anvil_simulateBridgeEvent", "params": [{
+            "kind": "depositFinalized",
+            "from": "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+            "to": "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+            "amount": "0xde0b6b3a7640000"
+        }] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::SimulateBridgeEvent(event) => {
+                assert_eq!(event.kind, crate::types::BridgeEventKind::DepositFinalized);
+                assert_eq!(event.amount, U256::from(10).pow(U256::from(18)));
+                assert_eq!(event.template, None);
+                assert_eq!(event.l2_token, None);
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "anvil_setBridgeTemplate", "params": ["custom", {
+            "l1Bridge": "0x99C9fc46f92E8a1c0deC1b1747d010903E884bE1",
+            "l2Bridge": "0x4200000000000000000000000000000000000010",
+            "ethEvents": {
+                "depositInitiated": "Deposit(address indexed from, address to, uint256 amount)"
+            }
+        }] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::SetBridgeTemplate(name, template) => {
+                assert_eq!(name, "custom");
+                assert_eq!(template.eth_events.len(), 1);
+                assert!(template.erc20_events.is_empty());
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_serde_custom_profile() {
+        let s = r#"{"method": "anvil_saveProfile", "params": ["ci"] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::SaveProfile(name) => assert_eq!(name, "ci"),
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "anvil_loadProfile", "params": ["ci"] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::LoadProfile(name) => assert_eq!(name, "ci"),
+            _ => unreachable!(),
+        }
+    }
+
     #[test]
     fn test_serde_custom_load_state() {
         let s = r#"{"method": "anvil_loadState", "params": ["0x0001"] }"#;
@@ -1265,6 +1586,13 @@ fn test_serde_custom_load_state() {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_custom_export_state_binary() {
+        let s = r#"{"method": "anvil_exportStateBinary", "params": [] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
     #[test]
     fn test_serde_custom_snapshot() {
         let s = r#"{"method": "anvil_snapshot", "params": [] }"#;
@@ -1532,7 +1860,24 @@ fn test_serde_debug_trace_transaction() {
 ["0x4a3b0fce2cb9707b0baa68640cf2fe858c8bb4121b2a8cb904ff369d38a560ff", {"disableStorage":
 true}]}"#;
         let value: serde_json::Value = serde_json::from_str(s).unwrap();
-        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::DebugTraceTransaction(_, opts) => {
+                assert!(opts.geth.config.disable_storage.unwrap());
+                assert!(!opts.foundry_debugger);
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "debug_traceTransaction", "params":
+["0x4a3b0fce2cb9707b0baa68640cf2fe858c8bb4121b2a8cb904ff369d38a560ff", {"foundryDebugger":
+true}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::DebugTraceTransaction(_, opts) => assert!(opts.foundry_debugger),
+            _ => unreachable!(),
+        }
     }
 
     #[test]
@@ -1643,6 +1988,27 @@ fn test_remove_pool_transactions() {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_rich_receipt() {
+        let s = r#"{"method": "anvil_getRichReceipt", "params": ["0x4a3b0fce2cb9707b0baa68640cf2fe858c8bb4121b2a8cb904ff369d38a560ff"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::GetRichReceipt(_, options) => assert!(options.is_none()),
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "anvil_getRichReceipt", "params": ["0x4a3b0fce2cb9707b0baa68640cf2fe858c8bb4121b2a8cb904ff369d38a560ff", {"events": ["Transfer(address indexed from, address indexed to, uint256 value)"]}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::GetRichReceipt(_, options) => {
+                assert_eq!(options.unwrap().events.len(), 1);
+            }
+            _ => unreachable!(),
+        }
+    }
+
     #[test]
     fn test_serde_anvil_reorg() {
         // TransactionData::JSON
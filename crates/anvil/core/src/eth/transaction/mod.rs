@@ -1106,6 +1106,9 @@ pub struct TransactionInfo {
     pub out: Option<Bytes>,
     pub nonce: u64,
     pub gas_used: u64,
+    /// The accounts and storage slots touched by the transaction
+    #[serde(default)]
+    pub access_list: AccessList,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
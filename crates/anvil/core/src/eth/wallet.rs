@@ -33,6 +33,11 @@ pub fn get(&self, chain_id: ChainId) -> Option<&Capabilities> {
     pub fn insert(&mut self, chain_id: ChainId, capabilities: Capabilities) {
         self.0.insert(U64::from(chain_id), capabilities);
     }
+
+    /// Removes the capabilities of the wallet API for the specified chain ID.
+    pub fn remove(&mut self, chain_id: ChainId) -> Option<Capabilities> {
+        self.0.remove(&U64::from(chain_id))
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
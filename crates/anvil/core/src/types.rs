@@ -1,9 +1,11 @@
-use alloy_primitives::{Bytes, B256, U256};
+use crate::eth::transaction::ReceiptResponse;
+use alloy_primitives::{Address, Bytes, B256, I256, U256};
 
-use alloy_rpc_types::TransactionRequest;
-use serde::Deserialize;
+use alloy_rpc_types::{trace::geth::GethDebugTracingOptions, AccessList, TransactionRequest};
 #[cfg(feature = "serde")]
 use serde::Serializer;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Represents the result of `eth_getWork`
 /// This may or may not include the block number
@@ -44,3 +46,353 @@ pub enum TransactionData {
     JSON(TransactionRequest),
     Raw(Bytes),
 }
+
+/// Details of a reverted transaction.
+///
+/// When rich errors are enabled, this is returned in the error `data` of `eth_sendTransaction` and
+/// `eth_sendRawTransaction` and added to the `eth_getTransactionReceipt` response, so that the
+/// revert can be inspected without an additional trace call.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevertInfo {
+    /// Hash of the reverted transaction.
+    pub transaction_hash: B256,
+    /// Decoded revert reason, if the revert data could be decoded.
+    pub reason: Option<String>,
+    /// Raw revert data.
+    pub data: Bytes,
+    /// Gas used by the transaction.
+    pub gas_used: u64,
+    /// Compact call trace of the transaction.
+    pub trace: Vec<RevertTraceFrame>,
+}
+
+/// A single call frame of a [`RevertInfo`] trace.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevertTraceFrame {
+    /// Call depth, starting at 0 for the transaction itself.
+    pub depth: usize,
+    /// The kind of call, e.g. `CALL` or `CREATE`.
+    pub kind: String,
+    /// The caller.
+    pub from: Address,
+    /// The callee, or the created contract.
+    pub to: Address,
+    /// Gas used by the call.
+    pub gas_used: u64,
+    /// Whether the call succeeded.
+    pub success: bool,
+    /// Decoded revert reason of a failed call, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Options of `debug_traceTransaction`, which are geth's tracing options, and anvil's own.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugTracingOptions {
+    /// geth's tracing options.
+    #[serde(flatten)]
+    pub geth: GethDebugTracingOptions,
+    /// Whether to return the recorded call trace arena, including the steps, which the debugger
+    /// opens, instead of a geth trace.
+    ///
+    /// Requires the node to record steps, i.e. `--steps-tracing`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub foundry_debugger: bool,
+}
+
+/// Options of `anvil_getRichReceipt`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RichReceiptOptions {
+    /// Solidity signatures of the events used to decode the logs, e.g. `Transfer(address indexed
+    /// from, address indexed to, uint256 value)`.
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+/// A transaction receipt with the details of the execution that would otherwise require trace
+/// calls, returned by `anvil_getRichReceipt`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RichReceipt {
+    #[serde(flatten)]
+    pub receipt: ReceiptResponse,
+    /// The accounts and storage slots touched by the transaction.
+    pub access_list: AccessList,
+    /// The contracts created by the transaction, in order of creation.
+    pub created_contracts: Vec<Address>,
+    /// The contracts that self-destructed during the transaction.
+    pub destroyed_contracts: Vec<Address>,
+    /// The logs of the receipt decoded with the events passed to `anvil_getRichReceipt`, `null`
+    /// for the logs that don't match any of them.
+    pub decoded_logs: Vec<Option<DecodedLog>>,
+    /// The gas used by the calls at every depth, starting at 0 for the transaction itself.
+    ///
+    /// The gas used by a call includes the gas used by its subcalls, but not the intrinsic gas of
+    /// the transaction.
+    pub gas_used_by_depth: Vec<u64>,
+}
+
+/// A log decoded with an event signature.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedLog {
+    /// The signature of the event, e.g. `Transfer(address,address,uint256)`.
+    pub event: String,
+    /// The parameters of the event, in order of declaration.
+    pub params: Vec<DecodedLogParam>,
+}
+
+/// A parameter of a [`DecodedLog`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedLogParam {
+    pub name: String,
+    /// Whether the parameter is a topic of the log.
+    pub indexed: bool,
+    /// The decoded value, formatted as in Solidity.
+    pub value: String,
+}
+
+/// Runtime settings of the node that can be saved with `anvil_saveProfile` and restored with
+/// `anvil_loadProfile`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeProfile {
+    /// Accounts impersonated with `anvil_impersonateAccount`.
+    pub impersonated_accounts: Vec<Address>,
+    /// Whether every account is impersonated.
+    pub auto_impersonate: bool,
+    /// Whether a block is mined for every transaction.
+    pub automine: bool,
+    /// The interval in seconds at which blocks are mined, if interval mining is enabled.
+    pub interval_mining: Option<u64>,
+    /// The base fee of the next block.
+    pub base_fee: u64,
+    /// The minimum gas price, used when EIP-1559 is not active.
+    pub gas_price: u128,
+}
+
+/// A token passed to `anvil_dealERC20` and `anvil_dealERC721`, either its address or the symbol
+/// it was registered with.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TokenRef {
+    Address(Address),
+    Symbol(String),
+}
+
+/// Protocol-level limits enforced by the EVM, set with `anvil_setLimits`.
+///
+/// Unset values use the defaults of the active hardfork.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolLimits {
+    /// The maximum size of deployed code in bytes (EIP-170).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code_size_limit: Option<usize>,
+    /// The maximum size of initcode in bytes (EIP-3860).
+    ///
+    /// Can be at most twice the code size limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initcode_size_limit: Option<usize>,
+    /// The gas charged for every zero byte of calldata.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calldata_zero_byte_gas: Option<u64>,
+    /// The gas charged for every non-zero byte of calldata.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calldata_nonzero_byte_gas: Option<u64>,
+}
+
+impl ProtocolLimits {
+    /// Returns the initcode size limit the EVM derives from the code size limit, which is the
+    /// highest value `initcode_size_limit` can be set to.
+    pub fn max_initcode_size_limit(&self) -> usize {
+        self.code_size_limit
+            .map_or(revm::primitives::MAX_INITCODE_SIZE, |limit| limit.saturating_mul(2))
+    }
+}
+
+/// State changes expected in the next block, registered with `anvil_expectStateDiff`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpectedStateDiff {
+    /// Storage slots expected to hold a value after the block.
+    #[serde(default)]
+    pub storage: Vec<ExpectedStorage>,
+    /// Balances expected to change by a given amount over the block.
+    #[serde(default)]
+    pub balances: Vec<ExpectedBalanceDelta>,
+}
+
+/// The value a storage slot is expected to hold after the block.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpectedStorage {
+    pub address: Address,
+    pub slot: B256,
+    pub value: B256,
+}
+
+/// The amount a balance is expected to change by over the block, negative for decreases.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpectedBalanceDelta {
+    pub address: Address,
+    pub delta: I256,
+}
+
+/// The result of checking the expectations registered with `anvil_expectStateDiff`, returned by
+/// `anvil_checkStateDiff`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateDiffCheck {
+    /// The block the expectations apply to.
+    pub block_number: u64,
+    /// Whether the block was mined. The expectations are only checked once it is.
+    pub mined: bool,
+    /// Whether the block was mined and all expectations held.
+    pub passed: bool,
+    /// The expectations that didn't hold.
+    pub mismatches: Vec<StateDiffMismatch>,
+}
+
+/// An expectation of `anvil_expectStateDiff` that didn't hold.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum StateDiffMismatch {
+    /// A storage slot holds another value.
+    #[serde(rename_all = "camelCase")]
+    Storage { address: Address, slot: B256, expected: B256, actual: B256 },
+    /// A balance changed by another amount.
+    #[serde(rename_all = "camelCase")]
+    Balance { address: Address, expected: I256, actual: I256 },
+}
+
+/// When a transaction passed to `anvil_scheduleTx` is executed, either `atBlock` or `atTimestamp`
+/// must be set.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleOptions {
+    /// The number of the block the transaction is included in.
+    #[serde(default, alias = "at_block")]
+    pub at_block: Option<u64>,
+    /// The transaction is included in the first block with a timestamp at or after this one.
+    #[serde(default, alias = "at_timestamp")]
+    pub at_timestamp: Option<u64>,
+}
+
+/// A call the miner executes every `every` blocks, registered with `anvil_addBlockAction`.
+///
+/// The call is sent from `from` without a signature and executed at the end of the block, after
+/// the transactions of the pool.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockAction {
+    pub from: Address,
+    pub to: Address,
+    #[serde(default)]
+    pub data: Bytes,
+    #[serde(default)]
+    pub value: U256,
+    /// The gas limit of the call, [`BlockAction::DEFAULT_GAS_LIMIT`] by default.
+    #[serde(default)]
+    pub gas: Option<u64>,
+    /// The interval in blocks at which the call is executed.
+    pub every: u64,
+    /// The first block the call is executed in, the next block by default.
+    #[serde(default, alias = "start_block")]
+    pub start_block: Option<u64>,
+}
+
+impl BlockAction {
+    /// The gas limit of the call if none is set.
+    pub const DEFAULT_GAS_LIMIT: u64 = 1_000_000;
+
+    /// Returns whether the action is executed in the block with the given number.
+    pub fn is_due(&self, block_number: u64) -> bool {
+        let start = self.start_block.unwrap_or_default();
+        self.every > 0 && block_number >= start && (block_number - start) % self.every == 0
+    }
+}
+
+/// The step of a bridge transfer simulated with `anvil_simulateBridgeEvent`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BridgeEventKind {
+    /// A deposit sent to the L1 bridge, which locks the deposited funds.
+    DepositInitiated,
+    /// A deposit relayed to the L2 bridge, which mints the deposited funds.
+    DepositFinalized,
+    /// A withdrawal sent to the L2 bridge, which burns the withdrawn funds.
+    WithdrawalInitiated,
+    /// A withdrawal relayed to the L1 bridge, which releases the withdrawn funds.
+    WithdrawalFinalized,
+}
+
+impl BridgeEventKind {
+    /// Returns whether the event is emitted by the L1 bridge.
+    pub fn is_l1(&self) -> bool {
+        matches!(self, Self::DepositInitiated | Self::WithdrawalFinalized)
+    }
+
+    /// Returns whether the event is emitted when the transfer is sent by the user, as opposed to
+    /// relayed to the other chain.
+    pub fn is_initiated(&self) -> bool {
+        matches!(self, Self::DepositInitiated | Self::WithdrawalInitiated)
+    }
+}
+
+/// A bridge transfer simulated with `anvil_simulateBridgeEvent`.
+///
+/// The transfer is of ETH unless the token on the chain of the event is set, `l1Token` for events
+/// of the L1 bridge and `l2Token` for events of the L2 bridge.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BridgeEvent {
+    /// The bridge template, registered with `anvil_setBridgeTemplate`, `optimism` by default.
+    #[serde(default)]
+    pub template: Option<String>,
+    pub kind: BridgeEventKind,
+    pub from: Address,
+    pub to: Address,
+    pub amount: U256,
+    #[serde(default, alias = "l1_token")]
+    pub l1_token: Option<Address>,
+    #[serde(default, alias = "l2_token")]
+    pub l2_token: Option<Address>,
+    #[serde(default, alias = "extra_data")]
+    pub extra_data: Bytes,
+}
+
+/// The contracts and events of a bridge, registered with `anvil_setBridgeTemplate`.
+///
+/// Events are given as Solidity signatures, e.g. `ETHDepositInitiated(address indexed from,
+/// address indexed to, uint256 amount, bytes extraData)`. Their parameters are set from the
+/// fields of the [`BridgeEvent`] with the same name: `from`, `to`, `amount`, `l1Token`, `l2Token`
+/// and `extraData`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BridgeTemplate {
+    /// The address of the L1 bridge contract.
+    #[serde(alias = "l1_bridge")]
+    pub l1_bridge: Address,
+    /// The address of the L2 bridge contract.
+    #[serde(alias = "l2_bridge")]
+    pub l2_bridge: Address,
+    /// The events emitted for ETH transfers.
+    #[serde(default, alias = "eth_events")]
+    pub eth_events: BTreeMap<BridgeEventKind, String>,
+    /// The events emitted for ERC-20 transfers.
+    #[serde(default, alias = "erc20_events")]
+    pub erc20_events: BTreeMap<BridgeEventKind, String>,
+    /// The `l1Token` parameter of the events of ETH transfers.
+    #[serde(default, alias = "eth_l1_token")]
+    pub eth_l1_token: Address,
+    /// The `l2Token` parameter of the events of ETH transfers.
+    #[serde(default, alias = "eth_l2_token")]
+    pub eth_l2_token: Address,
+}
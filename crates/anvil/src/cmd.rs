@@ -1,11 +1,14 @@
 use crate::{
     config::{ForkChoice, DEFAULT_MNEMONIC},
-    eth::{backend::db::SerializableState, pool::transactions::TransactionOrder, EthApi},
+    eth::{
+        backend::db::SerializableState, emulation::EmulatedClient,
+        pool::transactions::TransactionOrder, EthApi,
+    },
     hardfork::OptimismHardfork,
-    AccountGenerator, EthereumHardfork, NodeConfig, CHAIN_ID,
+    AccountGenerator, EthereumHardfork, HardforkSchedule, NodeConfig, CHAIN_ID,
 };
 use alloy_genesis::Genesis;
-use alloy_primitives::{utils::Unit, B256, U256};
+use alloy_primitives::{utils::Unit, Address, B256, U256};
 use alloy_signer_local::coins_bip39::{English, Mnemonic};
 use anvil_server::ServerConfig;
 use clap::Parser;
@@ -47,6 +50,15 @@ pub struct NodeArgs {
     #[arg(long, value_name = "NUM")]
     pub timestamp: Option<u64>,
 
+    /// Fix all nondeterministic inputs, so that RPC responses are identical across runs.
+    ///
+    /// Block timestamps start at a fixed genesis timestamp (2024-01-01, unless `--timestamp` is
+    /// set) and advance by one second, or by `--block-time`, for every block regardless of the
+    /// wall clock. `prevrandao` is derived from the block number, and filter and subscription ids
+    /// are assigned sequentially.
+    #[arg(long, conflicts_with = "mnemonic_random")]
+    pub deterministic: bool,
+
     /// BIP39 mnemonic phrase used for generating accounts.
     /// Cannot be used if `mnemonic_random` or `mnemonic_seed` are used.
     #[arg(long, short, conflicts_with_all = &["mnemonic_seed", "mnemonic_random"])]
@@ -80,6 +92,14 @@ pub struct NodeArgs {
     #[arg(long)]
     pub hardfork: Option<String>,
 
+    /// Hardforks to activate while the node is running.
+    ///
+    /// Comma separated list of `<hardfork>=<block>` or `<hardfork>=@<timestamp>` entries, e.g.
+    /// `cancun=0,prague=1000`. Each block uses the latest hardfork that is active at its number
+    /// or timestamp, falling back to `--hardfork` before the first activation.
+    #[arg(long, value_name = "SCHEDULE")]
+    pub hardfork_schedule: Option<String>,
+
     /// Block time in seconds for interval mining.
     #[arg(short, long, visible_alias = "blockTime", value_name = "SECONDS", value_parser = duration_from_secs_f64)]
     pub block_time: Option<Duration>,
@@ -99,6 +119,12 @@ pub struct NodeArgs {
     #[arg(long, visible_alias = "mixed-mining", requires = "block_time")]
     pub mixed_mining: bool,
 
+    /// The gas target of the blocks mined by `anvil_mineBatch`, defaults to the block gas limit.
+    ///
+    /// Use with `--no-mining` to pool transactions until they are mined in batches of blocks.
+    #[arg(long, value_name = "GAS")]
+    pub batch_gas_target: Option<u64>,
+
     /// The hosts the server will listen on.
     #[arg(
         long,
@@ -114,6 +140,13 @@ pub struct NodeArgs {
     #[arg(long, default_value = "fees")]
     pub order: TransactionOrder,
 
+    /// Emulate the RPC quirks of another execution client: geth, erigon or nethermind.
+    ///
+    /// Adjusts the codes and messages of RPC errors, e.g. for reverts and rejected transactions,
+    /// and the version returned by `web3_clientVersion` to match the client.
+    #[arg(long, value_name = "CLIENT")]
+    pub emulate: Option<EmulatedClient>,
+
     /// Initialize the genesis block with the given `genesis.json` file.
     #[arg(long, value_name = "PATH", value_parser= read_genesis_file)]
     pub init: Option<Genesis>,
@@ -164,6 +197,36 @@ pub struct NodeArgs {
     )]
     pub load_state: Option<SerializableState>,
 
+    /// Initialize the chain from a binary state dump.
+    ///
+    /// The dump is produced by `anvil_exportStateBinary` and is much faster to load than a JSON
+    /// state file. If the value is a directory, the dump is read from `<VALUE>/state.bin`.
+    #[arg(
+        long,
+        value_name = "PATH",
+        value_parser = SerializableState::parse_binary,
+        conflicts_with_all = &["init", "load_state", "state"]
+    )]
+    pub import_state: Option<SerializableState>,
+
+    /// Initialize the chain from a JSON state file or a binary state dump, detecting the format.
+    ///
+    /// If the value is a directory, the state is read from `<VALUE>/state.bin` if it exists, and
+    /// from `<VALUE>/state.json` otherwise. See --load-state-accounts to only load some accounts.
+    #[arg(
+        long,
+        value_name = "PATH",
+        value_parser = SerializableState::parse_any,
+        conflicts_with_all = &["init", "load_state", "import_state", "state"]
+    )]
+    pub load_state_on_start: Option<SerializableState>,
+
+    /// Only load the given accounts from the state passed to --load-state-on-start.
+    ///
+    /// The blocks and transactions of the state are not loaded.
+    #[arg(long, value_name = "ADDRESSES", value_delimiter = ',', requires = "load_state_on_start")]
+    pub load_state_accounts: Vec<Address>,
+
     #[arg(long, help = IPC_HELP, value_name = "PATH", visible_alias = "ipcpath")]
     pub ipc: Option<Option<String>>,
 
@@ -193,6 +256,34 @@ pub struct NodeArgs {
     /// Path to the cache directory where states are stored.    
     #[arg(long, value_name = "PATH")]
     pub cache_path: Option<PathBuf>,
+
+    /// Write structured JSON logs to the given file.
+    ///
+    /// The levels logged to the file default to `RUST_LOG`, or `info`, and can be changed at
+    /// runtime with `anvil_setLogFilter`, for example `info,backend=debug`.
+    #[arg(long, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
+    /// Rotate the log file once it exceeds the given size, for example `10MB`.
+    ///
+    /// The previous log files are kept as `<PATH>.1` to `<PATH>.5`.
+    #[arg(long, value_name = "SIZE", requires = "log_file", value_parser = parse_size)]
+    pub log_rotate: Option<u64>,
+
+    /// Register a token symbol, so that `anvil_dealERC20` and `anvil_dealERC721` accept it
+    /// instead of the token address.
+    ///
+    /// For example `USDC=0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48`.
+    #[arg(long, value_name = "SYMBOL=ADDRESS", value_parser = parse_token_symbol)]
+    pub token: Vec<(String, Address)>,
+
+    /// Set the storage slot of the balances mapping of a token, for example `<ADDRESS>=9`.
+    ///
+    /// `anvil_dealERC20` and `anvil_dealERC721` find the slot holding a balance by probing the
+    /// slots read by `balanceOf`, which fails for tokens that compute balances. The slot can also
+    /// be set at runtime with `anvil_setTokenBalanceSlot`.
+    #[arg(long, value_name = "ADDRESS=SLOT", value_parser = parse_token_balance_slot)]
+    pub token_balance_slot: Vec<(Address, U256)>,
 }
 
 #[cfg(windows)]
@@ -222,18 +313,33 @@ pub fn into_node_config(self) -> eyre::Result<NodeConfig> {
             }
             None => None,
         };
+        let hardfork_schedule = self
+            .hardfork_schedule
+            .as_deref()
+            .map(|schedule| HardforkSchedule::parse(schedule, self.evm.optimism))
+            .transpose()?;
+        let account_generator = self.account_generator();
+        let load_state_on_start = self.load_state_on_start.map(|mut state| {
+            if !self.load_state_accounts.is_empty() {
+                state.retain_accounts(&self.load_state_accounts);
+            }
+            state
+        });
 
-        Ok(NodeConfig::default()
+        let config = NodeConfig::default()
             .with_gas_limit(self.evm.gas_limit)
             .disable_block_gas_limit(self.evm.disable_block_gas_limit)
             .with_gas_price(self.evm.gas_price)
             .with_hardfork(hardfork)
+            .with_hardfork_schedule(hardfork_schedule)
             .with_blocktime(self.block_time)
             .with_no_mining(self.no_mining)
             .with_mixed_mining(self.mixed_mining, self.block_time)
-            .with_account_generator(self.account_generator())
+            .with_batch_gas_target(self.batch_gas_target)
+            .with_account_generator(account_generator)
             .with_genesis_balance(genesis_balance)
             .with_genesis_timestamp(self.timestamp)
+            .with_deterministic(self.deterministic)
             .with_port(self.port)
             .with_fork_choice(match (self.evm.fork_block_number, self.evm.fork_transaction_hash) {
                 (Some(block), None) => Some(ForkChoice::Block(block)),
@@ -256,15 +362,24 @@ pub fn into_node_config(self) -> eyre::Result<NodeConfig> {
             .set_config_out(self.config_out)
             .with_chain_id(self.evm.chain_id)
             .with_transaction_order(self.order)
+            .with_emulate(self.emulate)
             .with_genesis(self.init)
             .with_steps_tracing(self.evm.steps_tracing)
             .with_print_logs(!self.evm.disable_console_log)
+            .with_rich_errors(self.evm.rich_errors)
             .with_auto_impersonate(self.evm.auto_impersonate)
             .with_ipc(self.ipc)
             .with_code_size_limit(self.evm.code_size_limit)
             .disable_code_size_limit(self.evm.disable_code_size_limit)
+            .with_initcode_size_limit(self.evm.initcode_size_limit)
+            .with_calldata_gas(self.evm.calldata_zero_byte_gas, self.evm.calldata_nonzero_byte_gas)
             .set_pruned_history(self.prune_history)
-            .with_init_state(self.load_state.or_else(|| self.state.and_then(|s| s.state)))
+            .with_init_state(
+                self.load_state
+                    .or(self.import_state)
+                    .or(load_state_on_start)
+                    .or_else(|| self.state.and_then(|s| s.state)),
+            )
             .with_transaction_block_keeper(self.transaction_block_keeper)
             .with_max_persisted_states(self.max_persisted_states)
             .with_optimism(self.evm.optimism)
@@ -272,7 +387,23 @@ pub fn into_node_config(self) -> eyre::Result<NodeConfig> {
             .with_disable_default_create2_deployer(self.evm.disable_default_create2_deployer)
             .with_slots_in_an_epoch(self.slots_in_an_epoch)
             .with_memory_limit(self.evm.memory_limit)
-            .with_cache_path(self.cache_path))
+            .with_log_file(self.log_file)
+            .with_log_rotate(self.log_rotate)
+            .with_token_symbols(self.token)
+            .with_token_balance_slots(self.token_balance_slot)
+            .with_cache_path(self.cache_path);
+
+        let limits = config.protocol_limits();
+        if let Some(limit) = limits.initcode_size_limit {
+            let max = limits.max_initcode_size_limit();
+            if limit > max {
+                eyre::bail!(
+                    "--initcode-size-limit {limit} exceeds twice the code size limit ({max})"
+                );
+            }
+        }
+
+        Ok(config)
     }
 
     fn account_generator(&self) -> AccountGenerator {
@@ -530,6 +661,19 @@ pub struct AnvilEvmArgs {
     )]
     pub disable_code_size_limit: bool,
 
+    /// EIP-3860: Initcode size limit in bytes. Can only be lowered below twice the code size
+    /// limit. By default, it is 0xC000 (~49kb).
+    #[arg(long, value_name = "INITCODE_SIZE", help_heading = "Environment config")]
+    pub initcode_size_limit: Option<usize>,
+
+    /// The gas charged for every zero byte of calldata. By default, it is 4.
+    #[arg(long, value_name = "GAS", help_heading = "Environment config")]
+    pub calldata_zero_byte_gas: Option<u64>,
+
+    /// The gas charged for every non-zero byte of calldata. By default, it is 16.
+    #[arg(long, value_name = "GAS", help_heading = "Environment config")]
+    pub calldata_nonzero_byte_gas: Option<u64>,
+
     /// The gas price.
     #[arg(long, help_heading = "Environment config")]
     pub gas_price: Option<u128>,
@@ -559,12 +703,24 @@ pub struct AnvilEvmArgs {
     #[arg(long, visible_alias = "no-console-log")]
     pub disable_console_log: bool,
 
+    /// Return rich error payloads for reverted transactions.
+    ///
+    /// With automine enabled, `eth_sendTransaction` and `eth_sendRawTransaction` wait for the
+    /// transaction to be mined and return an error containing the decoded revert reason, a compact
+    /// call trace and the gas used if it reverted. The same details are added to the
+    /// `eth_getTransactionReceipt` response of reverted transactions.
+    #[arg(long)]
+    pub rich_errors: bool,
+
     /// Enables automatic impersonation on startup. This allows any transaction sender to be
     /// simulated as different accounts, which is useful for testing contract behavior.
     #[arg(long, visible_alias = "auto-unlock")]
     pub auto_impersonate: bool,
 
     /// Run an Optimism chain
+    ///
+    /// This is enabled automatically when forking an OP-stack chain, e.g. Optimism, Base or any
+    /// chain with the `L1Block` predeploy.
     #[arg(long, visible_alias = "optimism")]
     pub optimism: bool,
 
@@ -769,6 +925,41 @@ fn duration_from_secs_f64(s: &str) -> Result<Duration, String> {
     Duration::try_from_secs_f64(s).map_err(|e| e.to_string())
 }
 
+/// Parses a size in bytes with an optional `KB`, `MB` or `GB` suffix, in powers of 1024.
+fn parse_size(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let num = num.parse::<u64>().map_err(|_| format!("invalid size `{s}`"))?;
+    let multiplier = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1 << 10,
+        "m" | "mb" | "mib" => 1 << 20,
+        "g" | "gb" | "gib" => 1 << 30,
+        _ => return Err(format!("invalid size unit `{unit}`, expected `KB`, `MB` or `GB`")),
+    };
+    match num.checked_mul(multiplier) {
+        Some(0) => Err("size must be greater than 0".to_string()),
+        Some(size) => Ok(size),
+        None => Err(format!("size `{s}` is too large")),
+    }
+}
+
+/// Parses a `SYMBOL=ADDRESS` token symbol.
+fn parse_token_symbol(s: &str) -> Result<(String, Address), String> {
+    let (symbol, address) = s.split_once('=').ok_or("expected `SYMBOL=ADDRESS`")?;
+    let address = address.trim().parse().map_err(|err| format!("invalid address: {err}"))?;
+    Ok((symbol.trim().to_string(), address))
+}
+
+/// Parses an `ADDRESS=SLOT` token balance slot.
+fn parse_token_balance_slot(s: &str) -> Result<(Address, U256), String> {
+    let (address, slot) = s.split_once('=').ok_or("expected `ADDRESS=SLOT`")?;
+    let address = address.trim().parse().map_err(|err| format!("invalid address: {err}"))?;
+    let slot = slot.trim().parse().map_err(|err| format!("invalid slot: {err}"))?;
+    Ok((address, slot))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::EthereumHardfork;
@@ -867,6 +1058,33 @@ fn can_parse_disable_block_gas_limit() {
         assert!(args.is_err());
     }
 
+    #[test]
+    fn can_parse_protocol_limits() {
+        let args: NodeArgs = NodeArgs::parse_from([
+            "anvil",
+            "--code-size-limit",
+            "1000",
+            "--initcode-size-limit",
+            "2000",
+            "--calldata-nonzero-byte-gas",
+            "8",
+        ]);
+        let limits = args.into_node_config().unwrap().protocol_limits();
+        assert_eq!(limits.code_size_limit, Some(1000));
+        assert_eq!(limits.initcode_size_limit, Some(2000));
+        assert_eq!(limits.calldata_zero_byte_gas, None);
+        assert_eq!(limits.calldata_nonzero_byte_gas, Some(8));
+
+        let args: NodeArgs = NodeArgs::parse_from([
+            "anvil",
+            "--code-size-limit",
+            "1000",
+            "--initcode-size-limit",
+            "2001",
+        ]);
+        assert!(args.into_node_config().is_err());
+    }
+
     #[test]
     fn can_parse_disable_code_size_limit() {
         let args: NodeArgs = NodeArgs::parse_from(["anvil", "--disable-code-size-limit"]);
@@ -912,4 +1130,75 @@ fn can_parse_host() {
             ["::1", "1.1.1.1", "2.2.2.2"].map(|ip| ip.parse::<IpAddr>().unwrap()).to_vec()
         );
     }
+
+    #[test]
+    fn can_parse_load_state_accounts() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("state.json");
+        std::fs::write(&path, r#"{"block": null, "accounts": {}}"#).unwrap();
+        let path = path.to_str().unwrap();
+
+        let args = NodeArgs::parse_from([
+            "anvil",
+            "--load-state-on-start",
+            path,
+            "--load-state-accounts",
+            "0x0000000000000000000000000000000000000123,0x0000000000000000000000000000000000000456",
+        ]);
+        assert!(args.load_state_on_start.is_some());
+        assert_eq!(args.load_state_accounts.len(), 2);
+
+        assert!(NodeArgs::try_parse_from([
+            "anvil",
+            "--load-state-accounts",
+            "0x0000000000000000000000000000000000000123"
+        ])
+        .is_err());
+        assert!(NodeArgs::try_parse_from([
+            "anvil",
+            "--load-state-on-start",
+            path,
+            "--load-state",
+            path
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn can_parse_log_rotate() {
+        let args =
+            NodeArgs::parse_from(["anvil", "--log-file", "anvil.log", "--log-rotate", "10MB"]);
+        assert_eq!(args.log_file, Some(PathBuf::from("anvil.log")));
+        assert_eq!(args.log_rotate, Some(10 * 1024 * 1024));
+
+        assert_eq!(parse_size("512"), Ok(512));
+        assert_eq!(parse_size("2kb"), Ok(2048));
+        assert_eq!(parse_size("1 GiB"), Ok(1 << 30));
+        assert!(parse_size("0MB").is_err());
+        assert!(parse_size("10TB").is_err());
+        assert!(parse_size("MB").is_err());
+
+        // requires a log file
+        assert!(NodeArgs::try_parse_from(["anvil", "--log-rotate", "10MB"]).is_err());
+    }
+
+    #[test]
+    fn can_parse_tokens() {
+        let dai = "0x6B175474E89094C44Da98b954EedeAC495271d0F";
+        let args = NodeArgs::parse_from([
+            "anvil",
+            "--token",
+            &format!("DAI={dai}"),
+            "--token-balance-slot",
+            &format!("{dai}=2"),
+            "--token-balance-slot",
+            &format!("{dai}=0x3"),
+        ]);
+        let dai = dai.parse::<Address>().unwrap();
+        assert_eq!(args.token, vec![("DAI".to_string(), dai)]);
+        assert_eq!(args.token_balance_slot, vec![(dai, U256::from(2)), (dai, U256::from(3))]);
+
+        assert!(NodeArgs::try_parse_from(["anvil", "--token", "DAI"]).is_err());
+        assert!(NodeArgs::try_parse_from(["anvil", "--token-balance-slot", "DAI=2"]).is_err());
+    }
 }
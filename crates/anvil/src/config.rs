@@ -8,17 +8,20 @@
             mem::fork_db::ForkedDatabase,
             time::duration_since_unix_epoch,
         },
+        emulation::EmulatedClient,
         fees::{INITIAL_BASE_FEE, INITIAL_GAS_PRICE},
         pool::transactions::{PoolTransaction, TransactionOrder},
     },
-    hardfork::{ChainHardfork, OptimismHardfork},
+    hardfork::{ChainHardfork, HardforkSchedule, OptimismHardfork},
     mem::{self, in_memory_db::MemDb},
     EthereumHardfork, FeeManager, PrecompileFactory,
 };
 use alloy_consensus::BlockHeader;
 use alloy_genesis::Genesis;
 use alloy_network::{AnyNetwork, TransactionResponse};
-use alloy_primitives::{hex, map::HashMap, utils::Unit, BlockNumber, TxHash, U256};
+use alloy_primitives::{
+    address, hex, map::HashMap, utils::Unit, Address, BlockNumber, TxHash, U256,
+};
 use alloy_provider::Provider;
 use alloy_rpc_types::{Block, BlockNumberOrTag};
 use alloy_signer::Signer;
@@ -27,6 +30,7 @@
     MnemonicBuilder, PrivateKeySigner,
 };
 use alloy_transport::TransportError;
+use anvil_core::types::ProtocolLimits;
 use anvil_server::ServerConfig;
 use eyre::{Context, Result};
 use foundry_common::{
@@ -65,6 +69,8 @@
 pub const CHAIN_ID: u64 = 31337;
 /// The default gas limit for all transactions
 pub const DEFAULT_GAS_LIMIT: u128 = 30_000_000;
+/// Genesis block timestamp used in deterministic mode, `2024-01-01T00:00:00Z`
+pub const DETERMINISTIC_GENESIS_TIMESTAMP: u64 = 1_704_067_200;
 /// Default mnemonic for dev accounts
 pub const DEFAULT_MNEMONIC: &str = "test test test test test test test test test test test junk";
 
@@ -100,6 +106,8 @@ pub struct NodeConfig {
     pub blob_excess_gas_and_price: Option<BlobExcessGasAndPrice>,
     /// The hardfork to use
     pub hardfork: Option<ChainHardfork>,
+    /// Hardforks to activate at configured blocks or timestamps
+    pub hardfork_schedule: Option<HardforkSchedule>,
     /// Signer accounts that will be initialised with `genesis_balance` in the genesis block
     pub genesis_accounts: Vec<PrivateKeySigner>,
     /// Native token balance of every genesis account in the genesis block
@@ -114,6 +122,9 @@ pub struct NodeConfig {
     pub no_mining: bool,
     /// Enables auto and interval mining mode
     pub mixed_mining: bool,
+    /// The default gas target of the blocks mined by `anvil_mineBatch`, defaults to the block gas
+    /// limit
+    pub batch_gas_target: Option<u64>,
     /// port to use for the server
     pub port: u16,
     /// maximum number of transactions in a block
@@ -156,10 +167,23 @@ pub struct NodeConfig {
     pub enable_steps_tracing: bool,
     /// Enable printing of `console.log` invocations.
     pub print_logs: bool,
+    /// Return rich error payloads (revert reason, trace, gas used) for reverted transactions
+    pub rich_errors: bool,
+    /// The execution client whose RPC errors and client version to emulate
+    pub emulate: Option<EmulatedClient>,
     /// Enable auto impersonation of accounts on startup
     pub enable_auto_impersonate: bool,
     /// Configure the code size limit
     pub code_size_limit: Option<usize>,
+    /// Whether the code size limit is disabled, also when the limits are reset with
+    /// `anvil_setLimits`
+    pub disable_code_size_limit: bool,
+    /// Configure the initcode size limit
+    pub initcode_size_limit: Option<usize>,
+    /// The gas charged for every zero byte of calldata
+    pub calldata_zero_byte_gas: Option<u64>,
+    /// The gas charged for every non-zero byte of calldata
+    pub calldata_nonzero_byte_gas: Option<u64>,
     /// Configures how to remove historic state.
     ///
     /// If set to `Some(num)` keep latest num state in memory only.
@@ -184,8 +208,18 @@ pub struct NodeConfig {
     pub odyssey: bool,
     /// Do not print log messages.
     pub silent: bool,
+    /// The file structured logs are written to.
+    pub log_file: Option<PathBuf>,
+    /// The size in bytes above which the log file is rotated.
+    pub log_rotate: Option<u64>,
     /// The path where states are cached.
     pub cache_path: Option<PathBuf>,
+    /// Fix all nondeterministic inputs, such as the wall clock and random values
+    pub deterministic: bool,
+    /// Token symbols that can be passed to `anvil_dealERC20` and `anvil_dealERC721`
+    pub token_symbols: Vec<(String, Address)>,
+    /// Storage slots of the balances mappings of tokens, used instead of probing their storage
+    pub token_balance_slots: Vec<(Address, U256)>,
 }
 
 impl NodeConfig {
@@ -415,6 +449,7 @@ fn default() -> Self {
             disable_block_gas_limit: false,
             gas_price: None,
             hardfork: None,
+            hardfork_schedule: None,
             signer_accounts: genesis_accounts.clone(),
             genesis_timestamp: None,
             genesis_accounts,
@@ -423,6 +458,7 @@ fn default() -> Self {
             block_time: None,
             no_mining: false,
             mixed_mining: false,
+            batch_gas_target: None,
             port: NODE_PORT,
             // TODO make this something dependent on block capacity
             max_transactions: 1_000,
@@ -435,6 +471,8 @@ fn default() -> Self {
             enable_tracing: true,
             enable_steps_tracing: false,
             print_logs: true,
+            rich_errors: false,
+            emulate: None,
             enable_auto_impersonate: false,
             no_storage_caching: false,
             server_config: Default::default(),
@@ -451,6 +489,10 @@ fn default() -> Self {
             compute_units_per_second: ALCHEMY_FREE_TIER_CUPS,
             ipc_path: None,
             code_size_limit: None,
+            disable_code_size_limit: false,
+            initcode_size_limit: None,
+            calldata_zero_byte_gas: None,
+            calldata_nonzero_byte_gas: None,
             prune_history: Default::default(),
             max_persisted_states: None,
             init_state: None,
@@ -462,7 +504,12 @@ fn default() -> Self {
             precompile_factory: None,
             odyssey: false,
             silent: false,
+            log_file: None,
+            log_rotate: None,
             cache_path: None,
+            deterministic: false,
+            token_symbols: vec![],
+            token_balance_slots: vec![],
         }
     }
 }
@@ -506,12 +553,26 @@ pub fn get_hardfork(&self) -> ChainHardfork {
         if let Some(hardfork) = self.hardfork {
             return hardfork;
         }
+        if let Some(hardfork) = self
+            .hardfork_schedule
+            .as_ref()
+            .and_then(|schedule| schedule.hardfork_at(0, self.get_genesis_timestamp()))
+        {
+            return hardfork;
+        }
         if self.enable_optimism {
             return OptimismHardfork::default().into();
         }
         EthereumHardfork::default().into()
     }
 
+    /// Sets the hardforks to activate at configured blocks or timestamps
+    #[must_use]
+    pub fn with_hardfork_schedule(mut self, schedule: Option<HardforkSchedule>) -> Self {
+        self.hardfork_schedule = schedule;
+        self
+    }
+
     /// Sets a custom code size limit
     #[must_use]
     pub fn with_code_size_limit(mut self, code_size_limit: Option<usize>) -> Self {
@@ -524,9 +585,39 @@ pub fn disable_code_size_limit(mut self, disable_code_size_limit: bool) -> Self
         if disable_code_size_limit {
             self.code_size_limit = Some(usize::MAX);
         }
+        self.disable_code_size_limit = disable_code_size_limit;
         self
     }
 
+    /// Sets a custom initcode size limit
+    #[must_use]
+    pub fn with_initcode_size_limit(mut self, initcode_size_limit: Option<usize>) -> Self {
+        self.initcode_size_limit = initcode_size_limit;
+        self
+    }
+
+    /// Sets the gas charged for zero and non-zero bytes of calldata
+    #[must_use]
+    pub fn with_calldata_gas(
+        mut self,
+        zero_byte_gas: Option<u64>,
+        nonzero_byte_gas: Option<u64>,
+    ) -> Self {
+        self.calldata_zero_byte_gas = zero_byte_gas;
+        self.calldata_nonzero_byte_gas = nonzero_byte_gas;
+        self
+    }
+
+    /// Returns the protocol-level limits to enforce in the EVM
+    pub fn protocol_limits(&self) -> ProtocolLimits {
+        ProtocolLimits {
+            code_size_limit: self.code_size_limit,
+            initcode_size_limit: self.initcode_size_limit,
+            calldata_zero_byte_gas: self.calldata_zero_byte_gas,
+            calldata_nonzero_byte_gas: self.calldata_nonzero_byte_gas,
+        }
+    }
+
     /// Sets the init state if any
     #[must_use]
     pub fn with_init_state(mut self, init_state: Option<SerializableState>) -> Self {
@@ -642,6 +733,7 @@ pub fn with_genesis(mut self, genesis: Option<Genesis>) -> Self {
     pub fn get_genesis_timestamp(&self) -> u64 {
         self.genesis_timestamp
             .or_else(|| self.genesis.as_ref().map(|g| g.timestamp))
+            .or_else(|| self.deterministic.then_some(DETERMINISTIC_GENESIS_TIMESTAMP))
             .unwrap_or_else(|| duration_since_unix_epoch().as_secs())
     }
 
@@ -716,6 +808,13 @@ pub fn with_no_mining(mut self, no_mining: bool) -> Self {
         self
     }
 
+    /// Sets the default gas target of the blocks mined by `anvil_mineBatch`
+    #[must_use]
+    pub fn with_batch_gas_target(mut self, batch_gas_target: Option<u64>) -> Self {
+        self.batch_gas_target = batch_gas_target;
+        self
+    }
+
     /// Sets the slots in an epoch
     #[must_use]
     pub fn with_slots_in_an_epoch(mut self, slots_in_an_epoch: u64) -> Self {
@@ -863,6 +962,20 @@ pub fn with_print_logs(mut self, print_logs: bool) -> Self {
         self
     }
 
+    /// Sets whether to return rich error payloads for reverted transactions.
+    #[must_use]
+    pub fn with_rich_errors(mut self, rich_errors: bool) -> Self {
+        self.rich_errors = rich_errors;
+        self
+    }
+
+    /// Sets the execution client to emulate.
+    #[must_use]
+    pub fn with_emulate(mut self, emulate: Option<EmulatedClient>) -> Self {
+        self.emulate = emulate;
+        self
+    }
+
     /// Sets whether to enable autoImpersonate
     #[must_use]
     pub fn with_auto_impersonate(mut self, enable_auto_impersonate: bool) -> Self {
@@ -925,6 +1038,8 @@ pub fn block_cache_path(&self, block: u64) -> Option<PathBuf> {
     }
 
     /// Sets whether to enable optimism support
+    ///
+    /// This is enabled automatically when forking an OP-stack chain.
     #[must_use]
     pub fn with_optimism(mut self, enable_optimism: bool) -> Self {
         self.enable_optimism = enable_optimism;
@@ -964,6 +1079,20 @@ pub fn set_silent(mut self, silent: bool) -> Self {
         self
     }
 
+    /// Sets the file structured logs are written to
+    #[must_use]
+    pub fn with_log_file(mut self, log_file: Option<PathBuf>) -> Self {
+        self.log_file = log_file;
+        self
+    }
+
+    /// Sets the size in bytes above which the log file is rotated
+    #[must_use]
+    pub fn with_log_rotate(mut self, log_rotate: Option<u64>) -> Self {
+        self.log_rotate = log_rotate;
+        self
+    }
+
     /// Sets the path where states are cached
     #[must_use]
     pub fn with_cache_path(mut self, cache_path: Option<PathBuf>) -> Self {
@@ -971,6 +1100,31 @@ pub fn with_cache_path(mut self, cache_path: Option<PathBuf>) -> Self {
         self
     }
 
+    /// Sets whether to fix all nondeterministic inputs.
+    ///
+    /// Block timestamps advance by a fixed step for every block instead of following the wall
+    /// clock, starting at [`DETERMINISTIC_GENESIS_TIMESTAMP`] unless a genesis timestamp is set,
+    /// and `prevrandao` and ids are derived instead of random.
+    #[must_use]
+    pub fn with_deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self
+    }
+
+    /// Sets the token symbols that can be passed to `anvil_dealERC20` and `anvil_dealERC721`
+    #[must_use]
+    pub fn with_token_symbols(mut self, symbols: Vec<(String, Address)>) -> Self {
+        self.token_symbols = symbols;
+        self
+    }
+
+    /// Sets the storage slots of the balances mappings of tokens
+    #[must_use]
+    pub fn with_token_balance_slots(mut self, slots: Vec<(Address, U256)>) -> Self {
+        self.token_balance_slots = slots;
+        self
+    }
+
     /// Configures everything related to env, backend and database and returns the
     /// [Backend](mem::Backend)
     ///
@@ -1244,6 +1398,29 @@ pub async fn setup_fork_db_config(
             chain_id
         };
         let override_chain_id = self.chain_id;
+
+        // OP-stack chains accept deposit transactions, so enable op-stack support when forking
+        // one, unless it's already enabled
+        if !self.enable_optimism {
+            let remote_chain_id = if let Some(fork_chain_id) = fork_chain_id {
+                fork_chain_id.to()
+            } else if override_chain_id.is_some() {
+                provider.get_chain_id().await.wrap_err("failed to fetch network chain ID")?
+            } else {
+                chain_id
+            };
+            if is_op_stack_fork(&provider, remote_chain_id, fork_block_number).await {
+                trace!(target: "node", chain_id = remote_chain_id, "enabling op-stack support");
+                self.enable_optimism = true;
+                env.handler_cfg.is_optimism = true;
+                if self.hardfork.is_none() && self.hardfork_schedule.is_none() {
+                    let hardfork = OptimismHardfork::default();
+                    env.handler_cfg.spec_id = hardfork.into();
+                    self.hardfork = Some(hardfork.into());
+                }
+            }
+        }
+
         // apply changes such as difficulty -> prevrandao and chain specifics for current chain id
         apply_chain_and_block_specific_env_changes::<AnyNetwork>(env, &block);
 
@@ -1320,6 +1497,29 @@ pub(crate) fn gas_limit(&self) -> u128 {
     }
 }
 
+/// The address of the `L1Block` predeploy of OP-stack chains
+const L1_BLOCK_PREDEPLOY: Address = address!("4200000000000000000000000000000000000015");
+
+/// Returns whether the forked chain is an OP-stack chain.
+///
+/// Known chains are matched by their chain ID, any other chain is considered an OP-stack chain if
+/// it has code at the `L1Block` predeploy address.
+async fn is_op_stack_fork(
+    provider: &Arc<RetryProvider>,
+    chain_id: u64,
+    block_number: BlockNumber,
+) -> bool {
+    match alloy_chains::NamedChain::try_from(chain_id) {
+        Ok(chain) if chain.is_optimism() => true,
+        Ok(chain) if chain.is_ethereum() || chain.is_arbitrum() => false,
+        _ => provider
+            .get_code_at(L1_BLOCK_PREDEPLOY)
+            .number(block_number)
+            .await
+            .is_ok_and(|code| !code.is_empty()),
+    }
+}
+
 /// If the fork choice is a block number, simply return it with an empty list of transactions.
 /// If the fork choice is a transaction hash, determine the block that the transaction was mined in,
 /// and return the block number before the fork block along with all transactions in the fork block
@@ -3,14 +3,18 @@
     sign::build_typed_transaction,
 };
 use crate::{
+    config::anvil_dir,
     eth::{
         backend::{
             self,
+            bridge::{self, BRIDGE_RELAYER, DEFAULT_BRIDGE_TEMPLATE},
             db::SerializableState,
             mem::{MIN_CREATE_GAS, MIN_TRANSACTION_GAS},
             notifications::NewBlockNotifications,
+            schedule::ScheduledAt,
             validate::TransactionValidator,
         },
+        emulation::EmulatedClient,
         error::{
             BlockchainError, FeeHistoryError, InvalidTransactionError, Result, ToRpcResponseResult,
         },
@@ -30,16 +34,20 @@
     revm::primitives::{BlobExcessGasAndPrice, Output},
     ClientFork, LoggingManager, Miner, MiningMode, StorageInfo,
 };
-use alloy_consensus::{transaction::eip4844::TxEip4844Variant, Account};
+use alloy_consensus::{
+    transaction::eip4844::TxEip4844Variant, Account, SignableTransaction, TxLegacy,
+};
 use alloy_dyn_abi::TypedData;
 use alloy_eips::eip2718::Encodable2718;
+use alloy_json_abi::Event;
 use alloy_network::{
     eip2718::Decodable2718, AnyRpcBlock, AnyRpcTransaction, BlockResponse, Ethereum, NetworkWallet,
     TransactionBuilder, TransactionResponse,
 };
 use alloy_primitives::{
+    keccak256,
     map::{HashMap, HashSet},
-    Address, Bytes, PrimitiveSignature as Signature, TxHash, TxKind, B256, B64, U256, U64,
+    Address, Bytes, PrimitiveSignature as Signature, TxHash, TxKind, B256, B64, I256, U256, U64,
 };
 use alloy_provider::utils::{
     eip1559_default_estimator, EIP1559_FEE_ESTIMATION_PAST_BLOCKS,
@@ -61,10 +69,12 @@
     EIP1186AccountProofResponse, FeeHistory, Filter, FilteredParams, Index, Log,
 };
 use alloy_serde::WithOtherFields;
+use alloy_sol_types::{sol, SolCall};
 use alloy_transport::TransportErrorKind;
 use anvil_core::{
     eth::{
         block::BlockInfo,
+        subscription::SubscriptionId,
         transaction::{
             transaction_request_to_typed, PendingTransaction, ReceiptResponse, TypedTransaction,
             TypedTransactionRequest,
@@ -72,10 +82,15 @@
         wallet::{WalletCapabilities, WalletError},
         EthRequest,
     },
-    types::{ReorgOptions, TransactionData, Work},
+    types::{
+        BlockAction, BridgeEvent, BridgeTemplate, ExpectedBalanceDelta, ExpectedStateDiff,
+        ExpectedStorage, ProtocolLimits, ReorgOptions, RichReceipt, RichReceiptOptions,
+        RuntimeProfile, ScheduleOptions, StateDiffCheck, StateDiffMismatch, TokenRef,
+        TransactionData, Work,
+    },
 };
 use anvil_rpc::{error::RpcError, response::ResponseResult};
-use foundry_common::provider::ProviderBuilder;
+use foundry_common::{fs, provider::ProviderBuilder};
 use foundry_evm::{
     backend::DatabaseError,
     decode::RevertDecoder,
@@ -84,15 +99,24 @@
         interpreter::{return_ok, return_revert, InstructionResult},
         primitives::BlockEnv,
     },
+    traces::CallTraceArena,
+};
+use futures::{
+    channel::{mpsc::Receiver, oneshot},
+    StreamExt,
 };
-use futures::channel::{mpsc::Receiver, oneshot};
 use parking_lot::RwLock;
 use revm::primitives::Bytecode;
-use std::{future::Future, sync::Arc, time::Duration};
+use std::{future::Future, path::PathBuf, sync::Arc, time::Duration};
 
 /// The client version: `anvil/v{major}.{minor}.{patch}`
 pub const CLIENT_VERSION: &str = concat!("anvil/v", env!("CARGO_PKG_VERSION"));
 
+sol! {
+    function balanceOf(address owner) external view returns (uint256);
+    function ownerOf(uint256 tokenId) external view returns (address);
+}
+
 /// The entry point for executing eth api RPC call - The Eth RPC interface.
 ///
 /// This type is cheap to clone and can be used concurrently
@@ -126,6 +150,12 @@ pub struct EthApi {
     net_listening: bool,
     /// The instance ID. Changes on every reset.
     instance_id: Arc<RwLock<B256>>,
+    /// The state changes registered with `anvil_expectStateDiff`, and the block they apply to.
+    expected_state_diff: Arc<RwLock<Option<(u64, ExpectedStateDiff)>>>,
+    /// The execution client whose RPC quirks are emulated, if any.
+    emulate: Option<EmulatedClient>,
+    /// The default gas target of the blocks mined by `anvil_mineBatch`.
+    batch_gas_target: Option<u64>,
 }
 
 impl EthApi {
@@ -141,7 +171,11 @@ pub fn new(
         logger: LoggingManager,
         filters: Filters,
         transactions_order: TransactionOrder,
+        emulate: Option<EmulatedClient>,
+        batch_gas_target: Option<u64>,
     ) -> Self {
+        // the instance ID is derived from the previous one on every reset in deterministic mode
+        let instance_id = if backend.is_deterministic() { B256::ZERO } else { B256::random() };
         Self {
             pool,
             backend,
@@ -154,12 +188,24 @@ pub fn new(
             filters,
             net_listening: true,
             transaction_order: Arc::new(RwLock::new(transactions_order)),
-            instance_id: Arc::new(RwLock::new(B256::random())),
+            instance_id: Arc::new(RwLock::new(instance_id)),
+            expected_state_diff: Default::default(),
+            emulate,
+            batch_gas_target,
         }
     }
 
     /// Executes the [EthRequest] and returns an RPC [ResponseResult].
     pub async fn execute(&self, request: EthRequest) -> ResponseResult {
+        match (self.execute_request(request).await, self.emulate) {
+            (ResponseResult::Error(err), Some(client)) => {
+                ResponseResult::Error(client.adjust_error(err))
+            }
+            (response, _) => response,
+        }
+    }
+
+    async fn execute_request(&self, request: EthRequest) -> ResponseResult {
         trace!(target: "rpc::api", "executing eth request");
         match request {
             EthRequest::Web3ClientVersion(()) => self.client_version().to_rpc_result(),
@@ -268,7 +314,7 @@ pub async fn execute(&self, request: EthRequest) -> ResponseResult {
                 self.transaction_by_block_number_and_index(num, index).await.to_rpc_result()
             }
             EthRequest::EthGetTransactionReceipt(tx) => {
-                self.transaction_receipt(tx).await.to_rpc_result()
+                self.transaction_receipt_with_revert_info(tx).await.to_rpc_result()
             }
             EthRequest::EthGetBlockReceipts(number) => {
                 self.block_receipts(number).await.to_rpc_result()
@@ -297,7 +343,11 @@ pub async fn execute(&self, request: EthRequest) -> ResponseResult {
             }
             // non eth-standard rpc calls
             EthRequest::DebugTraceTransaction(tx, opts) => {
-                self.debug_trace_transaction(tx, opts).await.to_rpc_result()
+                if opts.foundry_debugger {
+                    self.debug_trace_transaction_arena(tx).to_rpc_result()
+                } else {
+                    self.debug_trace_transaction(tx, opts.geth).await.to_rpc_result()
+                }
             }
             // non eth-standard rpc calls
             EthRequest::DebugTraceCall(tx, block, opts) => {
@@ -347,9 +397,27 @@ pub async fn execute(&self, request: EthRequest) -> ResponseResult {
             EthRequest::SetStorageAt(addr, slot, val) => {
                 self.anvil_set_storage_at(addr, slot, val).await.to_rpc_result()
             }
+            EthRequest::DealErc20(token, to, amount) => {
+                self.anvil_deal_erc20(token, to, amount).await.to_rpc_result()
+            }
+            EthRequest::DealErc721(token, to, token_id) => {
+                self.anvil_deal_erc721(token, to, token_id).await.to_rpc_result()
+            }
+            EthRequest::SetTokenBalanceSlot(token, slot) => {
+                self.anvil_set_token_balance_slot(token, slot).await.to_rpc_result()
+            }
+            EthRequest::SimulateBridgeEvent(event) => {
+                self.anvil_simulate_bridge_event(event).await.to_rpc_result()
+            }
+            EthRequest::SetBridgeTemplate(name, template) => {
+                self.anvil_set_bridge_template(name, template).await.to_rpc_result()
+            }
             EthRequest::SetCoinbase(addr) => self.anvil_set_coinbase(addr).await.to_rpc_result(),
             EthRequest::SetChainId(id) => self.anvil_set_chain_id(id).await.to_rpc_result(),
             EthRequest::SetLogging(log) => self.anvil_set_logging(log).await.to_rpc_result(),
+            EthRequest::SetLogFilter(filter) => {
+                self.anvil_set_log_filter(filter).await.to_rpc_result()
+            }
             EthRequest::SetMinGasPrice(gas) => {
                 self.anvil_set_min_gas_price(gas).await.to_rpc_result()
             }
@@ -361,6 +429,28 @@ pub async fn execute(&self, request: EthRequest) -> ResponseResult {
                 .await
                 .to_rpc_result(),
             EthRequest::LoadState(buf) => self.anvil_load_state(buf).await.to_rpc_result(),
+            EthRequest::ExportStateBinary(()) => {
+                self.anvil_export_state_binary().await.to_rpc_result()
+            }
+            EthRequest::SetLimits(limits) => self.anvil_set_limits(limits).await.to_rpc_result(),
+            EthRequest::ExpectStateDiff(diff) => {
+                self.anvil_expect_state_diff(diff).await.to_rpc_result()
+            }
+            EthRequest::CheckStateDiff(()) => self.anvil_check_state_diff().await.to_rpc_result(),
+            EthRequest::ScheduleTx(tx, options) => {
+                self.anvil_schedule_tx(tx, options).await.to_rpc_result()
+            }
+            EthRequest::AddBlockAction(action) => {
+                self.anvil_add_block_action(action).await.to_rpc_result()
+            }
+            EthRequest::RemoveBlockAction(id) => {
+                self.anvil_remove_block_action(id).await.to_rpc_result()
+            }
+            EthRequest::MineBatch(blocks, gas_target) => {
+                self.anvil_mine_batch(blocks, gas_target).await.to_rpc_result()
+            }
+            EthRequest::SaveProfile(name) => self.anvil_save_profile(name).await.to_rpc_result(),
+            EthRequest::LoadProfile(name) => self.anvil_load_profile(name).await.to_rpc_result(),
             EthRequest::NodeInfo(_) => self.anvil_node_info().await.to_rpc_result(),
             EthRequest::AnvilMetadata(_) => self.anvil_metadata().await.to_rpc_result(),
             EthRequest::EvmSnapshot(_) => self.evm_snapshot().await.to_rpc_result(),
@@ -457,6 +547,9 @@ pub async fn execute(&self, request: EthRequest) -> ResponseResult {
                 self.anvil_reorg(reorg_options).await.to_rpc_result()
             }
             EthRequest::Rollback(depth) => self.anvil_rollback(depth).await.to_rpc_result(),
+            EthRequest::GetRichReceipt(hash, options) => {
+                self.anvil_get_rich_receipt(hash, options).await.to_rpc_result()
+            }
             EthRequest::WalletGetCapabilities(()) => self.get_capabilities().to_rpc_result(),
             EthRequest::WalletSendTransaction(tx) => {
                 self.wallet_send_transaction(*tx).await.to_rpc_result()
@@ -523,7 +616,7 @@ async fn inner_raw_transaction(&self, hash: B256) -> Result<Option<Bytes>> {
     /// Handler for ETH RPC call: `web3_clientVersion`
     pub fn client_version(&self) -> Result<String> {
         node_info!("web3_clientVersion");
-        Ok(CLIENT_VERSION.to_string())
+        Ok(self.emulate.map_or(CLIENT_VERSION, |client| client.client_version()).to_string())
     }
 
     /// Returns Keccak-256 (not the standardized SHA3-256) of the given data.
@@ -1007,7 +1100,9 @@ pub async fn send_transaction(
         let provides = vec![to_marker(nonce, from)];
         debug_assert!(requires != provides);
 
-        self.add_pending_transaction(pending_transaction, requires, provides)
+        let hash = self.add_pending_transaction(pending_transaction, requires, provides)?;
+        self.ensure_not_reverted(hash).await?;
+        Ok(hash)
     }
 
     /// Sends signed transaction, returning its hash.
@@ -1045,6 +1140,7 @@ pub async fn send_raw_transaction(&self, tx: Bytes) -> Result<TxHash> {
 
         let tx = self.pool.add_transaction(pool_transaction)?;
         trace!(target: "node", "Added transaction: [{:?}] sender={:?}", tx.hash(), from);
+        self.ensure_not_reverted(*tx.hash()).await?;
         Ok(*tx.hash())
     }
 
@@ -1236,6 +1332,34 @@ pub async fn transaction_receipt(&self, hash: B256) -> Result<Option<ReceiptResp
         self.backend.transaction_receipt(hash).await
     }
 
+    /// Returns the transaction receipt, including the revert details of reverted transactions if
+    /// rich errors are enabled.
+    ///
+    /// Handler for ETH RPC call: `eth_getTransactionReceipt`
+    pub async fn transaction_receipt_with_revert_info(
+        &self,
+        hash: B256,
+    ) -> Result<Option<WithOtherFields<ReceiptResponse>>> {
+        let Some(receipt) = self.transaction_receipt(hash).await? else { return Ok(None) };
+        let mut receipt = WithOtherFields::new(receipt);
+        if let Some(info) =
+            self.backend.rich_errors().then(|| self.backend.revert_info(hash)).flatten()
+        {
+            if let Some(reason) = info.reason {
+                receipt.other.insert("revertReason".to_string(), reason.into());
+            }
+            receipt.other.insert(
+                "revertData".to_string(),
+                serde_json::to_value(info.data).expect("Infallible"),
+            );
+            receipt.other.insert(
+                "revertTrace".to_string(),
+                serde_json::to_value(info.trace).expect("Infallible"),
+            );
+        }
+        Ok(Some(receipt))
+    }
+
     /// Returns block receipts by block number.
     ///
     /// Handler for ETH RPC call: `eth_getBlockReceipts`
@@ -1565,6 +1689,15 @@ pub async fn debug_trace_transaction(
         self.backend.debug_trace_transaction(tx_hash, opts).await
     }
 
+    /// Returns the recorded call trace arena of the transaction, including the steps, which the
+    /// debugger opens
+    ///
+    /// Handler for RPC call: `debug_traceTransaction` with the `foundryDebugger` option
+    pub fn debug_trace_transaction_arena(&self, tx_hash: B256) -> Result<Option<CallTraceArena>> {
+        node_info!("debug_traceTransaction");
+        self.backend.debug_trace_transaction_arena(tx_hash)
+    }
+
     /// Returns traces for the transaction for geth's tracing endpoint
     ///
     /// Handler for RPC call: `debug_traceCall`
@@ -1706,6 +1839,41 @@ pub async fn anvil_mine(&self, num_blocks: Option<U256>, interval: Option<U256>)
         Ok(())
     }
 
+    /// Mines a number of blocks from the transactions in the pool.
+    ///
+    /// Each block is filled with the ready transactions in pool order until their gas limits add
+    /// up to the gas target, which defaults to the configured batch gas target or the block gas
+    /// limit. Transactions that don't fit into the blocks stay in the pool.
+    ///
+    /// Handler for RPC call: `anvil_mineBatch`
+    pub async fn anvil_mine_batch(&self, num_blocks: U256, gas_target: Option<U256>) -> Result<()> {
+        node_info!("anvil_mineBatch");
+        let gas_target = gas_target
+            .map(|gas| gas.saturating_to::<u64>())
+            .or(self.batch_gas_target)
+            .unwrap_or_else(|| self.backend.gas_limit());
+
+        for _ in 0..num_blocks.saturating_to::<u64>() {
+            let mut gas = 0u64;
+            let transactions = self
+                .pool
+                .ready_transactions()
+                .take_while(|tx| {
+                    // every block includes at least one transaction, even if it exceeds the target
+                    let is_first = gas == 0;
+                    gas = gas.saturating_add(tx.pending_transaction.transaction.gas_limit());
+                    is_first || gas <= gas_target
+                })
+                .collect::<Vec<_>>();
+            trace!(target: "node", txs = transactions.len(), gas_target, "mining batch block");
+
+            let outcome = self.backend.mine_block(transactions).await;
+            self.pool.on_mined_block(outcome);
+        }
+
+        Ok(())
+    }
+
     /// Sets the mining behavior to interval with the given interval (seconds)
     ///
     /// Handler for ETH RPC call: `evm_setIntervalMining`
@@ -1758,9 +1926,29 @@ pub async fn anvil_reset(&self, forking: Option<Forking>) -> Result<()> {
         }
     }
 
+    /// Sets the chain id of the node.
+    ///
+    /// Transactions are validated against the new chain id from now on, so the pool transactions
+    /// signed for another chain are removed. Transactions without a chain id remain valid.
+    ///
+    /// Handler for RPC call: `anvil_setChainId`
     pub async fn anvil_set_chain_id(&self, chain_id: u64) -> Result<()> {
         node_info!("anvil_setChainId");
         self.backend.set_chain_id(chain_id);
+
+        let invalid = self
+            .pool
+            .ready_transactions()
+            .chain(self.pool.pending_transactions())
+            .filter(|tx| {
+                tx.pending_transaction.transaction.chain_id().is_some_and(|id| id != chain_id)
+            })
+            .map(|tx| tx.hash())
+            .collect::<Vec<_>>();
+        if !invalid.is_empty() {
+            trace!(target: "node", ?invalid, "removing transactions signed for another chain id");
+            self.pool.remove_invalid(invalid);
+        }
         Ok(())
     }
 
@@ -1805,6 +1993,215 @@ pub async fn anvil_set_storage_at(
         Ok(true)
     }
 
+    /// Sets the ERC-20 token balance of an account.
+    ///
+    /// The balance is written to the slot set with `anvil_setTokenBalanceSlot`, or else to the
+    /// slot found by probing the storage read by `balanceOf`. The total supply is unchanged.
+    ///
+    /// Handler for RPC call: `anvil_dealERC20`
+    pub async fn anvil_deal_erc20(&self, token: TokenRef, to: Address, amount: U256) -> Result<()> {
+        node_info!("anvil_dealERC20");
+        let token = self.resolve_token(&token)?;
+        let slot = self.token_balance_slot(token, to).await?;
+        self.backend.set_storage_at(token, slot, amount.into()).await?;
+        Ok(())
+    }
+
+    /// Transfers an ERC-721 token to an account, from its current owner if it has one.
+    ///
+    /// The owner is written to the slot found by probing the storage read by `ownerOf`, and the
+    /// balances of the previous and new owners are updated like `anvil_dealERC20` does.
+    ///
+    /// Handler for RPC call: `anvil_dealERC721`
+    pub async fn anvil_deal_erc721(
+        &self,
+        token: TokenRef,
+        to: Address,
+        token_id: U256,
+    ) -> Result<()> {
+        node_info!("anvil_dealERC721");
+        let token = self.resolve_token(&token)?;
+        let input = ownerOfCall { tokenId: token_id }.abi_encode().into();
+        let owner_slot = self.backend.find_storage_slot(token, input).await?.ok_or_else(|| {
+            RpcError::invalid_params(format!(
+                "could not find the storage slot of the owner of token {token_id} of {token}"
+            ))
+        })?;
+        let owner_word = self.backend.storage_at(token, owner_slot, None).await?;
+        let previous_owner = Address::from_word(owner_word);
+        if previous_owner == to {
+            return Ok(());
+        }
+
+        if !previous_owner.is_zero() {
+            let slot = self.token_balance_slot(token, previous_owner).await?;
+            let balance = U256::from_be_bytes(self.backend.storage_at(token, slot, None).await?.0);
+            let balance = balance.saturating_sub(U256::from(1));
+            self.backend.set_storage_at(token, slot, balance.into()).await?;
+        }
+        let slot = self.token_balance_slot(token, to).await?;
+        let balance = U256::from_be_bytes(self.backend.storage_at(token, slot, None).await?.0);
+        let balance = balance.saturating_add(U256::from(1));
+        self.backend.set_storage_at(token, slot, balance.into()).await?;
+
+        // keep the bits the owner may be packed with
+        let mut owner_word = owner_word;
+        owner_word[12..].copy_from_slice(to.as_slice());
+        self.backend.set_storage_at(token, owner_slot, owner_word).await?;
+        Ok(())
+    }
+
+    /// Sets the storage slot of the balances mapping of a token, used by `anvil_dealERC20` and
+    /// `anvil_dealERC721` instead of probing the token's storage.
+    ///
+    /// The mapping is expected to follow the Solidity storage layout.
+    ///
+    /// Handler for RPC call: `anvil_setTokenBalanceSlot`
+    pub async fn anvil_set_token_balance_slot(&self, token: TokenRef, slot: U256) -> Result<()> {
+        node_info!("anvil_setTokenBalanceSlot");
+        let token = self.resolve_token(&token)?;
+        self.backend.tokens().set_balance_slot(token, slot);
+        Ok(())
+    }
+
+    /// Simulates a step of a transfer through a bridge, in a new block.
+    ///
+    /// The event of the bridge template is emitted by the bridge contract, in a transaction sent
+    /// by `from` for initiated transfers and by a relayer for finalized ones. Initiated transfers
+    /// take the funds of `from`: ETH is sent to the bridge as the value of the transaction, and
+    /// ERC-20 tokens are deducted from its balance. Finalized transfers mint the funds to `to`.
+    /// ERC-20 balances are written like `anvil_dealERC20` does.
+    ///
+    /// Handler for RPC call: `anvil_simulateBridgeEvent`
+    pub async fn anvil_simulate_bridge_event(&self, event: BridgeEvent) -> Result<TxHash> {
+        node_info!("anvil_simulateBridgeEvent");
+        let name = event.template.as_deref().unwrap_or(DEFAULT_BRIDGE_TEMPLATE);
+        let template = self.backend.bridges().get(name).ok_or_else(|| {
+            RpcError::invalid_params(format!(
+                "unknown bridge template `{name}`, register it with `anvil_setBridgeTemplate`"
+            ))
+        })?;
+
+        let (bridge_address, token) = if event.kind.is_l1() {
+            (template.l1_bridge, event.l1_token)
+        } else {
+            (template.l2_bridge, event.l2_token)
+        };
+        let (signature, l1_token, l2_token) = match token {
+            Some(_) => (
+                template.erc20_events.get(&event.kind),
+                event.l1_token.unwrap_or_default(),
+                event.l2_token.unwrap_or_default(),
+            ),
+            None => {
+                (template.eth_events.get(&event.kind), template.eth_l1_token, template.eth_l2_token)
+            }
+        };
+        let signature = signature.ok_or_else(|| {
+            RpcError::invalid_params(format!(
+                "bridge template `{name}` has no {:?} event for {} transfers",
+                event.kind,
+                if token.is_some() { "ERC-20" } else { "ETH" }
+            ))
+        })?;
+        let log = bridge::encode_log(signature, &event, l1_token, l2_token)
+            .map_err(RpcError::invalid_params)?;
+
+        let token_slot = match token {
+            Some(token) => {
+                let owner = if event.kind.is_initiated() { event.from } else { event.to };
+                let slot = self.token_balance_slot(token, owner).await?;
+                let balance =
+                    U256::from_be_bytes(self.backend.storage_at(token, slot, None).await?.0);
+                let balance = if event.kind.is_initiated() {
+                    balance.checked_sub(event.amount).ok_or_else(|| {
+                        RpcError::invalid_params(format!(
+                            "the balance of {owner} in {token} is lower than {}",
+                            event.amount
+                        ))
+                    })?
+                } else {
+                    balance.saturating_add(event.amount)
+                };
+                Some((token, slot, balance))
+            }
+            None => None,
+        };
+
+        let input = bridge::emitter_calldata(&log);
+        let gas_price = self.backend.base_fee() as u128;
+        let gas_limit = 100_000 + 24 * input.len() as u64;
+        let (sender, value) = if event.kind.is_initiated() {
+            (event.from, if token.is_none() { event.amount } else { U256::ZERO })
+        } else {
+            // the relayer pays for the gas of its transaction only
+            let balance = self.backend.current_balance(BRIDGE_RELAYER).await?;
+            let fee = U256::from(gas_price * gas_limit as u128);
+            self.backend.set_balance(BRIDGE_RELAYER, balance.saturating_add(fee)).await?;
+            (BRIDGE_RELAYER, U256::ZERO)
+        };
+        let tx = TxLegacy {
+            chain_id: Some(self.backend.chain_id().to()),
+            nonce: self.backend.current_nonce(sender).await?,
+            gas_price,
+            gas_limit,
+            to: TxKind::Call(bridge_address),
+            value,
+            input,
+        };
+        // the same signature that bypasses signing in `eth_sendUnsignedTransaction`
+        let signature = Signature::from_scalars_and_parity(
+            B256::with_last_byte(1),
+            B256::with_last_byte(1),
+            false,
+        );
+        let tx = TypedTransaction::Legacy(tx.into_signed(signature));
+        let transaction =
+            Arc::new(PoolTransaction::new(PendingTransaction::with_impersonated(tx, sender)));
+        let hash = transaction.hash();
+
+        // the bridge emits the event with the code of the emitter, in a block of its own
+        let code = self.backend.get_code(bridge_address, None).await?;
+        self.backend.set_code(bridge_address, bridge::emitter_code(log.topics().len())).await?;
+        let outcome = self.backend.mine_block(vec![transaction]).await;
+        self.backend.set_code(bridge_address, code).await?;
+        let invalid = !outcome.invalid.is_empty();
+        self.pool.on_mined_block(outcome);
+        if invalid {
+            return Err(RpcError::invalid_params(format!(
+                "the bridge transaction of {sender} is invalid, it may not be able to pay for the \
+                 transferred ETH and gas"
+            ))
+            .into())
+        }
+
+        match token_slot {
+            Some((token, slot, balance)) => {
+                self.backend.set_storage_at(token, slot, balance.into()).await?;
+            }
+            None if !event.kind.is_initiated() => {
+                let balance = self.backend.current_balance(event.to).await?;
+                self.backend.set_balance(event.to, balance.saturating_add(event.amount)).await?;
+            }
+            None => {}
+        }
+        Ok(hash)
+    }
+
+    /// Registers a bridge template for `anvil_simulateBridgeEvent`, replacing the one with the
+    /// same name if any. The `optimism` template is registered by default.
+    ///
+    /// Handler for RPC call: `anvil_setBridgeTemplate`
+    pub async fn anvil_set_bridge_template(
+        &self,
+        name: String,
+        template: BridgeTemplate,
+    ) -> Result<()> {
+        node_info!("anvil_setBridgeTemplate");
+        self.backend.bridges().set(&name, template).map_err(RpcError::invalid_params)?;
+        Ok(())
+    }
+
     /// Enable or disable logging.
     ///
     /// Handler for RPC call: `anvil_setLoggingEnabled`
@@ -1814,6 +2211,15 @@ pub async fn anvil_set_logging(&self, enable: bool) -> Result<()> {
         Ok(())
     }
 
+    /// Sets the levels logged to the log file, using the `RUST_LOG` syntax.
+    ///
+    /// Handler for RPC call: `anvil_setLogFilter`
+    pub async fn anvil_set_log_filter(&self, filter: String) -> Result<()> {
+        node_info!("anvil_setLogFilter");
+        self.logger.set_file_filter(&filter).map_err(RpcError::invalid_params)?;
+        Ok(())
+    }
+
     /// Set the minimum gas price for the node.
     ///
     /// Handler for RPC call: `anvil_setMinGasPrice`
@@ -1829,6 +2235,154 @@ pub async fn anvil_set_min_gas_price(&self, gas: U256) -> Result<()> {
         Ok(())
     }
 
+    /// Sets the protocol-level limits: the maximum code and initcode sizes, and the gas charged
+    /// for calldata bytes. Unset limits are reset to the defaults of the active hardfork, except
+    /// for the code size limit if it was disabled with `--disable-code-size-limit`.
+    ///
+    /// Handler for RPC call: `anvil_setLimits`
+    pub async fn anvil_set_limits(&self, limits: ProtocolLimits) -> Result<()> {
+        node_info!("anvil_setLimits");
+        let limits = self.backend.resolve_protocol_limits(limits);
+        if let Some(limit) = limits.initcode_size_limit {
+            let max = limits.max_initcode_size_limit();
+            if limit > max {
+                return Err(RpcError::invalid_params(format!(
+                    "initcode size limit {limit} exceeds twice the code size limit ({max})"
+                ))
+                .into());
+            }
+        }
+        self.backend.set_protocol_limits(limits);
+        Ok(())
+    }
+
+    /// Registers the storage values and balance changes expected in the next block, so that e2e
+    /// suites can check the effects of transactions sent by other clients.
+    ///
+    /// Handler for RPC call: `anvil_expectStateDiff`
+    pub async fn anvil_expect_state_diff(&self, diff: ExpectedStateDiff) -> Result<()> {
+        node_info!("anvil_expectStateDiff");
+        let block_number = self.backend.best_number() + 1;
+        *self.expected_state_diff.write() = Some((block_number, diff));
+        Ok(())
+    }
+
+    /// Checks the expectations registered with `anvil_expectStateDiff` against the block they
+    /// apply to, once it is mined.
+    ///
+    /// Handler for RPC call: `anvil_checkStateDiff`
+    pub async fn anvil_check_state_diff(&self) -> Result<StateDiffCheck> {
+        node_info!("anvil_checkStateDiff");
+        let Some((block_number, diff)) = self.expected_state_diff.read().clone() else {
+            return Err(RpcError::invalid_params(
+                "no state diff is expected, register one with anvil_expectStateDiff",
+            )
+            .into());
+        };
+        let mut check = StateDiffCheck { block_number, ..Default::default() };
+        if self.backend.best_number() < block_number {
+            return Ok(check);
+        }
+        check.mined = true;
+
+        let at = |number| Some(BlockRequest::Number(number));
+        for ExpectedStorage { address, slot, value } in diff.storage {
+            let actual = self
+                .backend
+                .storage_at(address, U256::from_be_bytes(slot.0), at(block_number))
+                .await?;
+            if actual != value {
+                check.mismatches.push(StateDiffMismatch::Storage {
+                    address,
+                    slot,
+                    expected: value,
+                    actual,
+                });
+            }
+        }
+        for ExpectedBalanceDelta { address, delta } in diff.balances {
+            let before = self.backend.get_balance(address, at(block_number - 1)).await?;
+            let after = self.backend.get_balance(address, at(block_number)).await?;
+            let actual = I256::from_raw(after.wrapping_sub(before));
+            if actual != delta {
+                check.mismatches.push(StateDiffMismatch::Balance {
+                    address,
+                    expected: delta,
+                    actual,
+                });
+            }
+        }
+        check.passed = check.mismatches.is_empty();
+        Ok(check)
+    }
+
+    /// Schedules a signed transaction to be included in the block with the given number, or the
+    /// first block with a timestamp at or after the given one.
+    ///
+    /// The transaction is executed at the start of that block, or after the pool transactions of
+    /// its sender with lower nonces. If it can't be included it is retried in the next blocks,
+    /// until its nonce is used by another transaction.
+    ///
+    /// Handler for RPC call: `anvil_scheduleTx`
+    pub async fn anvil_schedule_tx(&self, tx: Bytes, options: ScheduleOptions) -> Result<TxHash> {
+        node_info!("anvil_scheduleTx");
+        let at = match (options.at_block, options.at_timestamp) {
+            (Some(number), None) => {
+                if number <= self.backend.best_number() {
+                    return Err(RpcError::invalid_params(format!(
+                        "block {number} is already mined"
+                    ))
+                    .into());
+                }
+                ScheduledAt::Block(number)
+            }
+            (None, Some(timestamp)) => ScheduledAt::Timestamp(timestamp),
+            _ => {
+                return Err(RpcError::invalid_params(
+                    "exactly one of atBlock and atTimestamp must be set",
+                )
+                .into())
+            }
+        };
+
+        let mut data = tx.as_ref();
+        if data.is_empty() {
+            return Err(BlockchainError::EmptyRawTransactionData);
+        }
+        let transaction = TypedTransaction::decode_2718(&mut data)
+            .map_err(|_| BlockchainError::FailedToDecodeSignedTransaction)?;
+        self.ensure_typed_transaction_supported(&transaction)?;
+        let pending_transaction = PendingTransaction::new(transaction)?;
+
+        let hash = *pending_transaction.hash();
+        self.backend.scheduler().schedule(Arc::new(PoolTransaction::new(pending_transaction)), at);
+        Ok(hash)
+    }
+
+    /// Registers a call the miner executes every `every` blocks, starting at `startBlock` or the
+    /// next block, and returns its id.
+    ///
+    /// The call is sent from `from` without a signature at the end of the block.
+    ///
+    /// Handler for RPC call: `anvil_addBlockAction`
+    pub async fn anvil_add_block_action(&self, mut action: BlockAction) -> Result<u64> {
+        node_info!("anvil_addBlockAction");
+        if action.every == 0 {
+            return Err(RpcError::invalid_params("`every` must be at least 1").into());
+        }
+        action.start_block.get_or_insert(self.backend.best_number() + 1);
+        Ok(self.backend.scheduler().add_action(action))
+    }
+
+    /// Removes a call registered with `anvil_addBlockAction`, returns `false` if there is no call
+    /// with this id.
+    ///
+    /// Handler for RPC call: `anvil_removeBlockAction`
+    pub async fn anvil_remove_block_action(&self, id: u64) -> Result<bool> {
+        node_info!("anvil_removeBlockAction");
+        Ok(self.backend.scheduler().remove_action(id))
+    }
+
     /// Sets the base fee of the next block.
     ///
     /// Handler for RPC call: `anvil_setNextBlockBaseFeePerGas`
@@ -1865,6 +2419,91 @@ pub async fn anvil_dump_state(
         self.backend.dump_state(preserve_historical_states.unwrap_or(false)).await
     }
 
+    /// Create a buffer with all state on the chain in the versioned binary dump format, which can
+    /// be loaded with `anvil_loadState`, `anvil --import-state` or `anvil --load-state-on-start`.
+    ///
+    /// Handler for RPC call: `anvil_exportStateBinary`
+    pub async fn anvil_export_state_binary(&self) -> Result<Bytes> {
+        node_info!("anvil_exportStateBinary");
+        self.backend.dump_state_binary().await
+    }
+
+    /// Saves the runtime settings of the node as a JSON profile under
+    /// `~/.foundry/anvil/profiles`, returning the path of the profile.
+    ///
+    /// Handler for RPC call: `anvil_saveProfile`
+    pub async fn anvil_save_profile(&self, name: String) -> Result<PathBuf> {
+        node_info!("anvil_saveProfile");
+        let path = profile_path(&name)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|err| BlockchainError::Message(err.to_string()))?;
+        }
+        fs::write_pretty_json_file(&path, &self.runtime_profile())
+            .map_err(|err| BlockchainError::Message(err.to_string()))?;
+        Ok(path)
+    }
+
+    /// Restores the runtime settings saved with `anvil_saveProfile`.
+    ///
+    /// Handler for RPC call: `anvil_loadProfile`
+    pub async fn anvil_load_profile(&self, name: String) -> Result<()> {
+        node_info!("anvil_loadProfile");
+        let path = profile_path(&name)?;
+        let profile: RuntimeProfile =
+            fs::read_json_file(&path).map_err(|err| BlockchainError::Message(err.to_string()))?;
+        self.apply_runtime_profile(profile).await
+    }
+
+    /// Returns the current runtime settings of the node.
+    pub fn runtime_profile(&self) -> RuntimeProfile {
+        let cheats = self.backend.cheats();
+        let mut impersonated_accounts =
+            cheats.impersonated_accounts().into_iter().collect::<Vec<_>>();
+        impersonated_accounts.sort();
+        RuntimeProfile {
+            impersonated_accounts,
+            auto_impersonate: cheats.auto_impersonate_accounts(),
+            automine: self.miner.is_auto_mine(),
+            interval_mining: self.miner.get_interval(),
+            base_fee: self.backend.base_fee(),
+            gas_price: self.backend.fees().raw_gas_price(),
+        }
+    }
+
+    /// Replaces the runtime settings of the node with the given ones.
+    pub async fn apply_runtime_profile(&self, profile: RuntimeProfile) -> Result<()> {
+        let RuntimeProfile {
+            impersonated_accounts,
+            auto_impersonate,
+            automine,
+            interval_mining,
+            base_fee,
+            gas_price,
+        } = profile;
+
+        for account in self.backend.cheats().impersonated_accounts() {
+            if !impersonated_accounts.contains(&account) {
+                self.backend.stop_impersonating(account);
+            }
+        }
+        for account in impersonated_accounts {
+            self.backend.impersonate(account);
+        }
+        self.backend.auto_impersonate_account(auto_impersonate);
+
+        if let Some(secs) = interval_mining {
+            self.anvil_set_interval_mining(secs)?;
+        } else if automine {
+            self.anvil_set_auto_mine(true).await?;
+        } else {
+            self.anvil_set_interval_mining(0)?;
+        }
+
+        self.backend.set_base_fee(base_fee);
+        self.backend.set_gas_price(gas_price);
+        Ok(())
+    }
+
     /// Returns the current state
     pub async fn serialized_state(
         &self,
@@ -1876,6 +2515,8 @@ pub async fn serialized_state(
     /// Append chain state buffer to current chain. Will overwrite any conflicting addresses or
     /// storage.
     ///
+    /// Accepts the buffers of both `anvil_dumpState` and `anvil_exportStateBinary`.
+    ///
     /// Handler for RPC call: `anvil_loadState`
     pub async fn anvil_load_state(&self, buf: Bytes) -> Result<bool> {
         node_info!("anvil_loadState");
@@ -2098,6 +2739,31 @@ pub async fn anvil_rollback(&self, depth: Option<u64>) -> Result<()> {
         Ok(())
     }
 
+    /// Returns the receipt of a transaction mined by the node, extended with the accounts and
+    /// storage slots it touched, the contracts it created and destroyed, its logs decoded with the
+    /// given events and the gas used by call depth, so that no trace calls are needed.
+    ///
+    /// Handler for RPC call: `anvil_getRichReceipt`
+    pub async fn anvil_get_rich_receipt(
+        &self,
+        hash: B256,
+        options: Option<RichReceiptOptions>,
+    ) -> Result<Option<RichReceipt>> {
+        node_info!("anvil_getRichReceipt");
+        let events = options
+            .unwrap_or_default()
+            .events
+            .iter()
+            .map(|signature| {
+                Event::parse(signature).map_err(|err| {
+                    RpcError::invalid_params(format!("invalid event `{signature}`: {err}"))
+                })
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let Some(receipt) = self.transaction_receipt(hash).await? else { return Ok(None) };
+        Ok(self.backend.rich_receipt(receipt, &events))
+    }
+
     /// Snapshot the state of the blockchain at the current block.
     ///
     /// Handler for RPC call: `evm_snapshot`
@@ -2781,7 +3447,14 @@ pub fn instance_id(&self) -> B256 {
 
     /// Resets the instance ID.
     pub fn reset_instance_id(&self) {
-        *self.instance_id.write() = B256::random();
+        let mut instance_id = self.instance_id.write();
+        *instance_id =
+            if self.backend.is_deterministic() { keccak256(*instance_id) } else { B256::random() };
+    }
+
+    /// Returns a new ID for a pubsub subscription.
+    pub fn new_subscription_id(&self) -> SubscriptionId {
+        SubscriptionId::String(self.filters.new_id())
     }
 
     /// Returns the first signer that can sign for the given address
@@ -2795,6 +3468,23 @@ pub fn new_block_notifications(&self) -> NewBlockNotifications {
         self.backend.new_block_notifications()
     }
 
+    /// Returns the logs of already mined blocks that match the filter of a new `logs`
+    /// subscription, and the number of the last block that was searched.
+    ///
+    /// Only filters with a `fromBlock` other than `latest` or `pending` are backfilled, the logs
+    /// of later blocks are delivered by the subscription itself.
+    pub async fn backfill_logs(&self, filter: &Filter) -> Result<(Vec<Log>, Option<u64>)> {
+        match filter.block_option.get_from_block() {
+            Some(from) if !from.is_latest() && !from.is_pending() => {}
+            _ => return Ok((Vec::new(), None)),
+        }
+        // blocks mined while collecting the logs are left to the subscription
+        let best = self.backend.best_number();
+        let mut logs = self.backend.logs(filter.clone()).await?;
+        logs.retain(|log| log.block_number.is_some_and(|number| number <= best));
+        Ok((logs, Some(best)))
+    }
+
     /// Returns a new listeners for ready transactions
     pub fn new_ready_transactions(&self) -> Receiver<TxHash> {
         self.pool.add_ready_listener()
@@ -3009,11 +3699,62 @@ fn add_pending_transaction(
         Ok(*tx.hash())
     }
 
+    /// Waits for a transaction submitted in automine mode to be mined and returns its revert
+    /// details as error if it reverted.
+    ///
+    /// This is a no-op unless rich errors are enabled.
+    async fn ensure_not_reverted(&self, hash: TxHash) -> Result<()> {
+        if !self.backend.rich_errors() || !self.miner.is_auto_mine() {
+            return Ok(());
+        }
+
+        let mut notifications = self.new_block_notifications();
+        loop {
+            if self.backend.mined_transaction(hash).is_some() {
+                return match self.backend.revert_info(hash) {
+                    Some(info) => Err(BlockchainError::TransactionReverted(Box::new(info))),
+                    None => Ok(()),
+                };
+            }
+            // transactions that are queued or were dropped won't be mined by the next block
+            if !self.pool.ready_transactions().any(|tx| *tx.hash() == hash) {
+                return Ok(());
+            }
+            // re-check periodically in case the transaction was dropped while mining
+            let _ = tokio::time::timeout(Duration::from_millis(100), notifications.next()).await;
+        }
+    }
+
     /// Returns the current state root
     pub async fn state_root(&self) -> Option<B256> {
         self.backend.get_db().read().await.maybe_state_root()
     }
 
+    /// Returns the address of a token passed to the token helpers
+    fn resolve_token(&self, token: &TokenRef) -> Result<Address> {
+        self.backend.tokens().resolve(token).ok_or_else(|| {
+            RpcError::invalid_params(format!(
+                "unknown token {token:?}, pass its address or register its symbol with `--token`"
+            ))
+            .into()
+        })
+    }
+
+    /// Returns the storage slot of `token` holding the balance of `owner`
+    async fn token_balance_slot(&self, token: Address, owner: Address) -> Result<U256> {
+        if let Some(slot) = self.backend.tokens().balance_slot(token, owner) {
+            return Ok(slot);
+        }
+        let input = balanceOfCall { owner }.abi_encode().into();
+        self.backend.find_storage_slot(token, input).await?.ok_or_else(|| {
+            RpcError::invalid_params(format!(
+                "could not find the storage slot of the balance of {owner} in {token}, set the \
+                 slot of its balances mapping with `anvil_setTokenBalanceSlot`"
+            ))
+            .into()
+        })
+    }
+
     /// additional validation against hardfork
     fn ensure_typed_transaction_supported(&self, tx: &TypedTransaction) -> Result<()> {
         match &tx {
@@ -3149,3 +3890,17 @@ fn try_from(res: Result<(InstructionResult, Option<Output>, u128, State)>) -> Re
         }
     }
 }
+
+/// Returns the path of the runtime profile with the given name.
+fn profile_path(name: &str) -> Result<PathBuf> {
+    let is_valid = !name.is_empty() &&
+        name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')) &&
+        !name.starts_with('.');
+    if !is_valid {
+        return Err(RpcError::invalid_params(format!("invalid profile name: {name:?}")).into());
+    }
+    let dir = anvil_dir().ok_or_else(|| {
+        BlockchainError::Message("could not determine the foundry directory".to_string())
+    })?;
+    Ok(dir.join("profiles").join(format!("{name}.json")))
+}
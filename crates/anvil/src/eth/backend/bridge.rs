@@ -0,0 +1,223 @@
+//! Support for the bridge helpers, `anvil_simulateBridgeEvent` and `anvil_setBridgeTemplate`
+
+use alloy_dyn_abi::{DynSolType, DynSolValue, Specifier};
+use alloy_json_abi::Event;
+use alloy_primitives::{address, keccak256, Address, Bytes, LogData, B256};
+use anvil_core::types::{BridgeEvent, BridgeEventKind, BridgeTemplate};
+use parking_lot::RwLock;
+use std::{collections::HashMap, sync::Arc};
+
+/// The template of the bridge events that don't set one.
+pub const DEFAULT_BRIDGE_TEMPLATE: &str = "optimism";
+
+/// The sender of the transactions of the finalized bridge events, relayed from the other chain.
+pub const BRIDGE_RELAYER: Address = address!("00000000000000000000000000000000000b41d6");
+
+/// The names of the event parameters set from the fields of a [`BridgeEvent`].
+const BRIDGE_EVENT_PARAMS: [&str; 6] = ["from", "to", "amount", "l1Token", "l2Token", "extraData"];
+
+/// Bridge templates known to the node, by name.
+#[derive(Clone, Debug)]
+pub struct BridgeTemplates {
+    /// shareable state
+    state: Arc<RwLock<HashMap<String, BridgeTemplate>>>,
+}
+
+impl Default for BridgeTemplates {
+    fn default() -> Self {
+        let templates = HashMap::from([(DEFAULT_BRIDGE_TEMPLATE.to_string(), optimism_template())]);
+        Self { state: Arc::new(RwLock::new(templates)) }
+    }
+}
+
+impl BridgeTemplates {
+    /// Registers a template, replacing the one with the same name if any.
+    ///
+    /// Returns an error if any of its event signatures is invalid or has parameters that can't
+    /// be set from a [`BridgeEvent`].
+    pub fn set(&self, name: &str, template: BridgeTemplate) -> Result<(), String> {
+        for signature in template.eth_events.values().chain(template.erc20_events.values()) {
+            parse_event(signature)?;
+        }
+        trace!(target: "bridge", "Registering bridge template {name}");
+        self.state.write().insert(name.to_string(), template);
+        Ok(())
+    }
+
+    /// Returns the template with the given name.
+    pub fn get(&self, name: &str) -> Option<BridgeTemplate> {
+        self.state.read().get(name).cloned()
+    }
+}
+
+/// Returns the template of the OP Stack standard bridge, `L1StandardBridge` and
+/// `L2StandardBridge` as deployed on OP Mainnet.
+pub fn optimism_template() -> BridgeTemplate {
+    let eth_event = |name: &str| {
+        format!("{name}(address indexed from, address indexed to, uint256 amount, bytes extraData)")
+    };
+    let erc20_event = |name: &str| {
+        format!(
+            "{name}(address indexed l1Token, address indexed l2Token, address indexed from, \
+             address to, uint256 amount, bytes extraData)"
+        )
+    };
+    BridgeTemplate {
+        l1_bridge: address!("99C9fc46f92E8a1c0deC1b1747d010903E884bE1"),
+        l2_bridge: address!("4200000000000000000000000000000000000010"),
+        eth_events: [
+            (BridgeEventKind::DepositInitiated, eth_event("ETHDepositInitiated")),
+            (BridgeEventKind::DepositFinalized, erc20_event("DepositFinalized")),
+            (BridgeEventKind::WithdrawalInitiated, erc20_event("WithdrawalInitiated")),
+            (BridgeEventKind::WithdrawalFinalized, eth_event("ETHWithdrawalFinalized")),
+        ]
+        .into(),
+        erc20_events: [
+            (BridgeEventKind::DepositInitiated, erc20_event("ERC20DepositInitiated")),
+            (BridgeEventKind::DepositFinalized, erc20_event("DepositFinalized")),
+            (BridgeEventKind::WithdrawalInitiated, erc20_event("WithdrawalInitiated")),
+            (BridgeEventKind::WithdrawalFinalized, erc20_event("ERC20WithdrawalFinalized")),
+        ]
+        .into(),
+        eth_l1_token: Address::ZERO,
+        eth_l2_token: address!("DeadDeAddeAddEAddeadDEaDDEAdDeaDDeAD0000"),
+    }
+}
+
+/// Parses an event signature, checking that all its parameters can be set from a
+/// [`BridgeEvent`].
+fn parse_event(signature: &str) -> Result<Event, String> {
+    let event =
+        Event::parse(signature).map_err(|err| format!("invalid event `{signature}`: {err}"))?;
+    if let Some(param) = event.inputs.iter().find(|p| !BRIDGE_EVENT_PARAMS.contains(&&*p.name)) {
+        return Err(format!(
+            "unknown parameter `{}` of event `{signature}`, expected one of: {}",
+            param.name,
+            BRIDGE_EVENT_PARAMS.join(", ")
+        ));
+    }
+    Ok(event)
+}
+
+/// Encodes the log of a bridge event with the given event signature.
+///
+/// `l1_token` and `l2_token` are the values of the `l1Token` and `l2Token` parameters, which are
+/// set for ETH transfers too.
+pub fn encode_log(
+    signature: &str,
+    event: &BridgeEvent,
+    l1_token: Address,
+    l2_token: Address,
+) -> Result<LogData, String> {
+    let abi_event = parse_event(signature)?;
+    let mut topics = Vec::new();
+    if !abi_event.anonymous {
+        topics.push(abi_event.selector());
+    }
+    let mut data = Vec::new();
+    for param in &abi_event.inputs {
+        let value = match param.name.as_str() {
+            "from" => event.from.to_string(),
+            "to" => event.to.to_string(),
+            "amount" => event.amount.to_string(),
+            "l1Token" => l1_token.to_string(),
+            "l2Token" => l2_token.to_string(),
+            _ => event.extra_data.to_string(),
+        };
+        let ty: DynSolType = param.resolve().map_err(|err| err.to_string())?;
+        let value = ty
+            .coerce_str(&value)
+            .map_err(|err| format!("invalid `{}` for event `{signature}`: {err}", param.name))?;
+        if param.indexed {
+            // dynamic values are indexed by the hash of their encoding
+            let topic = value.as_word().unwrap_or_else(|| keccak256(value.abi_encode_packed()));
+            topics.push(topic);
+        } else {
+            data.push(value);
+        }
+    }
+    if topics.len() > 4 {
+        return Err(format!("event `{signature}` has more than 4 topics"));
+    }
+    Ok(LogData::new_unchecked(topics, DynSolValue::Tuple(data).abi_encode_params().into()))
+}
+
+/// Returns the code of a contract emitting the log encoded in its calldata by
+/// [`emitter_calldata`], with `n_topics` topics.
+pub fn emitter_code(n_topics: usize) -> Bytes {
+    let data_offset = (32 * n_topics as u16).to_be_bytes();
+    let mut code = Vec::new();
+    // push the topics, the first one last
+    for i in (0..n_topics as u16).rev() {
+        code.push(0x61); // PUSH2
+        code.extend((32 * i).to_be_bytes());
+        code.push(0x35); // CALLDATALOAD
+    }
+    // copy the data after the topics to memory, and log it
+    code.push(0x61); // PUSH2
+    code.extend(data_offset);
+    code.extend([0x36, 0x03, 0x80]); // CALLDATASIZE SUB DUP1
+    code.push(0x61); // PUSH2
+    code.extend(data_offset);
+    code.extend([0x60, 0x00, 0x37]); // PUSH1 0 CALLDATACOPY
+    code.extend([0x60, 0x00, 0xa0 + n_topics as u8, 0x00]); // PUSH1 0 LOGn STOP
+    code.into()
+}
+
+/// Returns the calldata of the contract returned by [`emitter_code`] for the given log.
+pub fn emitter_calldata(log: &LogData) -> Bytes {
+    let mut input = log.topics().iter().flat_map(B256::as_slice).copied().collect::<Vec<_>>();
+    input.extend_from_slice(&log.data);
+    input.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{b256, U256};
+
+    #[test]
+    fn encode_optimism_eth_deposit() {
+        let template = optimism_template();
+        let signature = &template.eth_events[&BridgeEventKind::DepositInitiated];
+        let event = BridgeEvent {
+            template: None,
+            kind: BridgeEventKind::DepositInitiated,
+            from: address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045"),
+            to: address!("70997970C51812dc3A010C7d01b50e0d17dc79C8"),
+            amount: U256::from(1000),
+            l1_token: None,
+            l2_token: None,
+            extra_data: Bytes::new(),
+        };
+        let log = encode_log(signature, &event, Address::ZERO, template.eth_l2_token).unwrap();
+        assert_eq!(
+            log.topics(),
+            [
+                b256!("0x35d79ab81f2b2017e19afb5c5571778877782d7a8786f5907f93b0f4702f4f23"),
+                event.from.into_word(),
+                event.to.into_word(),
+            ]
+        );
+        let data = DynSolValue::Tuple(vec![
+            DynSolValue::Uint(event.amount, 256),
+            DynSolValue::Bytes(Vec::new()),
+        ]);
+        assert_eq!(log.data[..], data.abi_encode_params()[..]);
+        assert_eq!(emitter_calldata(&log).len(), 3 * 32 + log.data.len());
+    }
+
+    #[test]
+    fn reject_unknown_event_params() {
+        let templates = BridgeTemplates::default();
+        assert!(templates.get(DEFAULT_BRIDGE_TEMPLATE).is_some());
+
+        let mut template = optimism_template();
+        template
+            .eth_events
+            .insert(BridgeEventKind::DepositInitiated, "Deposit(address indexed sender)".into());
+        let err = templates.set("custom", template).unwrap_err();
+        assert!(err.starts_with("unknown parameter `sender`"), "{err}");
+        assert!(templates.get("custom").is_none());
+    }
+}
@@ -54,6 +54,11 @@ pub fn set_auto_impersonate_account(&self, enabled: bool) {
         self.state.write().auto_impersonate_accounts = enabled
     }
 
+    /// Returns `true` if every account is impersonated.
+    pub fn auto_impersonate_accounts(&self) -> bool {
+        self.state.read().auto_impersonate_accounts
+    }
+
     /// Returns all accounts that are currently being impersonated.
     pub fn impersonated_accounts(&self) -> AddressHashSet {
         self.state.read().impersonated_accounts.clone()
@@ -1,6 +1,9 @@
 //! Helper types for working with [revm](foundry_evm::revm)
 
-use crate::{mem::storage::MinedTransaction, revm::primitives::AccountInfo};
+use crate::{
+    eth::backend::schedule::SerializableSchedule, mem::storage::MinedTransaction,
+    revm::primitives::AccountInfo,
+};
 use alloy_consensus::Header;
 use alloy_primitives::{keccak256, Address, Bytes, B256, U256, U64};
 use alloy_rpc_types::BlockId;
@@ -398,6 +401,9 @@ pub struct SerializableState {
     /// Note: This is an Option for backwards compatibility.
     #[serde(default)]
     pub historical_states: Option<SerializableHistoricalStates>,
+    /// The scheduled transactions and block actions, see `anvil_scheduleTx`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<SerializableSchedule>,
 }
 
 impl SerializableState {
@@ -415,6 +421,229 @@ pub fn load(path: impl AsRef<Path>) -> Result<Self, FsPathError> {
     pub(crate) fn parse(path: &str) -> Result<Self, String> {
         Self::load(path).map_err(|err| err.to_string())
     }
+
+    /// Loads a state from a binary dump from the given file path.
+    ///
+    /// If the path is a directory, the dump is read from `<PATH>/state.bin`.
+    pub fn load_binary(path: impl AsRef<Path>) -> eyre::Result<Self> {
+        let path = path.as_ref();
+        let buf = if path.is_dir() {
+            foundry_common::fs::read(path.join("state.bin"))?
+        } else {
+            foundry_common::fs::read(path)?
+        };
+        Self::decode_binary(&buf)
+    }
+
+    /// This is used as the clap `value_parser` implementation for binary dumps
+    pub(crate) fn parse_binary(path: &str) -> Result<Self, String> {
+        Self::load_binary(path).map_err(|err| err.to_string())
+    }
+
+    /// Loads a JSON state file or a binary dump from the given file path, the format is detected
+    /// from the content.
+    ///
+    /// If the path is a directory, the state is read from `<PATH>/state.bin` if it exists, and
+    /// from `<PATH>/state.json` otherwise.
+    pub fn load_any(path: impl AsRef<Path>) -> eyre::Result<Self> {
+        let mut path = path.as_ref().to_path_buf();
+        if path.is_dir() {
+            let binary = path.join("state.bin");
+            path = if binary.exists() { binary } else { path.join("state.json") };
+        }
+        let buf = foundry_common::fs::read(&path)?;
+        if Self::is_binary(&buf) {
+            return Self::decode_binary(&buf)
+        }
+        serde_json::from_slice(&buf)
+            .map_err(|err| eyre::eyre!("failed to parse state file {}: {err}", path.display()))
+    }
+
+    /// This is used as the clap `value_parser` implementation for state files of either format
+    pub(crate) fn parse_any(path: &str) -> Result<Self, String> {
+        Self::load_any(path).map_err(|err| err.to_string())
+    }
+
+    /// Returns whether the buffer holds a binary dump, see [`SerializableState::encode_binary`].
+    pub fn is_binary(buf: &[u8]) -> bool {
+        buf.starts_with(STATE_BINARY_MAGIC)
+    }
+
+    /// Restricts the state to the given accounts, for partial loads.
+    ///
+    /// The block environment, blocks, transactions, historical states and schedule are dropped,
+    /// because they refer to accounts that are not loaded.
+    pub fn retain_accounts(&mut self, accounts: &[Address]) {
+        self.accounts.retain(|address, _| accounts.contains(address));
+        self.block = None;
+        self.best_block_number = None;
+        self.blocks.clear();
+        self.transactions.clear();
+        self.historical_states = None;
+        self.schedule = None;
+    }
+
+    /// Encodes this state into the versioned binary dump format.
+    ///
+    /// Layout (all integers big-endian):
+    ///
+    /// ```text
+    /// magic[8] major[1] minor[1] num_accounts[8]
+    /// ( address[20] nonce[8] balance[32] code_len[4] code[code_len]
+    ///   num_slots[4] ( slot[32] value[32] )* )*
+    /// num_sections[4] ( tag[1] len[8] payload[len] )*
+    /// ```
+    ///
+    /// The accounts are followed by tagged sections. The chain section holds the block
+    /// environment, blocks, transactions, historical states and schedule in the format of the JSON
+    /// state files, see [`Self::load`], so that it's as stable as those.
+    ///
+    /// Minor versions may only add sections, which are skipped when decoding with an older
+    /// version, so that dumps of any minor version of the current major version can be loaded.
+    /// Version 1 dumps have no minor version and no sections.
+    pub fn encode_binary(&self) -> eyre::Result<Vec<u8>> {
+        let size = self.accounts.values().fold(STATE_BINARY_MAGIC.len() + 10, |acc, account| {
+            acc + 20 + 8 + 32 + 4 + account.code.len() + 4 + account.storage.len() * 64
+        });
+        let mut buf = Vec::with_capacity(size);
+        buf.extend_from_slice(STATE_BINARY_MAGIC);
+        buf.extend_from_slice(&[STATE_BINARY_MAJOR_VERSION, STATE_BINARY_MINOR_VERSION]);
+        buf.extend_from_slice(&(self.accounts.len() as u64).to_be_bytes());
+        for (address, account) in &self.accounts {
+            buf.extend_from_slice(address.as_slice());
+            buf.extend_from_slice(&account.nonce.to_be_bytes());
+            buf.extend_from_slice(&account.balance.to_be_bytes::<32>());
+            buf.extend_from_slice(&(account.code.len() as u32).to_be_bytes());
+            buf.extend_from_slice(&account.code);
+            buf.extend_from_slice(&(account.storage.len() as u32).to_be_bytes());
+            for (slot, value) in &account.storage {
+                buf.extend_from_slice(slot.as_slice());
+                buf.extend_from_slice(value.as_slice());
+            }
+        }
+
+        let chain = Self {
+            block: self.block.clone(),
+            accounts: BTreeMap::new(),
+            best_block_number: self.best_block_number,
+            blocks: self.blocks.clone(),
+            transactions: self.transactions.clone(),
+            historical_states: self.historical_states.clone(),
+            schedule: self.schedule.clone(),
+        };
+        let mut sections = Vec::new();
+        if !chain.is_chain_empty() {
+            let payload = serde_json::to_vec(&chain)
+                .map_err(|err| eyre::eyre!("failed to encode the chain section: {err}"))?;
+            sections.push((STATE_BINARY_CHAIN_SECTION, payload));
+        }
+        buf.extend_from_slice(&(sections.len() as u32).to_be_bytes());
+        for (tag, payload) in sections {
+            buf.push(tag);
+            buf.extend_from_slice(&(payload.len() as u64).to_be_bytes());
+            buf.extend_from_slice(&payload);
+        }
+        Ok(buf)
+    }
+
+    /// Returns whether the state has no chain data besides its accounts.
+    fn is_chain_empty(&self) -> bool {
+        self.block.is_none() &&
+            self.best_block_number.is_none() &&
+            self.blocks.is_empty() &&
+            self.transactions.is_empty() &&
+            self.historical_states.is_none() &&
+            self.schedule.is_none()
+    }
+
+    /// Decodes a state previously encoded with [`SerializableState::encode_binary`].
+    pub fn decode_binary(buf: &[u8]) -> eyre::Result<Self> {
+        let mut reader = BinaryReader(buf);
+        eyre::ensure!(
+            reader.take(STATE_BINARY_MAGIC.len())? == STATE_BINARY_MAGIC,
+            "not an anvil binary state dump"
+        );
+        let major = reader.take(1)?[0];
+        eyre::ensure!(
+            major == 1 || major == STATE_BINARY_MAJOR_VERSION,
+            "unsupported binary state dump version {major}, the latest supported version is \
+             {STATE_BINARY_MAJOR_VERSION}"
+        );
+        if major >= 2 {
+            // later minor versions only add sections
+            let _minor = reader.take(1)?[0];
+        }
+
+        let num_accounts = reader.u64()?;
+        let mut accounts = BTreeMap::new();
+        for _ in 0..num_accounts {
+            let address = Address::from_slice(reader.take(20)?);
+            let nonce = reader.u64()?;
+            let balance = U256::from_be_slice(reader.take(32)?);
+            let code_len = reader.u32()? as usize;
+            let code = Bytes::copy_from_slice(reader.take(code_len)?);
+            let num_slots = reader.u32()?;
+            let mut storage = BTreeMap::new();
+            for _ in 0..num_slots {
+                let slot = B256::from_slice(reader.take(32)?);
+                let value = B256::from_slice(reader.take(32)?);
+                storage.insert(slot, value);
+            }
+            accounts.insert(address, SerializableAccountRecord { nonce, balance, code, storage });
+        }
+
+        let mut state = Self::default();
+        if major >= 2 {
+            let num_sections = reader.u32()?;
+            for _ in 0..num_sections {
+                let tag = reader.take(1)?[0];
+                let len = usize::try_from(reader.u64()?)?;
+                let payload = reader.take(len)?;
+                if tag == STATE_BINARY_CHAIN_SECTION {
+                    state = serde_json::from_slice(payload).map_err(|err| {
+                        eyre::eyre!("invalid chain section in binary state dump: {err}")
+                    })?;
+                }
+            }
+        }
+        eyre::ensure!(reader.0.is_empty(), "trailing bytes in binary state dump");
+
+        state.accounts = accounts;
+        Ok(state)
+    }
+}
+
+/// Magic prefix of the binary state dump format.
+const STATE_BINARY_MAGIC: &[u8; 8] = b"ANVILBIN";
+
+/// Current major version of the binary state dump format, see
+/// [`SerializableState::encode_binary`].
+const STATE_BINARY_MAJOR_VERSION: u8 = 2;
+
+/// Current minor version of the binary state dump format.
+const STATE_BINARY_MINOR_VERSION: u8 = 0;
+
+/// Tag of the section holding the chain data of a [`SerializableState`].
+const STATE_BINARY_CHAIN_SECTION: u8 = 1;
+
+/// Minimal cursor over a binary state dump.
+struct BinaryReader<'a>(&'a [u8]);
+
+impl<'a> BinaryReader<'a> {
+    fn take(&mut self, len: usize) -> eyre::Result<&'a [u8]> {
+        eyre::ensure!(self.0.len() >= len, "unexpected end of binary state dump");
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> eyre::Result<u32> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into()?))
+    }
+
+    fn u64(&mut self) -> eyre::Result<u64> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into()?))
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -612,4 +841,72 @@ fn test_deser_block() {
 
         let _block: SerializableBlock = serde_json::from_str(block).unwrap();
     }
+
+    #[test]
+    fn test_binary_state_roundtrip() {
+        let mut state = SerializableState::default();
+        state.accounts.insert(
+            Address::repeat_byte(1),
+            SerializableAccountRecord {
+                nonce: 7,
+                balance: U256::from(1337),
+                code: Bytes::from_static(&[0x60, 0x00]),
+                storage: BTreeMap::from([(B256::with_last_byte(1), B256::with_last_byte(2))]),
+            },
+        );
+        state.accounts.insert(
+            Address::repeat_byte(2),
+            SerializableAccountRecord {
+                nonce: 0,
+                balance: U256::MAX,
+                code: Bytes::new(),
+                storage: BTreeMap::new(),
+            },
+        );
+
+        let encoded = state.encode_binary().unwrap();
+        let decoded = SerializableState::decode_binary(&encoded).unwrap();
+        assert_eq!(decoded.accounts.len(), 2);
+        let account = &decoded.accounts[&Address::repeat_byte(1)];
+        assert_eq!(account.nonce, 7);
+        assert_eq!(account.balance, U256::from(1337));
+        assert_eq!(account.code, Bytes::from_static(&[0x60, 0x00]));
+        assert_eq!(account.storage[&B256::with_last_byte(1)], B256::with_last_byte(2));
+        assert_eq!(decoded.accounts[&Address::repeat_byte(2)].balance, U256::MAX);
+
+        assert!(SerializableState::decode_binary(&encoded[..encoded.len() - 1]).is_err());
+        assert!(SerializableState::decode_binary(b"{}").is_err());
+
+        // version 1 dumps have no minor version and end after the accounts
+        let magic = STATE_BINARY_MAGIC.len();
+        let v1 = [&STATE_BINARY_MAGIC[..], &[1], &encoded[magic + 2..encoded.len() - 4]].concat();
+        assert_eq!(SerializableState::decode_binary(&v1).unwrap().accounts.len(), 2);
+
+        // unknown major versions are rejected
+        let mut v3 = encoded;
+        v3[magic] = 3;
+        assert!(SerializableState::decode_binary(&v3).is_err());
+
+        state.block = Some(BlockEnv { number: U256::from(5), ..Default::default() });
+        state.best_block_number = Some(U64::from(5));
+        let mut encoded = state.encode_binary().unwrap();
+        let decoded = SerializableState::decode_binary(&encoded).unwrap();
+        assert_eq!(decoded.block.unwrap().number, U256::from(5));
+        assert_eq!(decoded.best_block_number, Some(U64::from(5)));
+        assert_eq!(decoded.accounts.len(), 2);
+
+        // later minor versions may add sections, which are skipped
+        encoded[magic + 1] += 1;
+        let num_sections = v1.len() + 1;
+        encoded[num_sections + 3] += 1;
+        encoded.push(0xff);
+        encoded.extend_from_slice(&3u64.to_be_bytes());
+        encoded.extend_from_slice(b"new");
+        let mut decoded = SerializableState::decode_binary(&encoded).unwrap();
+        assert_eq!(decoded.best_block_number, Some(U64::from(5)));
+
+        decoded.retain_accounts(&[Address::repeat_byte(2)]);
+        assert_eq!(decoded.accounts.keys().collect::<Vec<_>>(), [&Address::repeat_byte(2)]);
+        assert!(decoded.block.is_none() && decoded.best_block_number.is_none());
+    }
 }
@@ -4,28 +4,36 @@
         error::InvalidTransactionError,
         pool::transactions::PoolTransaction,
     },
-    inject_precompiles,
+    inject_precompiles, inject_protocol_limits,
     mem::inspector::Inspector,
     PrecompileFactory,
 };
 use alloy_consensus::{constants::EMPTY_WITHDRAWALS, Receipt, ReceiptWithBloom};
-use alloy_eips::{eip2718::Encodable2718, eip7685::EMPTY_REQUESTS_HASH};
+use alloy_eips::{
+    eip2718::Encodable2718,
+    eip2930::{AccessList, AccessListItem},
+    eip7685::EMPTY_REQUESTS_HASH,
+};
 use alloy_primitives::{Bloom, BloomInput, Log, B256};
-use anvil_core::eth::{
-    block::{Block, BlockInfo, PartialHeader},
-    transaction::{
-        DepositReceipt, PendingTransaction, TransactionInfo, TypedReceipt, TypedTransaction,
+use anvil_core::{
+    eth::{
+        block::{Block, BlockInfo, PartialHeader},
+        transaction::{
+            DepositReceipt, PendingTransaction, TransactionInfo, TypedReceipt, TypedTransaction,
+        },
+        trie,
     },
-    trie,
+    types::ProtocolLimits,
 };
 use foundry_evm::{
     backend::DatabaseError,
     revm::{
         interpreter::InstructionResult,
         primitives::{
-            BlockEnv, CfgEnvWithHandlerCfg, EVMError, EnvWithHandlerCfg, ExecutionResult, Output,
-            SpecId,
+            BlockEnv, CfgEnvWithHandlerCfg, EVMError, EnvWithHandlerCfg, EvmState, ExecutionResult,
+            Output, ResultAndState, SpecId,
         },
+        DatabaseCommit,
     },
     traces::CallTraceNode,
     utils::odyssey_handler_register,
@@ -43,6 +51,7 @@ pub struct ExecutedTransaction {
     logs: Vec<Log>,
     traces: Vec<CallTraceNode>,
     nonce: u64,
+    access_list: AccessList,
 }
 
 // == impl ExecutedTransaction ==
@@ -110,6 +119,8 @@ pub struct TransactionExecutor<'a, Db: ?Sized, V: TransactionValidator> {
     pub print_logs: bool,
     /// Precompiles to inject to the EVM.
     pub precompile_factory: Option<Arc<dyn PrecompileFactory>>,
+    /// Protocol-level limits to enforce in the EVM.
+    pub protocol_limits: ProtocolLimits,
 }
 
 impl<DB: Db + ?Sized, V: TransactionValidator> TransactionExecutor<'_, DB, V> {
@@ -179,7 +190,9 @@ pub fn execute(mut self) -> ExecutedTransactions {
             }
             let receipt = tx.create_receipt(&mut cumulative_gas_used);
 
-            let ExecutedTransaction { transaction, logs, out, traces, exit_reason: exit, .. } = tx;
+            let ExecutedTransaction {
+                transaction, logs, out, traces, exit_reason: exit, access_list, ..
+            } = tx;
             build_logs_bloom(logs.clone(), &mut bloom);
 
             let contract_address = out.as_ref().and_then(|out| {
@@ -203,6 +216,7 @@ pub fn execute(mut self) -> ExecutedTransactions {
                 out: out.map(Output::into_data),
                 nonce: tx.nonce,
                 gas_used: tx.gas_used,
+                access_list,
             };
 
             transaction_infos.push(info);
@@ -313,16 +327,21 @@ fn next(&mut self) -> Option<Self::Item> {
             inspector = inspector.with_log_collector();
         }
 
-        let exec_result = {
+        let (exec_result, access_list) = {
             let mut evm = new_evm_with_inspector(&mut *self.db, env, &mut inspector, self.odyssey);
             if let Some(factory) = &self.precompile_factory {
                 inject_precompiles(&mut evm, factory.precompiles());
             }
+            inject_protocol_limits(&mut evm, self.protocol_limits);
 
             trace!(target: "backend", "[{:?}] executing", transaction.hash());
-            // transact and commit the transaction
-            match evm.transact_commit() {
-                Ok(exec_result) => exec_result,
+            // transact and commit the transaction, keeping track of the touched state
+            match evm.transact() {
+                Ok(ResultAndState { result, state }) => {
+                    let access_list = touched_access_list(&state);
+                    evm.db_mut().commit(state);
+                    (result, access_list)
+                }
                 Err(err) => {
                     warn!(target: "backend", "[{:?}] failed to execute: {:?}", transaction.hash(), err);
                     match err {
@@ -382,6 +401,7 @@ fn next(&mut self) -> Option<Self::Item> {
             logs: logs.unwrap_or_default(),
             traces: inspector.tracer.map(|t| t.into_traces().into_nodes()).unwrap_or_default(),
             nonce,
+            access_list,
         };
 
         Some(TransactionExecutionOutcome::Executed(tx))
@@ -398,6 +418,21 @@ fn build_logs_bloom(logs: Vec<Log>, bloom: &mut Bloom) {
     }
 }
 
+/// Returns the accounts and storage slots of the state touched by a transaction, sorted.
+fn touched_access_list(state: &EvmState) -> AccessList {
+    let mut items = state
+        .iter()
+        .map(|(address, account)| {
+            let mut storage_keys =
+                account.storage.keys().map(|slot| B256::from(*slot)).collect::<Vec<_>>();
+            storage_keys.sort_unstable();
+            AccessListItem { address: *address, storage_keys }
+        })
+        .collect::<Vec<_>>();
+    items.sort_unstable_by_key(|item| item.address);
+    AccessList(items)
+}
+
 /// Creates a database with given database and inspector, optionally enabling odyssey features.
 pub fn new_evm_with_inspector<DB: revm::Database>(
     db: DB,
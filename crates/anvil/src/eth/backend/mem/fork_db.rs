@@ -71,6 +71,7 @@ fn dump_state(
             blocks,
             transactions,
             historical_states,
+            schedule: None,
         }))
     }
 
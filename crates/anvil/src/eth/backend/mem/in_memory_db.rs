@@ -67,6 +67,7 @@ fn dump_state(
             blocks,
             transactions,
             historical_states,
+            schedule: None,
         }))
     }
 
@@ -6,6 +6,7 @@
     config::PruneStateHistoryConfig,
     eth::{
         backend::{
+            bridge::BridgeTemplates,
             cheats::CheatsManager,
             db::{Db, MaybeFullDatabase, SerializableState},
             executor::{ExecutedTransactions, TransactionExecutor},
@@ -16,7 +17,9 @@
                 storage::MinedTransactionReceipt,
             },
             notifications::{NewBlockNotification, NewBlockNotifications},
+            schedule::{order_with_pool, BlockScheduler, ScheduledAt},
             time::{utc_from_secs, TimeManager},
+            tokens::TokenRegistry,
             validate::TransactionValidator,
         },
         error::{BlockchainError, ErrDetail, InvalidTransactionError},
@@ -25,25 +28,29 @@
         pool::transactions::PoolTransaction,
         util::get_precompiles_for,
     },
-    inject_precompiles,
+    inject_precompiles, inject_protocol_limits,
     mem::{
         inspector::Inspector,
         storage::{BlockchainStorage, InMemoryBlockStates, MinedBlockOutcome},
     },
     revm::{db::DatabaseRef, primitives::AccountInfo},
-    ForkChoice, NodeConfig, PrecompileFactory,
+    ForkChoice, HardforkSchedule, NodeConfig, PrecompileFactory,
 };
 use alloy_chains::NamedChain;
 use alloy_consensus::{
-    Account, Header, Receipt, ReceiptWithBloom, Signed, Transaction as TransactionTrait, TxEnvelope,
+    Account, Header, Receipt, ReceiptWithBloom, SignableTransaction, Signed,
+    Transaction as TransactionTrait, TxEnvelope, TxLegacy,
 };
+use alloy_dyn_abi::EventExt;
 use alloy_eips::eip4844::MAX_BLOBS_PER_BLOCK;
+use alloy_json_abi::Event;
 use alloy_network::{
     AnyHeader, AnyRpcBlock, AnyRpcTransaction, AnyTxEnvelope, AnyTxType, EthereumWallet,
     UnknownTxEnvelope, UnknownTypedTransaction,
 };
 use alloy_primitives::{
-    address, hex, keccak256, utils::Unit, Address, Bytes, TxHash, TxKind, B256, U256, U64,
+    address, hex, keccak256, utils::Unit, Address, Bytes, PrimitiveSignature as Signature, TxHash,
+    TxKind, B256, U256, U64,
 };
 use alloy_rpc_types::{
     anvil::Forking,
@@ -65,18 +72,25 @@
 use alloy_serde::{OtherFields, WithOtherFields};
 use alloy_signer_local::PrivateKeySigner;
 use alloy_trie::{proof::ProofRetainer, HashBuilder, Nibbles};
-use anvil_core::eth::{
-    block::{Block, BlockInfo},
-    transaction::{
-        optimism::DepositTransaction, DepositReceipt, MaybeImpersonatedTransaction,
-        PendingTransaction, ReceiptResponse, TransactionInfo, TypedReceipt, TypedTransaction,
+use anvil_core::{
+    eth::{
+        block::{Block, BlockInfo},
+        transaction::{
+            optimism::DepositTransaction, DepositReceipt, MaybeImpersonatedTransaction,
+            PendingTransaction, ReceiptResponse, TransactionInfo, TypedReceipt, TypedTransaction,
+        },
+        wallet::{Capabilities, DelegationCapability, WalletCapabilities},
+    },
+    types::{
+        BlockAction, DecodedLog, DecodedLogParam, ProtocolLimits, RevertInfo, RevertTraceFrame,
+        RichReceipt,
     },
-    wallet::{Capabilities, DelegationCapability, WalletCapabilities},
 };
 use anvil_rpc::error::RpcError;
 use chrono::Datelike;
 use eyre::{Context, Result};
 use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use foundry_common::fmt::format_token_raw;
 use foundry_evm::{
     backend::{DatabaseError, DatabaseResult, RevertStateSnapshotAction},
     constants::DEFAULT_CREATE2_DEPLOYER_RUNTIME_CODE,
@@ -90,7 +104,7 @@
             TxEnv, KECCAK_EMPTY,
         },
     },
-    traces::TracingInspectorConfig,
+    traces::{CallTraceArena, TracingInspectorConfig},
 };
 use futures::channel::mpsc::{unbounded, UnboundedSender};
 use op_alloy_consensus::{TxDeposit, DEPOSIT_TX_TYPE_ID};
@@ -183,6 +197,10 @@ pub struct Backend {
     time: TimeManager,
     /// Contains state of custom overrides.
     cheats: CheatsManager,
+    /// Tokens known to the token helpers.
+    tokens: TokenRegistry,
+    /// Templates of the bridge helpers.
+    bridges: BridgeTemplates,
     /// Contains fee data.
     fees: FeeManager,
     /// Initialised genesis.
@@ -203,6 +221,18 @@ pub struct Backend {
     slots_in_an_epoch: u64,
     /// Precompiles to inject to the EVM.
     precompile_factory: Option<Arc<dyn PrecompileFactory>>,
+    /// Protocol-level limits enforced by the EVM
+    protocol_limits: Arc<RwLock<ProtocolLimits>>,
+    /// Whether the code size limit is disabled when it's not set in the protocol limits
+    disable_code_size_limit: bool,
+    /// Whether to return rich error payloads for reverted transactions
+    rich_errors: bool,
+    /// Hardforks to activate at configured blocks or timestamps
+    hardfork_schedule: Option<HardforkSchedule>,
+    /// Transactions and calls executed by the miner
+    scheduler: BlockScheduler,
+    /// Whether nondeterministic inputs, like `prevrandao`, are derived instead of random
+    deterministic: bool,
     /// Prevent race conditions during mining
     mining: Arc<tokio::sync::Mutex<()>>,
     // === wallet === //
@@ -268,9 +298,35 @@ pub async fn with_genesis(
             states = states.disk_path(cache_path);
         }
 
-        let (slots_in_an_epoch, precompile_factory) = {
+        let (
+            slots_in_an_epoch,
+            precompile_factory,
+            protocol_limits,
+            disable_code_size_limit,
+            rich_errors,
+            hardfork_schedule,
+            deterministic,
+            tokens,
+        ) = {
             let cfg = node_config.read().await;
-            (cfg.slots_in_an_epoch, cfg.precompile_factory.clone())
+            (
+                cfg.slots_in_an_epoch,
+                cfg.precompile_factory.clone(),
+                cfg.protocol_limits(),
+                cfg.disable_code_size_limit,
+                cfg.rich_errors,
+                cfg.hardfork_schedule.clone(),
+                cfg.deterministic,
+                TokenRegistry::new(cfg.token_symbols.clone(), cfg.token_balance_slots.clone()),
+            )
+        };
+
+        let time = if deterministic {
+            // blocks are one second apart, unless a block time is configured
+            let step = automine_block_time.map_or(1, |block_time| block_time.as_secs().max(1));
+            TimeManager::deterministic(start_timestamp, step)
+        } else {
+            TimeManager::new(start_timestamp)
         };
 
         let (capabilities, executor_wallet) = if odyssey {
@@ -316,8 +372,10 @@ pub async fn with_genesis(
             states: Arc::new(RwLock::new(states)),
             env,
             fork,
-            time: TimeManager::new(start_timestamp),
+            time,
             cheats: Default::default(),
+            tokens,
+            bridges: Default::default(),
             new_block_listeners: Default::default(),
             fees,
             genesis,
@@ -330,6 +388,12 @@ pub async fn with_genesis(
             node_config,
             slots_in_an_epoch,
             precompile_factory,
+            protocol_limits: Arc::new(RwLock::new(protocol_limits)),
+            disable_code_size_limit,
+            rich_errors,
+            hardfork_schedule,
+            scheduler: Default::default(),
+            deterministic,
             mining: Arc::new(tokio::sync::Mutex::new(())),
             capabilities: Arc::new(RwLock::new(capabilities)),
             executor_wallet: Arc::new(RwLock::new(executor_wallet)),
@@ -597,11 +661,32 @@ pub fn time(&self) -> &TimeManager {
         &self.time
     }
 
+    /// Returns `true` if nondeterministic inputs are fixed, see
+    /// [`NodeConfig::with_deterministic`](crate::NodeConfig::with_deterministic)
+    pub fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
     /// Returns the `CheatsManager` responsible for executing cheatcodes
     pub fn cheats(&self) -> &CheatsManager {
         &self.cheats
     }
 
+    /// Returns the `TokenRegistry` of the token helpers
+    pub fn tokens(&self) -> &TokenRegistry {
+        &self.tokens
+    }
+
+    /// Returns the `BridgeTemplates` of the bridge helpers
+    pub fn bridges(&self) -> &BridgeTemplates {
+        &self.bridges
+    }
+
+    /// Returns the `BlockScheduler` of the transactions and calls executed by the miner
+    pub fn scheduler(&self) -> &BlockScheduler {
+        &self.scheduler
+    }
+
     /// Returns the `FeeManager` that manages fee/pricings
     pub fn fees(&self) -> &FeeManager {
         &self.fees
@@ -638,8 +723,15 @@ pub fn chain_id(&self) -> U256 {
         U256::from(self.env.read().cfg.chain_id)
     }
 
+    /// Sets the chain id used for subsequent blocks and transaction validation.
+    ///
+    /// The wallet capabilities of the previous chain id are moved to the new one.
     pub fn set_chain_id(&self, chain_id: u64) {
-        self.env.write().cfg.chain_id = chain_id;
+        let previous = std::mem::replace(&mut self.env.write().cfg.chain_id, chain_id);
+        let mut capabilities = self.capabilities.write();
+        if let Some(capability) = capabilities.remove(previous) {
+            capabilities.insert(chain_id, capability);
+        }
     }
 
     /// Returns balance of the given account.
@@ -682,9 +774,20 @@ pub async fn set_storage_at(
         self.db.write().await.set_storage_at(address, slot.into(), val)
     }
 
-    /// Returns the configured specid
+    /// Returns the spec id of the next block, which includes the hardforks of the schedule that
+    /// are active for it.
     pub fn spec_id(&self) -> SpecId {
-        self.env.read().handler_cfg.spec_id
+        let number = self.env.read().block.number.saturating_to::<u64>().saturating_add(1);
+        self.spec_id_at(number, self.time.current_call_timestamp())
+    }
+
+    /// Returns the spec id of a block with the given number and timestamp: the latest hardfork of
+    /// the schedule that is active for it, or the configured one.
+    pub fn spec_id_at(&self, number: u64, timestamp: u64) -> SpecId {
+        self.hardfork_schedule
+            .as_ref()
+            .and_then(|schedule| schedule.hardfork_at(number, timestamp))
+            .map_or_else(|| self.env.read().handler_cfg.spec_id, SpecId::from)
     }
 
     /// Returns true for post London
@@ -760,6 +863,26 @@ pub fn gas_limit(&self) -> u64 {
         self.env.read().block.gas_limit.saturating_to()
     }
 
+    /// Returns the protocol-level limits enforced by the EVM
+    pub fn protocol_limits(&self) -> ProtocolLimits {
+        *self.protocol_limits.read()
+    }
+
+    /// Returns the protocol-level limits with the unset code size limit disabled, if the node was
+    /// configured to disable it.
+    pub fn resolve_protocol_limits(&self, mut limits: ProtocolLimits) -> ProtocolLimits {
+        if self.disable_code_size_limit && limits.code_size_limit.is_none() {
+            limits.code_size_limit = Some(usize::MAX);
+        }
+        limits
+    }
+
+    /// Sets the protocol-level limits enforced by the EVM
+    pub fn set_protocol_limits(&self, limits: ProtocolLimits) {
+        self.env.write().cfg.limit_contract_code_size = limits.code_size_limit;
+        *self.protocol_limits.write() = limits;
+    }
+
     /// Sets the block gas limit
     pub fn set_gas_limit(&self, gas_limit: u64) {
         self.env.write().block.gas_limit = U256::from(gas_limit);
@@ -810,6 +933,7 @@ pub async fn create_state_snapshot(&self) -> U256 {
         let id = self.db.write().await.snapshot_state();
         trace!(target: "backend", "creating snapshot {} at {}", id, num);
         self.active_state_snapshots.lock().insert(id, (num, hash));
+        self.scheduler.snapshot(id);
         id
     }
 
@@ -857,6 +981,8 @@ pub async fn revert_state_snapshot(&self, id: U256) -> Result<bool, BlockchainEr
                 basefee: env.block.basefee,
                 ..Default::default()
             };
+
+            self.scheduler.revert(id);
         }
         Ok(self.db.write().await.revert_state(id, RevertStateSnapshotAction::RevertRemove))
     }
@@ -887,10 +1013,11 @@ pub async fn serialized_state(
             transactions,
             historical_states,
         )?;
-        state.ok_or_else(|| {
+        let mut state = state.ok_or_else(|| {
             RpcError::invalid_params("Dumping state not supported with the current configuration")
-                .into()
-        })
+        })?;
+        state.schedule = self.scheduler.serialized_state();
+        Ok(state)
     }
 
     /// Write all chain data to serialized bytes buffer
@@ -906,6 +1033,15 @@ pub async fn dump_state(
         Ok(encoder.finish().unwrap_or_default().into())
     }
 
+    /// Write all chain data into the versioned binary dump format.
+    ///
+    /// See [`SerializableState::encode_binary`].
+    pub async fn dump_state_binary(&self) -> Result<Bytes, BlockchainError> {
+        let state = self.serialized_state(false).await?;
+        let buf = state.encode_binary().map_err(|err| BlockchainError::Message(err.to_string()))?;
+        Ok(buf.into())
+    }
+
     /// Apply [SerializableState] data to the backend storage.
     pub async fn load_state(&self, state: SerializableState) -> Result<bool, BlockchainError> {
         // load the blocks and transactions into the storage
@@ -969,11 +1105,21 @@ pub async fn load_state(&self, state: SerializableState) -> Result<bool, Blockch
             self.states.write().load_states(historical_states);
         }
 
+        if let Some(schedule) = state.schedule {
+            self.scheduler.load_state(schedule)?;
+        }
+
         Ok(true)
     }
 
     /// Deserialize and add all chain data to the backend storage
     pub async fn load_state_bytes(&self, buf: Bytes) -> Result<bool, BlockchainError> {
+        if SerializableState::is_binary(&buf) {
+            let state = SerializableState::decode_binary(&buf)
+                .map_err(|_| BlockchainError::FailedToDecodeStateDump)?;
+            return self.load_state(state).await
+        }
+
         let orig_buf = &buf.0[..];
         let mut decoder = GzDecoder::new(orig_buf);
         let mut decoded_data = Vec::new();
@@ -998,9 +1144,16 @@ fn next_env(&self) -> EnvWithHandlerCfg {
         env.block.number = env.block.number.saturating_add(U256::from(1));
         env.block.basefee = U256::from(self.base_fee());
         env.block.timestamp = U256::from(self.time.current_call_timestamp());
+        self.apply_hardfork_schedule(&mut env);
         env
     }
 
+    /// Sets the spec id of the block of the given env, see [`Self::spec_id_at`].
+    fn apply_hardfork_schedule(&self, env: &mut EnvWithHandlerCfg) {
+        env.handler_cfg.spec_id =
+            self.spec_id_at(env.block.number.saturating_to(), env.block.timestamp.saturating_to());
+    }
+
     /// Creates an EVM instance with optionally injected precompiles.
     #[allow(clippy::type_complexity)]
     fn new_evm_with_inspector_ref<'i, 'db>(
@@ -1019,6 +1172,7 @@ fn new_evm_with_inspector_ref<'i, 'db>(
         if let Some(factory) = &self.precompile_factory {
             inject_precompiles(&mut evm, factory.precompiles());
         }
+        inject_protocol_limits(&mut evm, self.protocol_limits());
         evm
     }
 
@@ -1096,6 +1250,7 @@ pub async fn with_pending_block<F, T>(
             enable_steps_tracing: self.enable_steps_tracing,
             print_logs: self.print_logs,
             precompile_factory: self.precompile_factory.clone(),
+            protocol_limits: self.protocol_limits(),
             odyssey: self.odyssey,
         };
 
@@ -1148,8 +1303,12 @@ async fn do_mine_block(
             env.block.basefee = U256::from(current_base_fee);
             env.block.blob_excess_gas_and_price = current_excess_blob_gas_and_price;
 
-            // pick a random value for prevrandao
-            env.block.prevrandao = Some(B256::random());
+            // pick a random value for prevrandao, or derive it from the block number
+            env.block.prevrandao = Some(if self.deterministic {
+                keccak256(env.block.number.to_be_bytes::<32>())
+            } else {
+                B256::random()
+            });
 
             let best_hash = self.blockchain.storage.read().best_hash;
 
@@ -1165,12 +1324,33 @@ async fn do_mine_block(
                 // finally set the next block timestamp, this is done just before execution, because
                 // there can be concurrent requests that can delay acquiring the db lock and we want
                 // to ensure the timestamp is as close as possible to the actual execution.
+                let parent_spec_id = self.spec_id_at(
+                    env.block.number.saturating_to::<u64>().saturating_sub(1),
+                    env.block.timestamp.saturating_to(),
+                );
                 env.block.timestamp = U256::from(self.time.next_timestamp());
 
+                self.apply_hardfork_schedule(&mut env);
+                if env.handler_cfg.spec_id != parent_spec_id {
+                    node_info!(
+                        "Activated hardfork {:?} at block {block_number}",
+                        env.handler_cfg.spec_id
+                    );
+                }
+
+                // scheduled transactions go first unless they follow pool transactions of the same
+                // sender, block actions last so that their nonces follow the other transactions
+                let scheduled = self
+                    .scheduler
+                    .take_due_transactions(block_number.to(), env.block.timestamp.to());
+                let mut pending = order_with_pool(scheduled.clone(), pool_transactions);
+                let actions = self.block_action_transactions(&**db, &env, &pending);
+                pending.extend(actions);
+
                 let executor = TransactionExecutor {
                     db: &mut **db,
                     validator: self,
-                    pending: pool_transactions.into_iter(),
+                    pending: pending.into_iter(),
                     block_env: env.block.clone(),
                     cfg_env: CfgEnvWithHandlerCfg::new(env.cfg.clone(), env.handler_cfg),
                     parent_hash: best_hash,
@@ -1180,9 +1360,12 @@ async fn do_mine_block(
                     print_logs: self.print_logs,
                     odyssey: self.odyssey,
                     precompile_factory: self.precompile_factory.clone(),
+                    protocol_limits: self.protocol_limits(),
                 };
                 let executed_tx = executor.execute();
 
+                self.reschedule_missed(&**db, scheduled, &executed_tx.included, block_number.to());
+
                 // we also need to update the new blockhash in the db itself
                 let block_hash = executed_tx.block.block.header.hash_slow();
                 db.insert_block_hash(U256::from(executed_tx.block.block.header.number), block_hash);
@@ -1298,6 +1481,76 @@ async fn do_mine_block(
         outcome
     }
 
+    /// Schedules the scheduled transactions that were due in the mined block with the given number
+    /// but not included for the next block, unless their nonce was used, in which case they are
+    /// dropped
+    fn reschedule_missed(
+        &self,
+        db: &dyn Db,
+        scheduled: Vec<Arc<PoolTransaction>>,
+        included: &[Arc<PoolTransaction>],
+        block_number: u64,
+    ) {
+        for tx in scheduled {
+            if included.iter().any(|included| included.hash() == tx.hash()) {
+                continue
+            }
+            let sender = *tx.pending_transaction.sender();
+            let nonce = db.basic_ref(sender).ok().flatten().unwrap_or_default().nonce;
+            if tx.pending_transaction.nonce() < nonce {
+                node_info!("Dropped scheduled transaction {:?}, its nonce was used", tx.hash());
+            } else {
+                trace!(target: "backend", "rescheduling tx {:?} for the next block", tx.hash());
+                self.scheduler.schedule(tx, ScheduledAt::Block(block_number + 1));
+            }
+        }
+    }
+
+    /// Builds the unsigned transactions of the block actions due in the block of `env`, sent after
+    /// the `pending` transactions
+    fn block_action_transactions(
+        &self,
+        db: &dyn Db,
+        env: &EnvWithHandlerCfg,
+        pending: &[Arc<PoolTransaction>],
+    ) -> Vec<Arc<PoolTransaction>> {
+        let actions = self.scheduler.due_actions(env.block.number.to());
+        if actions.is_empty() {
+            return Vec::new()
+        }
+
+        let mut nonces = HashMap::<Address, u64>::default();
+        actions
+            .into_iter()
+            .map(|action| {
+                let BlockAction { from, to, data, value, gas, .. } = action;
+                let nonce = nonces.entry(from).or_insert_with(|| {
+                    let sent = pending.iter().filter(|tx| *tx.pending_transaction.sender() == from);
+                    db.basic_ref(from).ok().flatten().unwrap_or_default().nonce +
+                        sent.count() as u64
+                });
+                let tx = TxLegacy {
+                    chain_id: Some(env.cfg.chain_id),
+                    nonce: *nonce,
+                    gas_price: env.block.basefee.to(),
+                    gas_limit: gas.unwrap_or(BlockAction::DEFAULT_GAS_LIMIT),
+                    to: TxKind::Call(to),
+                    value,
+                    input: data,
+                };
+                *nonce += 1;
+                // the same signature that bypasses signing in `eth_sendUnsignedTransaction`
+                let signature = Signature::from_scalars_and_parity(
+                    B256::with_last_byte(1),
+                    B256::with_last_byte(1),
+                    false,
+                );
+                let tx = TypedTransaction::Legacy(tx.into_signed(signature));
+                Arc::new(PoolTransaction::new(PendingTransaction::with_impersonated(tx, from)))
+            })
+            .collect()
+    }
+
     /// Executes the [TransactionRequest] without writing to the DB
     ///
     /// # Errors
@@ -2039,6 +2292,50 @@ pub async fn storage_at(
         .await?
     }
 
+    /// Returns the storage slot of `address` that holds the word returned by calling it with
+    /// `input`.
+    ///
+    /// Every slot read by the call is overwritten in turn with a marker value, until the call
+    /// returns the marker. Returns `None` if the returned word isn't stored in a single slot, for
+    /// example because it's computed or packed with other values.
+    pub async fn find_storage_slot(
+        &self,
+        address: Address,
+        input: Bytes,
+    ) -> Result<Option<U256>, BlockchainError> {
+        // address-sized, so that it can also be returned by calls returning an address
+        let marker = U256::from_be_slice(&keccak256("anvil.storage_slot_marker")[12..]);
+        self.with_database_at(None, |state, block| {
+            let request =
+                WithOtherFields::new(TransactionRequest::default().to(address).input(input.into()));
+            let (_, _, _, accessed) = self.call_with_state(
+                state.as_dyn(),
+                request.clone(),
+                FeeDetails::zero(),
+                block.clone(),
+            )?;
+            let mut slots = accessed
+                .get(&address)
+                .map(|account| account.storage.keys().copied().collect::<Vec<_>>())
+                .unwrap_or_default();
+            slots.sort_unstable();
+
+            for slot in slots {
+                let mut db = CacheDB::new(state.as_dyn());
+                db.insert_account_storage(address, slot, marker)?;
+                let (exit, out, _, _) =
+                    self.call_with_state(&db, request.clone(), FeeDetails::zero(), block.clone())?;
+                if let Some(Output::Call(out)) = out {
+                    if exit.is_ok() && out.get(..32).map(U256::from_be_slice) == Some(marker) {
+                        return Ok(Some(slot));
+                    }
+                }
+            }
+            Ok(None)
+        })
+        .await?
+    }
+
     /// Returns the code of the address
     ///
     /// If the code is not present and fork mode is enabled then this will try to fetch it from the
@@ -2165,6 +2462,97 @@ pub(crate) fn mined_transaction(&self, hash: B256) -> Option<MinedTransaction> {
         self.blockchain.storage.read().transactions.get(&hash).cloned()
     }
 
+    /// Returns whether rich error payloads are returned for reverted transactions
+    pub fn rich_errors(&self) -> bool {
+        self.rich_errors
+    }
+
+    /// Returns the revert details of the given mined transaction, if it reverted
+    pub fn revert_info(&self, hash: B256) -> Option<RevertInfo> {
+        let storage = self.blockchain.storage.read();
+        let tx = storage.transactions.get(&hash)?;
+        if tx.receipt.as_receipt_with_bloom().receipt.status.coerce_status() {
+            return None;
+        }
+
+        let decoder = RevertDecoder::new();
+        let data = tx.info.out.clone().unwrap_or_default();
+        let trace = tx
+            .info
+            .traces
+            .iter()
+            .map(|node| {
+                let trace = &node.trace;
+                RevertTraceFrame {
+                    depth: trace.depth,
+                    kind: trace.kind.to_string(),
+                    from: trace.caller,
+                    to: trace.address,
+                    gas_used: trace.gas_used,
+                    success: trace.success,
+                    reason: (!trace.success)
+                        .then(|| decoder.maybe_decode(&trace.output, None))
+                        .flatten(),
+                }
+            })
+            .collect();
+
+        Some(RevertInfo {
+            transaction_hash: hash,
+            reason: decoder.maybe_decode(&data, None),
+            data,
+            gas_used: tx.info.gas_used,
+            trace,
+        })
+    }
+
+    /// Returns the receipt of a transaction mined by the node with the details of its execution,
+    /// decoding its logs with the given events
+    pub fn rich_receipt(&self, receipt: ReceiptResponse, events: &[Event]) -> Option<RichReceipt> {
+        let tx = self.mined_transaction(receipt.transaction_hash)?;
+        let nodes = &tx.info.traces;
+
+        // the state changes of a call are only kept if none of its callers reverted
+        let is_committed = |mut idx: usize| loop {
+            let node = &nodes[idx];
+            if !node.trace.success {
+                return false
+            }
+            match node.parent {
+                Some(parent) => idx = parent,
+                None => return true,
+            }
+        };
+
+        let mut created_contracts = Vec::new();
+        let mut destroyed_contracts = Vec::new();
+        let mut gas_used_by_depth = Vec::<u64>::new();
+        for (idx, node) in nodes.iter().enumerate() {
+            let trace = &node.trace;
+            if trace.kind.is_any_create() && is_committed(idx) {
+                created_contracts.push(trace.address);
+            }
+            if node.is_selfdestruct() && is_committed(idx) {
+                destroyed_contracts.push(trace.address);
+            }
+            if gas_used_by_depth.len() <= trace.depth {
+                gas_used_by_depth.resize(trace.depth + 1, 0);
+            }
+            gas_used_by_depth[trace.depth] += trace.gas_used;
+        }
+
+        let decoded_logs = tx.receipt.logs().iter().map(|log| decode_log(log, events)).collect();
+
+        Some(RichReceipt {
+            receipt,
+            access_list: tx.info.access_list,
+            created_contracts,
+            destroyed_contracts,
+            decoded_logs,
+            gas_used_by_depth,
+        })
+    }
+
     /// Returns the traces for the given block
     pub(crate) fn mined_parity_trace_block(
         &self,
@@ -2196,6 +2584,20 @@ pub async fn debug_trace_transaction(
         Ok(GethTrace::Default(Default::default()))
     }
 
+    /// Returns the recorded call trace arena of the mined transaction, including the steps
+    pub fn debug_trace_transaction_arena(
+        &self,
+        hash: B256,
+    ) -> Result<Option<CallTraceArena>, BlockchainError> {
+        self.blockchain
+            .storage
+            .read()
+            .transactions
+            .get(&hash)
+            .map(|tx| tx.call_trace_arena())
+            .transpose()
+    }
+
     fn mined_geth_trace_transaction(
         &self,
         hash: B256,
@@ -2681,6 +3083,27 @@ pub async fn rollback(&self, common_block: Block) -> Result<(), BlockchainError>
     }
 }
 
+/// Decodes a log with the first of the events it matches, if any
+fn decode_log(log: &alloy_primitives::Log, events: &[Event]) -> Option<DecodedLog> {
+    events.iter().find_map(|event| {
+        let decoded = event.decode_log(&log.data, true).ok()?;
+        let (mut indexed, mut body) = (decoded.indexed.into_iter(), decoded.body.into_iter());
+        let params = event
+            .inputs
+            .iter()
+            .map(|param| {
+                let value = if param.indexed { indexed.next() } else { body.next() };
+                DecodedLogParam {
+                    name: param.name.clone(),
+                    indexed: param.indexed,
+                    value: value.as_ref().map(format_token_raw).unwrap_or_default(),
+                }
+            })
+            .collect();
+        Some(DecodedLog { event: event.signature(), params })
+    })
+}
+
 /// Get max nonce from transaction pool by address
 fn get_pool_transactions_nonce(
     pool_transactions: &[Arc<PoolTransaction>],
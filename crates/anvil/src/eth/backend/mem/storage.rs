@@ -36,7 +36,8 @@
     backend::MemDb,
     revm::primitives::Env,
     traces::{
-        CallKind, FourByteInspector, GethTraceBuilder, ParityTraceBuilder, TracingInspectorConfig,
+        CallKind, CallTraceArena, FourByteInspector, GethTraceBuilder, ParityTraceBuilder,
+        TracingInspectorConfig,
     },
 };
 use parking_lot::RwLock;
@@ -581,6 +582,19 @@ pub fn geth_trace(&self, opts: GethDebugTracingOptions) -> Result<GethTrace, Blo
             )
             .into())
     }
+
+    /// Returns the recorded call trace arena, including the steps, for the debugger.
+    pub fn call_trace_arena(&self) -> Result<CallTraceArena, BlockchainError> {
+        if self.info.traces.iter().all(|node| node.trace.steps.is_empty()) {
+            return Err(RpcError::invalid_params(
+                "no steps were recorded for the transaction, steps tracing must be enabled",
+            )
+            .into())
+        }
+        let mut arena = CallTraceArena::default();
+        *arena.nodes_mut() = self.info.traces.clone();
+        Ok(arena)
+    }
 }
 
 /// Intermediary Anvil representation of a receipt
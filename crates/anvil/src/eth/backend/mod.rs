@@ -5,8 +5,11 @@
 /// In-memory Backend
 pub mod mem;
 
+pub mod bridge;
 pub mod cheats;
+pub mod schedule;
 pub mod time;
+pub mod tokens;
 
 pub mod executor;
 pub mod fork;
@@ -0,0 +1,206 @@
+//! Support for transactions and calls executed by the miner, `anvil_scheduleTx` and
+//! `anvil_addBlockAction`
+
+use crate::eth::{error::BlockchainError, pool::transactions::PoolTransaction};
+use alloy_eips::eip2718::{Decodable2718, Encodable2718};
+use alloy_primitives::{map::HashMap, Bytes, U256};
+use anvil_core::{
+    eth::transaction::{PendingTransaction, TypedTransaction},
+    types::BlockAction,
+};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, sync::Arc};
+
+/// When a scheduled transaction is executed
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScheduledAt {
+    /// In the block with this number
+    Block(u64),
+    /// In the first block with a timestamp at or after this one
+    Timestamp(u64),
+}
+
+impl ScheduledAt {
+    /// Returns whether a block with the given number and timestamp is due
+    fn is_due(&self, block_number: u64, timestamp: u64) -> bool {
+        match *self {
+            Self::Block(number) => number <= block_number,
+            Self::Timestamp(at) => at <= timestamp,
+        }
+    }
+}
+
+/// Transactions scheduled for future blocks and calls executed every few blocks
+#[derive(Clone, Debug, Default)]
+pub struct BlockScheduler {
+    /// shareable state
+    state: Arc<RwLock<BlockSchedulerState>>,
+    /// The state at each `evm_snapshot`, restored by `evm_revert`
+    snapshots: Arc<Mutex<HashMap<U256, BlockSchedulerState>>>,
+}
+
+impl BlockScheduler {
+    /// Schedules the transaction
+    pub fn schedule(&self, transaction: Arc<PoolTransaction>, at: ScheduledAt) {
+        trace!(target: "schedule", "Scheduling tx {:?} at {at:?}", transaction.hash());
+        self.state.write().transactions.push((transaction, at));
+    }
+
+    /// Removes and returns the scheduled transactions due in the block with the given number and
+    /// timestamp, in the order they were scheduled
+    pub fn take_due_transactions(
+        &self,
+        block_number: u64,
+        timestamp: u64,
+    ) -> Vec<Arc<PoolTransaction>> {
+        let mut state = self.state.write();
+        if state.transactions.is_empty() {
+            return Vec::new()
+        }
+        let (due, pending) = std::mem::take(&mut state.transactions)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(_, at)| at.is_due(block_number, timestamp));
+        state.transactions = pending;
+        due.into_iter().map(|(tx, _)| tx).collect()
+    }
+
+    /// Registers a block action and returns its id
+    pub fn add_action(&self, action: BlockAction) -> u64 {
+        let mut state = self.state.write();
+        state.last_action_id += 1;
+        let id = state.last_action_id;
+        trace!(target: "schedule", "Adding block action {id}: {action:?}");
+        state.actions.insert(id, action);
+        id
+    }
+
+    /// Removes a block action, returns `false` if there is no action with this id
+    pub fn remove_action(&self, id: u64) -> bool {
+        trace!(target: "schedule", "Removing block action {id}");
+        self.state.write().actions.remove(&id).is_some()
+    }
+
+    /// Returns the block actions due in the block with the given number, ordered by id
+    pub fn due_actions(&self, block_number: u64) -> Vec<BlockAction> {
+        self.state
+            .read()
+            .actions
+            .values()
+            .filter(|action| action.is_due(block_number))
+            .cloned()
+            .collect()
+    }
+
+    /// Saves the current state for the `evm_snapshot` with the given id
+    pub fn snapshot(&self, id: U256) {
+        let state = self.state.read().clone();
+        self.snapshots.lock().insert(id, state);
+    }
+
+    /// Restores the state saved for the `evm_snapshot` with the given id, if any
+    pub fn revert(&self, id: U256) {
+        if let Some(state) = self.snapshots.lock().remove(&id) {
+            trace!(target: "schedule", "Reverting to snapshot {id}");
+            *self.state.write() = state;
+        }
+    }
+
+    /// Returns the serializable state, or `None` if nothing is scheduled
+    pub fn serialized_state(&self) -> Option<SerializableSchedule> {
+        let state = self.state.read();
+        if state.transactions.is_empty() && state.actions.is_empty() {
+            return None
+        }
+        let transactions = state
+            .transactions
+            .iter()
+            .map(|(tx, at)| (tx.pending_transaction.transaction.encoded_2718().into(), *at))
+            .collect();
+        Some(SerializableSchedule {
+            transactions,
+            actions: state.actions.clone(),
+            last_action_id: state.last_action_id,
+        })
+    }
+
+    /// Replaces the state with the given serialized state
+    pub fn load_state(&self, schedule: SerializableSchedule) -> Result<(), BlockchainError> {
+        let transactions = schedule
+            .transactions
+            .into_iter()
+            .map(|(tx, at)| {
+                let tx = TypedTransaction::decode_2718(&mut tx.as_ref())
+                    .map_err(|_| BlockchainError::FailedToDecodeSignedTransaction)?;
+                let tx = PoolTransaction::new(PendingTransaction::new(tx)?);
+                Ok((Arc::new(tx), at))
+            })
+            .collect::<Result<_, BlockchainError>>()?;
+        *self.state.write() = BlockSchedulerState {
+            transactions,
+            actions: schedule.actions,
+            last_action_id: schedule.last_action_id,
+        };
+        Ok(())
+    }
+}
+
+/// Orders the scheduled transactions due in a block with the transactions of the pool.
+///
+/// Scheduled transactions go first, unless their sender has pool transactions with lower nonces,
+/// in which case they go right after the last of those, so that they don't fail with a nonce gap.
+pub fn order_with_pool(
+    scheduled: Vec<Arc<PoolTransaction>>,
+    pool: Vec<Arc<PoolTransaction>>,
+) -> Vec<Arc<PoolTransaction>> {
+    let sender_nonce =
+        |tx: &PoolTransaction| (*tx.pending_transaction.sender(), tx.pending_transaction.nonce());
+
+    let mut first = Vec::new();
+    let mut after = HashMap::<usize, Vec<Arc<PoolTransaction>>>::default();
+    for tx in scheduled {
+        let (sender, nonce) = sender_nonce(&tx);
+        let prev = pool.iter().rposition(|pool_tx| {
+            let (pool_sender, pool_nonce) = sender_nonce(pool_tx);
+            pool_sender == sender && pool_nonce < nonce
+        });
+        match prev {
+            Some(index) => after.entry(index).or_default().push(tx),
+            None => first.push(tx),
+        }
+    }
+
+    let mut ordered = first;
+    for (index, tx) in pool.into_iter().enumerate() {
+        ordered.push(tx);
+        if let Some(mut txs) = after.remove(&index) {
+            txs.sort_by_key(|tx| tx.pending_transaction.nonce());
+            ordered.extend(txs);
+        }
+    }
+    ordered
+}
+
+/// Container type for all the state variables
+#[derive(Clone, Debug, Default)]
+pub struct BlockSchedulerState {
+    /// Scheduled transactions, in the order they were scheduled
+    pub transactions: Vec<(Arc<PoolTransaction>, ScheduledAt)>,
+    /// Block actions by id
+    pub actions: BTreeMap<u64, BlockAction>,
+    /// The id of the last registered block action
+    pub last_action_id: u64,
+}
+
+/// The scheduled transactions and block actions of a state dump
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SerializableSchedule {
+    /// The EIP-2718 encoded scheduled transactions, in the order they were scheduled
+    pub transactions: Vec<(Bytes, ScheduledAt)>,
+    /// Block actions by id
+    pub actions: BTreeMap<u64, BlockAction>,
+    /// The id of the last registered block action
+    pub last_action_id: u64,
+}
@@ -23,6 +23,11 @@ pub struct TimeManager {
     next_exact_timestamp: Arc<RwLock<Option<u64>>>,
     /// The interval to use when determining the next block's timestamp
     interval: Arc<RwLock<Option<u64>>>,
+    /// If set, the wall clock is replaced by a virtual clock that advances by this many seconds
+    /// for every block, see [`TimeManager::deterministic`]
+    step: Option<u64>,
+    /// The current time of the virtual clock
+    clock: Arc<RwLock<u64>>,
 }
 
 impl TimeManager {
@@ -32,15 +37,41 @@ pub fn new(start_timestamp: u64) -> Self {
             offset: Default::default(),
             next_exact_timestamp: Default::default(),
             interval: Default::default(),
+            step: None,
+            clock: Default::default(),
         };
         time_manager.reset(start_timestamp);
         time_manager
     }
 
+    /// Creates a time manager that does not depend on the wall clock.
+    ///
+    /// Time only advances by `step` seconds for every new block, starting at `start_timestamp`.
+    pub fn deterministic(start_timestamp: u64, step: u64) -> Self {
+        let time_manager = Self { step: Some(step), ..Self::new(start_timestamp) };
+        time_manager.reset(start_timestamp);
+        time_manager
+    }
+
+    /// Returns the current time, in seconds since unix epoch.
+    ///
+    /// In deterministic mode, this is the time of the virtual clock at the next block.
+    fn now(&self) -> i128 {
+        match self.step {
+            Some(step) => self.clock.read().saturating_add(step) as i128,
+            None => duration_since_unix_epoch().as_secs() as i128,
+        }
+    }
+
     /// Resets the current time manager to the given timestamp, resetting the offsets and
     /// next block timestamp option
     pub fn reset(&self, start_timestamp: u64) {
-        let current = duration_since_unix_epoch().as_secs() as i128;
+        let current = if self.step.is_some() {
+            *self.clock.write() = start_timestamp;
+            start_timestamp as i128
+        } else {
+            duration_since_unix_epoch().as_secs() as i128
+        };
         *self.last_timestamp.write() = start_timestamp;
         *self.offset.write() = (start_timestamp as i128) - current;
         self.next_exact_timestamp.write().take();
@@ -100,7 +131,7 @@ pub fn remove_block_timestamp_interval(&self) -> bool {
 
     /// Computes the next timestamp without updating internals
     fn compute_next_timestamp(&self) -> (u64, Option<i128>) {
-        let current = duration_since_unix_epoch().as_secs() as i128;
+        let current = self.now();
         let last_timestamp = *self.last_timestamp.read();
 
         let (mut next_timestamp, update_offset) =
@@ -128,6 +159,10 @@ pub fn next_timestamp(&self) -> u64 {
             *self.offset.write() = next_offset;
         }
         *self.last_timestamp.write() = next_timestamp;
+        if let Some(step) = self.step {
+            let mut clock = self.clock.write();
+            *clock = clock.saturating_add(step);
+        }
         next_timestamp
     }
 
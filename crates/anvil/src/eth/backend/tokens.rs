@@ -0,0 +1,96 @@
+//! Support for the token helpers, `anvil_dealERC20` and `anvil_dealERC721`
+
+use alloy_primitives::{keccak256, map::AddressHashMap, Address, U256};
+use anvil_core::types::TokenRef;
+use parking_lot::RwLock;
+use std::{collections::HashMap, sync::Arc};
+
+/// Tokens known to the node: the symbols they can be referred to by, and the storage slots of
+/// their balances mappings when these can't be found by probing.
+#[derive(Clone, Debug, Default)]
+pub struct TokenRegistry {
+    /// shareable state
+    state: Arc<RwLock<TokenRegistryState>>,
+}
+
+impl TokenRegistry {
+    /// Creates a registry with the given symbols and balance slots.
+    pub fn new(
+        symbols: impl IntoIterator<Item = (String, Address)>,
+        balance_slots: impl IntoIterator<Item = (Address, U256)>,
+    ) -> Self {
+        let registry = Self::default();
+        for (symbol, token) in symbols {
+            registry.register_symbol(&symbol, token);
+        }
+        registry.state.write().balance_slots.extend(balance_slots);
+        registry
+    }
+
+    /// Registers the symbol of a token. Symbols are case-insensitive.
+    pub fn register_symbol(&self, symbol: &str, token: Address) {
+        trace!(target: "tokens", "Registering token {symbol} at {token:?}");
+        self.state.write().symbols.insert(symbol.to_uppercase(), token);
+    }
+
+    /// Returns the address of a token, or `None` if it's an unknown symbol.
+    pub fn resolve(&self, token: &TokenRef) -> Option<Address> {
+        match token {
+            TokenRef::Address(address) => Some(*address),
+            TokenRef::Symbol(symbol) => {
+                self.state.read().symbols.get(&symbol.to_uppercase()).copied()
+            }
+        }
+    }
+
+    /// Sets the storage slot of the balances mapping of a token.
+    pub fn set_balance_slot(&self, token: Address, slot: U256) {
+        trace!(target: "tokens", "Setting the balances slot of {token:?} to {slot}");
+        self.state.write().balance_slots.insert(token, slot);
+    }
+
+    /// Returns the storage slot holding the balance of `owner`, if the slot of the balances
+    /// mapping of the token is set.
+    pub fn balance_slot(&self, token: Address, owner: Address) -> Option<U256> {
+        let slot = *self.state.read().balance_slots.get(&token)?;
+        Some(mapping_slot(owner, slot))
+    }
+}
+
+/// Container type for all the state variables
+#[derive(Clone, Debug, Default)]
+pub struct TokenRegistryState {
+    /// Token addresses by uppercase symbol
+    pub symbols: HashMap<String, Address>,
+    /// Storage slots of the balances mappings, following the Solidity storage layout
+    pub balance_slots: AddressHashMap<U256>,
+}
+
+/// Returns the storage slot of the value of `key` in the Solidity mapping at `slot`.
+fn mapping_slot(key: Address, slot: U256) -> U256 {
+    let mut preimage = [0u8; 64];
+    preimage[..32].copy_from_slice(key.into_word().as_slice());
+    preimage[32..].copy_from_slice(&slot.to_be_bytes::<32>());
+    U256::from_be_bytes(keccak256(preimage).0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, b256};
+
+    #[test]
+    fn resolve_tokens_and_balance_slots() {
+        let dai = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+        let registry = TokenRegistry::new([("Dai".to_string(), dai)], []);
+        assert_eq!(registry.resolve(&TokenRef::Symbol("DAI".to_string())), Some(dai));
+        assert_eq!(registry.resolve(&TokenRef::Symbol("USDC".to_string())), None);
+        assert_eq!(registry.resolve(&TokenRef::Address(dai)), Some(dai));
+
+        let owner = address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045");
+        assert_eq!(registry.balance_slot(dai, owner), None);
+        registry.set_balance_slot(dai, U256::from(2));
+        let slot = b256!("0x85efa08969febcb72bd7c79e3795763c6a77762d27bd830f8777227bf55e86a3");
+        assert_eq!(registry.balance_slot(dai, owner), Some(U256::from_be_bytes(slot.0)));
+    }
+}
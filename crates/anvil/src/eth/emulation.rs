@@ -0,0 +1,161 @@
+//! Emulation of the RPC quirks of other execution clients, enabled with `--emulate`.
+
+use crate::eth::error::BlockchainError;
+use anvil_rpc::error::{ErrorCode, RpcError};
+use std::{fmt, str::FromStr};
+
+/// Transaction validation errors, as reported by anvil, geth and nethermind.
+///
+/// Erigon reports the same messages as geth.
+const TRANSACTION_ERRORS: &[(&str, &str, &str)] = &[
+    ("nonce too low", "nonce too low", "OldNonce"),
+    ("Nonce too high", "nonce too high", "NonceGap"),
+    ("nonce has max value", "nonce has max value", "NonceTooFar"),
+    (
+        "Insufficient funds for gas * price + value",
+        "insufficient funds for gas * price + value",
+        "InsufficientFunds",
+    ),
+    ("transaction already imported", "already known", "AlreadyKnown"),
+    (
+        "max fee per gas less than block base fee",
+        "max fee per gas less than block base fee",
+        "FeeTooLow",
+    ),
+    ("intrinsic gas too low", "intrinsic gas too low", "GasLimitExceeded"),
+];
+
+/// An execution client whose RPC behavior can be emulated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EmulatedClient {
+    Geth,
+    Erigon,
+    Nethermind,
+}
+
+impl EmulatedClient {
+    /// Returns the `web3_clientVersion` reported by the client.
+    pub fn client_version(&self) -> &'static str {
+        match self {
+            Self::Geth => "Geth/v1.14.12-stable/linux-amd64/go1.23.4",
+            Self::Erigon => "erigon/2.60.10/linux-amd64/go1.22.5",
+            Self::Nethermind => "Nethermind/v1.30.3+87c86379/linux-x64/dotnet9.0.0",
+        }
+    }
+
+    /// Rewrites an error returned by anvil into the code and message the client would return.
+    pub fn adjust_error(&self, mut err: RpcError) -> RpcError {
+        match err.code {
+            ErrorCode::ExecutionError if *self == Self::Nethermind => {
+                // nethermind reports reverts as VM errors, with the revert data in `data`
+                let data = err.data.take().and_then(|data| data.as_str().map(str::to_string));
+                err.code = ErrorCode::ServerError(-32015);
+                err.message = "VM execution error.".into();
+                err.data = Some(match data {
+                    Some(data) => format!("Reverted {data}").into(),
+                    None => "Reverted".into(),
+                });
+            }
+            ErrorCode::TransactionRejected => {
+                let message = TRANSACTION_ERRORS
+                    .iter()
+                    .find(|(anvil, ..)| err.message == *anvil)
+                    .map(|(_, geth, nethermind)| match self {
+                        Self::Geth | Self::Erigon => *geth,
+                        Self::Nethermind => *nethermind,
+                    });
+                if let Some(message) = message {
+                    err.message = message.into();
+                }
+                err.code = match self {
+                    // geth and erigon use the generic server error for rejected transactions
+                    Self::Geth | Self::Erigon => ErrorCode::ServerError(-32000),
+                    Self::Nethermind => ErrorCode::ServerError(-32010),
+                };
+            }
+            ErrorCode::ServerError(-32001)
+                if *self != Self::Nethermind &&
+                    err.message == BlockchainError::BlockNotFound.to_string() =>
+            {
+                // geth and erigon don't use the EIP-1898 code for unknown blocks
+                err.code = ErrorCode::ServerError(-32000);
+                err.message = "header not found".into();
+            }
+            _ => {}
+        }
+        err
+    }
+}
+
+impl FromStr for EmulatedClient {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "geth" => Ok(Self::Geth),
+            "erigon" => Ok(Self::Erigon),
+            "nethermind" => Ok(Self::Nethermind),
+            _ => Err(format!("Unknown client: `{s}`, expected geth, erigon or nethermind")),
+        }
+    }
+}
+
+impl fmt::Display for EmulatedClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Geth => f.write_str("geth"),
+            Self::Erigon => f.write_str("erigon"),
+            Self::Nethermind => f.write_str("nethermind"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adjust_errors() {
+        let revert = || RpcError {
+            code: ErrorCode::ExecutionError,
+            message: "execution reverted".into(),
+            data: Some("0x01".into()),
+        };
+        assert_eq!(EmulatedClient::Geth.adjust_error(revert()), revert());
+        let err = EmulatedClient::Nethermind.adjust_error(revert());
+        assert_eq!(err.code, ErrorCode::ServerError(-32015));
+        assert_eq!(err.message, "VM execution error.");
+        assert_eq!(err.data, Some("Reverted 0x01".into()));
+
+        let nonce = || RpcError::transaction_rejected("nonce too low");
+        let err = EmulatedClient::Erigon.adjust_error(nonce());
+        assert_eq!(err.code, ErrorCode::ServerError(-32000));
+        assert_eq!(err.message, "nonce too low");
+        let err = EmulatedClient::Nethermind.adjust_error(nonce());
+        assert_eq!(err.code, ErrorCode::ServerError(-32010));
+        assert_eq!(err.message, "OldNonce");
+        let err = EmulatedClient::Geth
+            .adjust_error(RpcError::transaction_rejected("transaction already imported"));
+        assert_eq!(err.message, "already known");
+
+        let not_found = || RpcError {
+            code: ErrorCode::ServerError(-32001),
+            message: BlockchainError::BlockNotFound.to_string().into(),
+            data: None,
+        };
+        let err = EmulatedClient::Geth.adjust_error(not_found());
+        assert_eq!(err.code, ErrorCode::ServerError(-32000));
+        assert_eq!(err.message, "header not found");
+        assert_eq!(EmulatedClient::Nethermind.adjust_error(not_found()), not_found());
+        // Other errors with the same code are kept as is.
+        let other = || RpcError {
+            code: ErrorCode::ServerError(-32001),
+            message: "Resource unavailable".into(),
+            data: None,
+        };
+        assert_eq!(EmulatedClient::Erigon.adjust_error(other()), other());
+
+        assert_eq!("Geth".parse::<EmulatedClient>(), Ok(EmulatedClient::Geth));
+        assert!("besu".parse::<EmulatedClient>().is_err());
+    }
+}
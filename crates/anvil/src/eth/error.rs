@@ -5,7 +5,7 @@
 use alloy_rpc_types::BlockNumberOrTag;
 use alloy_signer::Error as SignerError;
 use alloy_transport::TransportError;
-use anvil_core::eth::wallet::WalletError;
+use anvil_core::{eth::wallet::WalletError, types::RevertInfo};
 use anvil_rpc::{
     error::{ErrorCode, RpcError},
     response::ResponseResult,
@@ -38,6 +38,9 @@ pub enum BlockchainError {
     FailedToDecodeSignedTransaction,
     #[error("Failed to decode transaction")]
     FailedToDecodeTransaction,
+    /// Thrown if a submitted transaction reverted while rich errors are enabled
+    #[error("execution reverted{}", .0.reason.as_ref().map(|r| format!(": {r}")).unwrap_or_default())]
+    TransactionReverted(Box<RevertInfo>),
     #[error("Failed to decode receipt")]
     FailedToDecodeReceipt,
     #[error("Failed to decode state")]
@@ -386,6 +389,11 @@ fn to_rpc_result(self) -> ResponseResult {
                     }
                     _ => RpcError::transaction_rejected(err.to_string()),
                 },
+                BlockchainError::TransactionReverted(ref info) => RpcError {
+                    code: ErrorCode::ExecutionError,
+                    message: err.to_string().into(),
+                    data: serde_json::to_value(info).ok(),
+                },
                 BlockchainError::FeeHistory(err) => RpcError::invalid_params(err.to_string()),
                 BlockchainError::EmptyRawTransactionData => {
                     RpcError::invalid_params("Empty transaction data")
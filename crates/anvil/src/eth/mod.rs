@@ -1,4 +1,5 @@
 pub mod api;
+pub mod emulation;
 pub mod otterscan;
 pub mod sign;
 pub use api::EthApi;
@@ -1,5 +1,13 @@
-use alloy_primitives::Address;
+use alloy_primitives::{Address, Bytes};
+use anvil_core::types::ProtocolLimits;
 use foundry_evm::revm::precompile::Precompile;
+use revm::{
+    interpreter::{
+        gas::calculate_initial_tx_gas, CreateOutcome, Gas, InstructionResult, InterpreterResult,
+    },
+    primitives::{EVMError, InvalidTransaction, SpecId},
+    FrameOrResult, FrameResult,
+};
 use std::{fmt::Debug, sync::Arc};
 
 /// Object-safe trait that enables injecting extra precompiles when using
@@ -28,6 +36,82 @@ pub fn inject_precompiles<DB: revm::Database, I>(
     }));
 }
 
+/// Appends handler registers to `evm` that enforce the given protocol `limits`.
+///
+/// The code size limit is part of the [`CfgEnv`](revm::primitives::CfgEnv) and is not handled
+/// here. The initcode size limit can only be lowered below the one derived from the code size
+/// limit: transactions exceeding it are rejected and `CREATE`s exceeding it fail, consuming all
+/// the gas passed to them.
+pub fn inject_protocol_limits<DB: revm::Database, I>(
+    evm: &mut revm::Evm<'_, I, DB>,
+    limits: ProtocolLimits,
+) {
+    if let Some(limit) = limits.initcode_size_limit {
+        evm.handler.append_handler_register_box(Box::new(move |handler| {
+            let prev_env = handler.validation.env.clone();
+            handler.validation.env = Arc::new(move |env| {
+                if env.tx.transact_to.is_create() && env.tx.data.len() > limit {
+                    return Err(EVMError::Transaction(InvalidTransaction::CreateInitCodeSizeLimit))
+                }
+                prev_env(env)
+            });
+
+            let prev_create = handler.execution.create.clone();
+            handler.execution.create = Arc::new(move |ctx, inputs| {
+                if inputs.init_code.len() > limit {
+                    let result = InterpreterResult {
+                        result: InstructionResult::CreateInitCodeSizeLimit,
+                        output: Bytes::new(),
+                        gas: Gas::new_spent(inputs.gas_limit),
+                    };
+                    return Ok(FrameOrResult::Result(FrameResult::Create(CreateOutcome::new(
+                        result, None,
+                    ))))
+                }
+                prev_create(ctx, inputs)
+            });
+        }));
+    }
+
+    if limits.calldata_zero_byte_gas.is_some() || limits.calldata_nonzero_byte_gas.is_some() {
+        evm.handler.append_handler_register_box(Box::new(move |handler| {
+            let spec_id = handler.cfg.spec_id;
+            handler.validation.initial_tx_gas = Arc::new(move |env| {
+                let zero_bytes = env.tx.data.iter().filter(|b| **b == 0).count() as u64;
+                let nonzero_bytes = env.tx.data.len() as u64 - zero_bytes;
+                let default_nonzero_byte_gas =
+                    if spec_id.is_enabled_in(SpecId::ISTANBUL) { 16 } else { 68 };
+
+                let mut gas = calculate_initial_tx_gas(
+                    spec_id,
+                    &env.tx.data,
+                    env.tx.transact_to.is_create(),
+                    &env.tx.access_list,
+                    env.tx.authorization_list.as_ref().map_or(0, |list| list.len() as u64),
+                );
+                // Replace the default calldata cost with the configured one.
+                gas.initial_gas -= zero_bytes * 4 + nonzero_bytes * default_nonzero_byte_gas;
+                let zero_byte_gas = limits.calldata_zero_byte_gas.unwrap_or(4);
+                let nonzero_byte_gas =
+                    limits.calldata_nonzero_byte_gas.unwrap_or(default_nonzero_byte_gas);
+                gas.initial_gas = gas
+                    .initial_gas
+                    .saturating_add(zero_bytes.saturating_mul(zero_byte_gas))
+                    .saturating_add(nonzero_bytes.saturating_mul(nonzero_byte_gas));
+
+                if gas.initial_gas > env.tx.gas_limit {
+                    return Err(InvalidTransaction::CallGasCostMoreThanGasLimit.into())
+                }
+                // EIP-7623
+                if spec_id.is_enabled_in(SpecId::PRAGUE) && gas.floor_gas > env.tx.gas_limit {
+                    return Err(InvalidTransaction::GasFloorMoreThanGasLimit.into())
+                }
+                Ok(gas)
+            });
+        }));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{evm::inject_precompiles, PrecompileFactory};
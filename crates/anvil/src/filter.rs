@@ -11,7 +11,10 @@
 use futures::{channel::mpsc::Receiver, Stream, StreamExt};
 use std::{
     pin::Pin,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
     task::{Context, Poll},
     time::{Duration, Instant},
 };
@@ -30,12 +33,14 @@ pub struct Filters {
     active_filters: FilterMap,
     /// How long we keep a live the filter after the last poll
     keepalive: Duration,
+    /// If set, ids are assigned sequentially instead of randomly
+    next_id: Option<Arc<AtomicU64>>,
 }
 
 impl Filters {
     /// Adds a new `EthFilter` to the set
     pub async fn add_filter(&self, filter: EthFilter) -> String {
-        let id = new_id();
+        let id = self.new_id();
         trace!(target: "node::filter", "Adding new filter id {}", id);
         let mut filters = self.active_filters.lock().await;
         filters.insert(id.clone(), (filter, self.next_deadline()));
@@ -73,6 +78,20 @@ pub async fn uninstall_filter(&self, id: &str) -> Option<EthFilter> {
         self.active_filters.lock().await.remove(id).map(|(f, _)| f)
     }
 
+    /// Returns a new id for a filter or a subscription
+    pub fn new_id(&self) -> String {
+        match &self.next_id {
+            Some(next_id) => format!("{:#x}", next_id.fetch_add(1, Ordering::Relaxed)),
+            None => SubscriptionId::random_hex().to_string(),
+        }
+    }
+
+    /// Assigns ids sequentially instead of randomly, so that they are the same across runs
+    pub fn with_deterministic_ids(mut self) -> Self {
+        self.next_id = Some(Arc::new(AtomicU64::new(1)));
+        self
+    }
+
     /// The duration how long to keep alive stale filters
     pub fn keep_alive(&self) -> Duration {
         self.keepalive
@@ -103,15 +122,11 @@ fn default() -> Self {
         Self {
             active_filters: Arc::new(Default::default()),
             keepalive: Duration::from_secs(ACTIVE_FILTER_TIMEOUT_SECS),
+            next_id: None,
         }
     }
 }
 
-/// returns a new random hex id
-fn new_id() -> String {
-    SubscriptionId::random_hex().to_string()
-}
-
 /// Represents a poll based filter
 #[derive(Debug)]
 pub enum EthFilter {
@@ -214,9 +214,77 @@ fn from(fork: OptimismHardfork) -> Self {
     }
 }
 
+/// The point at which a scheduled hardfork activates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ActivationPoint {
+    /// Activates at the given block number.
+    Block(u64),
+    /// Activates at the first block with a timestamp greater than or equal to the given one.
+    Timestamp(u64),
+}
+
+impl ActivationPoint {
+    /// Returns whether the hardfork is active for a block with the given number and timestamp.
+    pub fn is_active(&self, number: u64, timestamp: u64) -> bool {
+        match *self {
+            Self::Block(block) => number >= block,
+            Self::Timestamp(activation) => timestamp >= activation,
+        }
+    }
+}
+
+/// Hardforks that activate at configured blocks or timestamps while the node is running.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HardforkSchedule {
+    activations: Vec<(ChainHardfork, ActivationPoint)>,
+}
+
+impl HardforkSchedule {
+    /// Parses a comma separated schedule of `<hardfork>=<block>` or `<hardfork>=@<timestamp>`
+    /// entries, e.g. `cancun=0,prague=1000`.
+    pub fn parse(s: &str, optimism: bool) -> eyre::Result<Self> {
+        let mut activations = Vec::new();
+        for entry in s.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let Some((name, at)) = entry.split_once('=') else {
+                bail!("invalid hardfork schedule entry `{entry}`, expected `<hardfork>=<block>`");
+            };
+            let hardfork: ChainHardfork = if optimism {
+                OptimismHardfork::from_str(name.trim())?.into()
+            } else {
+                EthereumHardfork::from_str(name.trim())?.into()
+            };
+            let at = at.trim();
+            let at = match at.strip_prefix('@') {
+                Some(timestamp) => ActivationPoint::Timestamp(timestamp.parse().map_err(|_| {
+                    eyre::eyre!("invalid activation timestamp `{timestamp}` for {name}")
+                })?),
+                None => ActivationPoint::Block(
+                    at.parse()
+                        .map_err(|_| eyre::eyre!("invalid activation block `{at}` for {name}"))?,
+                ),
+            };
+            activations.push((hardfork, at));
+        }
+        if activations.is_empty() {
+            bail!("empty hardfork schedule");
+        }
+        Ok(Self { activations })
+    }
+
+    /// Returns the latest hardfork that is active for a block with the given number and
+    /// timestamp, if any.
+    pub fn hardfork_at(&self, number: u64, timestamp: u64) -> Option<ChainHardfork> {
+        self.activations
+            .iter()
+            .filter(|(_, at)| at.is_active(number, timestamp))
+            .map(|(hardfork, _)| *hardfork)
+            .max_by_key(|hardfork| SpecId::from(*hardfork) as u8)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::EthereumHardfork;
+    use super::*;
 
     #[test]
     fn test_hardfork_blocks() {
@@ -229,4 +297,23 @@ fn test_hardfork_blocks() {
         let hf: EthereumHardfork = 12244000u64.into();
         assert_eq!(hf, EthereumHardfork::Berlin);
     }
+
+    #[test]
+    fn test_hardfork_schedule() {
+        let schedule =
+            HardforkSchedule::parse("cancun=0, prague=1000,pragueeof=@2000", false).unwrap();
+        assert_eq!(schedule.hardfork_at(0, 0), Some(EthereumHardfork::Cancun.into()));
+        assert_eq!(schedule.hardfork_at(999, 1999), Some(EthereumHardfork::Cancun.into()));
+        assert_eq!(schedule.hardfork_at(1000, 0), Some(EthereumHardfork::Prague.into()));
+        assert_eq!(schedule.hardfork_at(10, 2000), Some(EthereumHardfork::PragueEOF.into()));
+
+        let schedule = HardforkSchedule::parse("granite=5", true).unwrap();
+        assert_eq!(schedule.hardfork_at(4, 0), None);
+        assert_eq!(schedule.hardfork_at(5, 0), Some(OptimismHardfork::Granite.into()));
+
+        assert!(HardforkSchedule::parse("cancun", false).is_err());
+        assert!(HardforkSchedule::parse("cancun=soon", false).is_err());
+        assert!(HardforkSchedule::parse("regolith=0", false).is_err());
+        assert!(HardforkSchedule::parse("", false).is_err());
+    }
 }
@@ -12,7 +12,7 @@
         EthApi,
     },
     filter::Filters,
-    logging::{LoggingManager, NodeLogLayer},
+    logging::{LoggingManager, NodeLogLayer, RotatingLogFile},
     server::error::{NodeError, NodeResult},
     service::NodeService,
     shutdown::Signal,
@@ -21,7 +21,7 @@
 use alloy_primitives::{Address, U256};
 use alloy_signer_local::PrivateKeySigner;
 use eth::backend::fork::ClientFork;
-use eyre::Result;
+use eyre::{Result, WrapErr};
 use foundry_common::provider::{ProviderBuilder, RetryProvider};
 use foundry_evm::revm;
 use futures::{FutureExt, TryFutureExt};
@@ -44,17 +44,18 @@
 
 mod config;
 pub use config::{
-    AccountGenerator, ForkChoice, NodeConfig, CHAIN_ID, DEFAULT_GAS_LIMIT, VERSION_MESSAGE,
+    AccountGenerator, ForkChoice, NodeConfig, CHAIN_ID, DEFAULT_GAS_LIMIT,
+    DETERMINISTIC_GENESIS_TIMESTAMP, VERSION_MESSAGE,
 };
 
 mod hardfork;
-pub use hardfork::EthereumHardfork;
+pub use hardfork::{EthereumHardfork, HardforkSchedule};
 
 /// ethereum related implementations
 pub mod eth;
 /// Evm related abstractions
 mod evm;
-pub use evm::{inject_precompiles, PrecompileFactory};
+pub use evm::{inject_precompiles, inject_protocol_limits, PrecompileFactory};
 /// support for polling filters
 pub mod filter;
 /// commandline output
@@ -128,7 +129,17 @@ pub async fn spawn(config: NodeConfig) -> (EthApi, NodeHandle) {
 /// # }
 /// ```
 pub async fn try_spawn(mut config: NodeConfig) -> Result<(EthApi, NodeHandle)> {
-    let logger = if config.enable_tracing { init_tracing() } else { Default::default() };
+    let logger = if config.enable_tracing {
+        let log_file = config
+            .log_file
+            .as_ref()
+            .map(|path| RotatingLogFile::new(path, config.log_rotate))
+            .transpose()
+            .wrap_err("failed to open log file")?;
+        init_tracing(log_file)
+    } else {
+        Default::default()
+    };
     logger.set_enabled(!config.silent);
 
     let backend = Arc::new(config.setup().await?);
@@ -149,6 +160,8 @@ pub async fn try_spawn(mut config: NodeConfig) -> Result<(EthApi, NodeHandle)> {
         transaction_order,
         genesis,
         mixed_mining,
+        emulate,
+        batch_gas_target,
         ..
     } = config.clone();
 
@@ -201,7 +214,10 @@ pub async fn try_spawn(mut config: NodeConfig) -> Result<(EthApi, NodeHandle)> {
         fee_history_service.insert_cache_entry_for_block(header.hash_slow(), &header);
     }
 
-    let filters = Filters::default();
+    let mut filters = Filters::default();
+    if config.deterministic {
+        filters = filters.with_deterministic_ids();
+    }
 
     // create the cloneable api wrapper
     let api = EthApi::new(
@@ -214,6 +230,8 @@ pub async fn try_spawn(mut config: NodeConfig) -> Result<(EthApi, NodeHandle)> {
         logger,
         filters.clone(),
         transaction_order,
+        emulate,
+        batch_gas_target,
     );
 
     // spawn the node service
@@ -438,25 +456,37 @@ fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
     }
 }
 
+/// Initializes the stdout logger and, if a log file is given, the structured JSON logs written to
+/// it.
+///
+/// The levels logged to the file default to `RUST_LOG`, or `info`, and can be changed at runtime
+/// with [`LoggingManager::set_file_filter`].
 #[doc(hidden)]
-pub fn init_tracing() -> LoggingManager {
-    use tracing_subscriber::prelude::*;
+pub fn init_tracing(log_file: Option<RotatingLogFile>) -> LoggingManager {
+    use tracing_subscriber::{prelude::*, reload, EnvFilter};
+
+    let mut manager = LoggingManager::default();
+    let file_layer = log_file.map(|file| {
+        let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+        let (filter, handle) = reload::Layer::new(filter);
+        manager.file_filter = Some(handle);
+        tracing_subscriber::fmt::layer().json().with_writer(Arc::new(file)).with_filter(filter)
+    });
+    let registry = tracing_subscriber::Registry::default().with(file_layer);
 
-    let manager = LoggingManager::default();
     // check whether `RUST_LOG` is explicitly set
     let _ = if std::env::var("RUST_LOG").is_ok() {
-        tracing_subscriber::Registry::default()
-            .with(tracing_subscriber::EnvFilter::from_default_env())
-            .with(tracing_subscriber::fmt::layer())
+        registry
+            .with(tracing_subscriber::fmt::layer().with_filter(EnvFilter::from_default_env()))
             .try_init()
     } else {
-        tracing_subscriber::Registry::default()
-            .with(NodeLogLayer::new(manager.clone()))
+        registry
             .with(
                 tracing_subscriber::fmt::layer()
                     .without_time()
                     .with_target(false)
-                    .with_level(false),
+                    .with_level(false)
+                    .with_filter(NodeLogLayer::new(manager.clone())),
             )
             .try_init()
     };
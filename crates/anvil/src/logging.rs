@@ -1,9 +1,17 @@
 //! User facing Logger
 
-use parking_lot::RwLock;
-use std::sync::Arc;
+use parking_lot::{Mutex, RwLock};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    sync::Arc,
+};
 use tracing::{subscriber::Interest, Metadata};
-use tracing_subscriber::{layer::Context, Layer};
+use tracing_subscriber::{
+    layer::{Context, Filter},
+    reload, EnvFilter, Layer, Registry,
+};
 
 /// The target that identifies the events intended to be logged to stdout
 pub(crate) const NODE_USER_LOG_TARGET: &str = "node::user";
@@ -11,10 +19,14 @@
 /// The target that identifies the events coming from the `console.log` invocations.
 pub(crate) const EVM_CONSOLE_LOG_TARGET: &str = "node::console";
 
+/// The number of rotated log files that are kept.
+const ROTATED_LOG_FILES: usize = 5;
+
 /// A logger that listens for node related events and displays them.
 ///
 /// This layer is intended to be used as filter for `NODE_USER_LOG_TARGET` events that will
-/// eventually be logged to stdout
+/// eventually be logged to stdout. It can also be used as a per-layer [`Filter`], so it doesn't
+/// affect other layers such as the log file.
 #[derive(Clone, Debug, Default)]
 pub struct NodeLogLayer {
     state: LoggingManager,
@@ -48,11 +60,26 @@ fn enabled(&self, metadata: &Metadata<'_>, _ctx: Context<'_, S>) -> bool {
     }
 }
 
+impl<S> Filter<S> for NodeLogLayer
+where
+    S: tracing::Subscriber,
+{
+    fn callsite_enabled(&self, metadata: &'static Metadata<'static>) -> Interest {
+        Layer::<S>::register_callsite(self, metadata)
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, ctx: &Context<'_, S>) -> bool {
+        Layer::<S>::enabled(self, metadata, ctx.clone())
+    }
+}
+
 /// Contains the configuration of the logger
 #[derive(Clone, Debug)]
 pub struct LoggingManager {
     /// Whether the logger is currently enabled
     pub enabled: Arc<RwLock<bool>>,
+    /// Reloads the filter of the log file, if any
+    pub(crate) file_filter: Option<reload::Handle<EnvFilter, Registry>>,
 }
 
 impl LoggingManager {
@@ -66,10 +93,105 @@ pub fn set_enabled(&self, enabled: bool) {
         let mut current = self.enabled.write();
         *current = enabled;
     }
+
+    /// Sets the levels logged to the log file, using the `RUST_LOG` syntax, for example
+    /// `info,anvil::eth::backend=debug`
+    pub fn set_file_filter(&self, directives: &str) -> Result<(), String> {
+        let Some(handle) = &self.file_filter else {
+            return Err("no log file configured, see `--log-file`".to_string())
+        };
+        let filter = EnvFilter::try_new(directives).map_err(|err| err.to_string())?;
+        handle.reload(filter).map_err(|err| err.to_string())
+    }
 }
 
 impl Default for LoggingManager {
     fn default() -> Self {
-        Self { enabled: Arc::new(RwLock::new(true)) }
+        Self { enabled: Arc::new(RwLock::new(true)), file_filter: None }
+    }
+}
+
+/// A log file that is rotated once it exceeds a maximum size.
+///
+/// The rotated file is renamed to `<path>.1`, and previously rotated files are shifted up to
+/// `<path>.5`.
+#[derive(Debug)]
+pub struct RotatingLogFile {
+    path: PathBuf,
+    max_size: Option<u64>,
+    state: Mutex<LogFileState>,
+}
+
+#[derive(Debug)]
+struct LogFileState {
+    file: File,
+    size: u64,
+}
+
+impl RotatingLogFile {
+    /// Opens the log file at `path` in append mode, creating it if it doesn't exist.
+    pub fn new(path: impl Into<PathBuf>, max_size: Option<u64>) -> io::Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self { path, max_size, state: Mutex::new(LogFileState { file, size }) })
+    }
+
+    fn rotated_path(&self, n: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{n}"));
+        path.into()
+    }
+
+    fn rotate(&self, state: &mut LogFileState) -> io::Result<()> {
+        for n in (1..ROTATED_LOG_FILES).rev() {
+            let rotated = self.rotated_path(n);
+            if rotated.exists() {
+                fs::rename(rotated, self.rotated_path(n + 1))?;
+            }
+        }
+        fs::rename(&self.path, self.rotated_path(1))?;
+        state.file = File::create(&self.path)?;
+        state.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for &RotatingLogFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut state = self.state.lock();
+        if let Some(max_size) = self.max_size {
+            if state.size > 0 && state.size + buf.len() as u64 > max_size {
+                self.rotate(&mut state)?;
+            }
+        }
+        state.file.write_all(buf)?;
+        state.size += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.state.lock().file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotates_log_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("anvil.log");
+        let file = RotatingLogFile::new(&path, Some(10)).unwrap();
+        for line in ["first\n", "second\n", "third\n"] {
+            (&file).write_all(line.as_bytes()).unwrap();
+        }
+        assert_eq!(fs::read_to_string(&path).unwrap(), "third\n");
+        assert_eq!(fs::read_to_string(dir.path().join("anvil.log.1")).unwrap(), "second\n");
+        assert_eq!(fs::read_to_string(dir.path().join("anvil.log.2")).unwrap(), "first\n");
     }
 }
@@ -21,6 +21,9 @@ pub struct LogsSubscription {
     pub storage: StorageInfo,
     pub filter: FilteredParams,
     pub queued: VecDeque<Log>,
+    /// The last block whose logs were backfilled when the subscription was created, see
+    /// [`EthApi::backfill_logs`](crate::EthApi::backfill_logs)
+    pub backfilled_to: Option<u64>,
     pub id: SubscriptionId,
 }
 
@@ -36,6 +39,10 @@ fn poll(&mut self, cx: &mut Context<'_>) -> Poll<Option<EthSubscriptionResponse>
             }
 
             if let Some(block) = ready!(self.blocks.poll_next_unpin(cx)) {
+                if self.backfilled_to.is_some_and(|number| block.header.number <= number) {
+                    // the logs of this block were already backfilled
+                    continue;
+                }
                 let b = self.storage.block(block.hash);
                 let receipts = self.storage.receipts(block.hash);
                 if let (Some(receipts), Some(block)) = (receipts, b) {
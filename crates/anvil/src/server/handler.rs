@@ -1,6 +1,6 @@
 //! Contains RPC handlers
 use crate::{
-    eth::error::to_rpc_result,
+    eth::error::{to_rpc_result, ToRpcResponseResult},
     pubsub::{EthSubscription, LogsSubscription},
     EthApi,
 };
@@ -50,7 +50,7 @@ pub fn new(api: EthApi) -> Self {
 
     /// Invoked for an ethereum pubsub rpc call
     async fn on_pub_sub(&self, pubsub: EthPubSub, cx: PubSubContext<Self>) -> ResponseResult {
-        let id = SubscriptionId::random_hex();
+        let id = self.api.new_subscription_id();
         trace!(target: "rpc::ws", "received pubsub request {:?}", pubsub);
         match pubsub {
             EthPubSub::EthUnSubscribe(id) => {
@@ -73,13 +73,23 @@ async fn on_pub_sub(&self, pubsub: EthPubSub, cx: PubSubContext<Self>) -> Respon
                 let subscription = match kind {
                     SubscriptionKind::Logs => {
                         trace!(target: "rpc::ws", "received logs subscription {:?}", params);
+                        // listen for new blocks before backfilling, so that no block is missed
                         let blocks = self.api.new_block_notifications();
                         let storage = self.api.storage_info();
+                        let backfill = match &params.filter {
+                            Some(filter) => self.api.backfill_logs(filter).await,
+                            None => Ok(Default::default()),
+                        };
+                        let (logs, backfilled_to) = match backfill {
+                            Ok(backfill) => backfill,
+                            Err(err) => return Err::<(), _>(err).to_rpc_result(),
+                        };
                         EthSubscription::Logs(Box::new(LogsSubscription {
                             blocks,
                             storage,
                             filter: params,
-                            queued: Default::default(),
+                            queued: logs.into(),
+                            backfilled_to,
                             id: id.clone(),
                         }))
                     }
@@ -27,6 +27,14 @@
     }
 );
 
+sol!(
+    #[sol(rpc)]
+    interface ERC20 {
+        function balanceOf(address owner) external view returns (uint256);
+        function totalSupply() external view returns (uint256);
+    }
+);
+
 sol!(
     #[sol(rpc)]
     interface ERC721 {
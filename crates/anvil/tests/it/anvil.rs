@@ -2,9 +2,12 @@
 
 use alloy_consensus::EMPTY_ROOT_HASH;
 use alloy_eips::BlockNumberOrTag;
-use alloy_primitives::Address;
+use alloy_primitives::{Address, B256, U256};
 use alloy_provider::Provider;
-use anvil::{spawn, EthereumHardfork, NodeConfig};
+use anvil::{
+    spawn, EthereumHardfork, HardforkSchedule, NodeConfig, DETERMINISTIC_GENESIS_TIMESTAMP,
+};
+use foundry_evm::revm::primitives::SpecId;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_can_change_mining_mode() {
@@ -106,6 +109,50 @@ async fn test_shanghai_fields() {
     assert!(block.header.excess_blob_gas.is_none());
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_hardfork_schedule() {
+    let schedule = HardforkSchedule::parse("shanghai=0,cancun=2", false).unwrap();
+    let (api, _handle) = spawn(NodeConfig::test().with_hardfork_schedule(Some(schedule))).await;
+    assert_eq!(api.backend.spec_id(), SpecId::SHANGHAI);
+    assert!(api.backend.ensure_eip4844_active().is_err());
+
+    api.mine_one().await;
+    let block = api.block_by_number(BlockNumberOrTag::Latest).await.unwrap().unwrap();
+    assert_eq!(block.header.number, 1);
+    assert!(block.header.blob_gas_used.is_none());
+    // Blob transactions are accepted for the block that activates Cancun.
+    assert!(api.backend.ensure_eip4844_active().is_ok());
+
+    api.mine_one().await;
+    let block = api.block_by_number(BlockNumberOrTag::Latest).await.unwrap().unwrap();
+    assert_eq!(block.header.number, 2);
+    assert!(block.header.blob_gas_used.is_some());
+    assert_eq!(api.backend.spec_id(), SpecId::CANCUN);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_deterministic() {
+    let (api, _handle) = spawn(NodeConfig::test().with_deterministic(true)).await;
+    assert_eq!(api.instance_id(), B256::ZERO);
+
+    let block = api.block_by_number(BlockNumberOrTag::Latest).await.unwrap().unwrap();
+    assert_eq!(block.header.timestamp, DETERMINISTIC_GENESIS_TIMESTAMP);
+
+    api.mine_one().await;
+    api.mine_one().await;
+    let block = api.block_by_number(BlockNumberOrTag::Latest).await.unwrap().unwrap();
+    assert_eq!(block.header.number, 2);
+    assert_eq!(block.header.timestamp, DETERMINISTIC_GENESIS_TIMESTAMP + 2);
+
+    api.evm_increase_time(U256::from(100)).await.unwrap();
+    api.mine_one().await;
+    let block = api.block_by_number(BlockNumberOrTag::Latest).await.unwrap().unwrap();
+    assert_eq!(block.header.timestamp, DETERMINISTIC_GENESIS_TIMESTAMP + 103);
+
+    assert_eq!(api.new_block_filter().await.unwrap(), "0x1");
+    assert_eq!(api.new_block_filter().await.unwrap(), "0x2");
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_cancun_fields() {
     let (api, _handle) =
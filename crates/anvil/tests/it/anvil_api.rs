@@ -5,9 +5,11 @@
     fork::fork_config,
     utils::http_provider_with_signer,
 };
-use alloy_consensus::{SignableTransaction, TxEip1559};
-use alloy_network::{EthereumWallet, TransactionBuilder, TxSignerSync};
-use alloy_primitives::{address, fixed_bytes, utils::Unit, Address, Bytes, TxKind, U256};
+use alloy_consensus::{SignableTransaction, Transaction, TxEip1559};
+use alloy_network::{EthereumWallet, ReceiptResponse, TransactionBuilder, TxSignerSync};
+use alloy_primitives::{
+    address, fixed_bytes, utils::Unit, Address, Bytes, TxKind, B256, I256, U256,
+};
 use alloy_provider::{ext::TxPoolApi, Provider};
 use alloy_rpc_types::{
     anvil::{
@@ -16,10 +18,12 @@
     BlockId, BlockNumberOrTag, BlockTransactionsKind, TransactionRequest,
 };
 use alloy_serde::WithOtherFields;
+use alloy_signer_local::PrivateKeySigner;
 use anvil::{
     eth::{
         api::CLIENT_VERSION,
         backend::mem::{EXECUTOR, P256_DELEGATION_CONTRACT, P256_DELEGATION_RUNTIME_CODE},
+        EthApi,
     },
     spawn, EthereumHardfork, NodeConfig,
 };
@@ -28,7 +32,11 @@
         wallet::{Capabilities, DelegationCapability, WalletCapabilities},
         EthRequest,
     },
-    types::{ReorgOptions, TransactionData},
+    types::{
+        BlockAction, BridgeEvent, BridgeEventKind, ExpectedBalanceDelta, ExpectedStateDiff,
+        ExpectedStorage, ReorgOptions, RichReceiptOptions, ScheduleOptions, StateDiffMismatch,
+        TransactionData,
+    },
 };
 use foundry_evm::revm::primitives::SpecId;
 use std::{
@@ -158,6 +166,32 @@ async fn can_auto_impersonate_account() {
     assert!(api.accounts().unwrap().contains(&impersonate));
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn can_restore_runtime_profile() {
+    let (api, _handle) = spawn(NodeConfig::test()).await;
+
+    let impersonate = Address::random();
+    api.anvil_impersonate_account(impersonate).await.unwrap();
+    api.anvil_auto_impersonate_account(true).await.unwrap();
+    api.anvil_set_interval_mining(5).unwrap();
+    api.anvil_set_next_block_base_fee_per_gas(U256::from(1337)).await.unwrap();
+
+    let profile = api.runtime_profile();
+    assert_eq!(profile.impersonated_accounts, vec![impersonate]);
+    assert!(profile.auto_impersonate);
+    assert!(!profile.automine);
+    assert_eq!(profile.interval_mining, Some(5));
+    assert_eq!(profile.base_fee, 1337);
+
+    let (other, _handle) = spawn(NodeConfig::test()).await;
+    let other_impersonated = Address::random();
+    other.anvil_impersonate_account(other_impersonated).await.unwrap();
+    assert_ne!(other.runtime_profile(), profile);
+
+    other.apply_runtime_profile(profile.clone()).await.unwrap();
+    assert_eq!(other.runtime_profile(), profile);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn can_impersonate_contract() {
     let (api, handle) = spawn(NodeConfig::test()).await;
@@ -591,6 +625,47 @@ async fn test_set_chain_id() {
     assert_eq!(chain_id, 1234);
 }
 
+// test that transactions are validated against the new chain id
+#[tokio::test(flavor = "multi_thread")]
+async fn test_set_chain_id_validates_transactions() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+    let wallet = handle.dev_wallets().next().unwrap();
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let raw_tx = |chain_id: u64, nonce: u64| {
+        let mut tx = TxEip1559 {
+            chain_id,
+            nonce,
+            to: TxKind::Call(Address::random()),
+            value: U256::from(100),
+            max_priority_fee_per_gas: 1000000000000,
+            max_fee_per_gas: 10000000000000,
+            gas_limit: 21000,
+            ..Default::default()
+        };
+        let signature = wallet.sign_transaction_sync(&mut tx).unwrap();
+        let mut encoded = vec![];
+        tx.into_signed(signature).eip2718_encode(&mut encoded);
+        Bytes::from(encoded)
+    };
+
+    // the pending transaction signed for the previous chain id is removed
+    api.send_raw_transaction(raw_tx(31337, 0)).await.unwrap();
+    assert_eq!(api.txpool_status().await.unwrap().pending, 1);
+    api.anvil_set_chain_id(1234).await.unwrap();
+    assert_eq!(api.txpool_status().await.unwrap().pending, 0);
+
+    api.send_raw_transaction(raw_tx(31337, 0)).await.unwrap_err();
+    let tx_hash = api.send_raw_transaction(raw_tx(1234, 0)).await.unwrap();
+    api.mine_one().await;
+
+    let tx = provider.get_transaction_by_hash(tx_hash).await.unwrap().unwrap();
+    assert_eq!(tx.chain_id(), Some(1234));
+    let receipt = provider.get_transaction_receipt(tx_hash).await.unwrap().unwrap();
+    assert!(receipt.status());
+}
+
 // <https://github.com/foundry-rs/foundry/issues/6096>
 #[tokio::test(flavor = "multi_thread")]
 async fn test_fork_revert_next_block_timestamp() {
@@ -1061,3 +1136,324 @@ async fn test_mine_first_block_with_interval() {
     let second_block = api.block_by_number(2.into()).await.unwrap().unwrap();
     assert_eq!(second_block.header.timestamp, init_timestamp + 120);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_expect_state_diff() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+    let from = handle.dev_accounts().next().unwrap();
+    let to = Address::random();
+    let value = U256::from(1337);
+
+    api.anvil_check_state_diff().await.unwrap_err();
+
+    let slot = B256::with_last_byte(1);
+    let expected = ExpectedStateDiff {
+        storage: vec![ExpectedStorage { address: to, slot, value: B256::with_last_byte(2) }],
+        balances: vec![ExpectedBalanceDelta { address: to, delta: I256::from_raw(value) }],
+    };
+    api.anvil_expect_state_diff(expected).await.unwrap();
+    let check = api.anvil_check_state_diff().await.unwrap();
+    assert!(!check.mined && !check.passed);
+
+    let tx = TransactionRequest::default().with_from(from).with_to(to).with_value(value);
+    let tx = WithOtherFields::new(tx);
+    provider.send_transaction(tx).await.unwrap().get_receipt().await.unwrap();
+
+    let check = api.anvil_check_state_diff().await.unwrap();
+    assert!(check.mined && !check.passed);
+    assert_eq!(
+        check.mismatches,
+        vec![StateDiffMismatch::Storage {
+            address: to,
+            slot,
+            expected: B256::with_last_byte(2),
+            actual: B256::ZERO,
+        }]
+    );
+}
+
+fn signed_transfer(api: &EthApi, wallet: &PrivateKeySigner, to: Address, nonce: u64) -> Bytes {
+    let mut tx = TxEip1559 {
+        chain_id: api.chain_id(),
+        nonce,
+        to: TxKind::Call(to),
+        value: U256::from(100),
+        max_priority_fee_per_gas: 1000000000000,
+        max_fee_per_gas: 10000000000000,
+        gas_limit: 21000,
+        ..Default::default()
+    };
+    let signature = wallet.sign_transaction_sync(&mut tx).unwrap();
+    let mut encoded = vec![];
+    tx.into_signed(signature).eip2718_encode(&mut encoded);
+    Bytes::from(encoded)
+}
+
+fn at_block(number: u64) -> ScheduleOptions {
+    ScheduleOptions { at_block: Some(number), at_timestamp: None }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_schedule_tx() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+    let wallet = handle.dev_wallets().next().unwrap();
+    let to = Address::random();
+    let raw_tx = signed_transfer(&api, &wallet, to, 0);
+
+    api.anvil_schedule_tx(raw_tx.clone(), at_block(0)).await.unwrap_err();
+    api.anvil_schedule_tx(raw_tx.clone(), ScheduleOptions::default()).await.unwrap_err();
+
+    let tx_hash = api.anvil_schedule_tx(raw_tx, at_block(3)).await.unwrap();
+    api.mine_one().await;
+    api.mine_one().await;
+    assert!(provider.get_transaction_receipt(tx_hash).await.unwrap().is_none());
+
+    api.mine_one().await;
+    let receipt = provider.get_transaction_receipt(tx_hash).await.unwrap().unwrap();
+    assert!(receipt.status());
+    assert_eq!(receipt.block_number, Some(3));
+    assert_eq!(provider.get_balance(to).await.unwrap(), U256::from(100));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn scheduled_tx_follows_pool_txs_of_sender() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+    let wallet = handle.dev_wallets().next().unwrap();
+    let to = Address::random();
+
+    // retried until the transaction with nonce 0 is mined, and ordered after it
+    let raw_tx = signed_transfer(&api, &wallet, to, 1);
+    let scheduled = api.anvil_schedule_tx(raw_tx, at_block(1)).await.unwrap();
+    api.mine_one().await;
+    assert!(provider.get_transaction_receipt(scheduled).await.unwrap().is_none());
+
+    api.anvil_set_auto_mine(false).await.unwrap();
+    let pool_tx = api.send_raw_transaction(signed_transfer(&api, &wallet, to, 0)).await.unwrap();
+    api.mine_one().await;
+    let pool_receipt = provider.get_transaction_receipt(pool_tx).await.unwrap().unwrap();
+    let receipt = provider.get_transaction_receipt(scheduled).await.unwrap().unwrap();
+    assert_eq!(pool_receipt.block_number, Some(2));
+    assert_eq!(pool_receipt.transaction_index, Some(0));
+    assert_eq!(receipt.block_number, Some(2));
+    assert_eq!(receipt.transaction_index, Some(1));
+
+    // dropped once its nonce is used
+    let raw_tx = signed_transfer(&api, &wallet, Address::random(), 2);
+    let scheduled = api.anvil_schedule_tx(raw_tx, at_block(4)).await.unwrap();
+    api.send_raw_transaction(signed_transfer(&api, &wallet, to, 2)).await.unwrap();
+    api.mine_one().await;
+    api.mine_one().await;
+    api.mine_one().await;
+    assert!(provider.get_transaction_receipt(scheduled).await.unwrap().is_none());
+    assert_eq!(provider.get_balance(to).await.unwrap(), U256::from(300));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_snapshot_and_dump_schedule() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let wallet = handle.dev_wallets().next().unwrap();
+    let to = Address::random();
+
+    let state_snapshot = api.evm_snapshot().await.unwrap();
+    let scheduled =
+        api.anvil_schedule_tx(signed_transfer(&api, &wallet, to, 0), at_block(2)).await.unwrap();
+    let dump = api.anvil_dump_state(None).await.unwrap();
+
+    assert!(api.evm_revert(state_snapshot).await.unwrap());
+    api.mine_one().await;
+    api.mine_one().await;
+    assert!(handle.http_provider().get_transaction_receipt(scheduled).await.unwrap().is_none());
+
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    assert!(api.anvil_load_state(dump).await.unwrap());
+    api.mine_one().await;
+    api.mine_one().await;
+    let provider = handle.http_provider();
+    let receipt = provider.get_transaction_receipt(scheduled).await.unwrap().unwrap();
+    assert_eq!(receipt.block_number, Some(2));
+    assert_eq!(provider.get_balance(to).await.unwrap(), U256::from(100));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_execute_block_actions() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+    let from = handle.dev_accounts().next().unwrap();
+    let keeper = Address::random();
+
+    let action = BlockAction {
+        from,
+        to: keeper,
+        data: Bytes::new(),
+        value: U256::from(1),
+        gas: None,
+        every: 2,
+        start_block: None,
+    };
+    api.anvil_add_block_action(BlockAction { every: 0, ..action.clone() }).await.unwrap_err();
+    let id = api.anvil_add_block_action(action).await.unwrap();
+
+    // executed in blocks 1, 3 and 5, alongside the transactions of the pool
+    for _ in 0..4 {
+        api.mine_one().await;
+    }
+    let tx = TransactionRequest::default().with_from(from).with_to(Address::random());
+    let tx = WithOtherFields::new(tx);
+    provider.send_transaction(tx).await.unwrap().get_receipt().await.unwrap();
+    assert_eq!(provider.get_block_number().await.unwrap(), 5);
+    assert_eq!(provider.get_balance(keeper).await.unwrap(), U256::from(3));
+
+    assert!(api.anvil_remove_block_action(id).await.unwrap());
+    assert!(!api.anvil_remove_block_action(id).await.unwrap());
+    api.mine_one().await;
+    api.mine_one().await;
+    assert_eq!(provider.get_balance(keeper).await.unwrap(), U256::from(3));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_mine_batch() {
+    let (api, handle) =
+        spawn(NodeConfig::test().with_no_mining(true).with_batch_gas_target(Some(42_000))).await;
+    let provider = handle.http_provider();
+    let from = handle.dev_accounts().next().unwrap();
+
+    let send = || async {
+        let tx = TransactionRequest::default()
+            .with_from(from)
+            .with_to(Address::random())
+            .with_gas_limit(21_000);
+        api.send_transaction(WithOtherFields::new(tx)).await.unwrap();
+    };
+    let num_txs = |number: u64| {
+        let provider = provider.clone();
+        async move {
+            let block = provider
+                .get_block_by_number(number.into(), BlockTransactionsKind::Hashes)
+                .await
+                .unwrap()
+                .unwrap();
+            block.transactions.len()
+        }
+    };
+
+    for _ in 0..3 {
+        send().await;
+    }
+    assert_eq!(provider.get_block_number().await.unwrap(), 0);
+
+    // the pool is split into blocks of up to 42k gas
+    api.anvil_mine_batch(U256::from(3), None).await.unwrap();
+    assert_eq!(provider.get_block_number().await.unwrap(), 3);
+    assert_eq!(num_txs(1).await, 2);
+    assert_eq!(num_txs(2).await, 1);
+    assert_eq!(num_txs(3).await, 0);
+
+    // transactions that don't fit into the batch stay in the pool
+    send().await;
+    send().await;
+    api.anvil_mine_batch(U256::from(1), Some(U256::from(21_000))).await.unwrap();
+    assert_eq!(num_txs(4).await, 1);
+    assert_eq!(api.txpool_status().await.unwrap().pending, 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_simulate_bridge_events() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+    let l2_bridge = address!("4200000000000000000000000000000000000010");
+    let from = handle.dev_accounts().next().unwrap();
+    let to = Address::random();
+    let amount = Unit::ETHER.wei();
+
+    let event = BridgeEvent {
+        template: None,
+        kind: BridgeEventKind::DepositFinalized,
+        from,
+        to,
+        amount,
+        l1_token: None,
+        l2_token: None,
+        extra_data: Bytes::new(),
+    };
+    let hash = api.anvil_simulate_bridge_event(event.clone()).await.unwrap();
+    let receipt = provider.get_transaction_receipt(hash).await.unwrap().unwrap();
+    assert!(receipt.status());
+    let log = &receipt.inner.inner.logs()[0];
+    assert_eq!(log.address(), l2_bridge);
+    assert_eq!(
+        log.topics()[1..],
+        [
+            Address::ZERO.into_word(),
+            address!("DeadDeAddeAddEAddeadDEaDDEAdDeaDDeAD0000").into_word(),
+            from.into_word(),
+        ]
+    );
+    assert_eq!(provider.get_balance(to).await.unwrap(), amount);
+    // the bridge keeps its code
+    assert!(provider.get_code_at(l2_bridge).await.unwrap().is_empty());
+
+    // withdrawals of ETH are sent to the bridge
+    let withdrawal =
+        BridgeEvent { kind: BridgeEventKind::WithdrawalInitiated, from: to, ..event.clone() };
+    let hash = api.anvil_simulate_bridge_event(withdrawal.clone()).await;
+    assert!(hash.is_err(), "the sender can't pay for the gas");
+    api.anvil_set_balance(to, amount * U256::from(2)).await.unwrap();
+    api.anvil_simulate_bridge_event(withdrawal).await.unwrap();
+    assert_eq!(provider.get_balance(l2_bridge).await.unwrap(), amount);
+
+    let unknown = BridgeEvent { template: Some("unknown".to_string()), ..event };
+    assert!(api.anvil_simulate_bridge_event(unknown).await.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_get_rich_receipt() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let wallet = handle.dev_wallets().next().unwrap();
+    let from = wallet.address();
+    let provider = http_provider_with_signer(&handle.http_endpoint(), wallet.into());
+
+    let receipt = abi::SimpleStorage::deploy_builder(&provider, "initial".to_string())
+        .send()
+        .await
+        .unwrap()
+        .get_receipt()
+        .await
+        .unwrap();
+    let address = receipt.contract_address.unwrap();
+    let rich = api.anvil_get_rich_receipt(receipt.transaction_hash, None).await.unwrap().unwrap();
+    assert_eq!(rich.receipt.transaction_hash, receipt.transaction_hash);
+    assert_eq!(rich.created_contracts, vec![address]);
+    assert!(rich.destroyed_contracts.is_empty());
+    assert_eq!(rich.gas_used_by_depth.len(), 1);
+    // logs are only decoded with the given events
+    assert!(rich.decoded_logs.iter().all(Option::is_none));
+
+    let storage = abi::SimpleStorage::new(address, &provider);
+    let receipt =
+        storage.setValue("new".to_string()).send().await.unwrap().get_receipt().await.unwrap();
+    let options = RichReceiptOptions {
+        events: vec![
+            "ValueChanged(address indexed author, address indexed, string, string newValue)"
+                .to_string(),
+        ],
+    };
+    let rich =
+        api.anvil_get_rich_receipt(receipt.transaction_hash, Some(options)).await.unwrap().unwrap();
+    assert!(rich.created_contracts.is_empty());
+    assert!(rich.access_list.0.iter().any(|item| item.address == from));
+    let touched = rich.access_list.0.iter().find(|item| item.address == address).unwrap();
+    assert!(!touched.storage_keys.is_empty());
+
+    let log = rich.decoded_logs[0].as_ref().unwrap();
+    assert_eq!(log.event, "ValueChanged(address,address,string,string)");
+    assert_eq!(log.params[0].name, "author");
+    assert!(log.params[0].indexed);
+    assert_eq!(log.params[0].value, from.to_string());
+    assert_eq!(log.params[3].value, "new");
+
+    let invalid = RichReceiptOptions { events: vec!["ValueChanged(".to_string()] };
+    assert!(api.anvil_get_rich_receipt(receipt.transaction_hash, Some(invalid)).await.is_err());
+}
@@ -1,7 +1,7 @@
 //! various fork related test
 
 use crate::{
-    abi::{Greeter, ERC721},
+    abi::{Greeter, ERC20, ERC721},
     utils::{http_provider, http_provider_with_signer},
 };
 use alloy_chains::NamedChain;
@@ -16,6 +16,7 @@
 use alloy_serde::WithOtherFields;
 use alloy_signer_local::PrivateKeySigner;
 use anvil::{eth::EthApi, spawn, NodeConfig, NodeHandle};
+use anvil_core::types::TokenRef;
 use foundry_common::provider::get_http_provider;
 use foundry_config::Config;
 use foundry_test_utils::rpc::{self, next_http_rpc_endpoint, next_rpc_endpoint};
@@ -730,6 +731,42 @@ async fn test_fork_nft_set_approve_all() {
     assert_eq!(real_owner._0, wallet.address());
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fork_deal_erc20_and_erc721() {
+    let dai = address!("6B175474E89094C44Da98b954EedeAC495271d0F");
+    let (api, handle) = spawn(
+        fork_config()
+            .with_fork_block_number(Some(14812197u64))
+            .with_token_symbols(vec![("DAI".to_string(), dai)]),
+    )
+    .await;
+    let provider = handle.http_provider();
+    let account = PrivateKeySigner::random().address();
+
+    let token = ERC20::new(dai, provider.clone());
+    let supply = token.totalSupply().call().await.unwrap()._0;
+    let amount = U256::from(1_000_000e18 as u128);
+    api.anvil_deal_erc20(TokenRef::Symbol("dai".to_string()), account, amount).await.unwrap();
+    assert_eq!(token.balanceOf(account).call().await.unwrap()._0, amount);
+    assert_eq!(token.totalSupply().call().await.unwrap()._0, supply);
+
+    let nouns_addr = address!("9c8ff314c9bc7f6e59a9d9225fb22946427edc03");
+    let owner = address!("052564eb0fd8b340803df55def89c25c432f43f4");
+    let token_id = U256::from(154u64);
+    let nouns = ERC721::new(nouns_addr, provider.clone());
+    let owner_balance = nouns.balanceOf(owner).call().await.unwrap()._0;
+    api.anvil_deal_erc721(TokenRef::Address(nouns_addr), account, token_id).await.unwrap();
+    assert_eq!(nouns.ownerOf(token_id).call().await.unwrap()._0, account);
+    assert_eq!(nouns.balanceOf(account).call().await.unwrap()._0, U256::from(1));
+    assert_eq!(nouns.balanceOf(owner).call().await.unwrap()._0, owner_balance - U256::from(1));
+
+    let err = api
+        .anvil_deal_erc20(TokenRef::Symbol("USDC".to_string()), account, amount)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("unknown token"), "{err}");
+}
+
 // <https://github.com/foundry-rs/foundry/issues/2261>
 #[tokio::test(flavor = "multi_thread")]
 async fn test_fork_with_custom_chain_id() {
@@ -2,11 +2,12 @@
 
 use crate::utils::http_provider_with_signer;
 use alloy_network::{EthereumWallet, TransactionBuilder};
-use alloy_primitives::{uint, Address, U256, U64};
+use alloy_primitives::{bytes, uint, Address, U256, U64};
 use alloy_provider::Provider;
 use alloy_rpc_types::{BlockId, TransactionRequest};
 use alloy_serde::WithOtherFields;
 use anvil::{eth::fees::INITIAL_BASE_FEE, spawn, NodeConfig};
+use anvil_core::types::ProtocolLimits;
 
 const GAS_TRANSFER: u128 = 21_000;
 
@@ -216,3 +217,66 @@ async fn test_can_use_fee_history() {
         assert_eq!(latest_fee_history_fee, next_base_fee as u64);
     }
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_set_protocol_limits() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+    let sender = handle.dev_accounts().next().unwrap();
+
+    let tx = TransactionRequest::default()
+        .from(sender)
+        .with_to(Address::random())
+        .with_input(bytes!("01010101010101010101"));
+    let tx = WithOtherFields::new(tx);
+
+    let receipt = provider.send_transaction(tx.clone()).await.unwrap().get_receipt().await.unwrap();
+    assert_eq!(receipt.gas_used, 21_000 + 10 * 16);
+
+    api.anvil_set_limits(ProtocolLimits {
+        initcode_size_limit: Some(4),
+        calldata_nonzero_byte_gas: Some(0),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let receipt = provider.send_transaction(tx).await.unwrap().get_receipt().await.unwrap();
+    assert_eq!(receipt.gas_used, 21_000);
+
+    let deploy = TransactionRequest::default().from(sender).with_deploy_code(bytes!("5f5f5f5ffd"));
+    let err = provider.call(&WithOtherFields::new(deploy)).await.unwrap_err();
+    assert!(err.to_string().contains("max initcode size exceeded"), "{err}");
+
+    // The initcode size limit can't exceed twice the code size limit.
+    let err = api
+        .anvil_set_limits(ProtocolLimits {
+            code_size_limit: Some(100),
+            initcode_size_limit: Some(201),
+            ..Default::default()
+        })
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("exceeds twice the code size limit"), "{err}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_set_protocol_limits_keeps_code_size_limit_disabled() {
+    let (api, handle) = spawn(NodeConfig::test().disable_code_size_limit(true)).await;
+    let provider = handle.http_provider();
+    let sender = handle.dev_accounts().next().unwrap();
+
+    api.anvil_set_limits(ProtocolLimits {
+        calldata_nonzero_byte_gas: Some(0),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    assert_eq!(api.backend.protocol_limits().code_size_limit, Some(usize::MAX));
+
+    // PUSH3 0x006001 PUSH0 RETURN: deploys 24577 bytes of code, one more than the default limit.
+    let deploy =
+        TransactionRequest::default().from(sender).with_deploy_code(bytes!("620060015ff3"));
+    let code = provider.call(&WithOtherFields::new(deploy)).await.unwrap();
+    assert_eq!(code.len(), 0x6001);
+}
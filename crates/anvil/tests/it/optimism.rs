@@ -1,14 +1,19 @@
 //! Tests for OP chain support.
 
-use crate::utils::{http_provider, http_provider_with_signer};
+use crate::{
+    fork::fork_config,
+    utils::{http_provider, http_provider_with_signer},
+};
+use alloy_chains::NamedChain;
 use alloy_eips::eip2718::Encodable2718;
-use alloy_network::{EthereumWallet, TransactionBuilder};
-use alloy_primitives::{b256, Address, TxHash, TxKind, U256};
+use alloy_network::{EthereumWallet, ReceiptResponse, TransactionBuilder};
+use alloy_primitives::{address, b256, bytes, Address, TxHash, TxKind, U256};
 use alloy_provider::Provider;
 use alloy_rpc_types::TransactionRequest;
 use alloy_serde::WithOtherFields;
 use anvil::{spawn, EthereumHardfork, NodeConfig};
 use anvil_core::eth::transaction::optimism::DepositTransaction;
+use foundry_test_utils::rpc::next_rpc_endpoint;
 use op_alloy_rpc_types::OpTransactionFields;
 
 #[tokio::test(flavor = "multi_thread")]
@@ -233,3 +238,61 @@ async fn test_deposit_tx_checks_sufficient_funds_after_applying_deposited_value(
     // recipient should've received the entire deposited value
     assert_eq!(recipient_new_balance, U256::from(send_value));
 }
+
+// forking an OP-stack chain enables deposit transactions without `--optimism`
+#[tokio::test(flavor = "multi_thread")]
+async fn test_deposit_transaction_in_op_stack_fork() {
+    let (api, handle) = spawn(
+        fork_config()
+            .with_fork_block_number(None::<u64>)
+            .with_eth_rpc_url(Some(next_rpc_endpoint(NamedChain::Base))),
+    )
+    .await;
+    assert!(api.backend.is_optimism());
+
+    let provider = http_provider(&handle.http_endpoint());
+
+    let sender = Address::random();
+    let recipient = Address::random();
+    let send_value = U256::from(1_000_000_000_u128);
+
+    let deposit_tx = DepositTransaction {
+        source_hash: b256!("0000000000000000000000000000000000000000000000000000000000000001"),
+        from: sender,
+        nonce: 0,
+        kind: TxKind::Call(recipient),
+        mint: send_value,
+        value: send_value,
+        gas_limit: 21_000,
+        is_system_tx: false,
+        input: Vec::new().into(),
+    };
+
+    let mut tx_buffer = Vec::new();
+    deposit_tx.encode_2718(&mut tx_buffer);
+
+    let receipt =
+        provider.send_raw_transaction(&tx_buffer).await.unwrap().get_receipt().await.unwrap();
+    assert!(receipt.status());
+    assert!(receipt.other.get("depositNonce").is_some());
+
+    let recipient_balance = provider.get_balance(recipient).await.unwrap();
+    assert_eq!(recipient_balance, send_value);
+}
+
+// forking a chain that isn't known detects op-stack chains by their `L1Block` predeploy
+#[tokio::test(flavor = "multi_thread")]
+async fn test_op_stack_fork_detected_by_predeploy() {
+    let (origin_api, origin_handle) = spawn(NodeConfig::test().with_chain_id(Some(4242u64))).await;
+    let (api, _handle) =
+        spawn(NodeConfig::test().with_eth_rpc_url(Some(origin_handle.http_endpoint()))).await;
+    assert!(!api.backend.is_optimism());
+
+    origin_api
+        .anvil_set_code(address!("4200000000000000000000000000000000000015"), bytes!("00"))
+        .await
+        .unwrap();
+    let (api, _handle) =
+        spawn(NodeConfig::test().with_eth_rpc_url(Some(origin_handle.http_endpoint()))).await;
+    assert!(api.backend.is_optimism());
+}
@@ -113,6 +113,49 @@ async fn test_sub_logs() {
     assert_eq!(receipt.inner.logs()[0], log);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sub_logs_backfill() {
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let wallet = handle.dev_wallets().next().unwrap();
+    let provider = connect_pubsub(&handle.ws_endpoint()).await;
+
+    let contract_addr = EmitLogs::deploy_builder(provider.clone(), "First Message".to_string())
+        .from(wallet.address())
+        .deploy()
+        .await
+        .unwrap();
+    let contract = EmitLogs::new(contract_addr, provider.clone());
+
+    // emit an event before subscribing
+    let first = contract
+        .setValue("Second Message".to_string())
+        .send()
+        .await
+        .unwrap()
+        .get_receipt()
+        .await
+        .unwrap();
+
+    // subscribe to events from the block of the first event
+    let filter = Filter::new()
+        .address(contract.address().to_owned())
+        .from_block(first.block_number.unwrap());
+    let mut logs_sub = provider.subscribe_logs(&filter).await.unwrap().into_stream();
+
+    let second = contract
+        .setValue("Third Message".to_string())
+        .send()
+        .await
+        .unwrap()
+        .get_receipt()
+        .await
+        .unwrap();
+
+    // the past event is backfilled, followed by the new one
+    assert_eq!(logs_sub.next().await.unwrap(), first.inner.logs()[0]);
+    assert_eq!(logs_sub.next().await.unwrap(), second.inner.logs()[0]);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_sub_logs_impersonated() {
     let (api, handle) = spawn(NodeConfig::test()).await;
@@ -6,6 +6,7 @@
 use alloy_serde::WithOtherFields;
 use alloy_sol_types::sol;
 use anvil::{spawn, NodeConfig};
+use anvil_core::types::RevertInfo;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_deploy_reverting() {
@@ -124,3 +125,52 @@ async fn test_solc_revert_custom_errors() {
     let s = err.to_string();
     assert!(s.contains("execution reverted"), "{s:?}");
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rich_revert_errors() {
+    sol!(
+        #[sol(rpc, bytecode = "608080604052346025575f80546001600160a01b031916600117905560b69081602a8239f35b5f80fdfe60808060405260043610156011575f80fd5b5f3560e01c635b9fdc30146023575f80fd5b34607c575f366003190112607c575f546001600160a01b03163303604c576020604051607b8152f35b62461bcd60e51b815260206004820152600b60248201526a08585d5d1a1bdc9a5e995960aa1b6044820152606490fd5b5f80fdfea2646970667358221220f593e5ccd46935f623185de62a72d9f1492d8d15075a111b0fa4d7e16acf4a7064736f6c63430008190033")]
+        contract Contract {
+            address private owner;
+
+            constructor() {
+                owner = address(1);
+            }
+
+            modifier onlyOwner() {
+                require(msg.sender == owner, "!authorized");
+                _;
+            }
+
+            #[derive(Debug)]
+            function getSecret() public onlyOwner view returns(uint256 secret) {
+                return 123;
+            }
+        }
+    );
+
+    let (_api, handle) = spawn(NodeConfig::test().with_rich_errors(true)).await;
+    let provider = handle.http_provider();
+
+    let contract = Contract::deploy(&provider).await.unwrap();
+
+    // Sending the transaction fails with the revert details once it's mined.
+    let err = contract.getSecret().gas(100_000).send().await.unwrap_err();
+    let alloy_contract::Error::TransportError(err) = err else { panic!("{err:?}") };
+    let payload = err.as_error_resp().unwrap();
+    assert!(payload.message.contains("!authorized"), "{payload:?}");
+    let info: RevertInfo = payload.try_data_as().unwrap().unwrap();
+    assert_eq!(info.reason.as_deref(), Some("revert: !authorized"));
+    assert_eq!(info.trace.len(), 1);
+    assert!(!info.trace[0].success);
+    assert!(info.gas_used > 0);
+
+    // The receipt includes the revert details.
+    let receipt: serde_json::Value = provider
+        .raw_request("eth_getTransactionReceipt".into(), (info.transaction_hash,))
+        .await
+        .unwrap();
+    assert_eq!(receipt["status"], "0x0");
+    assert_eq!(receipt["revertReason"], "revert: !authorized");
+    assert_eq!(receipt["revertTrace"].as_array().unwrap().len(), 1);
+}
@@ -42,6 +42,54 @@ async fn can_load_state() {
     assert_eq!(num, U256::from(num_from_tag));
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn can_import_binary_state() {
+    let (api, _handle) = spawn(NodeConfig::test()).await;
+
+    let alice = address!("0x0000000000000000000000000000000000000123");
+    api.anvil_set_balance(alice, U256::from(1337)).await.unwrap();
+    api.anvil_set_code(alice, Bytes::from_static(&[0x60, 0x00])).await.unwrap();
+
+    let dump = api.anvil_export_state_binary().await.unwrap();
+    let state = anvil::eth::backend::db::SerializableState::decode_binary(&dump).unwrap();
+
+    let (api, handle) = spawn(NodeConfig::test().with_init_state(Some(state))).await;
+    let provider = handle.http_provider();
+
+    assert_eq!(provider.get_balance(alice).await.unwrap(), U256::from(1337));
+    assert_eq!(provider.get_code_at(alice).await.unwrap(), Bytes::from_static(&[0x60, 0x00]));
+    assert_eq!(api.block_number().unwrap(), U256::ZERO);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_load_binary_state_with_blocks() {
+    let (api, _handle) = spawn(NodeConfig::test()).await;
+
+    let alice = address!("0x0000000000000000000000000000000000000123");
+    let bob = address!("0x0000000000000000000000000000000000000456");
+    api.anvil_set_balance(alice, U256::from(1337)).await.unwrap();
+    api.anvil_set_balance(bob, U256::from(42)).await.unwrap();
+    api.mine_one().await;
+    api.mine_one().await;
+
+    let dump = api.anvil_export_state_binary().await.unwrap();
+
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    assert!(api.anvil_load_state(dump.clone()).await.unwrap());
+    let provider = handle.http_provider();
+    assert_eq!(api.block_number().unwrap(), U256::from(2));
+    assert_eq!(provider.get_balance(alice).await.unwrap(), U256::from(1337));
+
+    // partial load
+    let mut state = anvil::eth::backend::db::SerializableState::decode_binary(&dump).unwrap();
+    state.retain_accounts(&[bob]);
+    let (api, handle) = spawn(NodeConfig::test().with_init_state(Some(state))).await;
+    let provider = handle.http_provider();
+    assert_eq!(api.block_number().unwrap(), U256::ZERO);
+    assert_eq!(provider.get_balance(alice).await.unwrap(), U256::ZERO);
+    assert_eq!(provider.get_balance(bob).await.unwrap(), U256::from(42));
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn can_load_existing_state_legacy() {
     let state_file = "test-data/state-dump-legacy.json";
@@ -7,7 +7,7 @@
 use alloy_network::{EthereumWallet, TransactionBuilder};
 use alloy_primitives::{
     hex::{self, FromHex},
-    Address, Bytes, U256,
+    Address, Bytes, B256, U256,
 };
 use alloy_provider::{
     ext::{DebugApi, TraceApi},
@@ -28,6 +28,7 @@
 use alloy_serde::WithOtherFields;
 use alloy_sol_types::sol;
 use anvil::{spawn, EthereumHardfork, NodeConfig};
+use foundry_evm::traces::CallTraceArena;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_get_transfer_parity_traces() {
@@ -908,3 +909,66 @@ async fn test_trace_filter() {
     let traces = api.trace_filter(tracer).await.unwrap();
     assert_eq!(traces.len(), 5);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_foundry_debug_tracer() {
+    let (_api, handle) = spawn(NodeConfig::test().with_steps_tracing(true)).await;
+    let wallets = handle.dev_wallets().collect::<Vec<_>>();
+    let deployer: EthereumWallet = wallets[0].clone().into();
+    let provider = http_provider_with_signer(&handle.http_endpoint(), deployer);
+
+    let simple_storage_contract =
+        SimpleStorage::deploy(&provider, "init value".to_string()).await.unwrap();
+    let receipt = simple_storage_contract
+        .setValue("bar".to_string())
+        .send()
+        .await
+        .unwrap()
+        .get_receipt()
+        .await
+        .unwrap();
+
+    let opts = serde_json::json!({ "foundryDebugger": true });
+    let arena: Option<CallTraceArena> = provider
+        .raw_request("debug_traceTransaction".into(), (receipt.transaction_hash, opts))
+        .await
+        .unwrap();
+    let arena = arena.unwrap();
+    let root = &arena.nodes()[0].trace;
+    assert_eq!(root.address, *simple_storage_contract.address());
+    assert!(!root.steps.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_foundry_debug_tracer_requires_steps() {
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let wallets = handle.dev_wallets().collect::<Vec<_>>();
+    let deployer: EthereumWallet = wallets[0].clone().into();
+    let provider = http_provider_with_signer(&handle.http_endpoint(), deployer);
+
+    let simple_storage_contract =
+        SimpleStorage::deploy(&provider, "init value".to_string()).await.unwrap();
+    let receipt = simple_storage_contract
+        .setValue("bar".to_string())
+        .send()
+        .await
+        .unwrap()
+        .get_receipt()
+        .await
+        .unwrap();
+
+    let opts = serde_json::json!({ "foundryDebugger": true });
+    let err = provider
+        .raw_request::<_, Option<CallTraceArena>>(
+            "debug_traceTransaction".into(),
+            (receipt.transaction_hash, opts),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("steps tracing must be enabled"), "{err}");
+
+    let opts = serde_json::json!({ "foundryDebugger": true });
+    let arena: Option<CallTraceArena> =
+        provider.raw_request("debug_traceTransaction".into(), (B256::ZERO, opts)).await.unwrap();
+    assert!(arena.is_none());
+}
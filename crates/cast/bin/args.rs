@@ -2,8 +2,8 @@
     access_list::AccessListArgs, artifact::ArtifactArgs, bind::BindArgs, call::CallArgs,
     constructor_args::ConstructorArgsArgs, create2::Create2Args, creation_code::CreationCodeArgs,
     estimate::EstimateArgs, find_block::FindBlockArgs, interface::InterfaceArgs, logs::LogsArgs,
-    mktx::MakeTxArgs, rpc::RpcArgs, run::RunArgs, send::SendTxArgs, storage::StorageArgs,
-    wallet::WalletSubcommands,
+    mktx::MakeTxArgs, receipt::ReceiptArgs, rpc::RpcArgs, run::RunArgs, send::SendTxArgs,
+    storage::StorageArgs, tx::TxSubcommands, wallet::WalletSubcommands,
 };
 use alloy_primitives::{Address, B256, U256};
 use alloy_rpc_types::BlockId;
@@ -442,11 +442,19 @@ pub enum CastSubcommand {
     #[command(visible_aliases = &["na", "nh"])]
     Namehash { name: Option<String> },
 
-    /// Get information about a transaction.
-    #[command(visible_alias = "t")]
+    /// Get information about a transaction, or build and sign one offline.
+    #[command(
+        visible_alias = "t",
+        args_conflicts_with_subcommands = true,
+        subcommand_negates_reqs = true
+    )]
     Tx {
+        #[command(subcommand)]
+        command: Option<TxSubcommands>,
+
         /// The transaction hash.
-        tx_hash: String,
+        #[arg(required = true)]
+        tx_hash: Option<String>,
 
         /// If specified, only get the given field of the transaction. If "raw", the RLP encoded
         /// transaction will be printed.
@@ -456,31 +464,29 @@ pub enum CastSubcommand {
         #[arg(long, conflicts_with = "field")]
         raw: bool,
 
-        #[command(flatten)]
-        rpc: RpcOpts,
-    },
-
-    /// Get the transaction receipt for a transaction.
-    #[command(visible_alias = "re")]
-    Receipt {
-        /// The transaction hash.
-        tx_hash: String,
-
-        /// If specified, only get the given field of the transaction.
-        field: Option<String>,
+        /// Watch the transaction until it has the required number of confirmations, reporting
+        /// any reorgs it survives and whether it was dropped or replaced.
+        ///
+        /// Exits with an error if the transaction is not confirmed.
+        #[arg(long, conflicts_with_all = ["field", "raw"])]
+        wait: bool,
 
-        /// The number of confirmations until the receipt is fetched
-        #[arg(long, default_value = "1")]
+        /// The number of confirmations to wait for.
+        #[arg(long, default_value = "1", requires = "wait")]
         confirmations: u64,
 
-        /// Exit immediately if the transaction was not found.
-        #[arg(id = "async", long = "async", env = "CAST_ASYNC", alias = "cast-async")]
-        cast_async: bool,
+        /// Timeout for waiting for the transaction, in seconds.
+        #[arg(long, env = "ETH_TIMEOUT", requires = "wait")]
+        timeout: Option<u64>,
 
         #[command(flatten)]
         rpc: RpcOpts,
     },
 
+    /// Get the transaction receipt for a transaction.
+    #[command(visible_alias = "re")]
+    Receipt(ReceiptArgs),
+
     /// Sign and publish a transaction.
     #[command(name = "send", visible_alias = "s")]
     SendTx(SendTxArgs),
@@ -809,6 +815,19 @@ pub enum CastSubcommand {
         message: Option<String>,
     },
 
+    /// Hash typed data according to EIP-712.
+    ///
+    /// Prints the domain separator, the struct hash of the message and the digest that is signed.
+    #[command(visible_alias = "htd")]
+    HashTypedData {
+        /// The typed data as a JSON string, or a file name with `--from-file`.
+        data: Option<String>,
+
+        /// Treat the data as a file containing JSON typed data.
+        #[arg(long)]
+        from_file: bool,
+    },
+
     /// Perform an ENS lookup.
     #[command(visible_alias = "rn")]
     ResolveName {
@@ -935,6 +954,14 @@ pub enum CastSubcommand {
         #[arg(short, value_hint = ValueHint::DirPath, alias = "path")]
         directory: Option<PathBuf>,
 
+        /// Compile the source code with the settings it was verified with.
+        ///
+        /// The sources and artifacts are kept in `~/.foundry/cache/etherscan`, where they are
+        /// reused by commands that need the contract's artifacts, such as `cast run --debug` and
+        /// `cast storage`.
+        #[arg(long, conflicts_with_all = ["flatten", "directory"])]
+        recompile: bool,
+
         #[command(flatten)]
         etherscan: EtherscanOpts,
 
@@ -1132,6 +1159,37 @@ fn parse_call_data() {
         };
     }
 
+    #[test]
+    fn parse_tx_subcommands() {
+        let tx_hash = "0x5bcd22734cca2385dc25b2d38a3d33a640c5961bd46d390dff184c894204b594";
+        let args: Cast = Cast::parse_from(["foundry-cli", "tx", tx_hash]);
+        match args.cmd {
+            CastSubcommand::Tx { command, tx_hash: hash, .. } => {
+                assert!(command.is_none());
+                assert_eq!(hash.as_deref(), Some(tx_hash));
+            }
+            _ => unreachable!(),
+        };
+
+        let args: Cast = Cast::parse_from([
+            "foundry-cli",
+            "tx",
+            "build",
+            "0x5c9d55b78febcc2061715ba4f57ecf8ea2711f2c",
+            "--chain",
+            "1",
+        ]);
+        match args.cmd {
+            CastSubcommand::Tx { command, tx_hash, .. } => {
+                assert!(matches!(command, Some(TxSubcommands::Build(_))));
+                assert!(tx_hash.is_none());
+            }
+            _ => unreachable!(),
+        };
+
+        assert!(Cast::try_parse_from(["foundry-cli", "tx"]).is_err());
+    }
+
     // <https://github.com/foundry-rs/book/issues/1019>
     #[test]
     fn parse_signature() {
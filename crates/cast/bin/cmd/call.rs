@@ -1,14 +1,18 @@
 use crate::tx::{CastTxBuilder, SenderKind};
 use alloy_primitives::{TxKind, U256};
 use alloy_rpc_types::{BlockId, BlockNumberOrTag};
-use cast::{traces::TraceKind, Cast};
-use clap::Parser;
+use cast::{
+    batch::{BatchCall, BatchCaller},
+    traces::TraceKind,
+    Cast,
+};
+use clap::{Parser, ValueHint};
 use eyre::Result;
 use foundry_cli::{
     opts::{EthereumOpts, TransactionOpts},
     utils::{self, handle_traces, parse_ether_value, TraceResult},
 };
-use foundry_common::{ens::NameOrAddress, shell};
+use foundry_common::{ens::NameOrAddress, fs, shell};
 use foundry_compilers::artifacts::EvmVersion;
 use foundry_config::{
     figment::{
@@ -23,7 +27,7 @@
     opts::EvmOpts,
     traces::{InternalTraceMode, TraceMode},
 };
-use std::str::FromStr;
+use std::{path::PathBuf, str::FromStr};
 
 /// CLI arguments for `cast call`.
 #[derive(Debug, Parser)]
@@ -45,12 +49,31 @@ pub struct CallArgs {
     )]
     data: Option<String>,
 
+    /// Executes the calls of a JSON file and prints their results as JSON.
+    ///
+    /// The file contains an array of calls, each with a `to` address and either a `sig` with
+    /// `args`, or raw `data`. Return types in the signature, e.g. `balanceOf(address)(uint256)`,
+    /// are used to decode the output.
+    ///
+    /// The calls are aggregated through Multicall3 if it is deployed, and sent as concurrent
+    /// `eth_call`s otherwise.
+    #[arg(
+        long,
+        value_hint = ValueHint::FilePath,
+        value_name = "PATH",
+        conflicts_with_all = &["to", "sig", "args", "data", "trace"]
+    )]
+    batch: Option<PathBuf>,
+
     /// Forks the remote rpc, executes the transaction locally and prints a trace
     #[arg(long, default_value_t = false)]
     trace: bool,
 
     /// Opens an interactive debugger.
     /// Can only be used with `--trace`.
+    ///
+    /// Sources of the called contracts are fetched from Etherscan, or from Sourcify if they are
+    /// not verified on Etherscan, and compiled locally.
     #[arg(long, requires = "trace")]
     debug: bool,
 
@@ -135,10 +158,22 @@ pub async fn run(self) -> Result<()> {
             decode_internal,
             labels,
             data,
+            batch,
             with_local_artifacts,
             ..
         } = self;
 
+        if let Some(batch) = batch {
+            if command.is_some() {
+                eyre::bail!("`--batch` cannot be used with `--create`");
+            }
+            let calls: Vec<BatchCall> = fs::read_json_file(&batch)?;
+            let provider = utils::get_provider(&config)?;
+            let results = BatchCaller::new(provider, block).call(&calls).await?;
+            sh_println!("{}", serde_json::to_string_pretty(&results)?)?;
+            return Ok(());
+        }
+
         if let Some(data) = data {
             sig = Some(data);
         }
@@ -283,4 +318,18 @@ fn call_sig_and_data_exclusive() {
 
         assert!(args.is_err());
     }
+
+    #[test]
+    fn call_batch_exclusive() {
+        let args = CallArgs::parse_from(["foundry-cli", "--batch", "calls.json"]);
+        assert_eq!(args.batch, Some(PathBuf::from("calls.json")));
+
+        let args = CallArgs::try_parse_from([
+            "foundry-cli",
+            Address::ZERO.to_string().as_str(),
+            "--batch",
+            "calls.json",
+        ]);
+        assert!(args.is_err());
+    }
 }
@@ -17,8 +17,10 @@
 pub mod interface;
 pub mod logs;
 pub mod mktx;
+pub mod receipt;
 pub mod rpc;
 pub mod run;
 pub mod send;
 pub mod storage;
+pub mod tx;
 pub mod wallet;
@@ -0,0 +1,327 @@
+use super::interface::fetch_abi_from_etherscan;
+use alloy_dyn_abi::EventExt;
+use alloy_json_abi::{Event, JsonAbi};
+use alloy_network::AnyTransactionReceipt;
+use alloy_primitives::{Address, Selector, B256};
+use alloy_provider::Provider;
+use alloy_rpc_types::Log;
+use alloy_sol_types::{Panic, Revert, SolError};
+use cast::{
+    decode::RevertDecoder,
+    traces::identifier::{SignaturesIdentifier, SingleSignaturesIdentifier},
+    Cast,
+};
+use clap::Parser;
+use eyre::Result;
+use foundry_cli::{
+    opts::{EtherscanOpts, RpcOpts},
+    utils::{self, LoadConfig},
+};
+use foundry_common::{
+    abi::get_indexed_event,
+    fmt::{format_token_raw, UIfmt},
+    get_pretty_tx_receipt_attr,
+    provider::RetryProvider,
+    shell, TransactionReceiptWithRevertReason, SELECTOR_LEN,
+};
+use foundry_config::Config;
+use serde::Serialize;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+/// CLI arguments for `cast receipt`.
+#[derive(Debug, Parser)]
+pub struct ReceiptArgs {
+    /// The transaction hash.
+    tx_hash: String,
+
+    /// If specified, only get the given field of the transaction.
+    #[arg(conflicts_with = "decode_logs")]
+    field: Option<String>,
+
+    /// The number of confirmations until the receipt is fetched
+    #[arg(long, default_value = "1")]
+    confirmations: u64,
+
+    /// Exit immediately if the transaction was not found.
+    #[arg(id = "async", long = "async", env = "CAST_ASYNC", alias = "cast-async")]
+    cast_async: bool,
+
+    /// Decode the logs of the transaction, and the revert data if it failed.
+    ///
+    /// The ABIs are looked up in the artifacts of the current project, in `--abi-path`, on
+    /// Etherscan if an API key is set, and the event signatures in the signature database.
+    #[arg(long)]
+    decode_logs: bool,
+
+    /// A directory of JSON ABIs or artifacts used to decode the logs.
+    #[arg(long, value_name = "DIR", requires = "decode_logs")]
+    abi_path: Option<PathBuf>,
+
+    /// Exit with a non-zero code if the transaction failed.
+    #[arg(long)]
+    check: bool,
+
+    #[command(flatten)]
+    etherscan: EtherscanOpts,
+
+    #[command(flatten)]
+    rpc: RpcOpts,
+}
+
+impl ReceiptArgs {
+    pub async fn run(self) -> Result<()> {
+        let Self {
+            tx_hash,
+            field,
+            confirmations,
+            cast_async,
+            decode_logs,
+            abi_path,
+            check,
+            etherscan,
+            rpc,
+        } = self;
+
+        let config = rpc.load_config()?;
+        let provider = utils::get_provider(&config)?;
+        let mut receipt =
+            Cast::new(&provider).get_receipt(tx_hash, confirmations, None, cast_async).await?;
+
+        let decoded_logs = if decode_logs {
+            let mut abis =
+                KnownAbis::load(&config, abi_path.as_deref(), &provider, &receipt, etherscan)
+                    .await?;
+            // Prefer the decoded revert data to the reason reported by the node.
+            if let Some(data) = &receipt.revert_data {
+                receipt.revert_reason = Some(abis.decode_revert(data).await);
+            }
+            let logs = receipt.receipt.inner.inner.logs();
+            let mut decoded = Vec::with_capacity(logs.len());
+            for log in logs {
+                decoded.push(abis.decode_log(log).await);
+            }
+            Some(decoded)
+        } else {
+            None
+        };
+
+        if let Some(field) = field {
+            let value = get_pretty_tx_receipt_attr(&receipt, &field)
+                .ok_or_else(|| eyre::eyre!("invalid receipt field: {}", field))?;
+            sh_println!("{value}")?;
+        } else if shell::is_json() {
+            // to_value first to sort json object keys
+            let mut json = serde_json::to_value(&receipt)?;
+            if let Some(decoded_logs) = &decoded_logs {
+                json["decodedLogs"] = serde_json::to_value(decoded_logs)?;
+            }
+            sh_println!("{json}")?;
+        } else {
+            let mut out = receipt.pretty();
+            if let Some(decoded_logs) = &decoded_logs {
+                out.push_str(&format_decoded_logs(&receipt.receipt, decoded_logs));
+            }
+            sh_println!("{out}")?;
+        }
+
+        if check && receipt.is_failure() {
+            let hash = receipt.receipt.transaction_hash;
+            match &receipt.revert_reason {
+                Some(reason) => eyre::bail!("transaction {hash} failed: {reason}"),
+                None => eyre::bail!("transaction {hash} failed"),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A decoded log of a transaction.
+#[derive(Clone, Debug, Serialize)]
+struct DecodedLog {
+    /// The address of the contract that emitted the log.
+    address: Address,
+    /// The name of the event.
+    event: String,
+    /// The signature of the event, e.g. `Transfer(address,address,uint256)`.
+    signature: String,
+    /// The parameters of the event.
+    params: Vec<DecodedParam>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct DecodedParam {
+    name: String,
+    #[serde(rename = "type")]
+    ty: String,
+    indexed: bool,
+    value: String,
+}
+
+/// The events and errors used to decode a transaction.
+struct KnownAbis {
+    /// The events, by selector.
+    events: HashMap<B256, Vec<Event>>,
+    revert_decoder: RevertDecoder,
+    signatures: Option<SingleSignaturesIdentifier>,
+}
+
+impl KnownAbis {
+    /// Loads the ABIs of the project's artifacts and of the `abi_path` directory, then fetches
+    /// those of the contracts that emitted unknown events from Etherscan.
+    async fn load(
+        config: &Config,
+        abi_path: Option<&Path>,
+        provider: &RetryProvider,
+        receipt: &TransactionReceiptWithRevertReason,
+        mut etherscan: EtherscanOpts,
+    ) -> Result<Self> {
+        let mut abis = load_abis(&config.root.join(&config.out));
+        if let Some(abi_path) = abi_path {
+            if !abi_path.is_dir() {
+                eyre::bail!("ABI directory {} does not exist", abi_path.display());
+            }
+            abis.extend(load_abis(abi_path));
+        }
+
+        let mut known = Self {
+            events: HashMap::new(),
+            revert_decoder: RevertDecoder::new(),
+            signatures: SignaturesIdentifier::new(Config::foundry_cache_dir(), config.offline).ok(),
+        };
+        known.extend(&abis);
+
+        let chain = provider.get_chain_id().await?;
+        let has_key =
+            etherscan.key().is_some() || config.get_etherscan_api_key(Some(chain.into())).is_some();
+        if has_key && !config.offline {
+            etherscan.chain = Some(chain.into());
+            let unknown = receipt
+                .receipt
+                .inner
+                .inner
+                .logs()
+                .iter()
+                .filter(|log| log.topic0().is_some_and(|t0| !known.events.contains_key(t0)))
+                .map(|log| log.address())
+                .collect::<HashSet<_>>();
+            let mut fetched = Vec::new();
+            for address in unknown {
+                match fetch_abi_from_etherscan(address, &etherscan).await {
+                    Ok(items) => fetched.extend(items.into_iter().map(|(abi, _)| abi)),
+                    Err(err) => debug!(%address, %err, "failed to fetch ABI from Etherscan"),
+                }
+            }
+            known.extend(&fetched);
+            abis.extend(fetched);
+        }
+
+        known.revert_decoder = RevertDecoder::new().with_abis(&abis);
+        Ok(known)
+    }
+
+    fn extend(&mut self, abis: &[JsonAbi]) {
+        for event in abis.iter().flat_map(|abi| abi.events()) {
+            let events = self.events.entry(event.selector()).or_default();
+            if !events.contains(event) {
+                events.push(event.clone());
+            }
+        }
+    }
+
+    /// Decodes revert data with the known errors, or the signature database if its selector is
+    /// unknown.
+    async fn decode_revert(&mut self, data: &[u8]) -> String {
+        if let (Some((selector, _)), Some(signatures)) =
+            (data.split_first_chunk::<SELECTOR_LEN>(), &self.signatures)
+        {
+            let is_builtin = *selector == Revert::SELECTOR || *selector == Panic::SELECTOR;
+            if !is_builtin && !self.revert_decoder.errors.contains_key(&Selector::from(*selector)) {
+                if let Some(error) = signatures.write().await.identify_error(selector).await {
+                    self.revert_decoder.push_error(error);
+                }
+            }
+        }
+        self.revert_decoder.decode(data, None)
+    }
+
+    /// Decodes a log with the known events, or the signature database if none matches.
+    async fn decode_log(&self, log: &Log) -> Option<DecodedLog> {
+        let t0 = log.topic0()?;
+        let mut events = self.events.get(t0).cloned().unwrap_or_default();
+        if events.is_empty() {
+            if let Some(signatures) = &self.signatures {
+                if let Some(event) = signatures.write().await.identify_event(&t0[..]).await {
+                    events.push(get_indexed_event(event, log.data()));
+                }
+            }
+        }
+        events.iter().find_map(|event| decode_event(event, log))
+    }
+}
+
+fn decode_event(event: &Event, log: &Log) -> Option<DecodedLog> {
+    let decoded = event.decode_log(log.data(), true).ok()?;
+    let (mut indexed, mut body) = (decoded.indexed.into_iter(), decoded.body.into_iter());
+    let params = event
+        .inputs
+        .iter()
+        .map(|input| {
+            let value = if input.indexed { indexed.next() } else { body.next() };
+            DecodedParam {
+                name: input.name.clone(),
+                ty: input.ty.clone(),
+                indexed: input.indexed,
+                value: value.as_ref().map(format_token_raw).unwrap_or_default(),
+            }
+        })
+        .collect();
+    Some(DecodedLog {
+        address: log.address(),
+        event: event.name.clone(),
+        signature: event.signature(),
+        params,
+    })
+}
+
+/// Returns the ABIs of the JSON files in the directory, either bare ABIs or artifacts with an
+/// `abi` field. Other files are ignored.
+fn load_abis(dir: &Path) -> Vec<JsonAbi> {
+    if !dir.is_dir() {
+        return Vec::new()
+    }
+    foundry_common::fs::json_files(dir)
+        .filter(|path| !path.components().any(|c| c.as_os_str() == "build-info"))
+        .filter_map(|path| {
+            let mut value: serde_json::Value = foundry_common::fs::read_json_file(&path).ok()?;
+            if let Some(abi) = value.get_mut("abi") {
+                value = abi.take();
+            }
+            serde_json::from_value(value).ok()
+        })
+        .collect()
+}
+
+/// Formats the decoded logs to be appended to the pretty receipt.
+fn format_decoded_logs(
+    receipt: &AnyTransactionReceipt,
+    decoded_logs: &[Option<DecodedLog>],
+) -> String {
+    let mut out = String::from("\ndecodedLogs");
+    for (i, (log, decoded)) in receipt.inner.inner.logs().iter().zip(decoded_logs).enumerate() {
+        let Some(decoded) = decoded else {
+            let topic0 = log.topic0().map(|t0| t0.to_string());
+            let topic0 = topic0.as_deref().unwrap_or("(anonymous)");
+            let _ = write!(out, "\n  [{i}] {} unknown event {topic0}", log.address());
+            continue
+        };
+        let _ = write!(out, "\n  [{i}] {} {}", decoded.address, decoded.signature);
+        for param in &decoded.params {
+            let _ = write!(out, "\n        {}: {}", param.name, param.value);
+        }
+    }
+    out
+}
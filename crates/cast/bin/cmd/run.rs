@@ -1,16 +1,16 @@
 use alloy_consensus::Transaction;
-use alloy_network::TransactionResponse;
-use alloy_primitives::U256;
+use alloy_network::{AnyNetwork, TransactionResponse};
+use alloy_primitives::{TxHash, U256};
 use alloy_provider::Provider;
 use alloy_rpc_types::BlockTransactions;
 use cast::revm::primitives::EnvWithHandlerCfg;
-use clap::Parser;
+use clap::{Parser, ValueHint};
 use eyre::{Result, WrapErr};
 use foundry_cli::{
     opts::{EtherscanOpts, RpcOpts},
     utils::{handle_traces, init_progress, TraceResult},
 };
-use foundry_common::{is_known_system_sender, shell, SYSTEM_TRANSACTION_TYPE};
+use foundry_common::{fs, is_known_system_sender, shell, SYSTEM_TRANSACTION_TYPE};
 use foundry_compilers::artifacts::EvmVersion;
 use foundry_config::{
     figment::{
@@ -23,9 +23,13 @@
 use foundry_evm::{
     executors::{EvmError, TracingExecutor},
     opts::EvmOpts,
-    traces::{InternalTraceMode, TraceMode},
+    traces::{
+        CallTraceArena, InternalTraceMode, SparsedTraceArena, TraceExportFormat, TraceKind,
+        TraceMode,
+    },
     utils::configure_tx_env,
 };
+use std::path::PathBuf;
 
 /// CLI arguments for `cast run`.
 #[derive(Clone, Debug, Parser)]
@@ -37,6 +41,14 @@ pub struct RunArgs {
     #[arg(long, short)]
     debug: bool,
 
+    /// Debugs the transaction as it was executed by the node, instead of replaying it.
+    ///
+    /// The recorded steps are fetched from the node with `debug_traceTransaction`, and sources are
+    /// resolved from the local project artifacts. The node must be an anvil instance started with
+    /// `--steps-tracing`.
+    #[arg(long, requires = "debug", conflicts_with_all = ["quick", "trace_printer"])]
+    attach: bool,
+
     /// Whether to identify internal functions in traces.
     #[arg(long)]
     decode_internal: bool,
@@ -57,6 +69,19 @@ pub struct RunArgs {
     #[arg(long, short)]
     label: Vec<String>,
 
+    /// Export the trace of the transaction.
+    ///
+    /// `otlp` writes OpenTelemetry spans in the OTLP/JSON encoding, `speedscope` writes a profile
+    /// that can be opened in https://www.speedscope.app. Gas is used as the duration of calls.
+    #[arg(long, value_name = "FORMAT", conflicts_with = "debug")]
+    export_trace: Option<TraceExportFormat>,
+
+    /// The file to export the trace to.
+    ///
+    /// Defaults to `<TX_HASH>.<FORMAT>.json`.
+    #[arg(long, requires = "export_trace", value_hint = ValueHint::FilePath, value_name = "PATH")]
+    export_trace_out: Option<PathBuf>,
+
     #[command(flatten)]
     etherscan: EtherscanOpts,
 
@@ -131,6 +156,10 @@ pub async fn run(self) -> Result<()> {
         let tx_block_number =
             tx.block_number.ok_or_else(|| eyre::eyre!("tx may still be pending: {:?}", tx_hash))?;
 
+        if self.attach {
+            return self.debug_attached(&provider, &config, tx_hash).await
+        }
+
         // fetch the block the transaction was mined in
         let block = provider.get_block(tx_block_number.into(), true.into()).await?;
 
@@ -260,7 +289,7 @@ pub async fn run(self) -> Result<()> {
             }
         };
 
-        handle_traces(
+        let result = handle_traces(
             result,
             &config,
             chain,
@@ -271,6 +300,60 @@ pub async fn run(self) -> Result<()> {
         )
         .await?;
 
+        if let Some(format) = self.export_trace {
+            let path = self
+                .export_trace_out
+                .unwrap_or_else(|| format!("{tx_hash}.{}", format.extension()).into());
+            let arenas = result.traces.iter().flatten().map(|(_, arena)| arena.resolve_arena());
+            let arenas = arenas.collect::<Vec<_>>();
+            let exported = format.export(
+                &tx_hash.to_string(),
+                arenas.iter().map(|arena| ("transaction", arena.as_ref())),
+            );
+            fs::write_pretty_json_file(&path, &exported)?;
+            sh_println!("Exported trace to {}", path.display())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl RunArgs {
+    /// Opens the transaction in the debugger with the steps recorded by the node.
+    async fn debug_attached<P: Provider<AnyNetwork>>(
+        self,
+        provider: &P,
+        config: &Config,
+        tx_hash: TxHash,
+    ) -> Result<()> {
+        let arena: Option<CallTraceArena> = provider
+            .raw_request(
+                "debug_traceTransaction".into(),
+                (tx_hash, serde_json::json!({ "foundryDebugger": true })),
+            )
+            .await
+            .wrap_err(
+                "failed to fetch the recorded steps of the transaction, \
+                 is the node an anvil instance started with `--steps-tracing`?",
+            )?;
+        let arena = arena.ok_or_else(|| eyre::eyre!("tx not found: {tx_hash:?}"))?;
+
+        let root = &arena
+            .nodes()
+            .first()
+            .ok_or_else(|| eyre::eyre!("no calls were recorded for the transaction"))?
+            .trace;
+        let kind =
+            if root.kind.is_any_create() { TraceKind::Deployment } else { TraceKind::Execution };
+        let result = TraceResult {
+            success: root.success,
+            gas_used: root.gas_used,
+            traces: Some(vec![(kind, SparsedTraceArena { arena, ignored: Default::default() })]),
+        };
+
+        let chain = foundry_cli::utils::get_chain(config.chain, provider).await?;
+        handle_traces(result, config, Some(chain), self.label, true, true, self.decode_internal)
+            .await?;
         Ok(())
     }
 }
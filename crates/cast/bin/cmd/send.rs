@@ -1,10 +1,14 @@
 use crate::tx::{self, CastTxBuilder};
 use alloy_network::{AnyNetwork, EthereumWallet};
+use alloy_primitives::TxHash;
 use alloy_provider::{Provider, ProviderBuilder};
 use alloy_rpc_types::TransactionRequest;
 use alloy_serde::WithOtherFields;
 use alloy_signer::Signer;
-use cast::Cast;
+use cast::{
+    watch::{TxWatchOutcome, TxWatchStatus, TxWatcher},
+    Cast,
+};
 use clap::Parser;
 use eyre::Result;
 use foundry_cli::{
@@ -12,8 +16,9 @@
     utils,
     utils::LoadConfig,
 };
-use foundry_common::ens::NameOrAddress;
-use std::{path::PathBuf, str::FromStr};
+use foundry_common::{ens::NameOrAddress, shell};
+use foundry_config::TransactionWatchConfig;
+use std::{path::PathBuf, str::FromStr, time::Duration};
 
 /// CLI arguments for `cast send`.
 #[derive(Debug, Parser)]
@@ -38,6 +43,13 @@ pub struct SendTxArgs {
     #[arg(long, default_value = "1")]
     confirmations: u64,
 
+    /// Watch the transaction until it has the required number of confirmations, reporting any
+    /// reorgs it survives and whether it was dropped or replaced.
+    ///
+    /// Exits with an error if the transaction is not confirmed.
+    #[arg(long, conflicts_with = "async")]
+    wait: bool,
+
     #[command(subcommand)]
     command: Option<SendTxSubcommands>,
 
@@ -93,6 +105,7 @@ pub async fn run(self) -> eyre::Result<()> {
             mut args,
             tx,
             confirmations,
+            wait,
             command,
             unlocked,
             path,
@@ -126,6 +139,7 @@ pub async fn run(self) -> eyre::Result<()> {
             .with_blob_data(blob_data)?;
 
         let timeout = timeout.unwrap_or(config.transaction_timeout);
+        let watch = wait.then_some(&config.transaction_watch);
 
         // Case 1:
         // Default to sending via eth_sendTransaction if the --unlocked flag is passed.
@@ -153,7 +167,7 @@ pub async fn run(self) -> eyre::Result<()> {
 
             let (tx, _) = builder.build(config.sender).await?;
 
-            cast_send(provider, tx, cast_async, confirmations, timeout).await
+            cast_send(provider, tx, cast_async, confirmations, timeout, watch).await
         // Case 2:
         // An option to use a local signer was provided.
         // If we cannot successfully instantiate a local signer, then we will assume we don't have
@@ -172,7 +186,7 @@ pub async fn run(self) -> eyre::Result<()> {
                 .wallet(wallet)
                 .on_provider(&provider);
 
-            cast_send(provider, tx, cast_async, confirmations, timeout).await
+            cast_send(provider, tx, cast_async, confirmations, timeout, watch).await
         }
     }
 }
@@ -183,14 +197,17 @@ async fn cast_send<P: Provider<AnyNetwork>>(
     cast_async: bool,
     confs: u64,
     timeout: u64,
+    watch: Option<&TransactionWatchConfig>,
 ) -> Result<()> {
-    let cast = Cast::new(provider);
+    let cast = Cast::new(&provider);
     let pending_tx = cast.send(tx).await?;
 
-    let tx_hash = pending_tx.inner().tx_hash();
+    let tx_hash = *pending_tx.inner().tx_hash();
 
     if cast_async {
         sh_println!("{tx_hash:#x}")?;
+    } else if let Some(watch) = watch {
+        watch_tx(&provider, tx_hash, confs, timeout, watch).await?;
     } else {
         let receipt =
             cast.receipt(format!("{tx_hash:#x}"), None, confs, Some(timeout), false).await?;
@@ -199,3 +216,64 @@ async fn cast_send<P: Provider<AnyNetwork>>(
 
     Ok(())
 }
+
+/// Watches a transaction until it has `confs` confirmations and prints the outcome.
+///
+/// Returns an error if the transaction was not confirmed.
+pub async fn watch_tx<P: Provider<AnyNetwork>>(
+    provider: P,
+    tx_hash: TxHash,
+    confs: u64,
+    timeout: u64,
+    config: &TransactionWatchConfig,
+) -> Result<()> {
+    let watcher = TxWatcher::new(provider, confs, Duration::from_secs(timeout), config);
+    let outcome = watcher
+        .watch(tx_hash, |reorg| {
+            let _ = match reorg.new_block_number {
+                Some(number) => sh_warn!(
+                    "Transaction {tx_hash:#x} was reorged from block {} to block {number}",
+                    reorg.old_block_number
+                ),
+                None => sh_warn!(
+                    "Transaction {tx_hash:#x} was reorged out of block {}",
+                    reorg.old_block_number
+                ),
+            };
+        })
+        .await?;
+
+    if shell::is_json() {
+        sh_println!("{}", serde_json::to_string(&outcome)?)?;
+    } else {
+        print_watch_outcome(&outcome)?;
+    }
+
+    match outcome.status {
+        TxWatchStatus::Confirmed => Ok(()),
+        TxWatchStatus::Replaced => eyre::bail!("transaction {tx_hash:#x} was replaced"),
+        TxWatchStatus::Dropped => eyre::bail!("transaction {tx_hash:#x} was dropped"),
+        TxWatchStatus::TimedOut => eyre::bail!(
+            "transaction {tx_hash:#x} had {} of {confs} confirmations after {timeout}s",
+            outcome.confirmations
+        ),
+    }
+}
+
+fn print_watch_outcome(outcome: &TxWatchOutcome) -> Result<()> {
+    let status = match outcome.status {
+        TxWatchStatus::Confirmed => "confirmed",
+        TxWatchStatus::Replaced => "replaced",
+        TxWatchStatus::Dropped => "dropped",
+        TxWatchStatus::TimedOut => "timed out",
+    };
+    sh_println!("status               {status}")?;
+    sh_println!("transactionHash      {:#x}", outcome.tx_hash)?;
+    if let (Some(number), Some(hash)) = (outcome.block_number, outcome.block_hash) {
+        sh_println!("blockNumber          {number}")?;
+        sh_println!("blockHash            {hash:#x}")?;
+    }
+    sh_println!("confirmations        {}", outcome.confirmations)?;
+    sh_println!("reorgs               {}", outcome.reorgs.len())?;
+    Ok(())
+}
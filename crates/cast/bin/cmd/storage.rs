@@ -15,17 +15,23 @@
 };
 use foundry_common::{
     abi::find_source,
-    compile::{etherscan_project, ProjectCompiler},
+    compile::{
+        add_storage_layout_output, cached_etherscan_project, etherscan_project,
+        with_compilation_reporter, ProjectCompiler,
+    },
     ens::NameOrAddress,
-    shell,
+    erc7201, shell,
 };
 use foundry_compilers::{
-    artifacts::{ConfigurableContractArtifact, Contract, StorageLayout},
+    artifacts::{
+        ast::{Node, NodeType},
+        ConfigurableContractArtifact, Contract, Source, Sources, Storage, StorageLayout,
+    },
     compilers::{
         solc::{Solc, SolcCompiler},
         Compiler,
     },
-    Artifact, Project,
+    Artifact, ArtifactId, Project, ProjectCompileOutput,
 };
 use foundry_config::{
     figment::{self, value::Dict, Metadata, Profile},
@@ -33,7 +39,7 @@
 };
 use semver::Version;
 use serde::{Deserialize, Serialize};
-use std::str::FromStr;
+use std::{collections::HashMap, path::PathBuf, str::FromStr};
 
 /// The minimum Solc version for outputting storage layouts.
 ///
@@ -57,6 +63,19 @@ pub struct StorageArgs {
     #[arg(long, short)]
     block: Option<BlockId>,
 
+    /// List the ERC-7201 namespaces declared by the contract and its base contracts.
+    #[arg(long, conflicts_with_all = &["slot", "namespace"])]
+    namespaces: bool,
+
+    /// Get the layout and values of the struct stored in the given ERC-7201 namespace, instead
+    /// of the regular storage layout.
+    #[arg(long, value_name = "ID", conflicts_with = "slot")]
+    namespace: Option<String>,
+
+    /// Only get the given member of the namespace struct.
+    #[arg(long, value_name = "NAME", requires = "namespace")]
+    member: Option<String>,
+
     #[command(flatten)]
     rpc: RpcOpts,
 
@@ -87,7 +106,8 @@ impl StorageArgs {
     pub async fn run(self) -> Result<()> {
         let config = self.load_config()?;
 
-        let Self { address, slot, block, build, .. } = self;
+        let Self { address, slot, block, build, namespaces, namespace, member, .. } = self;
+        let query = StorageQuery::new(namespaces, namespace, member);
         let provider = utils::get_provider(&config)?;
         let address = address.resolve(&provider).await?;
 
@@ -112,18 +132,13 @@ pub async fn run(self) -> Result<()> {
             // Find in artifacts and pretty print
             add_storage_layout_output(&mut project);
             let out = ProjectCompiler::new().quiet(shell::is_json()).compile(&project)?;
-            let artifact = out.artifacts().find(|(_, artifact)| {
+            let artifact = out.artifact_ids().find(|(_, artifact)| {
                 artifact.get_deployed_bytecode_bytes().is_some_and(|b| *b == address_code)
             });
-            if let Some((_, artifact)) = artifact {
-                return fetch_and_print_storage(
-                    provider,
-                    address,
-                    block,
-                    artifact,
-                    !shell::is_json(),
-                )
-                .await;
+            if let Some((id, artifact)) = artifact {
+                return query
+                    .run(provider, address, block, &mut project, &out, &id, artifact)
+                    .await;
             }
         }
 
@@ -143,12 +158,18 @@ pub async fn run(self) -> Result<()> {
         let version = metadata.compiler_version()?;
         let auto_detect = version < MIN_SOLC;
 
-        // Create a new temp project
-        // TODO: Cache instead of using a temp directory: metadata from Etherscan won't change
+        // Reuse the project compiled by `cast source --recompile` if there is one, otherwise create
+        // a new temp project
+        let cached_root =
+            Config::foundry_etherscan_sources_dir(chain, address).filter(|dir| dir.is_dir());
         let root = tempfile::tempdir()?;
-        let root_path = root.path();
-        let mut project = etherscan_project(metadata, root_path)?;
-        add_storage_layout_output(&mut project);
+        let mut project = if let Some(cached_root) = &cached_root {
+            cached_etherscan_project(metadata, cached_root)?
+        } else {
+            let mut project = etherscan_project(metadata, root.path())?;
+            add_storage_layout_output(&mut project);
+            project
+        };
 
         project.compiler = if auto_detect {
             SolcCompiler::AutoDetect
@@ -158,35 +179,281 @@ pub async fn run(self) -> Result<()> {
 
         // Compile
         let mut out = ProjectCompiler::new().quiet(true).compile(&project)?;
-        let artifact = {
-            let (_, mut artifact) = out
-                .artifacts()
-                .find(|(name, _)| name == &metadata.contract_name)
-                .ok_or_else(|| eyre::eyre!("Could not find artifact"))?;
-
-            if is_storage_layout_empty(&artifact.storage_layout) && auto_detect {
-                // try recompiling with the minimum version
-                sh_warn!("The requested contract was compiled with {version} while the minimum version for storage layouts is {MIN_SOLC} and as a result the output may be empty.")?;
-                let solc = Solc::find_or_install(&MIN_SOLC)?;
-                project.compiler = SolcCompiler::Specific(solc);
-                if let Ok(output) = ProjectCompiler::new().quiet(true).compile(&project) {
-                    out = output;
-                    let (_, new_artifact) = out
-                        .artifacts()
-                        .find(|(name, _)| name == &metadata.contract_name)
-                        .ok_or_else(|| eyre::eyre!("Could not find artifact"))?;
-                    artifact = new_artifact;
-                }
+        let find_artifact = |out: &ProjectCompileOutput<SolcCompiler>| {
+            out.artifact_ids()
+                .find(|(id, _)| id.name == metadata.contract_name)
+                .map(|(id, artifact)| (id, artifact.clone()))
+                .ok_or_else(|| eyre::eyre!("Could not find artifact"))
+        };
+        let (mut id, mut artifact) = find_artifact(&out)?;
+
+        if is_storage_layout_empty(&artifact.storage_layout) && auto_detect {
+            // try recompiling with the minimum version
+            sh_warn!("The requested contract was compiled with {version} while the minimum version for storage layouts is {MIN_SOLC} and as a result the output may be empty.")?;
+            let solc = Solc::find_or_install(&MIN_SOLC)?;
+            project.compiler = SolcCompiler::Specific(solc);
+            if let Ok(output) = ProjectCompiler::new().quiet(true).compile(&project) {
+                out = output;
+                (id, artifact) = find_artifact(&out)?;
             }
+        }
 
-            artifact
-        };
+        let result = query.run(provider, address, block, &mut project, &out, &id, &artifact).await;
 
         // Clear temp directory
         root.close()?;
 
-        fetch_and_print_storage(provider, address, block, artifact, !shell::is_json()).await
+        result
+    }
+}
+
+/// What to get from the storage of a contract.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum StorageQuery {
+    /// The regular storage layout.
+    Layout,
+    /// The list of ERC-7201 namespaces.
+    Namespaces,
+    /// The struct stored in an ERC-7201 namespace, or one of its members.
+    Namespace { id: String, member: Option<String> },
+}
+
+impl StorageQuery {
+    fn new(namespaces: bool, namespace: Option<String>, member: Option<String>) -> Self {
+        match namespace {
+            Some(id) => Self::Namespace { id, member },
+            None if namespaces => Self::Namespaces,
+            None => Self::Layout,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn run<P: Provider<AnyNetwork>, C: Compiler<CompilerContract = Contract>>(
+        &self,
+        provider: P,
+        address: Address,
+        block: Option<BlockId>,
+        project: &mut Project<C>,
+        output: &ProjectCompileOutput<C>,
+        id: &ArtifactId,
+        artifact: &ConfigurableContractArtifact,
+    ) -> Result<()> {
+        let namespace_id = match self {
+            Self::Layout => {
+                return fetch_and_print_storage(
+                    provider,
+                    address,
+                    block,
+                    artifact,
+                    !shell::is_json(),
+                )
+                .await
+            }
+            Self::Namespaces => {
+                return print_namespaces(&find_namespaces(output, id), !shell::is_json())
+            }
+            Self::Namespace { id, .. } => id,
+        };
+
+        let namespaces = find_namespaces(output, id);
+        let Some(namespace) = namespaces.iter().find(|namespace| namespace.id == *namespace_id)
+        else {
+            let known =
+                namespaces.iter().map(|namespace| namespace.id.as_str()).collect::<Vec<_>>();
+            eyre::bail!(
+                "namespace `{namespace_id}` is not declared by {}; known namespaces: [{}]",
+                id.name,
+                known.join(", ")
+            );
+        };
+
+        let mut layout = namespace_layout(project, namespace)?;
+        if let Self::Namespace { member: Some(member), .. } = self {
+            layout.storage.retain(|storage| storage.label == *member);
+            if layout.storage.is_empty() {
+                eyre::bail!("`{}` has no member named `{member}`", namespace.struct_name);
+            }
+        }
+
+        let values = fetch_storage_slots(provider, address, block, &layout).await?;
+        print_storage(layout, values, !shell::is_json())
+    }
+}
+
+/// The name of the contract that is compiled to get the layout of a namespace struct.
+const NAMESPACE_LAYOUT_CONTRACT: &str = "FoundryErc7201Layout";
+
+/// An ERC-7201 namespace declared by a contract.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+struct Namespace {
+    /// The namespace id.
+    id: String,
+    /// The root storage slot of the namespace.
+    slot: B256,
+    /// The contract that declares the namespace struct.
+    contract: String,
+    /// The name of the namespace struct.
+    #[serde(rename = "struct")]
+    struct_name: String,
+    /// The source file of the contract.
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+/// Finds the ERC-7201 namespaces declared by the contract `target` and its base contracts.
+///
+/// Namespaces are declared with a `@custom:storage-location erc7201:<id>` annotation on a struct.
+fn find_namespaces<C: Compiler<CompilerContract = Contract>>(
+    output: &ProjectCompileOutput<C>,
+    target: &ArtifactId,
+) -> Vec<Namespace> {
+    // AST node IDs are only unique within a compilation, which is done per compiler version.
+    let mut contracts = HashMap::<usize, (PathBuf, &Node)>::new();
+    let mut target_node = None;
+    for (id, artifact) in output.artifact_ids() {
+        if id.version != target.version {
+            continue;
+        }
+        let Some(ast) = &artifact.ast else { continue };
+        for node in &ast.nodes {
+            if node.node_type != NodeType::ContractDefinition {
+                continue;
+            }
+            let Some(node_id) = node.id else { continue };
+            if id.source == target.source &&
+                node.attribute::<String>("name").is_some_and(|name| name == target.name)
+            {
+                target_node = Some(node);
+            }
+            contracts.entry(node_id).or_insert((id.source.clone(), node));
+        }
+    }
+
+    let Some(target_node) = target_node else { return Vec::new() };
+    let bases = target_node.attribute::<Vec<usize>>("linearizedBaseContracts").unwrap_or_default();
+
+    let mut namespaces = Vec::<Namespace>::new();
+    for (path, contract) in bases.iter().filter_map(|id| contracts.get(id)) {
+        let contract_name = contract.attribute::<String>("name").unwrap_or_default();
+        for node in &contract.nodes {
+            if node.node_type != NodeType::StructDefinition {
+                continue;
+            }
+            let Some(id) = node
+                .other
+                .get("documentation")
+                .and_then(|docs| docs.get("text").or(Some(docs)).and_then(|text| text.as_str()))
+            else {
+                continue;
+            };
+            let Some(id) = parse_storage_location(id) else { continue };
+            if namespaces.iter().any(|namespace| namespace.id == id) {
+                continue;
+            }
+            namespaces.push(Namespace {
+                id: id.to_string(),
+                slot: erc7201(id),
+                contract: contract_name.clone(),
+                struct_name: node.attribute("name").unwrap_or_default(),
+                path: path.clone(),
+            });
+        }
+    }
+    namespaces
+}
+
+/// Returns the namespace id of an `@custom:storage-location erc7201:<id>` annotation.
+fn parse_storage_location(docs: &str) -> Option<&str> {
+    let (_, rest) = docs.split_once("@custom:storage-location")?;
+    let location = rest.split_whitespace().next()?;
+    location.strip_prefix("erc7201:").filter(|id| !id.is_empty())
+}
+
+/// Returns the storage layout of the struct of `namespace`, with slots relative to the root of the
+/// namespace.
+///
+/// Solc does not include namespaced structs in the storage layout of a contract, so this compiles a
+/// contract that stores the struct in a state variable.
+fn namespace_layout<C: Compiler<CompilerContract = Contract>>(
+    project: &mut Project<C>,
+    namespace: &Namespace,
+) -> Result<StorageLayout> {
+    let path = project.root().join(&namespace.path);
+    let source = format!(
+        "// SPDX-License-Identifier: UNLICENSED\n\
+         import {{{contract} as Namespace}} from \"{path}\";\n\
+         contract {NAMESPACE_LAYOUT_CONTRACT} {{ Namespace.{name} internal namespace; }}\n",
+        contract = namespace.contract,
+        path = path.display(),
+        name = namespace.struct_name,
+    );
+    let file = project.root().join(format!("{NAMESPACE_LAYOUT_CONTRACT}.sol"));
+    let sources = Sources::from([(file, Source::new(source))]);
+
+    project.no_artifacts = true;
+    project.cached = false;
+    let output = with_compilation_reporter(true, || {
+        foundry_compilers::project::ProjectCompiler::with_sources(project, sources)?.compile()
+    })?;
+    if output.has_compiler_errors() {
+        eyre::bail!("{output}");
+    }
+
+    let (_, artifact) =
+        output.artifacts().find(|(name, _)| name == NAMESPACE_LAYOUT_CONTRACT).ok_or_else(
+            || eyre::eyre!("could not compile the layout of `{}`", namespace.struct_name),
+        )?;
+    let mut layout = artifact.storage_layout.clone().unwrap_or_default();
+    let members = layout
+        .storage
+        .first()
+        .and_then(|storage| layout.types.get(&storage.storage_type))
+        .and_then(|ty| ty.other.get("members"))
+        .and_then(|members| serde_json::from_value::<Vec<Storage>>(members.clone()).ok())
+        .ok_or_else(|| eyre::eyre!("missing storage layout of `{}`", namespace.struct_name))?;
+
+    let root = U256::from_be_bytes(namespace.slot.0);
+    layout.storage = members
+        .into_iter()
+        .map(|mut member| {
+            let slot = U256::from_str(&member.slot)?;
+            member.slot = (root + slot).to_string();
+            member.contract = format!("{}.{}", namespace.contract, namespace.struct_name);
+            Ok(member)
+        })
+        .collect::<Result<_>>()?;
+    Ok(layout)
+}
+
+fn print_namespaces(namespaces: &[Namespace], pretty: bool) -> Result<()> {
+    if !pretty {
+        sh_println!("{}", serde_json::to_string_pretty(namespaces)?)?;
+        return Ok(());
+    }
+
+    if namespaces.is_empty() {
+        sh_println!("No ERC-7201 namespaces found.")?;
+        return Ok(());
     }
+
+    let mut table = Table::new();
+    table.apply_modifier(UTF8_ROUND_CORNERS);
+    table.set_header(vec![
+        Cell::new("Namespace"),
+        Cell::new("Slot"),
+        Cell::new("Struct"),
+        Cell::new("Contract"),
+    ]);
+    for namespace in namespaces {
+        table.add_row([
+            namespace.id.as_str(),
+            &namespace.slot.to_string(),
+            &namespace.struct_name,
+            &namespace.contract,
+        ]);
+    }
+    sh_println!("\n{table}\n")?;
+    Ok(())
 }
 
 /// Represents the value of a storage slot `eth_getStorageAt` call.
@@ -324,17 +591,6 @@ fn print_storage(layout: StorageLayout, values: Vec<StorageValue>, pretty: bool)
     Ok(())
 }
 
-fn add_storage_layout_output<C: Compiler<CompilerContract = Contract>>(project: &mut Project<C>) {
-    project.artifacts.additional_values.storage_layout = true;
-    project.update_output_selection(|selection| {
-        selection.0.values_mut().for_each(|contract_selection| {
-            contract_selection
-                .values_mut()
-                .for_each(|selection| selection.push("storageLayout".to_string()))
-        });
-    })
-}
-
 fn is_storage_layout_empty(storage_layout: &Option<StorageLayout>) -> bool {
     if let Some(ref s) = storage_layout {
         s.storage.is_empty()
@@ -361,4 +617,43 @@ fn parse_storage_etherscan_api_key() {
         let key = config.get_etherscan_api_key(None).unwrap();
         assert_eq!(key, "dummykey".to_string());
     }
+
+    #[test]
+    fn parse_storage_namespace_args() {
+        let args = StorageArgs::parse_from([
+            "foundry-cli",
+            "addr.eth",
+            "--namespace",
+            "example.main",
+            "--member",
+            "x",
+        ]);
+        assert_eq!(
+            StorageQuery::new(args.namespaces, args.namespace, args.member),
+            StorageQuery::Namespace { id: "example.main".to_string(), member: Some("x".to_string()) }
+        );
+
+        let args = StorageArgs::parse_from(["foundry-cli", "addr.eth", "--namespaces"]);
+        assert_eq!(
+            StorageQuery::new(args.namespaces, args.namespace, args.member),
+            StorageQuery::Namespaces
+        );
+
+        assert!(StorageArgs::try_parse_from(["foundry-cli", "addr.eth", "--member", "x"]).is_err());
+    }
+
+    #[test]
+    fn parse_erc7201_storage_location() {
+        assert_eq!(
+            parse_storage_location("@custom:storage-location erc7201:example.main"),
+            Some("example.main")
+        );
+        assert_eq!(
+            parse_storage_location("Main storage.\n @custom:storage-location erc7201:a.b\n"),
+            Some("a.b")
+        );
+        assert_eq!(parse_storage_location("@custom:storage-location erc7201:"), None);
+        assert_eq!(parse_storage_location("@custom:storage-location other:a.b"), None);
+        assert_eq!(parse_storage_location("@notice no location"), None);
+    }
 }
@@ -0,0 +1,183 @@
+use crate::tx::{validate_from_address, CastTxBuilder};
+use alloy_network::{eip2718::Encodable2718, EthereumWallet, TransactionBuilder};
+use alloy_primitives::{hex, Address};
+use alloy_rpc_types::TransactionRequest;
+use alloy_serde::WithOtherFields;
+use alloy_signer::Signer;
+use clap::{Parser, ValueHint};
+use eyre::Result;
+use foundry_cli::opts::{ChainValueParser, TransactionOpts};
+use foundry_common::{fs, provider::RetryProvider};
+use foundry_config::Chain;
+use foundry_wallets::WalletOpts;
+use std::path::PathBuf;
+
+/// `cast tx` subcommands for building and signing transactions offline.
+#[derive(Debug, Parser)]
+#[allow(clippy::large_enum_variant)]
+pub enum TxSubcommands {
+    /// Build an unsigned transaction without connecting to a node.
+    ///
+    /// All the fields that are usually fetched from the node, such as the chain, nonce, gas limit
+    /// and fees, must be provided. The transaction is written as JSON, to be signed with
+    /// `cast tx sign`.
+    #[command(visible_alias = "b")]
+    Build(BuildTxArgs),
+
+    /// Sign a transaction built with `cast tx build` without connecting to a node.
+    ///
+    /// Prints the signed, RLP encoded transaction, which can be sent with `cast publish`.
+    #[command(visible_alias = "s")]
+    Sign(SignTxArgs),
+}
+
+impl TxSubcommands {
+    pub async fn run(self) -> Result<()> {
+        match self {
+            Self::Build(args) => args.run().await,
+            Self::Sign(args) => args.run().await,
+        }
+    }
+}
+
+/// CLI arguments for `cast tx build`.
+#[derive(Debug, Parser)]
+pub struct BuildTxArgs {
+    /// The destination of the transaction.
+    ///
+    /// If not provided, you must use `cast tx build --create`.
+    to: Option<Address>,
+
+    /// The signature of the function to call, or raw calldata.
+    sig: Option<String>,
+
+    /// The arguments of the function to call.
+    args: Vec<String>,
+
+    #[command(subcommand)]
+    command: Option<BuildTxSubcommands>,
+
+    /// The sender of the transaction.
+    #[arg(long, short, value_name = "ADDRESS", env = "ETH_FROM")]
+    from: Option<Address>,
+
+    /// The chain name or EIP-155 chain ID of the transaction.
+    #[arg(
+        long,
+        alias = "chain-id",
+        env = "CHAIN",
+        value_parser = ChainValueParser::default(),
+    )]
+    chain: Chain,
+
+    /// Write the transaction to the given file instead of printing it.
+    #[arg(long, short, value_hint = ValueHint::FilePath, value_name = "PATH")]
+    out: Option<PathBuf>,
+
+    #[command(flatten)]
+    tx: TransactionOpts,
+
+    /// The path of blob data to be sent.
+    #[arg(
+        long,
+        value_name = "BLOB_DATA_PATH",
+        conflicts_with = "legacy",
+        requires = "blob",
+        help_heading = "Transaction options"
+    )]
+    path: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+pub enum BuildTxSubcommands {
+    /// Use to deploy raw contract bytecode.
+    #[command(name = "--create")]
+    Create {
+        /// The initialization bytecode of the contract to deploy.
+        code: String,
+
+        /// The signature of the constructor.
+        sig: Option<String>,
+
+        /// The constructor arguments.
+        args: Vec<String>,
+    },
+}
+
+impl BuildTxArgs {
+    pub async fn run(self) -> Result<()> {
+        let Self { to, mut sig, mut args, command, from, chain, out, tx: tx_opts, path } = self;
+
+        let code = if let Some(BuildTxSubcommands::Create {
+            code,
+            sig: constructor_sig,
+            args: constructor_args,
+        }) = command
+        {
+            sig = constructor_sig;
+            args = constructor_args;
+            Some(code)
+        } else {
+            None
+        };
+
+        let blob_data = if let Some(path) = path { Some(std::fs::read(path)?) } else { None };
+
+        let tx = CastTxBuilder::<RetryProvider, _>::offline(tx_opts, chain)
+            .with_to(to.map(Into::into))
+            .await?
+            .with_code_sig_and_args(code, sig, args)
+            .await?
+            .with_blob_data(blob_data)?
+            .build_offline(from)
+            .await?;
+
+        match out {
+            Some(out) => {
+                fs::write_json_file(&out, &tx)?;
+                sh_println!("Transaction written to {}", out.display())?;
+            }
+            None => sh_println!("{}", serde_json::to_string_pretty(&tx)?)?,
+        }
+
+        Ok(())
+    }
+}
+
+/// CLI arguments for `cast tx sign`.
+#[derive(Debug, Parser)]
+pub struct SignTxArgs {
+    /// The JSON file of the transaction to sign, as written by `cast tx build`.
+    #[arg(value_hint = ValueHint::FilePath, value_name = "PATH")]
+    path: PathBuf,
+
+    #[command(flatten)]
+    wallet: WalletOpts,
+}
+
+impl SignTxArgs {
+    pub async fn run(self) -> Result<()> {
+        let Self { path, wallet } = self;
+
+        let mut tx: WithOtherFields<TransactionRequest> = fs::read_json_file(&path)?;
+
+        // Retrieve the signer, and bail if it can't be constructed.
+        let signer = wallet.signer().await?;
+        let signer_address = signer.address();
+        validate_from_address(wallet.from, signer_address)?;
+
+        match tx.from {
+            Some(from) if from != signer_address => {
+                eyre::bail!("Transaction sender {from} does not match the signer {signer_address}")
+            }
+            Some(_) => {}
+            None => tx.set_from(signer_address),
+        }
+
+        let tx = tx.build(&EthereumWallet::new(signer)).await?;
+
+        sh_println!("0x{}", hex::encode(tx.encoded_2718()))?;
+
+        Ok(())
+    }
+}
@@ -7,7 +7,7 @@
     coins_bip39::{English, Entropy, Mnemonic},
     MnemonicBuilder, PrivateKeySigner,
 };
-use cast::revm::primitives::Authorization;
+use cast::{revm::primitives::Authorization, SimpleCast};
 use clap::Parser;
 use eyre::{Context, Result};
 use foundry_cli::{opts::RpcOpts, utils, utils::LoadConfig};
@@ -135,10 +135,12 @@ pub enum WalletSubcommands {
         wallet: WalletOpts,
     },
 
-    /// Verify the signature of a message.
+    /// Verify the signature of a message or typed data.
     #[command(visible_alias = "v")]
     Verify {
-        /// The original message.
+        /// The original message or typed data.
+        ///
+        /// Typed data can be provided as a JSON string, or a file name with `--data --from-file`.
         message: String,
 
         /// The signature to verify.
@@ -147,6 +149,14 @@ pub enum WalletSubcommands {
         /// The address of the message signer.
         #[arg(long, short)]
         address: Address,
+
+        /// Treat the message as JSON typed data.
+        #[arg(long)]
+        data: bool,
+
+        /// Treat the message as a file containing JSON typed data. Requires `--data`.
+        #[arg(long, requires = "data")]
+        from_file: bool,
     },
 
     /// Import a private key into an encrypted keystore.
@@ -335,7 +345,11 @@ pub async fn run(self) -> Result<()> {
                 }
             }
             Self::Vanity(cmd) => {
-                cmd.run()?;
+                if cmd.contract {
+                    cmd.mine_salt()?;
+                } else {
+                    cmd.run()?;
+                }
             }
             Self::Address { wallet, private_key_override } => {
                 let wallet = private_key_override
@@ -352,13 +366,7 @@ pub async fn run(self) -> Result<()> {
             Self::Sign { message, data, from_file, no_hash, wallet } => {
                 let wallet = wallet.signer().await?;
                 let sig = if data {
-                    let typed_data: TypedData = if from_file {
-                        // data is a file name, read json from file
-                        foundry_common::fs::read_json_file(message.as_ref())?
-                    } else {
-                        // data is a json string
-                        serde_json::from_str(&message)?
-                    };
+                    let typed_data = SimpleCast::parse_typed_data(&message, from_file)?;
                     wallet.sign_dynamic_typed_data(&typed_data).await?
                 } else if no_hash {
                     wallet.sign_hash(&hex::decode(&message)?[..].try_into()?).await?
@@ -385,8 +393,13 @@ pub async fn run(self) -> Result<()> {
                 let auth = auth.into_signed(signature);
                 sh_println!("{}", hex::encode_prefixed(alloy_rlp::encode(&auth)))?;
             }
-            Self::Verify { message, signature, address } => {
-                let recovered_address = Self::recover_address_from_message(&message, &signature)?;
+            Self::Verify { message, signature, address, data, from_file } => {
+                let recovered_address = if data {
+                    let typed_data = SimpleCast::parse_typed_data(&message, from_file)?;
+                    Self::recover_address_from_typed_data(&typed_data, &signature)?
+                } else {
+                    Self::recover_address_from_message(&message, &signature)?
+                };
                 if address == recovered_address {
                     sh_println!("Validation succeeded. Address {address} signed this message.")?;
                 } else {
@@ -537,6 +550,14 @@ fn recover_address_from_message(message: &str, signature: &Signature) -> Result<
         Ok(signature.recover_address_from_msg(message)?)
     }
 
+    /// Recovers an address from the specified typed data and signature
+    fn recover_address_from_typed_data(
+        typed_data: &TypedData,
+        signature: &Signature,
+    ) -> Result<Address> {
+        Ok(signature.recover_address_from_prehash(&typed_data.eip712_signing_hash()?)?)
+    }
+
     fn hex_str_to_bytes(s: &str) -> Result<Vec<u8>> {
         Ok(match s.strip_prefix("0x") {
             Some(data) => hex::decode(data).wrap_err("Could not decode 0x-prefixed string.")?,
@@ -1,19 +1,28 @@
-use alloy_primitives::{hex, Address};
+use alloy_primitives::{hex, keccak256, Address, B256, U256};
 use alloy_signer::{k256::ecdsa::SigningKey, utils::secret_key_to_address};
 use alloy_signer_local::PrivateKeySigner;
 use clap::Parser;
 use eyre::Result;
-use foundry_common::sh_println;
+use foundry_common::{sh_eprintln, sh_println};
 use itertools::Either;
-use rayon::iter::{self, ParallelIterator};
-use regex::Regex;
+use rayon::iter::{self, IntoParallelIterator, ParallelIterator};
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
 use std::{
     fs,
     path::{Path, PathBuf},
-    time::Instant,
+    time::{Duration, Instant},
 };
 
+// https://etherscan.io/address/0x4e59b44847b379578588920ca78fbf26c0b4956c#code
+const DEPLOYER: &str = "0x4e59b44847b379578588920ca78fbf26c0b4956c";
+
+/// The number of salts tried by a thread before checking whether another thread found a match.
+const SALTS_PER_CHUNK: u64 = 1 << 16;
+
+/// The minimum interval between two progress reports when mining a salt.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
 /// Type alias for the result of [generate_wallet].
 pub type GeneratedWallet = (SigningKey, Address);
 
@@ -31,7 +40,7 @@ pub struct VanityArgs {
     // 2^64-1 is max possible nonce per [eip-2681](https://eips.ethereum.org/EIPS/eip-2681).
     /// Generate a vanity contract address created by the generated keypair with the specified
     /// nonce.
-    #[arg(long)]
+    #[arg(long, conflicts_with = "contract")]
     pub nonce: Option<u64>,
 
     /// Path to save the generated vanity contract address to.
@@ -42,8 +51,68 @@ pub struct VanityArgs {
         long,
         value_hint = clap::ValueHint::FilePath,
         value_name = "PATH",
+        conflicts_with = "contract",
     )]
     pub save_path: Option<PathBuf>,
+
+    /// Mine a CREATE2 salt for a vanity contract address instead of generating a keypair.
+    ///
+    /// Patterns are hex strings, where `X` matches any character, or regexes. They are matched
+    /// against the checksummed address with `--case-sensitive`.
+    #[arg(long)]
+    pub contract: bool,
+
+    /// Address of the CREATE2 deployer.
+    #[arg(long, default_value = DEPLOYER, value_name = "ADDRESS", requires = "contract")]
+    pub deployer: Address,
+
+    /// Init code of the contract to be deployed.
+    #[arg(long, value_name = "HEX", requires = "contract")]
+    pub init_code: Option<String>,
+
+    /// Init code hash of the contract to be deployed.
+    #[arg(long, value_name = "HASH", requires = "contract", conflicts_with = "init_code")]
+    pub init_code_hash: Option<B256>,
+
+    /// Match the letters of the patterns against the case of the checksummed address.
+    #[arg(long, requires = "contract")]
+    pub case_sensitive: bool,
+
+    /// Number of threads to use. Specifying 0 defaults to the number of logical cores.
+    #[arg(long, short = 'j', visible_alias = "jobs", requires = "contract")]
+    pub threads: Option<usize>,
+
+    /// Path of a file to periodically save the progress of the search to.
+    ///
+    /// If the file exists, the search resumes from it. It is removed once a salt is found.
+    #[arg(
+        long,
+        value_hint = clap::ValueHint::FilePath,
+        value_name = "PATH",
+        requires = "contract",
+    )]
+    pub checkpoint: Option<PathBuf>,
+}
+
+/// The parameters of a CREATE2 salt search, which a checkpoint must match to be resumed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct SaltSearch {
+    deployer: Address,
+    init_code_hash: B256,
+    starts_with: Option<String>,
+    ends_with: Option<String>,
+    case_sensitive: bool,
+}
+
+/// The progress of a CREATE2 salt search.
+#[derive(Debug, Serialize, Deserialize)]
+struct Checkpoint {
+    #[serde(flatten)]
+    search: SaltSearch,
+    /// The first salt of the search.
+    start: B256,
+    /// The number of consecutive salts tried from `start`.
+    tried: u64,
 }
 
 /// WalletData contains address and private_key information for a wallet.
@@ -70,7 +139,7 @@ pub fn new(wallet: &PrivateKeySigner) -> Self {
 
 impl VanityArgs {
     pub fn run(self) -> Result<PrivateKeySigner> {
-        let Self { starts_with, ends_with, nonce, save_path } = self;
+        let Self { starts_with, ends_with, nonce, save_path, .. } = self;
 
         let mut left_exact_hex = None;
         let mut left_regex = None;
@@ -160,6 +229,104 @@ macro_rules! find_vanity {
 
         Ok(wallet)
     }
+
+    /// Mines a salt for which the CREATE2 address of the contract matches the patterns, using all
+    /// the available threads. Returns the salt and the contract address.
+    pub fn mine_salt(self) -> Result<(B256, Address)> {
+        let Self {
+            starts_with,
+            ends_with,
+            deployer,
+            init_code,
+            init_code_hash,
+            case_sensitive,
+            threads,
+            checkpoint,
+            ..
+        } = self;
+
+        let init_code_hash = match (init_code_hash, init_code) {
+            (Some(init_code_hash), _) => init_code_hash,
+            (None, Some(init_code)) => keccak256(hex::decode(init_code)?),
+            (None, None) => eyre::bail!("Either --init-code or --init-code-hash is required"),
+        };
+        let matcher = ChecksumMatcher {
+            left: starts_with
+                .as_deref()
+                .map(|p| parse_checksum_pattern(p, true, case_sensitive))
+                .transpose()?,
+            right: ends_with
+                .as_deref()
+                .map(|p| parse_checksum_pattern(p, false, case_sensitive))
+                .transpose()?,
+            case_sensitive,
+        };
+
+        let search =
+            SaltSearch { deployer, init_code_hash, starts_with, ends_with, case_sensitive };
+        let mut state = match checkpoint.as_deref().filter(|path| path.exists()) {
+            Some(path) => {
+                let state: Checkpoint = serde_json::from_str(&fs::read_to_string(path)?)?;
+                if state.search != search {
+                    eyre::bail!("Checkpoint at {} is for a different search", path.display());
+                }
+                sh_println!("Resuming from checkpoint after {} salts...", state.tried)?;
+                state
+            }
+            None => Checkpoint { search, start: B256::from(rand::random::<[u8; 32]>()), tried: 0 },
+        };
+
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(threads.unwrap_or(0)).build()?;
+        let n_threads = pool.current_num_threads();
+        sh_println!("Starting to mine a CREATE2 salt with {n_threads} threads...")?;
+
+        let start = U256::from_be_bytes(state.start.0);
+        let resumed_at = state.tried;
+        let chunks_per_round = n_threads as u64 * 4;
+        let timer = Instant::now();
+        let mut last_report = Instant::now();
+        let (salt, address) = loop {
+            let tried = state.tried;
+            let found = pool.install(|| {
+                (0..chunks_per_round).into_par_iter().find_map_any(|chunk| {
+                    let first = tried + chunk * SALTS_PER_CHUNK;
+                    (first..first + SALTS_PER_CHUNK).find_map(|i| {
+                        let salt = B256::from(start.wrapping_add(U256::from(i)).to_be_bytes());
+                        let address = deployer.create2(salt, init_code_hash);
+                        matcher.is_match(&address).then_some((salt, address))
+                    })
+                })
+            });
+            if let Some(found) = found {
+                break found
+            }
+
+            state.tried += chunks_per_round * SALTS_PER_CHUNK;
+            if let Some(path) = &checkpoint {
+                fs::write(path, serde_json::to_string_pretty(&state)?)?;
+            }
+            if last_report.elapsed() >= PROGRESS_INTERVAL {
+                let rate = (state.tried - resumed_at) as f64 / timer.elapsed().as_secs_f64();
+                sh_eprintln!("Tried {} salts ({rate:.0} salts/s)...", state.tried)?;
+                last_report = Instant::now();
+            }
+        };
+
+        if let Some(path) = &checkpoint {
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+        }
+
+        sh_println!(
+            "Successfully found contract address in {:.3} seconds.\nAddress: {}\nSalt: {salt} ({})",
+            timer.elapsed().as_secs_f64(),
+            address.to_checksum(None),
+            U256::from_be_bytes(salt.0),
+        )?;
+
+        Ok((salt, address))
+    }
 }
 
 /// Saves the specified `wallet` to a 'vanity_addresses.json' file at the given `save_path`.
@@ -326,6 +493,44 @@ fn is_match(&self, addr: &Address) -> bool {
     }
 }
 
+/// Matches start and end regex against the hex of an address, checksummed if case sensitive.
+pub struct ChecksumMatcher {
+    pub left: Option<Regex>,
+    pub right: Option<Regex>,
+    pub case_sensitive: bool,
+}
+
+impl VanityMatcher for ChecksumMatcher {
+    #[inline]
+    fn is_match(&self, addr: &Address) -> bool {
+        let addr = if self.case_sensitive {
+            addr.to_checksum(None).split_off(2)
+        } else {
+            hex::encode(addr)
+        };
+        self.left.as_ref().is_none_or(|re| re.is_match(&addr)) &&
+            self.right.as_ref().is_none_or(|re| re.is_match(&addr))
+    }
+}
+
+/// Parses a pattern matched by [ChecksumMatcher]: a hex string where `X` matches any character,
+/// or a regex.
+fn parse_checksum_pattern(pattern: &str, is_start: bool, case_sensitive: bool) -> Result<Regex> {
+    let pattern = if is_start { pattern.strip_prefix("0x").unwrap_or(pattern) } else { pattern };
+    let pattern = if pattern.chars().all(|c| c.is_ascii_hexdigit() || c == 'X') {
+        if pattern.len() > 40 {
+            return Err(eyre::eyre!("Hex pattern must be less than 40 characters"));
+        }
+        pattern.replace('X', ".")
+    } else {
+        pattern.to_string()
+    };
+    let (prefix, suffix) = if is_start { ("^", "") } else { ("", "$") };
+    Ok(RegexBuilder::new(&format!("{prefix}{pattern}{suffix}"))
+        .case_insensitive(!case_sensitive)
+        .build()?)
+}
+
 fn parse_pattern(pattern: &str, is_start: bool) -> Result<Either<Vec<u8>, Regex>> {
     if let Ok(decoded) = hex::decode(pattern) {
         if decoded.len() > 20 {
@@ -385,4 +590,70 @@ fn save_path() {
         let wallets: Wallets = serde_json::from_str(&s).unwrap();
         assert!(!wallets.wallets.is_empty());
     }
+
+    #[test]
+    fn mine_create2_salt() {
+        let args = VanityArgs::parse_from([
+            "foundry-cli",
+            "--contract",
+            "--starts-with",
+            "0X0",
+            "--init-code",
+            "0x00",
+            "-j",
+            "2",
+        ]);
+        let deployer = args.deployer;
+        let (salt, address) = args.mine_salt().unwrap();
+        assert_eq!(address, deployer.create2(salt, keccak256([0])));
+        assert!(format!("{address:x}").starts_with('0'));
+        assert_eq!(format!("{address:x}").as_bytes()[2], b'0');
+    }
+
+    #[test]
+    fn create2_checkpoint() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("checkpoint.json");
+        let args = |starts_with: &str| {
+            VanityArgs::parse_from([
+                "foundry-cli",
+                "--contract",
+                "--starts-with",
+                starts_with,
+                "--init-code-hash",
+                &B256::ZERO.to_string(),
+                "--checkpoint",
+                path.to_str().unwrap(),
+            ])
+        };
+        let search = SaltSearch {
+            deployer: DEPLOYER.parse().unwrap(),
+            init_code_hash: B256::ZERO,
+            starts_with: Some("00".to_string()),
+            ends_with: None,
+            case_sensitive: false,
+        };
+        let checkpoint = Checkpoint { search, start: B256::ZERO, tried: 1 << 20 };
+        fs::write(&path, serde_json::to_string(&checkpoint).unwrap()).unwrap();
+
+        assert!(args("ff").mine_salt().is_err());
+        let (salt, _) = args("00").mine_salt().unwrap();
+        assert!(U256::from_be_bytes(salt.0) >= U256::from(1 << 20));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn match_checksum_patterns() {
+        let addr: Address = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".parse().unwrap();
+        let matches = |left: &str, right: &str, case_sensitive| {
+            let left = Some(parse_checksum_pattern(left, true, case_sensitive).unwrap());
+            let right = Some(parse_checksum_pattern(right, false, case_sensitive).unwrap());
+            ChecksumMatcher { left, right, case_sensitive }.is_match(&addr)
+        };
+        assert!(matches("5aA", "eAed", true));
+        assert!(!matches("5AA", "eAed", true));
+        assert!(matches("5AA", "EAED", false));
+        assert!(matches("0x5XXeb", "[a-f]ed", false));
+        assert!(!matches("5aX", "[0-9]ed", false));
+    }
 }
@@ -2,13 +2,13 @@
 extern crate tracing;
 
 use alloy_dyn_abi::{DynSolValue, ErrorExt, EventExt};
-use alloy_primitives::{eip191_hash_message, hex, keccak256, Address, B256};
+use alloy_primitives::{eip191_hash_message, hex, keccak256, Address, TxHash, B256};
 use alloy_provider::Provider;
 use alloy_rpc_types::{BlockId, BlockNumberOrTag::Latest};
 use cast::{Cast, SimpleCast};
 use clap::{CommandFactory, Parser};
 use clap_complete::generate;
-use eyre::Result;
+use eyre::{Result, WrapErr};
 use foundry_cli::{handler, utils, utils::LoadConfig};
 use foundry_common::{
     abi::{get_error, get_event},
@@ -485,22 +485,31 @@ async fn main_args(args: CastArgs) -> Result<()> {
                 sh_println!("{}", serde_json::json!(receipt))?;
             }
         }
-        CastSubcommand::Receipt { tx_hash, field, cast_async, confirmations, rpc } => {
-            let config = rpc.load_config()?;
-            let provider = utils::get_provider(&config)?;
-            sh_println!(
-                "{}",
-                Cast::new(provider)
-                    .receipt(tx_hash, field, confirmations, None, cast_async)
-                    .await?
-            )?
-        }
+        CastSubcommand::Receipt(cmd) => cmd.run().await?,
         CastSubcommand::Run(cmd) => cmd.run().await?,
         CastSubcommand::SendTx(cmd) => cmd.run().await?,
-        CastSubcommand::Tx { tx_hash, field, raw, rpc } => {
+        CastSubcommand::Tx { command, tx_hash, field, raw, wait, confirmations, timeout, rpc } => {
+            let tx_hash = match (command, tx_hash) {
+                (Some(command), _) => return command.run().await,
+                (None, Some(tx_hash)) => tx_hash,
+                (None, None) => eyre::bail!("A transaction hash or a subcommand is required"),
+            };
             let config = rpc.load_config()?;
             let provider = utils::get_provider(&config)?;
 
+            if wait {
+                let tx_hash: TxHash = tx_hash.parse().wrap_err("invalid tx hash")?;
+                let timeout = timeout.unwrap_or(config.transaction_timeout);
+                return cmd::send::watch_tx(
+                    &provider,
+                    tx_hash,
+                    confirmations,
+                    timeout,
+                    &config.transaction_watch,
+                )
+                .await;
+            }
+
             // Can use either --raw or specify raw as a field
             let raw = raw || field.as_ref().is_some_and(|f| f == "raw");
 
@@ -616,6 +625,25 @@ async fn main_args(args: CastArgs) -> Result<()> {
             let message = stdin::unwrap_line(message)?;
             sh_println!("{}", eip191_hash_message(message))?
         }
+        CastSubcommand::HashTypedData { data, from_file } => {
+            let data = stdin::unwrap_line(data)?;
+            let typed_data = SimpleCast::parse_typed_data(&data, from_file)?;
+            let domain_separator = typed_data.domain().separator();
+            let struct_hash = typed_data.hash_struct()?;
+            let digest = typed_data.eip712_signing_hash()?;
+            if shell::is_json() {
+                let hashes = serde_json::json!({
+                    "domainSeparator": domain_separator,
+                    "structHash": struct_hash,
+                    "digest": digest,
+                });
+                sh_println!("{}", serde_json::to_string_pretty(&hashes)?)?;
+            } else {
+                sh_println!("Domain separator: {domain_separator}")?;
+                sh_println!("Struct hash:      {struct_hash}")?;
+                sh_println!("Digest:           {digest}")?;
+            }
+        }
         CastSubcommand::SigEvent { event_string } => {
             let event_string = stdin::unwrap_line(event_string)?;
             let parsed_event = get_event(&event_string)?;
@@ -636,10 +664,23 @@ async fn main_args(args: CastArgs) -> Result<()> {
             explorer_url,
             etherscan,
             flatten,
+            recompile,
         } => {
             let config = etherscan.load_config()?;
             let chain = config.chain.unwrap_or_default();
             let api_key = config.get_etherscan_api_key(Some(chain));
+            if recompile {
+                let artifacts = SimpleCast::etherscan_source_recompile(
+                    chain,
+                    address,
+                    api_key,
+                    explorer_api_url,
+                    explorer_url,
+                )
+                .await?;
+                sh_println!("Artifacts written to {}", artifacts.display())?;
+                return Ok(());
+            }
             match (directory, flatten) {
                 (Some(dir), false) => {
                     SimpleCast::expand_etherscan_source_to_directory(
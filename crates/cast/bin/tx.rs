@@ -16,7 +16,11 @@
     opts::{CliAuthorizationList, TransactionOpts},
     utils::{self, parse_function_args},
 };
-use foundry_common::{ens::NameOrAddress, fmt::format_tokens};
+use foundry_common::{
+    abi::{encode_function_args, get_func},
+    ens::NameOrAddress,
+    fmt::format_tokens,
+};
 use foundry_config::{Chain, Config};
 use foundry_wallets::{WalletOpts, WalletSigner};
 use itertools::Itertools;
@@ -108,6 +112,62 @@ pub fn validate_from_address(
     Ok(())
 }
 
+/// Creates a [TransactionRequest] with the gas, fee, value and nonce fields set from the provided
+/// [TransactionOpts].
+///
+/// Fields which are not specified are left empty, this never touches the network.
+pub fn tx_from_opts(
+    tx_opts: &TransactionOpts,
+    legacy: bool,
+) -> WithOtherFields<TransactionRequest> {
+    let mut tx = WithOtherFields::<TransactionRequest>::default();
+
+    if let Some(gas_limit) = tx_opts.gas_limit {
+        tx.set_gas_limit(gas_limit.to());
+    }
+
+    if let Some(value) = tx_opts.value {
+        tx.set_value(value);
+    }
+
+    if let Some(gas_price) = tx_opts.gas_price {
+        if legacy {
+            tx.set_gas_price(gas_price.to());
+        } else {
+            tx.set_max_fee_per_gas(gas_price.to());
+        }
+    }
+
+    if !legacy {
+        if let Some(priority_fee) = tx_opts.priority_gas_price {
+            tx.set_max_priority_fee_per_gas(priority_fee.to());
+        }
+    }
+
+    if let Some(max_blob_fee) = tx_opts.blob_gas_price {
+        tx.set_max_fee_per_blob_gas(max_blob_fee.to())
+    }
+
+    if let Some(nonce) = tx_opts.nonce {
+        tx.set_nonce(nonce.to());
+    }
+
+    tx
+}
+
+/// Encodes the blob data into a sidecar and sets it, along with the blob versioned hashes, on the
+/// transaction.
+pub fn set_blob_data(tx: &mut WithOtherFields<TransactionRequest>, blob_data: &[u8]) -> Result<()> {
+    let mut coder = SidecarBuilder::<SimpleCoder>::default();
+    coder.ingest(blob_data);
+    let sidecar = coder.build()?;
+
+    tx.set_blob_sidecar(sidecar);
+    tx.populate_blob_hashes();
+
+    Ok(())
+}
+
 /// Initial state.
 #[derive(Debug)]
 pub struct InitState;
@@ -132,7 +192,8 @@ pub struct InputState {
 /// [TxKindState] -> [InputState].
 #[derive(Debug)]
 pub struct CastTxBuilder<P, S> {
-    provider: P,
+    /// The provider filling the missing fields, `None` when building the transaction offline.
+    provider: Option<P>,
     tx: WithOtherFields<TransactionRequest>,
     legacy: bool,
     blob: bool,
@@ -147,44 +208,13 @@ impl<P: Provider<AnyNetwork>> CastTxBuilder<P, InitState> {
     /// Creates a new instance of [CastTxBuilder] filling transaction with fields present in
     /// provided [TransactionOpts].
     pub async fn new(provider: P, tx_opts: TransactionOpts, config: &Config) -> Result<Self> {
-        let mut tx = WithOtherFields::<TransactionRequest>::default();
-
         let chain = utils::get_chain(config.chain, &provider).await?;
         let etherscan_api_key = config.get_etherscan_api_key(Some(chain));
         let legacy = tx_opts.legacy || chain.is_legacy();
-
-        if let Some(gas_limit) = tx_opts.gas_limit {
-            tx.set_gas_limit(gas_limit.to());
-        }
-
-        if let Some(value) = tx_opts.value {
-            tx.set_value(value);
-        }
-
-        if let Some(gas_price) = tx_opts.gas_price {
-            if legacy {
-                tx.set_gas_price(gas_price.to());
-            } else {
-                tx.set_max_fee_per_gas(gas_price.to());
-            }
-        }
-
-        if !legacy {
-            if let Some(priority_fee) = tx_opts.priority_gas_price {
-                tx.set_max_priority_fee_per_gas(priority_fee.to());
-            }
-        }
-
-        if let Some(max_blob_fee) = tx_opts.blob_gas_price {
-            tx.set_max_fee_per_blob_gas(max_blob_fee.to())
-        }
-
-        if let Some(nonce) = tx_opts.nonce {
-            tx.set_nonce(nonce.to());
-        }
+        let tx = tx_from_opts(&tx_opts, legacy);
 
         Ok(Self {
-            provider,
+            provider: Some(provider),
             tx,
             legacy,
             blob: tx_opts.blob,
@@ -196,9 +226,38 @@ pub async fn new(provider: P, tx_opts: TransactionOpts, config: &Config) -> Resu
         })
     }
 
+    /// Creates a new instance of [CastTxBuilder] which never connects to a node, for the given
+    /// chain.
+    ///
+    /// All the fields that are usually fetched from the node must be provided in the
+    /// [TransactionOpts].
+    pub fn offline(tx_opts: TransactionOpts, chain: Chain) -> Self {
+        let legacy = tx_opts.legacy || chain.is_legacy();
+        let tx = tx_from_opts(&tx_opts, legacy);
+
+        Self {
+            provider: None,
+            tx,
+            legacy,
+            blob: tx_opts.blob,
+            chain,
+            etherscan_api_key: None,
+            auth: tx_opts.auth,
+            access_list: tx_opts.access_list,
+            state: InitState,
+        }
+    }
+
     /// Sets [TxKind] for this builder and changes state to [TxKindState].
     pub async fn with_to(self, to: Option<NameOrAddress>) -> Result<CastTxBuilder<P, ToState>> {
-        let to = if let Some(to) = to { Some(to.resolve(&self.provider).await?) } else { None };
+        let to = match (to, &self.provider) {
+            (Some(to), Some(provider)) => Some(to.resolve(provider).await?),
+            (Some(NameOrAddress::Address(to)), None) => Some(to),
+            (Some(NameOrAddress::Name(name)), None) => {
+                eyre::bail!("Offline transactions need an address, `{name}` can't be resolved")
+            }
+            (None, _) => None,
+        };
         Ok(CastTxBuilder {
             provider: self.provider,
             tx: self.tx,
@@ -223,18 +282,32 @@ pub async fn with_code_sig_and_args(
         sig: Option<String>,
         args: Vec<String>,
     ) -> Result<CastTxBuilder<P, InputState>> {
-        let (mut args, func) = if let Some(sig) = sig {
-            parse_function_args(
-                &sig,
-                args,
-                self.state.to,
-                self.chain,
-                &self.provider,
-                self.etherscan_api_key.as_deref(),
-            )
-            .await?
-        } else {
-            (Vec::new(), None)
+        let (mut args, func) = match (sig, &self.provider) {
+            (Some(sig), Some(provider)) => {
+                parse_function_args(
+                    &sig,
+                    args,
+                    self.state.to,
+                    self.chain,
+                    provider,
+                    self.etherscan_api_key.as_deref(),
+                )
+                .await?
+            }
+            (Some(sig), None) => {
+                if let Ok(data) = hex::decode(&sig) {
+                    (data, None)
+                } else if sig.contains('(') {
+                    let func = get_func(&sig)?;
+                    (encode_function_args(&func, &args)?, Some(func))
+                } else {
+                    eyre::bail!(
+                        "Offline transactions need a full function signature, e.g. \
+                         `transfer(address,uint256)`, or raw calldata"
+                    )
+                }
+            }
+            (None, _) => (Vec::new(), None),
         };
 
         let input = if let Some(code) = &code {
@@ -276,7 +349,7 @@ pub async fn build(
         self,
         sender: impl Into<SenderKind<'_>>,
     ) -> Result<(WithOtherFields<TransactionRequest>, Option<Function>)> {
-        self._build(sender, true).await
+        self._build(Some(sender.into()), true).await
     }
 
     /// Builds [TransactionRequest] without filling missing fields. Used for read-only calls such as
@@ -285,42 +358,66 @@ pub async fn build_raw(
         self,
         sender: impl Into<SenderKind<'_>>,
     ) -> Result<(WithOtherFields<TransactionRequest>, Option<Function>)> {
-        self._build(sender, false).await
+        self._build(Some(sender.into()), false).await
+    }
+
+    /// Builds a complete [TransactionRequest] from the provided fields only, for builders created
+    /// with [CastTxBuilder::offline]. The sender is optional, it is set when signing.
+    pub async fn build_offline(
+        self,
+        from: Option<Address>,
+    ) -> Result<WithOtherFields<TransactionRequest>> {
+        let (tx, _) = self._build(from.map(SenderKind::Address), true).await?;
+        Ok(tx)
     }
 
     async fn _build(
         mut self,
-        sender: impl Into<SenderKind<'_>>,
+        sender: Option<SenderKind<'_>>,
         fill: bool,
     ) -> Result<(WithOtherFields<TransactionRequest>, Option<Function>)> {
-        let sender = sender.into();
-        let from = sender.address();
-
         self.tx.set_kind(self.state.kind);
 
         // we set both fields to the same value because some nodes only accept the legacy `data` field: <https://github.com/foundry-rs/foundry/issues/7764#issuecomment-2210453249>
         let input = Bytes::copy_from_slice(&self.state.input);
         self.tx.input = TransactionInput { input: Some(input.clone()), data: Some(input) };
 
-        self.tx.set_from(from);
+        if let Some(sender) = &sender {
+            self.tx.set_from(sender.address());
+        }
         self.tx.set_chain_id(self.chain.id());
 
+        let Some(provider) = self.provider.take() else {
+            self.resolve_auth(sender.as_ref(), None).await?;
+            if let Some(access_list) = self.access_list.take() {
+                let Some(access_list) = access_list else {
+                    eyre::bail!("Offline transactions need an explicit access list");
+                };
+                self.tx.set_access_list(access_list);
+            }
+            if let Err(missing) = self.tx.complete_preferred() {
+                eyre::bail!("Missing transaction fields: {}", missing.join(", "));
+            }
+            return Ok((self.tx, self.state.func));
+        };
+        let from = sender.as_ref().map(SenderKind::address).unwrap_or_default();
+
         let tx_nonce = if let Some(nonce) = self.tx.nonce {
             nonce
         } else {
-            let nonce = self.provider.get_transaction_count(from).await?;
+            let nonce = provider.get_transaction_count(from).await?;
             if fill {
                 self.tx.nonce = Some(nonce);
             }
             nonce
         };
 
-        self.resolve_auth(sender, tx_nonce).await?;
+        self.resolve_auth(sender.as_ref(), Some(tx_nonce)).await?;
 
         if let Some(access_list) = match self.access_list.take() {
             None => None,
             // --access-list provided with no value, call the provider to create it
-            Some(None) => Some(self.provider.create_access_list(&self.tx).await?.access_list),
+            Some(None) => Some(provider.create_access_list(&self.tx).await?.access_list),
             // Access list provided as a string, attempt to parse it
             Some(Some(access_list)) => Some(access_list),
         } {
@@ -332,17 +429,17 @@ async fn _build(
         }
 
         if self.legacy && self.tx.gas_price.is_none() {
-            self.tx.gas_price = Some(self.provider.get_gas_price().await?);
+            self.tx.gas_price = Some(provider.get_gas_price().await?);
         }
 
         if self.blob && self.tx.max_fee_per_blob_gas.is_none() {
-            self.tx.max_fee_per_blob_gas = Some(self.provider.get_blob_base_fee().await?)
+            self.tx.max_fee_per_blob_gas = Some(provider.get_blob_base_fee().await?)
         }
 
         if !self.legacy &&
             (self.tx.max_fee_per_gas.is_none() || self.tx.max_priority_fee_per_gas.is_none())
         {
-            let estimate = self.provider.estimate_eip1559_fees(None).await?;
+            let estimate = provider.estimate_eip1559_fees(None).await?;
 
             if !self.legacy {
                 if self.tx.max_fee_per_gas.is_none() {
@@ -356,15 +453,15 @@ async fn _build(
         }
 
         if self.tx.gas.is_none() {
-            self.estimate_gas().await?;
+            self.estimate_gas(&provider).await?;
         }
 
         Ok((self.tx, self.state.func))
     }
 
     /// Estimate tx gas from provider call. Tries to decode custom error if execution reverted.
-    async fn estimate_gas(&mut self) -> Result<()> {
-        match self.provider.estimate_gas(&self.tx).await {
+    async fn estimate_gas(&mut self, provider: &P) -> Result<()> {
+        match provider.estimate_gas(&self.tx).await {
             Ok(estimated) => {
                 self.tx.gas = Some(estimated);
                 Ok(())
@@ -387,18 +484,28 @@ async fn estimate_gas(&mut self) -> Result<()> {
     }
 
     /// Parses the passed --auth value and sets the authorization list on the transaction.
-    async fn resolve_auth(&mut self, sender: SenderKind<'_>, tx_nonce: u64) -> Result<()> {
+    ///
+    /// The nonce of the transaction is only known when building online, offline transactions need
+    /// a signed authorization.
+    async fn resolve_auth(
+        &mut self,
+        sender: Option<&SenderKind<'_>>,
+        tx_nonce: Option<u64>,
+    ) -> Result<()> {
         let Some(auth) = self.auth.take() else { return Ok(()) };
 
         let auth = match auth {
             CliAuthorizationList::Address(address) => {
+                let Some(tx_nonce) = tx_nonce else {
+                    eyre::bail!("Offline transactions need a signed authorization");
+                };
                 let auth = Authorization {
                     chain_id: U256::from(self.chain.id()),
                     nonce: tx_nonce + 1,
                     address,
                 };
 
-                let Some(signer) = sender.as_signer() else {
+                let Some(signer) = sender.and_then(SenderKind::as_signer) else {
                     eyre::bail!("No signer available to sign authorization");
                 };
                 let signature = signer.sign_hash(&auth.signature_hash()).await?;
@@ -420,14 +527,7 @@ impl<P, S> CastTxBuilder<P, S>
 {
     pub fn with_blob_data(mut self, blob_data: Option<Vec<u8>>) -> Result<Self> {
         let Some(blob_data) = blob_data else { return Ok(self) };
-
-        let mut coder = SidecarBuilder::<SimpleCoder>::default();
-        coder.ingest(&blob_data);
-        let sidecar = coder.build()?;
-
-        self.tx.set_blob_sidecar(sidecar);
-        self.tx.populate_blob_hashes();
-
+        set_blob_data(&mut self.tx, &blob_data)?;
         Ok(self)
     }
 }
@@ -0,0 +1,315 @@
+//! Batches of read-only calls, aggregated through Multicall3 when it is deployed.
+
+use alloy_dyn_abi::FunctionExt;
+use alloy_json_abi::Function;
+use alloy_network::{AnyNetwork, TransactionBuilder};
+use alloy_primitives::{address, Address, Bytes};
+use alloy_provider::Provider;
+use alloy_rpc_types::{BlockId, TransactionRequest};
+use alloy_serde::WithOtherFields;
+use alloy_sol_types::{sol, SolCall};
+use eyre::{Context, Result};
+use foundry_common::{
+    abi::{encode_function_args, get_func},
+    fmt::format_token_raw,
+};
+use foundry_evm::decode::RevertDecoder;
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+
+/// The address of the Multicall3 contract, deployed at the same address on most chains.
+pub const MULTICALL3_ADDRESS: Address = address!("cA11bde05977b3631167028862bE2a173976CA11");
+
+/// The maximum number of calls aggregated in a single Multicall3 call.
+const MULTICALL_CHUNK_SIZE: usize = 200;
+
+sol! {
+    struct Call3 {
+        address target;
+        bool allowFailure;
+        bytes callData;
+    }
+
+    struct Call3Result {
+        bool success;
+        bytes returnData;
+    }
+
+    function aggregate3(Call3[] calldata calls)
+        external
+        payable
+        returns (Call3Result[] memory returnData);
+}
+
+/// A call of a batch, as read from the batch file.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchCall {
+    /// The address to call.
+    pub to: Address,
+    /// The signature of the function to call, including its return types to decode the output,
+    /// e.g. `balanceOf(address)(uint256)`.
+    #[serde(default)]
+    pub sig: Option<String>,
+    /// The arguments of the function to call.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Raw calldata, instead of `sig` and `args`.
+    #[serde(default)]
+    pub data: Option<Bytes>,
+}
+
+impl BatchCall {
+    /// Returns the function to decode the output with, if any, and the calldata.
+    fn encode(&self) -> Result<(Option<Function>, Bytes)> {
+        match (&self.sig, &self.data) {
+            (Some(_), Some(_)) => eyre::bail!("call to {} has both `sig` and `data`", self.to),
+            (Some(sig), None) => {
+                let func = get_func(sig)?;
+                let input = encode_function_args(&func, &self.args)
+                    .wrap_err_with(|| format!("failed to encode call to {}", self.to))?;
+                Ok((Some(func), input.into()))
+            }
+            (None, data) => {
+                if !self.args.is_empty() {
+                    eyre::bail!("call to {} has `args` but no `sig`", self.to);
+                }
+                Ok((None, data.clone().unwrap_or_default()))
+            }
+        }
+    }
+}
+
+/// The result of a call of a batch.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct BatchCallResult {
+    pub to: Address,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<BatchCallOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The output of a successful call.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum BatchCallOutput {
+    /// The output decoded with the return types of the function signature.
+    Decoded(Vec<String>),
+    /// The raw output, if the function signature has no return types.
+    Raw(Bytes),
+}
+
+impl BatchCallResult {
+    fn new(to: Address, func: Option<&Function>, output: Result<Bytes, String>) -> Self {
+        let (result, error) = match output {
+            Ok(output) => match func.filter(|func| !func.outputs.is_empty()) {
+                Some(func) => match func.abi_decode_output(&output, false) {
+                    Ok(decoded) => {
+                        let decoded = decoded.iter().map(format_token_raw).collect();
+                        (Some(BatchCallOutput::Decoded(decoded)), None)
+                    }
+                    Err(err) => (None, Some(format!("could not decode output: {err}"))),
+                },
+                None => (Some(BatchCallOutput::Raw(output)), None),
+            },
+            Err(err) => (None, Some(err)),
+        };
+        Self { to, success: error.is_none(), result, error }
+    }
+}
+
+/// Executes batches of read-only calls.
+///
+/// The calls are aggregated through [Multicall3](MULTICALL3_ADDRESS) if it is deployed at the
+/// queried block, and sent as concurrent `eth_call`s otherwise, or if the aggregated call fails.
+/// Note that aggregated calls have Multicall3 as `msg.sender`.
+pub struct BatchCaller<P> {
+    provider: P,
+    block: BlockId,
+}
+
+impl<P: Provider<AnyNetwork>> BatchCaller<P> {
+    pub fn new(provider: P, block: Option<BlockId>) -> Self {
+        Self { provider, block: block.unwrap_or_default() }
+    }
+
+    /// Executes the calls, returning their results in order.
+    ///
+    /// Reverted calls don't fail the batch, they are reported in their result instead.
+    pub async fn call(&self, calls: &[BatchCall]) -> Result<Vec<BatchCallResult>> {
+        let encoded = calls.iter().map(BatchCall::encode).collect::<Result<Vec<_>>>()?;
+
+        let code = self.provider.get_code_at(MULTICALL3_ADDRESS).block_id(self.block).await?;
+        let outputs = if code.is_empty() {
+            self.call_each(calls, &encoded).await
+        } else {
+            self.aggregate(calls, &encoded).await
+        };
+
+        Ok(calls
+            .iter()
+            .zip(encoded)
+            .zip(outputs)
+            .map(|((call, (func, _)), output)| BatchCallResult::new(call.to, func.as_ref(), output))
+            .collect())
+    }
+
+    /// Aggregates the calls through Multicall3 in chunks, allowing any of them to fail.
+    ///
+    /// The calls of a chunk are sent separately if the aggregated call fails, e.g. because it runs
+    /// out of gas.
+    async fn aggregate(
+        &self,
+        calls: &[BatchCall],
+        encoded: &[(Option<Function>, Bytes)],
+    ) -> Vec<Result<Bytes, String>> {
+        let mut outputs = Vec::with_capacity(calls.len());
+        let chunks = calls.chunks(MULTICALL_CHUNK_SIZE).zip(encoded.chunks(MULTICALL_CHUNK_SIZE));
+        for (calls, encoded) in chunks {
+            match self.aggregate_chunk(calls, encoded).await {
+                Ok(chunk) => outputs.extend(chunk),
+                Err(err) => {
+                    tracing::debug!(%err, "Multicall3 aggregation failed, sending the calls separately");
+                    outputs.extend(self.call_each(calls, encoded).await);
+                }
+            }
+        }
+        outputs
+    }
+
+    /// Aggregates a chunk of calls in a single Multicall3 call.
+    async fn aggregate_chunk(
+        &self,
+        calls: &[BatchCall],
+        encoded: &[(Option<Function>, Bytes)],
+    ) -> Result<Vec<Result<Bytes, String>>> {
+        let call3s = calls
+            .iter()
+            .zip(encoded)
+            .map(|(call, (_, input))| Call3 {
+                target: call.to,
+                allowFailure: true,
+                callData: input.clone(),
+            })
+            .collect();
+        let req = WithOtherFields::new(TransactionRequest::default())
+            .with_to(MULTICALL3_ADDRESS)
+            .with_input(aggregate3Call { calls: call3s }.abi_encode());
+        let res = self.provider.call(&req).block(self.block).await?;
+        let results = aggregate3Call::abi_decode_returns(&res, false)
+            .wrap_err("could not decode Multicall3 output")?
+            .returnData;
+        eyre::ensure!(
+            results.len() == calls.len(),
+            "Multicall3 returned {} results for {} calls",
+            results.len(),
+            calls.len()
+        );
+        Ok(results
+            .into_iter()
+            .map(|result| {
+                if result.success {
+                    Ok(result.returnData)
+                } else {
+                    Err(RevertDecoder::new().decode(&result.returnData, None))
+                }
+            })
+            .collect())
+    }
+
+    /// Sends each call as a separate `eth_call`, concurrently.
+    async fn call_each(
+        &self,
+        calls: &[BatchCall],
+        encoded: &[(Option<Function>, Bytes)],
+    ) -> Vec<Result<Bytes, String>> {
+        join_all(calls.iter().zip(encoded).map(|(call, (_, input))| async move {
+            let req = WithOtherFields::new(TransactionRequest::default())
+                .with_to(call.to)
+                .with_input(input.clone());
+            self.provider.call(&req).block(self.block).await.map_err(|err| err.to_string())
+        }))
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{hex, U256};
+    use alloy_sol_types::SolValue;
+
+    #[test]
+    fn parse_batch_calls() {
+        let calls: Vec<BatchCall> = serde_json::from_str(
+            r#"[
+                {
+                    "to": "0x6B175474E89094C44Da98b954EedeAC495271d0F",
+                    "sig": "balanceOf(address)(uint256)",
+                    "args": ["0x70997970C51812dc3A010C7d01b50e0d17dc79C8"]
+                },
+                { "to": "0x6B175474E89094C44Da98b954EedeAC495271d0F", "data": "0x18160ddd" }
+            ]"#,
+        )
+        .unwrap();
+
+        let (func, input) = calls[0].encode().unwrap();
+        assert_eq!(func.unwrap().name, "balanceOf");
+        assert_eq!(
+            input[..],
+            hex!("70a0823100000000000000000000000070997970c51812dc3a010c7d01b50e0d17dc79c8")
+        );
+
+        let (func, input) = calls[1].encode().unwrap();
+        assert!(func.is_none());
+        assert_eq!(input[..], hex!("18160ddd"));
+    }
+
+    #[test]
+    fn reject_invalid_batch_calls() {
+        let both: BatchCall = serde_json::from_str(
+            r#"{
+                "to": "0x6B175474E89094C44Da98b954EedeAC495271d0F",
+                "sig": "totalSupply()",
+                "data": "0x18160ddd"
+            }"#,
+        )
+        .unwrap();
+        assert!(both.encode().is_err());
+
+        let args_only: BatchCall = serde_json::from_str(
+            r#"{ "to": "0x6B175474E89094C44Da98b954EedeAC495271d0F", "args": ["1"] }"#,
+        )
+        .unwrap();
+        assert!(args_only.encode().is_err());
+    }
+
+    #[test]
+    fn decode_batch_call_results() {
+        let func = get_func("balanceOf(address)(uint256)").unwrap();
+        let output = Bytes::from(U256::from(42).abi_encode());
+
+        let result = BatchCallResult::new(Address::ZERO, Some(&func), Ok(output.clone()));
+        assert_eq!(result.result, Some(BatchCallOutput::Decoded(vec!["42".to_string()])));
+        assert!(result.success);
+
+        let result = BatchCallResult::new(Address::ZERO, None, Ok(output.clone()));
+        assert_eq!(result.result, Some(BatchCallOutput::Raw(output)));
+
+        let result = BatchCallResult::new(Address::ZERO, Some(&func), Ok(Bytes::new()));
+        assert!(!result.success);
+        assert!(result.error.unwrap().starts_with("could not decode output"));
+
+        let result = BatchCallResult::new(Address::ZERO, Some(&func), Err("reverted".to_string()));
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            serde_json::json!({
+                "to": "0x0000000000000000000000000000000000000000",
+                "success": false,
+                "error": "reverted",
+            })
+        );
+    }
+}
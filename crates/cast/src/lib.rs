@@ -3,7 +3,7 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 use alloy_consensus::TxEnvelope;
-use alloy_dyn_abi::{DynSolType, DynSolValue, FunctionExt};
+use alloy_dyn_abi::{DynSolType, DynSolValue, FunctionExt, TypedData};
 use alloy_json_abi::Function;
 use alloy_network::AnyNetwork;
 use alloy_primitives::{
@@ -25,12 +25,12 @@
 use foundry_block_explorers::Client;
 use foundry_common::{
     abi::{encode_function_args, get_func},
-    compile::etherscan_project,
+    compile::{cached_etherscan_project, etherscan_project, ProjectCompiler},
     fmt::*,
     fs, get_pretty_tx_receipt_attr, shell, TransactionReceiptWithRevertReason,
 };
 use foundry_compilers::flatten::Flattener;
-use foundry_config::Chain;
+use foundry_config::{Chain, Config};
 use futures::{future::Either, FutureExt, StreamExt};
 use rayon::prelude::*;
 use revm::primitives::Eof;
@@ -38,7 +38,7 @@
     borrow::Cow,
     fmt::Write,
     io,
-    path::PathBuf,
+    path::{Path, PathBuf},
     str::FromStr,
     sync::atomic::{AtomicBool, Ordering},
     time::Duration,
@@ -50,8 +50,10 @@
 pub use foundry_evm::*;
 
 pub mod base;
+pub mod batch;
 pub mod errors;
 mod rlp_converter;
+pub mod watch;
 
 use rlp_converter::Item;
 
@@ -786,6 +788,29 @@ pub async fn receipt(
         timeout: Option<u64>,
         cast_async: bool,
     ) -> Result<String> {
+        let receipt = self.get_receipt(tx_hash, confs, timeout, cast_async).await?;
+
+        Ok(if let Some(ref field) = field {
+            get_pretty_tx_receipt_attr(&receipt, field)
+                .ok_or_else(|| eyre::eyre!("invalid receipt field: {}", field))?
+        } else if shell::is_json() {
+            // to_value first to sort json object keys
+            serde_json::to_value(&receipt)?.to_string()
+        } else {
+            receipt.pretty()
+        })
+    }
+
+    /// Fetches the receipt of a transaction, with the revert reason if it failed.
+    ///
+    /// Waits for the transaction to be mined and confirmed, unless `cast_async` is set.
+    pub async fn get_receipt(
+        &self,
+        tx_hash: String,
+        confs: u64,
+        timeout: Option<u64>,
+        cast_async: bool,
+    ) -> Result<TransactionReceiptWithRevertReason> {
         let tx_hash = TxHash::from_str(&tx_hash).wrap_err("invalid tx hash")?;
 
         let mut receipt: TransactionReceiptWithRevertReason =
@@ -810,15 +835,7 @@ pub async fn receipt(
         // Allow to fail silently
         let _ = receipt.update_revert_reason(&self.provider).await;
 
-        Ok(if let Some(ref field) = field {
-            get_pretty_tx_receipt_attr(&receipt, field)
-                .ok_or_else(|| eyre::eyre!("invalid receipt field: {}", field))?
-        } else if shell::is_json() {
-            // to_value first to sort json object keys
-            serde_json::to_value(&receipt)?.to_string()
-        } else {
-            receipt.pretty()
-        })
+        Ok(receipt)
     }
 
     /// Perform a raw JSON-RPC request
@@ -1867,6 +1884,20 @@ pub fn keccak(data: &str) -> Result<String> {
         Ok(hash.to_string())
     }
 
+    /// Parses EIP-712 typed data from a JSON string, or from a JSON file if `from_file` is set.
+    ///
+    /// The message is checked against the declared types, e.g. for missing fields, undefined
+    /// types or values that don't fit their type.
+    pub fn parse_typed_data(data: &str, from_file: bool) -> Result<TypedData> {
+        let typed_data: TypedData = if from_file {
+            foundry_common::fs::read_json_file(Path::new(data))?
+        } else {
+            serde_json::from_str(data).wrap_err("invalid typed data JSON")?
+        };
+        typed_data.coerce().wrap_err("typed data does not match its declared types")?;
+        Ok(typed_data)
+    }
+
     /// Performs the left shift operation (<<) on a number
     ///
     /// # Example
@@ -2029,6 +2060,40 @@ pub async fn etherscan_source_flatten(
         Ok(())
     }
 
+    /// Fetches the source code of verified contracts from etherscan and compiles it with the
+    /// verification settings, keeping the sources and the artifacts in
+    /// `~/.foundry/cache/etherscan/<chain>/sources/<address>`.
+    ///
+    /// The compiled contract is then reused by commands that need its artifacts, such as the
+    /// debugger and `cast storage`.
+    ///
+    /// Returns the directory of the artifacts.
+    pub async fn etherscan_source_recompile(
+        chain: Chain,
+        contract_address: String,
+        etherscan_api_key: Option<String>,
+        explorer_api_url: Option<String>,
+        explorer_url: Option<String>,
+    ) -> Result<PathBuf> {
+        let client = explorer_client(chain, etherscan_api_key, explorer_api_url, explorer_url)?;
+        let address = contract_address.parse()?;
+        let metadata = client.contract_source_code(address).await?;
+        let Some(metadata) = metadata.items.first() else {
+            eyre::bail!("Empty contract source code")
+        };
+        if metadata.is_vyper() {
+            eyre::bail!("Recompiling Vyper contracts is not supported")
+        }
+
+        let root = Config::foundry_etherscan_sources_dir(chain, address)
+            .ok_or_eyre("failed to resolve the foundry cache directory")?;
+        fs::create_dir_all(&root)?;
+        let project = cached_etherscan_project(metadata, &root)?;
+        ProjectCompiler::new().quiet(shell::is_json()).compile(&project)?;
+
+        Ok(project.artifacts_path().clone())
+    }
+
     /// Disassembles hex encoded bytecode into individual / human readable opcodes
     ///
     /// # Example
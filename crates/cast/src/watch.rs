@@ -0,0 +1,186 @@
+//! Watching transactions until they reach a number of confirmations.
+
+use alloy_consensus::Transaction;
+use alloy_network::{AnyNetwork, AnyTransactionReceipt};
+use alloy_primitives::{Address, TxHash, B256};
+use alloy_provider::Provider;
+use alloy_rpc_types::BlockId;
+use eyre::Result;
+use foundry_config::TransactionWatchConfig;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// Polls the inclusion status of a transaction until it has the required number of confirmations,
+/// it is dropped or replaced, or the timeout elapses.
+///
+/// Reorgs that move the transaction to another block, or back to the mempool, are recorded and
+/// the transaction keeps being watched.
+pub struct TxWatcher<P> {
+    provider: P,
+    confirmations: u64,
+    timeout: Duration,
+    poll_interval: Duration,
+    max_poll_interval: Duration,
+}
+
+/// The final status of a watched transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TxWatchStatus {
+    /// The transaction reached the required number of confirmations.
+    Confirmed,
+    /// Another transaction with the same sender and nonce was included instead.
+    Replaced,
+    /// The transaction is no longer known to the node, and its nonce has not been used.
+    Dropped,
+    /// The timeout elapsed before any of the other outcomes.
+    TimedOut,
+}
+
+/// A reorg survived by a watched transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxReorg {
+    /// The block the transaction was previously included in.
+    pub old_block_number: u64,
+    pub old_block_hash: B256,
+    /// The block the transaction is now included in, or `None` if it was returned to the mempool.
+    pub new_block_number: Option<u64>,
+    pub new_block_hash: Option<B256>,
+}
+
+/// The result of watching a transaction.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxWatchOutcome {
+    pub tx_hash: TxHash,
+    pub status: TxWatchStatus,
+    /// The number of confirmations the transaction had when watching stopped.
+    pub confirmations: u64,
+    pub block_number: Option<u64>,
+    pub block_hash: Option<B256>,
+    /// The reorgs observed while watching.
+    pub reorgs: Vec<TxReorg>,
+    /// The receipt of the transaction, if it was included.
+    pub receipt: Option<AnyTransactionReceipt>,
+}
+
+impl<P: Provider<AnyNetwork>> TxWatcher<P> {
+    /// Creates a new watcher waiting for `confirmations` blocks, using the polling settings of
+    /// the given config.
+    pub fn new(
+        provider: P,
+        confirmations: u64,
+        timeout: Duration,
+        config: &TransactionWatchConfig,
+    ) -> Self {
+        let poll_interval = Duration::from_millis(config.poll_interval.max(1));
+        let max_poll_interval = Duration::from_millis(config.max_poll_interval).max(poll_interval);
+        Self {
+            provider,
+            confirmations: confirmations.max(1),
+            timeout,
+            poll_interval,
+            max_poll_interval,
+        }
+    }
+
+    /// Watches the transaction until it reaches a final status.
+    ///
+    /// `on_reorg` is called for every reorg as it is observed.
+    pub async fn watch(
+        &self,
+        tx_hash: TxHash,
+        mut on_reorg: impl FnMut(&TxReorg),
+    ) -> Result<TxWatchOutcome> {
+        let start = Instant::now();
+        let mut interval = self.poll_interval;
+        let mut sender: Option<(Address, u64)> = None;
+        let mut included: Option<(u64, B256)> = None;
+        let mut last_block = None;
+        let mut outcome = TxWatchOutcome {
+            tx_hash,
+            status: TxWatchStatus::TimedOut,
+            confirmations: 0,
+            block_number: None,
+            block_hash: None,
+            reorgs: Vec::new(),
+            receipt: None,
+        };
+
+        loop {
+            let latest = self.provider.get_block_number().await?;
+            if last_block != Some(latest) {
+                last_block = Some(latest);
+                interval = self.poll_interval;
+            } else {
+                interval = (interval * 2).min(self.max_poll_interval);
+            }
+
+            let receipt = self.canonical_receipt(tx_hash).await?;
+            let block = receipt.as_ref().and_then(|r| Some((r.block_number?, r.block_hash?)));
+
+            if let Some((old_block_number, old_block_hash)) = included {
+                if block != included {
+                    let reorg = TxReorg {
+                        old_block_number,
+                        old_block_hash,
+                        new_block_number: block.map(|(number, _)| number),
+                        new_block_hash: block.map(|(_, hash)| hash),
+                    };
+                    on_reorg(&reorg);
+                    outcome.reorgs.push(reorg);
+                }
+            }
+            included = block;
+            (outcome.block_number, outcome.block_hash) = block.unzip();
+            outcome.confirmations =
+                block.map_or(0, |(number, _)| latest.saturating_sub(number) + 1);
+            outcome.receipt = receipt;
+
+            if outcome.confirmations >= self.confirmations {
+                outcome.status = TxWatchStatus::Confirmed;
+                return Ok(outcome);
+            }
+
+            if included.is_none() {
+                match self.provider.get_transaction_by_hash(tx_hash).await? {
+                    Some(tx) => sender = Some((tx.from, tx.nonce())),
+                    // Only a transaction seen before can be told apart from one that hasn't
+                    // propagated to this node yet.
+                    None => {
+                        if let Some((from, nonce)) = sender {
+                            let next_nonce = self.provider.get_transaction_count(from).await?;
+                            outcome.status = if next_nonce > nonce {
+                                TxWatchStatus::Replaced
+                            } else {
+                                TxWatchStatus::Dropped
+                            };
+                            return Ok(outcome);
+                        }
+                    }
+                }
+            }
+
+            if start.elapsed() >= self.timeout {
+                return Ok(outcome);
+            }
+            tokio::time::sleep(interval.min(self.timeout.saturating_sub(start.elapsed()))).await;
+        }
+    }
+
+    /// Returns the receipt of the transaction if the block it refers to is still canonical.
+    ///
+    /// Nodes may keep serving the receipt of a reorged block for a short while, so the hash of
+    /// the canonical block at that height is checked as well.
+    async fn canonical_receipt(&self, tx_hash: TxHash) -> Result<Option<AnyTransactionReceipt>> {
+        let Some(receipt) = self.provider.get_transaction_receipt(tx_hash).await? else {
+            return Ok(None);
+        };
+        let (Some(number), Some(hash)) = (receipt.block_number, receipt.block_hash) else {
+            return Ok(None);
+        };
+        let block = self.provider.get_block(BlockId::number(number), false.into()).await?;
+        Ok(block.filter(|block| block.header.hash == hash).map(|_| receipt))
+    }
+}
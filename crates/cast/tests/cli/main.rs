@@ -2,7 +2,7 @@
 
 use alloy_chains::NamedChain;
 use alloy_network::{TransactionBuilder, TransactionResponse};
-use alloy_primitives::{address, b256, Bytes, B256};
+use alloy_primitives::{address, b256, Bytes, B256, U256};
 use alloy_provider::{Provider, ProviderBuilder};
 use alloy_rpc_types::{BlockNumberOrTag, Index, TransactionRequest};
 use anvil::{EthereumHardfork, NodeConfig};
@@ -270,6 +270,130 @@
 "#]]);
 });
 
+// tests that `cast wallet verify --data --from-file` recovers the typed data signer
+casttest!(wallet_verify_typed_data_file, |_prj, cmd| {
+    cmd.args([
+        "wallet",
+        "verify",
+        "--data",
+        "--from-file",
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/sign_typed_data.json")
+            .into_os_string()
+            .into_string()
+            .unwrap()
+            .as_str(),
+        "0x06c18bdc8163219fddc9afaf5a0550e381326474bb757c86dc32317040cf384e07a2c72ce66c1a0626b6750ca9b6c035bf6f03e7ed67ae2d1134171e9085c0b51b",
+        "--address",
+        "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf",
+    ]).assert_success().stdout_eq(str![[r#"
+Validation succeeded. Address 0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf signed this message.
+
+"#]]);
+});
+
+// tests that `cast hash-typed-data --from-file` outputs the EIP-712 hashes
+casttest!(hash_typed_data_file, |_prj, cmd| {
+    cmd.args([
+        "hash-typed-data",
+        "--from-file",
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/sign_typed_data.json")
+            .into_os_string()
+            .into_string()
+            .unwrap()
+            .as_str(),
+    ]).assert_success().stdout_eq(str![[r#"
+Domain separator: 0x6a9be1e55f942a0d2a692c2f96bb87ed1ef0023b271ef424f8533b077311215f
+Struct hash:      0x15d2c54cdaa22a6a3a8dbd89086b2ffcf0853857db9bcf1541765a8f769a63ba
+Digest:           0x232cd3ec058eb935a709f093e3536ce26cc9e8e193584b0881992525f6236eef
+
+"#]]);
+});
+
+// tests that typed data not matching its declared types is rejected
+casttest!(hash_typed_data_invalid, |_prj, cmd| {
+    cmd.args([
+        "hash-typed-data",
+        r#"{"types":{"EIP712Domain":[{"name":"name","type":"string"}],"Mail":[{"name":"to","type":"Person"}]},"primaryType":"Mail","domain":{"name":"Test"},"message":{"to":"0x01"}}"#,
+    ]).assert_failure();
+});
+
+// tests that `cast tx build` and `cast tx sign` create a signed transaction without an RPC
+casttest!(tx_build_and_sign_offline, |prj, cmd| {
+    let tx_path = prj.root().join("tx.json");
+    cmd.args([
+        "tx",
+        "build",
+        "0x70997970C51812dc3A010C7d01b50e0d17dc79C8",
+        "--from",
+        "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf",
+        "--chain",
+        "1",
+        "--nonce",
+        "0",
+        "--gas-limit",
+        "21000",
+        "--gas-price",
+        "10gwei",
+        "--priority-gas-price",
+        "1gwei",
+        "--value",
+        "1ether",
+        "--out",
+        tx_path.to_str().unwrap(),
+    ])
+    .assert_success();
+
+    let tx: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&tx_path).unwrap()).unwrap();
+    assert_eq!(tx["chainId"], "0x1");
+    assert_eq!(tx["gas"], "0x5208");
+
+    cmd.cast_fuse()
+        .args([
+            "tx",
+            "sign",
+            tx_path.to_str().unwrap(),
+            "--private-key",
+            "0x0000000000000000000000000000000000000000000000000000000000000001",
+        ])
+        .assert_success()
+        .stdout_eq(str![[r#"
+0x02f8[..]
+
+"#]]);
+
+    // the sender recorded in the transaction must match the signer
+    cmd.cast_fuse()
+        .args([
+            "tx",
+            "sign",
+            tx_path.to_str().unwrap(),
+            "--private-key",
+            "0x0000000000000000000000000000000000000000000000000000000000000002",
+        ])
+        .assert_failure();
+});
+
+// tests that `cast tx build` fails if fields that are usually fetched from the RPC are missing
+casttest!(tx_build_missing_fields, |_prj, cmd| {
+    cmd.args([
+        "tx",
+        "build",
+        "0x70997970C51812dc3A010C7d01b50e0d17dc79C8",
+        "--chain",
+        "1",
+        "--gas-limit",
+        "21000",
+    ])
+    .assert_failure()
+    .stderr_eq(str![[r#"
+Error: Missing transaction fields: [..]
+
+"#]]);
+});
+
 // tests that `cast wallet sign-auth message` outputs the expected signature
 casttest!(wallet_sign_auth, |_prj, cmd| {
     cmd.args([
@@ -1457,6 +1581,188 @@
 "#]]);
 });
 
+casttest!(tx_wait_for_confirmations, async |_prj, cmd| {
+    let (api, handle) = anvil::spawn(NodeConfig::test()).await;
+    let endpoint = handle.http_endpoint();
+
+    let tx_hash = cmd
+        .args([
+            "send",
+            "0x70997970C51812dc3A010C7d01b50e0d17dc79C8",
+            "--value",
+            "1",
+            "--private-key",
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+            "--rpc-url",
+            &endpoint,
+            "--async",
+        ])
+        .assert_success()
+        .get_output()
+        .stdout_lossy();
+    let tx_hash = tx_hash.trim();
+
+    // Not enough confirmations yet.
+    cmd.cast_fuse()
+        .args([
+            "tx",
+            tx_hash,
+            "--wait",
+            "--confirmations",
+            "3",
+            "--timeout",
+            "1",
+            "--rpc-url",
+            &endpoint,
+        ])
+        .assert_failure()
+        .stderr_eq(str![[r#"
+Error: transaction [..] had 1 of 3 confirmations after 1s
+
+"#]]);
+
+    api.anvil_mine(Some(U256::from(2)), None).await.unwrap();
+
+    let output = cmd
+        .cast_fuse()
+        .args(["tx", tx_hash, "--wait", "--confirmations", "3", "--json", "--rpc-url", &endpoint])
+        .assert_success()
+        .get_output()
+        .stdout_lossy();
+    let outcome: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(outcome["status"], "confirmed");
+    assert_eq!(outcome["txHash"], tx_hash);
+    assert_eq!(outcome["confirmations"], 3);
+    assert_eq!(outcome["blockNumber"], 1);
+    assert_eq!(outcome["reorgs"], serde_json::json!([]));
+});
+
+// tests that `cast call --batch` falls back to separate calls when Multicall3 is not deployed
+casttest!(call_batch_without_multicall, async |prj, cmd| {
+    let (_, handle) = anvil::spawn(NodeConfig::test()).await;
+    let endpoint = handle.http_endpoint();
+
+    let batch = prj.root().join("calls.json");
+    std::fs::write(
+        &batch,
+        r#"[
+            { "to": "0x70997970C51812dc3A010C7d01b50e0d17dc79C8", "data": "0x" },
+            {
+                "to": "0x70997970C51812dc3A010C7d01b50e0d17dc79C8",
+                "sig": "balanceOf(address)(uint256)",
+                "args": ["0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"]
+            }
+        ]"#,
+    )
+    .unwrap();
+
+    cmd.args(["call", "--batch", batch.to_str().unwrap(), "--rpc-url", &endpoint])
+        .assert_success()
+        .stdout_eq(str![[r#"
+[
+  {
+    "to": "0x70997970[..]",
+    "success": true,
+    "result": "0x"
+  },
+  {
+    "to": "0x70997970[..]",
+    "success": false,
+    "error": "could not decode output: [..]"
+  }
+]
+
+"#]]);
+});
+
+// tests that `cast call --batch` aggregates the calls through Multicall3 when it is deployed
+casttest!(call_batch_with_multicall, |prj, cmd| {
+    let eth_rpc_url = next_http_rpc_endpoint();
+
+    let batch = prj.root().join("calls.json");
+    std::fs::write(
+        &batch,
+        r#"[
+            { "to": "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2", "sig": "decimals()(uint8)" },
+            { "to": "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2", "sig": "symbol()(string)" },
+            {
+                "to": "0x6B175474E89094C44Da98b954EedeAC495271d0F",
+                "sig": "transferFrom(address,address,uint256)(bool)",
+                "args": [
+                    "0x0000000000000000000000000000000000000123",
+                    "0x0000000000000000000000000000000000000456",
+                    "10000000000000000000000000000000000000000"
+                ]
+            }
+        ]"#,
+    )
+    .unwrap();
+
+    cmd.args([
+        "call",
+        "--batch",
+        batch.to_str().unwrap(),
+        "--rpc-url",
+        eth_rpc_url.as_str(),
+        "--block",
+        "21422087",
+    ])
+    .assert_success()
+    .stdout_eq(str![[r#"
+[
+  {
+    "to": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+    "success": true,
+    "result": [
+      "18"
+    ]
+  },
+  {
+    "to": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+    "success": true,
+    "result": [
+      "WETH"
+    ]
+  },
+  {
+    "to": "0x6b175474e89094c44da98b954eedeac495271d0f",
+    "success": false,
+    "error": "Dai/insufficient-balance"
+  }
+]
+
+"#]]);
+});
+
+// tests that `cast call --batch` sends the calls separately when the aggregated call fails
+casttest!(call_batch_with_failing_multicall, async |prj, cmd| {
+    let (api, handle) = anvil::spawn(NodeConfig::test()).await;
+    let endpoint = handle.http_endpoint();
+    // reverts unconditionally
+    let code = Bytes::from_static(&[0x60, 0x00, 0x80, 0xfd]);
+    api.anvil_set_code(cast::batch::MULTICALL3_ADDRESS, code).await.unwrap();
+
+    let batch = prj.root().join("calls.json");
+    std::fs::write(
+        &batch,
+        r#"[{ "to": "0x70997970C51812dc3A010C7d01b50e0d17dc79C8", "data": "0x" }]"#,
+    )
+    .unwrap();
+
+    cmd.args(["call", "--batch", batch.to_str().unwrap(), "--rpc-url", &endpoint])
+        .assert_success()
+        .stdout_eq(str![[r#"
+[
+  {
+    "to": "0x70997970c51812dc3a010c7d01b50e0d17dc79c8",
+    "success": true,
+    "result": "0x"
+  }
+]
+
+"#]]);
+});
+
 casttest!(hash_message, |_prj, cmd| {
     cmd.args(["hash-message", "hello"]).assert_success().stdout_eq(str![[r#"
 0x50b2c43fd39106bafbba0da34fc430e1f91e3c96ea2acee2bc34119f92b37750
@@ -1972,6 +2278,27 @@ struct Info {
 ..."#]]);
 });
 
+casttest!(fetch_src_recompile, |_prj, cmd| {
+    let url = "https://eth.blockscout.com/api";
+
+    let weth = address!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2");
+
+    cmd.args([
+        "source",
+        &weth.to_string(),
+        "--chain-id",
+        "1",
+        "--explorer-api-url",
+        url,
+        "--recompile",
+    ])
+    .assert_success()
+    .stdout_eq(str![[r#"
+...
+Artifacts written to [..]
+"#]]);
+});
+
 // tests cast send gas estimate execution failure message contains decoded custom error
 // <https://github.com/foundry-rs/foundry/issues/9789>
 forgetest_async!(cast_send_estimate_gas_error, |prj, cmd| {
@@ -2042,3 +2369,244 @@ struct Info {
 
 "#]]);
 });
+
+// tests that `cast receipt --decode-logs` decodes the logs and revert data with the project's
+// artifacts, and that `--check` fails for reverted transactions
+forgetest_async!(receipt_decode_logs, |prj, cmd| {
+    let (_, handle) = anvil::spawn(NodeConfig::test()).await;
+    let endpoint = handle.http_endpoint();
+
+    foundry_test_utils::util::initialize(prj.root());
+    prj.add_source(
+        "Capped",
+        r#"
+contract Capped {
+    event Added(address indexed by, uint256 total);
+    error TooLarge(uint256 amount);
+    uint256 public total;
+    function add(uint256 amount) public {
+        if (amount > 100) {
+            revert TooLarge(amount);
+        }
+        total += amount;
+        emit Added(msg.sender, total);
+    }
+}
+   "#,
+    )
+    .unwrap();
+    prj.add_script(
+        "CappedScript",
+        r#"
+import "forge-std/Script.sol";
+import {Capped} from "../src/Capped.sol";
+contract CappedScript is Script {
+    function run() public {
+        vm.startBroadcast();
+        new Capped();
+        vm.stopBroadcast();
+    }
+}
+   "#,
+    )
+    .unwrap();
+
+    cmd.args([
+        "script",
+        "--private-key",
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        "--rpc-url",
+        &endpoint,
+        "--broadcast",
+        "CappedScript",
+    ])
+    .assert_success();
+
+    let mut send = |amount: &str| {
+        let tx_hash = cmd
+            .cast_fuse()
+            .args([
+                "send",
+                "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+                "add(uint256)",
+                amount,
+                "--gas-limit",
+                "100000",
+                "--private-key",
+                "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+                "--rpc-url",
+                &endpoint,
+                "--async",
+            ])
+            .assert_success()
+            .get_output()
+            .stdout_lossy();
+        tx_hash.trim().to_string()
+    };
+    let added = send("5");
+    let reverted = send("1000");
+
+    let output = cmd
+        .cast_fuse()
+        .args(["receipt", &added, "--decode-logs", "--check", "--json", "--rpc-url", &endpoint])
+        .assert_success()
+        .get_output()
+        .stdout_lossy();
+    let receipt: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let log = &receipt["decodedLogs"][0];
+    assert_eq!(log["event"], "Added");
+    assert_eq!(log["signature"], "Added(address,uint256)");
+    assert_eq!(log["params"][0]["value"], "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266");
+    assert_eq!(log["params"][1]["name"], "total");
+    assert_eq!(log["params"][1]["value"], "5");
+
+    cmd.cast_fuse()
+        .args(["receipt", &reverted, "--decode-logs", "--check", "--rpc-url", &endpoint])
+        .assert_failure()
+        .stderr_eq(str![[r#"
+Error: transaction [..] failed: TooLarge(1000)
+
+"#]]);
+});
+
+// tests that `cast storage` lists the ERC-7201 namespaces of a contract and reads the struct
+// stored in a namespace
+forgetest_async!(storage_erc7201_namespace, |prj, cmd| {
+    let (_, handle) = anvil::spawn(NodeConfig::test()).await;
+    let endpoint = handle.http_endpoint();
+
+    foundry_test_utils::util::initialize(prj.root());
+    prj.add_source(
+        "Vault",
+        r#"
+contract Vault {
+    /// @custom:storage-location erc7201:example.main
+    struct MainStorage {
+        uint256 x;
+        uint256 y;
+    }
+    bytes32 private constant MAIN_STORAGE_LOCATION =
+        0x183a6125c38840424c4a85fa12bab2ab606c4b6d0e7cc73c0c06ba5300eab500;
+    constructor() {
+        MainStorage storage $;
+        assembly {
+            $.slot := MAIN_STORAGE_LOCATION
+        }
+        $.x = 1;
+        $.y = 2;
+    }
+}
+   "#,
+    )
+    .unwrap();
+    prj.add_script(
+        "VaultScript",
+        r#"
+import "forge-std/Script.sol";
+import {Vault} from "../src/Vault.sol";
+contract VaultScript is Script {
+    function run() public {
+        vm.startBroadcast();
+        new Vault();
+        vm.stopBroadcast();
+    }
+}
+   "#,
+    )
+    .unwrap();
+
+    cmd.args([
+        "script",
+        "--private-key",
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        "--rpc-url",
+        &endpoint,
+        "--broadcast",
+        "VaultScript",
+    ])
+    .assert_success();
+
+    let vault = "0x5FbDB2315678afecb367f032d93F642f64180aa3";
+    cmd.cast_fuse().set_current_dir(prj.root());
+
+    let output = cmd
+        .cast_fuse()
+        .args(["storage", vault, "--namespaces", "--json", "--rpc-url", &endpoint])
+        .assert_success()
+        .get_output()
+        .stdout_lossy();
+    let namespaces: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(
+        namespaces,
+        serde_json::json!([{
+            "id": "example.main",
+            "slot": "0x183a6125c38840424c4a85fa12bab2ab606c4b6d0e7cc73c0c06ba5300eab500",
+            "contract": "Vault",
+            "struct": "MainStorage",
+        }])
+    );
+
+    let output = cmd
+        .cast_fuse()
+        .args(["storage", vault, "--namespace", "example.main", "--json", "--rpc-url", &endpoint])
+        .assert_success()
+        .get_output()
+        .stdout_lossy();
+    let storage: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(storage["storage"][0]["label"], "x");
+    assert_eq!(storage["storage"][1]["label"], "y");
+    assert_eq!(
+        storage["values"],
+        serde_json::json!([
+            "0x0000000000000000000000000000000000000000000000000000000000000001",
+            "0x0000000000000000000000000000000000000000000000000000000000000002",
+        ])
+    );
+
+    let output = cmd
+        .cast_fuse()
+        .args([
+            "storage",
+            vault,
+            "--namespace",
+            "example.main",
+            "--member",
+            "y",
+            "--json",
+            "--rpc-url",
+            &endpoint,
+        ])
+        .assert_success()
+        .get_output()
+        .stdout_lossy();
+    let storage: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(storage["storage"].as_array().unwrap().len(), 1);
+    assert_eq!(storage["storage"][0]["label"], "y");
+    assert_eq!(
+        storage["storage"][0]["slot"],
+        U256::from_str("0x183a6125c38840424c4a85fa12bab2ab606c4b6d0e7cc73c0c06ba5300eab501")
+            .unwrap()
+            .to_string()
+    );
+
+    cmd.cast_fuse()
+        .args(["storage", vault, "--namespace", "example.other", "--rpc-url", &endpoint])
+        .assert_failure()
+        .stderr_eq(str![[r#"
+Error: namespace `example.other` is not declared by Vault; known namespaces: [example.main]
+
+"#]]);
+
+    cmd.cast_fuse()
+        .args(["storage", vault, "--namespace", "example.main", "--member", "z"])
+        .args(["--rpc-url", &endpoint])
+        .assert_failure()
+        .stderr_eq(str![[r#"
+Error: `MainStorage` has no member named `z`
+
+"#]]);
+
+    cmd.cast_fuse()
+        .args(["storage", vault, "--member", "x", "--rpc-url", &endpoint])
+        .assert_failure();
+});
@@ -491,6 +491,16 @@ struct PotentialRevert {
     #[cheatcode(group = Evm, safety = Unsafe)]
     function warp(uint256 newTimestamp) external;
 
+    /// Sets `block.timestamp` to the given RFC 3339 date-time (e.g. `2025-06-01T00:00:00Z`), date (e.g. `2025-06-01`,
+    /// interpreted as midnight UTC) or duration relative to the current `block.timestamp` (e.g. `+30 days`, `-1 week 2 hours`).
+    #[cheatcode(group = Evm, safety = Unsafe)]
+    function warpTo(string calldata timestamp) external;
+
+    /// Parses the given RFC 3339 date-time, date or relative duration into a unix timestamp.
+    /// Relative durations are resolved against the current `block.timestamp`. See `warpTo` for the accepted formats.
+    #[cheatcode(group = Evm, safety = Safe)]
+    function parseTimestamp(string calldata timestamp) external view returns (uint256 unixTimestamp);
+
     /// Gets the current `block.timestamp`.
     /// You should use this instead of `block.timestamp` if you use `vm.warp`, as `block.timestamp` is assumed to be constant across a transaction,
     /// and as a result will get optimized out by the compiler.
@@ -609,6 +619,15 @@ struct PotentialRevert {
     function mockCallRevert(address callee, uint256 msgValue, bytes4 data, bytes calldata revertData)
         external;
 
+    /// Reverts multiple calls to an address, with specified revert data for each call.
+    #[cheatcode(group = Evm, safety = Unsafe)]
+    function mockCallsRevert(address callee, bytes calldata data, bytes[] calldata revertData) external;
+
+    /// Reverts multiple calls to an address with a specific `msg.value`, with specified revert data for each call.
+    #[cheatcode(group = Evm, safety = Unsafe)]
+    function mockCallsRevert(address callee, uint256 msgValue, bytes calldata data, bytes[] calldata revertData)
+        external;
+
     /// Whenever a call is made to `callee` with calldata `data`, this cheatcode instead calls
     /// `target` with the same calldata. This functionality is similar to a delegate call made to
     /// `target` contract from `callee`.
@@ -805,6 +824,12 @@ struct PotentialRevert {
     #[cheatcode(group = Evm, safety = Unsafe)]
     function rollFork(uint256 forkId, bytes32 txHash) external;
 
+    /// Updates the currently active fork to the latest block whose timestamp is less than or equal to the given
+    /// timestamp, which accepts the same formats as `warpTo`. Unlike `blockAt`, the block can be after the one the fork is
+    /// pinned to.
+    #[cheatcode(group = Evm, safety = Unsafe)]
+    function rollForkTo(string calldata timestamp) external;
+
     /// Takes a fork identifier created by `createFork` and sets the corresponding forked state as active.
     #[cheatcode(group = Evm, safety = Unsafe)]
     function selectFork(uint256 forkId) external;
@@ -826,6 +851,22 @@ struct PotentialRevert {
         external
         returns (bytes memory data);
 
+    /// Performs an Ethereum JSON-RPC request to the current fork URL, and returns the raw JSON result.
+    #[cheatcode(group = Evm, safety = Safe)]
+    function rpcJson(string calldata method, string calldata params) external returns (string memory json);
+
+    /// Performs an Ethereum JSON-RPC request to the given endpoint, and returns the raw JSON result.
+    #[cheatcode(group = Evm, safety = Safe)]
+    function rpcJson(string calldata urlOrAlias, string calldata method, string calldata params)
+        external
+        returns (string memory json);
+
+    /// Returns the number of the latest block of the currently active fork, up to the block the fork is pinned to, whose
+    /// timestamp is less than or equal to the given timestamp. The block is located with a binary search over the fork's
+    /// RPC endpoint.
+    #[cheatcode(group = Evm, safety = Safe)]
+    function blockAt(uint256 timestamp) external returns (uint256 blockNumber);
+
     /// Gets all the logs according to specified filter.
     #[cheatcode(group = Evm, safety = Safe)]
     function eth_getLogs(uint256 fromBlock, uint256 toBlock, address target, bytes32[] calldata topics)
@@ -981,6 +1022,26 @@ struct PotentialRevert {
     function expectCallMinGas(address callee, uint256 msgValue, uint64 minGas, bytes calldata data, uint64 count)
         external;
 
+    /// Expects the next call to consume an amount of gas within the inclusive range `[minGas, maxGas]`.
+    /// Gas is measured from the callee perspective, like `lastCallGas`. In isolation mode this includes the
+    /// intrinsic cost of the transaction the call is executed in, and gas refunds are applied.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function expectGasUsed(uint64 minGas, uint64 maxGas) external;
+
+    /// Expects the next call to consume the amount of gas stored in the snapshot file for `name`, give or take `tolerance`.
+    /// The group name is derived from the contract name.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function expectGasUsed(string calldata name, uint64 tolerance) external;
+
+    /// Expects the next call to consume the amount of gas stored in the snapshot file for `name` in a group, give or take `tolerance`.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function expectGasUsed(string calldata group, string calldata name, uint64 tolerance) external;
+
+    /// Expects a call to `isValidSignature(digest, signature)` on the ERC-1271 `account` that returns the
+    /// magic value `0x1626ba7e`, before the end of the test.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function expectValidSignature(address account, bytes32 digest) external;
+
     /// Prepare an expected log with (bool checkTopic1, bool checkTopic2, bool checkTopic3, bool checkData.).
     /// Call this function, then emit an event, then call a function. Internally after the call, we check if
     /// logs were emitted in the expected order with the expected topics and data (as specified by the booleans).
@@ -1131,6 +1192,17 @@ struct PotentialRevert {
     #[cheatcode(group = Testing, safety = Unsafe)]
     function skip(bool skipTest, string calldata reason) external;
 
+    /// Marks a test as skipped unless the given environment variable is set and not empty, e.g. the
+    /// RPC URL of a fork. Must be called at the top level of a test.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function skipUnlessEnv(string calldata name) external;
+
+    /// Runs the test again, up to `maxRetries` times, if it fails because of a transient error
+    /// of an RPC endpoint, such as a timeout or rate limiting. Must be called at the top level of a
+    /// test. When called in `setUp`, applies to `setUp` and to all tests of the contract.
+    #[cheatcode(group = Testing, safety = Safe)]
+    function retryOnRpcError(uint256 maxRetries) external;
+
     /// Asserts that the given condition is true.
     #[cheatcode(group = Testing, safety = Safe)]
     function assertTrue(bool condition) external pure;
@@ -1727,6 +1799,19 @@ struct PotentialRevert {
     #[cheatcode(group = Filesystem)]
     function createDir(string calldata path, bool recursive) external;
 
+    /// Creates a new empty directory in the system's temporary directory and returns its path.
+    /// Files in the directory can be read and written until the end of the test, after which the
+    /// directory is removed.
+    #[cheatcode(group = Filesystem)]
+    function createTempDir() external returns (string memory path);
+
+    /// Returns the scratch directory of the current test, created in the system's temporary directory
+    /// on first use. Unlike `createTempDir`, every call of a test returns the same directory.
+    /// Files in the directory can be read and written until the end of the test, after which the
+    /// directory is removed.
+    #[cheatcode(group = Filesystem)]
+    function scratchDir() external returns (string memory path);
+
     /// Reads the directory at the given path recursively, up to `maxDepth`.
     /// `maxDepth` defaults to 1, meaning only the direct children of the given directory will be returned.
     /// Follows symbolic links if `followLinks` is true.
@@ -1820,6 +1905,24 @@ struct PotentialRevert {
     #[cheatcode(group = Filesystem)]
     function deployCode(string calldata artifactPath, bytes calldata constructorArgs) external returns (address deployedAddress);
 
+    /// Deploys a contract from an artifact file to the given address. Takes in the relative path to the json file or the
+    /// path to the artifact in the form of <path>:<contract>:<version> where <contract> and <version> parts are optional.
+    ///
+    /// The constructor is executed at the target address, and the runtime bytecode it returns, with its immutables, is set
+    /// as the code of the target. The storage of the target is not cleared beforehand.
+    #[cheatcode(group = Filesystem)]
+    function deployCodeTo(string calldata artifactPath, address target) external;
+
+    /// Deploys a contract from an artifact file to the given address. Takes in the relative path to the json file or the
+    /// path to the artifact in the form of <path>:<contract>:<version> where <contract> and <version> parts are optional.
+    ///
+    /// The constructor is executed at the target address, and the runtime bytecode it returns, with its immutables, is set
+    /// as the code of the target. The storage of the target is not cleared beforehand.
+    ///
+    /// Additionally accepts abi-encoded constructor arguments.
+    #[cheatcode(group = Filesystem)]
+    function deployCodeTo(string calldata artifactPath, bytes calldata constructorArgs, address target) external;
+
     /// Gets the deployed bytecode from an artifact file. Takes in the relative path to the json file or the path to the
     /// artifact in the form of <path>:<contract>:<version> where <contract> and <version> parts are optional.
     #[cheatcode(group = Filesystem)]
@@ -2574,6 +2677,26 @@ struct PotentialRevert {
     #[cheatcode(group = Crypto)]
     function signCompact(address signer, bytes32 digest) external pure returns (bytes32 r, bytes32 vs);
 
+    /// Signs `digest` with `privateKey` using the secp256k1 curve.
+    ///
+    /// Returns the signature packed as `abi.encodePacked(r, s, v)`, as accepted by the ERC-1271
+    /// accounts that validate the ECDSA signatures of their owner.
+    #[cheatcode(group = Crypto)]
+    function signErc1271(uint256 privateKey, bytes32 digest) external pure returns (bytes memory signature);
+
+    /// Signs `digest` with `privateKey`, the key of an owner of the smart `account`, returning the signature
+    /// that the `isValidSignature` of the account accepts for `digest`.
+    ///
+    /// `accountType` is the implementation of the account:
+    /// - `ecdsa`: the ECDSA signature of `digest`, packed as `abi.encodePacked(r, s, v)`.
+    /// - `safe`: the signature of the `SafeMessage` of `digest`, for Safe accounts with the compatibility fallback handler.
+    /// - `coinbase`: the signature of the `CoinbaseSmartWalletMessage` of `digest`, wrapped for the owner at index 0.
+    #[cheatcode(group = Crypto)]
+    function signErc1271(uint256 privateKey, address account, bytes32 digest, string calldata accountType)
+        external
+        view
+        returns (bytes memory signature);
+
     /// Signs `digest` with `privateKey` using the secp256r1 curve.
     #[cheatcode(group = Crypto)]
     function signP256(uint256 privateKey, bytes32 digest) external pure returns (bytes32 r, bytes32 s);
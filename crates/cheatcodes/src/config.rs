@@ -4,8 +4,8 @@
 use foundry_common::{fs::normalize_path, ContractsByArtifact};
 use foundry_compilers::{utils::canonicalize, ArtifactId, ProjectPathsConfig};
 use foundry_config::{
-    cache::StorageCachingConfig, fs_permissions::FsAccessKind, Config, FsPermissions,
-    ResolvedRpcEndpoint, ResolvedRpcEndpoints, RpcEndpoint, RpcEndpointUrl,
+    cache::StorageCachingConfig, fs_permissions::FsAccessKind, Config, FfiSandboxConfig,
+    FsPermissions, ResolvedRpcEndpoint, ResolvedRpcEndpoints, RpcEndpoint, RpcEndpointUrl,
 };
 use foundry_evm_core::opts::EvmOpts;
 use std::{
@@ -20,6 +20,8 @@
 pub struct CheatsConfig {
     /// Whether the FFI cheatcode is enabled.
     pub ffi: bool,
+    /// Limits enforced on the commands executed by the FFI cheatcodes.
+    pub ffi_sandbox: FfiSandboxConfig,
     /// Use the create 2 factory in all cases including tests and non-broadcasting scripts.
     pub always_use_create_2_factory: bool,
     /// Sets a timeout for vm.prompt cheatcodes
@@ -38,6 +40,8 @@ pub struct CheatsConfig {
     pub root: PathBuf,
     /// Absolute Path to broadcast dir i.e project_root/broadcast
     pub broadcast: PathBuf,
+    /// Absolute Path to gas snapshots dir i.e project_root/snapshots
+    pub snapshots: PathBuf,
     /// Paths (directories) where file reading/writing is allowed
     pub allowed_paths: Vec<PathBuf>,
     /// How the evm was configured by the user
@@ -56,6 +60,13 @@ pub struct CheatsConfig {
     pub seed: Option<U256>,
     /// Whether to allow `expectRevert` to work for internal calls.
     pub internal_expect_revert: bool,
+    /// Whether to check that Solidity contracts don't corrupt reserved memory or read
+    /// uninitialized memory.
+    pub memory_safety: bool,
+    /// Whether to record the peak memory used by the interpreter in each test.
+    pub memory_report: bool,
+    /// The maximum bytes of memory and stack the interpreter may use in a test.
+    pub test_memory_limit: Option<u64>,
 }
 
 impl CheatsConfig {
@@ -79,6 +90,7 @@ pub fn new(
 
         Self {
             ffi: evm_opts.ffi,
+            ffi_sandbox: config.ffi_sandbox.clone(),
             always_use_create_2_factory: evm_opts.always_use_create_2_factory,
             prompt_timeout: Duration::from_secs(config.prompt_timeout),
             rpc_storage_caching: config.rpc_storage_caching.clone(),
@@ -88,6 +100,7 @@ pub fn new(
             fs_permissions: config.fs_permissions.clone().joined(config.root.as_ref()),
             root: config.root.clone(),
             broadcast: config.root.clone().join(&config.broadcast),
+            snapshots: config.root.join(&config.snapshots),
             allowed_paths,
             evm_opts,
             labels: config.labels.clone(),
@@ -96,6 +109,9 @@ pub fn new(
             assertions_revert: config.assertions_revert,
             seed: config.fuzz.seed,
             internal_expect_revert: config.allow_internal_expect_revert,
+            memory_safety: config.memory_safety,
+            memory_report: config.memory_report,
+            test_memory_limit: config.test_memory_limit,
         }
     }
 
@@ -209,6 +225,7 @@ impl Default for CheatsConfig {
     fn default() -> Self {
         Self {
             ffi: false,
+            ffi_sandbox: Default::default(),
             always_use_create_2_factory: false,
             prompt_timeout: Duration::from_secs(120),
             rpc_storage_caching: Default::default(),
@@ -218,6 +235,7 @@ fn default() -> Self {
             fs_permissions: Default::default(),
             root: Default::default(),
             broadcast: Default::default(),
+            snapshots: Default::default(),
             allowed_paths: vec![],
             evm_opts: Default::default(),
             labels: Default::default(),
@@ -226,6 +244,9 @@ fn default() -> Self {
             assertions_revert: true,
             seed: None,
             internal_expect_revert: false,
+            memory_safety: false,
+            memory_report: false,
+            test_memory_limit: None,
         }
     }
 }
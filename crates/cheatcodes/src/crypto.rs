@@ -1,7 +1,7 @@
 //! Implementations of [`Crypto`](spec::Group::Crypto) Cheatcodes.
 
-use crate::{Cheatcode, Cheatcodes, Result, Vm::*};
-use alloy_primitives::{keccak256, Address, B256, U256};
+use crate::{Cheatcode, Cheatcodes, CheatsCtxt, Result, Vm::*};
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
 use alloy_signer::{Signer, SignerSync};
 use alloy_signer_local::{
     coins_bip39::{
@@ -22,6 +22,19 @@
 /// The BIP32 default derivation path prefix.
 const DEFAULT_DERIVATION_PATH_PREFIX: &str = "m/44'/60'/0'/0/";
 
+/// The EIP-712 domain type of most smart accounts.
+const EIP712_DOMAIN_TYPE: &str =
+    "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+
+/// The EIP-712 domain type of Safe accounts.
+const SAFE_DOMAIN_TYPE: &str = "EIP712Domain(uint256 chainId,address verifyingContract)";
+
+/// The type of the messages signed by the owners of Safe accounts.
+const SAFE_MESSAGE_TYPE: &str = "SafeMessage(bytes message)";
+
+/// The type of the messages signed by the owners of Coinbase Smart Wallets.
+const COINBASE_MESSAGE_TYPE: &str = "CoinbaseSmartWalletMessage(bytes32 hash)";
+
 impl Cheatcode for createWallet_0Call {
     fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self { walletLabel } = self;
@@ -178,6 +191,49 @@ fn apply(&self, state: &mut Cheatcodes) -> Result {
     }
 }
 
+impl Cheatcode for signErc1271_0Call {
+    fn apply(&self, _state: &mut Cheatcodes) -> Result {
+        let Self { privateKey, digest } = self;
+        let sig = sign(privateKey, digest)?;
+        Ok(Bytes::from(encode_packed_sig(sig)).abi_encode())
+    }
+}
+
+impl Cheatcode for signErc1271_1Call {
+    fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
+        let Self { privateKey, account, digest, accountType } = self;
+        let chain_id = U256::from(ccx.ecx.env.cfg.chain_id);
+        let signature = match accountType.as_str() {
+            "ecdsa" => encode_packed_sig(sign(privateKey, digest)?),
+            "safe" => {
+                // The `SafeMessage` of `abi.encode(digest)`, see `CompatibilityFallbackHandler`.
+                let domain = (keccak256(SAFE_DOMAIN_TYPE), chain_id, *account);
+                let message = (keccak256(SAFE_MESSAGE_TYPE), keccak256(digest));
+                let hash = eip712_hash(&domain, &message);
+                encode_packed_sig(sign(privateKey, &hash)?)
+            }
+            "coinbase" => {
+                // The replay-safe hash of `digest`, signed by the owner at index 0.
+                let domain = (
+                    keccak256(EIP712_DOMAIN_TYPE),
+                    keccak256("Coinbase Smart Wallet"),
+                    keccak256("1"),
+                    chain_id,
+                    *account,
+                );
+                let message = (keccak256(COINBASE_MESSAGE_TYPE), *digest);
+                let hash = eip712_hash(&domain, &message);
+                let sig = Bytes::from(encode_packed_sig(sign(privateKey, &hash)?));
+                (U256::ZERO, sig).abi_encode()
+            }
+            _ => bail!(
+                "unknown account type `{accountType}`, expected one of: ecdsa, safe, coinbase"
+            ),
+        };
+        Ok(Bytes::from(signature).abi_encode())
+    }
+}
+
 impl Cheatcode for signP256Call {
     fn apply(&self, _state: &mut Cheatcodes) -> Result {
         let Self { privateKey, digest } = self;
@@ -225,6 +281,21 @@ fn encode_full_sig(sig: alloy_primitives::PrimitiveSignature) -> Vec<u8> {
     (v, r, s).abi_encode()
 }
 
+/// Packs the signature as `abi.encodePacked(r, s, v)`.
+fn encode_packed_sig(sig: alloy_primitives::PrimitiveSignature) -> Vec<u8> {
+    let v = sig.v() as u8 + 27;
+    let r = B256::from(sig.r());
+    let s = B256::from(sig.s());
+    [r.as_slice(), s.as_slice(), &[v]].concat()
+}
+
+/// Returns the EIP-712 hash of the message in the domain, given as the fields of their structs.
+fn eip712_hash(domain: &impl SolValue, message: &impl SolValue) -> B256 {
+    let domain_separator = keccak256(domain.abi_encode());
+    let struct_hash = keccak256(message.abi_encode());
+    keccak256([&[0x19, 0x01], &domain_separator[..], &struct_hash[..]].concat())
+}
+
 fn encode_compact_sig(sig: alloy_primitives::PrimitiveSignature) -> Vec<u8> {
     // Implement EIP-2098 compact signature.
     let r = B256::from(sig.r());
@@ -10,6 +10,7 @@
 use alloy_primitives::{map::HashMap, Address, Bytes, B256, U256};
 use alloy_rlp::Decodable;
 use alloy_sol_types::SolValue;
+use chrono::{DateTime, NaiveDate, NaiveTime};
 use foundry_common::fs::{read_json_file, write_json_file};
 use foundry_evm_core::{
     backend::{DatabaseExt, RevertStateSnapshotAction},
@@ -470,6 +471,21 @@ fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
     }
 }
 
+impl Cheatcode for warpToCall {
+    fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
+        let Self { timestamp } = self;
+        ccx.ecx.env.block.timestamp = parse_timestamp(timestamp, ccx.ecx.env.block.timestamp)?;
+        Ok(Default::default())
+    }
+}
+
+impl Cheatcode for parseTimestampCall {
+    fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
+        let Self { timestamp } = self;
+        Ok(parse_timestamp(timestamp, ccx.ecx.env.block.timestamp)?.abi_encode())
+    }
+}
+
 impl Cheatcode for getBlockTimestampCall {
     fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
         let Self {} = self;
@@ -1032,7 +1048,7 @@ fn inner_stop_gas_snapshot(
 }
 
 // Derives the snapshot group and name from the provided group and name or the running contract.
-fn derive_snapshot_name(
+pub(crate) fn derive_snapshot_name(
     ccx: &CheatsCtxt,
     group: Option<String>,
     name: Option<String>,
@@ -1196,3 +1212,91 @@ fn get_recorded_state_diffs(state: &mut Cheatcodes) -> BTreeMap<Address, Account
     }
     state_diffs
 }
+
+/// Parses an RFC 3339 date-time, a `YYYY-MM-DD` date (midnight UTC) or a duration relative to
+/// `now` such as `+30 days` or `-1w 12h` into a unix timestamp.
+fn parse_timestamp(s: &str, now: U256) -> Result<U256> {
+    let s = s.trim();
+    if let Some(duration) = s.strip_prefix('+') {
+        return now
+            .checked_add(U256::from(parse_duration(duration)?))
+            .ok_or_else(|| fmt_err!("timestamp overflow when adding {s:?}"));
+    }
+    if let Some(duration) = s.strip_prefix('-') {
+        return now
+            .checked_sub(U256::from(parse_duration(duration)?))
+            .ok_or_else(|| fmt_err!("timestamp underflow when subtracting {s:?}"));
+    }
+
+    let secs = if let Ok(datetime) = DateTime::parse_from_rfc3339(s) {
+        datetime.timestamp()
+    } else if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        date.and_time(NaiveTime::MIN).and_utc().timestamp()
+    } else {
+        bail!(
+            "invalid timestamp {s:?}: expected an RFC 3339 date-time, a YYYY-MM-DD date \
+             or a relative duration like \"+30 days\""
+        )
+    };
+    u64::try_from(secs).map(U256::from).map_err(|_| fmt_err!("{s:?} is before the unix epoch"))
+}
+
+/// Parses a duration made of `<amount> <unit>` pairs, e.g. `30 days` or `1w 2d 3h`, into seconds.
+fn parse_duration(s: &str) -> Result<u64> {
+    let mut tokens = s.split_whitespace();
+    let mut total = None::<u64>;
+    while let Some(token) = tokens.next() {
+        let (amount, unit) =
+            token.split_at(token.find(|c: char| !c.is_ascii_digit()).unwrap_or(token.len()));
+        let amount: u64 = amount.parse().map_err(|_| fmt_err!("invalid duration {s:?}"))?;
+        let unit = if unit.is_empty() {
+            tokens.next().ok_or_else(|| fmt_err!("missing unit in duration {s:?}"))?
+        } else {
+            unit
+        };
+        let multiplier = match unit {
+            "s" | "sec" | "secs" | "second" | "seconds" => 1,
+            "m" | "min" | "mins" | "minute" | "minutes" => 60,
+            "h" | "hr" | "hrs" | "hour" | "hours" => 60 * 60,
+            "d" | "day" | "days" => 24 * 60 * 60,
+            "w" | "week" | "weeks" => 7 * 24 * 60 * 60,
+            _ => bail!(
+                "unknown duration unit {unit:?}; expected seconds, minutes, hours, days or weeks"
+            ),
+        };
+        total = amount
+            .checked_mul(multiplier)
+            .and_then(|secs| secs.checked_add(total.unwrap_or_default()))
+            .map(Some)
+            .ok_or_else(|| fmt_err!("duration {s:?} is too large"))?;
+    }
+    total.ok_or_else(|| fmt_err!("empty duration"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_timestamps() {
+        let now = U256::from(1_000_000);
+        assert_eq!(
+            parse_timestamp("2025-06-01T00:00:00Z", now).unwrap(),
+            U256::from(1_748_736_000)
+        );
+        assert_eq!(
+            parse_timestamp("2025-06-01T02:00:00+02:00", now).unwrap(),
+            U256::from(1_748_736_000)
+        );
+        assert_eq!(parse_timestamp("2025-06-01", now).unwrap(), U256::from(1_748_736_000));
+        assert_eq!(parse_timestamp("+30 days", now).unwrap(), U256::from(1_000_000 + 30 * 86400));
+        assert_eq!(parse_timestamp("-1w 2h", now).unwrap(), U256::from(1_000_000 - 604800 - 7200));
+        assert_eq!(parse_timestamp("+90s", now).unwrap(), U256::from(1_000_090));
+
+        assert!(parse_timestamp("-1000 weeks", now).is_err());
+        assert!(parse_timestamp("+30 fortnights", now).is_err());
+        assert!(parse_timestamp("+", now).is_err());
+        assert!(parse_timestamp("1969-12-31", now).is_err());
+        assert!(parse_timestamp("yesterday", now).is_err());
+    }
+}
@@ -3,9 +3,10 @@
     Result, Vm::*,
 };
 use alloy_dyn_abi::DynSolValue;
+use alloy_network::AnyNetwork;
 use alloy_primitives::{B256, U256};
 use alloy_provider::Provider;
-use alloy_rpc_types::Filter;
+use alloy_rpc_types::{BlockTransactionsKind, Filter};
 use alloy_sol_types::SolValue;
 use foundry_common::provider::ProviderBuilder;
 use foundry_evm_core::fork::CreateFork;
@@ -77,6 +78,30 @@ fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
     }
 }
 
+impl Cheatcode for rollForkToCall {
+    fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
+        let Self { timestamp } = self;
+        let timestamp = super::parse_timestamp(timestamp, ccx.ecx.env.block.timestamp)?;
+        let Ok(timestamp) = u64::try_from(timestamp) else {
+            bail!("timestamp must be less than 2^64 - 1")
+        };
+        let url =
+            ccx.ecx.db.active_fork_url().ok_or_else(|| fmt_err!("no active fork URL found"))?;
+        let provider = ProviderBuilder::new(&url).build()?;
+        // Rolling isn't bound by the block the fork is pinned to, like `rollFork`.
+        let block = foundry_common::block_on(async {
+            let latest = provider
+                .get_block_number()
+                .await
+                .map_err(|e| fmt_err!("failed to get latest block number: {e}"))?;
+            block_at(&provider, timestamp, latest).await
+        })?;
+        persist_caller(ccx);
+        ccx.ecx.db.roll_fork(None, block, &mut ccx.ecx.env, &mut ccx.ecx.journaled_state)?;
+        Ok(Default::default())
+    }
+}
+
 impl Cheatcode for rollFork_1Call {
     fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
         let Self { txHash } = self;
@@ -231,6 +256,42 @@ fn apply(&self, state: &mut Cheatcodes) -> Result {
     }
 }
 
+impl Cheatcode for rpcJson_0Call {
+    fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
+        let Self { method, params } = self;
+        let url =
+            ccx.ecx.db.active_fork_url().ok_or_else(|| fmt_err!("no active fork URL found"))?;
+        Ok(rpc_request(&url, method, params)?.to_string().abi_encode())
+    }
+}
+
+impl Cheatcode for rpcJson_1Call {
+    fn apply(&self, state: &mut Cheatcodes) -> Result {
+        let Self { urlOrAlias, method, params } = self;
+        let url = state.config.rpc_endpoint(urlOrAlias)?.url()?;
+        Ok(rpc_request(&url, method, params)?.to_string().abi_encode())
+    }
+}
+
+impl Cheatcode for blockAtCall {
+    fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
+        let Self { timestamp } = self;
+        let Ok(timestamp) = u64::try_from(timestamp) else {
+            bail!("timestamp must be less than 2^64 - 1")
+        };
+        let url =
+            ccx.ecx.db.active_fork_url().ok_or_else(|| fmt_err!("no active fork URL found"))?;
+        let fork_block = ccx
+            .ecx
+            .db
+            .active_fork_block_number()
+            .ok_or_else(|| fmt_err!("no active fork block found"))?;
+        let provider = ProviderBuilder::new(&url).build()?;
+        let block = foundry_common::block_on(block_at(&provider, timestamp, fork_block))?;
+        Ok(U256::from(block).abi_encode())
+    }
+}
+
 impl Cheatcode for eth_getLogsCall {
     fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
         let Self { fromBlock, toBlock, target, topics } = self;
@@ -335,13 +396,11 @@ fn create_fork_request(
     if let Some(Ok(auth)) = rpc_endpoint.auth {
         evm_opts.fork_headers = Some(vec![format!("Authorization: {auth}")]);
     }
-    let fork = CreateFork {
-        enable_caching: !ccx.state.config.no_storage_caching &&
-            ccx.state.config.rpc_storage_caching.enable_for_endpoint(&url),
-        url,
-        env: (*ccx.ecx.env).clone(),
-        evm_opts,
-    };
+    let enable_caching = !ccx.state.config.no_storage_caching &&
+        ccx.state.config.rpc_storage_caching.enable_for_endpoint(&url);
+    let evict_cache = enable_caching.then(|| ccx.state.config.rpc_storage_caching.clone());
+    let fork =
+        CreateFork { enable_caching, url, env: (*ccx.ecx.env).clone(), evm_opts, evict_cache };
     Ok(fork)
 }
 
@@ -377,13 +436,42 @@ fn persist_caller(ccx: &mut CheatsCtxt) {
     ccx.ecx.db.add_persistent_account(ccx.caller);
 }
 
+/// Binary searches for the latest block up to `high` with a timestamp less than or equal to
+/// `timestamp`.
+async fn block_at<P: Provider<AnyNetwork>>(provider: &P, timestamp: u64, high: u64) -> Result<u64> {
+    let (mut low, mut high) = (0, high);
+
+    if block_timestamp(provider, low).await? > timestamp {
+        bail!("timestamp {timestamp} is before the genesis block")
+    }
+    if block_timestamp(provider, high).await? <= timestamp {
+        return Ok(high);
+    }
+    // Invariant: block `low` is at or before `timestamp`, block `high` is after it.
+    while high - low > 1 {
+        let mid = low + (high - low) / 2;
+        if block_timestamp(provider, mid).await? <= timestamp {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Ok(low)
+}
+
+/// Returns the timestamp of the block with the given number.
+async fn block_timestamp<P: Provider<AnyNetwork>>(provider: &P, number: u64) -> Result<u64> {
+    let block = provider
+        .get_block_by_number(number.into(), BlockTransactionsKind::Hashes)
+        .await
+        .map_err(|e| fmt_err!("failed to get block {number}: {e}"))?
+        .ok_or_else(|| fmt_err!("block {number} not found"))?;
+    Ok(block.header.timestamp)
+}
+
 /// Performs an Ethereum JSON-RPC request to the given endpoint.
 fn rpc_call(url: &str, method: &str, params: &str) -> Result {
-    let provider = ProviderBuilder::new(url).build()?;
-    let params_json: serde_json::Value = serde_json::from_str(params)?;
-    let result =
-        foundry_common::block_on(provider.raw_request(method.to_string().into(), params_json))
-            .map_err(|err| fmt_err!("{method:?}: {err}"))?;
+    let result = rpc_request(url, method, params)?;
     let result_as_tokens = convert_to_bytes(
         &json_value_to_token(&result).map_err(|err| fmt_err!("failed to parse result: {err}"))?,
     );
@@ -391,6 +479,14 @@ fn rpc_call(url: &str, method: &str, params: &str) -> Result {
     Ok(result_as_tokens.abi_encode())
 }
 
+/// Performs a JSON-RPC request with the given JSON array of parameters, returning its result.
+fn rpc_request(url: &str, method: &str, params: &str) -> Result<serde_json::Value> {
+    let provider = ProviderBuilder::new(url).build()?;
+    let params_json: serde_json::Value = serde_json::from_str(params)?;
+    foundry_common::block_on(provider.raw_request(method.to_string().into(), params_json))
+        .map_err(|err| fmt_err!("{method:?}: {err}"))
+}
+
 /// Convert fixed bytes and address values to bytes in order to prevent encoding issues.
 fn convert_to_bytes(token: &DynSolValue) -> DynSolValue {
     match token {
@@ -171,6 +171,26 @@ fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
     }
 }
 
+impl Cheatcode for mockCallsRevert_0Call {
+    fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
+        let Self { callee, data, revertData } = self;
+        let _ = make_acc_non_empty(callee, ccx.ecx)?;
+
+        mock_calls(ccx.state, callee, data, None, revertData, InstructionResult::Revert);
+        Ok(Default::default())
+    }
+}
+
+impl Cheatcode for mockCallsRevert_1Call {
+    fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
+        let Self { callee, msgValue, data, revertData } = self;
+        let _ = make_acc_non_empty(callee, ccx.ecx)?;
+
+        mock_calls(ccx.state, callee, data, Some(msgValue), revertData, InstructionResult::Revert);
+        Ok(Default::default())
+    }
+}
+
 impl Cheatcode for mockFunctionCall {
     fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self { callee, target, data } = self;
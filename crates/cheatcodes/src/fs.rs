@@ -5,30 +5,37 @@
 use alloy_dyn_abi::DynSolType;
 use alloy_json_abi::ContractObject;
 use alloy_network::AnyTransactionReceipt;
-use alloy_primitives::{hex, map::Entry, Bytes, U256};
+use alloy_primitives::{hex, map::Entry, Address, Bytes, U256};
 use alloy_provider::network::ReceiptResponse;
 use alloy_sol_types::SolValue;
 use dialoguer::{Input, Password};
 use forge_script_sequence::{BroadcastReader, TransactionWithMetadata};
 use foundry_common::fs;
-use foundry_config::fs_permissions::FsAccessKind;
-use revm::interpreter::CreateInputs;
+use foundry_compilers::utils::canonicalize;
+use foundry_config::{fs_permissions::FsAccessKind, FfiSandboxConfig};
+use revm::{
+    interpreter::{CallInputs, CallScheme, CallValue, CreateInputs},
+    primitives::Bytecode,
+};
 use revm_inspectors::tracing::types::CallKind;
 use semver::Version;
 use std::{
-    io::{BufRead, BufReader, Write},
+    io::{BufRead, BufReader, Read, Write},
     path::{Path, PathBuf},
-    process::Command,
-    sync::mpsc,
+    process::{Command, Output, Stdio},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc, Arc,
+    },
     thread,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 use walkdir::WalkDir;
 
 impl Cheatcode for existsCall {
     fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self { path } = self;
-        let path = state.config.ensure_path_allowed(path, FsAccessKind::Read)?;
+        let path = state.ensure_path_allowed(path, FsAccessKind::Read)?;
         Ok(path.exists().abi_encode())
     }
 }
@@ -36,7 +43,7 @@ fn apply(&self, state: &mut Cheatcodes) -> Result {
 impl Cheatcode for fsMetadataCall {
     fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self { path } = self;
-        let path = state.config.ensure_path_allowed(path, FsAccessKind::Read)?;
+        let path = state.ensure_path_allowed(path, FsAccessKind::Read)?;
 
         let metadata = path.metadata()?;
 
@@ -62,7 +69,7 @@ fn apply(&self, state: &mut Cheatcodes) -> Result {
 impl Cheatcode for isDirCall {
     fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self { path } = self;
-        let path = state.config.ensure_path_allowed(path, FsAccessKind::Read)?;
+        let path = state.ensure_path_allowed(path, FsAccessKind::Read)?;
         Ok(path.is_dir().abi_encode())
     }
 }
@@ -70,7 +77,7 @@ fn apply(&self, state: &mut Cheatcodes) -> Result {
 impl Cheatcode for isFileCall {
     fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self { path } = self;
-        let path = state.config.ensure_path_allowed(path, FsAccessKind::Read)?;
+        let path = state.ensure_path_allowed(path, FsAccessKind::Read)?;
         Ok(path.is_file().abi_encode())
     }
 }
@@ -95,7 +102,7 @@ fn apply(&self, _state: &mut Cheatcodes) -> Result {
 impl Cheatcode for closeFileCall {
     fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self { path } = self;
-        let path = state.config.ensure_path_allowed(path, FsAccessKind::Read)?;
+        let path = state.ensure_path_allowed(path, FsAccessKind::Read)?;
 
         state.context.opened_read_files.remove(&path);
 
@@ -106,8 +113,8 @@ fn apply(&self, state: &mut Cheatcodes) -> Result {
 impl Cheatcode for copyFileCall {
     fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self { from, to } = self;
-        let from = state.config.ensure_path_allowed(from, FsAccessKind::Read)?;
-        let to = state.config.ensure_path_allowed(to, FsAccessKind::Write)?;
+        let from = state.ensure_path_allowed(from, FsAccessKind::Read)?;
+        let to = state.ensure_path_allowed(to, FsAccessKind::Write)?;
         state.config.ensure_not_foundry_toml(&to)?;
 
         let n = fs::copy(from, to)?;
@@ -118,7 +125,7 @@ fn apply(&self, state: &mut Cheatcodes) -> Result {
 impl Cheatcode for createDirCall {
     fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self { path, recursive } = self;
-        let path = state.config.ensure_path_allowed(path, FsAccessKind::Write)?;
+        let path = state.ensure_path_allowed(path, FsAccessKind::Write)?;
         if *recursive { fs::create_dir_all(path) } else { fs::create_dir(path) }?;
         Ok(Default::default())
     }
@@ -148,7 +155,7 @@ fn apply(&self, state: &mut Cheatcodes) -> Result {
 impl Cheatcode for readFileCall {
     fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self { path } = self;
-        let path = state.config.ensure_path_allowed(path, FsAccessKind::Read)?;
+        let path = state.ensure_path_allowed(path, FsAccessKind::Read)?;
         Ok(fs::read_to_string(path)?.abi_encode())
     }
 }
@@ -156,7 +163,7 @@ fn apply(&self, state: &mut Cheatcodes) -> Result {
 impl Cheatcode for readFileBinaryCall {
     fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self { path } = self;
-        let path = state.config.ensure_path_allowed(path, FsAccessKind::Read)?;
+        let path = state.ensure_path_allowed(path, FsAccessKind::Read)?;
         Ok(fs::read(path)?.abi_encode())
     }
 }
@@ -164,7 +171,7 @@ fn apply(&self, state: &mut Cheatcodes) -> Result {
 impl Cheatcode for readLineCall {
     fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self { path } = self;
-        let path = state.config.ensure_path_allowed(path, FsAccessKind::Read)?;
+        let path = state.ensure_path_allowed(path, FsAccessKind::Read)?;
 
         // Get reader for previously opened file to continue reading OR initialize new reader
         let reader = match state.context.opened_read_files.entry(path.clone()) {
@@ -187,10 +194,31 @@ fn apply(&self, state: &mut Cheatcodes) -> Result {
     }
 }
 
+impl Cheatcode for createTempDirCall {
+    fn apply(&self, state: &mut Cheatcodes) -> Result {
+        let Self {} = self;
+        let dir = TempDir::new()?;
+        let path = dir.path().display().to_string();
+        state.context.temp_dirs.push(dir);
+        Ok(path.abi_encode())
+    }
+}
+
+impl Cheatcode for scratchDirCall {
+    fn apply(&self, state: &mut Cheatcodes) -> Result {
+        let Self {} = self;
+        let dir = match &state.context.scratch_dir {
+            Some(dir) => dir,
+            None => state.context.scratch_dir.insert(TempDir::new()?),
+        };
+        Ok(dir.path().display().to_string().abi_encode())
+    }
+}
+
 impl Cheatcode for readLinkCall {
     fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self { linkPath: path } = self;
-        let path = state.config.ensure_path_allowed(path, FsAccessKind::Read)?;
+        let path = state.ensure_path_allowed(path, FsAccessKind::Read)?;
         let target = fs::read_link(path)?;
         Ok(target.display().to_string().abi_encode())
     }
@@ -199,7 +227,7 @@ fn apply(&self, state: &mut Cheatcodes) -> Result {
 impl Cheatcode for removeDirCall {
     fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self { path, recursive } = self;
-        let path = state.config.ensure_path_allowed(path, FsAccessKind::Write)?;
+        let path = state.ensure_path_allowed(path, FsAccessKind::Write)?;
         if *recursive { fs::remove_dir_all(path) } else { fs::remove_dir(path) }?;
         Ok(Default::default())
     }
@@ -208,7 +236,7 @@ fn apply(&self, state: &mut Cheatcodes) -> Result {
 impl Cheatcode for removeFileCall {
     fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self { path } = self;
-        let path = state.config.ensure_path_allowed(path, FsAccessKind::Write)?;
+        let path = state.ensure_path_allowed(path, FsAccessKind::Write)?;
         state.config.ensure_not_foundry_toml(&path)?;
 
         // also remove from the set if opened previously
@@ -239,7 +267,7 @@ fn apply(&self, state: &mut Cheatcodes) -> Result {
 impl Cheatcode for writeLineCall {
     fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self { path, data: line } = self;
-        let path = state.config.ensure_path_allowed(path, FsAccessKind::Write)?;
+        let path = state.ensure_path_allowed(path, FsAccessKind::Write)?;
         state.config.ensure_not_foundry_toml(&path)?;
 
         if state.fs_commit {
@@ -339,6 +367,70 @@ fn apply_full(&self, ccx: &mut CheatsCtxt, executor: &mut dyn CheatcodesExecutor
     }
 }
 
+impl Cheatcode for deployCodeTo_0Call {
+    fn apply_full(&self, ccx: &mut CheatsCtxt, executor: &mut dyn CheatcodesExecutor) -> Result {
+        let Self { artifactPath: path, target } = self;
+        let bytecode = get_artifact_code(ccx.state, path, false)?;
+        deploy_code_to(ccx, executor, bytecode, *target)
+    }
+}
+
+impl Cheatcode for deployCodeTo_1Call {
+    fn apply_full(&self, ccx: &mut CheatsCtxt, executor: &mut dyn CheatcodesExecutor) -> Result {
+        let Self { artifactPath: path, constructorArgs, target } = self;
+        let mut bytecode = get_artifact_code(ccx.state, path, false)?.to_vec();
+        bytecode.extend_from_slice(constructorArgs);
+        deploy_code_to(ccx, executor, bytecode.into(), *target)
+    }
+}
+
+/// Deploys a contract to `target` by executing its creation bytecode as the code of `target`, so
+/// that the constructor runs at that address, and setting the returned runtime bytecode as the code
+/// of `target`.
+///
+/// All changes are reverted if the constructor reverts, and its revert data is returned.
+fn deploy_code_to(
+    ccx: &mut CheatsCtxt,
+    executor: &mut dyn CheatcodesExecutor,
+    init_code: Bytes,
+    target: Address,
+) -> Result {
+    ensure_not_precompile!(&target, ccx);
+    ccx.ecx.load_account(target)?;
+
+    let checkpoint = ccx.ecx.journaled_state.checkpoint();
+    // Contract nonces start at 1, as if the contract was created.
+    if ccx.ecx.journaled_state.state[&target].info.nonce == 0 {
+        ccx.ecx.journaled_state.inc_nonce(target);
+    }
+    ccx.ecx.journaled_state.set_code(target, Bytecode::new_raw(init_code));
+
+    let outcome = executor.exec_call(
+        CallInputs {
+            caller: ccx.caller,
+            bytecode_address: target,
+            target_address: target,
+            scheme: CallScheme::Call,
+            value: CallValue::Transfer(U256::ZERO),
+            input: Bytes::new(),
+            gas_limit: ccx.gas_limit,
+            is_static: false,
+            return_memory_offset: 0..0,
+            is_eof: false,
+        },
+        ccx,
+    )?;
+
+    if !outcome.result.is_ok() {
+        ccx.ecx.journaled_state.checkpoint_revert(checkpoint);
+        return Err(outcome.result.output.into())
+    }
+    ccx.ecx.journaled_state.set_code(target, Bytecode::new_raw(outcome.result.output));
+    ccx.ecx.journaled_state.checkpoint_commit();
+
+    Ok(Default::default())
+}
+
 /// Returns the path to the json artifact depending on the input
 ///
 /// Can parse following input formats:
@@ -471,7 +563,7 @@ fn get_artifact_code(state: &Cheatcodes, path: &str, deployed: bool) -> Result<B
         }
     };
 
-    let path = state.config.ensure_path_allowed(path, FsAccessKind::Read)?;
+    let path = state.ensure_path_allowed(path, FsAccessKind::Read)?;
     let data = fs::read_to_string(path)?;
     let artifact = serde_json::from_str::<ContractObject>(&data)?;
     let maybe_bytecode = if deployed { artifact.deployed_bytecode } else { artifact.bytecode };
@@ -536,7 +628,7 @@ fn apply(&self, state: &mut Cheatcodes) -> Result {
 }
 
 pub(super) fn write_file(state: &Cheatcodes, path: &Path, contents: &[u8]) -> Result {
-    let path = state.config.ensure_path_allowed(path, FsAccessKind::Write)?;
+    let path = state.ensure_path_allowed(path, FsAccessKind::Write)?;
     // write access to foundry.toml is not allowed
     state.config.ensure_not_foundry_toml(&path)?;
 
@@ -548,7 +640,7 @@ pub(super) fn write_file(state: &Cheatcodes, path: &Path, contents: &[u8]) -> Re
 }
 
 fn read_dir(state: &Cheatcodes, path: &Path, max_depth: u64, follow_links: bool) -> Result {
-    let root = state.config.ensure_path_allowed(path, FsAccessKind::Read)?;
+    let root = state.ensure_path_allowed(path, FsAccessKind::Read)?;
     let paths: Vec<DirEntry> = WalkDir::new(root)
         .min_depth(1)
         .max_depth(max_depth.try_into().unwrap_or(usize::MAX))
@@ -577,21 +669,97 @@ fn read_dir(state: &Cheatcodes, path: &Path, max_depth: u64, follow_links: bool)
     Ok(paths.abi_encode())
 }
 
+/// A directory created in the system's temporary directory, removed when dropped.
+#[derive(Debug)]
+pub(crate) struct TempDir(PathBuf);
+
+impl TempDir {
+    /// Creates a new, uniquely named directory.
+    fn new() -> std::io::Result<Self> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let parent = std::env::temp_dir();
+        loop {
+            let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+            let path = parent.join(format!("foundry-{}-{id}", std::process::id()));
+            match std::fs::create_dir(&path) {
+                Ok(()) => return Ok(Self(canonicalize(&path).unwrap_or(path))),
+                Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Returns the path of the directory.
+    pub(crate) fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
 fn ffi(state: &Cheatcodes, input: &[String]) -> Result<FfiResult> {
     ensure!(
         state.config.ffi,
         "FFI is disabled; add the `--ffi` flag to allow tests to call external commands"
     );
     ensure!(!input.is_empty() && !input[0].is_empty(), "can't execute empty command");
-    let mut cmd = Command::new(&input[0]);
-    cmd.args(&input[1..]);
+    let sandbox = &state.config.ffi_sandbox;
+
+    let working_dir = match &sandbox.working_dir {
+        Some(dir) => {
+            let dir = state.config.normalized_path(dir);
+            canonicalize(&dir).map_err(|err| {
+                fmt_err!("invalid FFI working directory `{}`: {err}", dir.display())
+            })?
+        }
+        None => state.config.root.clone(),
+    };
+
+    let mut program = PathBuf::from(&input[0]);
+    if !sandbox.allowed_commands.is_empty() || sandbox.working_dir.is_some() {
+        let is_path = program.components().count() > 1;
+        // Executables given as a path must be located inside of the working directory.
+        let ensure_inside = |path: &Path| {
+            ensure!(
+                !is_path || sandbox.working_dir.is_none() || path.starts_with(&working_dir),
+                "FFI command `{}` is outside of the working directory `{}`",
+                path.display(),
+                working_dir.display()
+            );
+            Ok(())
+        };
+        ensure_inside(&state.config.normalized_path(working_dir.join(&program)))?;
+        let resolved = resolve_program(&program, &working_dir)
+            .ok_or_else(|| fmt_err!("FFI command `{}` was not found", program.display()))?;
+        ensure_inside(&resolved)?;
+
+        // Compare the executables that would be invoked, so that a program of the same name in
+        // another directory isn't allowed.
+        let name = program.file_name();
+        ensure!(
+            sandbox.allowed_commands.is_empty() ||
+                sandbox.allowed_commands.iter().any(|allowed| {
+                    let allowed = Path::new(allowed);
+                    allowed.file_name() == name &&
+                        resolve_program(allowed, &working_dir).as_ref() == Some(&resolved)
+                }),
+            "FFI command `{}` is not allowed; add it to `ffi_sandbox.allowed_commands` to allow it",
+            program.display()
+        );
+        // Invoke the executable that was checked.
+        program = resolved;
+    }
+
+    let mut cmd = Command::new(&program);
+    cmd.args(&input[1..]).current_dir(working_dir);
 
     debug!(target: "cheatcodes", ?cmd, "invoking ffi");
 
-    let output = cmd
-        .current_dir(&state.config.root)
-        .output()
-        .map_err(|err| fmt_err!("failed to execute command {cmd:?}: {err}"))?;
+    let output = ffi_output(&mut cmd, sandbox)?;
 
     // The stdout might be encoded on valid hex, or it might just be a string,
     // so we need to determine which it is to avoid improperly encoding later.
@@ -609,6 +777,103 @@ fn ffi(state: &Cheatcodes, input: &[String]) -> Result<FfiResult> {
     })
 }
 
+/// Returns the canonical path of the executable invoked for `program`: relative to the working
+/// directory if it's given as a path, looked up in `PATH` otherwise.
+fn resolve_program(program: &Path, working_dir: &Path) -> Option<PathBuf> {
+    if program.components().count() > 1 {
+        return canonicalize(working_dir.join(program)).ok();
+    }
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .flat_map(|dir| {
+            let path = dir.join(program);
+            let exe = path.with_extension(std::env::consts::EXE_EXTENSION);
+            [path, exe]
+        })
+        .find(|path| path.is_file())
+        .and_then(|path| canonicalize(path).ok())
+}
+
+/// Executes the command, enforcing the timeout and output limits of the FFI sandbox.
+fn ffi_output(cmd: &mut Command, sandbox: &FfiSandboxConfig) -> Result<Output> {
+    if sandbox.timeout.is_none() && sandbox.max_output.is_none() {
+        return cmd.output().map_err(|err| fmt_err!("failed to execute command {cmd:?}: {err}"));
+    }
+
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| fmt_err!("failed to execute command {cmd:?}: {err}"))?;
+
+    // Read one byte past the limit to detect when it is exceeded.
+    let limit = sandbox.max_output.map_or(u64::MAX, |max| max as u64 + 1);
+    let exceeded = Arc::new(AtomicBool::new(false));
+    let (tx, rx) = mpsc::channel();
+    let read_pipe = |index: usize, pipe: Option<Box<dyn Read + Send>>| {
+        let exceeded = exceeded.clone();
+        let tx = tx.clone();
+        thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(pipe) = pipe {
+                let _ = pipe.take(limit).read_to_end(&mut buf);
+            }
+            if buf.len() as u64 >= limit {
+                exceeded.store(true, Ordering::Relaxed);
+            }
+            let _ = tx.send((index, buf));
+        });
+    };
+    read_pipe(0, child.stdout.take().map(|pipe| Box::new(pipe) as _));
+    read_pipe(1, child.stderr.take().map(|pipe| Box::new(pipe) as _));
+    drop(tx);
+
+    let exceeded_err = || {
+        fmt_err!(
+            "FFI command {cmd:?} exceeded the maximum output size of {} bytes",
+            sandbox.max_output.unwrap_or_default()
+        )
+    };
+    let timeout_err =
+        || fmt_err!("FFI command {cmd:?} timed out after {}s", sandbox.timeout.unwrap_or_default());
+
+    let deadline = sandbox.timeout.map(|secs| Instant::now() + Duration::from_secs(secs));
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+
+        let timed_out = deadline.is_some_and(|deadline| Instant::now() >= deadline);
+        if timed_out || exceeded.load(Ordering::Relaxed) {
+            // The pipes may still be held open by processes spawned by the command, so the
+            // readers are left behind instead of being waited for.
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(if timed_out { timeout_err() } else { exceeded_err() });
+        }
+
+        thread::sleep(Duration::from_millis(10));
+    };
+
+    // Processes spawned by the command may keep the pipes open after it exited, so the output is
+    // only waited for until the deadline.
+    let mut outputs = [Vec::new(), Vec::new()];
+    for _ in 0..outputs.len() {
+        let received = match deadline {
+            Some(deadline) => rx.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+            None => rx.recv().map_err(Into::into),
+        };
+        let (index, buf) = received.map_err(|_| timeout_err())?;
+        outputs[index] = buf;
+    }
+    if exceeded.load(Ordering::Relaxed) {
+        return Err(exceeded_err());
+    }
+
+    let [stdout, stderr] = outputs;
+    Ok(Output { status, stdout, stderr })
+}
+
 fn prompt_input(prompt_text: &str) -> Result<String, dialoguer::Error> {
     Input::new().allow_empty(true).with_prompt(prompt_text).interact_text()
 }
@@ -814,8 +1079,13 @@ mod tests {
     use std::sync::Arc;
 
     fn cheats() -> Cheatcodes {
+        sandboxed_cheats(Default::default())
+    }
+
+    fn sandboxed_cheats(ffi_sandbox: FfiSandboxConfig) -> Cheatcodes {
         let config = CheatsConfig {
             ffi: true,
+            ffi_sandbox,
             root: PathBuf::from(&env!("CARGO_MANIFEST_DIR")),
             ..Default::default()
         };
@@ -840,6 +1110,96 @@ fn test_ffi_string() {
         assert_eq!(output.stdout, Bytes::from(msg.as_bytes()));
     }
 
+    #[test]
+    fn test_ffi_sandbox_allowed_commands() {
+        let cheats = sandboxed_cheats(FfiSandboxConfig {
+            allowed_commands: vec!["echo".to_string()],
+            ..Default::default()
+        });
+        assert!(ffi(&cheats, &["echo".to_string(), "gm".to_string()]).is_ok());
+        let err = ffi(&cheats, &["ls".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("`ls` is not allowed"), "{err}");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_ffi_sandbox_allowed_commands_resolve_executable() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = std::env::temp_dir().join(format!("foundry-ffi-sandbox-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let fake = root.join("echo");
+        std::fs::write(&fake, "#!/bin/sh\necho fake\n").unwrap();
+        std::fs::set_permissions(&fake, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let config = CheatsConfig {
+            ffi: true,
+            ffi_sandbox: FfiSandboxConfig {
+                allowed_commands: vec!["echo".to_string()],
+                ..Default::default()
+            },
+            root: root.clone(),
+            ..Default::default()
+        };
+        let cheats = Cheatcodes::new(Arc::new(config));
+        // Same name as an allowed command, but not the executable found in `PATH`.
+        let err = ffi(&cheats, &["./echo".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("`./echo` is not allowed"), "{err}");
+        assert!(ffi(&cheats, &["echo".to_string(), "gm".to_string()]).is_ok());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_ffi_sandbox_timeout() {
+        let cheats = sandboxed_cheats(FfiSandboxConfig { timeout: Some(1), ..Default::default() });
+        let err = ffi(&cheats, &["sleep".to_string(), "10".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("timed out after 1s"), "{err}");
+    }
+
+    #[test]
+    fn test_ffi_sandbox_max_output() {
+        let cheats =
+            sandboxed_cheats(FfiSandboxConfig { max_output: Some(4), ..Default::default() });
+        let output = ffi(&cheats, &["echo".to_string(), "gm".to_string()]).unwrap();
+        assert_eq!(output.stdout, Bytes::from(b"gm"));
+        let err = ffi(&cheats, &["echo".to_string(), "gm gm gm".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("maximum output size of 4 bytes"), "{err}");
+    }
+
+    #[test]
+    fn test_ffi_sandbox_working_dir() {
+        let cheats = sandboxed_cheats(FfiSandboxConfig {
+            working_dir: Some("src".into()),
+            ..Default::default()
+        });
+        let output = ffi(&cheats, &["pwd".to_string()]).unwrap();
+        assert!(String::from_utf8_lossy(&output.stdout).ends_with("src"));
+        let err = ffi(&cheats, &["../../script.sh".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("outside of the working directory"), "{err}");
+    }
+
+    #[test]
+    fn test_temp_dirs() {
+        let mut cheats = cheats();
+        let decode = |out: Vec<u8>| PathBuf::from(String::abi_decode(&out, true).unwrap());
+        let temp_dir = decode(createTempDirCall {}.apply(&mut cheats).unwrap());
+        let scratch_dir = decode(scratchDirCall {}.apply(&mut cheats).unwrap());
+        assert_eq!(decode(scratchDirCall {}.apply(&mut cheats).unwrap()), scratch_dir);
+        assert_ne!(temp_dir, scratch_dir);
+        for dir in [&temp_dir, &scratch_dir] {
+            assert!(dir.is_dir());
+            assert!(cheats.ensure_path_allowed(dir.join("file"), FsAccessKind::Write).is_ok());
+        }
+
+        // removed and no longer accessible at the end of the test
+        cheats.context.clear();
+        for dir in [&temp_dir, &scratch_dir] {
+            assert!(!dir.exists());
+            assert!(cheats.ensure_path_allowed(dir.join("file"), FsAccessKind::Write).is_err());
+        }
+    }
+
     #[test]
     fn test_artifact_parsing() {
         let s = include_str!("../../evm/test-data/solc-obj.json");
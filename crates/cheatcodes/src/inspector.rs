@@ -7,13 +7,14 @@
         prank::Prank,
         DealRecord, GasRecord, RecordAccess,
     },
-    inspector::utils::CommonCreateInput,
+    fs::TempDir,
+    inspector::{memory_safety::MemorySafety, memory_usage::MemoryUsage, utils::CommonCreateInput},
     script::{Broadcast, Wallets},
     test::{
         assume::AssumeNoRevert,
         expect::{
             self, ExpectedCallData, ExpectedCallTracker, ExpectedCallType, ExpectedEmitTracker,
-            ExpectedRevert, ExpectedRevertKind,
+            ExpectedGasUsed, ExpectedRevert, ExpectedRevertKind, ExpectedSignature,
         },
         revert_handlers,
     },
@@ -29,6 +30,7 @@
 use alloy_rpc_types::request::{TransactionInput, TransactionRequest};
 use alloy_sol_types::{SolCall, SolInterface, SolValue};
 use foundry_common::{evm::Breakpoints, TransactionMaybeSigned, SELECTOR_LEN};
+use foundry_config::fs_permissions::FsAccessKind;
 use foundry_evm_core::{
     abi::Vm::stopExpectSafeMemoryCall,
     backend::{DatabaseError, DatabaseExt, RevertDiagnostic},
@@ -45,7 +47,7 @@
     interpreter::{
         opcode as op, CallInputs, CallOutcome, CallScheme, CallValue, CreateInputs, CreateOutcome,
         EOFCreateInputs, EOFCreateKind, Gas, InstructionResult, Interpreter, InterpreterAction,
-        InterpreterResult,
+        InterpreterResult, OpCode,
     },
     primitives::{
         BlockEnv, CreateScheme, EVMError, EvmStorageSlot, SignedAuthorization, SpecId,
@@ -60,10 +62,14 @@
     fs::File,
     io::BufReader,
     ops::Range,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::Arc,
 };
 
+mod memory_safety;
+pub use memory_safety::MemorySafetyViolation;
+
+mod memory_usage;
 mod utils;
 
 pub type Ecx<'a, 'b, 'c> = &'a mut EvmContext<&'b mut (dyn DatabaseExt + 'c)>;
@@ -124,6 +130,36 @@ fn exec_create(
         })
     }
 
+    /// Obtains [revm::Evm] instance and executes the given CALL frame.
+    fn exec_call(
+        &mut self,
+        inputs: CallInputs,
+        ccx: &mut CheatsCtxt,
+    ) -> Result<CallOutcome, EVMError<DatabaseError>> {
+        with_evm(self, ccx, |evm| {
+            evm.context.evm.inner.journaled_state.depth += 1;
+
+            let first_frame_or_result =
+                evm.handler.execution().call(&mut evm.context, Box::new(inputs))?;
+
+            let mut result = match first_frame_or_result {
+                revm::FrameOrResult::Frame(first_frame) => evm.run_the_loop(first_frame)?,
+                revm::FrameOrResult::Result(result) => result,
+            };
+
+            evm.handler.execution().last_frame_return(&mut evm.context, &mut result)?;
+
+            let outcome = match result {
+                revm::FrameResult::Call(call) => call,
+                revm::FrameResult::Create(_) | revm::FrameResult::EOFCreate(_) => unreachable!(),
+            };
+
+            evm.context.evm.inner.journaled_state.depth -= 1;
+
+            Ok(outcome)
+        })
+    }
+
     fn console_log(&mut self, ccx: &mut CheatsCtxt, msg: &str) {
         self.get_inspector(ccx.state).console_log(msg);
     }
@@ -198,6 +234,10 @@ macro_rules! try_or_return {
 pub struct Context {
     /// Buffered readers for files opened for reading (path => BufReader mapping)
     pub opened_read_files: HashMap<PathBuf, BufReader<File>>,
+    /// Directories created by `createTempDir`, removed when the context is dropped.
+    pub(crate) temp_dirs: Vec<TempDir>,
+    /// The directory returned by `scratchDir`, removed when the context is dropped.
+    pub(crate) scratch_dir: Option<TempDir>,
 }
 
 /// Every time we clone `Context`, we want it to be empty
@@ -212,6 +252,14 @@ impl Context {
     #[inline]
     pub fn clear(&mut self) {
         self.opened_read_files.clear();
+        self.temp_dirs.clear();
+        self.scratch_dir = None;
+    }
+
+    /// Returns whether the normalized path is in one of the temporary directories of the test,
+    /// which can be read and written regardless of the `fs_permissions`.
+    pub(crate) fn is_in_temp_dir(&self, path: &Path) -> bool {
+        self.temp_dirs.iter().chain(&self.scratch_dir).any(|dir| path.starts_with(dir.path()))
     }
 }
 
@@ -431,10 +479,20 @@ pub struct Cheatcodes {
     pub expected_calls: ExpectedCallTracker,
     /// Expected emits
     pub expected_emits: ExpectedEmitTracker,
+    /// Expected gas usage of the next call
+    pub expected_gas_used: Option<ExpectedGasUsed>,
+    /// Expected ERC-1271 signature validations
+    pub expected_signatures: Vec<ExpectedSignature>,
 
     /// Map of context depths to memory offset ranges that may be written to within the call depth.
     pub allowed_mem_writes: HashMap<u64, Vec<Range<u64>>>,
 
+    /// Memory written by the frames being executed, when memory safety checks are enabled.
+    pub memory_safety: MemorySafety,
+
+    /// The memory used by the interpreter, when it is reported or limited.
+    pub memory_usage: Option<MemoryUsage>,
+
     /// Current broadcasting information
     pub broadcast: Option<Broadcast>,
 
@@ -502,6 +560,7 @@ fn default() -> Self {
 impl Cheatcodes {
     /// Creates a new `Cheatcodes` with the given settings.
     pub fn new(config: Arc<CheatsConfig>) -> Self {
+        let memory_usage = config.memory_report || config.test_memory_limit.is_some();
         Self {
             fs_commit: true,
             labels: config.labels.clone(),
@@ -521,7 +580,11 @@ pub fn new(config: Arc<CheatsConfig>) -> Self {
             mocked_functions: Default::default(),
             expected_calls: Default::default(),
             expected_emits: Default::default(),
+            expected_gas_used: Default::default(),
+            expected_signatures: Default::default(),
             allowed_mem_writes: Default::default(),
+            memory_safety: Default::default(),
+            memory_usage: memory_usage.then(Default::default),
             broadcast: Default::default(),
             broadcastable_transactions: Default::default(),
             context: Default::default(),
@@ -550,6 +613,20 @@ pub fn set_wallets(&mut self, wallets: Wallets) {
         self.wallets = Some(wallets);
     }
 
+    /// Returns the normalized path if it's allowed to be accessed for `kind` operations, either by
+    /// the `fs_permissions` or because it's in a temporary directory of the test.
+    pub fn ensure_path_allowed(
+        &self,
+        path: impl AsRef<Path>,
+        kind: FsAccessKind,
+    ) -> Result<PathBuf> {
+        let normalized = self.config.normalized_path(&path);
+        if self.context.is_in_temp_dir(&normalized) {
+            return Ok(normalized);
+        }
+        self.config.ensure_path_allowed(path, kind)
+    }
+
     /// Decodes the input data and applies the cheatcode.
     fn apply_cheatcode(
         &mut self,
@@ -1159,6 +1236,19 @@ pub fn is_arbitrary_storage_copy(&self, address: &Address) -> bool {
             None => false,
         }
     }
+
+    /// Whether calls must be executed, rather than having their results reused, because cheatcodes
+    /// record or alter the calls made or the state they access.
+    pub fn observes_calls(&self) -> bool {
+        self.accesses.is_some() ||
+            self.recorded_account_diffs_stack.is_some() ||
+            self.record_debug_steps_info.is_some() ||
+            self.mapping_slots.is_some() ||
+            self.arbitrary_storage.is_some() ||
+            !self.mocked_calls.is_empty() ||
+            !self.mocked_functions.is_empty() ||
+            !self.expected_calls.is_empty()
+    }
 }
 
 impl Inspector<&mut dyn DatabaseExt> for Cheatcodes {
@@ -1182,6 +1272,12 @@ fn initialize_interp(&mut self, interpreter: &mut Interpreter, ecx: Ecx) {
         if let Some(expected) = &mut self.expected_revert {
             expected.max_depth = max(ecx.journaled_state.depth(), expected.max_depth);
         }
+
+        // `memory_safety`: track the memory written by the new frame.
+        if self.config.memory_safety {
+            let code = interpreter.contract.bytecode.original_byte_slice();
+            self.memory_safety.enter(ecx.journaled_state.depth(), code);
+        }
     }
 
     #[inline]
@@ -1213,6 +1309,16 @@ fn step(&mut self, interpreter: &mut Interpreter, ecx: Ecx) {
             self.check_mem_opcodes(interpreter, ecx.journaled_state.depth());
         }
 
+        // `memory_safety`: check that memory is used as solc expects.
+        if self.config.memory_safety {
+            self.check_memory_safety(interpreter, ecx.journaled_state.depth());
+        }
+
+        // `memory_report` and `test_memory_limit`: record the opcode that may expand memory.
+        if let Some(memory_usage) = &mut self.memory_usage {
+            memory_usage.op = (interpreter.current_opcode(), self.pc);
+        }
+
         // `startMappingRecording`: record SSTORE and KECCAK256.
         if let Some(mapping_slots) = &mut self.mapping_slots {
             mapping::step(mapping_slots, interpreter);
@@ -1238,6 +1344,11 @@ fn step_end(&mut self, interpreter: &mut Interpreter, ecx: Ecx) {
         if self.arbitrary_storage.is_some() {
             self.arbitrary_storage_end(interpreter, ecx);
         }
+
+        // `memory_report` and `test_memory_limit`: track the memory used by the frames.
+        if self.memory_usage.is_some() {
+            self.check_memory_usage(interpreter, ecx.journaled_state.depth());
+        }
     }
 
     fn log(&mut self, interpreter: &mut Interpreter, _ecx: Ecx, log: &Log) {
@@ -1416,6 +1527,31 @@ fn call_end(&mut self, ecx: Ecx, call: &CallInputs, mut outcome: CallOutcome) ->
             gasRemaining: gas.remaining(),
         });
 
+        // Check the gas usage of the call if it was expected with `expectGasUsed`.
+        if self
+            .expected_gas_used
+            .as_ref()
+            .is_some_and(|expected| ecx.journaled_state.depth() == expected.depth)
+        {
+            let expected = self.expected_gas_used.take().unwrap();
+            // Calls made by the test contract are executed as separate transactions in isolation
+            // mode, in which case the recorded gas includes the intrinsic cost and refunds.
+            let isolated = self.config.evm_opts.isolate &&
+                call.scheme == CallScheme::Call &&
+                ecx.journaled_state.depth() == 1;
+            if let Err(msg) = expected.check(gas.spent(), isolated) {
+                if outcome.result.is_ok() {
+                    outcome.result.result = InstructionResult::Revert;
+                    outcome.result.output = Error::encode(msg);
+                }
+            }
+        }
+
+        // Match the calls to `isValidSignature` expected with `expectValidSignature`.
+        if !self.expected_signatures.is_empty() {
+            expect::match_expected_signatures(&mut self.expected_signatures, call, &outcome.result);
+        }
+
         // If `startStateDiffRecording` has been called, update the `reverted` status of the
         // previous call depth's recorded accesses, if any
         if let Some(recorded_account_diffs_stack) = &mut self.recorded_account_diffs_stack {
@@ -1613,6 +1749,29 @@ fn call_end(&mut self, ecx: Ecx, call: &CallInputs, mut outcome: CallOutcome) ->
                     }
                 }
             }
+            // Check if the expected gas usage was never matched by a call
+            if let Some(expected) = self.expected_gas_used.take() {
+                let msg = match expected.snapshot {
+                    Some(snapshot) => format!(
+                        "expected a call to use the gas of snapshot `{snapshot}`, \
+                         but no call was made afterwards"
+                    ),
+                    None => "expected a call to use a specific amount of gas, \
+                             but no call was made afterwards"
+                        .to_string(),
+                };
+                outcome.result.result = InstructionResult::Revert;
+                outcome.result.output = Error::encode(msg);
+                return outcome;
+            }
+            // Check if any expected signature was never accepted
+            if let Some(expected) = self.expected_signatures.first() {
+                let msg = expected.error();
+                self.expected_signatures.clear();
+                outcome.result.result = InstructionResult::Revert;
+                outcome.result.output = Error::encode(msg);
+                return outcome;
+            }
             // Check if we have any leftover expected emits
             // First, if any emits were found at the root call, then we its ok and we remove them.
             self.expected_emits.retain(|(expected, _)| expected.count > 0 && !expected.found);
@@ -2118,6 +2277,92 @@ macro_rules! mem_opcode_match {
             (REVERT, 0, 1, false),
         );
     }
+
+    /// Checks the memory accessed by the current opcode, and reverts if it breaks the memory
+    /// model of solc. The first violation is recorded to fail the test even if the revert is
+    /// caught.
+    #[cold]
+    fn check_memory_safety(&mut self, interpreter: &mut Interpreter, depth: u64) {
+        let Some(description) = self.memory_safety.step(interpreter, depth) else { return };
+
+        let code = interpreter.contract.bytecode.original_byte_slice();
+        let (artifact, init_code) = self
+            .config
+            .available_artifacts
+            .as_ref()
+            .and_then(|artifacts| {
+                artifacts
+                    .find_by_deployed_code_exact(code)
+                    .map(|artifact| (artifact, false))
+                    .or_else(|| artifacts.find_by_creation_code(code).map(|a| (a, true)))
+            })
+            .map_or((None, false), |((id, _), init_code)| (Some(id), init_code));
+        let violation = MemorySafetyViolation {
+            contract: artifact.map_or_else(
+                || interpreter.contract.target_address.to_string(),
+                |id| id.identifier(),
+            ),
+            name: artifact.map(|id| id.name.clone()),
+            init_code,
+            pc: interpreter.program_counter(),
+            description,
+        };
+        revert_with_message(interpreter, violation.to_string());
+        self.memory_safety.violation.get_or_insert(violation);
+    }
+
+    /// Records the memory and stack used by the frames after a step and, when a test memory limit
+    /// is set, reverts if they exceed it or if the interpreter ran out of memory.
+    fn check_memory_usage(&mut self, interpreter: &mut Interpreter, depth: u64) {
+        let Some(memory_usage) = &mut self.memory_usage else { return };
+        let used = interpreter.shared_memory.len() + interpreter.stack.len() * 32;
+        let total = memory_usage.step_end(depth as usize, used);
+
+        // Only recording the peak, the interpreter's own errors are left untouched.
+        let Some(limit) = self.config.test_memory_limit else { return };
+        let exceeded = total as u64 > limit;
+        let out_of_memory = matches!(
+            interpreter.instruction_result,
+            InstructionResult::MemoryLimitOOG | InstructionResult::MemoryOOG
+        );
+        if !exceeded && !out_of_memory {
+            return
+        }
+
+        let (op, pc) = memory_usage.op;
+        let op = OpCode::new(op).map_or_else(|| format!("{op:#04x}"), |op| op.to_string());
+        let location =
+            format!("{} at {op} (pc {pc}, depth {depth})", self.contract_name(interpreter));
+        let reason = if exceeded {
+            format!(
+                "test memory limit of {limit} bytes exceeded: {total} bytes used after {location}"
+            )
+        } else {
+            format!(
+                "{:?} with {total} bytes used after {location}",
+                interpreter.instruction_result
+            )
+        };
+        if let Some(memory_usage) = &mut self.memory_usage {
+            memory_usage.exceeded.get_or_insert_with(|| reason.clone());
+        }
+        revert_with_message(interpreter, reason);
+    }
+
+    /// Returns the identifier of the artifact of the contract being executed, or its address.
+    fn contract_name(&self, interpreter: &Interpreter) -> String {
+        let code = interpreter.contract.bytecode.original_byte_slice();
+        self.config
+            .available_artifacts
+            .as_ref()
+            .and_then(|artifacts| {
+                artifacts
+                    .find_by_deployed_code_exact(code)
+                    .or_else(|| artifacts.find_by_creation_code(code))
+            })
+            .map(|(id, _)| id.identifier())
+            .unwrap_or_else(|| interpreter.contract.target_address.to_string())
+    }
 }
 
 /// Helper that expands memory, stores a revert string pertaining to a disallowed memory write,
@@ -2137,7 +2382,11 @@ fn disallowed_mem_write(
         size,
         ranges.iter().map(|r| format!("(0x{:02X}, 0x{:02X}]", r.start, r.end)).join(" U ")
     );
+    revert_with_message(interpreter, revert_string);
+}
 
+/// Reverts the current frame with the given revert string.
+fn revert_with_message(interpreter: &mut Interpreter, revert_string: String) {
     interpreter.instruction_result = InstructionResult::Revert;
     interpreter.next_action = InterpreterAction::Return {
         result: InterpreterResult {
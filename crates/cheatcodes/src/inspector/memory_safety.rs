@@ -0,0 +1,191 @@
+//! Checks that Solidity contracts follow the memory model of the compiler, enabled with the
+//! `memory_safety` config.
+
+use alloy_primitives::{hex, map::HashMap, U256};
+use revm::interpreter::{opcode as op, Interpreter};
+use std::{fmt, ops::Range};
+
+/// Memory reserved by solc for the free memory pointer.
+const FREE_MEMORY_POINTER: usize = 0x40;
+/// Memory reserved by solc as the initial value of empty dynamic memory arrays, which must always
+/// be zero.
+const ZERO_SLOT: Range<usize> = 0x60..0x80;
+/// Start of the memory allocated by solc.
+const FREE_MEMORY_START: usize = 0x80;
+/// Writes past this offset are not tracked, as they run out of gas anyway.
+const MAX_TRACKED_MEMORY: usize = 1 << 24;
+/// `PUSH1 0x80 PUSH1 0x40 MSTORE`, which initializes the free memory pointer at the start of all
+/// contracts compiled by solc.
+const SOLC_PREFIX: [u8; 5] = hex!("6080604052");
+
+/// Tracks the memory written by the frames being executed.
+#[derive(Clone, Debug, Default)]
+pub struct MemorySafety {
+    /// The bytes of memory written, by call depth. `None` for frames whose code wasn't compiled
+    /// by solc, as they may use memory freely.
+    frames: HashMap<u64, Option<Vec<bool>>>,
+    /// The first violation of the test. It fails the test even if the reverted frame was caught.
+    pub violation: Option<MemorySafetyViolation>,
+}
+
+/// A violation of the memory model of solc.
+#[derive(Clone, Debug)]
+pub struct MemorySafetyViolation {
+    /// The identifier of the artifact of the contract, or its address.
+    pub contract: String,
+    /// The name of the artifact of the contract, if known.
+    pub name: Option<String>,
+    /// Whether the violation happened in the creation code of the contract.
+    pub init_code: bool,
+    /// The program counter of the opcode breaking the memory model.
+    pub pc: usize,
+    /// The description of the violation.
+    pub description: String,
+}
+
+impl fmt::Display for MemorySafetyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { contract, pc, description, .. } = self;
+        write!(f, "memory safety violation in {contract} at pc {pc}: {description}")
+    }
+}
+
+impl MemorySafety {
+    /// Starts tracking the memory of a new frame at the given depth.
+    pub fn enter(&mut self, depth: u64, code: &[u8]) {
+        self.frames.insert(depth, code.starts_with(&SOLC_PREFIX).then(Vec::new));
+    }
+
+    /// Checks the memory accessed by the current opcode, returning the description of the
+    /// violation if any.
+    pub fn step(&mut self, interpreter: &Interpreter, depth: u64) -> Option<String> {
+        let written = self.frames.get_mut(&depth)?.as_mut()?;
+        let memory = &interpreter.shared_memory;
+        let free_pointer = if memory.len() >= FREE_MEMORY_POINTER + 32 {
+            memory.get_u256(FREE_MEMORY_POINTER).saturating_to()
+        } else {
+            FREE_MEMORY_START
+        };
+        check(written, interpreter.current_opcode(), free_pointer, |n| {
+            interpreter.stack().peek(n).ok()
+        })
+    }
+}
+
+fn check(
+    written: &mut Vec<bool>,
+    opcode: u8,
+    free_pointer: usize,
+    peek: impl Fn(usize) -> Option<U256>,
+) -> Option<String> {
+    let offset = |n| peek(n).map(|v| v.saturating_to::<usize>());
+    // The destination range of copies to memory, and of the output of calls.
+    let dest = |offset_depth, size_depth| {
+        let start = offset(offset_depth)?;
+        Some(start..start.saturating_add(offset(size_depth)?))
+    };
+
+    match opcode {
+        op::MSTORE => {
+            let offset = offset(0)?;
+            let value = peek(1)?;
+            if offset == FREE_MEMORY_POINTER && value < U256::from(FREE_MEMORY_START) {
+                return Some(format!("free memory pointer set to reserved memory at {value:#x}"));
+            }
+            let bytes = value.to_be_bytes::<32>();
+            if bytes
+                .iter()
+                .enumerate()
+                .any(|(i, b)| *b != 0 && ZERO_SLOT.contains(&offset.saturating_add(i)))
+            {
+                return Some(format!("non-zero value written to the zero slot at {offset:#x}"));
+            }
+            mark_written(written, offset..offset.saturating_add(32));
+        }
+        op::MSTORE8 => {
+            let offset = offset(0)?;
+            if peek(1)?.byte(0) != 0 && ZERO_SLOT.contains(&offset) {
+                return Some(format!("non-zero value written to the zero slot at {offset:#x}"));
+            }
+            mark_written(written, offset..offset.saturating_add(1));
+        }
+        op::CALLDATACOPY | op::CODECOPY | op::RETURNDATACOPY | op::MCOPY | op::EXTCODECOPY => {
+            let range = if opcode == op::EXTCODECOPY { dest(1, 3)? } else { dest(0, 2)? };
+            if !range.is_empty() && range.start < ZERO_SLOT.end && ZERO_SLOT.start < range.end {
+                return Some(format!(
+                    "copy to {:#x}..{:#x} overwrites the zero slot",
+                    range.start, range.end
+                ));
+            }
+            mark_written(written, range);
+        }
+        op::CALL | op::CALLCODE => mark_written(written, dest(5, 6)?),
+        op::DELEGATECALL | op::STATICCALL => mark_written(written, dest(4, 5)?),
+        // Memory past the free memory pointer is scratch space, which solc reads without
+        // initializing it, e.g. when decoding return data.
+        op::MLOAD => {
+            let offset = offset(0)?;
+            if (FREE_MEMORY_START..free_pointer).contains(&offset) &&
+                !written.get(offset..).unwrap_or_default().iter().take(32).any(|w| *w)
+            {
+                return Some(format!("read of uninitialized memory at {offset:#x}"));
+            }
+        }
+        _ => {}
+    }
+    None
+}
+
+fn mark_written(written: &mut Vec<bool>, range: Range<usize>) {
+    if range.is_empty() || range.end > MAX_TRACKED_MEMORY {
+        return;
+    }
+    if written.len() < range.end {
+        written.resize(range.end, false);
+    }
+    written[range].fill(true);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(written: &mut Vec<bool>, opcode: u8, stack: &[u64]) -> Option<String> {
+        check(written, opcode, 0x100, |n| stack.get(n).map(|v| U256::from(*v)))
+    }
+
+    #[test]
+    fn memory_violations() {
+        let mut written = Vec::new();
+        assert_eq!(step(&mut written, op::MSTORE, &[0x40, 0x80]), None);
+        assert_eq!(
+            step(&mut written, op::MSTORE, &[0x40, 0x20]).as_deref(),
+            Some("free memory pointer set to reserved memory at 0x20")
+        );
+        assert_eq!(step(&mut written, op::MSTORE, &[0x60, 0]), None);
+        assert_eq!(
+            step(&mut written, op::MSTORE8, &[0x7f, 1]).as_deref(),
+            Some("non-zero value written to the zero slot at 0x7f")
+        );
+        // The only non-zero byte of the value lands right after the zero slot.
+        assert_eq!(step(&mut written, op::MSTORE, &[0x80 - 31, 0xff]), None);
+        assert!(step(&mut written, op::CALLDATACOPY, &[0x70, 0, 0x20]).is_some());
+        assert_eq!(step(&mut written, op::CALLDATACOPY, &[0x70, 0, 0]), None);
+
+        assert_eq!(
+            step(&mut written, op::MLOAD, &[0xa0]).as_deref(),
+            Some("read of uninitialized memory at 0xa0")
+        );
+        assert_eq!(step(&mut written, op::MLOAD, &[0x80]), None);
+        assert_eq!(step(&mut written, op::STATICCALL, &[0, 0, 0, 0, 0xb0, 0x20]), None);
+        assert_eq!(step(&mut written, op::MLOAD, &[0xa0]), None);
+        assert_eq!(step(&mut written, op::MLOAD, &[0x20]), None);
+        // Scratch space past the free memory pointer.
+        assert_eq!(step(&mut written, op::MLOAD, &[0x100]), None);
+        assert_eq!(step(&mut written, op::MLOAD, &[0x140]), None);
+        assert_eq!(
+            check(&mut written, op::MLOAD, 0x180, |_| Some(U256::from(0x140))).as_deref(),
+            Some("read of uninitialized memory at 0x140")
+        );
+    }
+}
@@ -0,0 +1,51 @@
+//! Tracks the memory used by the interpreter during a test, enabled with the `memory_report` and
+//! `test_memory_limit` configs.
+
+/// The memory and stack used by the frames being executed, and their peak over the test.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryUsage {
+    /// The bytes of memory and stack used by each frame being executed, by call depth.
+    frames: Vec<usize>,
+    /// The sum of `frames`.
+    current: usize,
+    /// The peak of `current` over the test.
+    pub peak: usize,
+    /// The opcode and program counter of the current step.
+    pub(crate) op: (u8, usize),
+    /// The description of where the test memory limit was exceeded, if it was.
+    pub exceeded: Option<String>,
+}
+
+impl MemoryUsage {
+    /// Records the bytes used by the frame at `depth` after a step, and returns the bytes used by
+    /// all the frames.
+    pub fn step_end(&mut self, depth: usize, used: usize) -> usize {
+        // Deeper frames have returned, freeing their memory.
+        if self.frames.len() > depth + 1 {
+            self.current -= self.frames.drain(depth + 1..).sum::<usize>();
+        } else if self.frames.len() <= depth {
+            self.frames.resize(depth + 1, 0);
+        }
+        self.current = self.current - self.frames[depth] + used;
+        self.frames[depth] = used;
+        self.peak = self.peak.max(self.current);
+        self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn track_frames() {
+        let mut usage = MemoryUsage::default();
+        assert_eq!(usage.step_end(0, 64), 64);
+        assert_eq!(usage.step_end(1, 96), 160);
+        assert_eq!(usage.step_end(2, 32), 192);
+        // The frames at depth 1 and 2 returned.
+        assert_eq!(usage.step_end(0, 128), 128);
+        assert_eq!(usage.step_end(1, 32), 160);
+        assert_eq!(usage.peak, 192);
+    }
+}
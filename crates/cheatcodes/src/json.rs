@@ -348,7 +348,7 @@ fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self { json, path, valueKey } = self;
         let json = serde_json::from_str(json).unwrap_or_else(|_| Value::String(json.to_owned()));
 
-        let data_path = state.config.ensure_path_allowed(path, FsAccessKind::Read)?;
+        let data_path = state.ensure_path_allowed(path, FsAccessKind::Read)?;
         let data_s = fs::read_to_string(data_path)?;
         let data = serde_json::from_str(&data_s)?;
         let value =
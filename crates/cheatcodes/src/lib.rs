@@ -24,6 +24,7 @@
 pub use error::{Error, ErrorKind, Result};
 pub use inspector::{
     BroadcastableTransaction, BroadcastableTransactions, Cheatcodes, CheatcodesExecutor, Context,
+    MemorySafetyViolation,
 };
 pub use spec::{CheatcodeDef, Vm};
 pub use Vm::ForgeContext;
@@ -4,7 +4,7 @@
 use alloy_primitives::Address;
 use alloy_sol_types::SolValue;
 use foundry_common::version::SEMVER_VERSION;
-use foundry_evm_core::constants::MAGIC_SKIP;
+use foundry_evm_core::{backend::request_rpc_error_retries, constants::MAGIC_SKIP};
 
 pub(crate) mod assert;
 pub(crate) mod assume;
@@ -84,6 +84,27 @@ fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
     }
 }
 
+impl Cheatcode for skipUnlessEnvCall {
+    fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
+        let Self { name } = self;
+        let is_set = std::env::var(name).is_ok_and(|value| !value.is_empty());
+        let reason = format!("environment variable `{name}` is not set");
+        skip_1Call { skipTest: !is_set, reason }.apply_stateful(ccx)
+    }
+}
+
+impl Cheatcode for retryOnRpcErrorCall {
+    fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
+        let Self { maxRetries } = *self;
+        ensure!(
+            ccx.ecx.journaled_state.depth() <= 1,
+            "`retryOnRpcError` can only be used at test level"
+        );
+        request_rpc_error_retries(maxRetries.saturating_to());
+        Ok(Default::default())
+    }
+}
+
 /// Adds or removes the given breakpoint to the state.
 fn breakpoint(state: &mut Cheatcodes, caller: &Address, s: &str, add: bool) -> Result {
     let mut chars = s.chars();
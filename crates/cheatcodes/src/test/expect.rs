@@ -1,11 +1,15 @@
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
 
-use crate::{Cheatcode, Cheatcodes, CheatsCtxt, Error, Result, Vm::*};
+use crate::{evm::derive_snapshot_name, Cheatcode, Cheatcodes, CheatsCtxt, Error, Result, Vm::*};
 use alloy_primitives::{
+    hex,
     map::{hash_map::Entry, AddressHashMap, HashMap},
-    Address, Bytes, LogData as RawLog, U256,
+    Address, Bytes, LogData as RawLog, B256, U256,
+};
+use foundry_common::fs::read_json_file;
+use revm::interpreter::{
+    CallInputs, CallScheme, InstructionResult, Interpreter, InterpreterAction, InterpreterResult,
 };
-use revm::interpreter::{InstructionResult, Interpreter, InterpreterAction, InterpreterResult};
 
 use super::revert_handlers::RevertParameters;
 /// Tracks the expected calls per address.
@@ -80,6 +84,98 @@ pub struct ExpectedRevert {
     pub actual_count: u64,
 }
 
+/// An expectation on the gas used by the next call, set with `expectGasUsed`.
+#[derive(Clone, Debug)]
+pub struct ExpectedGasUsed {
+    /// The depth at which the call is expected to be made.
+    pub depth: u64,
+    /// The minimum amount of gas the call is expected to use.
+    pub min_gas: u64,
+    /// The maximum amount of gas the call is expected to use.
+    pub max_gas: u64,
+    /// The `group/name` of the snapshot entry the expectation was derived from, if any.
+    pub snapshot: Option<String>,
+}
+
+impl ExpectedGasUsed {
+    /// Checks the gas used by a call against the expectation, returning an error message on
+    /// mismatch.
+    pub fn check(&self, gas_used: u64, isolated: bool) -> Result<(), String> {
+        if (self.min_gas..=self.max_gas).contains(&gas_used) {
+            return Ok(());
+        }
+        let expected = if self.min_gas == self.max_gas {
+            self.min_gas.to_string()
+        } else {
+            format!("between {} and {}", self.min_gas, self.max_gas)
+        };
+        let mut msg = format!("expected call to use {expected} gas, but it used {gas_used}");
+        if let Some(snapshot) = &self.snapshot {
+            msg.push_str(&format!(" (snapshot `{snapshot}`)"));
+        }
+        if isolated {
+            msg.push_str("; gas includes the transaction's intrinsic cost in isolation mode");
+        }
+        Err(msg)
+    }
+}
+
+/// The selector of the ERC-1271 `isValidSignature(bytes32,bytes)`, which is also the magic value it
+/// returns for valid signatures.
+const ERC1271_MAGIC_VALUE: [u8; 4] = hex!("1626ba7e");
+
+/// An expectation that an ERC-1271 account accepts a signature of a digest, set with
+/// `expectValidSignature`.
+#[derive(Clone, Debug)]
+pub struct ExpectedSignature {
+    /// The account expected to accept the signature.
+    pub account: Address,
+    /// The digest that was signed.
+    pub digest: B256,
+    /// How the last call to `isValidSignature` with the digest failed, if any.
+    pub rejection: Option<String>,
+}
+
+impl ExpectedSignature {
+    /// Returns the error message of the expectation, when it wasn't met by the end of the test.
+    pub fn error(&self) -> String {
+        let Self { account, digest, rejection } = self;
+        let but = rejection.as_deref().unwrap_or("it was never called");
+        format!(
+            "expected `isValidSignature` of {account} to accept a signature of {digest}, but {but}"
+        )
+    }
+}
+
+/// Matches a call against the signatures expected with `expectValidSignature`, removing the
+/// expectations that are met by the call.
+pub(crate) fn match_expected_signatures(
+    expected: &mut Vec<ExpectedSignature>,
+    call: &CallInputs,
+    result: &InterpreterResult,
+) {
+    // Delegate calls, e.g. of proxies, are matched by the call to the account itself.
+    if matches!(call.scheme, CallScheme::DelegateCall | CallScheme::CallCode) ||
+        call.input.len() < 36 ||
+        call.input[..4] != ERC1271_MAGIC_VALUE
+    {
+        return
+    }
+    let digest = B256::from_slice(&call.input[4..36]);
+    let Some(i) =
+        expected.iter().position(|e| e.account == call.target_address && e.digest == digest)
+    else {
+        return
+    };
+    if result.is_ok() && result.output.starts_with(&ERC1271_MAGIC_VALUE) {
+        expected.remove(i);
+    } else if result.is_ok() {
+        expected[i].rejection = Some(format!("it returned {}", result.output));
+    } else {
+        expected[i].rejection = Some("it reverted".to_string());
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ExpectedEmit {
     /// The depth at which we expect this emit to have occurred
@@ -205,6 +301,36 @@ fn apply(&self, state: &mut Cheatcodes) -> Result {
     }
 }
 
+impl Cheatcode for expectGasUsed_0Call {
+    fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
+        let Self { minGas, maxGas } = *self;
+        ensure!(minGas <= maxGas, "minimum gas {minGas} is greater than maximum gas {maxGas}");
+        expect_gas_used(ccx, minGas, maxGas, None)
+    }
+}
+
+impl Cheatcode for expectGasUsed_1Call {
+    fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
+        let Self { name, tolerance } = self;
+        expect_gas_used_snapshot(ccx, None, Some(name.clone()), *tolerance)
+    }
+}
+
+impl Cheatcode for expectGasUsed_2Call {
+    fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
+        let Self { group, name, tolerance } = self;
+        expect_gas_used_snapshot(ccx, Some(group.clone()), Some(name.clone()), *tolerance)
+    }
+}
+
+impl Cheatcode for expectValidSignatureCall {
+    fn apply(&self, state: &mut Cheatcodes) -> Result {
+        let Self { account, digest } = *self;
+        state.expected_signatures.push(ExpectedSignature { account, digest, rejection: None });
+        Ok(Default::default())
+    }
+}
+
 impl Cheatcode for expectEmit_0Call {
     fn apply_stateful(&self, ccx: &mut CheatsCtxt) -> Result {
         let Self { checkTopic1, checkTopic2, checkTopic3, checkData } = *self;
@@ -889,6 +1015,49 @@ pub fn count_unchecked(&self) -> u64 {
     }
 }
 
+fn expect_gas_used(
+    ccx: &mut CheatsCtxt,
+    min_gas: u64,
+    max_gas: u64,
+    snapshot: Option<String>,
+) -> Result {
+    ensure!(
+        ccx.state.expected_gas_used.is_none(),
+        "you must call another function prior to expecting gas usage a second time"
+    );
+    ccx.state.expected_gas_used = Some(ExpectedGasUsed {
+        depth: ccx.ecx.journaled_state.depth(),
+        min_gas,
+        max_gas,
+        snapshot,
+    });
+    Ok(Default::default())
+}
+
+fn expect_gas_used_snapshot(
+    ccx: &mut CheatsCtxt,
+    group: Option<String>,
+    name: Option<String>,
+    tolerance: u64,
+) -> Result {
+    let (group, name) = derive_snapshot_name(ccx, group, name);
+    let path = ccx.state.config.snapshots.join(format!("{group}.json"));
+    let snapshots: BTreeMap<String, String> =
+        if path.exists() { read_json_file(&path)? } else { Default::default() };
+    let Some(value) = snapshots.get(&name) else {
+        bail!("no gas snapshot `{name}` stored in {}", path.display());
+    };
+    let gas: u64 = value
+        .parse()
+        .map_err(|_| fmt_err!("gas snapshot `{group}/{name}` is not a valid amount: {value}"))?;
+    expect_gas_used(
+        ccx,
+        gas.saturating_sub(tolerance),
+        gas.saturating_add(tolerance),
+        Some(format!("{group}/{name}")),
+    )
+}
+
 fn expect_revert(
     state: &mut Cheatcodes,
     reason: Option<&[u8]>,
@@ -180,7 +180,7 @@ fn apply(&self, state: &mut Cheatcodes) -> Result {
         let json =
             serde_json::from_str(json).unwrap_or_else(|_| JsonValue::String(json.to_owned()));
 
-        let data_path = state.config.ensure_path_allowed(path, FsAccessKind::Read)?;
+        let data_path = state.ensure_path_allowed(path, FsAccessKind::Read)?;
         let toml_data = fs::read_to_string(data_path)?;
         let json_data: JsonValue =
             toml::from_str(&toml_data).map_err(|e| fmt_err!("failed parsing TOML: {e}"))?;
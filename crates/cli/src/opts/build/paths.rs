@@ -61,6 +61,11 @@ pub struct ProjectPathOpts {
     #[arg(long, value_hint = ValueHint::FilePath, value_name = "FILE")]
     #[serde(skip)]
     pub config_path: Option<PathBuf>,
+
+    /// Fail on unknown keys and sections in the config files instead of warning about them.
+    #[arg(long)]
+    #[serde(skip)]
+    pub strict_config: bool,
 }
 
 impl ProjectPathOpts {
@@ -115,6 +120,10 @@ fn data(&self) -> Result<Map<Profile, Dict>, figment::Error> {
             dict.insert("libs".to_string(), libs.into());
         }
 
+        if self.strict_config {
+            dict.insert("strict_config".to_string(), true.into());
+        }
+
         Ok(Map::from([(Config::selected_profile(), dict)]))
     }
 }
@@ -3,6 +3,7 @@
     shell::{ColorChoice, OutputFormat, OutputMode, Shell, Verbosity},
     version::{IS_NIGHTLY_VERSION, NIGHTLY_VERSION_WARNING_MESSAGE},
 };
+use foundry_config::{ConfigOverride, ConfigOverrides};
 use serde::{Deserialize, Serialize};
 
 /// Global arguments for the CLI.
@@ -37,6 +38,13 @@ pub struct GlobalArgs {
     /// Number of threads to use. Specifying 0 defaults to the number of logical cores.
     #[arg(global = true, long, short = 'j', visible_alias = "jobs")]
     threads: Option<usize>,
+
+    /// Override a config key for this run only, e.g. `--config-override fuzz.runs=10000`.
+    ///
+    /// The value is parsed as TOML, or as a string if it isn't valid TOML. Overrides take
+    /// precedence over all the other sources of the config. Can be passed multiple times.
+    #[arg(global = true, long, value_name = "KEY=VALUE")]
+    config_override: Vec<ConfigOverride>,
 }
 
 impl GlobalArgs {
@@ -45,6 +53,11 @@ pub fn init(&self) -> eyre::Result<()> {
         // Set the global shell.
         self.shell().set();
 
+        // Apply the config overrides to every config loaded from now on.
+        if !self.config_override.is_empty() {
+            ConfigOverrides::new(self.config_override.clone()).set_global();
+        }
+
         // Initialize the thread pool only if `threads` was requested to avoid unnecessary overhead.
         if self.threads.is_some() {
             self.force_init_thread_pool()?;
@@ -8,7 +8,9 @@
     utils::read_json_file,
     Artifact, ArtifactId, ProjectCompileOutput,
 };
-use foundry_config::{error::ExtractConfigError, figment::Figment, Chain, Config, NamedChain};
+use foundry_config::{
+    error::ExtractConfigError, figment::Figment, Chain, Config, ConfigOverrides, NamedChain,
+};
 use foundry_debugger::Debugger;
 use foundry_evm::{
     executors::{DeployResult, EvmError, RawCallResult},
@@ -216,8 +218,15 @@ fn load_config_unsanitized(&self) -> Result<Config, ExtractConfigError> {
     }
 
     /// Same as [`LoadConfig::load_config_unsanitized`] but also emits warnings generated
+    ///
+    /// This also applies the [`env`](Config::env) table of the selected profile, loads the
+    /// [`label_files`](Config::label_files) and [validates](Config::validate) the config.
     fn load_config_unsanitized_no_warnings(&self) -> Result<Config, ExtractConfigError> {
-        Config::from_provider(self.figment())
+        let mut config = Config::from_provider(self.figment())?;
+        config.apply_env();
+        config.load_label_files();
+        validate(&mut config)?;
+        Ok(config)
     }
 
     /// Load and sanitize the [`Config`], as well as extract [`EvmOpts`] from self
@@ -230,7 +239,11 @@ fn load_config_and_evm_opts_no_warnings(&self) -> Result<(Config, EvmOpts)> {
         let figment = self.figment();
 
         let mut evm_opts = figment.extract::<EvmOpts>().map_err(ExtractConfigError::new)?;
-        let config = Config::from_provider(figment)?.sanitized();
+        let mut config = Config::from_provider(figment)?;
+        validate(&mut config)?;
+        config = config.sanitized();
+        config.apply_env();
+        config.load_label_files();
 
         // update the fork url if it was an alias
         if let Some(fork_url) = config.get_rpc_url() {
@@ -247,10 +260,17 @@ impl<T> LoadConfig for T
     for<'a> Figment: From<&'a T>,
 {
     fn figment(&self) -> Figment {
-        self.into()
+        ConfigOverrides::merge_global(self.into())
     }
 }
 
+/// Validates the config, adding the warnings found to [`Config::warnings`].
+fn validate(config: &mut Config) -> Result<(), ExtractConfigError> {
+    let warnings = config.validate().map_err(|err| ExtractConfigError::new(err.into()))?;
+    config.warnings.extend(warnings);
+    Ok(())
+}
+
 fn emit_warnings(config: &Config) {
     for warning in &config.warnings {
         let _ = sh_warn!("{warning}");
@@ -325,6 +345,8 @@ fn try_from(value: Result<RawCallResult>) -> Result<Self, Self::Error> {
 }
 
 /// labels the traces, conditionally prints them or opens the debugger
+///
+/// Returns the result with its traces decoded, or without traces if the debugger was opened.
 pub async fn handle_traces(
     mut result: TraceResult,
     config: &Config,
@@ -333,7 +355,7 @@ pub async fn handle_traces(
     with_local_artifacts: bool,
     debug: bool,
     decode_internal: bool,
-) -> Result<()> {
+) -> Result<TraceResult> {
     let (known_contracts, mut sources) = if with_local_artifacts {
         let _ = sh_println!("Compiling project to generate artifacts");
         let project = config.project()?;
@@ -368,6 +390,10 @@ pub async fn handle_traces(
             config.offline,
         )?);
     let mut identifier = TraceIdentifiers::new().with_etherscan(config, chain)?;
+    if decode_internal || debug {
+        // Fall back to Sourcify for contracts that are not verified on Etherscan.
+        identifier = identifier.with_sourcify(config, chain)?;
+    }
     if let Some(contracts) = &known_contracts {
         builder = builder.with_known_contracts(contracts);
         identifier = identifier.with_local(contracts);
@@ -383,15 +409,20 @@ pub async fn handle_traces(
         if let Some(ref etherscan_identifier) = identifier.etherscan {
             sources.merge(etherscan_identifier.get_compiled_contracts().await?);
         }
+        if let Some(ref sourcify_identifier) = identifier.sourcify {
+            sources.merge(sourcify_identifier.get_compiled_contracts().await?);
+        }
 
         if debug {
             let mut debugger = Debugger::builder()
-                .traces(result.traces.expect("missing traces"))
+                .traces(result.traces.take().expect("missing traces"))
                 .decoder(&decoder)
                 .sources(sources)
+                .config(config.debugger.clone())
+                .root(&config.root)
                 .build();
             debugger.try_run_tui()?;
-            return Ok(())
+            return Ok(result)
         }
 
         decoder.debug_identifier = Some(DebugTraceIdentifier::new(sources));
@@ -399,7 +430,7 @@ pub async fn handle_traces(
 
     print_traces(&mut result, &decoder, shell::verbosity() > 0, shell::verbosity() > 4).await?;
 
-    Ok(())
+    Ok(result)
 }
 
 pub async fn print_traces(
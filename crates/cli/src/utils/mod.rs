@@ -175,7 +175,9 @@ pub fn block_on<F: Future>(future: F) -> F::Output {
     rt.block_on(future)
 }
 
-/// Loads a dotenv file, from the cwd and the project root, ignoring potential failure.
+/// Loads the layered dotenv files of the project root, ignoring potential failure.
+///
+/// See [`foundry_config::dotenv`] for the files that are loaded and their precedence.
 ///
 /// We could use `warn!` here, but that would imply that the dotenv file can't configure
 /// the logging behavior of Foundry.
@@ -183,19 +185,10 @@ pub fn block_on<F: Future>(future: F) -> F::Output {
 /// Similarly, we could just use `eprintln!`, but colors are off limits otherwise dotenv is implied
 /// to not be able to configure the colors. It would also mess up the JSON output.
 pub fn load_dotenv() {
-    let load = |p: &Path| {
-        dotenvy::from_path(p.join(".env")).ok();
-    };
-
-    // we only want the .env file of the cwd and project root
     // `find_project_root` calls `current_dir` internally so both paths are either both `Ok` or
     // both `Err`
     if let (Ok(cwd), Ok(prj_root)) = (std::env::current_dir(), find_project_root(None)) {
-        load(&prj_root);
-        if cwd != prj_root {
-            // prj root and cwd can be identical
-            load(&cwd);
-        }
+        foundry_config::Dotenv::load(&prj_root, &cwd);
     };
 }
 
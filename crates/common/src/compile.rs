@@ -10,22 +10,31 @@
 use eyre::Result;
 use foundry_block_explorers::contract::Metadata;
 use foundry_compilers::{
-    artifacts::{remappings::Remapping, BytecodeObject, Contract, Source},
+    artifacts::{remappings::Remapping, BytecodeObject, Contract, Source, Sources},
+    cache::CompilerCache,
     compilers::{
         solc::{Solc, SolcCompiler},
         Compiler,
     },
-    report::{BasicStdoutReporter, NoReporter, Report},
+    report::{self, BasicStdoutReporter, NoReporter, Report},
     solc::SolcSettings,
-    Artifact, Project, ProjectBuilder, ProjectCompileOutput, ProjectPathsConfig, SolcConfig,
+    Artifact, Graph, Project, ProjectBuilder, ProjectCompileOutput, ProjectPathsConfig, SolcConfig,
 };
 use num_format::{Locale, ToFormattedString};
+use serde::Serialize;
 use std::{
-    collections::BTreeMap,
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet, HashMap},
     fmt::Display,
     io::IsTerminal,
+    num::NonZeroUsize,
     path::{Path, PathBuf},
-    time::Instant,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
 };
 
 /// Builder type to configure how to compile a project.
@@ -54,6 +63,13 @@ pub struct ProjectCompiler {
 
     /// Extra files to include, that are not necessarily in the project's source dir.
     files: Vec<PathBuf>,
+
+    /// Whether to print the time and memory spent compiling each file.
+    timings: bool,
+
+    /// The maximum number of files of a compilation unit, imports included, if the sources should
+    /// be split into units compiled in parallel.
+    max_unit_files: Option<NonZeroUsize>,
 }
 
 impl Default for ProjectCompiler {
@@ -75,6 +91,8 @@ pub fn new() -> Self {
             bail: None,
             ignore_eip_3860: false,
             files: Vec::new(),
+            timings: false,
+            max_unit_files: None,
         }
     }
 
@@ -128,6 +146,25 @@ pub fn files(mut self, files: impl IntoIterator<Item = PathBuf>) -> Self {
         self
     }
 
+    /// Sets whether to print the time and memory spent compiling each file.
+    ///
+    /// The files that changed are then compiled one at a time, each with its imports.
+    #[inline]
+    pub fn timings(mut self, yes: bool) -> Self {
+        self.timings = yes;
+        self
+    }
+
+    /// Sets the maximum number of files of a compilation unit, imports included.
+    ///
+    /// When set, the sources are split along their imports into units that are compiled in
+    /// parallel, so that a few large files don't serialize the whole build.
+    #[inline]
+    pub fn max_unit_files(mut self, max: Option<NonZeroUsize>) -> Self {
+        self.max_unit_files = max;
+        self
+    }
+
     /// Compiles the project.
     pub fn compile<C: Compiler<CompilerContract = Contract>>(
         mut self,
@@ -142,41 +179,55 @@ pub fn compile<C: Compiler<CompilerContract = Contract>>(
 
         // Taking is fine since we don't need these in `compile_with`.
         let files = std::mem::take(&mut self.files);
-        self.compile_with(|| {
+        let (max_unit_files, timings) = (self.max_unit_files, self.timings);
+        self.compile_with(project.root(), || {
             let sources = if !files.is_empty() {
                 Source::read_all(files)?
             } else {
                 project.paths.read_input_files()?
             };
 
-            foundry_compilers::project::ProjectCompiler::with_sources(project, sources)?
-                .compile()
-                .map_err(Into::into)
+            // The units only populate the output and the cache, the output is then read from
+            // them. The timings are measured for every file on its own, unless it's in a unit.
+            let units = match max_unit_files {
+                Some(max_files) => compile_units(project, &sources, max_files.get(), timings)?,
+                None if timings => compile_units(project, &sources, 1, true)?,
+                None => Vec::new(),
+            };
+
+            let output =
+                foundry_compilers::project::ProjectCompiler::with_sources(project, sources)?
+                    .compile()?;
+            Ok((output, units))
         })
     }
 
     /// Compiles the project with the given closure
     ///
+    /// The closure also returns the compilation units it compiled, whose paths are displayed
+    /// relative to `root` in the timings report.
+    ///
     /// # Example
     ///
     /// ```ignore
     /// use foundry_common::compile::ProjectCompiler;
     /// let config = foundry_config::Config::load().unwrap();
     /// let prj = config.project().unwrap();
-    /// ProjectCompiler::new().compile_with(|| Ok(prj.compile()?)).unwrap();
+    /// ProjectCompiler::new().compile_with(prj.root(), || Ok((prj.compile()?, vec![]))).unwrap();
     /// ```
     #[instrument(target = "forge::compile", skip_all)]
     fn compile_with<C: Compiler<CompilerContract = Contract>, F>(
         self,
+        root: &Path,
         f: F,
     ) -> Result<ProjectCompileOutput<C>>
     where
-        F: FnOnce() -> Result<ProjectCompileOutput<C>>,
+        F: FnOnce() -> Result<(ProjectCompileOutput<C>, Vec<CompiledUnit>)>,
     {
         let quiet = self.quiet.unwrap_or(false);
         let bail = self.bail.unwrap_or(true);
 
-        let output = with_compilation_reporter(self.quiet.unwrap_or(false), || {
+        let (output, units) = with_compilation_reporter(quiet, || {
             tracing::debug!("compiling project");
 
             let timer = Instant::now();
@@ -193,7 +244,9 @@ fn compile_with<C: Compiler<CompilerContract = Contract>, F>(
 
         if !quiet {
             if !shell::is_json() {
-                if output.is_unchanged() {
+                // The compilation units populate the cache, so the output is unchanged even if
+                // they were compiled.
+                if output.is_unchanged() && units.is_empty() {
                     sh_println!("No files changed, compilation skipped")?;
                 } else {
                     // print the compiler output / warnings
@@ -202,6 +255,10 @@ fn compile_with<C: Compiler<CompilerContract = Contract>, F>(
             }
 
             self.handle_output(&output);
+
+            if self.timings {
+                let _ = sh_println!("{}", TimingsReport { root, units });
+            }
         }
 
         Ok(output)
@@ -479,7 +536,44 @@ pub fn etherscan_project(
     metadata: &Metadata,
     target_path: impl AsRef<Path>,
 ) -> Result<Project<SolcCompiler>> {
-    let target_path = dunce::canonicalize(target_path.as_ref())?;
+    let (builder, compiler) = etherscan_project_builder(metadata, target_path.as_ref())?;
+    Ok(builder.ephemeral().no_artifacts().build(compiler)?)
+}
+
+/// Creates a [Project] from an Etherscan source that writes its artifacts, including storage
+/// layouts, and a compilation cache, so that the sources are only compiled once.
+///
+/// The sources are written to `<target_path>/<contract name>`, and the artifacts to its `out`
+/// directory.
+pub fn cached_etherscan_project(
+    metadata: &Metadata,
+    target_path: impl AsRef<Path>,
+) -> Result<Project<SolcCompiler>> {
+    let (builder, compiler) = etherscan_project_builder(metadata, target_path.as_ref())?;
+    let mut project = builder.build(compiler)?;
+    add_storage_layout_output(&mut project);
+    Ok(project)
+}
+
+/// Adds the storage layout to the artifacts of the project.
+pub fn add_storage_layout_output<C: Compiler<CompilerContract = Contract>>(
+    project: &mut Project<C>,
+) {
+    project.artifacts.additional_values.storage_layout = true;
+    project.update_output_selection(|selection| {
+        selection.0.values_mut().for_each(|contract_selection| {
+            contract_selection
+                .values_mut()
+                .for_each(|selection| selection.push("storageLayout".to_string()))
+        });
+    })
+}
+
+fn etherscan_project_builder(
+    metadata: &Metadata,
+    target_path: &Path,
+) -> Result<(ProjectBuilder<SolcCompiler>, SolcCompiler)> {
+    let target_path = dunce::canonicalize(target_path)?;
     let sources_path = target_path.join(&metadata.contract_name);
     metadata.source_tree().write_to(&target_path)?;
 
@@ -514,15 +608,13 @@ pub fn etherscan_project(
 
     let compiler = SolcCompiler::Specific(solc);
 
-    Ok(ProjectBuilder::<SolcCompiler>::default()
+    let builder = ProjectBuilder::<SolcCompiler>::default()
         .settings(SolcSettings {
             settings: SolcConfig::builder().settings(settings).build(),
             ..Default::default()
         })
-        .paths(paths)
-        .ephemeral()
-        .no_artifacts()
-        .build(compiler)?)
+        .paths(paths);
+    Ok((builder, compiler))
 }
 
 /// Configures the reporter and runs the given closure.
@@ -540,3 +632,397 @@ pub fn with_compilation_reporter<O>(quiet: bool, f: impl FnOnce() -> O) -> O {
 
     foundry_compilers::report::with_scoped(&reporter, f)
 }
+
+/// A compilation unit that was compiled on its own.
+#[derive(Clone, Debug, Serialize)]
+pub struct CompiledUnit {
+    /// The input files of the unit.
+    pub files: Vec<PathBuf>,
+    /// The contracts defined in the input files.
+    pub contracts: Vec<String>,
+    /// The number of files compiled, imports included.
+    pub compiled_files: usize,
+    /// The time spent compiling the unit.
+    pub duration: Duration,
+    /// The peak memory used by the compilers, in bytes, if it was measured.
+    pub peak_memory: Option<u64>,
+}
+
+/// The time and memory spent compiling each unit, slowest first.
+struct TimingsReport<'a> {
+    /// The root of the project, that the paths are displayed relative to.
+    root: &'a Path,
+    units: Vec<CompiledUnit>,
+}
+
+impl Display for TimingsReport<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut units = self.units.clone();
+        units.sort_by_key(|unit| Reverse(unit.duration));
+        for unit in &mut units {
+            for file in &mut unit.files {
+                if let Ok(stripped) = file.strip_prefix(self.root) {
+                    *file = stripped.to_path_buf();
+                }
+            }
+        }
+
+        if shell::is_json() {
+            return writeln!(f, "{}", serde_json::to_string(&units).unwrap())
+        }
+
+        let mut table = Table::new();
+        table.apply_modifier(UTF8_ROUND_CORNERS);
+        table.set_header(vec![
+            Cell::new("File"),
+            Cell::new("Contracts"),
+            Cell::new("Compiled Files"),
+            Cell::new("Time"),
+            Cell::new("Peak Memory"),
+        ]);
+        for unit in &units {
+            let files = unit.files.iter().map(|file| file.display().to_string());
+            let memory = unit.peak_memory.map_or_else(
+                || "-".to_string(),
+                |bytes| format!("{:.1} MiB", bytes as f64 / (1024 * 1024) as f64),
+            );
+            table.add_row([
+                Cell::new(files.collect::<Vec<_>>().join("\n")),
+                Cell::new(unit.contracts.join(", ")),
+                Cell::new(unit.compiled_files),
+                Cell::new(format!("{:.2?}", unit.duration)),
+                Cell::new(memory),
+            ]);
+        }
+        writeln!(f, "\n{table}")
+    }
+}
+
+/// Compiles the sources that changed in compilation units of at most `max_files` files, imports
+/// included, to populate the output and the cache of the project.
+///
+/// Every unit is compiled on its own, in a scratch directory next to the cache, so that the units
+/// don't write to the same files. The units are compiled in parallel, the largest first, unless
+/// they are `timed`, in which case they are compiled one at a time to measure the peak memory of
+/// their compilers. The outputs of the units are then moved to the output of the project.
+fn compile_units<C: Compiler<CompilerContract = Contract>>(
+    project: &Project<C>,
+    sources: &Sources,
+    max_files: usize,
+    timed: bool,
+) -> Result<Vec<CompiledUnit>> {
+    let graph = Graph::<C::ParsedSource>::resolve_sources(&project.paths, sources.clone())?;
+
+    // Changes of the settings are not detected here, but by the compilation of all the sources.
+    let cache = project
+        .cached
+        .then(|| CompilerCache::<C::Settings>::read_joined(&project.paths).ok())
+        .flatten();
+    let mut cached = HashMap::<PathBuf, bool>::new();
+    let mut is_cached = |file: &PathBuf| {
+        *cached.entry(file.clone()).or_insert_with(|| {
+            cache.as_ref().and_then(|cache| cache.files.get(file)).is_some_and(|entry| {
+                entry.seen_by_compiler &&
+                    entry.all_artifacts_exist() &&
+                    Source::read(file).is_ok_and(|s| s.content_hash() == entry.content_hash)
+            })
+        })
+    };
+    let mut imports = BTreeMap::<_, BTreeSet<_>>::new();
+    for file in sources.keys() {
+        let file_imports = graph.imports(file).into_iter().cloned().collect::<BTreeSet<_>>();
+        if !file_imports.iter().chain([file]).all(&mut is_cached) {
+            imports.insert(file.clone(), file_imports);
+        }
+    }
+
+    let units = split_units(&imports, max_files);
+    if units.is_empty() || (units.len() < 2 && !timed) {
+        return Ok(Vec::new())
+    }
+
+    let scratch = project.paths.cache.with_file_name("units");
+    let _ = std::fs::remove_dir_all(&scratch);
+    let reporter = report::get_default(|reporter| reporter.clone());
+    let queue = Mutex::new(units.into_iter().enumerate());
+    let workers = if timed { 1 } else { thread::available_parallelism().map_or(1, |n| n.get()) };
+    let sampler = timed.then(MemorySampler::spawn);
+    let (reporter, queue, imports, scratch, sampler) =
+        (&reporter, &queue, &imports, &scratch, &sampler);
+    let compiled = thread::scope(|s| {
+        let handles = (0..workers)
+            .map(|_| {
+                s.spawn(move || {
+                    report::with_scoped(reporter, || -> Result<Vec<_>> {
+                        let mut compiled = Vec::new();
+                        loop {
+                            let Some((i, files)) = queue.lock().unwrap().next() else { break };
+                            let dir = scratch.join(i.to_string());
+                            let mut project = project.clone();
+                            project.paths.artifacts = dir.join("out");
+                            project.paths.build_infos = dir.join("build-info");
+                            project.paths.cache = dir.join("cache.json");
+
+                            let unit_sources = files
+                                .iter()
+                                .map(|file| (file.clone(), sources[file].clone()))
+                                .collect();
+                            if let Some(sampler) = sampler {
+                                sampler.reset();
+                            }
+                            let timer = Instant::now();
+                            let output = foundry_compilers::project::ProjectCompiler::with_sources(
+                                &project,
+                                unit_sources,
+                            )?
+                            .compile()?;
+                            let duration = timer.elapsed();
+
+                            // The output of a unit that failed to compile is not used, its errors
+                            // are reported by the compilation of all the sources.
+                            if output.has_compiler_errors() {
+                                continue
+                            }
+
+                            let contracts = output
+                                .artifact_ids()
+                                .filter(|(id, _)| files.contains(&id.source))
+                                .map(|(id, _)| id.name)
+                                .collect();
+                            let compiled_files = files
+                                .iter()
+                                .flat_map(|file| imports[file].iter().chain([file]))
+                                .collect::<BTreeSet<_>>()
+                                .len();
+                            let peak_memory = sampler.as_ref().and_then(MemorySampler::peak);
+                            let unit = CompiledUnit {
+                                files,
+                                contracts,
+                                compiled_files,
+                                duration,
+                                peak_memory,
+                            };
+                            compiled.push((dir, unit));
+                        }
+                        Ok(compiled)
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect::<Result<Vec<_>>>()
+    });
+
+    let merged = compiled.and_then(|compiled| {
+        let (dirs, units): (Vec<_>, Vec<_>) = compiled.into_iter().flatten().unzip();
+        if project.cached {
+            merge_units(project, &dirs)?;
+        }
+        Ok(units)
+    });
+    let _ = std::fs::remove_dir_all(scratch);
+    merged
+}
+
+/// Moves the artifacts and the build infos compiled in the unit directories to the output of the
+/// project, and merges their cache entries into the cache of the project.
+///
+/// A unit whose artifacts have the same paths as the artifacts of other files, e.g. contracts with
+/// the same name, is skipped and left to the compilation of all the sources.
+fn merge_units<C: Compiler<CompilerContract = Contract>>(
+    project: &Project<C>,
+    dirs: &[PathBuf],
+) -> Result<()> {
+    let cache_path = project.cache_path();
+    let paths = project.paths.paths_relative();
+    // The paths of the entries and the artifacts are relative in the cache file, so they are the
+    // same in the cache of every unit.
+    let artifact_files = |cache: &CompilerCache<C::Settings>| {
+        cache
+            .files
+            .iter()
+            .flat_map(|(file, entry)| {
+                entry.artifacts().map(move |artifact| (artifact.path.clone(), file.clone()))
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut merged =
+        CompilerCache::<C::Settings>::read(cache_path).ok().filter(|cache| cache.paths == paths);
+    let mut owners = merged.iter().flat_map(artifact_files).collect::<HashMap<_, _>>();
+    for dir in dirs {
+        let Ok(mut cache) = CompilerCache::<C::Settings>::read(&dir.join("cache.json")) else {
+            continue
+        };
+        let files = artifact_files(&cache);
+        if files.iter().any(|(artifact, file)| owners.get(artifact).is_some_and(|f| f != file)) {
+            continue
+        }
+        owners.extend(files);
+
+        move_files(&dir.join("build-info"), &project.paths.build_infos)?;
+        move_files(&dir.join("out"), &project.paths.artifacts)?;
+        match &mut merged {
+            Some(merged) => {
+                merged.files.extend(cache.files);
+                merged.builds.extend(cache.builds);
+                merged.profiles.extend(cache.profiles);
+            }
+            None => {
+                cache.paths = paths.clone();
+                merged = Some(cache);
+            }
+        }
+    }
+    if let Some(cache) = merged {
+        cache.write(cache_path)?;
+    }
+    Ok(())
+}
+
+/// Moves all the JSON files under the `from` dir to the `to` dir, keeping their relative paths.
+fn move_files(from: &Path, to: &Path) -> Result<()> {
+    for file in crate::fs::json_files(from) {
+        let target = to.join(file.strip_prefix(from)?);
+        crate::fs::create_dir_all(target.parent().unwrap())?;
+        if std::fs::rename(&file, &target).is_err() {
+            // The dirs can be on different file systems.
+            crate::fs::copy(&file, &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Samples the memory of the child processes, i.e. the compilers, in a background thread.
+///
+/// The memory is read from `/proc`, so it's only measured on Linux.
+struct MemorySampler {
+    /// The highest total of the peak resident memory of the child processes, in bytes.
+    peak: Arc<AtomicU64>,
+    stop: Arc<AtomicBool>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl MemorySampler {
+    fn spawn() -> Self {
+        let peak = Arc::<AtomicU64>::default();
+        let stop = Arc::<AtomicBool>::default();
+        let handle = thread::spawn({
+            let (peak, stop) = (peak.clone(), stop.clone());
+            move || {
+                while !stop.load(Ordering::Relaxed) {
+                    peak.fetch_max(children_memory(), Ordering::Relaxed);
+                    thread::sleep(Duration::from_millis(5));
+                }
+            }
+        });
+        Self { peak, stop, handle: Some(handle) }
+    }
+
+    /// Resets the peak memory, before running new processes.
+    fn reset(&self) {
+        self.peak.store(0, Ordering::Relaxed);
+    }
+
+    /// Returns the peak memory since the last reset, if any was measured.
+    fn peak(&self) -> Option<u64> {
+        Some(self.peak.load(Ordering::Relaxed)).filter(|peak| *peak > 0)
+    }
+}
+
+impl Drop for MemorySampler {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Returns the total of the peak resident memory (`VmHWM`) of the running child processes of
+/// this process, in bytes.
+fn children_memory() -> u64 {
+    let Ok(tasks) = std::fs::read_dir("/proc/self/task") else { return 0 };
+    let children = tasks
+        .filter_map(|task| std::fs::read_to_string(task.ok()?.path().join("children")).ok())
+        .collect::<Vec<_>>();
+    children
+        .iter()
+        .flat_map(|children| children.split_whitespace())
+        .filter_map(|pid| {
+            let status = std::fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
+            let kb = status.lines().find_map(|line| line.strip_prefix("VmHWM:"))?;
+            kb.trim().strip_suffix("kB")?.trim().parse::<u64>().ok()
+        })
+        .map(|kb| kb * 1024)
+        .sum()
+}
+
+/// Splits the input files into compilation units of at most `max_files` files, including the
+/// files they import.
+///
+/// `imports` maps every input file to all the files it imports, transitively. The files are added
+/// to the first unit they fit in, starting with the ones that import the most files, so that the
+/// units are ordered from the largest to the smallest. A file importing more than `max_files`
+/// files is compiled in a unit of its own.
+fn split_units(
+    imports: &BTreeMap<PathBuf, BTreeSet<PathBuf>>,
+    max_files: usize,
+) -> Vec<Vec<PathBuf>> {
+    let mut inputs = imports.iter().collect::<Vec<_>>();
+    inputs.sort_by_key(|(_, imports)| Reverse(imports.len()));
+
+    // The input files of every unit, and all the files it compiles.
+    let mut units: Vec<(Vec<PathBuf>, BTreeSet<&PathBuf>)> = Vec::new();
+    for (file, imports) in inputs {
+        let closure = || imports.iter().chain([file]);
+        let fits = |files: &BTreeSet<&PathBuf>| {
+            files.len() + closure().filter(|f| !files.contains(f)).count() <= max_files
+        };
+        match units.iter_mut().find(|(_, files)| fits(files)) {
+            Some((inputs, files)) => {
+                inputs.push(file.clone());
+                files.extend(closure());
+            }
+            None => units.push((vec![file.clone()], closure().collect())),
+        }
+    }
+    units.into_iter().map(|(inputs, _)| inputs).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_compilation_units() {
+        let imports = |files: &[&str]| files.iter().map(PathBuf::from).collect::<BTreeSet<_>>();
+        let graph = BTreeMap::from([
+            ("A.t.sol".into(), imports(&["A.sol", "Test.sol"])),
+            ("B.t.sol".into(), imports(&["B.sol", "Test.sol"])),
+            ("C.t.sol".into(), imports(&["B.sol", "C.sol", "Test.sol"])),
+            ("D.sol".into(), imports(&[])),
+        ]);
+
+        // The units share `Test.sol` and `B.sol`.
+        let units = split_units(&graph, 5);
+        assert_eq!(
+            units,
+            [
+                vec![PathBuf::from("C.t.sol"), "B.t.sol".into()],
+                vec!["A.t.sol".into(), "D.sol".into()],
+            ]
+        );
+
+        // A unit of a single file can be larger than the maximum.
+        assert_eq!(split_units(&graph, 1).len(), 4);
+        assert_eq!(split_units(&graph, 100).len(), 1);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn sample_compiler_memory() {
+        let sampler = MemorySampler::spawn();
+        std::process::Command::new("sleep").arg("0.1").status().unwrap();
+        assert!(sampler.peak().is_some());
+    }
+}
@@ -3,15 +3,19 @@
 pub mod runtime_transport;
 
 use crate::{
-    provider::runtime_transport::RuntimeTransportBuilder, ALCHEMY_FREE_TIER_CUPS, REQUEST_TIMEOUT,
+    provider::runtime_transport::{RuntimeTransportBuilder, RuntimeTransportError},
+    ALCHEMY_FREE_TIER_CUPS, REQUEST_TIMEOUT,
 };
+use alloy_json_rpc::RpcError;
 use alloy_provider::{
     fillers::{ChainIdFiller, FillProvider, GasFiller, JoinFill, NonceFiller, WalletFiller},
     network::{AnyNetwork, EthereumWallet},
     Identity, ProviderBuilder as AlloyProviderBuilder, RootProvider,
 };
 use alloy_rpc_client::ClientBuilder;
-use alloy_transport::{layers::RetryBackoffLayer, utils::guess_local_url};
+use alloy_transport::{
+    layers::RetryBackoffLayer, utils::guess_local_url, TransportError, TransportErrorKind,
+};
 use eyre::{Result, WrapErr};
 use foundry_config::NamedChain;
 use reqwest::Url;
@@ -81,6 +85,38 @@ pub fn try_get_http_provider(builder: impl AsRef<str>) -> Result<RetryProvider>
     ProviderBuilder::new(builder.as_ref()).build()
 }
 
+/// Returns `true` if the request failed because of a transient failure of the RPC endpoint, such as
+/// a timeout, a dropped connection, rate limiting or an unavailable server, rather than because of
+/// the request itself, so that sending it again may succeed.
+pub fn is_transient_transport_error(err: &TransportError) -> bool {
+    match err {
+        RpcError::Transport(kind) => match kind {
+            TransportErrorKind::BackendGone => true,
+            TransportErrorKind::HttpError(err) => matches!(err.status, 429 | 502 | 503 | 504),
+            TransportErrorKind::Custom(err) => {
+                if let Some(err) = err.downcast_ref::<reqwest::Error>() {
+                    err.is_timeout() || err.is_connect()
+                } else if let Some(RuntimeTransportError::TransportError(err, _)) =
+                    err.downcast_ref::<RuntimeTransportError>()
+                {
+                    is_transient_transport_error(err)
+                } else {
+                    kind.is_retry_err()
+                }
+            }
+            kind => kind.is_retry_err(),
+        },
+        RpcError::ErrorResp(payload) => payload.is_retry_err(),
+        _ => false,
+    }
+}
+
+/// Returns `true` if the error is a [`TransportError`] caused by a transient failure of the RPC
+/// endpoint, see [`is_transient_transport_error`].
+pub fn is_transient_error(err: &(dyn std::error::Error + 'static)) -> bool {
+    err.downcast_ref::<TransportError>().is_some_and(is_transient_transport_error)
+}
+
 /// Helper type to construct a `RetryProvider`
 #[derive(Debug)]
 pub struct ProviderBuilder {
@@ -360,4 +396,23 @@ fn can_auto_correct_missing_prefix() {
         let url = builder.url.unwrap();
         assert_eq!(url, Url::parse("http://localhost:8545").unwrap());
     }
+
+    #[test]
+    fn classify_transient_transport_errors() {
+        let rate_limited = TransportErrorKind::http_error(429, "slow down".to_string());
+        assert!(is_transient_transport_error(&rate_limited));
+        assert!(is_transient_transport_error(&TransportErrorKind::http_error(503, String::new())));
+        assert!(is_transient_transport_error(&TransportErrorKind::backend_gone()));
+        assert!(is_transient_transport_error(&TransportErrorKind::custom(
+            RuntimeTransportError::TransportError(
+                TransportErrorKind::http_error(502, String::new()),
+                "http://localhost:8545".to_string(),
+            )
+        )));
+
+        assert!(!is_transient_transport_error(&TransportErrorKind::http_error(400, String::new())));
+        assert!(!is_transient_transport_error(&TransportErrorKind::custom(
+            RuntimeTransportError::BadScheme("ftp".to_string())
+        )));
+    }
 }
@@ -69,6 +69,11 @@ fn is_fixture(&self) -> bool {
         self.test_function_kind().is_fixture()
     }
 
+    /// Returns `true` if this function is a benchmark.
+    fn is_bench(&self) -> bool {
+        self.test_function_kind().is_bench()
+    }
+
     #[doc(hidden)]
     fn tfe_as_str(&self) -> &str;
     #[doc(hidden)]
@@ -120,6 +125,8 @@ pub enum TestFunctionKind {
     AfterInvariant,
     /// `fixture*`.
     Fixture,
+    /// `bench_*`, without arguments.
+    Bench,
     /// Unknown kind.
     Unknown,
 }
@@ -143,6 +150,7 @@ pub fn classify(name: &str, has_inputs: bool) -> Self {
             _ if name.eq_ignore_ascii_case("setup") => Self::Setup,
             _ if name.eq_ignore_ascii_case("afterinvariant") => Self::AfterInvariant,
             _ if name.starts_with("fixture") => Self::Fixture,
+            _ if name.starts_with("bench_") && !has_inputs => Self::Bench,
             _ => Self::Unknown,
         }
     }
@@ -158,6 +166,7 @@ pub const fn name(&self) -> &'static str {
             Self::InvariantTest => "invariant",
             Self::AfterInvariant => "afterInvariant",
             Self::Fixture => "fixture",
+            Self::Bench => "bench",
             Self::Unknown => "unknown",
         }
     }
@@ -210,6 +219,12 @@ pub const fn is_fixture(&self) -> bool {
         matches!(self, Self::Fixture)
     }
 
+    /// Returns `true` if this function is a benchmark.
+    #[inline]
+    pub const fn is_bench(&self) -> bool {
+        matches!(self, Self::Bench)
+    }
+
     /// Returns `true` if this function kind is known.
     #[inline]
     pub const fn is_known(&self) -> bool {
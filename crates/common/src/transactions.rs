@@ -3,7 +3,7 @@
 use alloy_consensus::{Transaction, TxEnvelope};
 use alloy_eips::eip7702::SignedAuthorization;
 use alloy_network::AnyTransactionReceipt;
-use alloy_primitives::{Address, TxKind, U256};
+use alloy_primitives::{Address, Bytes, TxKind, U256};
 use alloy_provider::{
     network::{AnyNetwork, ReceiptResponse, TransactionBuilder},
     Provider,
@@ -24,6 +24,11 @@ pub struct TransactionReceiptWithRevertReason {
     /// The revert reason string if the transaction status is failed
     #[serde(skip_serializing_if = "Option::is_none", rename = "revertReason")]
     pub revert_reason: Option<String>,
+
+    /// The raw data the transaction reverted with if the transaction status is failed and the
+    /// node returned it
+    #[serde(skip)]
+    pub revert_data: Option<Bytes>,
 }
 
 impl TransactionReceiptWithRevertReason {
@@ -32,22 +37,22 @@ pub fn is_failure(&self) -> bool {
         !self.receipt.inner.inner.inner.receipt.status.coerce_status()
     }
 
-    /// Updates the revert reason field using `eth_call` and returns an Err variant if the revert
-    /// reason was not successfully updated
+    /// Updates the revert reason and revert data fields using `eth_call` and returns an Err
+    /// variant if the revert reason was not successfully updated
     pub async fn update_revert_reason<P: Provider<AnyNetwork>>(
         &mut self,
         provider: &P,
     ) -> Result<()> {
-        self.revert_reason = self.fetch_revert_reason(provider).await?;
+        (self.revert_reason, self.revert_data) = self.fetch_revert_reason(provider).await?;
         Ok(())
     }
 
     async fn fetch_revert_reason<P: Provider<AnyNetwork>>(
         &self,
         provider: &P,
-    ) -> Result<Option<String>> {
+    ) -> Result<(Option<String>, Option<Bytes>)> {
         if !self.is_failure() {
-            return Ok(None)
+            return Ok((None, None))
         }
 
         let transaction = provider
@@ -62,7 +67,10 @@ async fn fetch_revert_reason<P: Provider<AnyNetwork>>(
                 .block(BlockId::Hash(block_hash.into()))
                 .await
             {
-                Err(e) => return Ok(extract_revert_reason(e.to_string())),
+                Err(e) => {
+                    let data = e.as_error_resp().and_then(|resp| resp.as_revert_data());
+                    return Ok((extract_revert_reason(e.to_string()), data))
+                }
                 Ok(_) => eyre::bail!("no revert reason as transaction succeeded"),
             }
         }
@@ -72,7 +80,7 @@ async fn fetch_revert_reason<P: Provider<AnyNetwork>>(
 
 impl From<AnyTransactionReceipt> for TransactionReceiptWithRevertReason {
     fn from(receipt: AnyTransactionReceipt) -> Self {
-        Self { receipt, revert_reason: None }
+        Self { receipt, revert_reason: None, revert_data: None }
     }
 }
 
@@ -0,0 +1,54 @@
+//! Configuration for the commands run before and after `forge build`.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Contains the `[build]` config.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildConfig {
+    /// Commands run before and after compiling.
+    #[serde(default)]
+    pub hooks: BuildHooksConfig,
+}
+
+/// Contains the `[build.hooks]` config.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildHooksConfig {
+    /// Commands run before compiling, for example to generate sources.
+    #[serde(default)]
+    pub pre_build: Vec<BuildHook>,
+    /// Commands run after a successful compilation, for example to process artifacts.
+    #[serde(default)]
+    pub post_build: Vec<BuildHook>,
+}
+
+/// A command run by `forge build`.
+///
+/// The command only runs when its inputs or the command itself changed since its last run, or
+/// when one of its outputs is missing or was modified. Unchanged outputs are left untouched, so
+/// generated sources don't invalidate the compilation cache.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildHook {
+    /// The name of the hook, used in messages and to track its runs.
+    ///
+    /// Defaults to the command.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The program to run, followed by its arguments. The program is run in the project root.
+    pub command: Vec<String>,
+    /// Globs of the files read by the command, relative to the project root.
+    ///
+    /// If empty, the command runs on every build.
+    #[serde(default)]
+    pub inputs: Vec<String>,
+    /// The files written by the command, relative to the project root.
+    #[serde(default)]
+    pub outputs: Vec<PathBuf>,
+}
+
+impl BuildHook {
+    /// Returns the name of the hook.
+    pub fn name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| self.command.join(" "))
+    }
+}
@@ -3,7 +3,16 @@
 use crate::Chain;
 use number_prefix::NumberPrefix;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use std::{fmt, fmt::Formatter, str::FromStr};
+use std::{
+    collections::HashMap,
+    fmt,
+    fmt::Formatter,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Once,
+    time::{Duration, SystemTime},
+};
 
 /// Settings to configure caching of remote.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -12,6 +21,17 @@ pub struct StorageCachingConfig {
     pub chains: CachedChains,
     /// Endpoints to cache.
     pub endpoints: CachedEndpoints,
+    /// The maximum total size of the cached blocks, in bytes.
+    ///
+    /// The least recently used blocks are evicted first.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<u64>,
+    /// The number of days after which unused cached blocks are evicted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<u64>,
+    /// Overrides of [`max_age_days`](Self::max_age_days) for specific chains.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub chain_max_age_days: HashMap<Chain, u64>,
 }
 
 impl StorageCachingConfig {
@@ -28,6 +48,34 @@ pub fn enable_for_chain_id(&self, chain_id: u64) -> bool {
         }
         self.chains.is_match(chain_id)
     }
+
+    /// Whether a size or age limit is set.
+    pub fn has_eviction_policy(&self) -> bool {
+        self.max_size.is_some() ||
+            self.max_age_days.is_some() ||
+            !self.chain_max_age_days.is_empty()
+    }
+
+    /// Returns the number of days after which unused cached blocks of `chain` are evicted.
+    pub fn max_age_days(&self, chain: Chain) -> Option<u64> {
+        self.chain_max_age_days.get(&chain).copied().or(self.max_age_days)
+    }
+
+    /// Evicts the cached blocks exceeding the limits, at most once per process.
+    ///
+    /// This is called by the commands using the cache, so that it doesn't grow unbounded. Errors
+    /// are only logged.
+    pub fn evict_lazily(&self) {
+        static EVICT: Once = Once::new();
+        if !self.has_eviction_policy() {
+            return
+        }
+        EVICT.call_once(|| {
+            if let Err(err) = crate::Config::evict_rpc_cache(self) {
+                warn!(%err, "failed to evict cached blocks");
+            }
+        });
+    }
 }
 
 /// What chains to cache
@@ -212,6 +260,102 @@ fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
     }
 }
 
+/// A cached block, removed by [`evict_cached_blocks`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EvictedBlock {
+    /// The chain of the block.
+    pub chain: Chain,
+    /// The block number.
+    pub block: String,
+    /// The size of the cached data in bytes.
+    pub size: u64,
+}
+
+/// Evicts the cached blocks of the RPC cache at `cache_dir` exceeding the limits of `config`.
+///
+/// Blocks that were last used more than the maximum age of their chain before `now` are evicted
+/// first, followed by the least recently used blocks until the cache fits in the maximum size.
+pub fn evict_cached_blocks(
+    cache_dir: &Path,
+    config: &StorageCachingConfig,
+    now: SystemTime,
+) -> eyre::Result<Vec<EvictedBlock>> {
+    struct Entry {
+        chain: Chain,
+        block: String,
+        path: PathBuf,
+        size: u64,
+        last_used: SystemTime,
+    }
+
+    if !cache_dir.exists() {
+        return Ok(Vec::new())
+    }
+    let mut entries = Vec::new();
+    for chain_dir in fs::read_dir(cache_dir)?.flatten().filter(|e| e.path().is_dir()) {
+        let Ok(chain) = Chain::from_str(&chain_dir.file_name().to_string_lossy()) else { continue };
+        for block in fs::read_dir(chain_dir.path())?.flatten() {
+            let path = block.path();
+            let Some(file) = cached_block_file(&path) else { continue };
+            let metadata = fs::metadata(file)?;
+            entries.push(Entry {
+                chain,
+                block: block.file_name().to_string_lossy().into_owned(),
+                path,
+                size: metadata.len(),
+                last_used: metadata.modified()?,
+            });
+        }
+    }
+    // Least recently used first.
+    entries.sort_by_key(|entry| entry.last_used);
+
+    let mut total_size = entries.iter().map(|entry| entry.size).sum::<u64>();
+    let mut evicted = Vec::new();
+    for entry in entries {
+        let age = now.duration_since(entry.last_used).unwrap_or_default();
+        let expired = config
+            .max_age_days(entry.chain)
+            .is_some_and(|days| age > Duration::from_secs(days * 24 * 60 * 60));
+        let oversized = config.max_size.is_some_and(|max_size| total_size > max_size);
+        if !expired && !oversized {
+            continue
+        }
+        if entry.path.is_dir() {
+            fs::remove_dir_all(&entry.path)?;
+        } else {
+            fs::remove_file(&entry.path)?;
+        }
+        total_size -= entry.size;
+        evicted.push(EvictedBlock { chain: entry.chain, block: entry.block, size: entry.size });
+    }
+    Ok(evicted)
+}
+
+/// Marks the cached block at `path`, as returned by
+/// [`Config::foundry_block_cache_dir`](crate::Config::foundry_block_cache_dir), as used now, so
+/// that it is evicted after the blocks that weren't used since.
+pub fn mark_cached_block_used(path: &Path) -> std::io::Result<()> {
+    match cached_block_file(path) {
+        Some(file) => fs::File::options().write(true).open(file)?.set_modified(SystemTime::now()),
+        None => Ok(()),
+    }
+}
+
+/// Returns the file holding the data of the cached block at `path`, which is either the file
+/// itself or a `storage.json` file in a block directory.
+fn cached_block_file(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?.to_string_lossy();
+    if !name.chars().all(|c| c.is_ascii_digit()) {
+        return None
+    }
+    if path.is_dir() {
+        Some(path.join("storage.json")).filter(|file| file.is_file())
+    } else {
+        path.is_file().then(|| path.to_path_buf())
+    }
+}
+
 /// A representation of data for a given chain in the foundry cache
 #[derive(Debug)]
 pub struct ChainCache {
@@ -242,7 +386,11 @@ pub struct Wrapper {
 
         assert_eq!(
             w.rpc_storage_caching,
-            StorageCachingConfig { chains: CachedChains::All, endpoints: CachedEndpoints::Remote }
+            StorageCachingConfig {
+                chains: CachedChains::All,
+                endpoints: CachedEndpoints::Remote,
+                ..Default::default()
+            }
         );
 
         let s = r#"rpc_storage_caching = { chains = [1, "optimism", 999999], endpoints = "all"}"#;
@@ -257,8 +405,46 @@ pub struct Wrapper {
                     Chain::from_id(999999)
                 ]),
                 endpoints: CachedEndpoints::All,
+                ..Default::default()
             }
-        )
+        );
+
+        let s = r#"rpc_storage_caching = { chains = "all", endpoints = "all", max_size = 1000000000, max_age_days = 30, chain_max_age_days = { mainnet = 90 } }"#;
+        let w: Wrapper = toml::from_str(s).unwrap();
+        assert_eq!(w.rpc_storage_caching.max_size, Some(1_000_000_000));
+        assert_eq!(w.rpc_storage_caching.max_age_days(Chain::mainnet()), Some(90));
+        assert_eq!(w.rpc_storage_caching.max_age_days(Chain::optimism_mainnet()), Some(30));
+    }
+
+    #[test]
+    fn evicts_cached_blocks() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = SystemTime::now();
+        let day = Duration::from_secs(24 * 60 * 60);
+        let write_block = |chain: &str, block: &str, size: usize, age: Duration| {
+            let path = dir.path().join(chain).join(block);
+            fs::create_dir_all(&path).unwrap();
+            let file = path.join("storage.json");
+            fs::write(&file, vec![b'0'; size]).unwrap();
+            fs::File::options().write(true).open(&file).unwrap().set_modified(now - age).unwrap();
+        };
+        write_block("mainnet", "1", 100, 10 * day);
+        write_block("mainnet", "2", 100, 2 * day);
+        write_block("optimism", "3", 100, 3 * day);
+        write_block("optimism", "4", 100, day);
+
+        let config = StorageCachingConfig {
+            max_size: Some(250),
+            max_age_days: Some(30),
+            chain_max_age_days: HashMap::from([(Chain::mainnet(), 5)]),
+            ..Default::default()
+        };
+        let evicted = evict_cached_blocks(dir.path(), &config, now).unwrap();
+        let evicted = evicted.iter().map(|b| (b.chain, b.block.as_str())).collect::<Vec<_>>();
+        // Block 1 is too old, and block 3 is the least recently used of the others.
+        assert_eq!(evicted, [(Chain::mainnet(), "1"), (Chain::optimism_mainnet(), "3")]);
+        assert!(dir.path().join("mainnet").join("2").exists());
+        assert!(!dir.path().join("optimism").join("3").exists());
     }
 
     #[test]
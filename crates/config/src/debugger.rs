@@ -0,0 +1,98 @@
+//! Configuration for the TUI debugger.
+
+use serde::{Deserialize, Serialize};
+
+/// Contains the config for the TUI debugger.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DebuggerConfig {
+    /// How the panes are arranged.
+    pub layout: DebuggerLayout,
+    /// The minimum terminal width at which the `auto` layout uses the horizontal layout.
+    pub horizontal_min_width: u16,
+    /// The width of the left column in the horizontal layout, as a percentage of the terminal
+    /// width.
+    pub horizontal_split: u16,
+    /// The panes to hide.
+    pub hide: Vec<DebuggerPane>,
+    /// The relative heights of the panes.
+    ///
+    /// If not set, the default sizes of the selected layout are used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sizes: Option<DebuggerPaneSizes>,
+    /// The command used to open the current source location in an editor, e.g.
+    /// `code -g {file}:{line}:{column}`.
+    ///
+    /// `{file}`, `{line}` and `{column}` are replaced with the location. The command runs in the
+    /// background, so it must not need the terminal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub editor: Option<String>,
+}
+
+impl Default for DebuggerConfig {
+    fn default() -> Self {
+        Self {
+            layout: DebuggerLayout::Auto,
+            horizontal_min_width: 200,
+            horizontal_split: 50,
+            hide: Vec::new(),
+            sizes: None,
+            editor: None,
+        }
+    }
+}
+
+/// The arrangement of the debugger panes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DebuggerLayout {
+    /// Use the horizontal layout if the terminal is wide enough, otherwise the vertical layout.
+    #[default]
+    Auto,
+    /// Draw the panes in two columns: opcodes and source on the left, stack and buffer on the
+    /// right.
+    Horizontal,
+    /// Draw all the panes in a single column.
+    Vertical,
+}
+
+impl DebuggerLayout {
+    /// Returns the next layout, used to cycle through the layouts at runtime.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Auto => Self::Horizontal,
+            Self::Horizontal => Self::Vertical,
+            Self::Vertical => Self::Auto,
+        }
+    }
+}
+
+/// A debugger pane.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DebuggerPane {
+    /// The list of opcodes.
+    Op,
+    /// The stack.
+    Stack,
+    /// The memory, calldata or returndata buffer.
+    Buffer,
+    /// The source code.
+    Src,
+    /// The state variables of the contract being executed, decoded with its storage layout.
+    Storage,
+}
+
+/// The relative heights of the debugger panes.
+///
+/// In the vertical layout, all the panes share the terminal height. In the horizontal layout, the
+/// `op` and `src` panes share the left column, and the `stack`, `buffer` and `storage` panes share
+/// the right column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DebuggerPaneSizes {
+    pub op: u16,
+    pub stack: u16,
+    pub buffer: u16,
+    pub src: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage: Option<u16>,
+}
@@ -0,0 +1,153 @@
+//! Differences between a [`Config`] and the default config.
+
+use crate::{provenance::describe, Config};
+use figment::{Figment, Metadata};
+use serde::Serialize;
+use serde_json::Value;
+use std::{collections::BTreeMap, fmt};
+
+/// Keys that describe how the config was loaded rather than a setting.
+const IGNORED_KEYS: &[&str] = &["root", "profile", "profiles"];
+
+/// The keys of a [`Config`] that differ from the default config, see
+/// [`Config::diff_from_default`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct ConfigDiff {
+    /// The differing keys, sorted by key.
+    pub entries: Vec<ConfigDiffEntry>,
+}
+
+/// A key of a [`Config`] that differs from the default config.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ConfigDiffEntry {
+    /// The dotted path of the key, e.g. `fuzz.runs`.
+    pub key: String,
+    /// The default value, `null` if the key is not set by default.
+    pub default: Value,
+    /// The value of the config, `null` if the key is unset.
+    pub value: Value,
+    /// The provider that supplied the value, if known. See [`ConfigDiff::with_sources`].
+    pub source: Option<String>,
+}
+
+impl ConfigDiff {
+    /// Returns `true` if the config is the default config.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Sets the [`source`](ConfigDiffEntry::source) of each entry to the provider of the
+    /// `figment` the config was extracted from that supplied the value.
+    pub fn with_sources(mut self, figment: &Figment) -> Self {
+        for entry in &mut self.entries {
+            entry.source = find_metadata(figment, &entry.key).map(describe);
+        }
+        self
+    }
+}
+
+impl fmt::Display for ConfigDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            write!(f, "{} = {} (default: {})", entry.key, entry.value, entry.default)?;
+            if let Some(source) = &entry.source {
+                write!(f, " from {source}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl Config {
+    /// Returns the keys that differ from the default config, with the same root.
+    ///
+    /// Nested tables are compared key by key, arrays are compared as a whole.
+    pub fn diff_from_default(&self) -> ConfigDiff {
+        let default = Self { root: self.root.clone(), ..Default::default() };
+        let (Ok(value), Ok(default)) = (serde_json::to_value(self), serde_json::to_value(default))
+        else {
+            return ConfigDiff::default();
+        };
+
+        let mut values = BTreeMap::new();
+        flatten(String::new(), value, &mut values);
+        let mut defaults = BTreeMap::new();
+        flatten(String::new(), default, &mut defaults);
+
+        let mut entries = Vec::new();
+        for (key, value) in &values {
+            let default = defaults.get(key).cloned().unwrap_or(Value::Null);
+            if *value != default {
+                entries.push(ConfigDiffEntry {
+                    key: key.clone(),
+                    default,
+                    value: value.clone(),
+                    source: None,
+                });
+            }
+        }
+        for (key, default) in defaults {
+            if !values.contains_key(&key) && !default.is_null() {
+                entries.push(ConfigDiffEntry { key, default, value: Value::Null, source: None });
+            }
+        }
+        entries.retain(|entry| {
+            let root = entry.key.split('.').next().unwrap_or_default();
+            !IGNORED_KEYS.contains(&root)
+        });
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        ConfigDiff { entries }
+    }
+}
+
+/// Flattens the nested objects of `value` into `out`, keyed by their dotted path.
+fn flatten(key: String, value: Value, out: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (k, v) in map {
+                let key = if key.is_empty() { k } else { format!("{key}.{k}") };
+                flatten(key, v, out);
+            }
+        }
+        value => {
+            out.insert(key, value);
+        }
+    }
+}
+
+/// Returns the metadata of the provider that supplied the value of `key`, or of its closest
+/// parent table.
+fn find_metadata<'a>(figment: &'a Figment, key: &str) -> Option<&'a Metadata> {
+    let mut key = key;
+    loop {
+        if let Some(metadata) = figment.find_metadata(key) {
+            return Some(metadata);
+        }
+        key = &key[..key.rfind('.')?];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FuzzConfig;
+
+    #[test]
+    fn diff_nested_keys() {
+        let config = Config {
+            optimizer_runs: Some(1000),
+            fuzz: FuzzConfig { runs: 1, ..Config::default().fuzz },
+            ..Default::default()
+        };
+
+        let diff = config.diff_from_default();
+        let keys = diff.entries.iter().map(|entry| entry.key.as_str()).collect::<Vec<_>>();
+        assert_eq!(keys, ["fuzz.runs", "optimizer_runs"]);
+        assert_eq!(diff.entries[1].default, Value::Null);
+        assert_eq!(diff.entries[1].value, Value::from(1000));
+
+        assert!(Config::default().diff_from_default().is_empty());
+    }
+}
@@ -22,6 +22,23 @@ pub struct DocConfig {
     pub repository: Option<String>,
     /// Globs to ignore
     pub ignore: Vec<String>,
+    /// Whether to document the scripts, in their own section.
+    pub include_scripts: bool,
+    /// Whether to document the tests, in their own section, with the contracts they cover and
+    /// the cheatcodes they use.
+    pub include_tests: bool,
+    /// Path to a user provided template of the pages, in the Handlebars syntax.
+    ///
+    /// The generated Markdown of a page is available as `content`, along with its items and their
+    /// natspec comments.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<PathBuf>,
+    /// The languages to translate the docs to, each written to its own directory in the output
+    /// path.
+    ///
+    /// The `@custom:<language>-<tag>` natspec comments replace the `@<tag>` ones in a language,
+    /// e.g. `@custom:fr-notice` replaces `@notice` in the `fr` docs.
+    pub languages: Vec<String>,
 }
 
 impl Default for DocConfig {
@@ -33,6 +50,10 @@ fn default() -> Self {
             title: String::default(),
             repository: None,
             ignore: Vec::default(),
+            include_scripts: false,
+            include_tests: false,
+            template: None,
+            languages: Vec::default(),
         }
     }
 }
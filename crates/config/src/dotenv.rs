@@ -0,0 +1,213 @@
+//! Layered `.env` file support.
+//!
+//! Environment files are resolved relative to the project root and applied in the following
+//! order, where later files take precedence over earlier ones:
+//!
+//! 1. `.env` in the current working directory, if it is not the project root
+//! 2. `.env`
+//! 3. `.env.local`
+//! 4. `.env.<profile>`, where `<profile>` is the selected config profile (`FOUNDRY_PROFILE`)
+//!
+//! Variables that are set in the process environment always take precedence over all files.
+//! `FOUNDRY_PROFILE` itself may be set in `.env` or `.env.local` to select the profile file.
+
+use crate::Config;
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+/// The `.env` files that were loaded at startup.
+static LOADED: OnceLock<Dotenv> = OnceLock::new();
+
+/// The resolved `.env` layers of a project.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Dotenv {
+    /// The project root the files were resolved against.
+    pub root: PathBuf,
+    /// The selected profile, used to resolve the `.env.<profile>` file.
+    pub profile: String,
+    /// Candidate files, from lowest to highest precedence.
+    pub files: Vec<DotenvFile>,
+    /// The variables defined by the files.
+    pub vars: BTreeMap<String, DotenvVar>,
+}
+
+/// A candidate `.env` file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DotenvFile {
+    /// Path of the file.
+    pub path: PathBuf,
+    /// Whether the file exists and was read.
+    pub loaded: bool,
+}
+
+/// A variable defined in one or more `.env` files.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DotenvVar {
+    /// The file that supplies the value.
+    pub source: PathBuf,
+    /// Lower precedence files that also define the variable.
+    pub overrides: Vec<PathBuf>,
+    /// Whether the variable is set in the process environment, which takes precedence over the
+    /// value of the file.
+    pub from_process_env: bool,
+    #[serde(skip)]
+    value: String,
+}
+
+impl Dotenv {
+    /// Resolves the `.env` layers of the project at `root` and applies them to the process
+    /// environment.
+    ///
+    /// The result is stored and can be retrieved later with [`Dotenv::loaded`]. Files that can't
+    /// be read or parsed are ignored.
+    pub fn load(root: &Path, cwd: &Path) -> &'static Self {
+        let dotenv = Self::resolve(root, cwd, |name| std::env::var(name).ok());
+        dotenv.apply();
+        let _ = LOADED.set(dotenv);
+        LOADED.get().expect("initialized")
+    }
+
+    /// Returns the `.env` layers that were loaded with [`Dotenv::load`], if any.
+    pub fn loaded() -> Option<&'static Self> {
+        LOADED.get()
+    }
+
+    /// Returns `true` if the variable was set by a loaded `.env` file rather than by the process
+    /// environment.
+    pub fn is_from_file(name: &str) -> bool {
+        Self::loaded()
+            .and_then(|dotenv| dotenv.vars.get(name))
+            .is_some_and(|var| !var.from_process_env)
+    }
+
+    /// Resolves the `.env` layers of the project at `root` without modifying the process
+    /// environment.
+    ///
+    /// `env` returns the value of a variable in the process environment.
+    pub fn resolve(root: &Path, cwd: &Path, env: impl Fn(&str) -> Option<String>) -> Self {
+        let mut dotenv = Self { root: root.to_path_buf(), ..Default::default() };
+
+        if cwd != root {
+            dotenv.read(cwd.join(".env"), &env);
+        }
+        dotenv.read(root.join(".env"), &env);
+        dotenv.read(root.join(".env.local"), &env);
+
+        dotenv.profile = env("FOUNDRY_PROFILE")
+            .or_else(|| dotenv.vars.get("FOUNDRY_PROFILE").map(|var| var.value.clone()))
+            .filter(|profile| !profile.is_empty())
+            .unwrap_or_else(|| Config::DEFAULT_PROFILE.to_string());
+        dotenv.read(root.join(format!(".env.{}", dotenv.profile)), &env);
+
+        dotenv
+    }
+
+    /// Sets all variables that are not already set in the process environment.
+    pub fn apply(&self) {
+        for (name, var) in &self.vars {
+            if !var.from_process_env {
+                std::env::set_var(name, &var.value);
+            }
+        }
+    }
+
+    /// Returns the path relative to the project root, for display purposes.
+    pub fn display_path<'a>(&self, path: &'a Path) -> &'a Path {
+        path.strip_prefix(&self.root).unwrap_or(path)
+    }
+
+    /// Reads a file and merges its variables, overriding those of previously read files.
+    fn read(&mut self, path: PathBuf, env: &impl Fn(&str) -> Option<String>) {
+        let entries = dotenvy::from_path_iter(&path).map(|iter| iter.filter_map(Result::ok));
+        let loaded = entries.is_ok();
+        for (name, value) in entries.into_iter().flatten() {
+            let from_process_env = env(&name).is_some();
+            match self.vars.get_mut(&name) {
+                Some(var) => {
+                    let previous = std::mem::replace(&mut var.source, path.clone());
+                    var.overrides.push(previous);
+                    var.value = value;
+                }
+                None => {
+                    let var = DotenvVar {
+                        source: path.clone(),
+                        overrides: Vec::new(),
+                        from_process_env,
+                        value,
+                    };
+                    self.vars.insert(name, var);
+                }
+            }
+        }
+        self.files.push(DotenvFile { path, loaded });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_layers_in_order() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let nested = root.join("nested");
+        std::fs::create_dir(&nested).unwrap();
+
+        std::fs::write(nested.join(".env"), "CWD=cwd\nSHARED=cwd").unwrap();
+        std::fs::write(root.join(".env"), "SHARED=env\nFOUNDRY_PROFILE=ci\nPROCESS=env").unwrap();
+        std::fs::write(root.join(".env.local"), "SHARED=local\nLOCAL=local").unwrap();
+        std::fs::write(root.join(".env.ci"), "SHARED=ci").unwrap();
+        std::fs::write(root.join(".env.default"), "DEFAULT=default").unwrap();
+
+        let env = |name: &str| (name == "PROCESS").then(|| "process".to_string());
+        let dotenv = Dotenv::resolve(root, &nested, env);
+
+        assert_eq!(dotenv.profile, "ci");
+        assert_eq!(
+            dotenv
+                .files
+                .iter()
+                .map(|f| (dotenv.display_path(&f.path), f.loaded))
+                .collect::<Vec<_>>(),
+            vec![
+                (Path::new("nested/.env"), true),
+                (Path::new(".env"), true),
+                (Path::new(".env.local"), true),
+                (Path::new(".env.ci"), true),
+            ]
+        );
+
+        let shared = &dotenv.vars["SHARED"];
+        assert_eq!(shared.source, root.join(".env.ci"));
+        assert_eq!(shared.value, "ci");
+        assert_eq!(
+            shared.overrides,
+            vec![nested.join(".env"), root.join(".env"), root.join(".env.local")]
+        );
+        assert_eq!(dotenv.vars["CWD"].source, nested.join(".env"));
+        assert_eq!(dotenv.vars["LOCAL"].source, root.join(".env.local"));
+        assert!(dotenv.vars["PROCESS"].from_process_env);
+        assert!(!dotenv.vars.contains_key("DEFAULT"));
+    }
+
+    #[test]
+    fn process_env_selects_profile() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        std::fs::write(root.join(".env"), "FOUNDRY_PROFILE=ci").unwrap();
+        std::fs::write(root.join(".env.release"), "RELEASE=1").unwrap();
+
+        let env = |name: &str| (name == "FOUNDRY_PROFILE").then(|| "release".to_string());
+        let dotenv = Dotenv::resolve(root, root, env);
+
+        assert_eq!(dotenv.profile, "release");
+        assert_eq!(dotenv.files.len(), 3);
+        assert!(!dotenv.files[1].loaded);
+        assert_eq!(dotenv.vars["RELEASE"].source, root.join(".env.release"));
+    }
+}
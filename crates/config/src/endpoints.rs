@@ -52,6 +52,12 @@ fn deref(&self) -> &Self::Target {
     }
 }
 
+impl DerefMut for RpcEndpoints {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.endpoints
+    }
+}
+
 /// RPC endpoint wrapper type
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(untagged)]
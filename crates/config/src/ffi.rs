@@ -0,0 +1,28 @@
+//! Configuration for the `ffi` cheatcode.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Limits enforced on every command executed with the `ffi` cheatcode.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FfiSandboxConfig {
+    /// The maximum number of seconds a command is allowed to run for before being killed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// The maximum number of bytes a command is allowed to write to stdout and stderr each.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output: Option<usize>,
+    /// The executables that are allowed to be invoked, by name or by path relative to the working
+    /// directory.
+    ///
+    /// Names are looked up in `PATH`, and a command is only allowed if it resolves to the same
+    /// executable as one of these. If empty, all executables are allowed.
+    #[serde(default)]
+    pub allowed_commands: Vec<String>,
+    /// The directory, relative to the project root, in which commands are executed.
+    ///
+    /// If set, executables given as a path must also be located inside of this directory.
+    /// Defaults to the project root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<PathBuf>,
+}
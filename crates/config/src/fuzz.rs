@@ -30,6 +30,18 @@ pub struct FuzzConfig {
     pub show_logs: bool,
     /// Optional timeout (in seconds) for each property test
     pub timeout: Option<u32>,
+    /// Fuzz dictionary files to import values from before fuzzing, e.g. dictionaries exported by
+    /// other projects.
+    pub dictionary_files: Vec<PathBuf>,
+    /// Path to export the fuzz dictionaries learned by all fuzz tests to.
+    pub dictionary_export: Option<PathBuf>,
+    /// Whether to enumerate all the values of parameters with small domains, e.g. `bool`,
+    /// `uint8` or parameters with fixtures, instead of sampling them. The combinations are
+    /// limited to `runs` cases, which run before the sampled ones.
+    pub exhaustive_small_domains: bool,
+    /// Whether to track the edge coverage of every run, and mutate the inputs that increased it
+    /// to generate new inputs, in addition to the random and dictionary inputs.
+    pub coverage_guided: bool,
 }
 
 impl Default for FuzzConfig {
@@ -44,6 +56,10 @@ fn default() -> Self {
             failure_persist_file: None,
             show_logs: false,
             timeout: None,
+            dictionary_files: Vec::new(),
+            dictionary_export: None,
+            exhaustive_small_domains: false,
+            coverage_guided: false,
         }
     }
 }
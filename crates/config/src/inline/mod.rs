@@ -1,4 +1,4 @@
-use crate::Config;
+use crate::{filter::GlobMatcher, Config};
 use alloy_primitives::map::HashMap;
 use figment::{
     value::{Dict, Map, Value},
@@ -6,6 +6,7 @@
 };
 use foundry_compilers::ProjectCompileOutput;
 use itertools::Itertools;
+use std::path::Path;
 
 mod natspec;
 pub use natspec::*;
@@ -23,6 +24,9 @@ pub enum InlineConfigErrorKind {
     /// An invalid profile has been provided.
     #[error("invalid profile `{0}`; valid profiles: {1}")]
     InvalidProfile(String, String),
+    /// An invalid glob has been provided in the `overrides` config.
+    #[error("invalid glob: {0}")]
+    InvalidGlob(String),
 }
 
 /// Wrapper error struct that catches config parsing errors, enriching them with context information
@@ -40,8 +44,13 @@ pub struct InlineConfigError {
 /// Represents per-test configurations, declared inline
 /// as structured comments in Solidity test files. This allows
 /// to create configs directly bound to a solidity test.
+///
+/// It also holds the [`overrides`](Config::overrides) of the test files matching a glob, which
+/// the inline configurations take precedence over.
 #[derive(Clone, Debug, Default)]
 pub struct InlineConfig {
+    /// Configuration of the test files matching a glob.
+    path_level: Vec<(GlobMatcher, DataMap)>,
     /// Contract-level configuration.
     contract_level: HashMap<String, DataMap>,
     /// Function-level configuration.
@@ -60,6 +69,7 @@ pub fn new_parsed(output: &ProjectCompileOutput, config: &Config) -> eyre::Resul
         let natspecs: Vec<NatSpec> = NatSpec::parse(output, &config.root);
         let profiles = &config.profiles;
         let mut inline = Self::new();
+        inline.insert_overrides(config)?;
         for natspec in &natspecs {
             inline.insert(natspec)?;
             // Validate after parsing as TOML.
@@ -68,6 +78,19 @@ pub fn new_parsed(output: &ProjectCompileOutput, config: &Config) -> eyre::Resul
         Ok(inline)
     }
 
+    /// Inserts the [`overrides`](Config::overrides) of the config into the [`InlineConfig`].
+    pub fn insert_overrides(&mut self, config: &Config) -> Result<(), InlineConfigError> {
+        for (glob, dict) in &config.overrides {
+            let matcher = glob.parse::<GlobMatcher>().map_err(|e| InlineConfigError {
+                location: format!("overrides.\"{glob}\""),
+                kind: InlineConfigErrorKind::InvalidGlob(e.to_string()),
+            })?;
+            let data = DataMap::from([(config.profile.clone(), dict.clone())]);
+            self.path_level.push((matcher, data));
+        }
+        Ok(())
+    }
+
     /// Inserts a new [`NatSpec`] into the [`InlineConfig`].
     pub fn insert(&mut self, natspec: &NatSpec) -> Result<(), InlineConfigError> {
         let map = if let Some(function) = &natspec.function {
@@ -107,9 +130,11 @@ pub fn merge(&self, contract: &str, function: &str, base: &Config) -> Figment {
         Figment::from(base).merge(self.provide(contract, function))
     }
 
-    /// Returns `true` if a configuration is present at the given contract level.
+    /// Returns `true` if a configuration is present at the given contract level, or for the file
+    /// of the contract.
     pub fn contains_contract(&self, contract: &str) -> bool {
-        self.get_contract(contract).is_some_and(|map| !map.is_empty())
+        self.get_contract(contract).is_some_and(|map| !map.is_empty()) ||
+            self.get_path(contract).any(|map| !map.is_empty())
     }
 
     /// Returns `true` if a configuration is present at the function level.
@@ -119,6 +144,13 @@ pub fn contains_function(&self, contract: &str, function: &str) -> bool {
         self.get_function(contract, function).is_some_and(|map| !map.is_empty())
     }
 
+    /// Returns the configurations of the globs matching the file of the contract, given as
+    /// `<path>:<name>`.
+    fn get_path<'a>(&'a self, contract: &'a str) -> impl Iterator<Item = &'a DataMap> + 'a {
+        let path = Path::new(contract.rsplit_once(':').map_or(contract, |(path, _)| path));
+        self.path_level.iter().filter(move |(glob, _)| glob.is_match(path)).map(|(_, map)| map)
+    }
+
     fn get_contract(&self, contract: &str) -> Option<&DataMap> {
         self.contract_level.get(contract)
     }
@@ -146,6 +178,9 @@ fn metadata(&self) -> figment::Metadata {
 
     fn data(&self) -> figment::Result<DataMap> {
         let mut map = DataMap::new();
+        for new in self.inline.get_path(self.contract) {
+            extend_data_map(&mut map, new);
+        }
         if let Some(new) = self.inline.get_contract(self.contract) {
             extend_data_map(&mut map, new);
         }
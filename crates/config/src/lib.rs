@@ -49,7 +49,7 @@
     path::{Path, PathBuf},
     str::FromStr,
     sync::mpsc::{self, RecvTimeoutError},
-    time::Duration,
+    time::{Duration, SystemTime},
 };
 
 mod macros;
@@ -63,15 +63,17 @@
 };
 
 mod etherscan;
-use etherscan::{
-    EtherscanConfigError, EtherscanConfigs, EtherscanEnvProvider, ResolvedEtherscanConfig,
+use etherscan::EtherscanEnvProvider;
+pub use etherscan::{
+    EtherscanApiKey, EtherscanConfig, EtherscanConfigError, EtherscanConfigs,
+    ResolvedEtherscanConfig, ResolvedEtherscanConfigs,
 };
 
 mod resolve;
 pub use resolve::UnresolvedEnvVarError;
 
 pub mod cache;
-use cache::{Cache, ChainCache};
+use cache::{Cache, ChainCache, EvictedBlock};
 
 pub mod fmt;
 pub use fmt::FormatterConfig;
@@ -87,21 +89,40 @@
 pub mod doc;
 pub use doc::DocConfig;
 
+pub mod lint;
+pub use lint::{LintConfig, LintSeverity};
+
 pub mod filter;
 pub use filter::SkipBuildFilters;
 
 mod warning;
 pub use warning::*;
 
+mod validate;
+pub use validate::{ValidationError, ValidationErrors};
+
 pub mod fix;
 
+pub mod dotenv;
+pub use dotenv::Dotenv;
+
+mod diff;
+pub use diff::{ConfigDiff, ConfigDiffEntry};
+
+pub mod provenance;
+use provenance::FigmentTraceExt;
+pub use provenance::Provenance;
+
 // reexport so cli types can implement `figment::Provider` to easily merge compiler arguments
 pub use alloy_chains::{Chain, NamedChain};
 pub use figment;
 
 pub mod providers;
-pub use providers::Remappings;
 use providers::*;
+pub use providers::{
+    ConfigOverride, ConfigOverrides, ConflictingRemappings, RemappingCandidate, RemappingSource,
+    Remappings, RemappingsReport,
+};
 
 mod fuzz;
 pub use fuzz::{FuzzConfig, FuzzDictionaryConfig};
@@ -121,9 +142,27 @@
 mod bind_json;
 use bind_json::BindJsonConfig;
 
+mod ffi;
+pub use ffi::FfiSandboxConfig;
+
+mod debugger;
+pub use debugger::{DebuggerConfig, DebuggerLayout, DebuggerPane, DebuggerPaneSizes};
+
+mod build_hooks;
+pub use build_hooks::{BuildConfig, BuildHook, BuildHooksConfig};
+
+mod project_dirs;
+pub use project_dirs::ProjectDirs;
+
+mod transaction;
+pub use transaction::TransactionWatchConfig;
+
 mod compilation;
 use compilation::{CompilationRestrictions, SettingsOverrides};
 
+mod remappings_update;
+pub use remappings_update::{dependency_remappings, RemappingConflict, RemappingsUpdate};
+
 /// Foundry configuration
 ///
 /// # Defaults
@@ -155,7 +194,13 @@
 ///     the "default" meta-profile.
 ///
 /// Note that these behaviors differ from those of [`Config::figment()`].
+///
+/// # Serialization
+///
+/// The values set by the secrets files are serialized as [`Config::REDACTED`], see
+/// [`Config::secrets`].
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub struct Config {
     /// The selected profile. **(default: _default_ `default`)**
     ///
@@ -322,8 +367,16 @@ pub struct Config {
     pub invariant: InvariantConfig,
     /// Whether to allow ffi cheatcodes in test
     pub ffi: bool,
+    /// Limits enforced on the commands executed by the ffi cheatcodes
+    pub ffi_sandbox: FfiSandboxConfig,
     /// Whether to allow `expectRevert` for internal functions.
     pub allow_internal_expect_revert: bool,
+    /// Whether to fail tests whose Solidity contracts corrupt the reserved memory slots or read
+    /// uninitialized memory.
+    pub memory_safety: bool,
+    /// Whether unknown keys and sections in the config files are errors instead of warnings,
+    /// e.g. a misspelled `optimzer_runs`.
+    pub strict_config: bool,
     /// Use the create 2 factory in all cases including tests and non-broadcasting scripts.
     pub always_use_create_2_factory: bool,
     /// Sets a timeout in seconds for vm.prompt cheatcodes
@@ -367,6 +420,13 @@ pub struct Config {
     ///
     /// The default is 128MiB.
     pub memory_limit: u64,
+    /// The maximum bytes of memory and stack the interpreter may use across the call frames of a
+    /// test, distinct from [`memory_limit`](Self::memory_limit).
+    ///
+    /// A test exceeding it fails, with the call frame and opcode that exceeded it.
+    pub test_memory_limit: Option<u64>,
+    /// Whether to report the peak bytes of memory and stack used by the interpreter in each test.
+    pub memory_report: bool,
     /// Additional output selection for all contracts, such as "ir", "devdoc", "storageLayout",
     /// etc.
     ///
@@ -446,8 +506,14 @@ pub struct Config {
     pub fmt: FormatterConfig,
     /// Configuration for `forge doc`
     pub doc: DocConfig,
+    /// Configuration for `forge lint`
+    pub lint: LintConfig,
     /// Configuration for `forge bind-json`
     pub bind_json: BindJsonConfig,
+    /// Configuration for the TUI debugger
+    pub debugger: DebuggerConfig,
+    /// Configuration for `forge build`, such as the commands run before and after compiling
+    pub build: BuildConfig,
     /// Configures the permissions of cheat codes that touch the file system.
     ///
     /// This includes what operations can be executed (read, write)
@@ -464,6 +530,19 @@ pub struct Config {
     /// Address labels
     pub labels: AddressHashMap<String>,
 
+    /// Files of address labels, relative to the project root.
+    ///
+    /// Each file is a JSON object mapping addresses to labels. See [`Config::load_label_files`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub label_files: Vec<PathBuf>,
+
+    /// Environment variables to set when this profile is selected.
+    ///
+    /// Variables that are already set in the process environment take precedence, while values
+    /// from `.env` files are overridden. See [`Config::apply_env`].
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+
     /// Whether to enable safety checks for `vm.getCode` and `vm.getDeployedCode` invocations.
     /// If disabled, it is possible to access artifacts which were not recompiled or cached.
     pub unchecked_cheatcode_artifacts: bool,
@@ -506,6 +585,9 @@ pub struct Config {
     /// Timeout for transactions in seconds.
     pub transaction_timeout: u64,
 
+    /// Polling settings used when waiting for transactions to be confirmed.
+    pub transaction_watch: TransactionWatchConfig,
+
     /// Use EOF-enabled solc for compilation.
     pub eof: bool,
 
@@ -513,6 +595,14 @@ pub struct Config {
     #[serde(rename = "__warnings", default, skip_serializing)]
     pub warnings: Vec<Warning>,
 
+    /// The keys set by the secrets files, e.g. `etherscan_api_key` or `rpc_endpoints.mainnet`.
+    ///
+    /// The secrets files are `~/.foundry/secrets.toml` and `foundry.secrets.toml` in the project
+    /// root. They can only set API keys and RPC URLs, which are redacted when the config is
+    /// serialized and never written back by [`Self::to_string_pretty`].
+    #[serde(rename = "__secrets", default, skip_serializing)]
+    pub secrets: Vec<String>,
+
     /// Additional settings profiles to use when compiling.
     #[serde(default)]
     pub additional_compiler_profiles: Vec<SettingsOverrides>,
@@ -521,6 +611,13 @@ pub struct Config {
     #[serde(default)]
     pub compilation_restrictions: Vec<CompilationRestrictions>,
 
+    /// Config overrides for the test files matching a glob, declared as
+    /// `[profile.default.overrides."test/integration/**"]` tables.
+    ///
+    /// They are applied like [inline config](InlineConfig), which takes precedence over them.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub overrides: BTreeMap<String, Dict>,
+
     /// PRIVATE: This structure may grow, As such, constructing this structure should
     /// _always_ be done using a public constructor or update syntax:
     ///
@@ -558,6 +655,7 @@ impl Config {
         "etherscan",
         "fmt",
         "doc",
+        "lint",
         "fuzz",
         "invariant",
         "labels",
@@ -565,11 +663,23 @@ impl Config {
         "soldeer",
         "vyper",
         "bind_json",
+        "debugger",
+        "build",
     ];
 
     /// File name of config toml file
     pub const FILE_NAME: &'static str = "foundry.toml";
 
+    /// The value that replaces the values of the secrets files when the config is serialized, see
+    /// [`Self::secrets`].
+    pub const REDACTED: &'static str = "<redacted>";
+
+    /// File name of the project's secrets file, see [`Self::secrets`]
+    pub const SECRETS_FILE_NAME: &'static str = "foundry.secrets.toml";
+
+    /// File name of the global secrets file in [`Self::foundry_dir`], see [`Self::secrets`]
+    pub const GLOBAL_SECRETS_FILE_NAME: &'static str = "secrets.toml";
+
     /// The name of the directory foundry reserves for itself under the user's home directory: `~`
     pub const FOUNDRY_DIR_NAME: &'static str = ".foundry";
 
@@ -610,6 +720,19 @@ pub fn load_with_root(root: impl AsRef<Path>) -> Result<Self, ExtractConfigError
         Self::from_provider(Self::figment_with_root(root.as_ref()))
     }
 
+    /// Loads the `Config` of the project at `root` from its `foundry.toml` files and environment
+    /// variables only.
+    ///
+    /// Unlike [`load_with_root`](Self::load_with_root), this doesn't auto-detect remappings or
+    /// resolve the compiler, so it's suited for tools that only need the [`dirs`](Self::dirs),
+    /// [RPC endpoints](Self::resolved_rpc_endpoints) or
+    /// [Etherscan configs](Self::resolved_etherscan) of a project. All paths are made absolute.
+    pub fn load_minimal(root: impl AsRef<Path>) -> Result<Self, ExtractConfigError> {
+        let root = root.as_ref();
+        let config = Self { root: root.to_path_buf(), ..Self::default() };
+        Ok(Self::from_provider(config.to_figment(FigmentProviders::Cast))?.canonic_at(root))
+    }
+
     /// Attempts to extract a `Config` from `provider`, returning the result.
     ///
     /// # Example
@@ -637,9 +760,27 @@ pub fn try_from<T: Provider>(provider: T) -> Result<Self, ExtractConfigError> {
     }
 
     fn from_figment(figment: Figment) -> Result<Self, ExtractConfigError> {
+        let figment = ConfigOverrides::merge_global(figment);
+        // Placeholders of unset variables are only errors if no other source overrides them.
+        if let Ok(data) = figment.extract::<figment::value::Dict>() {
+            providers::ensure_resolved(&data).map_err(ExtractConfigError::new)?;
+        }
         let mut config = figment.extract::<Self>().map_err(ExtractConfigError::new)?;
         config.profile = figment.profile().clone();
 
+        // Unknown keys and sections are errors in strict mode.
+        if config.strict_config {
+            let unknown = config
+                .warnings
+                .iter()
+                .filter(|warning| warning.is_unknown_config())
+                .map(|warning| figment::Error::from(warning.to_string()))
+                .reduce(figment::Error::chain);
+            if let Some(err) = unknown {
+                return Err(ExtractConfigError::new(err))
+            }
+        }
+
         // The `"profile"` profile contains all the profiles as keys.
         let mut add_profile = |profile: &Profile| {
             if !config.profiles.contains(profile) {
@@ -673,9 +814,31 @@ pub fn to_figment(&self, providers: FigmentProviders) -> Figment {
             return Figment::from(self);
         }
 
-        let root = self.root.as_path();
+        // the config is the base of the figment, see below
+        provenance::trace(self);
+
         let profile = Self::selected_profile();
-        let mut figment = Figment::default().merge(DappHardhatDirProvider(root));
+        let mut figment = self.providers_figment(profile.clone());
+
+        // only resolve remappings if all providers are requested
+        if providers.is_all() {
+            // we try to merge remappings after we've merged all other providers, this prevents
+            // redundant fs lookups to determine the default remappings that are eventually updated
+            // by other providers, like the toml file
+            let remappings = self.remappings_provider(&figment);
+            figment = figment.merge_traced(remappings);
+        }
+
+        // normalize defaults
+        figment = self.normalize_defaults(figment);
+
+        Figment::from(self).merge(figment).select(profile)
+    }
+
+    /// Returns the figment of the config files and environment variables, without remappings.
+    fn providers_figment(&self, profile: Profile) -> Figment {
+        let root = self.root.as_path();
+        let mut figment = Figment::default().merge_traced(DappHardhatDirProvider(root));
 
         // merge global foundry.toml file
         if let Some(global_toml) = Self::foundry_dir_toml().filter(|p| p.exists()) {
@@ -692,23 +855,26 @@ pub fn to_figment(&self, providers: FigmentProviders) -> Figment {
             profile.clone(),
         );
 
+        // merge secrets files
+        figment = figment.merge_traced(SecretsProvider::new(root, profile.clone()));
+
         // merge environment variables
-        figment = figment
-            .merge(
+        figment
+            .merge_traced(
                 Env::prefixed("DAPP_")
-                    .ignore(&["REMAPPINGS", "LIBRARIES", "FFI", "FS_PERMISSIONS"])
+                    .ignore(&["REMAPPINGS", "LIBRARIES", "FFI", "FS_PERMISSIONS", "ENV"])
                     .global(),
             )
-            .merge(
+            .merge_traced(
                 Env::prefixed("DAPP_TEST_")
                     .ignore(&["CACHE", "FUZZ_RUNS", "DEPTH", "FFI", "FS_PERMISSIONS"])
                     .global(),
             )
-            .merge(DappEnvCompatProvider)
-            .merge(EtherscanEnvProvider::default())
-            .merge(
+            .merge_traced(DappEnvCompatProvider)
+            .merge_traced(EtherscanEnvProvider::default())
+            .merge_traced(
                 Env::prefixed("FOUNDRY_")
-                    .ignore(&["PROFILE", "REMAPPINGS", "LIBRARIES", "FFI", "FS_PERMISSIONS"])
+                    .ignore(&["PROFILE", "REMAPPINGS", "LIBRARIES", "FFI", "FS_PERMISSIONS", "ENV"])
                     .map(|key| {
                         let key = key.as_str();
                         if Self::STANDALONE_SECTIONS.iter().any(|section| {
@@ -721,31 +887,33 @@ pub fn to_figment(&self, providers: FigmentProviders) -> Figment {
                     })
                     .global(),
             )
-            .select(profile.clone());
-
-        // only resolve remappings if all providers are requested
-        if providers.is_all() {
-            // we try to merge remappings after we've merged all other providers, this prevents
-            // redundant fs lookups to determine the default remappings that are eventually updated
-            // by other providers, like the toml file
-            let remappings = RemappingsProvider {
-                auto_detect_remappings: figment
-                    .extract_inner::<bool>("auto_detect_remappings")
-                    .unwrap_or(true),
-                lib_paths: figment
-                    .extract_inner::<Vec<PathBuf>>("libs")
-                    .map(Cow::Owned)
-                    .unwrap_or_else(|_| Cow::Borrowed(&self.libs)),
-                root,
-                remappings: figment.extract_inner::<Vec<Remapping>>("remappings"),
-            };
-            figment = figment.merge(remappings);
+            .select(profile)
+    }
+
+    /// Returns the provider that resolves the remappings on top of `figment`.
+    fn remappings_provider(&self, figment: &Figment) -> RemappingsProvider<'_> {
+        RemappingsProvider {
+            auto_detect_remappings: figment
+                .extract_inner::<bool>("auto_detect_remappings")
+                .unwrap_or(true),
+            lib_paths: figment
+                .extract_inner::<Vec<PathBuf>>("libs")
+                .map(Cow::Owned)
+                .unwrap_or_else(|_| Cow::Borrowed(&self.libs)),
+            root: &self.root,
+            remappings: figment.extract_inner::<Vec<Remapping>>("remappings"),
+            warnings: figment.extract_inner::<Vec<Warning>>("__warnings").unwrap_or_default(),
         }
+    }
 
-        // normalize defaults
-        figment = self.normalize_defaults(figment);
-
-        Figment::from(self).merge(figment).select(profile)
+    /// Returns the report of where the remappings of the project are declared, and of the
+    /// prefixes that are mapped to different paths.
+    ///
+    /// Conflicts between remappings of the same source, e.g. two dependencies, are also recorded
+    /// as [`Warning::RemappingConflict`] when the config is loaded.
+    pub fn remappings_report(&self) -> Result<RemappingsReport, figment::Error> {
+        let figment = self.providers_figment(Self::selected_profile());
+        self.remappings_provider(&figment).report()
     }
 
     /// The config supports relative paths and tracks the root path separately see
@@ -797,6 +965,12 @@ fn p(root: &Path, rem: &Path) -> PathBuf {
 
         self.libs = self.libs.into_iter().map(|lib| p(&root, &lib)).collect();
 
+        self.fuzz.dictionary_files =
+            self.fuzz.dictionary_files.into_iter().map(|file| p(&root, &file)).collect();
+        if let Some(export) = self.fuzz.dictionary_export {
+            self.fuzz.dictionary_export = Some(p(&root, &export));
+        }
+
         self.remappings =
             self.remappings.into_iter().map(|r| RelativeRemapping::new(r.into(), &root)).collect();
 
@@ -865,6 +1039,52 @@ pub fn get_normalized_evm_version(&self) -> EvmVersion {
         self.evm_version
     }
 
+    /// Sets the variables of the [`env`](Self::env) table in the process environment.
+    ///
+    /// Values loaded from `.env` files are overridden, while variables that are set in the process
+    /// environment are left untouched. A warning is added for each collision.
+    pub fn apply_env(&mut self) {
+        for (name, value) in &self.env {
+            let overridden = match std::env::var(name) {
+                Ok(current) if current == *value => continue,
+                Ok(_) => Dotenv::is_from_file(name),
+                Err(_) => {
+                    std::env::set_var(name, value);
+                    continue;
+                }
+            };
+            self.warnings.push(Warning::EnvCollision {
+                name: name.clone(),
+                profile: self.profile.to_string(),
+                overridden,
+            });
+            if overridden {
+                std::env::set_var(name, value);
+            }
+        }
+    }
+
+    /// Adds the labels of the [`label_files`](Self::label_files) to [`labels`](Self::labels).
+    ///
+    /// Labels that are already set take precedence. A warning is added for each file that can't
+    /// be read.
+    pub fn load_label_files(&mut self) {
+        for path in &self.label_files {
+            let path = self.root.join(path);
+            let labels = fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|s| {
+                serde_json::from_str::<AddressHashMap<String>>(&s).map_err(|e| e.to_string())
+            });
+            match labels {
+                Ok(labels) => {
+                    for (address, label) in labels {
+                        self.labels.entry(address).or_insert(label);
+                    }
+                }
+                Err(err) => self.warnings.push(Warning::CouldNotReadLabels { path, err }),
+            }
+        }
+    }
+
     /// Returns a sanitized version of the Config where are paths are set correctly and potential
     /// duplicates are resolved
     ///
@@ -1125,6 +1345,13 @@ fn ensure_solc(&self) -> Result<Option<Solc>, SolcError> {
 
         if let Some(solc) = &self.solc {
             let solc = match solc {
+                #[cfg(not(feature = "svm"))]
+                SolcReq::Version(version) => {
+                    return Err(SolcError::msg(format!(
+                        "can't resolve solc {version} without the `svm` feature"
+                    )));
+                }
+                #[cfg(feature = "svm")]
                 SolcReq::Version(version) => {
                     if let Some(solc) = Solc::find_svm_installed_version(version)? {
                         solc
@@ -1212,13 +1439,52 @@ pub fn project_paths<L>(&self) -> ProjectPathsConfig<L> {
         builder.build_with_root(&self.root)
     }
 
+    /// Returns the directories of the project, resolved against its root.
+    ///
+    /// Unlike [`project_paths`](Self::project_paths), this doesn't resolve remappings.
+    pub fn dirs(&self) -> ProjectDirs {
+        ProjectDirs {
+            root: self.root.clone(),
+            src: self.root.join(&self.src),
+            test: self.root.join(&self.test),
+            script: self.root.join(&self.script),
+            out: self.root.join(&self.out),
+            libs: self.libs.iter().map(|lib| self.root.join(lib)).collect(),
+            cache: self.root.join(&self.cache_path),
+            broadcast: self.root.join(&self.broadcast),
+            snapshots: self.root.join(&self.snapshots),
+        }
+    }
+
+    /// Returns the RPC endpoints with their environment variables resolved.
+    ///
+    /// Endpoints whose environment variables are not set are resolved to errors.
+    pub fn resolved_rpc_endpoints(&self) -> ResolvedRpcEndpoints {
+        self.rpc_endpoints.clone().resolved()
+    }
+
+    /// Returns the Etherscan configs with their environment variables resolved.
+    ///
+    /// Configs whose environment variables are not set are resolved to errors.
+    pub fn resolved_etherscan(&self) -> ResolvedEtherscanConfigs {
+        self.etherscan.clone().resolved()
+    }
+
     /// Returns configuration for a compiler to use when setting up a [Project].
+    ///
+    /// Without the `svm` feature, the `solc` in `PATH` is used if no `solc` is configured.
     pub fn solc_compiler(&self) -> Result<SolcCompiler, SolcError> {
         if let Some(solc) = self.ensure_solc()? {
-            Ok(SolcCompiler::Specific(solc))
-        } else {
+            return Ok(SolcCompiler::Specific(solc))
+        }
+        #[cfg(feature = "svm")]
+        {
             Ok(SolcCompiler::AutoDetect)
         }
+        #[cfg(not(feature = "svm"))]
+        {
+            Ok(SolcCompiler::Specific(Solc::new("solc")?))
+        }
     }
 
     /// Returns the solc version, if any.
@@ -1771,6 +2037,39 @@ pub fn update_libs(&self) -> eyre::Result<()> {
         })
     }
 
+    /// Returns the config with the values set by the secrets files replaced by [`Self::REDACTED`],
+    /// see [`Self::secrets`].
+    ///
+    /// This is how the config is serialized.
+    pub fn redacted(&self) -> Self {
+        let mut config = self.clone();
+        let redacted = || Self::REDACTED.to_string();
+        for secret in &self.secrets {
+            match secret.split_once('.') {
+                Some(("rpc_endpoints", name)) => {
+                    if let Some(endpoint) = config.rpc_endpoints.get_mut(name) {
+                        endpoint.endpoint = RpcEndpointUrl::Url(redacted());
+                        if endpoint.auth.is_some() {
+                            endpoint.auth = Some(endpoints::RpcAuth::Raw(redacted()));
+                        }
+                    }
+                }
+                Some(("etherscan", name)) => {
+                    if let Some(etherscan) = config.etherscan.get_mut(name) {
+                        etherscan.key = EtherscanApiKey::Key(redacted());
+                    }
+                }
+                Some(_) => {}
+                None if secret == "eth_rpc_url" => config.eth_rpc_url = Some(redacted()),
+                None if secret == "etherscan_api_key" => {
+                    config.etherscan_api_key = Some(redacted());
+                }
+                None => {}
+            }
+        }
+        config
+    }
+
     /// Serialize the config type as a String of TOML.
     ///
     /// This serializes to a table with the name of the profile
@@ -1787,6 +2086,20 @@ pub fn to_string_pretty(&self) -> Result<String, toml::ser::Error> {
         let mut value = toml::Value::try_from(self)?;
         // Config map always gets serialized as a table
         let value_table = value.as_table_mut().unwrap();
+        // never write back the values of the secrets files
+        for secret in &self.secrets {
+            match secret.split_once('.') {
+                Some((section, key)) => {
+                    if let Some(table) = value_table.get_mut(section).and_then(|v| v.as_table_mut())
+                    {
+                        table.remove(key);
+                    }
+                }
+                None => {
+                    value_table.remove(secret);
+                }
+            }
+        }
         // remove standalone sections from inner table
         let standalone_sections = Self::STANDALONE_SECTIONS
             .iter()
@@ -1875,6 +2188,16 @@ pub fn foundry_etherscan_chain_cache_dir(chain_id: impl Into<Chain>) -> Option<P
         Some(Self::foundry_etherscan_cache_dir()?.join(chain_id.into().to_string()))
     }
 
+    /// Returns the path to the directory the verified sources of the contract at `address` on
+    /// `chain_id` are compiled in: `~/.foundry/cache/etherscan/<chain>/sources/<address>`
+    pub fn foundry_etherscan_sources_dir(
+        chain_id: impl Into<Chain>,
+        address: Address,
+    ) -> Option<PathBuf> {
+        let chain_dir = Self::foundry_etherscan_chain_cache_dir(chain_id)?;
+        Some(chain_dir.join("sources").join(address.to_string()))
+    }
+
     /// Returns the path to the cache dir of the `block` on the `chain`:
     /// `~/.foundry/cache/rpc/<chain>/<block>`
     pub fn foundry_block_cache_dir(chain_id: impl Into<Chain>, block: u64) -> Option<PathBuf> {
@@ -1988,6 +2311,15 @@ pub fn clean_foundry_etherscan_chain_cache(chain: Chain) -> eyre::Result<()> {
         Ok(())
     }
 
+    /// Evicts the cached RPC data exceeding the limits of `caching`, see
+    /// [`cache::evict_cached_blocks`].
+    pub fn evict_rpc_cache(caching: &StorageCachingConfig) -> eyre::Result<Vec<EvictedBlock>> {
+        let Some(cache_dir) = Self::foundry_rpc_cache_dir() else {
+            eyre::bail!("failed to get foundry_rpc_cache_dir");
+        };
+        cache::evict_cached_blocks(&cache_dir, caching, SystemTime::now())
+    }
+
     /// List the data in the foundry cache.
     pub fn list_foundry_cache() -> eyre::Result<Cache> {
         if let Some(cache_dir) = Self::foundry_rpc_cache_dir() {
@@ -2089,39 +2421,96 @@ fn merge_toml_provider(
             figment.merge(warnings)
         };
 
+        // resolve the profiles extended by the selected profile, errors are reported by the
+        // warnings provider
+        let (extended, _) =
+            Self::extended_profiles(&toml_provider.data().unwrap_or_default(), &profile)
+                .unwrap_or_default();
+
         // use [profile.<profile>] as [<profile>]
         let mut profiles = vec![Self::DEFAULT_PROFILE];
         if profile != Self::DEFAULT_PROFILE {
+            profiles.extend(extended.iter().cloned());
             profiles.push(profile.clone());
         }
-        let provider = toml_provider.strict_select(profiles);
+        let provider = toml_provider.strict_select(profiles.clone());
 
         // apply any key fixes
-        let provider = &BackwardsCompatTomlProvider(ForcedSnakeCaseData(provider));
+        let provider = BackwardsCompatTomlProvider(ForcedSnakeCaseData(provider));
+
+        // resolve `${ENV_VAR}` placeholders in string values
+        let provider = &EnvInterpolationProvider::new(provider, profiles);
 
         // merge the default profile as a base
         if profile != Self::DEFAULT_PROFILE {
-            figment = figment.merge(provider.rename(Self::DEFAULT_PROFILE, profile.clone()));
+            figment = figment.merge_traced(provider.rename(Self::DEFAULT_PROFILE, profile.clone()));
         }
         // merge special keys into config
         for standalone_key in Self::STANDALONE_SECTIONS {
             if let Some((_, fallback)) =
                 STANDALONE_FALLBACK_SECTIONS.iter().find(|(key, _)| standalone_key == key)
             {
-                figment = figment.merge(
+                figment = figment.merge_traced(
                     provider
                         .fallback(standalone_key, fallback)
                         .wrap(profile.clone(), standalone_key),
                 );
             } else {
-                figment = figment.merge(provider.wrap(profile.clone(), standalone_key));
+                figment = figment.merge_traced(provider.wrap(profile.clone(), standalone_key));
             }
         }
+        // merge the extended profiles, from the most distant ancestor
+        for extended in extended {
+            figment = figment.merge_traced(provider.rename(extended, profile.clone()));
+        }
         // merge the profile
-        figment = figment.merge(provider);
+        figment = figment.merge_traced(provider);
         figment
     }
 
+    /// Returns the profiles extended by `profile` with `extends = "<profile>"`, from the most
+    /// distant ancestor to the direct parent. The default profile is always the base, so it's not
+    /// included.
+    ///
+    /// Resolution stops at the first unknown profile, for which a warning is returned. A cyclic
+    /// `extends` chain is an error.
+    pub(crate) fn extended_profiles(
+        data: &Map<Profile, Dict>,
+        profile: &Profile,
+    ) -> Result<(Vec<Profile>, Option<Warning>), figment::Error> {
+        let profiles = data.get(&Profile::new(Self::PROFILE_SECTION));
+        let get = |name: &Profile| {
+            profiles?.iter().find(|(key, _)| *name == key.as_str()).and_then(|(_, v)| v.as_dict())
+        };
+
+        let mut chain = Vec::new();
+        let mut warning = None;
+        let mut current = profile.clone();
+        while let Some(parent) =
+            get(&current).and_then(|dict| dict.get("extends")).and_then(Value::as_str)
+        {
+            let parent = Profile::new(parent);
+            if parent == Self::DEFAULT_PROFILE {
+                break
+            }
+            if parent == *profile || chain.contains(&parent) {
+                return Err(figment::Error::from(format!(
+                    "Could not resolve `extends` of [profile.{current}]: profile `{parent}` is \
+                     extended cyclically"
+                )))
+            }
+            if get(&parent).is_none() {
+                let err = format!("unknown profile `{parent}`");
+                warning = Some(Warning::InvalidExtends { profile: current.to_string(), err });
+                break
+            }
+            chain.push(parent.clone());
+            current = parent;
+        }
+        chain.reverse();
+        Ok((chain, warning))
+    }
+
     /// Check if any defaults need to be normalized.
     ///
     /// This normalizes the default `evm_version` if a `solc` was provided in the config.
@@ -2140,7 +2529,7 @@ fn normalize_defaults(&self, mut figment: Figment) -> Figment {
                 .ok()
                 .and_then(|version| self.evm_version.normalize_version_solc(&version))
             {
-                figment = figment.merge(("evm_version", version));
+                figment = figment.merge_traced(("evm_version", version));
             }
         }
 
@@ -2291,7 +2680,8 @@ fn metadata(&self) -> Metadata {
 
     #[track_caller]
     fn data(&self) -> Result<Map<Profile, Dict>, figment::Error> {
-        let mut data = Serialized::defaults(self).data()?;
+        // the values of the secrets files are only redacted in the output
+        let mut data = Serialized::defaults(Unredacted(self)).data()?;
         if let Some(entry) = data.get_mut(&self.profile) {
             entry.insert("root".to_string(), Value::serialize(self.root.clone())?);
         }
@@ -2303,6 +2693,31 @@ fn profile(&self) -> Option<Profile> {
     }
 }
 
+impl Serialize for Config {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.secrets.is_empty() {
+            Self::serialize(self, serializer)
+        } else {
+            Self::serialize(&self.redacted(), serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Config {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::deserialize(deserializer)
+    }
+}
+
+/// Serializes a [`Config`] without redacting the values of the secrets files.
+struct Unredacted<'a>(&'a Config);
+
+impl Serialize for Unredacted<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Config::serialize(self.0, serializer)
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -2354,7 +2769,10 @@ fn default() -> Self {
             invariant: InvariantConfig::new("cache/invariant".into()),
             always_use_create_2_factory: false,
             ffi: false,
+            ffi_sandbox: Default::default(),
             allow_internal_expect_revert: false,
+            memory_safety: false,
+            strict_config: false,
             prompt_timeout: 120,
             sender: Self::DEFAULT_SENDER,
             tx_origin: Self::DEFAULT_SENDER,
@@ -2373,6 +2791,8 @@ fn default() -> Self {
             block_gas_limit: None,
             disable_block_gas_limit: false,
             memory_limit: 1 << 27, // 2**27 = 128MiB = 134_217_728 bytes
+            test_memory_limit: None,
+            memory_report: false,
             eth_rpc_url: None,
             eth_rpc_jwt: None,
             eth_rpc_timeout: None,
@@ -2406,8 +2826,13 @@ fn default() -> Self {
             build_info_path: None,
             fmt: Default::default(),
             doc: Default::default(),
+            lint: Default::default(),
             bind_json: Default::default(),
+            debugger: Default::default(),
+            build: Default::default(),
             labels: Default::default(),
+            label_files: Default::default(),
+            env: Default::default(),
             unchecked_cheatcode_artifacts: false,
             create2_library_salt: Self::DEFAULT_CREATE2_LIBRARY_SALT,
             create2_deployer: Self::DEFAULT_CREATE2_DEPLOYER,
@@ -2417,12 +2842,15 @@ fn default() -> Self {
             assertions_revert: true,
             legacy_assertions: false,
             warnings: vec![],
+            secrets: vec![],
             extra_args: vec![],
             eof_version: None,
             odyssey: false,
             transaction_timeout: 120,
+            transaction_watch: Default::default(),
             additional_compiler_profiles: Default::default(),
             compilation_restrictions: Default::default(),
+            overrides: Default::default(),
             eof: false,
             _non_exhaustive: (),
         }
@@ -2715,6 +3143,63 @@ fn ffi_env_disallowed() {
         });
     }
 
+    #[test]
+    fn test_profile_env_table() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [profile.default.env]
+                FOUNDRY_TEST_PROFILE_ENV_RPC = "http://localhost:8545"
+                FOUNDRY_TEST_PROFILE_ENV_KEY = "default"
+                [profile.ci.env]
+                FOUNDRY_TEST_PROFILE_ENV_RPC = "https://ci.example.com"
+                FOUNDRY_TEST_PROFILE_ENV_PROCESS = "ci"
+            "#,
+            )?;
+            jail.set_env("FOUNDRY_PROFILE", "ci");
+            jail.set_env("FOUNDRY_TEST_PROFILE_ENV_PROCESS", "process");
+
+            let mut config = Config::load().unwrap();
+            assert_eq!(
+                config.env,
+                BTreeMap::from([
+                    ("FOUNDRY_TEST_PROFILE_ENV_KEY".to_string(), "default".to_string()),
+                    ("FOUNDRY_TEST_PROFILE_ENV_PROCESS".to_string(), "ci".to_string()),
+                    (
+                        "FOUNDRY_TEST_PROFILE_ENV_RPC".to_string(),
+                        "https://ci.example.com".to_string()
+                    ),
+                ])
+            );
+
+            config.apply_env();
+            assert_eq!(
+                std::env::var("FOUNDRY_TEST_PROFILE_ENV_RPC").unwrap(),
+                "https://ci.example.com"
+            );
+            assert_eq!(std::env::var("FOUNDRY_TEST_PROFILE_ENV_KEY").unwrap(), "default");
+            assert_eq!(std::env::var("FOUNDRY_TEST_PROFILE_ENV_PROCESS").unwrap(), "process");
+            assert_eq!(
+                config.warnings,
+                vec![Warning::EnvCollision {
+                    name: "FOUNDRY_TEST_PROFILE_ENV_PROCESS".to_string(),
+                    profile: "ci".to_string(),
+                    overridden: false,
+                }]
+            );
+
+            // Values that were already applied don't collide again.
+            config.warnings.clear();
+            config.apply_env();
+            assert_eq!(config.warnings.len(), 1);
+
+            std::env::remove_var("FOUNDRY_TEST_PROFILE_ENV_RPC");
+            std::env::remove_var("FOUNDRY_TEST_PROFILE_ENV_KEY");
+            Ok(())
+        });
+    }
+
     #[test]
     fn test_profile_env() {
         figment::Jail::expect_with(|jail| {
@@ -2870,6 +3355,64 @@ fn test_remappings() {
         });
     }
 
+    #[test]
+    fn test_remappings_conflicts() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "remappings.txt",
+                r"
+                ds-test/=lib/ds-test/
+                other/=lib/other/
+                other/=lib/other/src/
+            ",
+            )?;
+            jail.set_env("DAPP_REMAPPINGS", "ds-test/=lib/ds-test/src/");
+
+            let config = Config::load().unwrap();
+            assert_eq!(
+                config.remappings,
+                vec![
+                    Remapping::from_str("ds-test/=lib/ds-test/src/").unwrap().into(),
+                    Remapping::from_str("other/=lib/other/").unwrap().into(),
+                ],
+            );
+
+            let report = config.remappings_report().unwrap();
+            assert_eq!(
+                report.remappings,
+                vec![
+                    (
+                        Remapping::from_str("ds-test/=lib/ds-test/src/").unwrap(),
+                        RemappingSource::Env
+                    ),
+                    (
+                        Remapping::from_str("other/=lib/other/").unwrap(),
+                        RemappingSource::RemappingsTxt
+                    ),
+                ],
+            );
+            assert_eq!(report.conflicts.len(), 2);
+
+            // the environment overrides remappings.txt, which is not reported as a warning
+            let ds_test = &report.conflicts[0];
+            assert_eq!(ds_test.name, "ds-test/");
+            assert_eq!(
+                ds_test.ignored,
+                vec![RemappingCandidate {
+                    path: "lib/ds-test/".to_string(),
+                    source: RemappingSource::RemappingsTxt
+                }]
+            );
+            assert!(!ds_test.is_ambiguous());
+
+            let other = report.conflicts[1].clone();
+            assert!(other.is_ambiguous());
+            assert_eq!(config.warnings, vec![Warning::RemappingConflict(other)]);
+
+            Ok(())
+        });
+    }
+
     #[test]
     fn test_remappings_override() {
         figment::Jail::expect_with(|jail| {
@@ -3597,6 +4140,7 @@ fn test_toml_file() {
                             Chain::from_id(999999)
                         ]),
                         endpoints: CachedEndpoints::All,
+                        ..Default::default()
                     },
                     use_literal_content: false,
                     bytecode_hash: BytecodeHash::Ipfs,
@@ -4055,6 +4599,235 @@ fn test_fallback_provider() {
         })
     }
 
+    #[test]
+    fn test_profile_extends() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [profile.default]
+                optimizer_runs = 1
+
+                [fuzz]
+                runs = 1
+
+                [profile.ci]
+                optimizer_runs = 200
+                verbosity = 3
+
+                [profile.ci.fuzz]
+                runs = 1000
+
+                [profile.local]
+                extends = "ci"
+                verbosity = 4
+
+                [profile.cycle]
+                extends = "cycle2"
+
+                [profile.cycle2]
+                extends = "cycle"
+                optimizer_runs = 5
+
+                [profile.unknown]
+                extends = "missing"
+            "#,
+            )?;
+
+            jail.set_env("FOUNDRY_PROFILE", "local");
+            let config = Config::load().unwrap();
+            assert_eq!(config.optimizer_runs, Some(200));
+            assert_eq!(config.verbosity, 4);
+            assert_eq!(config.fuzz.runs, 1000);
+            assert!(config.warnings.is_empty());
+
+            jail.set_env("FOUNDRY_PROFILE", "cycle");
+            let err = Config::load().unwrap_err().to_string();
+            assert!(
+                err.contains(
+                    "Could not resolve `extends` of [profile.cycle2]: profile `cycle` is extended \
+                     cyclically"
+                ),
+                "{err}"
+            );
+
+            jail.set_env("FOUNDRY_PROFILE", "unknown");
+            let config = Config::load().unwrap();
+            assert_eq!(config.optimizer_runs, Some(1));
+            assert_eq!(
+                config.warnings,
+                vec![Warning::InvalidExtends {
+                    profile: "unknown".to_string(),
+                    err: "unknown profile `missing`".to_string(),
+                }]
+            );
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_interpolate_env_vars() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [profile.default]
+                eth_rpc_url = "http://${_INTERPOLATE_HOST}:8545"
+                etherscan_api_key = "${_INTERPOLATE_KEY}"
+                libraries = ["src/Lib.sol:Lib:${_INTERPOLATE_LIB}"]
+
+                [profile.default.env]
+                _INTERPOLATE_KEY = "from-table"
+
+                [profile.missing]
+                etherscan_api_key = "${_INTERPOLATE_MISSING}"
+
+                [rpc_endpoints]
+                mainnet = "${_INTERPOLATE_MISSING}"
+
+                [labels]
+                0x0000000000000000000000000000000000000001 = "${_INTERPOLATE_LABEL}"
+            "#,
+            )?;
+            jail.set_env("_INTERPOLATE_HOST", "localhost");
+            jail.set_env("_INTERPOLATE_LIB", "0x8ce361602B935680E8DeC218b820ff5056BeB7af");
+            jail.set_env("_INTERPOLATE_LABEL", "Precompile");
+
+            let config = Config::load().unwrap();
+            assert_eq!(config.eth_rpc_url.as_deref(), Some("http://localhost:8545"));
+            assert_eq!(config.etherscan_api_key.as_deref(), Some("from-table"));
+            assert_eq!(
+                config.libraries,
+                vec!["src/Lib.sol:Lib:0x8ce361602B935680E8DeC218b820ff5056BeB7af".to_string()]
+            );
+            assert_eq!(
+                config.labels.get(&address!("0x0000000000000000000000000000000000000001")),
+                Some(&"Precompile".to_string())
+            );
+            // resolved lazily
+            assert_eq!(
+                config.rpc_endpoints,
+                RpcEndpoints::new([(
+                    "mainnet",
+                    RpcEndpointUrl::Env("${_INTERPOLATE_MISSING}".to_string())
+                )])
+            );
+
+            // the variables of the process environment take precedence over the table
+            jail.set_env("_INTERPOLATE_KEY", "from-env");
+            let config = Config::load().unwrap();
+            assert_eq!(config.etherscan_api_key.as_deref(), Some("from-env"));
+
+            // overridden values don't need their variables, `FOUNDRY_ETHERSCAN_*` variables set
+            // the `etherscan` section instead
+            jail.set_env("ETHERSCAN_API_KEY", "from-etherscan-env");
+            jail.set_env("FOUNDRY_PROFILE", "missing");
+            let config = Config::load().unwrap();
+            assert_eq!(config.etherscan_api_key.as_deref(), Some("from-etherscan-env"));
+
+            jail.clear_env();
+            jail.set_env("_INTERPOLATE_HOST", "localhost");
+            jail.set_env("_INTERPOLATE_LIB", "0x8ce361602B935680E8DeC218b820ff5056BeB7af");
+            jail.set_env("_INTERPOLATE_LABEL", "Precompile");
+            jail.set_env("FOUNDRY_PROFILE", "missing");
+            let err = Config::load().unwrap_err().to_string();
+            assert!(err.contains("environment variable `_INTERPOLATE_MISSING` not found"), "{err}");
+            assert!(err.contains("for setting `etherscan_api_key`"), "{err}");
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_secrets_file() {
+        figment::Jail::expect_with(|jail| {
+            // don't read the global secrets file of the user
+            jail.set_env("HOME", jail.directory().join("home").display().to_string());
+            jail.create_dir("home/.foundry")?;
+            jail.create_file(
+                "home/.foundry/secrets.toml",
+                r#"
+                etherscan_api_key = "global-secret-key"
+                eth_rpc_url = "https://global.example.com/secret"
+            "#,
+            )?;
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [profile.default]
+                optimizer_runs = 1000
+
+                [rpc_endpoints]
+                optimism = "https://optimism.example.com"
+            "#,
+            )?;
+            jail.create_file(
+                "foundry.secrets.toml",
+                r#"
+                etherscan_api_key = "secret-key"
+
+                [rpc_endpoints]
+                mainnet = "https://mainnet.example.com/secret"
+            "#,
+            )?;
+
+            let config = Config::load().unwrap();
+            // the project's secrets file takes precedence
+            assert_eq!(config.etherscan_api_key.as_deref(), Some("secret-key"));
+            assert_eq!(config.eth_rpc_url.as_deref(), Some("https://global.example.com/secret"));
+            assert_eq!(config.optimizer_runs, Some(1000));
+            assert_eq!(
+                config.rpc_endpoints,
+                RpcEndpoints::new([
+                    (
+                        "mainnet",
+                        RpcEndpointUrl::Url("https://mainnet.example.com/secret".to_string())
+                    ),
+                    ("optimism", RpcEndpointUrl::Url("https://optimism.example.com".to_string())),
+                ])
+            );
+            assert_eq!(
+                config.secrets,
+                ["eth_rpc_url", "etherscan_api_key", "rpc_endpoints.mainnet"]
+            );
+
+            // secrets are never written back
+            let s = config.to_string_pretty().unwrap();
+            assert!(!s.contains("secret"), "{s}");
+            assert!(s.contains("optimism"), "{s}");
+
+            // and redacted in any other output
+            let json = serde_json::to_value(&config).unwrap();
+            assert_eq!(json["etherscan_api_key"], Config::REDACTED);
+            assert_eq!(json["eth_rpc_url"], Config::REDACTED);
+            assert_eq!(json["rpc_endpoints"]["mainnet"], Config::REDACTED);
+            assert_eq!(json["rpc_endpoints"]["optimism"], "https://optimism.example.com");
+            let diff = config.diff_from_default().to_string();
+            assert!(!diff.contains("secret"), "{diff}");
+            let (figment, provenance) = Provenance::record(Config::figment);
+            let explanation = provenance.explain(&figment, "etherscan_api_key").to_string();
+            assert!(!explanation.contains("secret"), "{explanation}");
+            assert!(explanation.contains(Config::REDACTED), "{explanation}");
+
+            // the loaded values are not redacted
+            let reloaded = Config::from_provider(&config).unwrap();
+            assert_eq!(reloaded.etherscan_api_key.as_deref(), Some("secret-key"));
+
+            // the environment takes precedence over the secrets files
+            jail.set_env("ETHERSCAN_API_KEY", "env-key");
+            let config = Config::load().unwrap();
+            assert_eq!(config.etherscan_api_key.as_deref(), Some("env-key"));
+
+            // only secrets are allowed
+            jail.create_file("foundry.secrets.toml", "optimizer_runs = 1")?;
+            let err = Config::load().unwrap_err().to_string();
+            assert!(err.contains("unsupported key `optimizer_runs` in secrets file"), "{err}");
+
+            Ok(())
+        })
+    }
+
     #[test]
     fn test_standalone_profile_sections() {
         figment::Jail::expect_with(|jail| {
@@ -4421,6 +5194,38 @@ fn test_model_checker_settings_relative_paths() {
         });
     }
 
+    #[test]
+    fn test_load_minimal() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [profile.default]
+                src = "contracts"
+                libs = ["node_modules"]
+
+                [rpc_endpoints]
+                mainnet = "https://example.com/${RPC_KEY}"
+            "#,
+            )?;
+            jail.set_env("RPC_KEY", "key");
+
+            let root = jail.directory();
+            let config = Config::load_minimal(root).unwrap();
+            let dirs = config.dirs();
+            assert_eq!(dirs.src, canonic(root.join("contracts")));
+            assert_eq!(dirs.libs, vec![canonic(root.join("node_modules"))]);
+            assert_eq!(dirs.out, canonic(root.join("out")));
+            assert_eq!(
+                config.resolved_rpc_endpoints().get("mainnet").unwrap().url().unwrap(),
+                "https://example.com/key"
+            );
+            assert!(config.resolved_etherscan().is_empty());
+
+            Ok(())
+        });
+    }
+
     #[test]
     fn test_fmt_config() {
         figment::Jail::expect_with(|jail| {
@@ -4448,6 +5253,34 @@ fn test_fmt_config() {
         });
     }
 
+    #[test]
+    fn test_build_hooks_config() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [build.hooks]
+                pre_build = [
+                    { name = "constants", command = ["python3", "gen.py"], inputs = ["data/*.json"], outputs = ["src/Constants.sol"] },
+                ]
+            "#,
+            )?;
+            let loaded = Config::load().unwrap().sanitized();
+            assert_eq!(
+                loaded.build.hooks.pre_build,
+                vec![BuildHook {
+                    name: Some("constants".to_string()),
+                    command: vec!["python3".to_string(), "gen.py".to_string()],
+                    inputs: vec!["data/*.json".to_string()],
+                    outputs: vec![PathBuf::from("src/Constants.sol")],
+                }]
+            );
+            assert!(loaded.build.hooks.post_build.is_empty());
+
+            Ok(())
+        });
+    }
+
     #[test]
     fn test_invariant_config() {
         figment::Jail::expect_with(|jail| {
@@ -4553,6 +5386,68 @@ fn test_implicit_profile_loads() {
         });
     }
 
+    #[test]
+    fn test_unknown_keys() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r"
+                [profile.default]
+                optimzer_runs = 1000
+                solc_version = '0.8.20'
+                label_files = []
+
+                [profile.default.fuzz]
+                runz = 10
+                seed = '0x1'
+
+                [profile.default.fmt]
+                line_length = 100
+
+                [profile.ci]
+                xyz = 1
+            ",
+            )?;
+            let loaded = Config::load().unwrap();
+            assert_eq!(
+                loaded.warnings,
+                vec![
+                    Warning::UnknownKey {
+                        key: "xyz".to_string(),
+                        profile: "ci".to_string(),
+                        source: Some("foundry.toml".into()),
+                        suggestion: None,
+                    },
+                    Warning::UnknownKey {
+                        key: "fuzz.runz".to_string(),
+                        profile: "default".to_string(),
+                        source: Some("foundry.toml".into()),
+                        suggestion: Some("fuzz.runs".to_string()),
+                    },
+                    Warning::UnknownKey {
+                        key: "optimzer_runs".to_string(),
+                        profile: "default".to_string(),
+                        source: Some("foundry.toml".into()),
+                        suggestion: Some("optimizer_runs".to_string()),
+                    },
+                ]
+            );
+
+            jail.create_file(
+                "foundry.toml",
+                r"
+                [profile.default]
+                strict_config = true
+                optimzer_runs = 1000
+            ",
+            )?;
+            let err = Config::load().unwrap_err();
+            assert!(err.to_string().contains("Did you mean `optimizer_runs`?"), "{err}");
+
+            Ok(())
+        });
+    }
+
     #[test]
     fn test_etherscan_api_key() {
         figment::Jail::expect_with(|jail| {
@@ -4815,6 +5710,51 @@ fn test_parse_optimizer_settings() {
         });
     }
 
+    #[test]
+    fn test_load_label_files() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [profile.default]
+                label_files = ["labels.json", "missing.json"]
+
+                [labels]
+                0x1F98431c8aD98523631AE4a59f267346ea31F984 = "Uniswap V3: Factory"
+            "#,
+            )?;
+            jail.create_file(
+                "labels.json",
+                r#"{
+                    "0x1F98431c8aD98523631AE4a59f267346ea31F984": "UniswapV3Factory",
+                    "0xC36442b4a4522E871399CD717aBDD847Ab11FE88": "Uniswap V3: Positions NFT"
+                }"#,
+            )?;
+
+            let mut config = Config::load().unwrap();
+            config.load_label_files();
+            assert_eq!(
+                config.labels,
+                AddressHashMap::from_iter(vec![
+                    (
+                        Address::from_str("0x1F98431c8aD98523631AE4a59f267346ea31F984").unwrap(),
+                        "Uniswap V3: Factory".to_string()
+                    ),
+                    (
+                        Address::from_str("0xC36442b4a4522E871399CD717aBDD847Ab11FE88").unwrap(),
+                        "Uniswap V3: Positions NFT".to_string()
+                    ),
+                ])
+            );
+            assert!(matches!(
+                config.warnings.as_slice(),
+                [Warning::CouldNotReadLabels { path, .. }] if path.ends_with("missing.json")
+            ));
+
+            Ok(())
+        });
+    }
+
     #[test]
     fn test_parse_labels() {
         figment::Jail::expect_with(|jail| {
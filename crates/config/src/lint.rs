@@ -0,0 +1,68 @@
+//! Configuration for `forge lint`.
+
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// Contains the config for `forge lint`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintConfig {
+    /// The minimum severity of the reported lints.
+    pub severity: LintSeverity,
+    /// The ids of the lints to run. If empty, all the lints run.
+    pub include_lints: Vec<String>,
+    /// The ids of the lints to skip.
+    pub exclude_lints: Vec<String>,
+    /// Globs of the files to skip.
+    pub ignore: Vec<String>,
+}
+
+impl LintConfig {
+    /// Returns `true` if the lint with the given id and severity should run.
+    pub fn is_enabled(&self, id: &str, severity: LintSeverity) -> bool {
+        severity >= self.severity &&
+            (self.include_lints.is_empty() || self.include_lints.iter().any(|l| l == id)) &&
+            !self.exclude_lints.iter().any(|l| l == id)
+    }
+}
+
+/// The severity of a lint.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    /// A matter of style or code quality.
+    #[default]
+    Info,
+    /// A pattern that is likely a mistake, but harmless.
+    Low,
+    /// A pattern that may lead to bugs.
+    Medium,
+    /// A pattern that may lead to loss of funds.
+    High,
+}
+
+impl FromStr for LintSeverity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "info" => Ok(Self::Info),
+            "low" => Ok(Self::Low),
+            "medium" => Ok(Self::Medium),
+            "high" => Ok(Self::High),
+            _ => Err(format!("Unknown severity: `{s}`, expected info, low, medium or high")),
+        }
+    }
+}
+
+impl fmt::Display for LintSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Info => f.write_str("info"),
+            Self::Low => f.write_str("low"),
+            Self::Medium => f.write_str("medium"),
+            Self::High => f.write_str("high"),
+        }
+    }
+}
@@ -58,7 +58,8 @@ macro_rules! impl_figment_convert {
     ($name:ty) => {
         impl<'a> From<&'a $name> for $crate::figment::Figment {
             fn from(args: &'a $name) -> Self {
-                $crate::Config::figment_with_root_opt(args.root.as_deref()).merge(args)
+                let figment = $crate::Config::figment_with_root_opt(args.root.as_deref());
+                $crate::provenance::FigmentTraceExt::merge_traced(figment, args)
             }
         }
     };
@@ -67,7 +68,8 @@ impl<'a> From<&'a $name> for $crate::figment::Figment {
             fn from(args: &'a $name) -> Self {
                 let mut figment: $crate::figment::Figment = From::from(&args.$start);
                 $(
-                    figment = figment.merge(&args.$more);
+                    figment =
+                        $crate::provenance::FigmentTraceExt::merge_traced(figment, &args.$more);
                 )*
                 figment
             }
@@ -78,9 +80,10 @@ impl<'a> From<&'a $name> for $crate::figment::Figment {
             fn from(args: &'a $name) -> Self {
                 let mut figment: $crate::figment::Figment = From::from(&args.$start);
                 $(
-                    figment = figment.merge(&args.$more);
+                    figment =
+                        $crate::provenance::FigmentTraceExt::merge_traced(figment, &args.$more);
                 )*
-                figment = figment.merge(args);
+                figment = $crate::provenance::FigmentTraceExt::merge_traced(figment, args);
                 figment
             }
         }
@@ -142,9 +145,10 @@ impl<'a> From<&'a $name> for $crate::figment::Figment {
             fn from(args: &'a $name) -> Self {
                 let mut figment: $crate::figment::Figment = From::from(&args.$start);
                 $ (
-                  figment =  figment.merge(&args.$more);
+                  figment =
+                      $crate::provenance::FigmentTraceExt::merge_traced(figment, &args.$more);
                 )*
-                figment = figment.merge(args);
+                figment = $crate::provenance::FigmentTraceExt::merge_traced(figment, args);
                 figment
             }
         }
@@ -163,9 +167,9 @@ impl<'a> From<&'a $name> for $crate::figment::Figment {
             fn from(args: &'a $name) -> Self {
                 let root =
                     $crate::find_project_root(None).expect("could not determine project root");
-                $crate::Config::with_root(&root)
-                    .to_figment($crate::FigmentProviders::Cast)
-                    .merge(args)
+                let figment =
+                    $crate::Config::with_root(&root).to_figment($crate::FigmentProviders::Cast);
+                $crate::provenance::FigmentTraceExt::merge_traced(figment, args)
             }
         }
     };
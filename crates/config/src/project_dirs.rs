@@ -0,0 +1,30 @@
+//! The directories of a project.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// The directories of a project, resolved against its root.
+///
+/// Unlike [`ProjectPathsConfig`](foundry_compilers::ProjectPathsConfig), creating this doesn't
+/// resolve remappings, so it doesn't touch the filesystem.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectDirs {
+    /// The root of the project.
+    pub root: PathBuf,
+    /// The contract sources.
+    pub src: PathBuf,
+    /// The tests.
+    pub test: PathBuf,
+    /// The scripts.
+    pub script: PathBuf,
+    /// The artifacts.
+    pub out: PathBuf,
+    /// The libraries.
+    pub libs: Vec<PathBuf>,
+    /// The cache.
+    pub cache: PathBuf,
+    /// The broadcast logs of scripts.
+    pub broadcast: PathBuf,
+    /// The gas snapshots.
+    pub snapshots: PathBuf,
+}
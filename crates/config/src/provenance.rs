@@ -0,0 +1,244 @@
+//! Tracks the providers that contributed to a config, see [`Provenance`].
+
+use crate::{providers::SecretsProvider, Config};
+use figment::{
+    value::{Dict, Map, Value},
+    Figment, Metadata, Profile, Provider,
+};
+use serde::Serialize;
+use std::{cell::RefCell, collections::HashSet, fmt};
+
+thread_local! {
+    /// The layers merged on this thread while recording, see [`Provenance::record`].
+    static LAYERS: RefCell<Option<Vec<Layer>>> = const { RefCell::new(None) };
+}
+
+/// The data supplied by a provider merged into a figment.
+#[derive(Clone, Debug)]
+struct Layer {
+    metadata: Metadata,
+    data: Map<Profile, Dict>,
+}
+
+/// The providers merged into a figment, in merge order.
+///
+/// Only the merges that go through [`FigmentTraceExt::merge_traced`] are recorded, which covers
+/// the providers of [`Config::to_figment`](crate::Config::to_figment) and the CLI arguments
+/// merged by the `impl_figment_convert` macros.
+#[derive(Clone, Debug, Default)]
+pub struct Provenance {
+    layers: Vec<Layer>,
+}
+
+/// How the value of a config key was resolved, see [`Provenance::explain`].
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Explanation {
+    /// The dotted path of the key, e.g. `fuzz.runs`.
+    pub key: String,
+    /// The final value of the key, if it is set.
+    pub value: Option<Value>,
+    /// The values supplied by each provider, from lowest to highest precedence.
+    pub contributions: Vec<Contribution>,
+    /// The provider that supplied the final value.
+    pub winner: Option<String>,
+}
+
+/// A value supplied by a provider.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Contribution {
+    /// The provider, e.g. ``TOML file `foundry.toml` ``.
+    pub provider: String,
+    /// The profile the provider set the value for.
+    pub profile: String,
+    /// The value.
+    pub value: Value,
+}
+
+impl Provenance {
+    /// Records the providers merged into the figments built on this thread by `f`.
+    pub fn record<T>(f: impl FnOnce() -> T) -> (T, Self) {
+        let previous = LAYERS.with_borrow_mut(|layers| layers.replace(Vec::new()));
+        let res = f();
+        let layers = LAYERS.with_borrow_mut(|layers| std::mem::replace(layers, previous));
+        (res, Self { layers: layers.unwrap_or_default() })
+    }
+
+    /// Explains the value of `key` in `figment`, which must be the figment that was built while
+    /// recording.
+    ///
+    /// The values of the secrets files are replaced by [`Config::REDACTED`].
+    pub fn explain(&self, figment: &Figment, key: &str) -> Explanation {
+        let secrets = self.secrets();
+        let profiles = [Profile::Default, figment.profile().clone(), Profile::Global];
+        let mut contributions = Vec::new();
+        for layer in &self.layers {
+            for profile in profiles.iter() {
+                let Some(dict) = layer.data.get(profile) else { continue };
+                if let Some(value) = find(dict, key) {
+                    let mut value = value.clone();
+                    redact(&mut value, &secrets);
+                    contributions.push(Contribution {
+                        provider: describe(&layer.metadata),
+                        profile: profile.to_string(),
+                        value,
+                    });
+                }
+            }
+        }
+        // the same provider may be merged several times, e.g. once per profile of a TOML file
+        contributions.dedup();
+
+        let mut value = figment.find_value(key).ok();
+        if let Some(value) = &mut value {
+            redact(value, &secrets);
+        }
+        Explanation {
+            key: key.to_string(),
+            value,
+            contributions,
+            winner: figment.find_metadata(key).map(describe),
+        }
+    }
+
+    /// Returns the string values supplied by the secrets files.
+    fn secrets(&self) -> HashSet<String> {
+        fn collect(value: &Value, secrets: &mut HashSet<String>) {
+            match value {
+                Value::String(_, s) => {
+                    secrets.insert(s.clone());
+                }
+                Value::Dict(_, dict) => dict.values().for_each(|value| collect(value, secrets)),
+                Value::Array(_, values) => values.iter().for_each(|value| collect(value, secrets)),
+                _ => {}
+            }
+        }
+
+        let mut secrets = HashSet::new();
+        for layer in self.layers.iter().filter(|layer| layer.metadata.name == SecretsProvider::NAME)
+        {
+            for (key, value) in layer.data.values().flatten() {
+                if key != SecretsProvider::SECRETS_KEY {
+                    collect(value, &mut secrets);
+                }
+            }
+        }
+        secrets
+    }
+}
+
+/// Replaces the strings of `value` that are in `secrets` with [`Config::REDACTED`].
+fn redact(value: &mut Value, secrets: &HashSet<String>) {
+    match value {
+        Value::String(_, s) if secrets.contains(s.as_str()) => *s = Config::REDACTED.to_string(),
+        Value::Dict(_, dict) => dict.values_mut().for_each(|value| redact(value, secrets)),
+        Value::Array(_, values) => values.iter_mut().for_each(|value| redact(value, secrets)),
+        _ => {}
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(value) = &self.value else {
+            return writeln!(f, "`{}` is not set", self.key);
+        };
+        writeln!(f, "{} = {}", self.key, display_value(value))?;
+        if !self.contributions.is_empty() {
+            writeln!(f, "Contributions (lowest to highest precedence):")?;
+            for contribution in &self.contributions {
+                writeln!(
+                    f,
+                    "  {} from {} [{}]",
+                    display_value(&contribution.value),
+                    contribution.provider,
+                    contribution.profile
+                )?;
+            }
+        }
+        if let Some(winner) = &self.winner {
+            writeln!(f, "Set by {winner}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Merges providers into a [`Figment`] and records them in the current [`Provenance`].
+#[doc(hidden)]
+pub trait FigmentTraceExt {
+    /// Same as [`Figment::merge`], but records the provider if [`Provenance::record`] is active.
+    fn merge_traced<P: Provider>(self, provider: P) -> Self;
+}
+
+impl FigmentTraceExt for Figment {
+    fn merge_traced<P: Provider>(self, provider: P) -> Self {
+        trace(&provider);
+        self.merge(provider)
+    }
+}
+
+/// Records the data of the provider if [`Provenance::record`] is active.
+pub(crate) fn trace(provider: &impl Provider) {
+    if !LAYERS.with_borrow(Option::is_some) {
+        return;
+    }
+    // the data is read outside of the borrow, since providers may build traced figments
+    let layer = Layer { metadata: provider.metadata(), data: provider.data().unwrap_or_default() };
+    LAYERS.with_borrow_mut(|layers| {
+        if let Some(layers) = layers {
+            layers.push(layer);
+        }
+    });
+}
+
+/// Describes a provider, e.g. ``TOML file `foundry.toml` ``.
+pub(crate) fn describe(metadata: &Metadata) -> String {
+    match &metadata.source {
+        Some(source) => format!("{} `{source}`", metadata.name),
+        None => metadata.name.to_string(),
+    }
+}
+
+/// Returns the value at the dotted `key` in `dict`.
+fn find<'a>(dict: &'a Dict, key: &str) -> Option<&'a Value> {
+    let (first, rest) = match key.split_once('.') {
+        Some((first, rest)) => (first, Some(rest)),
+        None => (key, None),
+    };
+    let value = dict.get(first)?;
+    match rest {
+        Some(rest) => value.find_ref(rest),
+        None => Some(value),
+    }
+}
+
+fn display_value(value: &Value) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| format!("{value:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_traced_merges() {
+        let (figment, provenance) = Provenance::record(|| {
+            Figment::new()
+                .merge_traced(("optimizer_runs", 200))
+                .merge(("verbosity", 1))
+                .merge_traced(("optimizer_runs", 1000))
+        });
+        assert_eq!(provenance.layers.len(), 2);
+
+        let explanation = provenance.explain(&figment, "optimizer_runs");
+        assert_eq!(explanation.value, Some(Value::from(1000)));
+        assert_eq!(
+            explanation.contributions.iter().map(|c| c.value.clone()).collect::<Vec<_>>(),
+            [Value::from(200), Value::from(1000)]
+        );
+
+        // nothing is recorded outside of `record`
+        let figment = Figment::new().merge_traced(("optimizer_runs", 1));
+        let (_, provenance) = Provenance::record(|| ());
+        assert!(provenance.layers.is_empty());
+        assert_eq!(provenance.explain(&figment, "fuzz.runs").value, None);
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{
+    resolve::{interpolate_with, RE_PLACEHOLDER},
+    Dotenv,
+};
+use figment::{
+    value::{Dict, Map, Value},
+    Error, Metadata, Profile, Provider,
+};
+use std::{collections::BTreeMap, env::VarError};
+
+/// A provider that replaces the `${ENV_VAR}` placeholders in the string values of the config with
+/// the values of the environment variables.
+///
+/// Variables that are not set in the environment are looked up in the `env` tables of the
+/// selected profiles, since these are only applied after the config is loaded.
+///
+/// Placeholders of variables that are not set are left as-is, so that values overridden by other
+/// sources don't need them: only the placeholders left in the extracted config are errors, see
+/// [`ensure_resolved`].
+///
+/// The `rpc_endpoints` and `etherscan` sections are left as-is, their placeholders are resolved
+/// when an endpoint is used, see [`RpcEndpoints`](crate::RpcEndpoints).
+pub(crate) struct EnvInterpolationProvider<P> {
+    provider: P,
+    /// The selected profiles, from lowest to highest precedence.
+    profiles: Vec<Profile>,
+}
+
+impl<P> EnvInterpolationProvider<P> {
+    /// The sections whose placeholders are resolved lazily.
+    const LAZY_SECTIONS: &'static [&'static str] = &["rpc_endpoints", "etherscan"];
+
+    /// The profile that contains all the `[profile.<name>]` tables.
+    const PROFILE_PROFILE: Profile = Profile::const_new("profile");
+
+    pub(crate) fn new(provider: P, profiles: Vec<Profile>) -> Self {
+        Self { provider, profiles }
+    }
+
+    fn is_lazy(key: &str) -> bool {
+        Self::LAZY_SECTIONS.contains(&key)
+    }
+}
+
+impl<P: Provider> Provider for EnvInterpolationProvider<P> {
+    fn metadata(&self) -> Metadata {
+        self.provider.metadata()
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let mut data = self.provider.data()?;
+
+        // the `env` tables of the selected profiles, later profiles take precedence
+        let mut env_table = BTreeMap::new();
+        for profile in &self.profiles {
+            if let Some(Value::Dict(_, table)) = data.get(profile).and_then(|dict| dict.get("env"))
+            {
+                for (name, value) in table {
+                    if let Some(value) = value.as_str() {
+                        env_table.insert(name.clone(), value.to_string());
+                    }
+                }
+            }
+        }
+        // mirrors `Config::apply_env`: the table overrides variables loaded from `.env` files
+        let lookup = |name: &str| match env_table.get(name) {
+            Some(value) if std::env::var(name).is_err() || Dotenv::is_from_file(name) => {
+                Ok(value.clone())
+            }
+            _ => std::env::var(name),
+        };
+
+        for (profile, dict) in data.iter_mut() {
+            if *profile == Self::PROFILE_PROFILE || Self::is_lazy(profile.as_ref()) {
+                continue
+            }
+            for (key, value) in dict.iter_mut() {
+                if Self::is_lazy(key) {
+                    continue
+                }
+                interpolate_value(value, &lookup);
+            }
+        }
+        Ok(data)
+    }
+}
+
+/// Returns an error for the first placeholder of an unset variable left in the extracted values of
+/// the config, outside of the lazily resolved sections.
+pub(crate) fn ensure_resolved(dict: &Dict) -> Result<(), Error> {
+    for (key, value) in dict {
+        if EnvInterpolationProvider::<()>::is_lazy(key) {
+            continue
+        }
+        ensure_value_resolved(value, &mut vec![key.clone()])?;
+    }
+    Ok(())
+}
+
+/// Replaces the placeholders of all the strings in `value`, leaving the strings with placeholders
+/// of unset variables untouched.
+fn interpolate_value(value: &mut Value, lookup: &impl Fn(&str) -> Result<String, VarError>) {
+    match value {
+        Value::String(_, s) if RE_PLACEHOLDER.is_match(s) => {
+            if let Ok(interpolated) = interpolate_with(s, lookup) {
+                *s = interpolated;
+            }
+        }
+        Value::Dict(_, dict) => {
+            dict.values_mut().for_each(|value| interpolate_value(value, lookup));
+        }
+        Value::Array(_, values) => {
+            values.iter_mut().for_each(|value| interpolate_value(value, lookup));
+        }
+        _ => {}
+    }
+}
+
+/// Returns an error if a string in `value` still has a placeholder of an unset variable, `path` is
+/// the path to `value` used in errors.
+fn ensure_value_resolved(value: &Value, path: &mut Vec<String>) -> Result<(), Error> {
+    match value {
+        Value::String(_, s) if RE_PLACEHOLDER.is_match(s) => {
+            interpolate_with(s, |name| std::env::var(name)).map_err(|err| {
+                let mut err = Error::from(err.to_string());
+                err.path.clone_from(path);
+                err
+            })?;
+        }
+        Value::Dict(_, dict) => {
+            for (key, value) in dict {
+                path.push(key.clone());
+                ensure_value_resolved(value, path)?;
+                path.pop();
+            }
+        }
+        Value::Array(_, values) => {
+            for value in values {
+                ensure_value_resolved(value, path)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
@@ -3,8 +3,17 @@
 mod ext;
 pub use ext::*;
 
+mod interpolate;
+pub(crate) use interpolate::*;
+
+mod overrides;
+pub use overrides::*;
+
 mod remappings;
 pub use remappings::*;
 
+mod secrets;
+pub(crate) use secrets::*;
+
 mod warnings;
 pub use warnings::*;
@@ -0,0 +1,232 @@
+use crate::{
+    providers::warnings::{closest_key, suggest_key, KNOWN_KEYS},
+    Config,
+};
+use figment::{
+    value::{Dict, Map, Value},
+    Error, Figment, Metadata, Profile, Provider,
+};
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr, sync::OnceLock};
+
+/// The overrides applied to every config loaded by the process, set from the command line.
+static GLOBAL_OVERRIDES: OnceLock<ConfigOverrides> = OnceLock::new();
+
+/// An override of a config key, in the form `key=value`, e.g. `fuzz.runs=10000`.
+///
+/// The key is the dot-separated path of the key in a profile, and the value is parsed as TOML, or
+/// as a string if it isn't valid TOML.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConfigOverride {
+    /// The dot-separated path of the key.
+    pub key: String,
+    /// The value of the key.
+    pub value: toml::Value,
+}
+
+impl ConfigOverride {
+    /// Returns an error if the key doesn't exist in the config.
+    fn validate_key(&self) -> Result<(), String> {
+        let mut segments = self.key.split('.');
+        let first = segments.next().unwrap_or_default();
+        if !KNOWN_KEYS.iter().any(|key| key == first) {
+            return Err(unknown_key(&self.key, suggest_key(first)));
+        }
+
+        // Nested keys are checked against the sections of the default config. Empty sections are
+        // maps, e.g. `labels`, which accept any key.
+        let default = serde_json::to_value(Config::default()).unwrap_or_default();
+        let mut section = default.get(first);
+        let mut path = first.to_string();
+        for segment in segments {
+            let Some(keys) = section.and_then(|section| section.as_object()) else { break };
+            if keys.is_empty() {
+                break
+            }
+            section = keys.get(segment);
+            if section.is_none() {
+                let suggestion =
+                    closest_key(segment, keys.keys()).map(|key| format!("{path}.{key}"));
+                return Err(unknown_key(&self.key, suggestion));
+            }
+            path = format!("{path}.{segment}");
+        }
+        Ok(())
+    }
+
+    /// Returns an error if the value has the wrong type for the key.
+    fn validate_value(&self) -> Result<(), String> {
+        Figment::from(Config::default())
+            .merge(ConfigOverrides::new(vec![self.clone()]))
+            .extract::<Config>()
+            .map(drop)
+            .map_err(|err| format!("invalid value for `{}`: {}", self.key, err.kind))
+    }
+}
+
+impl FromStr for ConfigOverride {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((key, value)) = s.split_once('=') else {
+            return Err(format!("invalid config override `{s}`, expected `key=value`"));
+        };
+        let key = key.trim();
+        if key.is_empty() || key.split('.').any(str::is_empty) {
+            return Err(format!("invalid config key `{key}`"));
+        }
+        let value = value.trim();
+        let value = toml::from_str::<toml::Table>(&format!("value = {value}"))
+            .ok()
+            .and_then(|mut table| table.remove("value"))
+            .unwrap_or_else(|| toml::Value::String(value.to_string()));
+
+        let config_override = Self { key: key.to_string(), value };
+        config_override.validate_key()?;
+        config_override.validate_value()?;
+        Ok(config_override)
+    }
+}
+
+impl fmt::Display for ConfigOverride {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.key, self.value)
+    }
+}
+
+fn unknown_key(key: &str, suggestion: Option<String>) -> String {
+    match suggestion {
+        Some(suggestion) => format!("unknown config key `{key}`, did you mean `{suggestion}`?"),
+        None => format!("unknown config key `{key}`"),
+    }
+}
+
+/// A [Provider] of config overrides, e.g. from `--config-override`.
+///
+/// The overrides are set in the global profile, so they take precedence over all the other
+/// sources of the config, whatever the selected profile.
+#[derive(Clone, Debug, Default)]
+pub struct ConfigOverrides {
+    overrides: Vec<ConfigOverride>,
+}
+
+impl ConfigOverrides {
+    /// Creates a provider of the given overrides, the last ones taking precedence.
+    pub fn new(overrides: Vec<ConfigOverride>) -> Self {
+        Self { overrides }
+    }
+
+    /// Sets the overrides applied to every config loaded by the process.
+    ///
+    /// Only the first call has an effect.
+    pub fn set_global(self) {
+        let _ = GLOBAL_OVERRIDES.set(self);
+    }
+
+    /// Merges the global overrides, if any, into the figment.
+    pub fn merge_global(figment: Figment) -> Figment {
+        match GLOBAL_OVERRIDES.get() {
+            Some(overrides) => figment.merge(overrides),
+            None => figment,
+        }
+    }
+}
+
+impl Provider for ConfigOverrides {
+    fn metadata(&self) -> Metadata {
+        Metadata::named("--config-override")
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let mut dict = Dict::new();
+        for config_override in &self.overrides {
+            let path = config_override.key.split('.').collect::<Vec<_>>();
+            insert_nested(&mut dict, &path, Value::serialize(&config_override.value)?);
+        }
+        Ok(Map::from([(Profile::Global, dict)]))
+    }
+}
+
+/// Inserts the value at the dot-separated path in the dict, creating the intermediate dicts.
+fn insert_nested(dict: &mut Dict, path: &[&str], value: Value) {
+    let Some((key, rest)) = path.split_first() else { return };
+    if rest.is_empty() {
+        dict.insert(key.to_string(), value);
+        return
+    }
+    let entry = dict.entry(key.to_string()).or_insert_with(|| Dict::new().into());
+    if !matches!(entry, Value::Dict(..)) {
+        *entry = Dict::new().into();
+    }
+    if let Value::Dict(_, inner) = entry {
+        insert_nested(inner, rest, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_config_overrides() {
+        let runs: ConfigOverride = "fuzz.runs=10000".parse().unwrap();
+        assert_eq!(runs.key, "fuzz.runs");
+        assert_eq!(runs.value, toml::Value::Integer(10000));
+
+        let evm_version: ConfigOverride = "evm_version = cancun".parse().unwrap();
+        assert_eq!(evm_version.value, toml::Value::String("cancun".to_string()));
+
+        let libs: ConfigOverride = r#"libs=["lib", "node_modules"]"#.parse().unwrap();
+        assert_eq!(libs.value.as_array().unwrap().len(), 2);
+
+        let label: ConfigOverride =
+            "labels.0x6B175474E89094C44Da98b954EedeAC495271d0F=DAI".parse().unwrap();
+        assert_eq!(label.value, toml::Value::String("DAI".to_string()));
+    }
+
+    #[test]
+    fn reject_invalid_config_overrides() {
+        let err = "fuzz.run=1".parse::<ConfigOverride>().unwrap_err();
+        assert_eq!(err, "unknown config key `fuzz.run`, did you mean `fuzz.runs`?");
+
+        let err = "optimizer_rusn=1".parse::<ConfigOverride>().unwrap_err();
+        assert_eq!(err, "unknown config key `optimizer_rusn`, did you mean `optimizer_runs`?");
+
+        let err = "fuzz.runs=many".parse::<ConfigOverride>().unwrap_err();
+        assert!(err.starts_with("invalid value for `fuzz.runs`: invalid type"), "{err}");
+
+        assert!("fuzz.runs".parse::<ConfigOverride>().is_err());
+        assert!("fuzz..runs=1".parse::<ConfigOverride>().is_err());
+    }
+
+    #[test]
+    fn overrides_take_precedence() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r"
+                [profile.default]
+                optimizer_runs = 100
+
+                [profile.ci]
+                optimizer_runs = 1000
+
+                [profile.ci.fuzz]
+                runs = 500
+                max_test_rejects = 10
+            ",
+            )?;
+            jail.set_env("FOUNDRY_PROFILE", "ci");
+
+            let overrides = ConfigOverrides::new(vec![
+                "optimizer_runs=2000".parse().unwrap(),
+                "fuzz.runs=10000".parse().unwrap(),
+            ]);
+            let config = Config::from_provider(Config::figment().merge(overrides)).unwrap();
+            assert_eq!(config.optimizer_runs, Some(2000));
+            assert_eq!(config.fuzz.runs, 10000);
+            assert_eq!(config.fuzz.max_test_rejects, 10);
+            Ok(())
+        });
+    }
+}
@@ -1,13 +1,14 @@
-use crate::{foundry_toml_dirs, remappings_from_env_var, remappings_from_newline, Config};
+use crate::{foundry_toml_dirs, remappings_from_env_var, remappings_from_newline, Config, Warning};
 use figment::{
-    value::{Dict, Map},
+    value::{Dict, Map, Value},
     Error, Figment, Metadata, Profile, Provider,
 };
 use foundry_compilers::artifacts::remappings::{RelativeRemapping, Remapping};
+use serde::{Deserialize, Serialize};
 use std::{
     borrow::Cow,
     collections::{btree_map::Entry, BTreeMap, HashSet},
-    fs,
+    fmt, fs,
     path::{Path, PathBuf},
 };
 
@@ -125,6 +126,158 @@ pub fn extend(&mut self, remappings: Vec<Remapping>) {
     }
 }
 
+/// Where a remapping is declared, from the highest to the lowest precedence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemappingSource {
+    /// The `DAPP_REMAPPINGS` or `FOUNDRY_REMAPPINGS` environment variable.
+    Env,
+    /// The `remappings.txt` file of the project.
+    RemappingsTxt,
+    /// The `remappings` setting of `foundry.toml`.
+    Config,
+    /// A dependency in one of the `libs` directories, auto-detected or declared in its
+    /// `foundry.toml`.
+    Dependency,
+}
+
+impl fmt::Display for RemappingSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Env => "environment",
+            Self::RemappingsTxt => "remappings.txt",
+            Self::Config => "foundry.toml",
+            Self::Dependency => "dependency",
+        })
+    }
+}
+
+/// A path a remapped prefix is mapped to, and where it is declared.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemappingCandidate {
+    /// The path, relative to the project root.
+    pub path: String,
+    pub source: RemappingSource,
+}
+
+/// A remapped prefix that is mapped to different paths, see [`RemappingsReport`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConflictingRemappings {
+    /// The context of the remapping, if any.
+    pub context: Option<String>,
+    /// The remapped prefix.
+    pub name: String,
+    /// The path the prefix resolves to.
+    pub used: RemappingCandidate,
+    /// The other paths the prefix is mapped to.
+    pub ignored: Vec<RemappingCandidate>,
+}
+
+impl ConflictingRemappings {
+    /// Returns `true` if the prefix is mapped to different paths by the same source, e.g. by two
+    /// dependencies, rather than a source overriding another one with a lower precedence.
+    pub fn is_ambiguous(&self) -> bool {
+        self.ignored.iter().any(|candidate| candidate.source == self.used.source)
+    }
+}
+
+impl fmt::Display for ConflictingRemappings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("`")?;
+        if let Some(context) = &self.context {
+            write!(f, "{context}:")?;
+        }
+        write!(f, "{}` resolves to `{}` ({})", self.name, self.used.path, self.used.source)?;
+        for candidate in &self.ignored {
+            write!(f, ", ignoring `{}` ({})", candidate.path, candidate.source)?;
+        }
+        Ok(())
+    }
+}
+
+/// How the remappings of a project are resolved, see [`Config::remappings_report`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RemappingsReport {
+    /// The resolved remappings, relative to the project root, and where they are declared.
+    pub remappings: Vec<(Remapping, RemappingSource)>,
+    /// The prefixes that are mapped to different paths.
+    pub conflicts: Vec<ConflictingRemappings>,
+}
+
+impl RemappingsReport {
+    /// Creates the report from the resolved remappings and all declared `candidates`, in order of
+    /// precedence.
+    fn new(
+        root: &Path,
+        resolved: &[Remapping],
+        candidates: Vec<(Remapping, RemappingSource)>,
+    ) -> Self {
+        let relative =
+            |r: &Remapping| RelativeRemapping::new(r.clone(), root).to_relative_remapping();
+        let same_path = |a: &str, b: &str| a.trim_end_matches('/') == b.trim_end_matches('/');
+        let candidates =
+            candidates.iter().map(|(r, source)| (relative(r), *source)).collect::<Vec<_>>();
+
+        let mut report = Self::default();
+        for remapping in resolved.iter().map(relative) {
+            let declared = candidates
+                .iter()
+                .filter(|(r, _)| r.context == remapping.context && r.name == remapping.name);
+            let Some(source) = declared
+                .clone()
+                .find(|(r, _)| same_path(&r.path, &remapping.path))
+                .map(|(_, source)| *source)
+            else {
+                continue
+            };
+
+            let mut ignored: Vec<RemappingCandidate> = Vec::new();
+            for (r, source) in declared {
+                if !same_path(&r.path, &remapping.path) &&
+                    !ignored.iter().any(|c| same_path(&c.path, &r.path))
+                {
+                    ignored.push(RemappingCandidate { path: r.path.clone(), source: *source });
+                }
+            }
+            if !ignored.is_empty() {
+                report.conflicts.push(ConflictingRemappings {
+                    context: remapping.context.clone(),
+                    name: remapping.name.clone(),
+                    used: RemappingCandidate { path: remapping.path.clone(), source },
+                    ignored,
+                });
+            }
+            report.remappings.push((remapping, source));
+        }
+        report
+    }
+
+    /// Returns the warnings of the ambiguous conflicts, see
+    /// [`ConflictingRemappings::is_ambiguous`].
+    pub fn warnings(&self) -> impl Iterator<Item = Warning> + '_ {
+        self.conflicts
+            .iter()
+            .filter(|conflict| conflict.is_ambiguous())
+            .cloned()
+            .map(Warning::RemappingConflict)
+    }
+}
+
+impl fmt::Display for RemappingsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (remapping, source) in &self.remappings {
+            writeln!(f, "{remapping} ({source})")?;
+        }
+        if !self.conflicts.is_empty() {
+            writeln!(f, "\nConflicts:")?;
+            for conflict in &self.conflicts {
+                writeln!(f, "  {conflict}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
 /// A figment provider that checks if the remappings were previously set and if they're unset looks
 /// up the fs via
 ///   - `DAPP_REMAPPINGS` || `FOUNDRY_REMAPPINGS` env var
@@ -143,6 +296,8 @@ pub struct RemappingsProvider<'a> {
     ///   - a `MissingField` error, which means previous provider didn't set the "remappings" field
     ///   - other error, like formatting
     pub remappings: Result<Vec<Remapping>, Error>,
+    /// The previously collected warnings, the remapping conflicts are added to them.
+    pub warnings: Vec<Warning>,
 }
 
 impl RemappingsProvider<'_> {
@@ -156,7 +311,12 @@ impl RemappingsProvider<'_> {
     /// - `remappings.txt`
     /// - Environment variables
     /// - CLI parameters
-    fn get_remappings(&self, remappings: Vec<Remapping>) -> Result<Vec<Remapping>, Error> {
+    ///
+    /// Also returns the report of where the remappings are declared and of their conflicts.
+    fn get_remappings(
+        &self,
+        remappings: Vec<Remapping>,
+    ) -> Result<(Vec<Remapping>, RemappingsReport), Error> {
         trace!("get all remappings from {:?}", self.root);
         /// prioritizes remappings that are closer: shorter `path`
         ///   - ("a", "1/2") over ("a", "1/2/3")
@@ -184,6 +344,8 @@ fn insert_closest(
         // Let's first just extend the remappings with the ones that were passed in,
         // without any filtering.
         let mut user_remappings = Vec::new();
+        // All declared remappings and their source, in order of precedence.
+        let mut candidates = Vec::new();
 
         // check env vars
         if let Some(env_remappings) = remappings_from_env_var("DAPP_REMAPPINGS")
@@ -192,6 +354,7 @@ fn insert_closest(
             user_remappings
                 .extend(env_remappings.map_err::<Error, _>(|err| err.to_string().into())?);
         }
+        candidates.extend(user_remappings.iter().map(|r| (r.clone(), RemappingSource::Env)));
 
         // check remappings.txt file
         let remappings_file = self.root.join("remappings.txt");
@@ -199,10 +362,15 @@ fn insert_closest(
             let content = fs::read_to_string(remappings_file).map_err(|err| err.to_string())?;
             let remappings_from_file: Result<Vec<_>, _> =
                 remappings_from_newline(&content).collect();
-            user_remappings
-                .extend(remappings_from_file.map_err::<Error, _>(|err| err.to_string().into())?);
+            let remappings_from_file =
+                remappings_from_file.map_err::<Error, _>(|err| err.to_string().into())?;
+            candidates.extend(
+                remappings_from_file.iter().map(|r| (r.clone(), RemappingSource::RemappingsTxt)),
+            );
+            user_remappings.extend(remappings_from_file);
         }
 
+        candidates.extend(remappings.iter().map(|r| (r.clone(), RemappingSource::Config)));
         user_remappings.extend(remappings);
         // Let's now use the wrapper to conditionally extend the remappings with the autodetected
         // ones. We want to avoid duplicates, and the wrapper will handle this for us.
@@ -215,6 +383,7 @@ fn insert_closest(
             let mut lib_remappings = BTreeMap::new();
             // find all remappings of from libs that use a foundry.toml
             for r in self.lib_foundry_toml_remappings() {
+                candidates.push((r.clone(), RemappingSource::Dependency));
                 insert_closest(&mut lib_remappings, r.context, r.name, r.path.into());
             }
             // use auto detection for all libs
@@ -230,6 +399,7 @@ fn insert_closest(
                     trace!(target: "forge", "- skipping the remapping");
                     continue
                 }
+                candidates.push((r.clone(), RemappingSource::Dependency));
                 insert_closest(&mut lib_remappings, r.context, r.name, r.path.into());
             }
 
@@ -247,7 +417,28 @@ fn insert_closest(
             );
         }
 
-        Ok(all_remappings.into_inner())
+        let remappings = all_remappings.into_inner();
+        let report = RemappingsReport::new(self.root, &remappings, candidates);
+        Ok((remappings, report))
+    }
+
+    /// Returns the remappings and the report of how they are resolved.
+    fn resolve(&self) -> Result<(Vec<Remapping>, RemappingsReport), Error> {
+        match &self.remappings {
+            Ok(remappings) => self.get_remappings(remappings.clone()),
+            Err(err) => {
+                if let figment::error::Kind::MissingField(_) = err.kind {
+                    self.get_remappings(vec![])
+                } else {
+                    Err(err.clone())
+                }
+            }
+        }
+    }
+
+    /// Returns the report of where the remappings are declared and of their conflicts.
+    pub fn report(&self) -> Result<RemappingsReport, Error> {
+        self.resolve().map(|(_, report)| report)
     }
 
     /// Returns all remappings declared in foundry.toml files of libraries
@@ -305,16 +496,7 @@ fn metadata(&self) -> Metadata {
     }
 
     fn data(&self) -> Result<Map<Profile, Dict>, Error> {
-        let remappings = match &self.remappings {
-            Ok(remappings) => self.get_remappings(remappings.clone()),
-            Err(err) => {
-                if let figment::error::Kind::MissingField(_) = err.kind {
-                    self.get_remappings(vec![])
-                } else {
-                    return Err(err.clone())
-                }
-            }
-        }?;
+        let (remappings, report) = self.resolve()?;
 
         // turn the absolute remapping into a relative one by stripping the `root`
         let remappings = remappings
@@ -322,10 +504,14 @@ fn data(&self) -> Result<Map<Profile, Dict>, Error> {
             .map(|r| RelativeRemapping::new(r, self.root).to_string())
             .collect::<Vec<_>>();
 
-        Ok(Map::from([(
-            Config::selected_profile(),
-            Dict::from([("remappings".to_string(), figment::value::Value::from(remappings))]),
-        )]))
+        let mut dict = Dict::from([("remappings".to_string(), Value::from(remappings))]);
+        if report.conflicts.iter().any(ConflictingRemappings::is_ambiguous) {
+            let warnings =
+                self.warnings.iter().cloned().chain(report.warnings()).collect::<Vec<_>>();
+            dict.insert("__warnings".to_string(), Value::serialize(warnings)?);
+        }
+
+        Ok(Map::from([(Config::selected_profile(), dict)]))
     }
 
     fn profile(&self) -> Option<Profile> {
@@ -0,0 +1,93 @@
+use crate::Config;
+use figment::{
+    providers::{Format, Toml},
+    value::{Dict, Map, Value},
+    Error, Metadata, Profile, Provider,
+};
+use std::path::{Path, PathBuf};
+
+/// A provider that loads API keys and private RPC URLs from secrets files.
+///
+/// The files are loaded in order, later files take precedence:
+/// 1. `~/.foundry/secrets.toml`
+/// 2. `<root>/foundry.secrets.toml`
+///
+/// Only the [`SECRET_KEYS`](Self::SECRET_KEYS) are allowed at the top level of a secrets file,
+/// they apply to the selected profile. The keys that were set are recorded in
+/// [`Config::secrets`], so that they are never written back by [`Config::to_string_pretty`].
+pub(crate) struct SecretsProvider {
+    files: Vec<PathBuf>,
+    profile: Profile,
+}
+
+impl SecretsProvider {
+    /// The keys that can be set in a secrets file.
+    pub(crate) const SECRET_KEYS: &'static [&'static str] =
+        &["eth_rpc_url", "etherscan_api_key", "rpc_endpoints", "etherscan"];
+
+    /// The name of the provider in its [`Metadata`].
+    pub(crate) const NAME: &'static str = "Secrets file";
+
+    /// The key of the recorded secrets, see [`Config::secrets`].
+    pub(crate) const SECRETS_KEY: &'static str = "__secrets";
+
+    pub(crate) fn new(root: &Path, profile: Profile) -> Self {
+        let files = Config::foundry_dir()
+            .map(|dir| dir.join(Config::GLOBAL_SECRETS_FILE_NAME))
+            .into_iter()
+            .chain([root.join(Config::SECRETS_FILE_NAME)])
+            .collect();
+        Self { files, profile }
+    }
+
+    /// Reads the top-level keys of a secrets file.
+    fn read(path: &Path) -> Result<Dict, Error> {
+        let mut data = Toml::file(path).data()?;
+        let dict = data.remove(&Profile::Default).unwrap_or_default();
+        if let Some(key) = dict.keys().find(|key| !Self::SECRET_KEYS.contains(&key.as_str())) {
+            return Err(Error::from(format!(
+                "unsupported key `{key}` in secrets file {}, expected one of: {}",
+                path.display(),
+                Self::SECRET_KEYS.join(", ")
+            )));
+        }
+        Ok(dict)
+    }
+}
+
+impl Provider for SecretsProvider {
+    fn metadata(&self) -> Metadata {
+        Metadata::named(Self::NAME)
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let mut dict = Dict::new();
+        for path in self.files.iter().filter(|path| path.is_file()) {
+            for (key, value) in Self::read(path)? {
+                // tables are merged entry by entry, e.g. `[rpc_endpoints]`
+                if let (Some(Value::Dict(_, table)), Value::Dict(_, entries)) =
+                    (dict.get_mut(&key), &value)
+                {
+                    table.extend(entries.clone());
+                    continue
+                }
+                dict.insert(key, value);
+            }
+        }
+        if dict.is_empty() {
+            return Ok(Map::new())
+        }
+
+        let mut secrets = Vec::new();
+        for (key, value) in &dict {
+            match value {
+                Value::Dict(_, table) => {
+                    secrets.extend(table.keys().map(|entry| format!("{key}.{entry}")));
+                }
+                _ => secrets.push(key.clone()),
+            }
+        }
+        dict.insert(Self::SECRETS_KEY.to_string(), Value::from(secrets));
+        Ok(Map::from([(self.profile.clone(), dict)]))
+    }
+}
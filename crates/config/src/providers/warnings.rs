@@ -1,11 +1,95 @@
-use crate::{Config, Warning, DEPRECATIONS};
+use crate::{utils::edit_distance, Config, Warning, DEPRECATIONS};
 use figment::{
     value::{Dict, Map, Value},
     Error, Figment, Metadata, Profile, Provider,
 };
-use std::collections::BTreeMap;
+use inflector::Inflector;
+use serde::{
+    de::{self, value, Visitor},
+    Deserialize, Deserializer,
+};
+use std::{collections::BTreeMap, sync::LazyLock};
+
+/// Keys of a profile that are read by the providers, and are not fields of the config.
+const PROVIDER_KEYS: &[&str] = &["solc_version", "extends"];
+
+/// Sections of a profile whose keys are checked against their serialized default.
+const NESTED_SECTIONS: &[&str] = &["fuzz", "invariant", "fmt", "lint", "bind_json"];
+
+/// All keys that are accepted in a profile.
+pub(crate) static KNOWN_KEYS: LazyLock<Vec<String>> = LazyLock::new(|| {
+    let mut keys: Vec<_> = struct_fields::<Config>().iter().map(|key| key.to_string()).collect();
+    keys.extend(PROVIDER_KEYS.iter().map(|key| key.to_string()));
+    keys.extend(DEPRECATIONS.iter().map(|(key, _)| key.to_string()));
+    keys.extend(Config::STANDALONE_SECTIONS.iter().map(|key| key.to_string()));
+    keys
+});
+
+/// The keys accepted in the [`NESTED_SECTIONS`] of a profile, from the serialized default config.
+static NESTED_KEYS: LazyLock<BTreeMap<&'static str, Vec<String>>> = LazyLock::new(|| {
+    let default = serde_json::to_value(Config::default()).unwrap_or_default();
+    NESTED_SECTIONS
+        .iter()
+        .filter_map(|section| {
+            let keys = default.get(section)?.as_object()?.keys().cloned().collect();
+            Some((*section, keys))
+        })
+        .collect()
+});
+
+/// Returns the fields accepted when deserializing the struct `T`, including their aliases.
+fn struct_fields<'de, T: Deserialize<'de>>() -> &'static [&'static str] {
+    /// Records the fields passed to [`Deserializer::deserialize_struct`], and fails.
+    struct FieldsDeserializer<'a>(&'a mut &'static [&'static str]);
+
+    impl<'de> Deserializer<'de> for FieldsDeserializer<'_> {
+        type Error = value::Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+            Err(de::Error::custom("expected a struct"))
+        }
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            *self.0 = fields;
+            Err(de::Error::custom("recorded the fields"))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+            option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+            ignored_any
+        }
+    }
 
-/// Generate warnings for unknown sections and deprecated keys
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldsDeserializer(&mut fields));
+    fields
+}
+
+/// Returns the known key closest to the given unknown key, if any is close enough.
+pub(crate) fn suggest_key(key: &str) -> Option<String> {
+    closest_key(key, KNOWN_KEYS.iter())
+}
+
+/// Returns the key of `keys` closest to the given unknown key, if any is close enough.
+pub(crate) fn closest_key<'a>(
+    key: &str,
+    keys: impl IntoIterator<Item = &'a String>,
+) -> Option<String> {
+    let max_distance = (key.len() / 3).max(1);
+    keys.into_iter()
+        .map(|known| (edit_distance(key, known), known))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, known)| known.clone())
+}
+
+/// Generate warnings for unknown sections, unknown keys and deprecated keys
 pub struct WarningsProvider<P> {
     provider: P,
     profile: Profile,
@@ -76,12 +160,46 @@ pub fn collect_warnings(&self) -> Result<Vec<Warning>, Error> {
         out.extend(profiles.clone().flat_map(BTreeMap::keys).filter_map(deprecated_key_warning));
         out.extend(
             profiles
+                .clone()
                 .filter_map(|dict| dict.get(self.profile.as_str().as_str()))
                 .filter_map(Value::as_dict)
                 .flat_map(BTreeMap::keys)
                 .filter_map(deprecated_key_warning),
         );
 
+        // Add warning for unknown keys in the profiles and their nested sections.
+        let unknown_key = |profile: &String, key: String, suggestion| Warning::UnknownKey {
+            key,
+            profile: profile.clone(),
+            source: self.provider.metadata().source.map(|s| s.to_string()),
+            suggestion,
+        };
+        for (profile, keys) in profiles.flat_map(|dict| dict.iter()) {
+            let Some(keys) = keys.as_dict() else { continue };
+            for (key, value) in keys {
+                let snake_key = key.to_snake_case();
+                if !KNOWN_KEYS.contains(&snake_key) {
+                    out.push(unknown_key(profile, key.clone(), suggest_key(&snake_key)));
+                    continue
+                }
+
+                let Some(known) = NESTED_KEYS.get(snake_key.as_str()) else { continue };
+                let Some(nested) = value.as_dict() else { continue };
+                for nested_key in nested.keys() {
+                    let snake_nested_key = nested_key.to_snake_case();
+                    if known.contains(&snake_nested_key) {
+                        continue
+                    }
+                    let suggestion = closest_key(&snake_nested_key, known)
+                        .map(|known| format!("{snake_key}.{known}"));
+                    out.push(unknown_key(profile, format!("{key}.{nested_key}"), suggestion));
+                }
+            }
+        }
+
+        // Add warning for an invalid `extends` chain.
+        out.extend(Config::extended_profiles(&data, &self.profile)?.1);
+
         Ok(out)
     }
 }
@@ -0,0 +1,298 @@
+//! Keeping the remappings declared by a project in sync with its dependencies.
+
+use crate::{remappings_from_newline, soldeer::RemappingsLocation, Config};
+use foundry_compilers::artifacts::remappings::Remapping;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// A remapping of an installed dependency whose prefix is already remapped to another path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RemappingConflict {
+    /// The remapping the prefix currently resolves to.
+    pub existing: Remapping,
+    /// The remapping of the installed dependency.
+    pub remapping: Remapping,
+    /// The remapping with a version-suffixed prefix that can be added instead, if the version of
+    /// the dependency is known.
+    pub suffixed: Option<Remapping>,
+}
+
+/// Changes to the remappings declared by a project when dependencies are installed or removed.
+///
+/// Changes are collected first and written at once with [`RemappingsUpdate::apply`], so the
+/// declared remappings are left untouched if installing or removing a dependency fails.
+///
+/// Projects that don't declare any remappings rely on auto-detection, so only the remappings that
+/// can't be auto-detected, because their prefix conflicts with an existing one, are added.
+#[derive(Clone, Debug)]
+pub struct RemappingsUpdate {
+    /// The root of the project.
+    root: PathBuf,
+    /// Where the remappings are declared.
+    pub location: RemappingsLocation,
+    /// The declared remappings.
+    declared: Vec<Remapping>,
+    /// All the remappings of the project, including auto-detected ones.
+    resolved: Vec<Remapping>,
+    /// The remappings to add.
+    pub added: Vec<Remapping>,
+    /// The declared remappings to remove.
+    pub removed: Vec<Remapping>,
+}
+
+impl RemappingsUpdate {
+    /// Loads the remappings declared in `remappings.txt` or, if it doesn't exist, in the selected
+    /// profile of `foundry.toml`.
+    pub fn new(config: &Config) -> eyre::Result<Self> {
+        let remappings_file = config.root.join("remappings.txt");
+        let (location, declared) = if remappings_file.is_file() {
+            let content = fs::read_to_string(remappings_file)?;
+            let declared = remappings_from_newline(&content).collect::<Result<Vec<_>, _>>()?;
+            (RemappingsLocation::Txt, declared)
+        } else if let Some(declared) = config_remappings(config)? {
+            (RemappingsLocation::Config, declared)
+        } else {
+            (RemappingsLocation::Txt, Vec::new())
+        };
+        let resolved =
+            config.remappings.iter().map(|r| r.clone().to_relative_remapping()).collect();
+        Ok(Self {
+            root: config.root.clone(),
+            location,
+            declared,
+            resolved,
+            added: Vec::new(),
+            removed: Vec::new(),
+        })
+    }
+
+    /// Returns `true` if there are no changes.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+
+    /// Adds the remappings of a dependency installed in `path`, auto-detected with
+    /// [`dependency_remappings`].
+    ///
+    /// `on_conflict` is called for every remapping whose prefix is already remapped to another
+    /// path, and decides whether the version-suffixed remapping is added instead.
+    pub fn add_dependency(
+        &mut self,
+        remappings: Vec<Remapping>,
+        version: Option<&str>,
+        mut on_conflict: impl FnMut(&RemappingConflict) -> bool,
+    ) {
+        for remapping in remappings {
+            match self.find(&remapping).cloned() {
+                Some(existing) if existing.path == remapping.path => {}
+                Some(existing) => {
+                    let suffixed = version.map(|version| version_suffixed(&remapping, version));
+                    let conflict = RemappingConflict { existing, remapping, suffixed };
+                    if let Some(suffixed) = conflict.suffixed.clone() {
+                        if on_conflict(&conflict) && self.find(&suffixed).is_none() {
+                            self.added.push(suffixed);
+                        }
+                    } else {
+                        on_conflict(&conflict);
+                    }
+                }
+                None => {
+                    if !self.declared.is_empty() {
+                        self.added.push(remapping);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Removes the remappings pointing into the directory of a removed dependency.
+    pub fn remove_dependency(&mut self, path: &Path) {
+        let path = path.strip_prefix(&self.root).unwrap_or(path);
+        let is_stale = |r: &Remapping| Path::new(&r.path).starts_with(path);
+        self.added.retain(|r| !is_stale(r));
+        for remapping in &self.declared {
+            if is_stale(remapping) && !self.removed.contains(remapping) {
+                self.removed.push(remapping.clone());
+            }
+        }
+    }
+
+    /// Returns the declared remappings after the update.
+    pub fn remappings(&self) -> Vec<Remapping> {
+        self.declared
+            .iter()
+            .filter(|r| !self.removed.contains(r))
+            .chain(&self.added)
+            .cloned()
+            .collect()
+    }
+
+    /// Writes the updated remappings to `remappings.txt` or `foundry.toml`.
+    pub fn apply(&self, config: &Config) -> eyre::Result<()> {
+        if self.is_empty() {
+            return Ok(())
+        }
+        let remappings = self.remappings().iter().map(ToString::to_string).collect::<Vec<_>>();
+        match self.location {
+            RemappingsLocation::Txt => {
+                // Write to a temporary file first, so the file is replaced atomically.
+                let path = self.root.join("remappings.txt");
+                let tmp = self.root.join("remappings.txt.tmp");
+                let mut content = remappings.join("\n");
+                content.push('\n');
+                fs::write(&tmp, content)?;
+                fs::rename(tmp, path)?;
+            }
+            RemappingsLocation::Config => config.update(|doc| {
+                let profile = config.profile.as_str().as_str();
+                let remappings: toml_edit::Array =
+                    remappings.iter().map(|r| toml_edit::Value::from(r.as_str())).collect();
+                doc[Config::PROFILE_SECTION][profile]["remappings"] = toml_edit::value(remappings);
+                true
+            })?,
+        }
+        Ok(())
+    }
+
+    /// Returns the remapping with the same context and prefix, if any.
+    fn find(&self, remapping: &Remapping) -> Option<&Remapping> {
+        self.declared
+            .iter()
+            .filter(|r| !self.removed.contains(r))
+            .chain(&self.added)
+            .chain(&self.resolved)
+            .find(|r| r.context == remapping.context && r.name == remapping.name)
+    }
+}
+
+/// Returns the remappings auto-detected for the dependency installed in `path`, relative to
+/// `root`.
+///
+/// Remappings of the dependency's own dependencies are not included.
+pub fn dependency_remappings(root: &Path, path: &Path) -> Vec<Remapping> {
+    let Some(lib_dir) = path.parent() else { return Vec::new() };
+    Remapping::find_many(lib_dir)
+        .into_iter()
+        .filter(|r| {
+            Path::new(&r.path).strip_prefix(path).is_ok_and(|rest| {
+                !rest
+                    .components()
+                    .any(|c| c.as_os_str() == "lib" || c.as_os_str() == "node_modules")
+            })
+        })
+        .filter(|r| !["lib/", "src/", "contracts/"].contains(&r.name.as_str()))
+        .map(|r| r.into_relative(root).to_relative_remapping())
+        .collect()
+}
+
+/// Returns the remappings declared in the selected profile of `foundry.toml`, if any.
+fn config_remappings(config: &Config) -> eyre::Result<Option<Vec<Remapping>>> {
+    let path = config.get_config_path();
+    if !path.is_file() {
+        return Ok(None)
+    }
+    let doc = fs::read_to_string(path)?.parse::<toml_edit::DocumentMut>()?;
+    let Some(remappings) = doc
+        .get(Config::PROFILE_SECTION)
+        .and_then(|profiles| profiles.get(config.profile.as_str().as_str()))
+        .and_then(|profile| profile.get("remappings"))
+        .and_then(|remappings| remappings.as_array())
+    else {
+        return Ok(None)
+    };
+    if remappings.is_empty() {
+        return Ok(None)
+    }
+    let remappings = remappings
+        .iter()
+        .filter_map(|r| r.as_str())
+        .map(Remapping::from_str)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Some(remappings))
+}
+
+/// Suffixes the prefix of the remapping with the version, for example `forge-std-1.9.4/`.
+fn version_suffixed(remapping: &Remapping, version: &str) -> Remapping {
+    let version = match version.strip_prefix('v') {
+        Some(v) if v.starts_with(|c: char| c.is_ascii_digit()) => v,
+        _ => version,
+    };
+    let (name, slash) =
+        remapping.name.strip_suffix('/').map_or((remapping.name.as_str(), ""), |name| (name, "/"));
+    Remapping {
+        context: remapping.context.clone(),
+        name: format!("{name}-{version}{slash}"),
+        path: remapping.path.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_update(declared: &[&str], resolved: &[&str]) -> RemappingsUpdate {
+        let parse = |r: &&str| Remapping::from_str(r).unwrap();
+        RemappingsUpdate {
+            root: PathBuf::from("/project"),
+            location: RemappingsLocation::Txt,
+            declared: declared.iter().map(parse).collect(),
+            resolved: resolved.iter().map(parse).collect(),
+            added: Vec::new(),
+            removed: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn adds_declared_remappings() {
+        let mut update = new_update(&["forge-std/=lib/forge-std/src/"], &[]);
+        let solady = Remapping::from_str("solady/=lib/solady/src/").unwrap();
+        update.add_dependency(vec![solady.clone()], None, |_| unreachable!());
+        assert_eq!(update.added, vec![solady]);
+
+        // Projects without declared remappings rely on auto-detection.
+        let mut update = new_update(&[], &[]);
+        update.add_dependency(
+            vec![Remapping::from_str("solady/=lib/solady/src/").unwrap()],
+            None,
+            |_| unreachable!(),
+        );
+        assert!(update.is_empty());
+    }
+
+    #[test]
+    fn suffixes_conflicting_remappings() {
+        let mut update = new_update(&[], &["@openzeppelin/=lib/openzeppelin-contracts/"]);
+        let remapping = Remapping::from_str("@openzeppelin/=lib/oz-v4/").unwrap();
+        let mut conflicts = Vec::new();
+        update.add_dependency(vec![remapping], Some("v4.9.6"), |conflict| {
+            conflicts.push(conflict.clone());
+            true
+        });
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(
+            update.added,
+            vec![Remapping::from_str("@openzeppelin-4.9.6/=lib/oz-v4/").unwrap()]
+        );
+
+        // Rejected conflicts are skipped.
+        let mut update = new_update(&[], &["forge-std/=lib/forge-std/src/"]);
+        let remapping = Remapping::from_str("forge-std/=lib/forge-std-old/src/").unwrap();
+        update.add_dependency(vec![remapping], Some("1.0.0"), |_| false);
+        assert!(update.is_empty());
+    }
+
+    #[test]
+    fn removes_stale_remappings() {
+        let mut update =
+            new_update(&["forge-std/=lib/forge-std/src/", "solady/=lib/solady/src/"], &[]);
+        update.remove_dependency(Path::new("/project/lib/solady"));
+        assert_eq!(update.removed, vec![Remapping::from_str("solady/=lib/solady/src/").unwrap()]);
+        assert_eq!(
+            update.remappings(),
+            vec![Remapping::from_str("forge-std/=lib/forge-std/src/").unwrap()]
+        );
+    }
+}
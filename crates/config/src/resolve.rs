@@ -51,12 +51,20 @@ fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
 
 /// Replaces all Env var placeholders in the input string with the values they hold
 pub fn interpolate(input: &str) -> Result<String, UnresolvedEnvVarError> {
+    interpolate_with(input, |var| env::var(var))
+}
+
+/// Replaces all placeholders in the input string with the values returned by `lookup`
+pub fn interpolate_with(
+    input: &str,
+    lookup: impl Fn(&str) -> Result<String, VarError>,
+) -> Result<String, UnresolvedEnvVarError> {
     let mut res = input.to_string();
 
     // loop over all placeholders in the input and replace them one by one
     for caps in RE_PLACEHOLDER.captures_iter(input) {
         let var = &caps["inner"];
-        let value = env::var(var).map_err(|source| UnresolvedEnvVarError {
+        let value = lookup(var).map_err(|source| UnresolvedEnvVarError {
             unresolved: input.to_string(),
             var: var.to_string(),
             source,
@@ -86,4 +94,24 @@ fn can_find_placeholder() {
         assert_eq!(cap.name("outer").unwrap().as_str(), "${API_KEY}");
         assert_eq!(cap.name("inner").unwrap().as_str(), "API_KEY");
     }
+
+    #[test]
+    fn can_interpolate_with_lookup() {
+        let lookup = |var: &str| match var {
+            "HOST" => Ok("localhost".to_string()),
+            "PORT" => Ok("8545".to_string()),
+            _ => Err(VarError::NotPresent),
+        };
+        assert_eq!(
+            interpolate_with("http://${HOST}:${ PORT }", lookup).unwrap(),
+            "http://localhost:8545"
+        );
+
+        let err = interpolate_with("http://${HOST}:${MISSING}", lookup).unwrap_err();
+        assert_eq!(err.var, "MISSING");
+        assert_eq!(
+            err.to_string(),
+            "environment variable `MISSING` not found in `http://${HOST}:${MISSING}`"
+        );
+    }
 }
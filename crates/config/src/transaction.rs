@@ -0,0 +1,21 @@
+//! Configuration for watching transactions until they are confirmed.
+
+use serde::{Deserialize, Serialize};
+
+/// Contains the config for polling a transaction's inclusion status.
+///
+/// The poll interval starts at `poll_interval` and doubles every time a poll observes no change,
+/// up to `max_poll_interval`. It is reset whenever a new block is seen.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionWatchConfig {
+    /// The initial interval between polls, in milliseconds.
+    pub poll_interval: u64,
+    /// The maximum interval between polls, in milliseconds.
+    pub max_poll_interval: u64,
+}
+
+impl Default for TransactionWatchConfig {
+    fn default() -> Self {
+        Self { poll_interval: 1000, max_poll_interval: 12000 }
+    }
+}
@@ -244,6 +244,22 @@ fn from_str(s: &str) -> Result<Self, Self::Err> {
     }
 }
 
+/// Returns the Levenshtein distance between two strings, used to suggest known config keys.
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == *cb { diagonal } else { 1 + diagonal.min(above).min(row[j]) };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
 /// Returns the [SpecId] derived from [EvmVersion]
 #[inline]
 pub fn evm_spec_id(evm_version: EvmVersion, odyssey: bool) -> SpecId {
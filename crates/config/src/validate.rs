@@ -0,0 +1,139 @@
+//! Validation of contradictory settings, see [`Config::validate`].
+
+use crate::{Config, SolcReq, Warning};
+#[cfg(feature = "svm")]
+use foundry_compilers::compilers::solc::Solc;
+use semver::Version;
+use std::fmt;
+
+/// The first solc version that supports `via_ir` without it being experimental.
+const VIA_IR_MIN_SOLC: Version = Version::new(0, 8, 13);
+
+/// A setting that contradicts another setting of the config.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum ValidationError {
+    /// `eof_version` is set without `via_ir`.
+    #[error("`eof_version` requires `via_ir`; set `via_ir = true` or remove `eof_version`")]
+    EofWithoutViaIr,
+}
+
+impl ValidationError {
+    /// The key of the setting that is invalid.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::EofWithoutViaIr => "eof_version",
+        }
+    }
+}
+
+/// The errors found by [`Config::validate`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationErrors(pub Vec<ValidationError>);
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, err) in self.0.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{err}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+impl From<ValidationErrors> for figment::Error {
+    fn from(errors: ValidationErrors) -> Self {
+        errors
+            .0
+            .into_iter()
+            .map(|err| Self::from(err.to_string()))
+            .reduce(Self::chain)
+            .unwrap_or_else(|| Self::from("invalid config".to_string()))
+    }
+}
+
+impl Config {
+    /// Checks the config for contradictory settings, instead of failing later deep inside
+    /// compilation.
+    ///
+    /// Returns an error for settings that can't work together, and [`Warning`]s for settings that
+    /// may fail once the compiler is needed, e.g. a missing solc version in offline mode or
+    /// `via_ir` with a solc version where it is experimental.
+    pub fn validate(&self) -> Result<Vec<Warning>, ValidationErrors> {
+        let mut errors = Vec::new();
+        let mut warnings = Vec::new();
+
+        if let Some(SolcReq::Version(version)) = &self.solc {
+            if self.via_ir && *version < VIA_IR_MIN_SOLC {
+                warnings.push(Warning::ViaIrExperimental { version: version.clone() });
+            }
+
+            if self.offline && !is_solc_installed(version) {
+                warnings.push(Warning::SolcNotInstalledOffline { version: version.clone() });
+            }
+        }
+
+        // `eof = true` enables `via_ir` when the config is sanitized
+        if self.eof_version.is_some() && !self.via_ir && !self.eof {
+            errors.push(ValidationError::EofWithoutViaIr);
+        }
+
+        if errors.is_empty() {
+            Ok(warnings)
+        } else {
+            Err(ValidationErrors(errors))
+        }
+    }
+}
+
+/// Returns whether the solc `version` is installed with `svm`.
+#[cfg(feature = "svm")]
+fn is_solc_installed(version: &Version) -> bool {
+    matches!(Solc::find_svm_installed_version(version), Ok(Some(_)))
+}
+
+/// Without `svm`, solc versions can't be resolved in either mode, see `Config::ensure_solc`.
+#[cfg(not(feature = "svm"))]
+fn is_solc_installed(_version: &Version) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use foundry_compilers::artifacts::EofVersion;
+
+    #[test]
+    fn validate_contradictions() {
+        assert_eq!(Config::default().validate(), Ok(vec![]));
+
+        let config = Config {
+            solc: Some(SolcReq::Version(Version::new(0, 8, 12))),
+            via_ir: true,
+            eof_version: Some(EofVersion::V1),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Ok(vec![Warning::ViaIrExperimental { version: Version::new(0, 8, 12) }])
+        );
+
+        let config = Config {
+            solc: Some(SolcReq::Version(Version::new(0, 8, 13))),
+            via_ir: true,
+            ..Default::default()
+        };
+        assert_eq!(config.validate(), Ok(vec![]));
+
+        let config = Config { eof_version: Some(EofVersion::V1), ..Default::default() };
+        let err = figment::Error::from(config.validate().unwrap_err());
+        assert_eq!(err.count(), 1);
+        assert!(err.to_string().contains("set `via_ir = true`"), "{err}");
+
+        let config = Config { eof: true, eof_version: Some(EofVersion::V1), ..Default::default() };
+        assert!(config.validate().is_ok());
+    }
+}
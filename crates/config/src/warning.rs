@@ -1,3 +1,4 @@
+use crate::ConflictingRemappings;
 use figment::Profile;
 use serde::{Deserialize, Serialize};
 use std::{fmt, path::PathBuf};
@@ -13,6 +14,17 @@ pub enum Warning {
         /// The source where the key was found
         source: Option<String>,
     },
+    /// An unknown key was encountered in a profile of a TOML file
+    UnknownKey {
+        /// The unknown key
+        key: String,
+        /// The profile declaring the key
+        profile: String,
+        /// The source where the key was found
+        source: Option<String>,
+        /// The known key closest to the unknown key, if any is close enough
+        suggestion: Option<String>,
+    },
     /// No local TOML file found, with location tried
     NoLocalToml(PathBuf),
     /// Could not read TOML
@@ -46,6 +58,51 @@ pub enum Warning {
         /// is being removed completely without replacement
         new: String,
     },
+    /// Could not read a file of address labels
+    CouldNotReadLabels {
+        /// The path of the labels file
+        path: PathBuf,
+        /// The error message that occurred
+        err: String,
+    },
+    /// A variable of a profile's `env` table is already set in the environment.
+    EnvCollision {
+        /// The name of the variable
+        name: String,
+        /// The profile that defines the variable
+        profile: String,
+        /// Whether the value of the profile was used, which is the case for values loaded from
+        /// `.env` files
+        overridden: bool,
+    },
+    /// The `extends` key of a profile names an unknown profile
+    InvalidExtends {
+        /// The profile declaring `extends`
+        profile: String,
+        /// The error message that occurred
+        err: String,
+    },
+    /// The configured solc version is not installed and can't be installed in offline mode
+    SolcNotInstalledOffline {
+        /// The configured solc version
+        version: semver::Version,
+    },
+    /// `via_ir` is enabled with a solc version where it is experimental
+    ViaIrExperimental {
+        /// The configured solc version
+        version: semver::Version,
+    },
+    /// A remapped prefix is mapped to different paths by the same source, e.g. by two
+    /// dependencies
+    RemappingConflict(ConflictingRemappings),
+}
+
+impl Warning {
+    /// Returns whether this is a warning about an unknown key or section, which are errors with
+    /// [`Config::strict_config`](crate::Config::strict_config).
+    pub fn is_unknown_config(&self) -> bool {
+        matches!(self, Self::UnknownSection { .. } | Self::UnknownKey { .. })
+    }
 }
 
 impl fmt::Display for Warning {
@@ -61,6 +118,14 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
                      Please use [profile.{unknown_section}] instead or run `forge config --fix`."
                 )
             }
+            Self::UnknownKey { key, profile, source, suggestion } => {
+                let source = source.as_ref().map(|src| format!(" in {src}")).unwrap_or_default();
+                write!(f, "Found unknown `{key}` config for [profile.{profile}]{source}")?;
+                if let Some(suggestion) = suggestion {
+                    write!(f, ". Did you mean `{suggestion}`?")?;
+                }
+                Ok(())
+            }
             Self::NoLocalToml(path) => write!(
                 f,
                 "No local TOML found to fix at {}.\n\
@@ -84,6 +149,27 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             Self::DeprecatedKey { old, new } => {
                 write!(f, "Key `{old}` is being deprecated in favor of `{new}`. It will be removed in future versions.")
             }
+            Self::CouldNotReadLabels { path, err } => {
+                write!(f, "Could not read labels from {}: {err}", path.display())
+            }
+            Self::EnvCollision { name, profile, overridden: true } => {
+                write!(f, "Environment variable `{name}` from a `.env` file is overridden by `[profile.{profile}.env]`.")
+            }
+            Self::EnvCollision { name, profile, overridden: false } => {
+                write!(f, "Environment variable `{name}` is already set; ignoring the value from `[profile.{profile}.env]`.")
+            }
+            Self::InvalidExtends { profile, err } => {
+                write!(f, "Could not resolve `extends` of [profile.{profile}]: {err}")
+            }
+            Self::SolcNotInstalledOffline { version } => {
+                write!(f, "solc {version} is not installed and can't be installed in offline mode; disable `offline` to install it.")
+            }
+            Self::ViaIrExperimental { version } => {
+                write!(f, "`via_ir` is experimental before solc 0.8.13, but `solc` is set to {version}; set `solc` to a newer version or disable `via_ir`.")
+            }
+            Self::RemappingConflict(conflict) => {
+                write!(f, "Ambiguous remapping: {conflict}. Declare the remapping in remappings.txt or foundry.toml to choose the path.")
+            }
         }
     }
 }
@@ -0,0 +1,221 @@
+//! Breakpoints set in the TUI.
+//!
+//! A breakpoint is written as a location, optionally followed by `if` and a condition:
+//! - `op SSTORE`: any step executing the opcode;
+//! - `pc 0x1a`: any step at the program counter;
+//! - `line 12` or `line Counter.sol:12`: the steps of a source line;
+//! - `depth 2`: the steps executed at the call depth.
+//!
+//! Conditions compare two watch expressions, e.g. `op SSTORE if stack[1] == 0`. A breakpoint is
+//! hit when execution reaches its location from a step outside of it, and the condition holds.
+
+use crate::eval::{EvalContext, Expr};
+use revm::interpreter::OpCode;
+use std::{fmt, path::Path, str::FromStr};
+
+/// A parsed breakpoint.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Breakpoint {
+    pub(crate) location: Location,
+    pub(crate) condition: Option<Condition>,
+}
+
+/// Where a breakpoint stops.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Location {
+    Op(OpCode),
+    Pc(usize),
+    /// A 1-based line, in the file whose path ends with `file`, or in any file if `None`.
+    Line {
+        file: Option<String>,
+        line: usize,
+    },
+    Depth(u64),
+}
+
+/// A comparison of two expressions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Condition {
+    lhs: Expr,
+    op: Comparison,
+    rhs: Expr,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Comparison {
+    Eq,
+    Ne,
+    Le,
+    Ge,
+    Lt,
+    Gt,
+}
+
+impl Comparison {
+    /// All the comparisons, with the ones that are prefixes of others last.
+    const ALL: [Self; 6] = [Self::Eq, Self::Ne, Self::Le, Self::Ge, Self::Lt, Self::Gt];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Eq => "==",
+            Self::Ne => "!=",
+            Self::Le => "<=",
+            Self::Ge => ">=",
+            Self::Lt => "<",
+            Self::Gt => ">",
+        }
+    }
+}
+
+impl Location {
+    /// Returns `true` if a step executing `op` at `pc` and `depth` is at this location.
+    ///
+    /// `source` returns the path and content of the source file of the step, and the offset of
+    /// the step in it; it's only called for line breakpoints.
+    pub(crate) fn matches<'a>(
+        &self,
+        op: OpCode,
+        pc: usize,
+        depth: u64,
+        source: impl FnOnce() -> Option<(&'a Path, &'a str, usize)>,
+    ) -> bool {
+        match self {
+            Self::Op(expected) => op == *expected,
+            Self::Pc(expected) => pc == *expected,
+            Self::Depth(expected) => depth == *expected,
+            Self::Line { file, line } => {
+                let Some((path, source, offset)) = source() else { return false };
+                file.as_ref().is_none_or(|file| path.ends_with(file)) &&
+                    source.get(..offset).is_some_and(|s| s.matches('\n').count() + 1 == *line)
+            }
+        }
+    }
+}
+
+impl Condition {
+    /// Evaluates the condition, which doesn't hold if either side can't be evaluated.
+    pub(crate) fn holds(&self, ctx: &EvalContext<'_>) -> bool {
+        let (Ok(lhs), Ok(rhs)) = (ctx.eval_word(&self.lhs), ctx.eval_word(&self.rhs)) else {
+            return false
+        };
+        match self.op {
+            Comparison::Eq => lhs == rhs,
+            Comparison::Ne => lhs != rhs,
+            Comparison::Le => lhs <= rhs,
+            Comparison::Ge => lhs >= rhs,
+            Comparison::Lt => lhs < rhs,
+            Comparison::Gt => lhs > rhs,
+        }
+    }
+}
+
+impl FromStr for Breakpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (location, condition) = match s.split_once(" if ") {
+            Some((location, condition)) => (location, Some(condition.parse()?)),
+            None => (s, None),
+        };
+        Ok(Self { location: location.parse()?, condition })
+    }
+}
+
+impl FromStr for Location {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, target) = s.trim().split_once(char::is_whitespace).ok_or_else(|| {
+            "expected `op <opcode>`, `pc <pc>`, `line [<file>:]<line>` or `depth <depth>`"
+                .to_string()
+        })?;
+        let target = target.trim();
+        let number = |s: &str| {
+            let n = match s.strip_prefix("0x") {
+                Some(hex) => usize::from_str_radix(hex, 16),
+                None => s.parse(),
+            };
+            n.map_err(|e| format!("invalid number `{s}`: {e}"))
+        };
+        match kind {
+            "op" => (0..=u8::MAX)
+                .filter_map(OpCode::new)
+                .find(|op| op.to_string().eq_ignore_ascii_case(target))
+                .map(Self::Op)
+                .ok_or_else(|| format!("unknown opcode `{target}`")),
+            "pc" => number(target).map(Self::Pc),
+            "line" => match target.rsplit_once(':') {
+                Some((file, line)) => {
+                    Ok(Self::Line { file: Some(file.to_string()), line: number(line)? })
+                }
+                None => Ok(Self::Line { file: None, line: number(target)? }),
+            },
+            "depth" => number(target).map(|depth| Self::Depth(depth as u64)),
+            _ => Err(format!("unknown breakpoint kind `{kind}`")),
+        }
+    }
+}
+
+impl FromStr for Condition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (lhs, op, rhs) = Comparison::ALL
+            .into_iter()
+            .find_map(|op| s.split_once(op.as_str()).map(|(lhs, rhs)| (lhs, op, rhs)))
+            .ok_or_else(|| format!("expected a comparison in `{s}`"))?;
+        Ok(Self { lhs: lhs.parse()?, op, rhs: rhs.parse()? })
+    }
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.location {
+            Location::Op(op) => write!(f, "op {op}")?,
+            Location::Pc(pc) => write!(f, "pc {pc:#x}")?,
+            Location::Line { file: Some(file), line } => write!(f, "line {file}:{line}")?,
+            Location::Line { file: None, line } => write!(f, "line {line}")?,
+            Location::Depth(depth) => write!(f, "depth {depth}")?,
+        }
+        if let Some(Condition { lhs, op, rhs }) = &self.condition {
+            write!(f, " if {lhs} {} {rhs}", op.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_breakpoints() {
+        let parse = |s: &str| s.parse::<Breakpoint>().map(|b| b.to_string());
+        assert_eq!(parse("op sstore").unwrap(), "op SSTORE");
+        assert_eq!(parse("pc 26").unwrap(), "pc 0x1a");
+        assert_eq!(parse("line src/Counter.sol:12").unwrap(), "line src/Counter.sol:12");
+        assert_eq!(parse("depth 2 if stack[0]>=0x10").unwrap(), "depth 2 if stack[0] >= 16");
+        assert_eq!(parse("op CALL if x != 1").unwrap(), "op CALL if x != 1");
+        assert!(parse("op NOTANOPCODE").is_err());
+        assert!(parse("line").is_err());
+        assert!(parse("pc 1 if x").is_err());
+    }
+
+    #[test]
+    fn match_locations() {
+        let source = "contract A {\n    function f() {}\n}\n";
+        let offset = source.find("function").unwrap();
+        let at = |location: &str, op, pc| {
+            let location = location.parse::<Location>().unwrap();
+            location.matches(op, pc, 1, || Some((Path::new("src/A.sol"), source, offset)))
+        };
+        assert!(at("op SSTORE", OpCode::SSTORE, 0));
+        assert!(!at("op SSTORE", OpCode::SLOAD, 0));
+        assert!(at("pc 0x1a", OpCode::SLOAD, 26));
+        assert!(at("depth 1", OpCode::SLOAD, 26));
+        assert!(at("line 2", OpCode::SLOAD, 0));
+        assert!(at("line A.sol:2", OpCode::SLOAD, 0));
+        assert!(!at("line B.sol:2", OpCode::SLOAD, 0));
+        assert!(!at("line 3", OpCode::SLOAD, 0));
+    }
+}
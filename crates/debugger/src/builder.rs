@@ -1,9 +1,16 @@
 //! Debugger builder.
 
-use crate::{node::flatten_call_trace, DebugNode, Debugger};
-use alloy_primitives::{map::AddressHashMap, Address};
+use crate::{debug_info::ContractDebugInfo, node::flatten_call_trace, DebugNode, Debugger};
+use alloy_primitives::{
+    map::{AddressHashMap, HashMap},
+    Address,
+};
 use foundry_common::{evm::Breakpoints, get_contract_name};
+use foundry_compilers::artifacts::StorageLayout;
+use foundry_config::DebuggerConfig;
 use foundry_evm_traces::{debug::ContractSources, CallTraceArena, CallTraceDecoder, Traces};
+use std::path::PathBuf;
+
 /// Debugger builder.
 #[derive(Debug, Default)]
 #[must_use = "builders do nothing unless you call `build` on them"]
@@ -12,10 +19,22 @@ pub struct DebuggerBuilder {
     debug_arena: Vec<DebugNode>,
     /// Identified contracts.
     identified_contracts: AddressHashMap<String>,
+    /// Artifact identifiers (`path:Name`) of the identified contracts.
+    contract_ids: AddressHashMap<String>,
+    /// Address labels.
+    labels: AddressHashMap<String>,
     /// Map of source files.
     sources: ContractSources,
     /// Map of the debugger breakpoints.
     breakpoints: Breakpoints,
+    /// ethdebug information, keyed by artifact identifier.
+    debug_info: HashMap<String, ContractDebugInfo>,
+    /// Storage layouts, keyed by contract name.
+    storage_layouts: HashMap<String, StorageLayout>,
+    /// The TUI configuration.
+    config: DebuggerConfig,
+    /// The project root, which source paths are relative to.
+    root: PathBuf,
 }
 
 impl DebuggerBuilder {
@@ -50,11 +69,19 @@ pub fn decoders(mut self, decoders: &[CallTraceDecoder]) -> Self {
         self
     }
 
-    /// Extends the identified contracts from a decoder.
+    /// Extends the identified contracts and address labels from a decoder.
+    ///
+    /// Proxies are labeled with their implementation, see [`CallTraceDecoder::label`].
     #[inline]
-    pub fn decoder(self, decoder: &CallTraceDecoder) -> Self {
+    pub fn decoder(mut self, decoder: &CallTraceDecoder) -> Self {
+        self.contract_ids.extend(decoder.contracts.iter().map(|(k, v)| (*k, v.clone())));
         let c = decoder.contracts.iter().map(|(k, v)| (*k, get_contract_name(v).to_string()));
-        self.identified_contracts(c)
+        let labels = decoder
+            .labels
+            .keys()
+            .chain(decoder.proxies.keys())
+            .filter_map(|address| Some((*address, decoder.label(address)?)));
+        self.identified_contracts(c).labels(labels)
     }
 
     /// Extends the identified contracts.
@@ -67,6 +94,13 @@ pub fn identified_contracts(
         self
     }
 
+    /// Extends the address labels.
+    #[inline]
+    pub fn labels(mut self, labels: impl IntoIterator<Item = (Address, String)>) -> Self {
+        self.labels.extend(labels);
+        self
+    }
+
     /// Sets the sources for the debugger.
     #[inline]
     pub fn sources(mut self, sources: ContractSources) -> Self {
@@ -81,10 +115,66 @@ pub fn breakpoints(mut self, breakpoints: Breakpoints) -> Self {
         self
     }
 
+    /// Extends the ethdebug information used to name stack values, keyed by artifact identifier.
+    #[inline]
+    pub fn debug_info(
+        mut self,
+        debug_info: impl IntoIterator<Item = (String, ContractDebugInfo)>,
+    ) -> Self {
+        self.debug_info.extend(debug_info);
+        self
+    }
+
+    /// Extends the storage layouts used to evaluate state variables in watch expressions.
+    #[inline]
+    pub fn storage_layouts(
+        mut self,
+        storage_layouts: impl IntoIterator<Item = (String, StorageLayout)>,
+    ) -> Self {
+        self.storage_layouts.extend(storage_layouts);
+        self
+    }
+
+    /// Sets the TUI configuration, such as the layout of the panes.
+    #[inline]
+    pub fn config(mut self, config: DebuggerConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Sets the project root, used to open source files in an editor.
+    #[inline]
+    pub fn root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = root.into();
+        self
+    }
+
     /// Builds the debugger.
     #[inline]
     pub fn build(self) -> Debugger {
-        let Self { debug_arena, identified_contracts, sources, breakpoints } = self;
-        Debugger::new(debug_arena, identified_contracts, sources, breakpoints)
+        let Self {
+            debug_arena,
+            identified_contracts,
+            contract_ids,
+            labels,
+            sources,
+            breakpoints,
+            debug_info,
+            storage_layouts,
+            config,
+            root,
+        } = self;
+        Debugger::new(
+            debug_arena,
+            identified_contracts,
+            contract_ids,
+            labels,
+            sources,
+            breakpoints,
+            debug_info,
+            storage_layouts,
+            config,
+            root,
+        )
     }
 }
@@ -0,0 +1,328 @@
+//! Loader and variable resolution for the [ethdebug](https://ethdebug.github.io/format/) debug
+//! information format emitted by solc.
+//!
+//! Only the subset required to name stack values is supported: per-instruction `variables`
+//! contexts (optionally nested in `gather` contexts) with `stack` pointers and elementary types.
+//! Everything else is ignored, in which case the debugger falls back to the raw stack display.
+
+use alloy_primitives::{Address, I256, U256};
+use eyre::Result;
+use serde::Deserialize;
+use std::{collections::HashMap, path::Path};
+
+/// ethdebug information for the creation and runtime bytecode of a contract.
+#[derive(Clone, Debug, Default)]
+pub struct ContractDebugInfo {
+    /// Debug information of the creation bytecode.
+    pub creation: Option<ProgramDebugInfo>,
+    /// Debug information of the runtime bytecode.
+    pub runtime: Option<ProgramDebugInfo>,
+}
+
+impl ContractDebugInfo {
+    /// Extracts the debug information of all contracts from a solc standard JSON output.
+    ///
+    /// Returns a map of contract identifier (`path:Name`) to its debug information, read from the
+    /// `evm.bytecode.ethdebug` and `evm.deployedBytecode.ethdebug` outputs. Contracts without any
+    /// ethdebug output are skipped.
+    pub fn from_standard_json_output(output: &serde_json::Value) -> HashMap<String, Self> {
+        let mut contracts = HashMap::new();
+        let Some(files) = output.get("contracts").and_then(|c| c.as_object()) else {
+            return contracts;
+        };
+        for (file, file_contracts) in files {
+            let Some(file_contracts) = file_contracts.as_object() else { continue };
+            for (name, contract) in file_contracts {
+                let program = |bytecode: &str| {
+                    contract
+                        .get("evm")?
+                        .get(bytecode)?
+                        .get("ethdebug")
+                        .and_then(|v| ProgramDebugInfo::deserialize(v).ok())
+                };
+                let info =
+                    Self { creation: program("bytecode"), runtime: program("deployedBytecode") };
+                if info.creation.is_some() || info.runtime.is_some() {
+                    contracts.insert(format!("{file}:{name}"), info);
+                }
+            }
+        }
+        contracts
+    }
+
+    /// Loads the debug information of all contracts from the build info files in the given
+    /// directory, skipping the files that can't be read.
+    pub fn load_build_info_dir(dir: &Path) -> HashMap<String, Self> {
+        let mut contracts = HashMap::new();
+        for path in foundry_common::fs::json_files(dir) {
+            let build_info: serde_json::Value = match foundry_common::fs::read_json_file(&path) {
+                Ok(build_info) => build_info,
+                Err(err) => {
+                    warn!(?path, %err, "failed to read build info");
+                    continue
+                }
+            };
+            if let Some(output) = build_info.get("output") {
+                contracts.extend(Self::from_standard_json_output(output));
+            }
+        }
+        contracts
+    }
+
+    /// Returns the debug information of either the creation or the runtime bytecode.
+    pub fn program(&self, creation: bool) -> Option<&ProgramDebugInfo> {
+        if creation {
+            self.creation.as_ref()
+        } else {
+            self.runtime.as_ref()
+        }
+    }
+}
+
+/// ethdebug information of a single bytecode program.
+#[derive(Clone, Debug, Default)]
+pub struct ProgramDebugInfo {
+    /// Variables in scope, keyed by instruction offset (program counter).
+    variables: HashMap<usize, Vec<Variable>>,
+}
+
+impl<'de> Deserialize<'de> for ProgramDebugInfo {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Program {
+            #[serde(default)]
+            instructions: Vec<Instruction>,
+        }
+
+        #[derive(Deserialize)]
+        struct Instruction {
+            offset: usize,
+            #[serde(default)]
+            context: Option<Context>,
+        }
+
+        let program = Program::deserialize(deserializer)?;
+        let variables = program
+            .instructions
+            .into_iter()
+            .filter_map(|instruction| {
+                let mut variables = Vec::new();
+                instruction.context?.collect_variables(&mut variables);
+                (!variables.is_empty()).then_some((instruction.offset, variables))
+            })
+            .collect();
+        Ok(Self { variables })
+    }
+}
+
+impl ProgramDebugInfo {
+    /// Parses the ethdebug program from its JSON representation.
+    pub fn from_json(s: &str) -> Result<Self> {
+        Ok(serde_json::from_str(s)?)
+    }
+
+    /// Returns the variables in scope at the given program counter.
+    pub fn variables_at(&self, pc: usize) -> &[Variable] {
+        self.variables.get(&pc).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Resolves the variables in scope at the given program counter to their values on the stack.
+    ///
+    /// `stack` is ordered bottom to top, as recorded in the debug steps.
+    pub fn resolve(&self, pc: usize, stack: &[U256]) -> Vec<ResolvedVariable<'_>> {
+        self.variables_at(pc)
+            .iter()
+            .filter_map(|variable| {
+                let Pointer::Stack { slot } = variable.pointer.as_ref()?;
+                let word = variable.stack_word(stack)?;
+                Some(ResolvedVariable {
+                    name: &variable.identifier,
+                    stack_index: *slot,
+                    value: variable
+                        .ty
+                        .as_ref()
+                        .map_or_else(|| format!("{word:#x}"), |ty| ty.format_word(word)),
+                })
+            })
+            .collect()
+    }
+}
+
+/// A context of an ethdebug instruction.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+enum Context {
+    Variables { variables: Vec<Variable> },
+    Gather { gather: Vec<Self> },
+    Other(#[allow(dead_code)] serde_json::Value),
+}
+
+impl Context {
+    fn collect_variables(self, out: &mut Vec<Variable>) {
+        match self {
+            Self::Variables { variables } => out.extend(variables),
+            Self::Gather { gather } => gather.into_iter().for_each(|c| c.collect_variables(out)),
+            Self::Other(_) => {}
+        }
+    }
+}
+
+/// A variable declared in an ethdebug context.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Variable {
+    /// The source-level name of the variable.
+    pub identifier: String,
+    /// The type of the variable, if known.
+    #[serde(rename = "type", default, deserialize_with = "deserialize_lenient")]
+    pub ty: Option<VariableType>,
+    /// Where the variable's value is located.
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub pointer: Option<Pointer>,
+}
+
+impl Variable {
+    /// Returns the value of the variable on the given stack, which is ordered bottom to top, or
+    /// `None` if the variable is not on the stack.
+    pub fn stack_word(&self, stack: &[U256]) -> Option<U256> {
+        let Pointer::Stack { slot } = self.pointer.as_ref()?;
+        stack.len().checked_sub(slot + 1).and_then(|i| stack.get(i)).copied()
+    }
+}
+
+/// The elementary type of a variable.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum VariableType {
+    Uint { bits: u16 },
+    Int { bits: u16 },
+    Bool,
+    Address,
+    Bytes { size: Option<u16> },
+}
+
+impl VariableType {
+    /// Formats a stack word as a value of this type.
+    pub fn format_word(&self, word: U256) -> String {
+        match self {
+            Self::Uint { .. } => word.to_string(),
+            Self::Int { bits } => {
+                let bits = (*bits as usize).clamp(8, 256);
+                let value = I256::from_raw(word);
+                // Sign-extend values narrower than a full word.
+                let shift = 256 - bits;
+                let value = (value << shift).asr(shift);
+                value.to_string()
+            }
+            Self::Bool => (!word.is_zero()).to_string(),
+            Self::Address => Address::from_word(word.into()).to_checksum(None),
+            Self::Bytes { size: Some(size) } if *size <= 32 => {
+                let bytes = word.to_be_bytes::<32>();
+                alloy_primitives::hex::encode_prefixed(&bytes[..*size as usize])
+            }
+            Self::Bytes { .. } => format!("{word:#x}"),
+        }
+    }
+}
+
+/// The location of a variable's value.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(tag = "location", rename_all = "lowercase")]
+pub enum Pointer {
+    /// Value stored on the stack, where slot `0` is the top of the stack.
+    Stack { slot: usize },
+}
+
+/// A variable resolved to its value at a specific step.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResolvedVariable<'a> {
+    /// The source-level name of the variable.
+    pub name: &'a str,
+    /// Position of the value on the stack, counted from the top.
+    pub stack_index: usize,
+    /// The decoded value.
+    pub value: String,
+}
+
+/// Deserializes unsupported values as `None` instead of failing.
+fn deserialize_lenient<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: serde::de::DeserializeOwned,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(serde_json::from_value(value).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAM: &str = r#"{
+        "instructions": [
+            { "offset": 0 },
+            {
+                "offset": 5,
+                "context": {
+                    "gather": [
+                        { "variables": [
+                            { "identifier": "amount", "type": { "kind": "uint", "bits": 256 }, "pointer": { "location": "stack", "slot": 0 } },
+                            { "identifier": "delta", "type": { "kind": "int", "bits": 8 }, "pointer": { "location": "stack", "slot": 1 } }
+                        ] },
+                        { "variables": [
+                            { "identifier": "owner", "type": { "kind": "address" }, "pointer": { "location": "stack", "slot": 2 } },
+                            { "identifier": "data", "type": { "kind": "string" }, "pointer": { "location": "memory", "offset": 128 } }
+                        ] },
+                        { "code": { "source": { "id": 0 } } }
+                    ]
+                }
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn resolves_stack_variables() {
+        let program = ProgramDebugInfo::from_json(PROGRAM).unwrap();
+        assert!(program.variables_at(0).is_empty());
+        assert_eq!(program.variables_at(5).len(), 4);
+
+        let owner = U256::from_be_slice(Address::repeat_byte(0x11).as_slice());
+        let stack = [owner, U256::from(0xff), U256::from(42)];
+        let resolved = program.resolve(5, &stack);
+        assert_eq!(
+            resolved,
+            vec![
+                ResolvedVariable { name: "amount", stack_index: 0, value: "42".into() },
+                ResolvedVariable { name: "delta", stack_index: 1, value: "-1".into() },
+                ResolvedVariable {
+                    name: "owner",
+                    stack_index: 2,
+                    value: Address::repeat_byte(0x11).to_checksum(None)
+                },
+            ]
+        );
+
+        // Variables pointing past the bottom of the stack are skipped.
+        assert_eq!(program.resolve(5, &stack[2..]).len(), 1);
+    }
+
+    #[test]
+    fn keys_standard_json_output_by_identifier() {
+        let program: serde_json::Value = serde_json::from_str(PROGRAM).unwrap();
+        let output = serde_json::json!({
+            "contracts": {
+                "src/A.sol": {
+                    "Token": { "evm": { "deployedBytecode": { "ethdebug": program } } },
+                    "Empty": { "evm": { "bytecode": { "object": "0x" } } }
+                },
+                "src/B.sol": {
+                    "Token": { "evm": { "bytecode": { "ethdebug": program } } }
+                }
+            }
+        });
+        let contracts = ContractDebugInfo::from_standard_json_output(&output);
+        assert_eq!(contracts.len(), 2);
+        assert!(contracts["src/A.sol:Token"].program(false).is_some());
+        assert!(contracts["src/A.sol:Token"].program(true).is_none());
+        assert!(contracts["src/B.sol:Token"].program(true).is_some());
+    }
+}
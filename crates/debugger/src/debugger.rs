@@ -1,18 +1,34 @@
 //! Debugger implementation.
 
-use crate::{tui::TUI, DebugNode, DebuggerBuilder, ExitReason};
-use alloy_primitives::map::AddressHashMap;
+use crate::{
+    debug_info::ContractDebugInfo, tui::TUI, DebugNode, DebuggerBuilder, DumpFormat, ExitReason,
+};
+use alloy_primitives::map::{AddressHashMap, HashMap};
 use eyre::Result;
 use foundry_common::evm::Breakpoints;
+use foundry_compilers::artifacts::StorageLayout;
+use foundry_config::DebuggerConfig;
 use foundry_evm_traces::debug::ContractSources;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub struct DebuggerContext {
     pub debug_arena: Vec<DebugNode>,
     pub identified_contracts: AddressHashMap<String>,
+    /// Artifact identifiers (`path:Name`) of the identified contracts.
+    pub contract_ids: AddressHashMap<String>,
+    /// Address labels, shown next to the addresses of calls.
+    pub labels: AddressHashMap<String>,
     /// Source map of contract sources
     pub contracts_sources: ContractSources,
     pub breakpoints: Breakpoints,
+    /// ethdebug information, keyed by artifact identifier.
+    pub debug_info: HashMap<String, ContractDebugInfo>,
+    /// Storage layouts, keyed by contract name.
+    pub storage_layouts: HashMap<String, StorageLayout>,
+    /// The TUI configuration.
+    pub config: DebuggerConfig,
+    /// The project root, which source paths are relative to.
+    pub root: PathBuf,
 }
 
 pub struct Debugger {
@@ -27,18 +43,31 @@ pub fn builder() -> DebuggerBuilder {
     }
 
     /// Creates a new debugger.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         debug_arena: Vec<DebugNode>,
         identified_contracts: AddressHashMap<String>,
+        contract_ids: AddressHashMap<String>,
+        labels: AddressHashMap<String>,
         contracts_sources: ContractSources,
         breakpoints: Breakpoints,
+        debug_info: HashMap<String, ContractDebugInfo>,
+        storage_layouts: HashMap<String, StorageLayout>,
+        config: DebuggerConfig,
+        root: PathBuf,
     ) -> Self {
         Self {
             context: DebuggerContext {
                 debug_arena,
                 identified_contracts,
+                contract_ids,
+                labels,
                 contracts_sources,
                 breakpoints,
+                debug_info,
+                storage_layouts,
+                config,
+                root,
             },
         }
     }
@@ -65,7 +94,12 @@ pub fn try_run_tui(&mut self) -> Result<ExitReason> {
 
     /// Dumps debugger data to file.
     pub fn dump_to_file(&mut self, path: &Path) -> Result<()> {
+        self.dump_to_file_as(path, DumpFormat::Debugger)
+    }
+
+    /// Dumps debugger data, or the execution trace, to file in the given format.
+    pub fn dump_to_file_as(&mut self, path: &Path, format: DumpFormat) -> Result<()> {
         eyre::ensure!(!self.context.debug_arena.is_empty(), "debug arena is empty");
-        crate::dump::dump(path, &self.context)
+        crate::dump::dump(path, &self.context, format)
     }
 }
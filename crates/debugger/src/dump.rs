@@ -1,21 +1,213 @@
-use crate::{debugger::DebuggerContext, DebugNode};
-use alloy_primitives::map::AddressMap;
-use foundry_common::fs::write_json_file;
+use crate::{debugger::DebuggerContext, tui::line_column, DebugNode};
+use alloy_primitives::{map::AddressMap, Address, Bytes, U256};
+use foundry_common::fs::{create_file, write_json_file};
 use foundry_compilers::{
     artifacts::sourcemap::{Jump, SourceElement},
     multi::MultiCompilerLanguage,
 };
-use foundry_evm_core::utils::PcIcMap;
+use foundry_evm_core::{buffer::get_buffer_accesses, utils::PcIcMap};
 use foundry_evm_traces::debug::{ArtifactData, ContractSources, SourceData};
+use revm::interpreter::OpCode;
 use serde::Serialize;
-use std::{collections::HashMap, path::Path};
+use std::{
+    collections::HashMap,
+    fmt,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// The format of a debugger dump.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DumpFormat {
+    /// The debugger data, which includes the sources and source maps of the contracts.
+    #[default]
+    Debugger,
+    /// The executed steps, as a JSON array.
+    Json,
+    /// The executed steps, as CSV.
+    Csv,
+}
+
+impl FromStr for DumpFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "debugger" => Ok(Self::Debugger),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            _ => Err(format!("unknown dump format `{s}`, expected `debugger`, `json` or `csv`")),
+        }
+    }
+}
+
+impl fmt::Display for DumpFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Debugger => "debugger",
+            Self::Json => "json",
+            Self::Csv => "csv",
+        })
+    }
+}
 
-/// Dumps debugger data to a JSON file.
-pub(crate) fn dump(path: &Path, context: &DebuggerContext) -> eyre::Result<()> {
-    write_json_file(path, &DebuggerDump::new(context))?;
+/// Dumps debugger data to a file in the given format.
+pub(crate) fn dump(path: &Path, context: &DebuggerContext, format: DumpFormat) -> eyre::Result<()> {
+    match format {
+        DumpFormat::Debugger => write_json_file(path, &DebuggerDump::new(context))?,
+        DumpFormat::Json => write_json_file(path, &trace_steps(context))?,
+        DumpFormat::Csv => {
+            let mut writer = BufWriter::new(create_file(path)?);
+            writeln!(writer, "{}", StepDump::CSV_HEADER)?;
+            for step in trace_steps(context) {
+                writeln!(writer, "{}", step.csv_row())?;
+            }
+            writer.flush()?;
+        }
+    }
     Ok(())
 }
 
+/// A step of the execution trace.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StepDump<'a> {
+    /// The index of the debug node in the arena.
+    call: usize,
+    depth: u64,
+    address: Address,
+    contract: Option<&'a str>,
+    pc: usize,
+    op: String,
+    gas_remaining: u64,
+    gas_cost: u64,
+    /// The stack before the step, with the top of the stack last.
+    stack: &'a [U256],
+    /// The memory written by the step, read from the next step of the same call.
+    memory_diff: Option<MemoryDiff>,
+    /// The storage slot written by the step.
+    storage_diff: Option<StorageDiff>,
+    source: Option<SourceLocation>,
+}
+
+#[derive(Serialize)]
+struct MemoryDiff {
+    offset: usize,
+    data: Bytes,
+}
+
+#[derive(Serialize)]
+struct StorageDiff {
+    slot: U256,
+    value: U256,
+}
+
+#[derive(Serialize)]
+struct SourceLocation {
+    file: PathBuf,
+    line: usize,
+    column: usize,
+}
+
+impl StepDump<'_> {
+    const CSV_HEADER: &'static str = "call,depth,address,contract,pc,op,gas_remaining,gas_cost,\
+                                      stack,memory_diff,storage_diff,source";
+
+    fn csv_row(&self) -> String {
+        let stack = self.stack.iter().map(|word| format!("{word:#x}")).collect::<Vec<_>>();
+        let fields = [
+            self.call.to_string(),
+            self.depth.to_string(),
+            self.address.to_string(),
+            self.contract.unwrap_or_default().to_string(),
+            self.pc.to_string(),
+            self.op.clone(),
+            self.gas_remaining.to_string(),
+            self.gas_cost.to_string(),
+            stack.join(" "),
+            self.memory_diff
+                .as_ref()
+                .map(|diff| format!("{:#x}:{}", diff.offset, diff.data))
+                .unwrap_or_default(),
+            self.storage_diff
+                .as_ref()
+                .map(|diff| format!("{:#x}={:#x}", diff.slot, diff.value))
+                .unwrap_or_default(),
+            self.source
+                .as_ref()
+                .map(|source| {
+                    format!("{}:{}:{}", source.file.display(), source.line, source.column)
+                })
+                .unwrap_or_default(),
+        ];
+        fields.iter().map(|field| csv_field(field)).collect::<Vec<_>>().join(",")
+    }
+}
+
+/// Quotes a CSV field if needed.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Returns all the steps of the debug arena, in execution order.
+fn trace_steps(context: &DebuggerContext) -> Vec<StepDump<'_>> {
+    let mut steps = Vec::new();
+    for (call, node) in context.debug_arena.iter().enumerate() {
+        let contract = context.identified_contracts.get(&node.address).map(String::as_str);
+        for (i, step) in node.steps.iter().enumerate() {
+            let stack = step.stack.as_deref().unwrap_or_default();
+            let memory_diff = get_buffer_accesses(step.op.get(), stack)
+                .and_then(|accesses| accesses.write)
+                .filter(|write| write.len > 0)
+                .map(|write| {
+                    let memory = node.steps.get(i + 1).and_then(|next| next.memory.as_ref());
+                    let data = memory
+                        .and_then(|memory| {
+                            memory.as_bytes().get(write.offset..write.offset + write.len)
+                        })
+                        .map(Bytes::copy_from_slice)
+                        .unwrap_or_default();
+                    MemoryDiff { offset: write.offset, data }
+                });
+            let storage_diff = match (step.op, stack) {
+                (OpCode::SSTORE, [.., value, slot]) => {
+                    Some(StorageDiff { slot: *slot, value: *value })
+                }
+                _ => None,
+            };
+            let source = contract.and_then(|name| {
+                let (element, source) = context.contracts_sources.find_source_mapping(
+                    name,
+                    step.pc as u32,
+                    node.kind.is_any_create(),
+                )?;
+                let (line, column) = line_column(&source.source, element.offset() as usize);
+                Some(SourceLocation { file: source.path.clone(), line, column })
+            });
+            steps.push(StepDump {
+                call,
+                depth: step.depth,
+                address: node.address,
+                contract,
+                pc: step.pc,
+                op: step.op.to_string(),
+                gas_remaining: step.gas_remaining,
+                gas_cost: step.gas_cost,
+                stack,
+                memory_diff,
+                storage_diff,
+                source,
+            });
+        }
+    }
+    steps
+}
+
 /// Holds info of debugger dump.
 #[derive(Serialize)]
 struct DebuggerDump<'a> {
@@ -146,3 +338,17 @@ fn new(v: &'a ArtifactData) -> Self {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_fields() {
+        assert_eq!(csv_field("0x1 0x2"), "0x1 0x2");
+        assert_eq!(csv_field("src/A,B.sol:1:2"), "\"src/A,B.sol:1:2\"");
+        assert_eq!(csv_field("a\"b"), "\"a\"\"b\"");
+        assert_eq!("csv".parse::<DumpFormat>(), Ok(DumpFormat::Csv));
+        assert!("yaml".parse::<DumpFormat>().is_err());
+    }
+}
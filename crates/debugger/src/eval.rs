@@ -0,0 +1,844 @@
+//! Evaluation of watch expressions.
+//!
+//! Watch expressions are a small subset of Solidity expressions: identifiers, member accesses,
+//! index accesses and literals, e.g. `balances[msg.sender]` or `config.owner`. Identifiers are
+//! resolved to the stack variables described by the ethdebug information, then to the state
+//! variables of the contract's storage layout. String literals, e.g. `names["alice"]`, can be used
+//! as `string`, `bytes` and `bytesN` mapping keys. The builtins `msg.sender`, `this`, `stack[i]`,
+//! `memory[offset]`, `calldata[offset]` and `storage[slot]` are always available.
+//!
+//! Storage is not part of the debug steps, so state variables are read from the values that were
+//! loaded or stored by the steps executed so far against the same storage, which is the caller's
+//! for delegate calls.
+
+use crate::{
+    debug_info::{Variable, VariableType},
+    DebugNode,
+};
+use alloy_primitives::{keccak256, map::HashMap, U256};
+use foundry_compilers::artifacts::{Storage, StorageLayout, StorageType};
+use revm::interpreter::OpCode;
+use std::{fmt, str::FromStr};
+
+/// A parsed watch expression.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Expr {
+    /// An identifier, e.g. `balances`.
+    Ident(String),
+    /// A number, boolean or address literal.
+    Literal(U256),
+    /// A string literal, e.g. `"alice"`.
+    Str(String),
+    /// A member access, e.g. `config.owner`.
+    Member(Box<Self>, String),
+    /// An index access, e.g. `balances[msg.sender]`.
+    Index(Box<Self>, Box<Self>),
+}
+
+impl FromStr for Expr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { s, pos: 0 };
+        let expr = parser.expr()?;
+        parser.skip_whitespace();
+        if parser.pos < s.len() {
+            return Err(format!("unexpected `{}`", &s[parser.pos..]))
+        }
+        Ok(expr)
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ident(name) => f.write_str(name),
+            Self::Literal(value) => write!(f, "{value}"),
+            Self::Str(s) => write!(f, "{s:?}"),
+            Self::Member(base, member) => write!(f, "{base}.{member}"),
+            Self::Index(base, index) => write!(f, "{base}[{index}]"),
+        }
+    }
+}
+
+struct Parser<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn expr(&mut self) -> Result<Expr, String> {
+        let mut expr = self.primary()?;
+        loop {
+            self.skip_whitespace();
+            if self.eat('.') {
+                self.skip_whitespace();
+                let member = self.word();
+                if member.is_empty() || !is_ident(member) {
+                    return Err(format!("expected a member name after `{expr}.`"))
+                }
+                expr = Expr::Member(Box::new(expr), member.to_string());
+            } else if self.eat('[') {
+                let index = self.expr()?;
+                self.skip_whitespace();
+                if !self.eat(']') {
+                    return Err(format!("expected `]` after `{expr}[{index}`"))
+                }
+                expr = Expr::Index(Box::new(expr), Box::new(index));
+            } else {
+                return Ok(expr)
+            }
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        self.skip_whitespace();
+        if self.eat('(') {
+            let expr = self.expr()?;
+            self.skip_whitespace();
+            if !self.eat(')') {
+                return Err(format!("expected `)` after `({expr}`"))
+            }
+            return Ok(expr)
+        }
+        if self.eat('"') {
+            let rest = &self.s[self.pos..];
+            let len = rest.find('"').ok_or("unterminated string literal")?;
+            self.pos += len + 1;
+            return Ok(Expr::Str(rest[..len].to_string()))
+        }
+        let word = self.word();
+        match word {
+            "" => Err(match self.s[self.pos..].chars().next() {
+                Some(c) => format!("unexpected `{c}`"),
+                None => "expected an expression".to_string(),
+            }),
+            "true" => Ok(Expr::Literal(U256::from(1))),
+            "false" => Ok(Expr::Literal(U256::ZERO)),
+            _ if word.starts_with(|c: char| c.is_ascii_digit()) => {
+                let digits = word.replace('_', "");
+                U256::from_str(&digits)
+                    .map(Expr::Literal)
+                    .map_err(|e| format!("invalid number `{word}`: {e}"))
+            }
+            _ if is_ident(word) => Ok(Expr::Ident(word.to_string())),
+            _ => Err(format!("invalid identifier `{word}`")),
+        }
+    }
+
+    /// Consumes the next identifier or number.
+    fn word(&mut self) -> &str {
+        let rest = &self.s[self.pos..];
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '$'))
+            .unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let found = self.s[self.pos..].starts_with(c);
+        if found {
+            self.pos += c.len_utf8();
+        }
+        found
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.s[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+}
+
+fn is_ident(word: &str) -> bool {
+    !word.starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// The builtin namespaces, which are shadowed by variables of the same name.
+const BUILTINS: [&str; 5] = ["msg", "stack", "memory", "calldata", "storage"];
+
+/// The state an expression is evaluated against.
+pub(crate) struct EvalContext<'a> {
+    /// The call being executed.
+    pub(crate) node: &'a DebugNode,
+    /// The index of the current step in the call.
+    pub(crate) step: usize,
+    /// The ethdebug variables in scope at the current step.
+    pub(crate) variables: &'a [Variable],
+    /// The storage layout of the contract being executed, if known.
+    pub(crate) layout: Option<&'a StorageLayout>,
+    /// The storage values observed so far, see [`observed_storage`].
+    pub(crate) storage: &'a HashMap<U256, U256>,
+}
+
+/// An intermediate evaluation result.
+enum Value<'a> {
+    /// A value with an optional elementary type.
+    Word(U256, Option<VariableType>),
+    /// The bytes of a string literal.
+    Bytes(Vec<u8>),
+    /// A storage location holding a value of the given storage layout type.
+    Storage { slot: U256, offset: usize, ty: &'a str },
+    /// One of the builtin namespaces, which can only be accessed further.
+    Builtin(&'static str),
+}
+
+impl EvalContext<'_> {
+    /// Evaluates an expression and formats its value.
+    pub(crate) fn eval(&self, expr: &Expr) -> Result<String, String> {
+        match self.value(expr)? {
+            Value::Word(word, ty) => Ok(format_word(word, ty.as_ref())),
+            Value::Bytes(bytes) => Ok(format!("{:?}", String::from_utf8_lossy(&bytes))),
+            Value::Storage { slot, offset, ty } => self.format_storage(slot, offset, ty, 1),
+            Value::Builtin(name) => Err(format!("`{name}` must be accessed further")),
+        }
+    }
+
+    /// Evaluates an expression to its value as it would be on the stack.
+    pub(crate) fn eval_word(&self, expr: &Expr) -> Result<U256, String> {
+        match self.value(expr)? {
+            Value::Word(word, _) => Ok(word),
+            Value::Storage { slot, offset, ty } => Ok(self.load(slot, offset, ty)?.0),
+            Value::Bytes(_) => Err(format!("`{expr}` is not a word")),
+            Value::Builtin(name) => Err(format!("`{name}` is not a value")),
+        }
+    }
+
+    /// Decodes all the state variables of the storage layout, returning their names, types and
+    /// values.
+    pub(crate) fn state_variables(&self) -> Vec<(&str, &str, Result<String, String>)> {
+        let Some(layout) = self.layout else { return Vec::new() };
+        layout
+            .storage
+            .iter()
+            .map(|storage| {
+                let ty = self.type_id(&storage.storage_type);
+                let label = self.storage_type(ty).map_or("?", |ty| ty.label.as_str());
+                let value = parse_slot(&storage.slot)
+                    .and_then(|slot| self.format_storage(slot, storage.offset as usize, ty, 1));
+                (storage.label.as_str(), label, value)
+            })
+            .collect()
+    }
+
+    fn value(&self, expr: &Expr) -> Result<Value<'_>, String> {
+        match expr {
+            Expr::Literal(value) => Ok(Value::Word(*value, None)),
+            Expr::Str(s) => Ok(Value::Bytes(s.as_bytes().to_vec())),
+            Expr::Ident(name) => self.ident(name),
+            Expr::Member(base, member) => match (self.value(base)?, member.as_str()) {
+                (Value::Builtin("msg"), "sender") => Ok(Value::Word(
+                    self.node.caller.into_word().into(),
+                    Some(VariableType::Address),
+                )),
+                (Value::Storage { slot, ty, .. }, _) => {
+                    let members = self.storage_type(ty)?.other.get("members").ok_or_else(|| {
+                        format!("`{base}` is not a struct and has no member `{member}`")
+                    })?;
+                    let members: Vec<Storage> = serde_json::from_value(members.clone())
+                        .map_err(|e| format!("invalid members of `{ty}`: {e}"))?;
+                    let field = members
+                        .iter()
+                        .find(|field| field.label == *member)
+                        .ok_or_else(|| format!("`{base}` has no member `{member}`"))?;
+                    Ok(Value::Storage {
+                        slot: slot + parse_slot(&field.slot)?,
+                        offset: field.offset as usize,
+                        ty: self.type_id(&field.storage_type),
+                    })
+                }
+                _ => Err(format!("`{base}` has no member `{member}`")),
+            },
+            Expr::Index(base, index) => {
+                let base_value = self.value(base)?;
+                if let Value::Storage { slot, ty, .. } = base_value {
+                    return self.index_storage(base, slot, ty, index)
+                }
+                let index = self.eval_word(index)?;
+                match base_value {
+                    Value::Builtin("stack") => {
+                        let stack = self.step().stack.as_deref().unwrap_or_default();
+                        let i = usize::try_from(index).ok().filter(|i| *i < stack.len());
+                        let i = i.ok_or_else(|| format!("stack has {} items", stack.len()))?;
+                        Ok(Value::Word(stack[stack.len() - 1 - i], None))
+                    }
+                    Value::Builtin("memory") => {
+                        let memory =
+                            self.step().memory.as_ref().map_or(&[][..], |m| &m.as_bytes()[..]);
+                        Ok(Value::Word(read_word(memory, index), None))
+                    }
+                    Value::Builtin("calldata") => {
+                        Ok(Value::Word(read_word(&self.node.calldata, index), None))
+                    }
+                    Value::Builtin("storage") => {
+                        Ok(Value::Storage { slot: index, offset: 0, ty: "" })
+                    }
+                    _ => Err(format!("`{base}` can't be indexed")),
+                }
+            }
+        }
+    }
+
+    fn ident(&self, name: &str) -> Result<Value<'_>, String> {
+        // Local variables shadow state variables.
+        if let Some(variable) = self.variables.iter().rev().find(|v| v.identifier == name) {
+            let stack = self.step().stack.as_deref().unwrap_or_default();
+            let word = variable
+                .stack_word(stack)
+                .ok_or_else(|| format!("`{name}` is not on the stack"))?;
+            return Ok(Value::Word(word, variable.ty.clone()))
+        }
+        if let Some(storage) = self.layout.and_then(|l| l.storage.iter().find(|s| s.label == name))
+        {
+            return Ok(Value::Storage {
+                slot: parse_slot(&storage.slot)?,
+                offset: storage.offset as usize,
+                ty: self.type_id(&storage.storage_type),
+            })
+        }
+        if let Some(builtin) = BUILTINS.iter().find(|builtin| **builtin == name) {
+            return Ok(Value::Builtin(builtin))
+        }
+        match name {
+            "this" => Ok(Value::Word(
+                self.node.storage_address.into_word().into(),
+                Some(VariableType::Address),
+            )),
+            _ if self.layout.is_none() => Err(format!(
+                "`{name}` is not in scope; state variables require the `storageLayout` extra output"
+            )),
+            _ => Err(format!("`{name}` is not in scope")),
+        }
+    }
+
+    fn index_storage(
+        &self,
+        base: &Expr,
+        slot: U256,
+        ty: &str,
+        index: &Expr,
+    ) -> Result<Value<'_>, String> {
+        let storage_type = self.storage_type(ty)?;
+        let element = |key: &str| {
+            storage_type
+                .other
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(|ty| self.type_id(ty))
+                .ok_or_else(|| format!("`{base}` can't be indexed"))
+        };
+        match storage_type.encoding.as_str() {
+            "mapping" => {
+                let value = storage_type.value.as_deref().map(|ty| self.type_id(ty));
+                let value = value.ok_or_else(|| format!("`{base}` has no value type"))?;
+                let key = storage_type.key.as_deref().map(|ty| self.type_id(ty));
+                let key = key.ok_or_else(|| format!("`{base}` has no key type"))?;
+                let key = self.mapping_key(index, key)?;
+                Ok(Value::Storage { slot: mapping_slot(&key, slot), offset: 0, ty: value })
+            }
+            "inplace" => {
+                let element = element("base")?;
+                let index = self.eval_word(index)?;
+                let length = storage_type
+                    .label
+                    .rsplit_once('[')
+                    .and_then(|(_, len)| len.strip_suffix(']')?.parse::<U256>().ok())
+                    .ok_or_else(|| format!("`{base}` can't be indexed"))?;
+                if index >= length {
+                    return Err(format!("index {index} out of bounds for length {length}"))
+                }
+                let (slot, offset) = self.array_element(slot, element, index)?;
+                Ok(Value::Storage { slot, offset, ty: element })
+            }
+            "dynamic_array" => {
+                let element = element("base")?;
+                let index = self.eval_word(index)?;
+                let length = self.load(slot, 0, "")?.0;
+                if index >= length {
+                    return Err(format!("index {index} out of bounds for length {length}"))
+                }
+                let data = U256::from_be_bytes(keccak256(slot.to_be_bytes::<32>()).0);
+                let (slot, offset) = self.array_element(data, element, index)?;
+                Ok(Value::Storage { slot, offset, ty: element })
+            }
+            _ => Err(format!("`{base}` can't be indexed")),
+        }
+    }
+
+    /// Encodes a mapping key of the given type as it is hashed with the mapping's slot: value
+    /// types are padded to a full word, while `string` and `bytes` keys are hashed as is.
+    fn mapping_key(&self, key: &Expr, ty: &str) -> Result<Vec<u8>, String> {
+        let key_type = self.storage_type(ty)?;
+        let mismatch = || format!("`{key}` can't be used as a `{}` key", key_type.label);
+        let value = self.value(key)?;
+        if key_type.encoding == "bytes" {
+            return match value {
+                Value::Bytes(bytes) => Ok(bytes),
+                Value::Storage { slot, ty, .. }
+                    if self.storage_type(ty).is_ok_and(|ty| ty.encoding == "bytes") =>
+                {
+                    self.read_bytes(slot)
+                }
+                _ => Err(mismatch()),
+            }
+        }
+        let bytes_size = match elementary_type(&key_type.label) {
+            Some(VariableType::Bytes { size: Some(size) }) => Some(size as usize),
+            _ => None,
+        };
+        let word = match (value, bytes_size) {
+            // Literals are converted to `bytesN` keys left-aligned, as in Solidity.
+            (Value::Bytes(bytes), Some(size)) if bytes.len() <= size => {
+                let mut word = [0u8; 32];
+                word[..bytes.len()].copy_from_slice(&bytes);
+                U256::from_be_bytes(word)
+            }
+            (Value::Word(word, None), Some(size)) if matches!(key, Expr::Literal(_)) => {
+                if word.byte_len() > size {
+                    return Err(mismatch())
+                }
+                word << ((32 - size) * 8)
+            }
+            (Value::Bytes(_) | Value::Builtin(_), _) => return Err(mismatch()),
+            _ => self.eval_word(key)?,
+        };
+        Ok(word.to_be_bytes::<32>().to_vec())
+    }
+
+    /// Reads the `string` or `bytes` value stored at the given slot.
+    fn read_bytes(&self, slot: U256) -> Result<Vec<u8>, String> {
+        let word = self.load(slot, 0, "")?.0;
+        if !word.bit(0) {
+            let bytes = word.to_be_bytes::<32>();
+            return Ok(bytes[..(bytes[31] / 2) as usize].to_vec())
+        }
+        let length = usize::try_from((word - U256::from(1)) / U256::from(2))
+            .map_err(|_| format!("invalid length at slot {slot:#x}"))?;
+        let data = U256::from_be_bytes(keccak256(slot.to_be_bytes::<32>()).0);
+        let mut bytes = Vec::new();
+        for i in 0..length.div_ceil(32) {
+            let word = self.load(data + U256::from(i), 0, "")?.0;
+            bytes.extend_from_slice(&word.to_be_bytes::<32>());
+        }
+        bytes.truncate(length);
+        Ok(bytes)
+    }
+
+    /// Returns the slot and offset of an array element, packing elements of at most 16 bytes.
+    fn array_element(
+        &self,
+        data: U256,
+        element: &str,
+        index: U256,
+    ) -> Result<(U256, usize), String> {
+        let size = self.size_of(element)?;
+        if size <= 16 {
+            let per_slot = U256::from(32 / size);
+            let offset = (index % per_slot).to::<usize>() * size;
+            Ok((data + index / per_slot, offset))
+        } else {
+            Ok((data + index * U256::from(size.div_ceil(32)), 0))
+        }
+    }
+
+    /// Loads the value of the given type from storage, returning it as it would be on the stack.
+    fn load(
+        &self,
+        slot: U256,
+        offset: usize,
+        ty: &str,
+    ) -> Result<(U256, Option<VariableType>), String> {
+        let word =
+            *self.storage.get(&slot).ok_or_else(|| format!("slot {slot:#x} not loaded yet"))?;
+        if ty.is_empty() {
+            return Ok((word, None))
+        }
+        let storage_type = self.storage_type(ty)?;
+        let size = self.size_of(ty)?.min(32);
+        let mut value = word >> (offset * 8);
+        if size < 32 {
+            value &= (U256::from(1) << (size * 8)) - U256::from(1);
+        }
+        let ty = elementary_type(&storage_type.label);
+        match ty {
+            // `bytesN` values are left-aligned on the stack.
+            Some(VariableType::Bytes { .. }) => value <<= (32 - size) * 8,
+            // Signed values are sign-extended on the stack.
+            Some(VariableType::Int { .. }) if size < 32 && value.bit(size * 8 - 1) => {
+                value |= U256::MAX << (size * 8);
+            }
+            _ => {}
+        }
+        Ok((value, ty))
+    }
+
+    fn format_storage(
+        &self,
+        slot: U256,
+        offset: usize,
+        ty: &str,
+        depth: usize,
+    ) -> Result<String, String> {
+        if ty.is_empty() {
+            return self.load(slot, offset, ty).map(|(word, _)| format!("{word:#x}"))
+        }
+        let storage_type = self.storage_type(ty)?;
+        if let Some(members) = storage_type.other.get("members") {
+            if depth > 2 {
+                return Ok(format!("{} {{ .. }}", storage_type.label))
+            }
+            let members: Vec<Storage> = serde_json::from_value(members.clone())
+                .map_err(|e| format!("invalid members of `{ty}`: {e}"))?;
+            let fields = members
+                .iter()
+                .map(|field| {
+                    let slot = slot + parse_slot(&field.slot)?;
+                    let ty = self.type_id(&field.storage_type);
+                    let value = self
+                        .format_storage(slot, field.offset as usize, ty, depth + 1)
+                        .unwrap_or_else(|e| format!("<{e}>"));
+                    Ok(format!("{}: {value}", field.label))
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            return Ok(format!("{{ {} }}", fields.join(", ")))
+        }
+        match storage_type.encoding.as_str() {
+            "mapping" => Ok(format!("{} at slot {slot:#x}", storage_type.label)),
+            "dynamic_array" => {
+                let length = self.load(slot, 0, "")?.0;
+                Ok(format!("{} of length {length}", storage_type.label))
+            }
+            "bytes" => {
+                let word = self.load(slot, 0, "")?.0;
+                let bytes = word.to_be_bytes::<32>();
+                if word.bit(0) {
+                    let length = (word - U256::from(1)) / U256::from(2);
+                    return Ok(format!("{} of length {length}", storage_type.label))
+                }
+                let data = &bytes[..(bytes[31] / 2) as usize];
+                match std::str::from_utf8(data) {
+                    Ok(s) if storage_type.label == "string" => Ok(format!("{s:?}")),
+                    _ => Ok(alloy_primitives::hex::encode_prefixed(data)),
+                }
+            }
+            "inplace" if storage_type.other.contains_key("base") => {
+                Ok(format!("{} at slot {slot:#x}", storage_type.label))
+            }
+            _ => {
+                let (word, ty) = self.load(slot, offset, ty)?;
+                Ok(format_word(word, ty.as_ref()))
+            }
+        }
+    }
+
+    fn step(&self) -> &revm_inspectors::tracing::types::CallTraceStep {
+        &self.node.steps[self.step]
+    }
+
+    fn storage_type(&self, ty: &str) -> Result<&StorageType, String> {
+        self.layout
+            .and_then(|layout| layout.types.get(ty))
+            .ok_or_else(|| format!("unknown storage type `{ty}`"))
+    }
+
+    /// Returns the identifier of a storage type as borrowed from the layout, or an empty
+    /// identifier, which is read as a raw word, if the type is unknown.
+    fn type_id<'a>(&'a self, ty: &str) -> &'a str {
+        self.layout.and_then(|layout| layout.types.get_key_value(ty)).map_or("", |(k, _)| k)
+    }
+
+    fn size_of(&self, ty: &str) -> Result<usize, String> {
+        let storage_type = self.storage_type(ty)?;
+        storage_type
+            .number_of_bytes
+            .parse::<usize>()
+            .ok()
+            .filter(|size| *size > 0)
+            .ok_or_else(|| format!("invalid size of `{}`", storage_type.label))
+    }
+}
+
+/// Returns the storage values of the call's storage address observed by the steps executed up to
+/// the given step: the values loaded by `SLOAD`s and stored by `SSTORE`s, later ones taking
+/// precedence.
+pub(crate) fn observed_storage(
+    arena: &[DebugNode],
+    call_index: usize,
+    step: usize,
+) -> HashMap<U256, U256> {
+    let address = arena[call_index].storage_address;
+    let mut storage = HashMap::default();
+    for (i, node) in arena[..=call_index].iter().enumerate() {
+        if node.storage_address != address {
+            continue
+        }
+        let last = if i == call_index { step } else { node.steps.len().saturating_sub(1) };
+        for (j, current) in node.steps.iter().enumerate().take(last + 1) {
+            let stack = current.stack.as_deref().unwrap_or_default();
+            match current.op {
+                // The loaded value is on top of the stack of the next step.
+                OpCode::SLOAD => {
+                    let value = node.steps.get(j + 1).and_then(|next| next.stack.as_ref()?.last());
+                    if let (Some(&slot), Some(&value)) = (stack.last(), value) {
+                        storage.insert(slot, value);
+                    }
+                }
+                // The current step hasn't been executed yet.
+                OpCode::SSTORE if i != call_index || j < step => {
+                    if let [.., value, slot] = stack {
+                        storage.insert(*slot, *value);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    storage
+}
+
+/// Returns the storage slot of the value of the encoded `key` in the mapping at `slot`.
+fn mapping_slot(key: &[u8], slot: U256) -> U256 {
+    let mut preimage = key.to_vec();
+    preimage.extend_from_slice(&slot.to_be_bytes::<32>());
+    U256::from_be_bytes(keccak256(preimage).0)
+}
+
+/// Reads a 32-byte word from the given buffer, padding it with zeros past the end.
+fn read_word(buf: &[u8], offset: U256) -> U256 {
+    let mut word = [0u8; 32];
+    if let Ok(offset) = usize::try_from(offset) {
+        let bytes = buf.get(offset..).unwrap_or_default();
+        let len = bytes.len().min(32);
+        word[..len].copy_from_slice(&bytes[..len]);
+    }
+    U256::from_be_bytes(word)
+}
+
+fn parse_slot(slot: &str) -> Result<U256, String> {
+    U256::from_str(slot).map_err(|e| format!("invalid slot `{slot}`: {e}"))
+}
+
+/// Returns the elementary type of a storage layout type label, e.g. `uint128`.
+fn elementary_type(label: &str) -> Option<VariableType> {
+    let parse_bits = |s: &str| if s.is_empty() { Some(256) } else { s.parse().ok() };
+    if let Some(bits) = label.strip_prefix("uint") {
+        Some(VariableType::Uint { bits: parse_bits(bits)? })
+    } else if let Some(bits) = label.strip_prefix("int") {
+        Some(VariableType::Int { bits: parse_bits(bits)? })
+    } else if let Some(size) = label.strip_prefix("bytes") {
+        Some(VariableType::Bytes { size: Some(size.parse().ok()?) })
+    } else if label == "bool" {
+        Some(VariableType::Bool)
+    } else if label.starts_with("address") || label.starts_with("contract ") {
+        Some(VariableType::Address)
+    } else if label.starts_with("enum ") {
+        Some(VariableType::Uint { bits: 8 })
+    } else {
+        None
+    }
+}
+
+fn format_word(word: U256, ty: Option<&VariableType>) -> String {
+    ty.map_or_else(|| format!("{word:#x}"), |ty| ty.format_word(word))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{b256, Address};
+    use revm::interpreter::InstructionResult;
+    use revm_inspectors::tracing::types::CallTraceStep;
+
+    const LAYOUT: &str = r#"{
+        "storage": [
+            { "astId": 1, "contract": "C", "label": "owner", "offset": 0, "slot": "0",
+              "type": "t_address" },
+            { "astId": 2, "contract": "C", "label": "paused", "offset": 20, "slot": "0",
+              "type": "t_bool" },
+            { "astId": 3, "contract": "C", "label": "balances", "offset": 0, "slot": "1",
+              "type": "t_map" },
+            { "astId": 4, "contract": "C", "label": "config", "offset": 0, "slot": "2",
+              "type": "t_config" },
+            { "astId": 7, "contract": "C", "label": "ids", "offset": 0, "slot": "4",
+              "type": "t_map_string" },
+            { "astId": 8, "contract": "C", "label": "sigs", "offset": 0, "slot": "5",
+              "type": "t_map_bytes4" },
+            { "astId": 9, "contract": "C", "label": "name", "offset": 0, "slot": "6",
+              "type": "t_string" }
+        ],
+        "types": {
+            "t_address": { "encoding": "inplace", "label": "address", "numberOfBytes": "20" },
+            "t_bool": { "encoding": "inplace", "label": "bool", "numberOfBytes": "1" },
+            "t_int8": { "encoding": "inplace", "label": "int8", "numberOfBytes": "1" },
+            "t_uint256": { "encoding": "inplace", "label": "uint256", "numberOfBytes": "32" },
+            "t_bytes4": { "encoding": "inplace", "label": "bytes4", "numberOfBytes": "4" },
+            "t_string": { "encoding": "bytes", "label": "string", "numberOfBytes": "32" },
+            "t_map": {
+                "encoding": "mapping", "key": "t_address", "label": "mapping(address => uint256)",
+                "numberOfBytes": "32", "value": "t_uint256"
+            },
+            "t_map_string": {
+                "encoding": "mapping", "key": "t_string", "label": "mapping(string => uint256)",
+                "numberOfBytes": "32", "value": "t_uint256"
+            },
+            "t_map_bytes4": {
+                "encoding": "mapping", "key": "t_bytes4", "label": "mapping(bytes4 => uint256)",
+                "numberOfBytes": "32", "value": "t_uint256"
+            },
+            "t_config": {
+                "encoding": "inplace", "label": "struct C.Config", "numberOfBytes": "64",
+                "members": [
+                    { "astId": 5, "contract": "C", "label": "fee", "offset": 0, "slot": "0",
+                      "type": "t_int8" },
+                    { "astId": 6, "contract": "C", "label": "limit", "offset": 0, "slot": "1",
+                      "type": "t_uint256" }
+                ]
+            }
+        }
+    }"#;
+
+    #[test]
+    fn parse_expressions() {
+        let expr: Expr = "balances[msg.sender]".parse().unwrap();
+        assert_eq!(
+            expr,
+            Expr::Index(
+                Box::new(Expr::Ident("balances".into())),
+                Box::new(Expr::Member(Box::new(Expr::Ident("msg".into())), "sender".into())),
+            )
+        );
+        assert_eq!(expr.to_string(), "balances[msg.sender]");
+        assert_eq!(" config . limit ".parse::<Expr>().unwrap().to_string(), "config.limit");
+        assert_eq!("stack[0x1]".parse::<Expr>().unwrap().to_string(), "stack[1]");
+        assert_eq!("(x)[1_000]".parse::<Expr>().unwrap().to_string(), "x[1000]");
+        assert_eq!(
+            r#"ids[ "alice" ]"#.parse::<Expr>().unwrap(),
+            Expr::Index(Box::new(Expr::Ident("ids".into())), Box::new(Expr::Str("alice".into())))
+        );
+        assert!(r#"ids["alice]"#.parse::<Expr>().is_err());
+        assert!("balances[".parse::<Expr>().is_err());
+        assert!("a.1".parse::<Expr>().is_err());
+        assert!("a b".parse::<Expr>().is_err());
+        assert!("".parse::<Expr>().is_err());
+    }
+
+    #[test]
+    fn eval_storage_variables() {
+        let layout: StorageLayout = serde_json::from_str(LAYOUT).unwrap();
+        let caller = Address::repeat_byte(0x11);
+        let node = DebugNode { caller, ..Default::default() };
+
+        let mut storage = HashMap::default();
+        let owner = U256::from_be_bytes(Address::repeat_byte(0x22).into_word().0);
+        storage.insert(U256::ZERO, owner | (U256::from(1) << 160));
+        let balance_slot =
+            b256!("0xcc69885fda6bcc1a4ace058b4a62bf5e179ea78fd58a1ccd71c22cc9b688792f");
+        let word = |value: u64| U256::from(value).to_be_bytes::<32>();
+        let left_aligned = |bytes: &[u8]| {
+            let mut word = [0u8; 32];
+            word[..bytes.len()].copy_from_slice(bytes);
+            word
+        };
+        assert_eq!(mapping_slot(&word(1), U256::from(1)), U256::from_be_bytes(balance_slot.0));
+        storage.insert(mapping_slot(&caller.into_word().0, U256::from(1)), U256::from(42));
+        storage.insert(U256::from(2), U256::from(0xff));
+        // `string` keys are hashed unpadded, `bytesN` keys are left-aligned.
+        storage.insert(mapping_slot(b"alice", U256::from(4)), U256::from(7));
+        let sig = left_aligned(&[0x12, 0x34, 0x56, 0x78]);
+        storage.insert(mapping_slot(&sig, U256::from(5)), U256::from(8));
+        // Short strings are stored with their length times two in the last byte.
+        let mut name = left_aligned(b"alice");
+        name[31] = 5 * 2;
+        storage.insert(U256::from(6), U256::from_be_bytes(name));
+
+        let ctx = EvalContext {
+            node: &node,
+            step: 0,
+            variables: &[],
+            layout: Some(&layout),
+            storage: &storage,
+        };
+        let eval = |s: &str| ctx.eval(&s.parse().unwrap());
+        assert_eq!(eval("owner").unwrap(), Address::repeat_byte(0x22).to_checksum(None));
+        assert_eq!(eval("paused").unwrap(), "true");
+        assert_eq!(eval("balances[msg.sender]").unwrap(), "42");
+        assert_eq!(eval("config.fee").unwrap(), "-1");
+        assert_eq!(eval("config").unwrap(), "{ fee: -1, limit: <slot 0x3 not loaded yet> }");
+        assert_eq!(eval("storage[2]").unwrap(), "0xff");
+        assert_eq!(
+            eval("balances[0]").unwrap_err(),
+            format!("slot {:#x} not loaded yet", mapping_slot(&word(0), U256::from(1)))
+        );
+        assert_eq!(eval(r#"ids["alice"]"#).unwrap(), "7");
+        assert_eq!(eval("ids[name]").unwrap(), "7");
+        assert_eq!(eval("sigs[0x12345678]").unwrap(), "8");
+        assert_eq!(
+            eval(r#"sigs["abcd"]"#).unwrap_err(),
+            format!(
+                "slot {:#x} not loaded yet",
+                mapping_slot(&left_aligned(b"abcd"), U256::from(5))
+            )
+        );
+        assert!(eval("ids[1]").is_err());
+        assert!(eval("sigs[0x1234567890]").is_err());
+        assert!(eval("unknown").is_err());
+        assert!(eval("owner.x").is_err());
+
+        let variables = ctx.state_variables();
+        assert_eq!(variables.len(), 7);
+        assert_eq!(variables[1], ("paused", "bool", Ok("true".to_string())));
+        assert_eq!(variables[2].2.as_deref(), Ok("mapping(address => uint256) at slot 0x1"));
+    }
+
+    fn step(op: OpCode, stack: &[u64]) -> CallTraceStep {
+        CallTraceStep {
+            depth: 0,
+            pc: 0,
+            code_section_idx: 0,
+            op,
+            contract: Address::ZERO,
+            stack: Some(stack.iter().map(|value| U256::from(*value)).collect()),
+            push_stack: None,
+            memory: None,
+            returndata: Default::default(),
+            gas_remaining: 0,
+            gas_refund_counter: 0,
+            gas_used: 0,
+            gas_cost: 0,
+            storage_change: None,
+            status: InstructionResult::Continue,
+            immediate_bytes: None,
+            decoded: None,
+        }
+    }
+
+    #[test]
+    fn observed_storage_of_delegate_calls() {
+        let (proxy, logic) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let node = |address, storage_address, steps| DebugNode {
+            address,
+            storage_address,
+            steps,
+            ..Default::default()
+        };
+        let arena = [
+            node(
+                proxy,
+                proxy,
+                vec![step(OpCode::SSTORE, &[1, 0]), step(OpCode::DELEGATECALL, &[])],
+            ),
+            // Delegated from the proxy, so it writes to the proxy's storage.
+            node(logic, proxy, vec![step(OpCode::SSTORE, &[2, 1]), step(OpCode::STOP, &[])]),
+            // Called directly, so it has its own storage.
+            node(logic, logic, vec![step(OpCode::SSTORE, &[3, 2])]),
+        ];
+
+        let storage = observed_storage(&arena, 1, 1);
+        assert_eq!(storage.len(), 2);
+        assert_eq!(storage.get(&U256::ZERO), Some(&U256::from(1)));
+        assert_eq!(storage.get(&U256::from(1)), Some(&U256::from(2)));
+
+        // The pending `SSTORE` isn't observed yet.
+        assert!(observed_storage(&arena, 2, 0).is_empty());
+    }
+}
@@ -13,9 +13,12 @@
 
 mod op;
 
+mod breakpoints;
 mod builder;
+mod debug_info;
 mod debugger;
 mod dump;
+mod eval;
 mod tui;
 
 mod node;
@@ -23,5 +26,7 @@
 pub use node::DebugNode;
 
 pub use builder::DebuggerBuilder;
+pub use debug_info::{ContractDebugInfo, ProgramDebugInfo};
 pub use debugger::Debugger;
+pub use dump::DumpFormat;
 pub use tui::{ExitReason, TUI};
@@ -1,6 +1,6 @@
 use alloy_primitives::{Address, Bytes};
 use foundry_evm_traces::{CallKind, CallTraceArena};
-use revm_inspectors::tracing::types::{CallTraceStep, TraceMemberOrder};
+use revm_inspectors::tracing::types::{CallTraceNode, CallTraceStep, TraceMemberOrder};
 use serde::{Deserialize, Serialize};
 
 /// Represents a part of the execution frame before the next call or end of the execution.
@@ -10,6 +10,12 @@ pub struct DebugNode {
     ///
     /// Note that this is the address of the *code*, not necessarily the address of the storage.
     pub address: Address,
+    /// The address whose storage the call executes against, i.e. `address(this)`.
+    ///
+    /// This differs from [`address`](Self::address) for delegate calls.
+    pub storage_address: Address,
+    /// The caller of the call, i.e. `msg.sender`.
+    pub caller: Address,
     /// The kind of call this is.
     pub kind: CallKind,
     /// Calldata of the call.
@@ -22,11 +28,13 @@ impl DebugNode {
     /// Creates a new debug node.
     pub fn new(
         address: Address,
+        storage_address: Address,
+        caller: Address,
         kind: CallKind,
         steps: Vec<CallTraceStep>,
         calldata: Bytes,
     ) -> Self {
-        Self { address, kind, steps, calldata }
+        Self { address, storage_address, caller, kind, steps, calldata }
     }
 }
 
@@ -78,8 +86,22 @@ fn inner(arena: &CallTraceArena, node_idx: usize, out: &mut Vec<PendingNode>) {
 
         let call = &arena_nodes[pending.node_idx].trace;
         let calldata = if call.kind.is_any_create() { Bytes::new() } else { call.data.clone() };
-        let node = DebugNode::new(call.address, call.kind, steps, calldata);
+        // Delegate calls execute against the storage of their caller.
+        let storage_address = if call.kind.is_delegate() { call.caller } else { call.address };
+        let caller = msg_sender(&arena_nodes, pending.node_idx);
+        let node =
+            DebugNode::new(call.address, storage_address, caller, call.kind, steps, calldata);
 
         out.push(node);
     }
 }
+
+/// Returns the `msg.sender` of the call at the given index, which delegate calls inherit from
+/// their parent.
+fn msg_sender(nodes: &[CallTraceNode], mut idx: usize) -> Address {
+    while nodes[idx].trace.kind == CallKind::DelegateCall {
+        let Some(parent) = nodes[idx].parent else { break };
+        idx = parent;
+    }
+    nodes[idx].trace.caller
+}
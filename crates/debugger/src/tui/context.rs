@@ -1,12 +1,23 @@
 //! Debugger context and event handler implementation.
 
-use crate::{debugger::DebuggerContext, DebugNode, ExitReason};
+use crate::{
+    breakpoints::{Breakpoint, Location},
+    debugger::DebuggerContext,
+    eval::{observed_storage, EvalContext, Expr},
+    DebugNode, ExitReason, ProgramDebugInfo,
+};
 use alloy_primitives::{hex, Address};
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+use foundry_compilers::artifacts::StorageLayout;
+use foundry_config::{DebuggerConfig, DebuggerPane};
 use foundry_evm_core::buffer::BufferKind;
 use revm::interpreter::OpCode;
 use revm_inspectors::tracing::types::{CallKind, CallTraceStep};
-use std::ops::ControlFlow;
+use std::{
+    ops::ControlFlow,
+    path::Path,
+    process::{Command, Stdio},
+};
 
 /// This is currently used to remember last scroll position so screen doesn't wiggle as much.
 #[derive(Default)]
@@ -33,10 +44,23 @@ pub(crate) struct TUIContext<'a> {
     pub(crate) show_shortcuts: bool,
     /// The currently active buffer (memory, calldata, returndata) to be drawn.
     pub(crate) active_buffer: BufferKind,
+    /// The layout of the panes, initialized from the config and updated with keybindings.
+    pub(crate) layout: DebuggerConfig,
+    /// A message about the last action, shown in the source pane until the next key press.
+    pub(crate) status: Option<String>,
+    /// The watch expressions, evaluated at each step.
+    pub(crate) watches: Vec<Expr>,
+    /// The watch expression being typed, if any.
+    pub(crate) watch_input: Option<String>,
+    /// The breakpoints set with `x`, see [`crate::breakpoints`].
+    pub(crate) breakpoints: Vec<Breakpoint>,
+    /// The breakpoint being typed, if any.
+    pub(crate) breakpoint_input: Option<String>,
 }
 
 impl<'a> TUIContext<'a> {
     pub(crate) fn new(debugger_context: &'a mut DebuggerContext) -> Self {
+        let layout = debugger_context.config.clone();
         TUIContext {
             debugger_context,
 
@@ -50,6 +74,12 @@ pub(crate) fn new(debugger_context: &'a mut DebuggerContext) -> Self {
             buf_utf: false,
             show_shortcuts: true,
             active_buffer: BufferKind::Memory,
+            layout,
+            status: None,
+            watches: Vec::new(),
+            watch_input: None,
+            breakpoints: Vec::new(),
+            breakpoint_input: None,
         }
     }
 
@@ -85,6 +115,120 @@ pub(crate) fn current_step(&self) -> &CallTraceStep {
         &self.debug_steps()[self.current_step]
     }
 
+    /// Returns the ethdebug information of the current call's bytecode, if any.
+    pub(crate) fn program_debug_info(&self) -> Option<&ProgramDebugInfo> {
+        let id = self.debugger_context.contract_ids.get(self.address())?;
+        self.debugger_context.debug_info.get(id)?.program(self.call_kind().is_any_create())
+    }
+
+    /// Returns the storage layout of the current contract, if known.
+    pub(crate) fn storage_layout(&self) -> Option<&StorageLayout> {
+        let name = self.debugger_context.identified_contracts.get(self.address())?;
+        self.debugger_context.storage_layouts.get(name)
+    }
+
+    /// Decodes the state variables of the current contract at the current step, returning their
+    /// names, types and values.
+    pub(crate) fn state_variables(&self) -> Vec<(String, String, Result<String, String>)> {
+        self.with_eval_context(self.draw_memory.inner_call_index, self.current_step, |ctx| {
+            ctx.state_variables()
+                .into_iter()
+                .map(|(name, ty, value)| (name.to_string(), ty.to_string(), value))
+                .collect()
+        })
+    }
+
+    /// Evaluates the watch expressions at the current step.
+    pub(crate) fn eval_watches(&self) -> Vec<Result<String, String>> {
+        if self.watches.is_empty() {
+            return Vec::new()
+        }
+        self.with_eval_context(self.draw_memory.inner_call_index, self.current_step, |ctx| {
+            self.watches.iter().map(|expr| ctx.eval(expr)).collect()
+        })
+    }
+
+    /// Calls `f` with the context to evaluate expressions against at the given step.
+    fn with_eval_context<R>(
+        &self,
+        call_index: usize,
+        step: usize,
+        f: impl FnOnce(&EvalContext<'_>) -> R,
+    ) -> R {
+        let node = &self.debug_arena()[call_index];
+        let storage = observed_storage(self.debug_arena(), call_index, step);
+        let name = self.debugger_context.identified_contracts.get(&node.address);
+        let variables = self
+            .debugger_context
+            .contract_ids
+            .get(&node.address)
+            .and_then(|id| {
+                self.debugger_context.debug_info.get(id)?.program(node.kind.is_any_create())
+            })
+            .map(|program| program.variables_at(node.steps[step].pc))
+            .unwrap_or_default();
+        let layout = name.and_then(|name| self.debugger_context.storage_layouts.get(name));
+        f(&EvalContext { node, step, variables, layout, storage: &storage })
+    }
+
+    /// Returns the index of the first breakpoint hit at the given step, which is preceded by
+    /// the step at `prev`, if any.
+    pub(crate) fn breakpoint_hit(
+        &self,
+        call_index: usize,
+        step: usize,
+        prev: Option<(usize, usize)>,
+    ) -> Option<usize> {
+        let arena = self.debug_arena();
+        self.breakpoints.iter().position(|breakpoint| {
+            let at_location = |(i, j): (usize, usize)| {
+                let (node, step) = (&arena[i], &arena[i].steps[j]);
+                breakpoint.location.matches(step.op, step.pc, step.depth, || {
+                    let name = self.debugger_context.identified_contracts.get(&node.address)?;
+                    let (element, source) = self
+                        .debugger_context
+                        .contracts_sources
+                        .find_source_mapping(name, step.pc as u32, node.kind.is_any_create())?;
+                    Some((source.path.as_path(), source.source.as_str(), element.offset() as usize))
+                })
+            };
+            at_location((call_index, step)) &&
+                !prev.is_some_and(at_location) &&
+                breakpoint.condition.as_ref().is_none_or(|condition| {
+                    self.with_eval_context(call_index, step, |ctx| condition.holds(ctx))
+                })
+        })
+    }
+
+    /// Returns the call index and step of the closest breakpoint hit after, or before, the
+    /// current step, and the index of the breakpoint.
+    fn find_breakpoint_hit(&self, forward: bool) -> Option<(usize, usize, usize)> {
+        let position = (self.draw_memory.inner_call_index, self.current_step);
+        let steps = self
+            .debug_arena()
+            .iter()
+            .enumerate()
+            .flat_map(|(i, node)| (0..node.steps.len()).map(move |j| (i, j)));
+        let mut prev = None;
+        let mut last_hit = None;
+        for (i, j) in steps {
+            let prev = prev.replace((i, j));
+            if forward && (i, j) <= position {
+                continue
+            }
+            if !forward && (i, j) >= position {
+                break
+            }
+            if let Some(breakpoint) = self.breakpoint_hit(i, j, prev) {
+                if forward {
+                    return Some((i, j, breakpoint))
+                }
+                last_hit = Some((i, j, breakpoint));
+            }
+        }
+        last_hit
+    }
+
     fn gen_opcode_list(&mut self) {
         self.opcode_list.clear();
         let debug_steps =
@@ -101,6 +245,20 @@ fn gen_opcode_list_if_necessary(&mut self) {
         }
     }
 
+    /// Returns `true` if the given pane is not hidden, and has something to show.
+    pub(crate) fn is_visible(&self, pane: DebuggerPane) -> bool {
+        !self.layout.hide.contains(&pane) &&
+            (pane != DebuggerPane::Storage || self.storage_layout().is_some())
+    }
+
+    fn toggle_pane(&mut self, pane: DebuggerPane) {
+        if let Some(i) = self.layout.hide.iter().position(|hidden| *hidden == pane) {
+            self.layout.hide.remove(i);
+        } else {
+            self.layout.hide.push(pane);
+        }
+    }
+
     fn active_buffer(&self) -> &[u8] {
         match self.active_buffer {
             BufferKind::Memory => self.current_step().memory.as_ref().unwrap().as_bytes(),
@@ -123,6 +281,18 @@ pub(crate) fn handle_event(&mut self, event: Event) -> ControlFlow<ExitReason> {
     }
 
     fn handle_key_event(&mut self, event: KeyEvent) -> ControlFlow<ExitReason> {
+        // Watch expression input
+        if self.watch_input.is_some() {
+            self.handle_watch_input(event);
+            return ControlFlow::Continue(());
+        }
+
+        // Breakpoint input
+        if self.breakpoint_input.is_some() {
+            self.handle_breakpoint_input(event);
+            return ControlFlow::Continue(());
+        }
+
         // Breakpoints
         if let KeyCode::Char(c) = event.code {
             if c.is_alphabetic() && self.key_buffer.starts_with('\'') {
@@ -132,6 +302,7 @@ fn handle_key_event(&mut self, event: KeyEvent) -> ControlFlow<ExitReason> {
         }
 
         let control = event.modifiers.contains(KeyModifiers::CONTROL);
+        self.status = None;
 
         match event.code {
             // Exit
@@ -202,6 +373,38 @@ fn handle_key_event(&mut self, event: KeyEvent) -> ControlFlow<ExitReason> {
                 }
             }
 
+            // Go to the previous call boundary
+            KeyCode::Char('[') => self.repeat(|this| {
+                if let Some((call_index, step)) = this.find_step(false, is_call_boundary) {
+                    this.draw_memory.inner_call_index = call_index;
+                    this.current_step = step;
+                }
+            }),
+
+            // Go to the next call boundary
+            KeyCode::Char(']') => self.repeat(|this| {
+                if let Some((call_index, step)) = this.find_step(true, is_call_boundary) {
+                    this.draw_memory.inner_call_index = call_index;
+                    this.current_step = step;
+                }
+            }),
+
+            // Go to the previous revert
+            KeyCode::Char('r') => self.repeat(|this| {
+                if let Some((call_index, step)) = this.find_step(false, |step, _| is_revert(step)) {
+                    this.draw_memory.inner_call_index = call_index;
+                    this.current_step = step;
+                }
+            }),
+
+            // Go to the next revert
+            KeyCode::Char('R') => self.repeat(|this| {
+                if let Some((call_index, step)) = this.find_step(true, |step, _| is_revert(step)) {
+                    this.draw_memory.inner_call_index = call_index;
+                    this.current_step = step;
+                }
+            }),
+
             // Step forward
             KeyCode::Char('s') => self.repeat(|this| {
                 let remaining_steps = &this.debug_steps()[this.current_step..];
@@ -231,6 +434,17 @@ fn handle_key_event(&mut self, event: KeyEvent) -> ControlFlow<ExitReason> {
                     .unwrap_or_default();
             }),
 
+            // Step back to the previous jump, or call boundary, across calls
+            KeyCode::Char('p') => self.repeat(|this| {
+                let is_step = |step: &CallTraceStep, prev: Option<&CallTraceStep>| {
+                    is_call_boundary(step, prev) || prev.is_some_and(|prev| is_jump(step, prev))
+                };
+                if let Some((call_index, step)) = this.find_step(false, is_step) {
+                    this.draw_memory.inner_call_index = call_index;
+                    this.current_step = step;
+                }
+            }),
+
             // Toggle stack labels
             KeyCode::Char('t') => self.stack_labels = !self.stack_labels,
 
@@ -240,6 +454,93 @@ fn handle_key_event(&mut self, event: KeyEvent) -> ControlFlow<ExitReason> {
             // Toggle help notice
             KeyCode::Char('h') => self.show_shortcuts = !self.show_shortcuts,
 
+            // Cycle layouts
+            KeyCode::Char('L') => self.layout.layout = self.layout.layout.next(),
+
+            // Toggle panes
+            KeyCode::Char('O') => self.toggle_pane(DebuggerPane::Op),
+            KeyCode::Char('S') => self.toggle_pane(DebuggerPane::Stack),
+            KeyCode::Char('B') => self.toggle_pane(DebuggerPane::Buffer),
+            KeyCode::Char('V') => self.toggle_pane(DebuggerPane::Src),
+            KeyCode::Char('T') => self.toggle_pane(DebuggerPane::Storage),
+
+            // Resize the columns of the horizontal layout
+            KeyCode::Char('<') => {
+                self.layout.horizontal_split =
+                    self.layout.horizontal_split.saturating_sub(5).max(10)
+            }
+            KeyCode::Char('>') => {
+                self.layout.horizontal_split = (self.layout.horizontal_split + 5).min(90)
+            }
+
+            // Open the current source location in the editor
+            KeyCode::Char('e') => {
+                self.status = Some(self.open_in_editor().unwrap_or_else(|e| e));
+            }
+
+            // Jump to the definition of the function called at the current source location
+            KeyCode::Char('d') => match self.find_definition_step() {
+                Ok((call_index, step)) => {
+                    self.draw_memory.inner_call_index = call_index;
+                    self.current_step = step;
+                }
+                Err(e) => self.status = Some(e),
+            },
+
+            // Add a watch expression
+            KeyCode::Char('w') => self.watch_input = Some(String::new()),
+
+            // Remove the watch expression at the index in the key buffer, or the last one
+            KeyCode::Char('W') => {
+                let index = match self.key_buffer.parse::<usize>() {
+                    Ok(n) => n.checked_sub(1),
+                    Err(_) => self.watches.len().checked_sub(1),
+                };
+                match index.filter(|&i| i < self.watches.len()) {
+                    Some(i) => {
+                        self.watches.remove(i);
+                    }
+                    None => self.status = Some("No watch expression to remove".into()),
+                }
+            }
+
+            // Add a breakpoint
+            KeyCode::Char('x') => self.breakpoint_input = Some(String::new()),
+
+            // Remove the breakpoint at the index in the key buffer, or the last one
+            KeyCode::Char('X') => {
+                let index = match self.key_buffer.parse::<usize>() {
+                    Ok(n) => n.checked_sub(1),
+                    Err(_) => self.breakpoints.len().checked_sub(1),
+                };
+                match index.filter(|&i| i < self.breakpoints.len()) {
+                    Some(i) => {
+                        self.breakpoints.remove(i);
+                    }
+                    None => self.status = Some("No breakpoint to remove".into()),
+                }
+            }
+
+            // Go to the next, or previous, breakpoint hit
+            KeyCode::Char(c @ ('n' | 'N')) => {
+                if self.breakpoints.is_empty() {
+                    self.status = Some("No breakpoints, add one with [x]".into());
+                } else {
+                    self.repeat(|this| match this.find_breakpoint_hit(c == 'n') {
+                        Some((call_index, step, breakpoint)) => {
+                            this.draw_memory.inner_call_index = call_index;
+                            this.current_step = step;
+                            this.status = Some(format!(
+                                "Breakpoint {}: {}",
+                                breakpoint + 1,
+                                this.breakpoints[breakpoint]
+                            ));
+                        }
+                        None => this.status = Some("No more breakpoint hits".into()),
+                    });
+                }
+            }
+
             // Numbers for repeating commands or breakpoints
             KeyCode::Char(
                 other @ ('0' | '1' | '2' | '3' | '4' | '5' | '6' | '7' | '8' | '9' | '\''),
@@ -257,6 +558,53 @@ fn handle_key_event(&mut self, event: KeyEvent) -> ControlFlow<ExitReason> {
         ControlFlow::Continue(())
     }
 
+    fn handle_watch_input(&mut self, event: KeyEvent) {
+        let Some(input) = &mut self.watch_input else { return };
+        match event.code {
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Esc => self.watch_input = None,
+            KeyCode::Enter => match input.parse::<Expr>() {
+                Ok(expr) => {
+                    self.watches.push(expr);
+                    self.watch_input = None;
+                    self.status = None;
+                }
+                Err(e) => self.status = Some(format!("Invalid watch expression: {e}")),
+            },
+            _ => {}
+        }
+    }
+
+    fn handle_breakpoint_input(&mut self, event: KeyEvent) {
+        let Some(input) = &mut self.breakpoint_input else { return };
+        match event.code {
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Esc => self.breakpoint_input = None,
+            KeyCode::Enter => match input.parse::<Breakpoint>() {
+                Ok(mut breakpoint) => {
+                    // Lines without a file refer to the current source file.
+                    if let Location::Line { file: file @ None, .. } = &mut breakpoint.location {
+                        *file = self
+                            .src_map()
+                            .ok()
+                            .map(|(_, source)| source.path.to_string_lossy().into_owned());
+                    }
+                    self.breakpoints.push(breakpoint);
+                    self.breakpoint_input = None;
+                    self.status = None;
+                }
+                Err(e) => self.status = Some(format!("Invalid breakpoint: {e}")),
+            },
+            _ => {}
+        }
+    }
+
     fn handle_breakpoint(&mut self, c: char) {
         // Find the location of the called breakpoint in the whole debug arena (at this address with
         // this pc)
@@ -302,6 +650,125 @@ fn step(&mut self) {
         }
     }
 
+    /// Opens the current source location with the `debugger.editor` command, returning a message
+    /// for the status line.
+    fn open_in_editor(&self) -> Result<String, String> {
+        let Some(template) = &self.layout.editor else {
+            return Err("Set `debugger.editor` to open sources, e.g. `code -g {file}:{line}`".into())
+        };
+        let (element, source) = self.src_map()?;
+        let (line, column) = line_column(&source.source, element.offset() as usize);
+        let file = self.debugger_context.root.join(&source.path);
+        let args = template
+            .split_whitespace()
+            .map(|arg| {
+                arg.replace("{file}", &file.to_string_lossy())
+                    .replace("{line}", &line.to_string())
+                    .replace("{column}", &column.to_string())
+            })
+            .collect::<Vec<_>>();
+        let Some((program, args)) = args.split_first() else {
+            return Err("`debugger.editor` is empty".into())
+        };
+
+        // The editor runs in the background, as the terminal is used by the debugger.
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Failed to run `{program}`: {e}"))?;
+        std::thread::spawn(move || child.wait());
+
+        Ok(format!("Opened {}:{line}:{column}", source.path.display()))
+    }
+
+    /// Returns the call index and step of the next step executing the definition of a function
+    /// called at the current source location.
+    fn find_definition_step(&self) -> Result<(usize, usize), String> {
+        let (element, source) = self.src_map()?;
+        let start = element.offset() as usize;
+        let code = source.source.get(start..start + element.length() as usize).unwrap_or_default();
+        let called = called_functions(code);
+        if called.is_empty() {
+            return Err("No function call at the current location".into())
+        }
+
+        let sources = &self.debugger_context.contracts_sources;
+        let definitions = sources
+            .sources_by_id
+            .values()
+            .flat_map(|files| files.values())
+            .flat_map(|source| {
+                called.iter().flat_map(move |&name| {
+                    source
+                        .find_function_definitions(name)
+                        .map(move |range| (source.path.as_path(), range))
+                })
+            })
+            .collect::<Vec<_>>();
+        if definitions.is_empty() {
+            return Err(format!("No definition found for `{}`", called.join("`, `")))
+        }
+        let is_definition = |path: &Path, offset: usize| {
+            definitions.iter().any(|(p, range)| *p == path && range.contains(&offset))
+        };
+
+        // Stop at the first step entering a definition, so that recursive calls are followed.
+        let mut was_inside = is_definition(&source.path, start);
+        let call_index = self.draw_memory.inner_call_index;
+        for (i, node) in self.debug_arena().iter().enumerate().skip(call_index) {
+            let Some(name) = self.debugger_context.identified_contracts.get(&node.address) else {
+                was_inside = false;
+                continue
+            };
+            let first_step = if i == call_index { self.current_step + 1 } else { 0 };
+            if i != call_index {
+                was_inside = false;
+            }
+            for (j, step) in node.steps.iter().enumerate().skip(first_step) {
+                let Some((element, source)) =
+                    sources.find_source_mapping(name, step.pc as u32, node.kind.is_any_create())
+                else {
+                    continue
+                };
+                let inside = is_definition(&source.path, element.offset() as usize);
+                if inside && !was_inside {
+                    return Ok((i, j))
+                }
+                was_inside = inside;
+            }
+        }
+        Err(format!("`{}` is not called after the current step", called.join("`, `")))
+    }
+
+    /// Returns the call index and step of the closest step after, or before, the current one that
+    /// matches the predicate, which is also given the step executed right before it, if any.
+    fn find_step(
+        &self,
+        forward: bool,
+        f: impl Fn(&CallTraceStep, Option<&CallTraceStep>) -> bool,
+    ) -> Option<(usize, usize)> {
+        let position = (self.draw_memory.inner_call_index, self.current_step);
+        let steps = self
+            .debug_arena()
+            .iter()
+            .enumerate()
+            .flat_map(|(i, node)| node.steps.iter().enumerate().map(move |(j, step)| (i, j, step)))
+            .collect::<Vec<_>>();
+        let is_match = |k: &usize| {
+            let (i, j, step) = steps[*k];
+            let prev = k.checked_sub(1).map(|k| steps[k].2);
+            let after = (i, j) > position;
+            let before = (i, j) < position;
+            (if forward { after } else { before }) && f(step, prev)
+        };
+        let mut indices = 0..steps.len();
+        let found = if forward { indices.find(is_match) } else { indices.rev().find(is_match) };
+        found.map(|k| (steps[k].0, steps[k].1))
+    }
+
     /// Calls a closure `f` the number of times specified in the key buffer, and at least once.
     fn repeat(&mut self, mut f: impl FnMut(&mut Self)) {
         for _ in 0..buffer_as_number(&self.key_buffer) {
@@ -321,6 +788,33 @@ fn buffer_as_number(s: &str) -> usize {
     s.parse().unwrap_or(MIN).clamp(MIN, MAX)
 }
 
+/// Returns the 1-based line and column of the given byte offset in the source code.
+pub(crate) fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = source.get(..offset).unwrap_or(source);
+    let line = before.matches('\n').count() + 1;
+    let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+    (line, column)
+}
+
+/// Returns the names of the functions called in the given source code, in order of appearance.
+fn called_functions(code: &str) -> Vec<&str> {
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$';
+    let mut names = Vec::new();
+    let mut rest = code;
+    while let Some(start) = rest.find(is_ident) {
+        let len = rest[start..].find(|c: char| !is_ident(c)).unwrap_or(rest.len() - start);
+        let name = &rest[start..start + len];
+        rest = &rest[start + len..];
+        if rest.trim_start().starts_with('(') &&
+            !name.starts_with(|c: char| c.is_ascii_digit()) &&
+            !names.contains(&name)
+        {
+            names.push(name);
+        }
+    }
+    names
+}
+
 fn pretty_opcode(step: &CallTraceStep) -> String {
     if let Some(immediate) = step.immediate_bytes.as_ref().filter(|b| !b.is_empty()) {
         format!("{}(0x{})", step.op, hex::encode(immediate))
@@ -329,6 +823,16 @@ fn pretty_opcode(step: &CallTraceStep) -> String {
     }
 }
 
+/// Returns `true` if the step reverts the call.
+fn is_revert(step: &CallTraceStep) -> bool {
+    matches!(step.op, OpCode::REVERT | OpCode::INVALID)
+}
+
+/// Returns `true` if the step is the first one executed after entering, or returning from, a call.
+fn is_call_boundary(step: &CallTraceStep, prev: Option<&CallTraceStep>) -> bool {
+    prev.is_some_and(|prev| prev.depth != step.depth)
+}
+
 fn is_jump(step: &CallTraceStep, prev: &CallTraceStep) -> bool {
     if !matches!(
         prev.op,
@@ -352,3 +856,105 @@ fn is_jump(step: &CallTraceStep, prev: &CallTraceStep) -> bool {
         step.code_section_idx != prev.code_section_idx
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DebugNode;
+    use revm::interpreter::InstructionResult;
+
+    #[test]
+    fn source_locations() {
+        let source = "contract A {\n    function f() {}\n}\n";
+        assert_eq!(line_column(source, 0), (1, 1));
+        assert_eq!(line_column(source, source.find("function").unwrap()), (2, 5));
+
+        assert_eq!(called_functions("counter.setNumber(add(x, 1))"), ["setNumber", "add"]);
+        assert_eq!(called_functions("require (f(), \"f\"); f();"), ["require", "f"]);
+        assert_eq!(called_functions("uint256 x = 1;"), Vec::<&str>::new());
+    }
+
+    fn step(depth: u64, pc: usize, op: OpCode) -> CallTraceStep {
+        CallTraceStep {
+            depth,
+            pc,
+            code_section_idx: 0,
+            op,
+            contract: Address::ZERO,
+            stack: Some(Vec::new()),
+            push_stack: None,
+            memory: None,
+            returndata: Default::default(),
+            gas_remaining: 0,
+            gas_refund_counter: 0,
+            gas_used: 0,
+            gas_cost: 0,
+            storage_change: None,
+            status: InstructionResult::Continue,
+            immediate_bytes: None,
+            decoded: None,
+        }
+    }
+
+    fn debugger_context() -> DebuggerContext {
+        let node = |steps| DebugNode { steps, ..Default::default() };
+        DebuggerContext {
+            debug_arena: vec![
+                node(vec![
+                    step(1, 0, OpCode::PUSH1),
+                    step(1, 2, OpCode::JUMP),
+                    step(1, 10, OpCode::JUMPDEST),
+                    step(1, 11, OpCode::CALL),
+                ]),
+                node(vec![step(2, 0, OpCode::PUSH0), step(2, 1, OpCode::REVERT)]),
+                node(vec![step(1, 12, OpCode::POP), step(1, 13, OpCode::STOP)]),
+            ],
+            identified_contracts: Default::default(),
+            contract_ids: Default::default(),
+            labels: Default::default(),
+            contracts_sources: Default::default(),
+            breakpoints: Default::default(),
+            debug_info: Default::default(),
+            storage_layouts: Default::default(),
+            config: Default::default(),
+            root: Default::default(),
+        }
+    }
+
+    /// Presses the keys in order, returning the call index and step after each of them.
+    fn press(context: &mut TUIContext<'_>, keys: &str) -> Vec<(usize, usize)> {
+        keys.chars()
+            .map(|c| {
+                let event = KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+                let _ = context.handle_event(Event::Key(event));
+                (context.draw_memory.inner_call_index, context.current_step)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn navigate_call_boundaries() {
+        let mut debugger_context = debugger_context();
+        let mut context = TUIContext::new(&mut debugger_context);
+        context.init();
+
+        assert_eq!(press(&mut context, "]]]"), [(1, 0), (2, 0), (2, 0)]);
+        // The start of the execution is not a boundary.
+        assert_eq!(press(&mut context, "[["), [(1, 0), (1, 0)]);
+        assert_eq!(press(&mut context, "G2["), [(2, 1), (2, 1), (1, 0)]);
+    }
+
+    #[test]
+    fn step_back_across_calls() {
+        let mut debugger_context = debugger_context();
+        let mut context = TUIContext::new(&mut debugger_context);
+        context.init();
+
+        // Back to the return from the call, its start, and the jump destination before it.
+        assert_eq!(press(&mut context, "Gppp"), [(2, 1), (2, 0), (1, 0), (0, 2)]);
+        assert_eq!(press(&mut context, "p"), [(0, 2)]);
+        // Unlike stepping back to the previous jump, which stays in the current call.
+        assert_eq!(press(&mut context, "Raa"), [(1, 1), (1, 0), (1, 0)]);
+        assert_eq!(press(&mut context, "p"), [(0, 2)]);
+    }
+}
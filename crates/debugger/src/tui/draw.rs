@@ -3,6 +3,7 @@
 use super::context::TUIContext;
 use crate::op::OpcodeParam;
 use foundry_compilers::artifacts::sourcemap::SourceElement;
+use foundry_config::{DebuggerLayout, DebuggerPane};
 use foundry_evm_core::buffer::{get_buffer_accesses, BufferKind};
 use foundry_evm_traces::debug::SourceData;
 use ratatui::{
@@ -25,16 +26,25 @@ pub(crate) fn draw(&self, terminal: &mut super::DebuggerTerminal) -> io::Result<
     fn draw_layout(&self, f: &mut Frame<'_>) {
         // We need 100 columns to display a 32 byte word in the memory and stack panes.
         let area = f.area();
-        let min_width = 100;
+        let min_width =
+            if self.is_visible(DebuggerPane::Stack) || self.is_visible(DebuggerPane::Buffer) {
+                100
+            } else {
+                50
+            };
         let min_height = 16;
         if area.width < min_width || area.height < min_height {
             self.size_too_small(f, min_width, min_height);
             return;
         }
 
-        // The horizontal layout draws these panes at 50% width.
-        let min_column_width_for_horizontal = 200;
-        if area.width >= min_column_width_for_horizontal {
+        let horizontal = match self.layout.layout {
+            // The horizontal layout draws the stack and buffer panes at 50% width by default.
+            DebuggerLayout::Auto => area.width >= self.layout.horizontal_min_width,
+            DebuggerLayout::Horizontal => true,
+            DebuggerLayout::Vertical => false,
+        };
+        if horizontal {
             self.horizontal_layout(f);
         } else {
             self.vertical_layout(f);
@@ -78,14 +88,75 @@ fn size_too_small(&self, f: &mut Frame<'_>, min_width: u16, min_height: u16) {
     /// |-----------------------------|
     /// |             buf             |
     /// |-----------------------------|
+    /// |           storage           |
+    /// |-----------------------------|
     /// |                             |
     /// |             src             |
     /// |                             |
     /// |-----------------------------|
     /// ```
     fn vertical_layout(&self, f: &mut Frame<'_>) {
+        let app = self.draw_app_and_footer(f);
+        self.draw_column(
+            f,
+            app,
+            &[
+                (DebuggerPane::Op, 1),
+                (DebuggerPane::Stack, 1),
+                (DebuggerPane::Buffer, 1),
+                (DebuggerPane::Storage, 1),
+                (DebuggerPane::Src, 3),
+            ],
+        );
+    }
+
+    /// Draws the layout in horizontal mode.
+    ///
+    /// ```text
+    /// |-----------------|-----------|
+    /// |        op       |   stack   |
+    /// |-----------------|-----------|
+    /// |                 |    buf    |
+    /// |       src       |-----------|
+    /// |                 |  storage  |
+    /// |-----------------|-----------|
+    /// ```
+    fn horizontal_layout(&self, f: &mut Frame<'_>) {
+        let app = self.draw_app_and_footer(f);
+
+        let left = [(DebuggerPane::Op, 1), (DebuggerPane::Src, 3)];
+        let right =
+            [(DebuggerPane::Stack, 1), (DebuggerPane::Buffer, 3), (DebuggerPane::Storage, 2)];
+
+        // If all the panes of a column are hidden, the other column takes the whole width.
+        let left_visible = left.iter().any(|&(pane, _)| self.is_visible(pane));
+        let right_visible = right.iter().any(|&(pane, _)| self.is_visible(pane));
+        let split = match (left_visible, right_visible) {
+            (true, true) => self.layout.horizontal_split.min(100) as u32,
+            (true, false) => 100,
+            (false, _) => 0,
+        };
+
+        // Split app in 2 horizontally.
+        let [app_left, app_right] = Layout::new(
+            Direction::Horizontal,
+            [Constraint::Ratio(split, 100), Constraint::Ratio(100 - split, 100)],
+        )
+        .split(app)[..] else {
+            unreachable!()
+        };
+
+        // Split left pane vertically to opcode list and source.
+        self.draw_column(f, app_left, &left);
+        // Split right pane vertically to construct stack, memory and storage.
+        self.draw_column(f, app_right, &right);
+    }
+
+    /// Splits off the footer from the terminal area and draws it, returning the area left for
+    /// the panes.
+    fn draw_app_and_footer(&self, f: &mut Frame<'_>) -> Rect {
         let area = f.area();
-        let h_height = if self.show_shortcuts { 4 } else { 0 };
+        let h_height = if self.show_shortcuts { 6 } else { 0 };
 
         // NOTE: `Layout::split` always returns a slice of the same length as the number of
         // constraints, so the `else` branch is unreachable.
@@ -99,94 +170,189 @@ fn vertical_layout(&self, f: &mut Frame<'_>) {
             unreachable!()
         };
 
-        // Split the app in 4 vertically to construct all the panes.
-        let [op_pane, stack_pane, memory_pane, src_pane] = Layout::new(
-            Direction::Vertical,
-            [
-                Constraint::Ratio(1, 6),
-                Constraint::Ratio(1, 6),
-                Constraint::Ratio(1, 6),
-                Constraint::Ratio(3, 6),
-            ],
-        )
-        .split(app)[..] else {
-            unreachable!()
-        };
-
         if self.show_shortcuts {
             self.draw_footer(f, footer);
         }
-        self.draw_src(f, src_pane);
-        self.draw_op_list(f, op_pane);
-        self.draw_stack(f, stack_pane);
-        self.draw_buffer(f, memory_pane);
+        app
     }
 
-    /// Draws the layout in horizontal mode.
+    /// Draws the visible panes on top of each other, sized relative to each other.
     ///
-    /// ```text
-    /// |-----------------|-----------|
-    /// |        op       |   stack   |
-    /// |-----------------|-----------|
-    /// |                 |           |
-    /// |       src       |    buf    |
-    /// |                 |           |
-    /// |-----------------|-----------|
-    /// ```
-    fn horizontal_layout(&self, f: &mut Frame<'_>) {
-        let area = f.area();
-        let h_height = if self.show_shortcuts { 4 } else { 0 };
+    /// The given default sizes are overridden by the sizes in the config, if any. Panes with a
+    /// size of 0 are hidden.
+    fn draw_column(&self, f: &mut Frame<'_>, area: Rect, panes: &[(DebuggerPane, u32)]) {
+        let panes = panes
+            .iter()
+            .map(|&(pane, default_size)| (pane, self.pane_size(pane).unwrap_or(default_size)))
+            .filter(|&(pane, size)| size > 0 && self.is_visible(pane))
+            .collect::<Vec<_>>();
+        let total: u32 = panes.iter().map(|(_, size)| size).sum();
+        let constraints = panes.iter().map(|&(_, size)| Constraint::Ratio(size, total));
+        let areas = Layout::new(Direction::Vertical, constraints).split(area);
+        for (&(pane, _), &area) in panes.iter().zip(areas.iter()) {
+            match pane {
+                DebuggerPane::Op => self.draw_op_list(f, area),
+                DebuggerPane::Stack => self.draw_stack(f, area),
+                DebuggerPane::Buffer => self.draw_buffer(f, area),
+                DebuggerPane::Src => self.draw_src_and_watches(f, area),
+                DebuggerPane::Storage => self.draw_storage(f, area),
+            }
+        }
+    }
 
-        // Split off footer.
-        let [app, footer] = Layout::new(
+    /// Returns the size of the given pane set in the config.
+    fn pane_size(&self, pane: DebuggerPane) -> Option<u32> {
+        let sizes = self.layout.sizes?;
+        let size = match pane {
+            DebuggerPane::Op => sizes.op,
+            DebuggerPane::Stack => sizes.stack,
+            DebuggerPane::Buffer => sizes.buffer,
+            DebuggerPane::Src => sizes.src,
+            DebuggerPane::Storage => sizes.storage?,
+        };
+        Some(size as u32)
+    }
+
+    fn draw_footer(&self, f: &mut Frame<'_>, area: Rect) {
+        let l1 = "[q]: quit | [k/j]: prev/next op | [a/s]: prev/next jump | [p]: step back across calls | [c/C]: prev/next call | [[/]]: prev/next call boundary | [r/R]: prev/next revert | [g/G]: start/end | [b]: cycle memory/calldata/returndata buffers";
+        let l2 = "[t]: stack labels | [m]: buffer decoding | [shift + j/k]: scroll stack | [ctrl + j/k]: scroll buffer | ['<char>]: goto breakpoint | [x/X]: add/remove breakpoint | [n/N]: next/prev breakpoint | [h] toggle help";
+        let l3 = "[L]: cycle auto/horizontal/vertical layout | [O/S/B/V/T]: toggle op/stack/buffer/source/storage panes | [</>]: resize columns | [e]: open in editor | [d]: jump to definition | [w/W]: add/remove watch";
+        let dimmed = Style::new().add_modifier(Modifier::DIM);
+        let lines = vec![
+            Line::from(Span::styled(l1, dimmed)),
+            Line::from(Span::styled(l2, dimmed)),
+            Line::from(Span::styled(l3, dimmed)),
+        ];
+        let paragraph =
+            Paragraph::new(lines).alignment(Alignment::Center).wrap(Wrap { trim: false });
+        f.render_widget(paragraph, area);
+    }
+
+    /// Draws the source pane, with the watch expressions and the breakpoints below it if there
+    /// are any.
+    fn draw_src_and_watches(&self, f: &mut Frame<'_>, area: Rect) {
+        let watch_lines = self.watches.len() + self.watch_input.is_some() as usize;
+        let breakpoint_lines = self.breakpoints.len() + self.breakpoint_input.is_some() as usize;
+        let height =
+            |lines: usize| if lines == 0 { 0 } else { (lines as u16 + 2).min(area.height / 4) };
+        let [src, watches, breakpoints] = Layout::new(
             Direction::Vertical,
-            [Constraint::Ratio(100 - h_height, 100), Constraint::Ratio(h_height, 100)],
+            [
+                Constraint::Min(0),
+                Constraint::Length(height(watch_lines)),
+                Constraint::Length(height(breakpoint_lines)),
+            ],
         )
         .split(area)[..] else {
             unreachable!()
         };
+        self.draw_src(f, src);
+        if watch_lines > 0 {
+            self.draw_watches(f, watches);
+        }
+        if breakpoint_lines > 0 {
+            self.draw_breakpoints(f, breakpoints);
+        }
+    }
 
-        // Split app in 2 horizontally.
-        let [app_left, app_right] =
-            Layout::new(Direction::Horizontal, [Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
-                .split(app)[..]
-        else {
-            unreachable!()
-        };
+    fn draw_watches(&self, f: &mut Frame<'_>, area: Rect) {
+        let mut lines = self
+            .watches
+            .iter()
+            .zip(self.eval_watches())
+            .enumerate()
+            .map(|(i, (expr, value))| {
+                let (value, style) = match value {
+                    Ok(value) => (value, Style::new().fg(Color::Yellow)),
+                    Err(e) => (e, Style::new().fg(Color::Red)),
+                };
+                Line::from(vec![
+                    Span::styled(format!("{}| ", i + 1), Style::new().fg(Color::White)),
+                    Span::raw(format!("{expr} = ")),
+                    Span::styled(value, style),
+                ])
+            })
+            .collect::<Vec<_>>();
 
-        // Split left pane in 2 vertically to opcode list and source.
-        let [op_pane, src_pane] =
-            Layout::new(Direction::Vertical, [Constraint::Ratio(1, 4), Constraint::Ratio(3, 4)])
-                .split(app_left)[..]
-        else {
-            unreachable!()
-        };
+        let mut title = format!("Watch: {}", self.watches.len());
+        if let Some(input) = &self.watch_input {
+            lines.push(Line::from(vec![
+                Span::styled("> ", Style::new().fg(Color::Cyan)),
+                Span::raw(input.as_str()),
+                Span::styled("_", Style::new().add_modifier(Modifier::SLOW_BLINK)),
+            ]));
+            title.push_str(" | [enter]: add | [esc]: cancel");
+        }
 
-        // Split right pane horizontally to construct stack and memory.
-        let [stack_pane, memory_pane] =
-            Layout::new(Direction::Vertical, [Constraint::Ratio(1, 4), Constraint::Ratio(3, 4)])
-                .split(app_right)[..]
-        else {
-            unreachable!()
+        let block = Block::default().title(title).borders(Borders::ALL);
+        let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: false });
+        f.render_widget(paragraph, area);
+    }
+
+    fn draw_breakpoints(&self, f: &mut Frame<'_>, area: Rect) {
+        let (call_index, step) = (self.draw_memory.inner_call_index, self.current_step);
+        let prev = match step.checked_sub(1) {
+            Some(prev) => Some((call_index, prev)),
+            None => call_index
+                .checked_sub(1)
+                .map(|prev| (prev, self.debug_arena()[prev].steps.len() - 1)),
         };
+        let hit = self.breakpoint_hit(call_index, step, prev);
+        let mut lines = self
+            .breakpoints
+            .iter()
+            .enumerate()
+            .map(|(i, breakpoint)| {
+                let style = if hit == Some(i) {
+                    Style::new().fg(Color::Green).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::new()
+                };
+                Line::from(vec![
+                    Span::styled(format!("{}| ", i + 1), Style::new().fg(Color::White)),
+                    Span::styled(breakpoint.to_string(), style),
+                ])
+            })
+            .collect::<Vec<_>>();
 
-        if self.show_shortcuts {
-            self.draw_footer(f, footer);
+        let mut title = format!("Breakpoints: {}", self.breakpoints.len());
+        if let Some(input) = &self.breakpoint_input {
+            lines.push(Line::from(vec![
+                Span::styled("> ", Style::new().fg(Color::Cyan)),
+                Span::raw(input.as_str()),
+                Span::styled("_", Style::new().add_modifier(Modifier::SLOW_BLINK)),
+            ]));
+            title.push_str(
+                " | op <op>, pc <pc>, line [<file>:]<line> or depth <depth> [if <a> == <b>]",
+            );
         }
-        self.draw_src(f, src_pane);
-        self.draw_op_list(f, op_pane);
-        self.draw_stack(f, stack_pane);
-        self.draw_buffer(f, memory_pane);
+
+        let block = Block::default().title(title).borders(Borders::ALL);
+        let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: false });
+        f.render_widget(paragraph, area);
     }
 
-    fn draw_footer(&self, f: &mut Frame<'_>, area: Rect) {
-        let l1 = "[q]: quit | [k/j]: prev/next op | [a/s]: prev/next jump | [c/C]: prev/next call | [g/G]: start/end | [b]: cycle memory/calldata/returndata buffers";
-        let l2 = "[t]: stack labels | [m]: buffer decoding | [shift + j/k]: scroll stack | [ctrl + j/k]: scroll buffer | ['<char>]: goto breakpoint | [h] toggle help";
-        let dimmed = Style::new().add_modifier(Modifier::DIM);
-        let lines =
-            vec![Line::from(Span::styled(l1, dimmed)), Line::from(Span::styled(l2, dimmed))];
-        let paragraph =
-            Paragraph::new(lines).alignment(Alignment::Center).wrap(Wrap { trim: false });
+    fn draw_storage(&self, f: &mut Frame<'_>, area: Rect) {
+        let lines = self
+            .state_variables()
+            .into_iter()
+            .map(|(name, ty, value)| {
+                // Slots that weren't accessed yet are expected, so they are not shown as errors.
+                let (value, style) = match value {
+                    Ok(value) => (value, Style::new().fg(Color::Yellow)),
+                    Err(e) => (e, Style::new().add_modifier(Modifier::DIM)),
+                };
+                Line::from(vec![
+                    Span::styled(format!("{ty} "), Style::new().fg(Color::Cyan)),
+                    Span::raw(format!("{name} = ")),
+                    Span::styled(value, style),
+                ])
+            })
+            .collect::<Vec<_>>();
+
+        let title = format!("Storage: {}", lines.len());
+        let block = Block::default().title(title).borders(Borders::ALL);
+        let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: false });
         f.render_widget(paragraph, area);
     }
 
@@ -206,7 +372,10 @@ fn draw_src(&self, f: &mut Frame<'_>, area: Rect) {
             call_kind_text,
             source_name.map(|s| format!("| {s}")).unwrap_or_default()
         );
-        let block = Block::default().title(title).borders(Borders::ALL);
+        let mut block = Block::default().title(title).borders(Borders::ALL);
+        if let Some(status) = &self.status {
+            block = block.title_bottom(format!(" {status} "));
+        }
         let paragraph = Paragraph::new(text_output).block(block).wrap(Wrap { trim: false });
         f.render_widget(paragraph, area);
     }
@@ -341,7 +510,7 @@ fn src_text(&self, area: Rect) -> (Text<'_>, Option<&str>) {
     }
 
     /// Returns source map, source code and source name of the current line.
-    fn src_map(&self) -> Result<(SourceElement, &SourceData), String> {
+    pub(crate) fn src_map(&self) -> Result<(SourceElement, &SourceData), String> {
         let address = self.address();
         let Some(contract_name) = self.debugger_context.identified_contracts.get(address) else {
             return Err(format!("Unknown contract at address {address}"));
@@ -375,9 +544,12 @@ fn draw_op_list(&self, f: &mut Frame<'_>, area: Rect) {
             })
             .collect::<Vec<_>>();
 
+        let address = match self.debugger_context.labels.get(self.address()) {
+            Some(label) => format!("{} ({label})", self.address()),
+            None => self.address().to_string(),
+        };
         let title = format!(
-            "Address: {} | PC: {} | Gas used in call: {} | Code section: {}",
-            self.address(),
+            "Address: {address} | PC: {} | Gas used in call: {} | Code section: {}",
             self.current_step().pc,
             self.current_step().gas_used,
             self.current_step().code_section_idx,
@@ -401,6 +573,12 @@ fn draw_stack(&self, f: &mut Frame<'_>, area: Rect) {
 
         let params = OpcodeParam::of(step.op.get(), step.immediate_bytes.as_ref());
 
+        let variables = self
+            .program_debug_info()
+            .zip(stack)
+            .map(|(program, stack)| program.resolve(step.pc, stack))
+            .unwrap_or_default();
+
         let text: Vec<Line<'_>> = stack
             .map(|stack| {
                 stack
@@ -437,6 +615,14 @@ fn draw_stack(&self, f: &mut Frame<'_>, area: Rect) {
                             }
                         }
 
+                        for variable in variables.iter().filter(|v| v.stack_index == i) {
+                            spans.push(Span::raw("| "));
+                            spans.push(Span::styled(
+                                format!("{} = {}", variable.name, variable.value),
+                                Style::new().fg(Color::Yellow),
+                            ));
+                        }
+
                         spans.push(Span::raw("\n"));
 
                         Line::from(spans)
@@ -445,7 +631,10 @@ fn draw_stack(&self, f: &mut Frame<'_>, area: Rect) {
             })
             .unwrap_or_default();
 
-        let title = format!("Stack: {stack_len}");
+        let mut title = format!("Stack: {stack_len}");
+        if !variables.is_empty() {
+            let _ = write!(title, " | Variables: {}", variables.len());
+        }
         let block = Block::default().title(title).borders(Borders::ALL);
         let paragraph = Paragraph::new(text).block(block).wrap(Wrap { trim: true });
         f.render_widget(paragraph, area);
@@ -648,6 +837,130 @@ fn hex_digits(n: usize) -> usize {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::{debugger::DebuggerContext, DebugNode};
+    use alloy_primitives::{Address, U256};
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+    use ratatui::{backend::TestBackend, Terminal};
+    use revm::interpreter::{InstructionResult, OpCode};
+    use revm_inspectors::tracing::types::CallTraceStep;
+
+    const LAYOUT: &str = r#"{
+        "storage": [
+            { "astId": 1, "contract": "C", "label": "count", "offset": 0, "slot": "0",
+              "type": "t_uint256" },
+            { "astId": 2, "contract": "C", "label": "paused", "offset": 0, "slot": "1",
+              "type": "t_bool" }
+        ],
+        "types": {
+            "t_bool": { "encoding": "inplace", "label": "bool", "numberOfBytes": "1" },
+            "t_uint256": { "encoding": "inplace", "label": "uint256", "numberOfBytes": "32" }
+        }
+    }"#;
+
+    fn step(op: OpCode, stack: &[u64]) -> CallTraceStep {
+        CallTraceStep {
+            depth: 1,
+            pc: 0,
+            code_section_idx: 0,
+            op,
+            contract: Address::ZERO,
+            stack: Some(stack.iter().map(|value| U256::from(*value)).collect()),
+            push_stack: None,
+            memory: Some(Default::default()),
+            returndata: Default::default(),
+            gas_remaining: 0,
+            gas_refund_counter: 0,
+            gas_used: 0,
+            gas_cost: 0,
+            storage_change: None,
+            status: InstructionResult::Continue,
+            immediate_bytes: None,
+            decoded: None,
+        }
+    }
+
+    /// A context with a single call that stores 5 in slot 0 of a contract named `C`.
+    fn debugger_context(identified: bool) -> DebuggerContext {
+        let address = Address::repeat_byte(1);
+        let node = DebugNode {
+            address,
+            storage_address: address,
+            steps: vec![step(OpCode::SSTORE, &[5, 0]), step(OpCode::STOP, &[])],
+            ..Default::default()
+        };
+        let identified_contracts = if identified {
+            [(address, "C".to_string())].into_iter().collect()
+        } else {
+            Default::default()
+        };
+        DebuggerContext {
+            debug_arena: vec![node],
+            identified_contracts,
+            contract_ids: Default::default(),
+            labels: Default::default(),
+            contracts_sources: Default::default(),
+            breakpoints: Default::default(),
+            debug_info: Default::default(),
+            storage_layouts: [("C".to_string(), serde_json::from_str(LAYOUT).unwrap())]
+                .into_iter()
+                .collect(),
+            config: Default::default(),
+            root: Default::default(),
+        }
+    }
+
+    /// Draws the context to a test terminal, returning its lines.
+    fn render(context: &TUIContext<'_>) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(160, 60)).unwrap();
+        terminal.draw(|f| context.draw_layout(f)).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content()
+            .chunks(buffer.area.width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect())
+            .collect()
+    }
+
+    fn contains(lines: &[String], text: &str) -> bool {
+        lines.iter().any(|line| line.contains(text))
+    }
+
+    #[test]
+    fn draw_storage_pane() {
+        let mut debugger_context = debugger_context(true);
+        let mut context = TUIContext::new(&mut debugger_context);
+        context.init();
+        assert!(context.is_visible(DebuggerPane::Storage));
+
+        // The pending `SSTORE` is not observed yet.
+        let lines = render(&context);
+        assert!(contains(&lines, "Storage: 2"), "{lines:#?}");
+        assert!(contains(&lines, "uint256 count = slot 0x0 not loaded yet"), "{lines:#?}");
+        assert!(contains(&lines, "bool paused = slot 0x1 not loaded yet"), "{lines:#?}");
+
+        let next = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE);
+        let _ = context.handle_event(Event::Key(next));
+        let lines = render(&context);
+        assert!(contains(&lines, "uint256 count = 5"), "{lines:#?}");
+
+        // Toggled with `T`.
+        let toggle = KeyEvent::new(KeyCode::Char('T'), KeyModifiers::NONE);
+        let _ = context.handle_event(Event::Key(toggle));
+        assert!(!context.is_visible(DebuggerPane::Storage));
+        assert!(!contains(&render(&context), "Storage:"));
+    }
+
+    #[test]
+    fn hide_storage_pane_without_layout() {
+        let mut debugger_context = debugger_context(false);
+        let mut context = TUIContext::new(&mut debugger_context);
+        context.init();
+        assert!(context.state_variables().is_empty());
+        assert!(!context.is_visible(DebuggerPane::Storage));
+        assert!(!contains(&render(&context), "Storage:"));
+    }
+
     #[test]
     fn decimal_digits() {
         assert_eq!(super::decimal_digits(0), 1);
@@ -20,6 +20,7 @@
 
 mod context;
 use crate::debugger::DebuggerContext;
+pub(crate) use context::line_column;
 use context::TUIContext;
 
 mod draw;
@@ -1,8 +1,9 @@
 use crate::{
-    document::DocumentContent, helpers::merge_toml_table, AsDoc, BufWriter, Document, ParseItem,
-    ParseSource, Parser, Preprocessor,
+    document::DocumentContent, helpers::merge_toml_table, template, AsDoc, BufWriter, Document,
+    ParseItem, ParseSource, Parser, Preprocessor, Template,
 };
 use alloy_primitives::map::HashMap;
+use eyre::WrapErr;
 use forge_fmt::{FormatterConfig, Visitable};
 use foundry_compilers::{compilers::solc::SOLC_EXTENSIONS, utils::source_files_iter};
 use foundry_config::{filter::expand_globs, DocConfig};
@@ -25,6 +26,8 @@ pub struct DocBuilder {
     pub root: PathBuf,
     /// Path to Solidity source files.
     pub sources: PathBuf,
+    /// Paths to other Solidity files to document in their own sections, e.g. scripts and tests.
+    pub extra_sources: Vec<PathBuf>,
     /// Paths to external libraries.
     pub libraries: Vec<PathBuf>,
     /// Flag whether to build mdbook.
@@ -56,6 +59,7 @@ pub fn new(
         Self {
             root,
             sources,
+            extra_sources: Vec::new(),
             libraries,
             include_libraries,
             should_build: false,
@@ -71,6 +75,12 @@ pub fn with_should_build(mut self, should_build: bool) -> Self {
         self
     }
 
+    /// Add a directory of Solidity files to document in its own section.
+    pub fn with_extra_sources(mut self, path: PathBuf) -> Self {
+        self.extra_sources.push(path);
+        self
+    }
+
     /// Set config on the builder.
     pub fn with_config(mut self, config: DocConfig) -> Self {
         self.config = config;
@@ -94,13 +104,16 @@ pub fn out_dir(&self) -> PathBuf {
         self.root.join(&self.config.out)
     }
 
-    /// Parse the sources and build the documentation.
+    /// Parse the sources and build the documentation, in the default language and in each of the
+    /// configured languages.
     pub fn build(self) -> eyre::Result<()> {
         // Expand ignore globs
         let ignored = expand_globs(&self.root, self.config.ignore.iter())?;
 
         // Collect and parse source files
-        let sources = source_files_iter(&self.sources, SOLC_EXTENSIONS)
+        let sources = std::iter::once(&self.sources)
+            .chain(&self.extra_sources)
+            .flat_map(|dir| source_files_iter(dir, SOLC_EXTENSIONS))
             .filter(|file| !ignored.contains(file))
             .collect::<Vec<_>>();
 
@@ -121,6 +134,38 @@ pub fn build(self) -> eyre::Result<()> {
             .chain(library_sources.iter().map(|path| (path, true)))
             .collect::<Vec<_>>();
 
+        let template = match &self.config.template {
+            Some(path) => {
+                let path = self.root.join(path);
+                let source = fs::read_to_string(&path)
+                    .wrap_err_with(|| format!("failed to read template {}", path.display()))?;
+                Some(
+                    Template::parse(&source)
+                        .wrap_err_with(|| format!("failed to parse template {}", path.display()))?,
+                )
+            }
+            None => None,
+        };
+
+        self.build_language(&combined_sources, None, template.as_ref())?;
+        for language in &self.config.languages {
+            self.build_language(&combined_sources, Some(language.as_str()), template.as_ref())?;
+        }
+
+        Ok(())
+    }
+
+    /// Builds the documentation of the sources in the given language, or in the default language
+    /// if `None`.
+    fn build_language(
+        &self,
+        combined_sources: &[(&PathBuf, bool)],
+        language: Option<&str>,
+        template: Option<&Template>,
+    ) -> eyre::Result<()> {
+        let out = self.language_out(language);
+        // The preprocessors aren't shared between threads, so only these are captured.
+        let (root, fmt, languages) = (&self.root, &self.fmt, &self.config.languages);
         let documents = combined_sources
             .par_iter()
             .enumerate()
@@ -148,14 +193,19 @@ pub fn build(self) -> eyre::Result<()> {
                 };
 
                 // Visit the parse tree
-                let mut doc = Parser::new(comments, source).with_fmt(self.fmt.clone());
+                let mut doc = Parser::new(comments, source).with_fmt(fmt.clone());
                 source_unit
                     .visit(&mut doc)
                     .map_err(|err| eyre::eyre!("Failed to parse source: {err}"))?;
 
+                // Translate the comments.
+                let mut items = doc.items();
+                for item in &mut items {
+                    item.localize(language, languages);
+                }
+
                 // Split the parsed items on top-level constants and rest.
-                let (items, consts): (Vec<ParseItem>, Vec<ParseItem>) = doc
-                    .items()
+                let (items, consts): (Vec<ParseItem>, Vec<ParseItem>) = items
                     .into_iter()
                     .partition(|item| !matches!(item.source, ParseSource::Variable(_)));
 
@@ -180,16 +230,11 @@ pub fn build(self) -> eyre::Result<()> {
                 let mut files = remaining
                     .into_iter()
                     .map(|item| {
-                        let relative_path = path.strip_prefix(&self.root)?.join(item.filename());
-                        let target_path = self.config.out.join(Self::SRC).join(relative_path);
+                        let relative_path = path.strip_prefix(root)?.join(item.filename());
+                        let target_path = out.join(Self::SRC).join(relative_path);
                         let ident = item.source.ident();
-                        Ok(Document::new(
-                            path.clone(),
-                            target_path,
-                            from_library,
-                            self.config.out.clone(),
-                        )
-                        .with_content(DocumentContent::Single(item), ident))
+                        Ok(Document::new(path.clone(), target_path, from_library, out.clone())
+                            .with_content(DocumentContent::Single(item), ident))
                     })
                     .collect::<eyre::Result<Vec<_>>>()?;
 
@@ -205,8 +250,8 @@ pub fn build(self) -> eyre::Result<()> {
                         name.push_str(".md");
                         name
                     };
-                    let relative_path = path.strip_prefix(&self.root)?.join(filename);
-                    let target_path = self.config.out.join(Self::SRC).join(relative_path);
+                    let relative_path = path.strip_prefix(root)?.join(filename);
+                    let target_path = out.join(Self::SRC).join(relative_path);
 
                     let identity = match filestem {
                         Some(stem) if stem.to_lowercase().contains("constants") => stem.to_owned(),
@@ -215,13 +260,8 @@ pub fn build(self) -> eyre::Result<()> {
                     };
 
                     files.push(
-                        Document::new(
-                            path.clone(),
-                            target_path,
-                            from_library,
-                            self.config.out.clone(),
-                        )
-                        .with_content(DocumentContent::Constants(consts), identity),
+                        Document::new(path.clone(), target_path, from_library, out.clone())
+                            .with_content(DocumentContent::Constants(consts), identity),
                     )
                 }
 
@@ -229,16 +269,11 @@ pub fn build(self) -> eyre::Result<()> {
                 if !overloaded.is_empty() {
                     for (ident, funcs) in overloaded {
                         let filename = funcs.first().expect("no overloaded functions").filename();
-                        let relative_path = path.strip_prefix(&self.root)?.join(filename);
-                        let target_path = self.config.out.join(Self::SRC).join(relative_path);
+                        let relative_path = path.strip_prefix(root)?.join(filename);
+                        let target_path = out.join(Self::SRC).join(relative_path);
                         files.push(
-                            Document::new(
-                                path.clone(),
-                                target_path,
-                                from_library,
-                                self.config.out.clone(),
-                            )
-                            .with_content(DocumentContent::OverloadedFunctions(funcs), ident),
+                            Document::new(path.clone(), target_path, from_library, out.clone())
+                                .with_content(DocumentContent::OverloadedFunctions(funcs), ident),
                         );
                     }
                 }
@@ -263,11 +298,14 @@ pub fn build(self) -> eyre::Result<()> {
         // Write mdbook related files
         self.write_mdbook(
             documents.filter(|d| !d.from_library || self.include_libraries).collect_vec(),
+            &out,
+            language,
+            template,
         )?;
 
         // Build the book if requested
         if self.should_build {
-            MDBook::load(self.out_dir())
+            MDBook::load(self.root.join(&out))
                 .and_then(|book| book.build())
                 .map_err(|err| eyre::eyre!("failed to build book: {err:?}"))?;
         }
@@ -275,8 +313,22 @@ pub fn build(self) -> eyre::Result<()> {
         Ok(())
     }
 
-    fn write_mdbook(&self, documents: Vec<Document>) -> eyre::Result<()> {
-        let out_dir = self.out_dir();
+    /// Returns the relative output path of the documentation in the given language.
+    fn language_out(&self, language: Option<&str>) -> PathBuf {
+        match language {
+            Some(language) => self.config.out.join(language),
+            None => self.config.out.clone(),
+        }
+    }
+
+    fn write_mdbook(
+        &self,
+        documents: Vec<Document>,
+        out: &Path,
+        language: Option<&str>,
+        template: Option<&Template>,
+    ) -> eyre::Result<()> {
+        let out_dir = self.root.join(out);
         let out_dir_src = out_dir.join(Self::SRC);
         fs::create_dir_all(&out_dir_src)?;
 
@@ -311,7 +363,8 @@ fn write_mdbook(&self, documents: Vec<Document>) -> eyre::Result<()> {
         let mut summary = BufWriter::default();
         summary.write_title("Summary")?;
         summary.write_link_list_item("Home", Self::README, 0)?;
-        self.write_summary_section(&mut summary, &documents.iter().collect::<Vec<_>>(), None, 0)?;
+        let files = documents.iter().collect::<Vec<_>>();
+        self.write_summary_section(&mut summary, &files, None, 0, out)?;
         fs::write(out_dir_src.join(Self::SUMMARY), summary.finish())?;
 
         // Write solidity syntax highlighting
@@ -321,11 +374,11 @@ fn write_mdbook(&self, documents: Vec<Document>) -> eyre::Result<()> {
         fs::write(out_dir.join("book.css"), include_str!("../static/book.css"))?;
 
         // Write book config
-        fs::write(self.out_dir().join("book.toml"), self.book_config()?)?;
+        fs::write(out_dir.join("book.toml"), self.book_config(language)?)?;
 
         // Write .gitignore
         let gitignore = "book/";
-        fs::write(self.out_dir().join(".gitignore"), gitignore)?;
+        fs::write(out_dir.join(".gitignore"), gitignore)?;
 
         // Write doc files
         for document in documents {
@@ -335,19 +388,24 @@ fn write_mdbook(&self, documents: Vec<Document>) -> eyre::Result<()> {
                     .parent()
                     .ok_or_else(|| eyre::format_err!("empty target path; noop"))?,
             )?;
-            fs::write(&document.target_path, document.as_doc()?)?;
+            let mut content = document.as_doc()?;
+            if let Some(template) = template {
+                content = template.render(&template::context(&document, &content, language))?;
+            }
+            fs::write(&document.target_path, content)?;
         }
 
         Ok(())
     }
 
-    fn book_config(&self) -> eyre::Result<String> {
+    fn book_config(&self, language: Option<&str>) -> eyre::Result<String> {
         // Read the default book first
         let mut book: value::Table = toml::from_str(include_str!("../static/book.toml"))?;
-        book["book"]
-            .as_table_mut()
-            .unwrap()
-            .insert(String::from("title"), self.config.title.clone().into());
+        let book_table = book["book"].as_table_mut().unwrap();
+        book_table.insert(String::from("title"), self.config.title.clone().into());
+        if let Some(language) = language {
+            book_table.insert(String::from("language"), language.into());
+        }
         if let Some(ref repo) = self.config.repository {
             book["output"].as_table_mut().unwrap()["html"]
                 .as_table_mut()
@@ -383,6 +441,7 @@ fn write_summary_section(
         files: &[&Document],
         base_path: Option<&Path>,
         depth: usize,
+        out: &Path,
     ) -> eyre::Result<()> {
         if files.is_empty() {
             return Ok(())
@@ -428,9 +487,7 @@ fn write_summary_section(
                 for file in files {
                     let ident = &file.identity;
 
-                    let summary_path = file
-                        .target_path
-                        .strip_prefix(self.out_dir().strip_prefix(&self.root)?.join(Self::SRC))?;
+                    let summary_path = file.target_path.strip_prefix(out.join(Self::SRC))?;
                     summary.write_link_list_item(
                         ident,
                         &summary_path.display().to_string(),
@@ -447,12 +504,12 @@ fn write_summary_section(
                 let name = path.iter().next_back().unwrap().to_string_lossy();
                 let readme_path = Path::new("/").join(&path).display().to_string();
                 readme.write_link_list_item(&name, &readme_path, 0)?;
-                self.write_summary_section(summary, &files, Some(&path), depth + 1)?;
+                self.write_summary_section(summary, &files, Some(&path), depth + 1, out)?;
             }
         }
         if !readme.is_empty() {
             if let Some(path) = base_path {
-                let path = self.out_dir().join(Self::SRC).join(path);
+                let path = self.root.join(out).join(Self::SRC).join(path);
                 fs::create_dir_all(&path)?;
                 fs::write(path.join(Self::README), readme.finish())?;
             }
@@ -27,6 +27,9 @@
 mod preprocessor;
 pub use preprocessor::*;
 
+mod template;
+pub use template::Template;
+
 mod writer;
 pub use writer::{AsDoc, AsDocResult, BufWriter, Markdown};
 
@@ -101,6 +101,23 @@ pub fn match_first_word(&self, expected: &str) -> Option<&str> {
     pub fn is_custom(&self) -> bool {
         matches!(self.tag, CommentTag::Custom(_))
     }
+
+    /// Returns the language and the translated tag of a `@custom:<language>-<tag>` comment.
+    fn translation<'a>(&self, languages: &'a [String]) -> Option<(&'a str, CommentTag)> {
+        let CommentTag::Custom(custom) = &self.tag else { return None };
+        let (lang, tag) = custom.split_once('-')?;
+        let lang = languages.iter().find(|language| *language == lang)?;
+        let tag = match tag {
+            "title" => CommentTag::Title,
+            "author" => CommentTag::Author,
+            "notice" => CommentTag::Notice,
+            "dev" => CommentTag::Dev,
+            "param" => CommentTag::Param,
+            "return" => CommentTag::Return,
+            _ => return None,
+        };
+        Some((lang, tag))
+    }
 }
 
 /// The collection of natspec [Comment] items.
@@ -149,6 +166,29 @@ pub fn merge_inheritdoc(
 
         result
     }
+
+    /// Returns the comments translated to the given language, or in the default language if
+    /// `None`.
+    ///
+    /// The `@custom:<language>-<tag>` comments replace the `@<tag>` ones, and are removed from
+    /// the comments of the other languages.
+    pub fn localize(&self, language: Option<&str>, languages: &[String]) -> Self {
+        let mut translated = Vec::new();
+        let mut result = Vec::new();
+        for comment in self.iter() {
+            match comment.translation(languages) {
+                Some((lang, tag)) if Some(lang) == language => {
+                    translated.push(Comment::new(tag, comment.value.clone()));
+                }
+                Some(_) => {}
+                None => result.push(comment.clone()),
+            }
+        }
+        let translated = Self(translated);
+        result.retain(|comment| !translated.contains_tag(comment));
+        result.extend(translated.0);
+        Self(result)
+    }
 }
 
 impl From<Vec<DocCommentTag>> for Comments {
@@ -239,6 +279,36 @@ fn parse_comment_tag() {
         assert_eq!(CommentTag::from_str("sometag"), None);
     }
 
+    #[test]
+    fn localize_comments() {
+        let comments = Comments(vec![
+            Comment::new(CommentTag::Notice, "Transfers tokens".to_owned()),
+            Comment::new(CommentTag::Param, "amount The amount".to_owned()),
+            Comment::new(CommentTag::Param, "to The recipient".to_owned()),
+            Comment::new(CommentTag::Custom("fr-notice".to_owned()), "Transfère".to_owned()),
+            Comment::new(CommentTag::Custom("fr-param".to_owned()), "amount Le montant".to_owned()),
+            Comment::new(CommentTag::Custom("es-notice".to_owned()), "Transfiere".to_owned()),
+        ]);
+        let languages = ["fr".to_owned(), "es".to_owned()];
+
+        let default = comments.localize(None, &languages);
+        assert_eq!(default, Comments(comments[..3].to_vec()));
+
+        let fr = comments.localize(Some("fr"), &languages);
+        assert_eq!(
+            fr,
+            Comments(vec![
+                Comment::new(CommentTag::Param, "to The recipient".to_owned()),
+                Comment::new(CommentTag::Notice, "Transfère".to_owned()),
+                Comment::new(CommentTag::Param, "amount Le montant".to_owned()),
+            ])
+        );
+
+        // Languages that aren't translated to are kept as custom tags.
+        let default = comments.localize(None, &languages[..1]);
+        assert_eq!(default.len(), 4);
+    }
+
     #[test]
     fn test_is_custom() {
         // Test custom tag.
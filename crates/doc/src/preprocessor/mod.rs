@@ -19,6 +19,9 @@
 mod deployments;
 pub use deployments::{Deployment, Deployments, DEPLOYMENTS_ID};
 
+mod test_coverage;
+pub use test_coverage::{TestCoverage, TestInfo, TEST_COVERAGE_ID};
+
 /// The preprocessor id.
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct PreprocessorId(&'static str);
@@ -40,6 +43,9 @@ pub enum PreprocessorOutput {
     /// The deployments output.
     /// The deployment address of the item path.
     Deployments(Vec<Deployment>),
+    /// The test coverage output.
+    /// The contracts covered by a test contract and the cheatcodes used by its functions.
+    TestCoverage(TestInfo),
 }
 
 /// Trait for preprocessing and/or modifying existing documents
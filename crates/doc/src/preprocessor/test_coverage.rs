@@ -0,0 +1,140 @@
+use super::{Preprocessor, PreprocessorId};
+use crate::{document::DocumentContent, Document, ParseSource, PreprocessorOutput};
+use alloy_primitives::map::HashMap;
+use forge_fmt::solang_ext::SafeUnwrap;
+use regex::Regex;
+use solang_parser::pt::CodeLocation;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+/// [TestCoverage] preprocessor id.
+pub const TEST_COVERAGE_ID: PreprocessorId = PreprocessorId("test_coverage");
+
+/// Matches the path of an import directive, e.g. `import {A} from "./A.sol";`.
+static RE_IMPORT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"\bimport\b[^;]*?["']([^"']+)["'][^;]*;"#).unwrap());
+
+/// Matches a cheatcode call, e.g. `vm.prank(`.
+static RE_CHEATCODE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\bvm\s*\.\s*([a-zA-Z_][a-zA-Z0-9_]*)\s*\(").unwrap());
+
+/// The test coverage preprocessor.
+///
+/// It matches the contract documents of the test files, links them to the contracts of the files
+/// they import, and collects the cheatcodes called by each of their functions.
+///
+/// This preprocessor writes to [Document]'s context.
+#[derive(Debug)]
+pub struct TestCoverage {
+    /// The project root, which non-relative imports are resolved against.
+    pub root: PathBuf,
+    /// The test directory.
+    pub tests: PathBuf,
+}
+
+/// The contracts covered by a test contract, and the cheatcodes used by its functions.
+#[derive(Clone, Debug, Default)]
+pub struct TestInfo {
+    /// The contracts declared in the files imported by the test, and their document paths.
+    pub covers: Vec<(String, PathBuf)>,
+    /// The cheatcodes called by each function, in order of first use.
+    pub cheatcodes: HashMap<String, Vec<String>>,
+}
+
+impl Preprocessor for TestCoverage {
+    fn id(&self) -> PreprocessorId {
+        TEST_COVERAGE_ID
+    }
+
+    fn preprocess(&self, documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        let tests = canonicalize(&self.tests);
+        for document in documents.iter() {
+            let path = canonicalize(&document.item_path);
+            if !path.starts_with(&tests) {
+                continue
+            }
+            let DocumentContent::Single(ref item) = document.content else { continue };
+            let ParseSource::Contract(_) = item.source else { continue };
+            let source = fs::read_to_string(&document.item_path)?;
+
+            let mut info = TestInfo::default();
+            for import in imports(&source) {
+                let imported = if import.starts_with('.') {
+                    path.parent().map_or_else(|| PathBuf::from(import), |dir| dir.join(import))
+                } else {
+                    self.root.join(import)
+                };
+                let imported = canonicalize(&imported);
+                for candidate in documents.iter().filter(|doc| !doc.from_library) {
+                    if canonicalize(&candidate.item_path) != imported {
+                        continue
+                    }
+                    if let DocumentContent::Single(ref item) = candidate.content {
+                        if let ParseSource::Contract(ref contract) = item.source {
+                            let name = contract.name.safe_unwrap().name.clone();
+                            info.covers.push((name, candidate.target_path.clone()));
+                        }
+                    }
+                }
+            }
+
+            for (func, ..) in item.functions().unwrap_or_default() {
+                let (Some(name), Some(body)) = (&func.name, &func.body) else { continue };
+                let cheatcodes = source
+                    .get(body.loc().start()..body.loc().end())
+                    .map(cheatcodes)
+                    .unwrap_or_default();
+                if !cheatcodes.is_empty() {
+                    info.cheatcodes.insert(name.name.clone(), cheatcodes);
+                }
+            }
+
+            if !info.covers.is_empty() || !info.cheatcodes.is_empty() {
+                document.add_context(self.id(), PreprocessorOutput::TestCoverage(info));
+            }
+        }
+
+        Ok(documents)
+    }
+}
+
+fn canonicalize(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Returns the paths imported by a source file.
+fn imports(source: &str) -> Vec<&str> {
+    RE_IMPORT.captures_iter(source).filter_map(|c| c.get(1)).map(|m| m.as_str()).collect()
+}
+
+/// Returns the distinct cheatcodes called in a function body, in order of first use.
+fn cheatcodes(body: &str) -> Vec<String> {
+    let mut cheatcodes = Vec::<String>::new();
+    for name in RE_CHEATCODE.captures_iter(body).filter_map(|c| c.get(1)) {
+        if !cheatcodes.iter().any(|c| c == name.as_str()) {
+            cheatcodes.push(name.as_str().to_string());
+        }
+    }
+    cheatcodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_imports_and_cheatcodes() {
+        let source = r#"
+            import {Test} from "forge-std/Test.sol";
+            import "../src/Counter.sol";
+            import * as Lib from './Lib.sol';
+        "#;
+        assert_eq!(imports(source), ["forge-std/Test.sol", "../src/Counter.sol", "./Lib.sol"]);
+
+        let body = "{ vm.prank(alice); vm.expectRevert(); counter.inc(); vm . prank(bob); }";
+        assert_eq!(cheatcodes(body), ["prank", "expectRevert"]);
+    }
+}
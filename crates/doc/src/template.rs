@@ -0,0 +1,184 @@
+//! User provided templates of the pages.
+
+use crate::{
+    document::{read_context, DocumentContent},
+    CommentTag, Comments, CommentsRef, Document, PreprocessorOutput, CONTRACT_INHERITANCE_ID,
+    GIT_SOURCE_ID, INHERITDOC_ID,
+};
+use handlebars::Handlebars;
+use itertools::Itertools;
+use serde_json::{json, Value};
+use std::path::Path;
+
+/// A template of the pages, in the Handlebars syntax.
+///
+/// Values are not escaped, as the pages are Markdown.
+#[derive(Clone, Debug)]
+pub struct Template {
+    registry: Handlebars<'static>,
+}
+
+impl Template {
+    /// The name the template is registered with.
+    const NAME: &'static str = "page";
+
+    /// Parses a template.
+    pub fn parse(source: &str) -> eyre::Result<Self> {
+        let mut registry = Handlebars::new();
+        registry.register_escape_fn(handlebars::no_escape);
+        registry.register_template_string(Self::NAME, source)?;
+        Ok(Self { registry })
+    }
+
+    /// Renders the template with the given context.
+    pub fn render(&self, context: &Value) -> eyre::Result<String> {
+        Ok(self.registry.render(Self::NAME, context)?)
+    }
+}
+
+/// Returns the template context of a document, given its generated Markdown.
+///
+/// The context contains the `title`, `kind`, `content`, `language`, `path` and `git_source` of
+/// the page, the `natspec` comments of its item and the `inherits` links of contracts. The
+/// functions, variables, events, errors, structs and enums of a contract, or the items of other
+/// pages, are listed in `items` and by kind, with their `name`, `kind`, `anchor`, `code` and
+/// `natspec`.
+pub(crate) fn context(document: &Document, content: &str, language: Option<&str>) -> Value {
+    let item = match &document.content {
+        DocumentContent::Single(item) => Some(item),
+        _ => None,
+    };
+    let kind = match &document.content {
+        DocumentContent::Single(item) => item.kind(),
+        DocumentContent::Constants(_) => "constants",
+        DocumentContent::OverloadedFunctions(_) => "function",
+        DocumentContent::Empty => "",
+    };
+
+    let inheritdocs = read_context!(document, INHERITDOC_ID, Inheritdoc);
+    let items = match item {
+        Some(item) if item.as_contract().is_some() => item.children.iter().collect(),
+        _ => document.content.iter_items().collect::<Vec<_>>(),
+    };
+    let items = items
+        .into_iter()
+        .map(|item| {
+            let name = item.source.ident();
+            let comments = item.comments.merge_inheritdoc(&name, inheritdocs.clone());
+            json!({
+                "anchor": format!("#{}", name.to_lowercase()),
+                "name": name,
+                "kind": item.kind(),
+                "code": item.code,
+                "natspec": natspec(&comments),
+            })
+        })
+        .collect::<Vec<_>>();
+    let of_kind = |kind: &str| items.iter().filter(|item| item["kind"] == kind).collect::<Vec<_>>();
+
+    let src_dir = document.out_target_dir.join(crate::DocBuilder::SRC);
+    let link = |path: &Path| {
+        Path::new("/").join(path.strip_prefix(&src_dir).unwrap_or(path)).display().to_string()
+    };
+    let inherits = item
+        .and_then(|item| item.as_contract())
+        .map(|contract| {
+            let linked = read_context!(document, CONTRACT_INHERITANCE_ID, ContractInheritance)
+                .unwrap_or_default();
+            contract
+                .base
+                .iter()
+                .map(|base| {
+                    let name = &base.name.identifiers.last().unwrap().name;
+                    json!({ "name": name, "link": linked.get(name).map(|path| link(path)) })
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    json!({
+        "title": document.identity,
+        "kind": kind,
+        "content": content,
+        "language": language,
+        "path": Path::new("/").join(document.relative_output_path()).display().to_string(),
+        "git_source": read_context!(document, GIT_SOURCE_ID, GitSource),
+        "natspec": item.map(|item| natspec(&item.comments)),
+        "inherits": inherits,
+        "functions": of_kind("function"),
+        "variables": of_kind("variable"),
+        "events": of_kind("event"),
+        "errors": of_kind("error"),
+        "structs": of_kind("struct"),
+        "enums": of_kind("enum"),
+        "items": items,
+    })
+}
+
+/// Returns the natspec fields of the comments.
+fn natspec(comments: &Comments) -> Value {
+    let join = |tag: CommentTag, separator: &str| {
+        comments.include_tag(tag).iter().map(|c| c.value.trim()).join(separator)
+    };
+    let params = |tag: CommentTag| {
+        comments
+            .include_tag(tag)
+            .iter()
+            .map(|c| {
+                let (name, description) = c.split_first_word().unwrap_or((c.value.trim(), ""));
+                json!({ "name": name, "description": description.trim() })
+            })
+            .collect::<Vec<_>>()
+    };
+    let custom = CommentsRef::from(comments)
+        .get_custom_tags()
+        .iter()
+        .filter_map(|c| match &c.tag {
+            CommentTag::Custom(tag) => Some(json!({ "tag": tag, "value": c.value.trim() })),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "title": join(CommentTag::Title, " "),
+        "author": join(CommentTag::Author, ", "),
+        "notice": join(CommentTag::Notice, "\n"),
+        "dev": join(CommentTag::Dev, "\n"),
+        "params": params(CommentTag::Param),
+        "returns": params(CommentTag::Return),
+        "custom": custom,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_template() {
+        let template = Template::parse(
+            "# {{title}}{{! the functions }}\n{{#each functions}}- [{{name}}]({{anchor}}): \
+             {{#if natspec.notice}}{{natspec.notice}}{{else}}{{../title}}{{/if}} \
+             {{@index}}\n{{/each}}{{content}}",
+        )
+        .unwrap();
+        let context = json!({
+            "title": "Token",
+            "content": "<body>",
+            "functions": [
+                { "name": "mint", "anchor": "#mint", "natspec": { "notice": "Mints" } },
+                { "name": "burn", "anchor": "#burn", "natspec": { "notice": "" } },
+            ],
+        });
+        assert_eq!(
+            template.render(&context).unwrap(),
+            "# Token\n- [mint](#mint): Mints 0\n- [burn](#burn): Token 1\n<body>"
+        );
+    }
+
+    #[test]
+    fn parse_invalid_templates() {
+        assert!(Template::parse("{{#each items}}").is_err());
+        assert!(Template::parse("{{#if a}}{{/each}}").is_err());
+        assert!(Template::parse("{{title").is_err());
+    }
+}
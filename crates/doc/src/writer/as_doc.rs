@@ -3,7 +3,7 @@
     parser::ParseSource,
     writer::BufWriter,
     CommentTag, Comments, CommentsRef, Document, Markdown, PreprocessorOutput,
-    CONTRACT_INHERITANCE_ID, DEPLOYMENTS_ID, GIT_SOURCE_ID, INHERITDOC_ID,
+    CONTRACT_INHERITANCE_ID, DEPLOYMENTS_ID, GIT_SOURCE_ID, INHERITDOC_ID, TEST_COVERAGE_ID,
 };
 use forge_fmt::solang_ext::SafeUnwrap;
 use itertools::Itertools;
@@ -174,6 +174,25 @@ fn as_doc(&self) -> AsDocResult {
                             writer.writeln()?;
                         }
 
+                        if let Some(info) = read_context!(self, TEST_COVERAGE_ID, TestCoverage) {
+                            if !info.covers.is_empty() {
+                                writer.write_bold("Covers:")?;
+                                let src_target_dir = self.target_src_dir();
+                                let covers = info
+                                    .covers
+                                    .iter()
+                                    .map(|(name, path)| {
+                                        let path = Path::new("/").join(
+                                            path.strip_prefix(&src_target_dir).unwrap_or(path),
+                                        );
+                                        Markdown::Link(name, &path.display().to_string()).as_doc()
+                                    })
+                                    .collect::<Result<Vec<_>, _>>()?;
+                                writer.writeln_raw(covers.join(", "))?;
+                                writer.writeln()?;
+                            }
+                        }
+
                         writer.writeln_doc(&item.comments)?;
 
                         if let Some(state_vars) = item.variables() {
@@ -314,6 +333,15 @@ fn write_function(
         // Write function header
         writer.write_code(code)?;
 
+        // Write the cheatcodes used by test functions
+        if let Some(cheatcodes) = read_context!(self, TEST_COVERAGE_ID, TestCoverage)
+            .and_then(|info| info.cheatcodes.get(&func_name).cloned())
+        {
+            writer.write_bold("Cheatcodes:")?;
+            writer.writeln_raw(cheatcodes.iter().map(|c| format!("`{c}`")).join(", "))?;
+            writer.writeln()?;
+        }
+
         // Write function parameter comments in a table
         let params = func.params.iter().filter_map(|p| p.1.as_ref()).collect::<Vec<_>>();
         writer.try_write_param_table(CommentTag::Param, &params, &comments)?;
@@ -209,6 +209,10 @@ fn active_fork_url(&self) -> Option<String> {
         self.backend.active_fork_url()
     }
 
+    fn active_fork_block_number(&self) -> Option<u64> {
+        self.backend.active_fork_block_number()
+    }
+
     fn ensure_fork(&self, id: Option<LocalForkId>) -> eyre::Result<LocalForkId> {
         self.backend.ensure_fork(id)
     }
@@ -246,6 +250,14 @@ fn is_persistent(&self, acc: &Address) -> bool {
         self.backend.is_persistent(acc)
     }
 
+    fn is_unmodified_fork_account(
+        &self,
+        account: &Address,
+        journaled_state: &JournaledState,
+    ) -> bool {
+        self.backend.is_unmodified_fork_account(account, journaled_state)
+    }
+
     fn remove_persistent_account(&mut self, account: &Address) -> bool {
         self.backend.to_mut().remove_persistent_account(account)
     }
@@ -1,7 +1,8 @@
 use alloy_primitives::Address;
+use foundry_common::provider::is_transient_error;
 pub use foundry_fork_db::{DatabaseError, DatabaseResult};
 use revm::primitives::EVMError;
-use std::convert::Infallible;
+use std::{cell::Cell, convert::Infallible};
 
 pub type BackendResult<T> = Result<T, BackendError>;
 
@@ -64,3 +65,147 @@ fn from(err: EVMError<T>) -> Self {
         }
     }
 }
+
+/// Returns `true` if the error, or any of its sources, was caused by a transient failure of an RPC
+/// endpoint, such as a timeout, a dropped connection, rate limiting or an unavailable server,
+/// rather than by the request itself.
+pub fn is_transient_rpc_error(err: &(dyn std::error::Error + 'static)) -> bool {
+    std::iter::successors(Some(err), |err| err.source()).any(|err| {
+        let db_err = match err.downcast_ref::<BackendError>() {
+            Some(BackendError::Database(err)) => Some(err),
+            _ => err.downcast_ref::<DatabaseError>(),
+        };
+        match db_err {
+            Some(err) => rpc_error(err).is_some_and(|err| err.chain().any(is_transient_rpc_error)),
+            None => is_transient_error(err),
+        }
+    })
+}
+
+/// Returns the error returned by the RPC endpoint, if any.
+fn rpc_error(err: &DatabaseError) -> Option<&eyre::Error> {
+    match err {
+        DatabaseError::GetAccount(_, err) |
+        DatabaseError::GetStorage(_, _, err) |
+        DatabaseError::GetBlockHash(_, err) |
+        DatabaseError::GetFullBlock(_, err) |
+        DatabaseError::GetTransaction(_, err) |
+        DatabaseError::AnyRequest(err) => Some(err),
+        DatabaseError::MissingCode(_) |
+        DatabaseError::Recv(_) |
+        DatabaseError::Send(_) |
+        DatabaseError::BlockNotFound(_) => None,
+    }
+}
+
+/// The RPC errors of the code running in a [`with_rpc_error_scope`] call.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RpcErrorScope {
+    /// The number of retries requested with `vm.retryOnRpcError`, if any.
+    pub max_retries: Option<u32>,
+    /// Whether a request to an RPC endpoint failed because of a transient error.
+    pub transient_error: bool,
+}
+
+thread_local! {
+    static RPC_ERROR_SCOPE: Cell<Option<RpcErrorScope>> = const { Cell::new(None) };
+}
+
+/// Runs `f`, returning its output along with the RPC errors recorded on this thread while it ran.
+///
+/// Scopes nest: the scope of the caller is restored afterwards, so work stolen by this thread while
+/// `f` is blocked (e.g. by rayon) can't clobber it.
+pub fn with_rpc_error_scope<T>(f: impl FnOnce() -> T) -> (T, RpcErrorScope) {
+    struct Guard(Option<RpcErrorScope>);
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            RPC_ERROR_SCOPE.set(self.0);
+        }
+    }
+
+    let guard = Guard(RPC_ERROR_SCOPE.replace(Some(RpcErrorScope::default())));
+    let output = f();
+    let scope = RPC_ERROR_SCOPE.get().unwrap_or_default();
+    drop(guard);
+    (output, scope)
+}
+
+/// Records the number of retries requested with `vm.retryOnRpcError` in the current
+/// [`RpcErrorScope`].
+pub fn request_rpc_error_retries(max_retries: u32) {
+    update_rpc_error_scope(|scope| scope.max_retries = Some(max_retries));
+}
+
+/// Records the error of a request to an RPC endpoint in the current [`RpcErrorScope`].
+pub fn record_rpc_error(err: &(dyn std::error::Error + 'static)) {
+    update_rpc_error_scope(|scope| {
+        scope.transient_error = scope.transient_error || is_transient_rpc_error(err);
+    });
+}
+
+fn update_rpc_error_scope(f: impl FnOnce(&mut RpcErrorScope)) {
+    if let Some(mut scope) = RPC_ERROR_SCOPE.get() {
+        f(&mut scope);
+        RPC_ERROR_SCOPE.set(Some(scope));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+    use alloy_provider::Provider;
+    use foundry_common::provider::ProviderBuilder;
+    use std::sync::Arc;
+
+    /// Returns the error of a request to an endpoint that refuses connections.
+    async fn connection_refused() -> eyre::Report {
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let provider =
+            ProviderBuilder::new(&format!("http://127.0.0.1:{port}")).max_retry(0).build().unwrap();
+        provider.get_block_number().await.unwrap_err().into()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn classify_transient_rpc_errors() {
+        let refused = connection_refused().await;
+        assert!(is_transient_rpc_error(refused.as_ref()));
+
+        let refused = refused.wrap_err("could not instantiate forked environment");
+        assert!(is_transient_rpc_error(refused.as_ref()));
+
+        let err = DatabaseError::GetAccount(Address::ZERO, Arc::new(refused));
+        assert!(is_transient_rpc_error(&err));
+        assert!(is_transient_rpc_error(&BackendError::Database(err)));
+
+        // Messages that merely look like a transient error are not classified as one.
+        let err = eyre::eyre!("HTTP error 429 with body: rate limited");
+        assert!(!is_transient_rpc_error(err.as_ref()));
+        let err = DatabaseError::GetStorage(Address::ZERO, U256::ZERO, Arc::new(err));
+        assert!(!is_transient_rpc_error(&err));
+        assert!(!is_transient_rpc_error(&BackendError::msg("connection reset")));
+        assert!(!is_transient_rpc_error(&DatabaseError::MissingCode(Default::default())));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn record_rpc_errors_in_scope() {
+        let transient =
+            BackendError::Database(DatabaseError::AnyRequest(Arc::new(connection_refused().await)));
+
+        // Errors outside of a scope are ignored.
+        record_rpc_error(&transient);
+
+        let ((), outer) = with_rpc_error_scope(|| {
+            request_rpc_error_retries(3);
+            let ((), inner) = with_rpc_error_scope(|| record_rpc_error(&transient));
+            assert!(inner.transient_error);
+            assert_eq!(inner.max_retries, None);
+        });
+        assert_eq!(outer.max_retries, Some(3));
+        assert!(!outer.transient_error);
+
+        let ((), scope) = with_rpc_error_scope(|| record_rpc_error(&transient));
+        assert!(scope.transient_error);
+        assert_eq!(scope.max_retries, None);
+    }
+}
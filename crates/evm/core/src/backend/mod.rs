@@ -15,7 +15,7 @@
 use foundry_common::{is_known_system_sender, SYSTEM_TRANSACTION_TYPE};
 pub use foundry_fork_db::{cache::BlockchainDbMeta, BlockchainDb, SharedBackend};
 use revm::{
-    db::{CacheDB, DatabaseRef},
+    db::{AccountState, CacheDB, DatabaseRef},
     inspectors::NoOpInspector,
     precompile::{PrecompileSpecId, Precompiles},
     primitives::{
@@ -33,7 +33,10 @@
 pub use diagnostic::RevertDiagnostic;
 
 mod error;
-pub use error::{BackendError, BackendResult, DatabaseError, DatabaseResult};
+pub use error::{
+    is_transient_rpc_error, record_rpc_error, request_rpc_error_retries, with_rpc_error_scope,
+    BackendError, BackendResult, DatabaseError, DatabaseResult, RpcErrorScope,
+};
 
 mod cow;
 pub use cow::CowBackend;
@@ -218,6 +221,9 @@ fn transact_from_tx(
     /// Returns the Fork url that's currently used in the database, if fork mode is on
     fn active_fork_url(&self) -> Option<String>;
 
+    /// Returns the number of the block the active fork is pinned to, if fork mode is on
+    fn active_fork_block_number(&self) -> Option<u64>;
+
     /// Whether the database is currently in forked mode.
     fn is_forked_mode(&self) -> bool {
         self.active_fork_id().is_some()
@@ -368,6 +374,18 @@ fn ensure_cheatcode_access_forking_mode(&self, account: &Address) -> Result<(),
     /// - Setting a blockhash for future blocks (number > block.number) has no effect
     /// - Setting a blockhash for blocks older than `block.number - 256` has no effect
     fn set_blockhash(&mut self, block_number: U256, block_hash: B256);
+
+    /// Returns `true` if the given account is in forking mode and wasn't modified since it was
+    /// fetched from the remote endpoint of the active fork, including by the given journaled
+    /// state.
+    ///
+    /// Balance, nonce, code and every storage slot loaded so far are compared against the fork's
+    /// cached remote state.
+    fn is_unmodified_fork_account(
+        &self,
+        account: &Address,
+        journaled_state: &JournaledState,
+    ) -> bool;
 }
 
 struct _ObjectSafe(dyn DatabaseExt);
@@ -1305,6 +1323,11 @@ fn active_fork_url(&self) -> Option<String> {
         self.forks.get_fork_url(fork.clone()).ok()?
     }
 
+    fn active_fork_block_number(&self) -> Option<u64> {
+        let fork = self.inner.issued_local_fork_ids.get(&self.active_fork_id()?)?;
+        self.forks.get_fork_block_number(fork.clone()).ok()?
+    }
+
     fn ensure_fork(&self, id: Option<LocalForkId>) -> eyre::Result<LocalForkId> {
         if let Some(id) = id {
             if self.inner.issued_local_fork_ids.contains_key(&id) {
@@ -1468,6 +1491,38 @@ fn set_blockhash(&mut self, block_number: U256, block_hash: B256) {
             self.mem_db.block_hashes.insert(block_number, block_hash);
         }
     }
+
+    fn is_unmodified_fork_account(
+        &self,
+        account: &Address,
+        journaled_state: &JournaledState,
+    ) -> bool {
+        let Some(db) = self.active_fork_db() else { return false };
+        let Ok(remote_info) = db.db.basic_ref(*account) else { return false };
+        let remote_info = remote_info.unwrap_or_default();
+        let is_remote_info = |info: &AccountInfo| {
+            info.balance == remote_info.balance &&
+                info.nonce == remote_info.nonce &&
+                info.code_hash == remote_info.code_hash
+        };
+        let is_remote_slot = |slot: &U256, value: U256| {
+            db.db.storage_ref(*account, *slot).is_ok_and(|remote| remote == value)
+        };
+
+        if let Some(cached) = db.accounts.get(account) {
+            if cached.account_state == AccountState::StorageCleared ||
+                !is_remote_info(&cached.info) ||
+                !cached.storage.iter().all(|(slot, value)| is_remote_slot(slot, *value))
+            {
+                return false;
+            }
+        }
+        journaled_state.state.get(account).is_none_or(|acc| {
+            !acc.is_created() &&
+                is_remote_info(&acc.info) &&
+                acc.storage.iter().all(|(slot, value)| is_remote_slot(slot, value.present_value))
+        })
+    }
 }
 
 impl DatabaseRef for Backend {
@@ -1475,7 +1530,7 @@ impl DatabaseRef for Backend {
 
     fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
         if let Some(db) = self.active_fork_db() {
-            db.basic_ref(address)
+            db.basic_ref(address).inspect_err(|err| record_rpc_error(err))
         } else {
             Ok(self.mem_db.basic_ref(address)?)
         }
@@ -1483,7 +1538,7 @@ fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error
 
     fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
         if let Some(db) = self.active_fork_db() {
-            db.code_by_hash_ref(code_hash)
+            db.code_by_hash_ref(code_hash).inspect_err(|err| record_rpc_error(err))
         } else {
             Ok(self.mem_db.code_by_hash_ref(code_hash)?)
         }
@@ -1491,7 +1546,7 @@ fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
 
     fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
         if let Some(db) = self.active_fork_db() {
-            DatabaseRef::storage_ref(db, address, index)
+            DatabaseRef::storage_ref(db, address, index).inspect_err(|err| record_rpc_error(err))
         } else {
             Ok(DatabaseRef::storage_ref(&self.mem_db, address, index)?)
         }
@@ -1499,7 +1554,7 @@ fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error
 
     fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
         if let Some(db) = self.active_fork_db() {
-            db.block_hash_ref(number)
+            db.block_hash_ref(number).inspect_err(|err| record_rpc_error(err))
         } else {
             Ok(self.mem_db.block_hash_ref(number)?)
         }
@@ -1520,7 +1575,7 @@ impl Database for Backend {
     type Error = DatabaseError;
     fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
         if let Some(db) = self.active_fork_db_mut() {
-            Ok(db.basic(address)?)
+            db.basic(address).inspect_err(|err| record_rpc_error(err))
         } else {
             Ok(self.mem_db.basic(address)?)
         }
@@ -1528,7 +1583,7 @@ fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error
 
     fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
         if let Some(db) = self.active_fork_db_mut() {
-            Ok(db.code_by_hash(code_hash)?)
+            db.code_by_hash(code_hash).inspect_err(|err| record_rpc_error(err))
         } else {
             Ok(self.mem_db.code_by_hash(code_hash)?)
         }
@@ -1536,7 +1591,7 @@ fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
 
     fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
         if let Some(db) = self.active_fork_db_mut() {
-            Ok(Database::storage(db, address, index)?)
+            Database::storage(db, address, index).inspect_err(|err| record_rpc_error(err))
         } else {
             Ok(Database::storage(&mut self.mem_db, address, index)?)
         }
@@ -1544,7 +1599,7 @@ fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error
 
     fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
         if let Some(db) = self.active_fork_db_mut() {
-            Ok(db.block_hash(number)?)
+            db.block_hash(number).inspect_err(|err| record_rpc_error(err))
         } else {
             Ok(self.mem_db.block_hash(number)?)
         }
@@ -2024,6 +2079,7 @@ async fn can_read_write_cache() {
             url: endpoint.to_string(),
             env: env.clone(),
             evm_opts,
+            evict_cache: None,
         };
 
         let backend = Backend::spawn(Some(fork));
@@ -40,6 +40,9 @@
 /// Test timeout return value.
 pub const TEST_TIMEOUT: &str = "FOUNDRY::TEST_TIMEOUT";
 
+/// Test cancelled return value, see `FailFast`.
+pub const TEST_CANCELLED: &str = "FOUNDRY::TEST_CANCELLED";
+
 /// The address that deploys the default CREATE2 deployer contract.
 pub const DEFAULT_CREATE2_DEPLOYER_DEPLOYER: Address =
     address!("3fAB184622Dc19b6109349B94811493BF2a45362");
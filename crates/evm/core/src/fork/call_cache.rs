@@ -0,0 +1,228 @@
+//! Memoization of the static calls made against forks.
+
+use super::ForkId;
+use alloy_primitives::{Address, Bytes, Keccak256, B256};
+use parking_lot::Mutex;
+use revm::primitives::Env;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+/// The name of the file, relative to the project's cache directory, that stores fork calls.
+pub const FORK_CALL_CACHE_FILE: &str = "fork-calls.json";
+
+/// The result of a successful static call.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedCall {
+    /// The returned data.
+    pub output: Bytes,
+    /// The gas used by the call.
+    pub gas_used: u64,
+    /// The accounts whose code ran, or whose balance or code was read, during the call.
+    pub accounts: Vec<Address>,
+}
+
+/// Results of static calls made against forks, shared by all the executors of a run.
+///
+/// Calls are keyed by the fork they were made on, the environment, the caller, the called address
+/// and the calldata. Results are only recorded, and only reused, while every account whose code
+/// ran or whose balance or code was read during the call is unmodified compared to the fork, so a
+/// hit always returns what executing the call would have returned.
+///
+/// Cloning is cheap and all clones share the same cached calls and statistics.
+#[derive(Clone, Debug, Default)]
+pub struct ForkCallCache {
+    inner: Arc<ForkCallCacheInner>,
+}
+
+#[derive(Debug, Default)]
+struct ForkCallCacheInner {
+    /// The cached calls, and whether they were used during this run.
+    calls: Mutex<HashMap<B256, (CachedCall, bool)>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// Statistics about a [`ForkCallCache`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ForkCallCacheStats {
+    /// The number of calls answered from the cache, without being executed.
+    pub hits: u64,
+    /// The number of cacheable calls that had to be executed.
+    pub misses: u64,
+    /// The number of cached calls.
+    pub entries: usize,
+}
+
+impl ForkCallCache {
+    /// Creates a new, empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the calls cached by a previous run from the given file, or starts empty if it does
+    /// not exist.
+    pub fn load(path: &Path) -> eyre::Result<Self> {
+        let calls: BTreeMap<B256, CachedCall> =
+            if path.exists() { foundry_common::fs::read_json_file(path)? } else { BTreeMap::new() };
+        let cache = Self::new();
+        cache.inner.calls.lock().extend(calls.into_iter().map(|(key, call)| (key, (call, false))));
+        Ok(cache)
+    }
+
+    /// Writes the calls that were cached or used during this run to the given file.
+    ///
+    /// Calls that were loaded but not used are dropped, so that the file doesn't keep growing
+    /// with the results of blocks that are no longer forked.
+    pub fn save(&self, path: &Path) -> eyre::Result<()> {
+        let calls = self
+            .inner
+            .calls
+            .lock()
+            .iter()
+            .filter(|(_, (_, used))| *used)
+            .map(|(key, (call, _))| (*key, call.clone()))
+            .collect::<BTreeMap<_, _>>();
+        if let Some(parent) = path.parent() {
+            foundry_common::fs::create_dir_all(parent)?;
+        }
+        foundry_common::fs::write_json_file(path, &calls)?;
+        Ok(())
+    }
+
+    /// Returns the key of a call.
+    ///
+    /// Everything the called code can observe besides the state of the accounts is part of the
+    /// key: the chain, block and transaction environments, and the call itself.
+    pub fn key(
+        fork: &ForkId,
+        env: &Env,
+        caller: Address,
+        target: Address,
+        bytecode_address: Address,
+        input: &[u8],
+    ) -> B256 {
+        let mut hasher = Keccak256::new();
+        hasher.update(fork.as_str().len().to_be_bytes());
+        hasher.update(fork.as_str());
+        hasher.update(env.cfg.chain_id.to_be_bytes());
+
+        let block = &env.block;
+        hasher.update(block.number.to_be_bytes::<32>());
+        hasher.update(block.coinbase);
+        hasher.update(block.timestamp.to_be_bytes::<32>());
+        hasher.update(block.gas_limit.to_be_bytes::<32>());
+        hasher.update(block.basefee.to_be_bytes::<32>());
+        hasher.update(block.difficulty.to_be_bytes::<32>());
+        hasher.update(block.prevrandao.unwrap_or_default());
+        hasher.update(block.get_blob_gasprice().unwrap_or_default().to_be_bytes());
+
+        let tx = &env.tx;
+        hasher.update(tx.caller);
+        hasher.update(tx.gas_price.to_be_bytes::<32>());
+        hasher.update(tx.blob_hashes.len().to_be_bytes());
+        for hash in &tx.blob_hashes {
+            hasher.update(hash);
+        }
+
+        hasher.update(caller);
+        hasher.update(target);
+        hasher.update(bytecode_address);
+        hasher.update(input);
+        hasher.finalize()
+    }
+
+    /// Returns the cached result of a call if it is still valid, recording a hit or a miss.
+    pub fn get(
+        &self,
+        key: &B256,
+        is_valid: impl FnOnce(&CachedCall) -> bool,
+    ) -> Option<CachedCall> {
+        let call = self.inner.calls.lock().get_mut(key).filter(|(call, _)| is_valid(call)).map(
+            |(call, used)| {
+                *used = true;
+                call.clone()
+            },
+        );
+        let counter = if call.is_some() { &self.inner.hits } else { &self.inner.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        call
+    }
+
+    /// Caches the result of a call.
+    pub fn insert(&self, key: B256, call: CachedCall) {
+        self.inner.calls.lock().insert(key, (call, true));
+    }
+
+    /// Returns statistics about the use of the cache so far.
+    pub fn stats(&self) -> ForkCallCacheStats {
+        ForkCallCacheStats {
+            hits: self.inner.hits.load(Ordering::Relaxed),
+            misses: self.inner.misses.load(Ordering::Relaxed),
+            entries: self.inner.calls.lock().len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fork_calls_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache").join(FORK_CALL_CACHE_FILE);
+        let fork = ForkId::new("http://localhost:8545", Some(1));
+        let env = Env::default();
+        let key = |input: &[u8]| {
+            ForkCallCache::key(&fork, &env, Address::ZERO, Address::ZERO, Address::ZERO, input)
+        };
+        let call = CachedCall {
+            output: Bytes::from_static(&[0x12]),
+            gas_used: 2_000,
+            accounts: vec![Address::ZERO],
+        };
+
+        let cache = ForkCallCache::load(&path).unwrap();
+        assert_eq!(cache.get(&key(b"decimals"), |_| true), None);
+        cache.insert(key(b"decimals"), call.clone());
+        cache.insert(key(b"symbol"), call.clone());
+        assert_eq!(cache.get(&key(b"decimals"), |_| true), Some(call.clone()));
+        assert_eq!(cache.get(&key(b"decimals"), |_| false), None);
+        assert_eq!(cache.stats(), ForkCallCacheStats { hits: 1, misses: 2, entries: 2 });
+        cache.save(&path).unwrap();
+
+        // Calls that aren't used by a run aren't saved again.
+        let cache = ForkCallCache::load(&path).unwrap();
+        assert_eq!(cache.get(&key(b"symbol"), |_| true), Some(call));
+        cache.save(&path).unwrap();
+        let cache = ForkCallCache::load(&path).unwrap();
+        assert_eq!(cache.stats().entries, 1);
+        assert_eq!(cache.get(&key(b"decimals"), |_| true), None);
+    }
+
+    #[test]
+    fn key_depends_on_env() {
+        let fork = ForkId::new("http://localhost:8545", Some(1));
+        let key = |env: &Env| {
+            ForkCallCache::key(&fork, env, Address::ZERO, Address::ZERO, Address::ZERO, b"balance")
+        };
+        let env = Env::default();
+        let mut origin = env.clone();
+        origin.tx.caller = Address::repeat_byte(1);
+        let mut gas_price = env.clone();
+        gas_price.tx.gas_price = alloy_primitives::U256::from(1);
+        let mut coinbase = env.clone();
+        coinbase.block.coinbase = Address::repeat_byte(1);
+        for changed in [origin, gas_price, coinbase] {
+            assert_ne!(key(&env), key(&changed));
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use super::opts::EvmOpts;
+use foundry_config::cache::StorageCachingConfig;
 use revm::primitives::Env;
 
 mod init;
@@ -9,6 +10,15 @@
 mod multi;
 pub use multi::{ForkId, MultiFork, MultiForkHandler};
 
+mod call_cache;
+pub use call_cache::{CachedCall, ForkCallCache, ForkCallCacheStats, FORK_CALL_CACHE_FILE};
+
+mod pins;
+pub use pins::{ForkPins, ForkPinsFile, FORK_PINS_FILE};
+
+mod prefetch;
+pub use prefetch::{ForkPrefetchHints, FORK_PREFETCH_FILE};
+
 /// Represents a _fork_ of a remote chain whose data is available only via the `url` endpoint.
 #[derive(Clone, Debug)]
 pub struct CreateFork {
@@ -20,4 +30,7 @@ pub struct CreateFork {
     pub env: Env,
     /// All env settings as configured by the user
     pub evm_opts: EvmOpts,
+    /// The caching config whose eviction policy is applied to the cached blocks once the forked
+    /// block is marked as used, if caching is enabled
+    pub evict_cache: Option<StorageCachingConfig>,
 }
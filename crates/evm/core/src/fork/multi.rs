@@ -3,12 +3,13 @@
 //! The design is similar to the single `SharedBackend`, `BackendHandler` but supports multiple
 //! concurrently active pairs at once.
 
-use super::CreateFork;
+use super::{CreateFork, ForkPrefetchHints};
+use crate::backend::record_rpc_error;
 use alloy_consensus::BlockHeader;
 use alloy_primitives::{map::HashMap, U256};
 use alloy_provider::network::BlockResponse;
 use foundry_common::provider::{ProviderBuilder, RetryProvider};
-use foundry_config::Config;
+use foundry_config::{cache::mark_cached_block_used, Config};
 use foundry_fork_db::{cache::BlockchainDbMeta, BackendHandler, BlockchainDb, SharedBackend};
 use futures::{
     channel::mpsc::{channel, Receiver, Sender},
@@ -122,7 +123,7 @@ pub fn create_fork(&self, fork: CreateFork) -> eyre::Result<(ForkId, SharedBacke
         let (sender, rx) = oneshot_channel();
         let req = Request::CreateFork(Box::new(fork), sender);
         self.handler.clone().try_send(req).map_err(|e| eyre::eyre!("{:?}", e))?;
-        rx.recv()?
+        rx.recv()?.inspect_err(|err| record_rpc_error(err.as_ref()))
     }
 
     /// Rolls the block of the fork.
@@ -137,7 +138,7 @@ pub fn roll_fork(
         let (sender, rx) = oneshot_channel();
         let req = Request::RollFork(fork, block, sender);
         self.handler.clone().try_send(req).map_err(|e| eyre::eyre!("{:?}", e))?;
-        rx.recv()?
+        rx.recv()?.inspect_err(|err| record_rpc_error(err.as_ref()))
     }
 
     /// Returns the `Env` of the given fork, if any.
@@ -179,6 +180,16 @@ pub fn get_fork_url(&self, id: impl Into<ForkId>) -> eyre::Result<Option<String>
         self.handler.clone().try_send(req).map_err(|e| eyre::eyre!("{:?}", e))?;
         Ok(rx.recv()?)
     }
+
+    /// Returns the number of the block the fork is pinned to if it exists.
+    ///
+    /// Unlike the block number of the fork's `Env`, this is not affected by `roll`.
+    pub fn get_fork_block_number(&self, id: impl Into<ForkId>) -> eyre::Result<Option<u64>> {
+        let (sender, rx) = oneshot_channel();
+        let req = Request::GetForkBlockNumber(id.into(), sender);
+        self.handler.clone().try_send(req).map_err(|e| eyre::eyre!("{:?}", e))?;
+        Ok(rx.recv()?)
+    }
 }
 
 type Handler = BackendHandler<Arc<RetryProvider>>;
@@ -205,6 +216,8 @@ enum Request {
     ShutDown(OneshotSender<()>),
     /// Returns the Fork Url for the `ForkId` if it exists.
     GetForkUrl(ForkId, OneshotSender<Option<String>>),
+    /// Returns the number of the block the fork is pinned to for the `ForkId` if it exists.
+    GetForkBlockNumber(ForkId, OneshotSender<Option<u64>>),
 }
 
 enum ForkTask {
@@ -309,6 +322,22 @@ fn update_block(&mut self, fork_id: ForkId, block_number: U256, block_timestamp:
         }
     }
 
+    /// Records the state read from the forks with storage caching enabled, so that it's prefetched
+    /// when their chains are forked again.
+    fn save_prefetch_hints(&self) {
+        let mut hints = HashMap::<u64, ForkPrefetchHints>::default();
+        for fork in self.forks.values().filter(|fork| fork.opts.enable_caching) {
+            hints.entry(fork.opts.env.cfg.chain_id).or_default().extend_from(&fork.db);
+        }
+        for (chain_id, mut hints) in hints {
+            let Some(path) = ForkPrefetchHints::path(chain_id) else { continue };
+            hints.merge(ForkPrefetchHints::load(&path));
+            if let Err(err) = hints.save(&path) {
+                warn!(target: "fork::multi", %err, "failed to save the prefetch hints");
+            }
+        }
+    }
+
     fn on_request(&mut self, req: Request) {
         match req {
             Request::CreateFork(fork, sender) => self.create_fork(*fork, sender),
@@ -334,6 +363,7 @@ fn on_request(&mut self, req: Request) {
             }
             Request::ShutDown(sender) => {
                 trace!(target: "fork::multi", "received shutdown signal");
+                self.save_prefetch_hints();
                 // We're emptying all fork backends, this way we ensure all caches get flushed.
                 self.forks.clear();
                 self.handlers.clear();
@@ -343,6 +373,9 @@ fn on_request(&mut self, req: Request) {
                 let fork = self.forks.get(&fork_id).map(|f| f.opts.url.clone());
                 let _ = sender.send(fork);
             }
+            Request::GetForkBlockNumber(fork_id, sender) => {
+                let _ = sender.send(self.forks.get(&fork_id).map(|f| f.block_number));
+            }
         }
     }
 }
@@ -457,14 +490,23 @@ struct CreatedFork {
     opts: CreateFork,
     /// Copy of the sender.
     backend: SharedBackend,
+    /// The database of the backend.
+    db: BlockchainDb,
+    /// The number of the block the fork is pinned to.
+    block_number: u64,
     /// How many consumers there are, since a `SharedBacked` can be used by multiple
     /// consumers.
     num_senders: Arc<AtomicUsize>,
 }
 
 impl CreatedFork {
-    pub fn new(opts: CreateFork, backend: SharedBackend) -> Self {
-        Self { opts, backend, num_senders: Arc::new(AtomicUsize::new(1)) }
+    pub fn new(
+        opts: CreateFork,
+        backend: SharedBackend,
+        db: BlockchainDb,
+        block_number: u64,
+    ) -> Self {
+        Self { opts, backend, db, block_number, num_senders: Arc::new(AtomicUsize::new(1)) }
     }
 
     /// Increment senders and return unique identifier of the fork.
@@ -531,10 +573,29 @@ async fn create_fork(mut fork: CreateFork) -> eyre::Result<(ForkId, CreatedFork,
     } else {
         None
     };
+    if let Some(path) = &cache_path {
+        let _ = mark_cached_block_used(path);
+    }
+    // Evict only after marking the forked block as used, so that it's the last one evicted.
+    if let Some(caching) = &fork.evict_cache {
+        caching.evict_lazily();
+    }
 
     let db = BlockchainDb::new(meta, cache_path);
-    let (backend, handler) = SharedBackend::new(provider, db, Some(number.into()));
-    let fork = CreatedFork::new(fork, backend);
+
+    // Fetch the state read by previous runs in the background.
+    if fork.enable_caching {
+        let hints = ForkPrefetchHints::path(fork.env.cfg.chain_id)
+            .map(|path| ForkPrefetchHints::load(&path))
+            .unwrap_or_default();
+        if !hints.is_empty() {
+            let (provider, db) = (provider.clone(), db.clone());
+            tokio::spawn(async move { hints.prefetch(&provider, &db, number.into()).await });
+        }
+    }
+
+    let (backend, handler) = SharedBackend::new(provider, db.clone(), Some(number.into()));
+    let fork = CreatedFork::new(fork, backend, db, number);
     let fork_id = ForkId::new(&fork.opts.url, number.into());
 
     Ok((fork_id, fork, handler))
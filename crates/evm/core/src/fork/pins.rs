@@ -0,0 +1,102 @@
+//! Recording and reuse of the block numbers that forks of `latest` resolved to.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::Path, sync::Arc};
+
+/// The name of the file, relative to the project's cache directory, that stores fork pins.
+pub const FORK_PINS_FILE: &str = "fork-pins.toml";
+
+/// Block numbers of forks created without an explicit block, keyed by chain ID.
+///
+/// Forks that don't specify a block number are created at the `latest` block of the remote
+/// endpoint. These block numbers are recorded so they can be persisted and, in pinning mode,
+/// reused by subsequent runs instead of `latest`, making fork tests reproducible.
+///
+/// Cloning is cheap and all clones share the same recorded pins.
+#[derive(Clone, Debug, Default)]
+pub struct ForkPins {
+    /// Whether previously recorded pins should be used instead of `latest`.
+    pin: bool,
+    inner: Arc<Mutex<ForkPinsFile>>,
+}
+
+/// On-disk representation of [`ForkPins`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForkPinsFile {
+    /// Block number per chain ID.
+    #[serde(default)]
+    pub pins: BTreeMap<String, u64>,
+}
+
+impl ForkPins {
+    /// Creates a new set of pins from previously recorded ones.
+    pub fn new(pins: ForkPinsFile, pin: bool) -> Self {
+        Self { pin, inner: Arc::new(Mutex::new(pins)) }
+    }
+
+    /// Loads the pins from the given file, or starts empty if it does not exist.
+    pub fn load(path: &Path, pin: bool) -> eyre::Result<Self> {
+        let pins = if path.exists() {
+            toml::from_str(&foundry_common::fs::read_to_string(path)?)?
+        } else {
+            ForkPinsFile::default()
+        };
+        Ok(Self::new(pins, pin))
+    }
+
+    /// Writes the recorded pins to the given file.
+    pub fn save(&self, path: &Path) -> eyre::Result<()> {
+        let pins = self.inner.lock().clone();
+        if let Some(parent) = path.parent() {
+            foundry_common::fs::create_dir_all(parent)?;
+        }
+        foundry_common::fs::write(path, toml::to_string_pretty(&pins)?)?;
+        Ok(())
+    }
+
+    /// Returns `true` if pinned blocks are used instead of `latest`.
+    pub fn is_pinning(&self) -> bool {
+        self.pin
+    }
+
+    /// Returns the pinned block number for the given chain, if pinning is enabled.
+    pub fn pinned(&self, chain_id: u64) -> Option<u64> {
+        if !self.pin {
+            return None;
+        }
+        self.inner.lock().pins.get(&chain_id.to_string()).copied()
+    }
+
+    /// Records the block number a fork of `latest` on the given chain resolved to.
+    pub fn record(&self, chain_id: u64, block: u64) {
+        self.inner.lock().pins.insert(chain_id.to_string(), block);
+    }
+
+    /// Returns a snapshot of all recorded pins.
+    pub fn pins(&self) -> ForkPinsFile {
+        self.inner.lock().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pins_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache").join(FORK_PINS_FILE);
+
+        let pins = ForkPins::load(&path, false).unwrap();
+        pins.record(1, 100);
+        pins.record(10, 200);
+        assert_eq!(pins.pinned(1), None);
+        pins.save(&path).unwrap();
+
+        let pins = ForkPins::load(&path, true).unwrap();
+        assert_eq!(pins.pinned(1), Some(100));
+        assert_eq!(pins.pinned(10), Some(200));
+        assert_eq!(pins.pinned(5), None);
+    }
+}
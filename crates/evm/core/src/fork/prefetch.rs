@@ -0,0 +1,228 @@
+//! Prefetching of the fork state read by previous runs.
+//!
+//! Concurrent reads of the same account or storage slot are already coalesced into a single
+//! request by the shared fork backend, but the reads of a test still happen one after the other,
+//! each waiting for the previous one. The state read from the forks of a chain is recorded, and
+//! fetched concurrently as soon as the chain is forked again.
+
+use alloy_primitives::{Address, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::BlockId;
+use foundry_common::provider::RetryProvider;
+use foundry_config::Config;
+use foundry_fork_db::BlockchainDb;
+use futures::{future, stream, StreamExt};
+use revm::primitives::{AccountInfo, Bytecode};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    future::IntoFuture,
+    path::{Path, PathBuf},
+};
+use tokio::sync::Semaphore;
+
+/// The name of the file, relative to the cache directory of a chain, that stores the state read
+/// from its forks.
+pub const FORK_PREFETCH_FILE: &str = "prefetch.json";
+
+/// The maximum number of storage slots recorded per chain.
+const MAX_SLOTS: usize = 10_000;
+
+/// The maximum number of requests in flight while prefetching, shared by the prefetches of all
+/// forks so that creating or rolling many forks doesn't multiply it.
+const MAX_CONCURRENT_REQUESTS: usize = 32;
+
+/// Permits for [`MAX_CONCURRENT_REQUESTS`] requests, one per request in flight.
+static REQUEST_PERMITS: Semaphore = Semaphore::const_new(MAX_CONCURRENT_REQUESTS);
+
+/// The accounts and storage slots read from the forks of a chain by previous runs.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForkPrefetchHints {
+    /// The storage slots read from every account.
+    #[serde(default)]
+    pub accounts: BTreeMap<Address, BTreeSet<U256>>,
+}
+
+impl ForkPrefetchHints {
+    /// Returns the path of the hints of the given chain.
+    pub fn path(chain_id: u64) -> Option<PathBuf> {
+        Config::foundry_chain_cache_dir(chain_id).map(|dir| dir.join(FORK_PREFETCH_FILE))
+    }
+
+    /// Loads the hints from the given file, or starts empty if it does not exist or is invalid.
+    pub fn load(path: &Path) -> Self {
+        if !path.exists() {
+            return Self::default();
+        }
+        foundry_common::fs::read_json_file(path).unwrap_or_default()
+    }
+
+    /// Writes the hints to the given file.
+    pub fn save(&self, path: &Path) -> eyre::Result<()> {
+        if let Some(parent) = path.parent() {
+            foundry_common::fs::create_dir_all(parent)?;
+        }
+        foundry_common::fs::write_json_file(path, self)?;
+        Ok(())
+    }
+
+    /// Returns `true` if no account is recorded.
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// Returns the number of recorded storage slots.
+    pub fn num_slots(&self) -> usize {
+        self.accounts.values().map(BTreeSet::len).sum()
+    }
+
+    /// Records the accounts and storage slots loaded in the database.
+    pub fn extend_from(&mut self, db: &BlockchainDb) {
+        for address in db.accounts().read().keys() {
+            self.accounts.entry(*address).or_default();
+        }
+        for (address, storage) in db.storage().read().iter() {
+            self.insert_slots(*address, storage.keys().copied());
+        }
+    }
+
+    /// Records the accounts and storage slots of other hints, after the ones already recorded.
+    pub fn merge(&mut self, other: Self) {
+        for (address, slots) in other.accounts {
+            self.insert_slots(address, slots);
+        }
+    }
+
+    /// Records storage slots of an account, until there are [`MAX_SLOTS`] of them.
+    fn insert_slots(&mut self, address: Address, slots: impl IntoIterator<Item = U256>) {
+        let mut num_slots = self.num_slots();
+        let recorded = self.accounts.entry(address).or_default();
+        for slot in slots {
+            if num_slots >= MAX_SLOTS {
+                return;
+            }
+            num_slots += recorded.insert(slot) as usize;
+        }
+    }
+
+    /// Fetches the recorded accounts and storage slots that are missing from the database, at the
+    /// given block.
+    ///
+    /// Failed requests are ignored: the values are then fetched when they're read.
+    pub async fn prefetch(&self, provider: &RetryProvider, db: &BlockchainDb, block: BlockId) {
+        let accounts = {
+            let loaded = db.accounts().read();
+            self.accounts.keys().filter(|address| !loaded.contains_key(*address)).copied().collect()
+        };
+        let slots = {
+            let loaded = db.storage().read();
+            self.accounts
+                .iter()
+                .flat_map(|(address, slots)| slots.iter().map(move |slot| (*address, *slot)))
+                .filter(|(address, slot)| {
+                    !loaded.get(address).is_some_and(|storage| storage.contains_key(slot))
+                })
+                .collect()
+        };
+        trace!(target: "fork::prefetch", ?block, "prefetching fork state");
+
+        let accounts = fetch(accounts, 3, |address: Address| async move {
+            let (balance, nonce, code) = tokio::try_join!(
+                provider.get_balance(address).block_id(block).into_future(),
+                provider.get_transaction_count(address).block_id(block).into_future(),
+                provider.get_code_at(address).block_id(block).into_future(),
+            )
+            .ok()?;
+            let code = Bytecode::new_raw(code);
+            Some((address, AccountInfo::new(balance, nonce, code.hash_slow(), code)))
+        })
+        .await;
+        let num_accounts = accounts.len();
+        db.accounts().write().extend(accounts);
+
+        let slots = fetch(slots, 1, |(address, slot): (Address, U256)| async move {
+            let value = provider.get_storage_at(address, slot).block_id(block).await.ok()?;
+            Some((address, slot, value))
+        })
+        .await;
+        let num_slots = slots.len();
+        let mut storage = db.storage().write();
+        for (address, slot, value) in slots {
+            storage.entry(address).or_default().insert(slot, value);
+        }
+        trace!(target: "fork::prefetch", num_accounts, num_slots, "prefetched fork state");
+    }
+}
+
+/// Runs the requests for all items, each sending `num_requests` RPC requests, with up to
+/// [`MAX_CONCURRENT_REQUESTS`] in flight across all prefetches, returning the successful results.
+async fn fetch<T, R, F, Fut>(items: Vec<T>, num_requests: u32, request: F) -> Vec<R>
+where
+    F: Fn(T) -> Fut,
+    Fut: std::future::Future<Output = Option<R>>,
+{
+    let request = &request;
+    stream::iter(items)
+        .map(|item| async move {
+            let _permit = REQUEST_PERMITS.acquire_many(num_requests).await.ok()?;
+            request(item).await
+        })
+        .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+        .filter_map(future::ready)
+        .collect()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use foundry_fork_db::cache::BlockchainDbMeta;
+    use revm::primitives::Env;
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    #[test]
+    fn hints_roundtrip() {
+        let db = BlockchainDb::new(BlockchainDbMeta::new(Env::default(), String::new()), None);
+        let (a, b) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        db.accounts().write().insert(a, AccountInfo::default());
+        db.storage().write().entry(b).or_default().insert(U256::from(1), U256::from(2));
+
+        let mut hints = ForkPrefetchHints::default();
+        hints.extend_from(&db);
+        assert_eq!(hints.accounts.len(), 2);
+        assert!(hints.accounts[&a].is_empty());
+        assert_eq!(hints.accounts[&b], BTreeSet::from([U256::from(1)]));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("1").join(FORK_PREFETCH_FILE);
+        assert!(ForkPrefetchHints::load(&path).is_empty());
+        hints.save(&path).unwrap();
+        assert_eq!(ForkPrefetchHints::load(&path), hints);
+    }
+    #[tokio::test(flavor = "multi_thread")]
+    async fn bounds_concurrent_requests() {
+        let (in_flight, max_in_flight) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let request = |item: usize| {
+            let (in_flight, max_in_flight) = (&in_flight, &max_in_flight);
+            async move {
+                let num = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(num, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(1)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                Some(item)
+            }
+        };
+
+        // the prefetches of concurrently created forks share the limit
+        let (accounts, slots) = tokio::join!(
+            fetch((0..100).collect(), 3, request),
+            fetch((0..1000).collect(), 1, request),
+        );
+        assert_eq!(accounts.len(), 100);
+        assert_eq!(slots.len(), 1000);
+        assert!(max_in_flight.into_inner() <= MAX_CONCURRENT_REQUESTS);
+    }
+}
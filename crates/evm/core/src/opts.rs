@@ -1,5 +1,8 @@
 use super::fork::environment;
-use crate::{constants::DEFAULT_CREATE2_DEPLOYER, fork::CreateFork};
+use crate::{
+    constants::DEFAULT_CREATE2_DEPLOYER,
+    fork::{CreateFork, ForkCallCache, ForkPins},
+};
 use alloy_primitives::{Address, B256, U256};
 use alloy_provider::{network::AnyRpcBlock, Provider};
 use eyre::WrapErr;
@@ -73,6 +76,14 @@ pub struct EvmOpts {
 
     /// The CREATE2 deployer's address.
     pub create2_deployer: Address,
+
+    /// Records, and optionally reuses, the blocks that forks of `latest` resolved to.
+    #[serde(skip)]
+    pub fork_pins: Option<ForkPins>,
+
+    /// Caches the results of static calls made against forks, if enabled.
+    #[serde(skip)]
+    pub fork_call_cache: Option<ForkCallCache>,
 }
 
 impl Default for EvmOpts {
@@ -97,6 +108,8 @@ fn default() -> Self {
             disable_block_gas_limit: false,
             odyssey: false,
             create2_deployer: DEFAULT_CREATE2_DEPLOYER,
+            fork_pins: None,
+            fork_call_cache: None,
         }
     }
 }
@@ -123,12 +136,24 @@ pub async fn fork_evm_env(
         let provider = ProviderBuilder::new(fork_url)
             .compute_units_per_second(self.get_compute_units_per_second())
             .build()?;
-        environment(
+
+        // Forks of `latest` are recorded, or resolved to their pinned block if pinning is enabled.
+        let mut fork_block_number = self.fork_block_number;
+        let pins = match (&self.fork_pins, fork_block_number) {
+            (Some(pins), None) => {
+                let chain_id = provider.get_chain_id().await.wrap_err("failed to get chain id")?;
+                fork_block_number = pins.pinned(chain_id);
+                Some((pins, chain_id))
+            }
+            _ => None,
+        };
+
+        let (env, block) = environment(
             &provider,
             self.memory_limit,
             self.env.gas_price.map(|v| v as u128),
             self.env.chain_id,
-            self.fork_block_number,
+            fork_block_number,
             self.sender,
             self.disable_block_gas_limit,
         )
@@ -141,7 +166,13 @@ pub async fn fork_evm_env(
                 }
             }
             msg
-        })
+        })?;
+
+        if let Some((pins, chain_id)) = pins {
+            pins.record(chain_id, block.header.number);
+        }
+
+        Ok((env, block))
     }
 
     /// Returns the `revm::Env` configured with only local settings
@@ -193,7 +224,8 @@ pub fn local_evm_env(&self) -> revm::primitives::Env {
     pub fn get_fork(&self, config: &Config, env: revm::primitives::Env) -> Option<CreateFork> {
         let url = self.fork_url.clone()?;
         let enable_caching = config.enable_caching(&url, env.cfg.chain_id);
-        Some(CreateFork { url, enable_caching, env, evm_opts: self.clone() })
+        let evict_cache = enable_caching.then(|| config.rpc_storage_caching.clone());
+        Some(CreateFork { url, enable_caching, env, evm_opts: self.clone(), evict_cache })
     }
 
     /// Returns the gas limit to use
@@ -1,4 +1,4 @@
-use super::{CoverageItem, CoverageItemKind, SourceLocation};
+use super::{vyper::VyperVisitor, yul::YulVisitor, CoverageItem, CoverageItemKind, SourceLocation};
 use alloy_primitives::map::HashMap;
 use foundry_common::TestFunctionExt;
 use foundry_compilers::artifacts::{
@@ -529,6 +529,7 @@ impl SourceAnalysis {
     ///
     /// Coverage items are found by:
     /// - Walking the AST of each contract (except interfaces)
+    /// - Walking the source code of each Vyper contract and Yul object, which have no AST
     /// - Recording the items of each contract
     ///
     /// Each coverage item contains relevant information to find opcodes corresponding to them: the
@@ -542,7 +543,22 @@ pub fn new(data: &SourceFiles<'_>) -> eyre::Result<Self> {
         let mut sourced_items = data
             .sources
             .par_iter()
-            .flat_map_iter(|(&source_id, SourceFile { source, ast })| {
+            .flat_map_iter(|(&source_id, SourceFile { source, ast, name, is_yul })| {
+                if *is_yul {
+                    let mut visitor = YulVisitor::new(source_id, &source.content, name);
+                    if let Err(err) = visitor.visit_source() {
+                        warn!(%err, %name, "could not analyze Yul source");
+                    }
+                    return vec![Ok((source_id, visitor.items))];
+                }
+
+                let Some(ast) = ast else {
+                    let mut visitor = VyperVisitor::new(source_id, &source.content, name);
+                    visitor.visit_source();
+                    let items = filter_test_items(visitor.items);
+                    return vec![Ok((source_id, items))];
+                };
+
                 let items = ast.nodes.iter().map(move |node| {
                     if !matches!(node.node_type, NodeType::ContractDefinition) {
                         return Ok(vec![]);
@@ -562,22 +578,9 @@ pub fn new(data: &SourceFiles<'_>) -> eyre::Result<Self> {
 
                     let mut visitor = ContractVisitor::new(source_id, &source.content, &name);
                     visitor.visit_contract(node)?;
-                    let mut items = visitor.items;
-
-                    let is_test = items.iter().any(|item| {
-                        if let CoverageItemKind::Function { name } = &item.kind {
-                            name.is_any_test()
-                        } else {
-                            false
-                        }
-                    });
-                    if is_test {
-                        items.clear();
-                    }
-
-                    Ok(items)
+                    Ok(filter_test_items(visitor.items))
                 });
-                items.map(move |items| items.map(|items| (source_id, items)))
+                items.map(move |items| items.map(|items| (source_id, items))).collect()
             })
             .collect::<eyre::Result<Vec<(usize, Vec<CoverageItem>)>>>()?;
 
@@ -634,6 +637,21 @@ pub fn get(&self, item_id: u32) -> Option<&CoverageItem> {
     }
 }
 
+/// Returns no items if the given items belong to a test contract.
+fn filter_test_items(mut items: Vec<CoverageItem>) -> Vec<CoverageItem> {
+    let is_test = items.iter().any(|item| {
+        if let CoverageItemKind::Function { name } = &item.kind {
+            name.is_any_test()
+        } else {
+            false
+        }
+    });
+    if is_test {
+        items.clear();
+    }
+    items
+}
+
 /// A list of versioned sources and their ASTs.
 #[derive(Debug, Default)]
 pub struct SourceFiles<'a> {
@@ -647,5 +665,12 @@ pub struct SourceFile<'a> {
     /// The source code.
     pub source: Source,
     /// The AST of the source code.
-    pub ast: &'a Ast,
+    ///
+    /// This is `None` for Vyper and Yul sources, which are analyzed from their source code
+    /// instead.
+    pub ast: Option<&'a Ast>,
+    /// The name of the contract defined in the source, used for Vyper and Yul sources.
+    pub name: Arc<str>,
+    /// Whether this is a standalone Yul source.
+    pub is_yul: bool,
 }
@@ -1,7 +1,17 @@
-use crate::{HitMap, HitMaps};
-use alloy_primitives::B256;
-use revm::{interpreter::Interpreter, Database, EvmContext, Inspector};
-use std::ptr::NonNull;
+use crate::{Counter, HitMap, HitMaps};
+use alloy_primitives::{
+    map::{B256HashMap, HashSet},
+    Address, Bytes, Selector, B256,
+};
+use foundry_evm_core::constants::CHEATCODE_ADDRESS;
+use revm::{
+    interpreter::{
+        opcode, CallInputs, CallOutcome, CreateInputs, CreateOutcome, Gas, InstructionResult,
+        Interpreter, InterpreterResult,
+    },
+    Database, EvmContext, Inspector,
+};
+use std::{ptr::NonNull, sync::Arc};
 
 /// Inspector implementation for collecting coverage information.
 #[derive(Clone, Debug)]
@@ -13,6 +23,12 @@ pub struct CoverageCollector {
     current_hash: B256,
 
     maps: HitMaps,
+
+    /// Tracks whether the effects of function calls are asserted on, if enabled.
+    assertions: Option<Box<AssertionTracker>>,
+
+    /// Erases the gas used by the counters of instrumented code.
+    counter_gas: CounterGas,
 }
 
 // SAFETY: See comments on `current_map`.
@@ -25,24 +41,126 @@ fn default() -> Self {
             current_map: NonNull::dangling(),
             current_hash: B256::ZERO,
             maps: Default::default(),
+            assertions: None,
+            counter_gas: Default::default(),
         }
     }
 }
 
 impl<DB: Database> Inspector<DB> for CoverageCollector {
     fn initialize_interp(&mut self, interpreter: &mut Interpreter, _context: &mut EvmContext<DB>) {
-        get_or_insert_contract_hash(interpreter);
+        let hash = *get_or_insert_contract_hash(interpreter);
         self.insert_map(interpreter);
+        if let Some(tracker) = &mut self.assertions {
+            tracker.initialize_frame(hash);
+        }
     }
 
     #[inline]
     fn step(&mut self, interpreter: &mut Interpreter, _context: &mut EvmContext<DB>) {
         let map = self.get_or_insert_map(interpreter);
         map.hit(interpreter.program_counter() as u32);
+        self.counter_gas.step(interpreter);
+
+        if let Some(tracker) = &mut self.assertions {
+            if interpreter.current_opcode() == opcode::SSTORE {
+                if let Ok(value) = interpreter.stack.peek(1) {
+                    tracker.store(value.into());
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn step_end(&mut self, interpreter: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        self.counter_gas.step_end(interpreter);
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        // Counters of instrumented code are called by the contract whose map is the current one,
+        // as it executed the call instruction. They're not executed, so that they have no effect.
+        if let Some(counter) = Counter::from_address(&inputs.target_address) {
+            if !self.current_hash.is_zero() {
+                // SAFETY: See comments on `current_map`.
+                unsafe { self.current_map.as_mut() }.hit_counter(counter);
+            }
+            let result = InstructionResult::Stop;
+            return Some(CallOutcome {
+                result: InterpreterResult {
+                    result,
+                    output: Bytes::new(),
+                    gas: Gas::new(inputs.gas_limit),
+                },
+                memory_offset: inputs.return_memory_offset.clone(),
+            })
+        }
+
+        if let Some(tracker) = &mut self.assertions {
+            let mut selector = inputs.input.get(..4).map(Selector::from_slice);
+            if inputs.target_address == CHEATCODE_ADDRESS {
+                tracker.check_assertion(&inputs.input, &mut self.maps);
+                selector = None;
+            }
+            tracker.enter(context.journaled_state.depth, selector);
+        }
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        if Counter::from_address(&inputs.target_address).is_some() {
+            return outcome
+        }
+        if let Some(tracker) = &mut self.assertions {
+            tracker.exit(context.journaled_state.depth, &outcome.result, true, &mut self.maps);
+        }
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        _inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        if let Some(tracker) = &mut self.assertions {
+            tracker.enter(context.journaled_state.depth, None);
+        }
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        _inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        if let Some(tracker) = &mut self.assertions {
+            tracker.exit(context.journaled_state.depth, &outcome.result, false, &mut self.maps);
+        }
+        outcome
     }
 }
 
 impl CoverageCollector {
+    /// Creates a collector that also records, for every function called, whether an assertion
+    /// cheatcode was called with its effects.
+    ///
+    /// `assertions` are the selectors of the assertion cheatcodes.
+    pub fn with_assertions(assertions: Arc<HashSet<Selector>>) -> Self {
+        Self {
+            assertions: Some(Box::new(AssertionTracker { assertions, ..Default::default() })),
+            ..Default::default()
+        }
+    }
+
     /// Finish collecting coverage information and return the [`HitMaps`].
     pub fn finish(self) -> HitMaps {
         self.maps
@@ -76,6 +194,169 @@ fn insert_map(&mut self, interpreter: &Interpreter) {
     }
 }
 
+/// Erases the gas used by the counters of instrumented code, so that the gas used by the
+/// instrumented contracts is the same as without the counters.
+///
+/// A counter is `pop(staticcall(0, counter, 0, 0, 0, 0))`: the costs of the `STATICCALL`, of the
+/// instructions pushing its arguments right before it, and of the `POP` right after it are erased.
+#[derive(Clone, Debug, Default)]
+struct CounterGas {
+    /// The opcode of the instruction being executed, the gas remaining before it, and whether it
+    /// calls a counter.
+    current: (u8, u64, bool),
+    /// The opcodes and the costs of the last instructions executed, the latest last.
+    recent: [(u8, u64); 6],
+    /// Whether the last instruction executed called a counter.
+    called: bool,
+}
+
+impl CounterGas {
+    #[inline]
+    fn step(&mut self, interpreter: &Interpreter) {
+        let opcode = interpreter.current_opcode();
+        let is_counter = opcode == opcode::STATICCALL &&
+            interpreter.stack.peek(1).is_ok_and(|word| {
+                Counter::from_address(&Address::from_word(word.into())).is_some()
+            });
+        self.current = (opcode, interpreter.gas.remaining(), is_counter);
+    }
+
+    #[inline]
+    fn step_end(&mut self, interpreter: &mut Interpreter) {
+        let (opcode, remaining, is_counter) = self.current;
+        let cost = remaining.saturating_sub(interpreter.gas.remaining());
+        if std::mem::take(&mut self.called) && opcode == opcode::POP {
+            interpreter.gas.erase_cost(cost);
+        } else if is_counter {
+            let pushes_arguments = self.recent.iter().all(|(opcode, _)| {
+                matches!(*opcode, opcode::PUSH0..=opcode::PUSH32 | opcode::DUP1..=opcode::DUP16)
+            });
+            let arguments_cost = if pushes_arguments {
+                self.recent.iter().map(|(_, cost)| cost).sum::<u64>()
+            } else {
+                0
+            };
+            interpreter.gas.erase_cost(cost + arguments_cost);
+            self.recent = Default::default();
+            self.called = true;
+            return
+        }
+        self.recent.rotate_left(1);
+        self.recent[self.recent.len() - 1] = (opcode, cost);
+    }
+}
+
+/// Records whether the effects of function calls are asserted on.
+///
+/// The effects of a call are the words it returned and the values stored by it and the calls it
+/// made. An assertion is on the effects of a call if one of the arguments of an assertion
+/// cheatcode called afterwards is one of these words. Values are matched rather than tracked
+/// through every instruction, so this is a heuristic: zero words are ignored, and a function can
+/// be reported as asserted on if an unrelated value happens to be equal to one of its effects.
+#[derive(Clone, Debug, Default)]
+struct AssertionTracker {
+    /// The selectors of the assertion cheatcodes.
+    assertions: Arc<HashSet<Selector>>,
+    /// The call frames being executed, indexed by depth.
+    frames: Vec<Frame>,
+    /// The functions whose effects include each word, as code hashes and selectors.
+    effects: B256HashMap<Vec<(B256, Selector)>>,
+}
+
+/// A call frame tracked by [`AssertionTracker`].
+#[derive(Clone, Debug, Default)]
+struct Frame {
+    /// The hash of the executed code, or zero if no code was executed.
+    code_hash: B256,
+    /// The selector of the called function, unless it's a creation or a cheatcode call.
+    selector: Option<Selector>,
+    /// The values stored by the frame and the frames it called.
+    stored: Vec<B256>,
+}
+
+impl AssertionTracker {
+    /// Starts a call frame at the given depth.
+    fn enter(&mut self, depth: usize, selector: Option<Selector>) {
+        self.frames.resize_with(depth, Default::default);
+        self.frames.push(Frame { selector, ..Default::default() });
+    }
+
+    /// Records the code executed by the current call frame.
+    fn initialize_frame(&mut self, code_hash: B256) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.code_hash = code_hash;
+        }
+    }
+
+    /// Records a value stored by the current call frame.
+    fn store(&mut self, value: B256) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.stored.push(value);
+        }
+    }
+
+    /// Ends the call frame at the given depth, recording its effects if it succeeded.
+    fn exit(
+        &mut self,
+        depth: usize,
+        result: &InterpreterResult,
+        is_call: bool,
+        maps: &mut HitMaps,
+    ) {
+        self.frames.truncate(depth + 1);
+        if self.frames.len() != depth + 1 {
+            return
+        }
+        let Some(frame) = self.frames.pop() else { return };
+
+        let function = frame.selector.filter(|_| !frame.code_hash.is_zero());
+        if let Some(selector) = function {
+            if let Some(map) = maps.get_mut(&frame.code_hash) {
+                map.call_function(selector);
+            }
+        }
+        if !result.is_ok() {
+            return
+        }
+
+        if let Some(selector) = function {
+            // The output of a creation is the deployed code, not a returned value.
+            let returned: &[u8] = if is_call { &result.output } else { &[] };
+            let words = returned.chunks(32).map(B256::right_padding_from);
+            for word in words.chain(frame.stored.iter().copied()) {
+                if word.is_zero() {
+                    continue
+                }
+                let functions = self.effects.entry(word).or_default();
+                if !functions.contains(&(frame.code_hash, selector)) {
+                    functions.push((frame.code_hash, selector));
+                }
+            }
+        }
+        if let Some(parent) = self.frames.last_mut() {
+            parent.stored.extend(frame.stored);
+        }
+    }
+
+    /// Records the functions whose effects are asserted on if `input` is the input of a call to
+    /// an assertion cheatcode.
+    fn check_assertion(&self, input: &[u8], maps: &mut HitMaps) {
+        let Some((selector, args)) = input.split_first_chunk::<4>() else { return };
+        if !self.assertions.contains(&Selector::from(*selector)) {
+            return
+        }
+        for word in args.chunks(32).map(B256::right_padding_from) {
+            let Some(functions) = self.effects.get(&word) else { continue };
+            for (code_hash, selector) in functions {
+                // Only existing maps are accessed, so `current_map` stays valid.
+                if let Some(map) = maps.get_mut(code_hash) {
+                    map.assert_function(*selector);
+                }
+            }
+        }
+    }
+}
+
 /// Helper function for extracting contract hash used to record coverage hit map.
 ///
 /// If the contract hash is zero (contract not yet created but it's going to be created in current
@@ -0,0 +1,458 @@
+//! Instrumentation of the Yul IR of Solidity contracts with coverage counters.
+//!
+//! The IR emitted by the compiler when compiling with `viaIR` is annotated with `@src` comments,
+//! which map its code to the Solidity sources. [Counters](Counter) are injected in the IR where the
+//! code of each coverage item starts, and the instrumented IR is then compiled as a Yul source,
+//! with the optimizer settings of the project. The counters are calls, which the optimizer neither
+//! removes nor moves, so the hits of the items are accurate whatever these settings, unlike the
+//! ones found from source maps. The gas they use is erased by the
+//! [`CoverageCollector`](crate::CoverageCollector).
+
+use super::{
+    analysis::SourceAnalysis,
+    yul::{Block, Parser, Statement},
+    Counter, CoverageItemKind,
+};
+use alloy_primitives::map::{HashMap, HashSet};
+use std::{ops::Range, sync::Arc};
+
+/// Injects coverage counters in the IR of contracts, for the items of a [`SourceAnalysis`].
+#[derive(Clone, Debug, Default)]
+pub struct Instrumenter {
+    /// The targets of the counters, by source ID.
+    targets: HashMap<usize, Vec<(Range<u32>, Target)>>,
+}
+
+/// Where the counters of a coverage item, or of the paths of a branch, are injected.
+#[derive(Clone, Copy, Debug)]
+enum Target {
+    /// Before the first statement in the range of the item.
+    Entry(u32),
+    /// At the start of the body of the Yul function of the Solidity function.
+    Function(u32),
+    /// At the start of the body of the Yul `if` of a Solidity `if` statement without `else`,
+    /// whose true body is the range of the item.
+    If(u32),
+    /// In the `default` and `case 0` clauses of the Yul `switch` of a Solidity `if`/`else`
+    /// statement, which starts where the range of the false path does.
+    IfElse { true_path: u32, false_path: u32 },
+    /// At the start of the body of an `if` statement of inline assembly.
+    YulIf(u32),
+    /// Around the statements of a `require` call: the reverting path is incremented before them
+    /// and decremented after them, where the passing path is incremented.
+    Require { pass: u32, revert: u32 },
+}
+
+impl Instrumenter {
+    /// Creates an instrumenter for the items of the given analysis.
+    pub fn new(analysis: &SourceAnalysis) -> Self {
+        let mut targets = HashMap::<usize, Vec<_>>::default();
+        let mut branches = HashMap::<(usize, Arc<str>, u32), Vec<_>>::default();
+        for (item_id, item) in analysis.all_items().iter().enumerate() {
+            let item_id = item_id as u32;
+            let target = match item.kind {
+                CoverageItemKind::Line | CoverageItemKind::Statement => Target::Entry(item_id),
+                CoverageItemKind::Function { .. } => Target::Function(item_id),
+                CoverageItemKind::Branch { branch_id, .. } => {
+                    let key = (item.loc.source_id, item.loc.contract_name.clone(), branch_id);
+                    branches.entry(key).or_default().push(item_id);
+                    continue
+                }
+            };
+            targets.entry(item.loc.source_id).or_default().push((item.loc.bytes.clone(), target));
+        }
+
+        for paths in branches.into_values() {
+            // Only the true paths of Solidity `if` statements are anchored at their first opcode.
+            let is_if_statement = paths.iter().any(|&id| {
+                matches!(
+                    analysis.all_items()[id as usize].kind,
+                    CoverageItemKind::Branch { path_id: 0, is_first_opcode: true, .. }
+                )
+            });
+            let path = |path_id| {
+                paths.iter().copied().find(|&id| {
+                    matches!(
+                        analysis.all_items()[id as usize].kind,
+                        CoverageItemKind::Branch { path_id: p, .. } if p == path_id
+                    )
+                })
+            };
+            let (target, loc) = match (path(0), path(1)) {
+                (Some(true_path), Some(false_path)) if is_if_statement => {
+                    (Target::IfElse { true_path, false_path }, false_path)
+                }
+                (Some(pass), Some(revert)) => (Target::Require { pass, revert }, pass),
+                (Some(true_path), None) if is_if_statement => (Target::If(true_path), true_path),
+                (Some(path), None) => (Target::YulIf(path), path),
+                _ => continue,
+            };
+            let loc = &analysis.all_items()[loc as usize].loc;
+            targets.entry(loc.source_id).or_default().push((loc.bytes.clone(), target));
+        }
+
+        Self { targets }
+    }
+
+    /// Returns the IR with the counters of the items injected.
+    ///
+    /// The IR is a Yul object, whose `@src` annotations use the source IDs of the analysis.
+    pub fn instrument(&self, ir: &str) -> eyre::Result<String> {
+        let mut parser = Parser::new(ir)?;
+        let mut code = Vec::new();
+        parser.parse_object(&mut code)?;
+
+        let annotations = parser
+            .comments
+            .iter()
+            .filter_map(|range| Some((range.start, parse_annotation(&ir[range.clone()])?)))
+            .collect();
+        let mut visitor = InstrumentVisitor {
+            targets: &self.targets,
+            annotations,
+            instrumented: HashSet::default(),
+            insertions: Vec::new(),
+        };
+        // Counters are injected in every code block, as the code of the items may be duplicated in
+        // the creation and runtime code of the objects.
+        for block in &code {
+            visitor.instrumented.clear();
+            visitor.visit_block(block);
+        }
+
+        let mut insertions = visitor.insertions;
+        insertions.sort_by_key(|(pos, _)| *pos);
+        let mut out = String::with_capacity(ir.len() + insertions.len() * 80);
+        let mut last = 0;
+        for (pos, code) in insertions {
+            out.push_str(&ir[last..pos]);
+            out.push_str(&code);
+            last = pos;
+        }
+        out.push_str(&ir[last..]);
+        Ok(out)
+    }
+}
+
+/// A location in the Solidity sources, from an `@src` annotation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct SrcLocation {
+    source_id: usize,
+    bytes: Range<u32>,
+}
+
+impl SrcLocation {
+    fn is_within(&self, range: &Range<u32>) -> bool {
+        range.start <= self.bytes.start && self.bytes.end <= range.end
+    }
+}
+
+/// Parses the `@src` annotation of a comment, e.g. `/// @src 0:120:250 "..."`.
+///
+/// Returns `Some(None)` for annotations of code with no location, `-1:-1:-1`.
+fn parse_annotation(comment: &str) -> Option<Option<SrcLocation>> {
+    let (_, rest) = comment.split_once("@src ")?;
+    let location = rest.split_whitespace().next()?;
+    let mut parts = location.split(':').map(|part| part.parse::<i64>().ok());
+    let (source_id, start, end) = (parts.next()??, parts.next()??, parts.next()??);
+    if source_id < 0 || start < 0 || end < start {
+        return Some(None)
+    }
+    Some(Some(SrcLocation { source_id: source_id as usize, bytes: start as u32..end as u32 }))
+}
+
+/// Walks the code blocks of the IR and records where to inject the counters.
+struct InstrumentVisitor<'a> {
+    targets: &'a HashMap<usize, Vec<(Range<u32>, Target)>>,
+    /// The `@src` annotations, by position in the IR.
+    annotations: Vec<(usize, Option<SrcLocation>)>,
+    /// The targets already instrumented in the current code block, by source ID and index.
+    instrumented: HashSet<(usize, usize)>,
+    /// The code to insert, by position in the IR.
+    insertions: Vec<(usize, String)>,
+}
+
+impl<'a> InstrumentVisitor<'a> {
+    fn visit_block(&mut self, block: &Block<'_>) {
+        for (i, statement) in block.statements.iter().enumerate() {
+            let location = self.location_at(statement.range().start);
+            if let Some(location) = &location {
+                if !matches!(statement, Statement::Function { .. }) {
+                    self.visit_entry(&block.statements[i..], location);
+                }
+            }
+
+            match statement {
+                Statement::Block(block) => self.visit_block(block),
+                Statement::Function { name, body, .. } => {
+                    // The external functions are the ABI wrappers of the internal ones.
+                    if let Some(location) = location.filter(|_| !name.starts_with("external_")) {
+                        self.instrument(&location, body, |range, target| match target {
+                            Target::Function(id) if *range == location.bytes => Some(*id),
+                            _ => None,
+                        });
+                    }
+                    self.visit_block(body);
+                }
+                Statement::If { body, .. } => {
+                    if let Some(location) = location {
+                        // The true body of the `if` statement is the first one in its range, as
+                        // the other ones are nested.
+                        let target = self.find(&location, |range, target| match target {
+                            Target::YulIf(_) => *range == location.bytes,
+                            Target::If(_) => {
+                                location.bytes.start < range.start &&
+                                    range.end <= location.bytes.end
+                            }
+                            _ => false,
+                        });
+                        if let Some((index, Target::YulIf(id) | Target::If(id))) = target {
+                            self.instrumented.insert((location.source_id, index));
+                            self.insert_at_start(body, Counter { item_id: id, decrement: false });
+                        }
+                    }
+                    self.visit_block(body);
+                }
+                Statement::Switch { cases, .. } => {
+                    if let (Some(location), [false_case, true_case]) = (location, &cases[..]) {
+                        if false_case.value == Some("0") && true_case.value.is_none() {
+                            let target = self.find(&location, |range, target| {
+                                matches!(target, Target::IfElse { .. }) &&
+                                    range.start == location.bytes.start
+                            });
+                            if let Some((index, Target::IfElse { true_path, false_path })) = target
+                            {
+                                self.instrumented.insert((location.source_id, index));
+                                let counter = |item_id| Counter { item_id, decrement: false };
+                                self.insert_at_start(&true_case.body, counter(true_path));
+                                self.insert_at_start(&false_case.body, counter(false_path));
+                            }
+                        }
+                    }
+                    for case in cases {
+                        self.visit_block(&case.body);
+                    }
+                }
+                Statement::For { pre, post, body, .. } => {
+                    self.visit_block(pre);
+                    self.visit_block(post);
+                    self.visit_block(body);
+                }
+                Statement::Simple(_) | Statement::Expression(_) => {}
+            }
+        }
+    }
+
+    /// Instruments the items whose code starts with the first of the given statements, at
+    /// `location`.
+    fn visit_entry(&mut self, statements: &[Statement<'_>], location: &SrcLocation) {
+        let targets: &'a HashMap<_, _> = self.targets;
+        let Some(targets) = targets.get(&location.source_id) else { return };
+        let start = statements[0].range().start;
+        for (index, (range, target)) in targets.iter().enumerate() {
+            if !matches!(target, Target::Entry(_) | Target::Require { .. }) ||
+                !location.is_within(range) ||
+                !self.instrumented.insert((location.source_id, index))
+            {
+                continue
+            }
+            match *target {
+                Target::Entry(item_id) => {
+                    self.insert(start, Counter { item_id, decrement: false });
+                }
+                Target::Require { pass, revert } => {
+                    // The call is made by the statements in the range of the `require`.
+                    let last = statements
+                        .iter()
+                        .take_while(|statement| {
+                            self.location_at(statement.range().start)
+                                .is_some_and(|location| location.is_within(range))
+                        })
+                        .last()
+                        .unwrap_or(&statements[0]);
+                    let end = last.range().end;
+                    self.insert(start, Counter { item_id: revert, decrement: false });
+                    self.insert(end, Counter { item_id: pass, decrement: false });
+                    self.insert(end, Counter { item_id: revert, decrement: true });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Instruments the first target at `location` matching `predicate`, at the start of `body`.
+    fn instrument(
+        &mut self,
+        location: &SrcLocation,
+        body: &Block<'_>,
+        predicate: impl Fn(&Range<u32>, &Target) -> Option<u32>,
+    ) {
+        let targets: &'a HashMap<_, _> = self.targets;
+        let Some(targets) = targets.get(&location.source_id) else { return };
+        let found = targets.iter().enumerate().find_map(|(index, (range, target))| {
+            if self.instrumented.contains(&(location.source_id, index)) {
+                return None
+            }
+            predicate(range, target).map(|item_id| (index, item_id))
+        });
+        if let Some((index, item_id)) = found {
+            self.instrumented.insert((location.source_id, index));
+            self.insert_at_start(body, Counter { item_id, decrement: false });
+        }
+    }
+
+    /// Returns the target at `location` matching `predicate` that starts first, along with its
+    /// index, if it isn't instrumented yet.
+    fn find(
+        &self,
+        location: &SrcLocation,
+        predicate: impl Fn(&Range<u32>, &Target) -> bool,
+    ) -> Option<(usize, Target)> {
+        self.targets
+            .get(&location.source_id)?
+            .iter()
+            .enumerate()
+            .filter(|(index, (range, target))| {
+                !self.instrumented.contains(&(location.source_id, *index)) &&
+                    predicate(range, target)
+            })
+            .min_by_key(|(_, (range, _))| range.start)
+            .map(|(index, (_, target))| (index, *target))
+    }
+
+    /// Returns the location of the code at `pos`, which is the one of the last annotation before
+    /// it.
+    fn location_at(&self, pos: usize) -> Option<SrcLocation> {
+        let index = self.annotations.partition_point(|(annotation, _)| *annotation < pos);
+        index.checked_sub(1).and_then(|index| self.annotations[index].1.clone())
+    }
+
+    fn insert_at_start(&mut self, block: &Block<'_>, counter: Counter) {
+        // Skip the opening brace.
+        self.insert(block.range.start + 1, counter);
+    }
+
+    fn insert(&mut self, pos: usize, counter: Counter) {
+        let code = format!(" pop(staticcall(0, {}, 0, 0, 0, 0)) ", counter.address());
+        self.insertions.push((pos, code));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counter(item_id: u32, decrement: bool) -> String {
+        format!("pop(staticcall(0, {}, 0, 0, 0, 0))", Counter { item_id, decrement }.address())
+    }
+
+    fn instrument(targets: Vec<(Range<u32>, Target)>, ir: &str) -> String {
+        let instrumenter = Instrumenter { targets: HashMap::from_iter([(0, targets)]) };
+        instrumenter.instrument(ir).unwrap()
+    }
+
+    #[test]
+    fn parse_src_annotations() {
+        assert_eq!(
+            parse_annotation(r#"/// @src 0:120:250  "contract A {...""#),
+            Some(Some(SrcLocation { source_id: 0, bytes: 120..250 }))
+        );
+        assert_eq!(parse_annotation("/// @src -1:-1:-1"), Some(None));
+        assert_eq!(parse_annotation(r#"/// @use-src 0:"src/A.sol""#), None);
+        assert_eq!(parse_annotation("/// a comment"), None);
+    }
+
+    #[test]
+    fn instrument_functions_statements_and_ifs() {
+        let ir = r#"
+/// @use-src 0:"src/A.sol"
+object "A_1" {
+    code {
+        /// @src 0:0:100  "contract A {..."
+        mstore(64, memoryguard(128))
+    }
+    object "A_1_deployed" {
+        code {
+            /// @src 0:20:90  "function f(uint256 x) ..."
+            function fun_f_10(var_x) {
+                /// @src 0:40:50  "y = 1"
+                sstore(0, 1)
+                /// @src 0:55:85  "if (x > 0) {..."
+                if gt(var_x, 0) {
+                    /// @src 0:70:80  "y = 2"
+                    sstore(1, 2)
+                }
+            }
+            /// @src 0:20:90  "function f(uint256 x) ..."
+            function external_fun_f_10() {
+                fun_f_10(calldataload(4))
+            }
+        }
+    }
+}
+"#;
+        let targets = vec![
+            (20..90, Target::Function(1)),
+            (40..50, Target::Entry(2)),
+            (65..85, Target::If(3)),
+            (70..80, Target::Entry(4)),
+        ];
+        let out = instrument(targets, ir);
+
+        assert_eq!(out.matches("staticcall").count(), 4, "{out}");
+        assert!(out.contains(&format!("fun_f_10(var_x) {{ {} ", counter(1, false))), "{out}");
+        assert!(out.contains(&format!("{} sstore(0, 1)", counter(2, false))), "{out}");
+        assert!(out.contains(&format!("if gt(var_x, 0) {{ {} ", counter(3, false))), "{out}");
+        assert!(out.contains(&format!("{} sstore(1, 2)", counter(4, false))), "{out}");
+        // The ABI wrapper isn't counted as the function.
+        assert!(out.contains("function external_fun_f_10() {\n"), "{out}");
+        // The rest of the IR is unchanged.
+        let stripped =
+            (1..=4).fold(out, |out, id| out.replace(&format!(" {} ", counter(id, false)), ""));
+        assert_eq!(stripped, ir);
+    }
+
+    #[test]
+    fn instrument_if_else_and_require() {
+        let ir = r#"
+object "B_1" {
+    code {
+        /// @src 0:0:60  "if (x) {...} else {...}"
+        switch x
+        case 0 {
+            /// @src 0:45:55  "b()"
+            b()
+        }
+        default {
+            /// @src 0:10:20  "a()"
+            a()
+        }
+        /// @src 0:70:90  "require(x > 1)"
+        require_helper(gt(x, 1))
+        /// @src 0:100:110  "c()"
+        c()
+    }
+}
+"#;
+        let targets = vec![
+            (0..60, Target::IfElse { true_path: 0, false_path: 1 }),
+            (70..90, Target::Require { pass: 2, revert: 3 }),
+        ];
+        let out = instrument(targets, ir);
+
+        assert_eq!(out.matches("staticcall").count(), 5, "{out}");
+        assert!(out.contains(&format!("case 0 {{ {} ", counter(1, false))), "{out}");
+        assert!(out.contains(&format!("default {{ {} ", counter(0, false))), "{out}");
+        // The reverting path is counted before the call, and uncounted after it if it passed.
+        assert!(
+            out.contains(&format!(
+                "{} require_helper(gt(x, 1)) {}  {} ",
+                counter(3, false),
+                counter(2, false),
+                counter(3, true)
+            )),
+            "{out}"
+        );
+        assert!(out.contains("\n        c()\n"), "{out}");
+    }
+}
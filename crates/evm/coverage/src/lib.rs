@@ -9,8 +9,9 @@
 extern crate tracing;
 
 use alloy_primitives::{
+    hex,
     map::{B256HashMap, HashMap},
-    Bytes,
+    Address, Bytes, Selector,
 };
 use analysis::SourceAnalysis;
 use eyre::Result;
@@ -27,8 +28,11 @@
 
 pub mod analysis;
 pub mod anchors;
+pub mod instrument;
 
 mod inspector;
+mod vyper;
+mod yul;
 pub use inspector::CoverageCollector;
 
 /// A coverage report.
@@ -49,6 +53,11 @@ pub struct CoverageReport {
     pub bytecode_hits: HashMap<ContractId, HitMap>,
     /// The bytecode -> source mappings.
     pub source_maps: HashMap<ContractId, (SourceMap, SourceMap)>,
+    /// The external functions executed by the tests, keyed by their contract ID and name, and
+    /// whether their effects were asserted on.
+    ///
+    /// Only collected for the assertion coverage report.
+    pub function_assertions: HashMap<ContractId, HashMap<String, bool>>,
 }
 
 impl CoverageReport {
@@ -84,6 +93,51 @@ pub fn add_anchors(
         self.anchors.extend(anchors);
     }
 
+    /// Add the external functions executed by the tests, and whether their effects were asserted
+    /// on.
+    pub fn add_function_assertions(
+        &mut self,
+        contract_id: &ContractId,
+        functions: impl IntoIterator<Item = (String, bool)>,
+    ) {
+        let assertions = self.function_assertions.entry(contract_id.clone()).or_default();
+        for (name, asserted) in functions {
+            *assertions.entry(name).or_default() |= asserted;
+        }
+    }
+
+    /// Returns an iterator over assertion coverage summaries by source file path.
+    pub fn assertions_by_file(&self) -> impl Iterator<Item = (&Path, AssertionSummary)> {
+        let mut by_file: BTreeMap<&Path, AssertionSummary> = BTreeMap::new();
+        for (id, functions) in &self.function_assertions {
+            let Some(path) = self.source_paths.get(&(id.version.clone(), id.source_id)) else {
+                continue
+            };
+            // Only report the functions of the contracts that are part of the report, which
+            // excludes test contracts and filtered out sources.
+            let is_reported = self.analyses.get(&id.version).is_some_and(|analysis| {
+                analysis.all_items().iter().any(|item| {
+                    item.loc.source_id == id.source_id &&
+                        item.loc.contract_name == id.contract_name
+                })
+            });
+            if !is_reported {
+                continue
+            }
+            let summary = by_file.entry(path).or_default();
+            for (name, &asserted) in functions {
+                summary.executed += 1;
+                if asserted {
+                    summary.asserted += 1;
+                } else {
+                    summary.unchecked.push(format!("{}.{name}", id.contract_name));
+                }
+            }
+            summary.unchecked.sort_unstable();
+        }
+        by_file.into_iter()
+    }
+
     /// Returns an iterator over coverage summaries by source file path.
     pub fn summary_by_file(&self) -> impl Iterator<Item = (&Path, CoverageSummary)> {
         self.by_file(|summary: &mut CoverageSummary, item| summary.add_item(item))
@@ -126,6 +180,23 @@ pub fn add_hit_map(
             .and_modify(|m| m.merge(hit_map))
             .or_insert_with(|| hit_map.clone());
 
+        // Add the hits of the instrumentation counters, decrements last so they can't underflow.
+        if let Some(analysis) = self.analyses.get_mut(&contract_id.version) {
+            let mut counters = hit_map.counters().collect::<Vec<_>>();
+            counters.sort_by_key(|(counter, _)| counter.decrement);
+            for (counter, hits) in counters {
+                let item = analysis
+                    .all_items_mut()
+                    .get_mut(counter.item_id as usize)
+                    .expect("Counter refers to non-existent coverage item");
+                if counter.decrement {
+                    item.hits = item.hits.saturating_sub(hits);
+                } else {
+                    item.hits += hits;
+                }
+            }
+        }
+
         // Add source level hits.
         if let Some(anchors) = self.anchors.get(contract_id) {
             let anchors = if is_deployed_code { &anchors.1 } else { &anchors.0 };
@@ -210,13 +281,24 @@ fn deref_mut(&mut self) -> &mut Self::Target {
 pub struct HitMap {
     bytecode: Bytes,
     hits: HashMap<u32, u32>,
+    /// The selectors of the functions called, and whether their effects were asserted on.
+    ///
+    /// Only recorded when tracking assertions.
+    functions: HashMap<Selector, bool>,
+    /// The hits of the counters injected in the code, if it was instrumented.
+    counters: HashMap<Counter, u32>,
 }
 
 impl HitMap {
     /// Create a new hitmap with the given bytecode.
     #[inline]
     pub fn new(bytecode: Bytes) -> Self {
-        Self { bytecode, hits: HashMap::with_capacity_and_hasher(1024, Default::default()) }
+        Self {
+            bytecode,
+            hits: HashMap::with_capacity_and_hasher(1024, Default::default()),
+            functions: HashMap::default(),
+            counters: HashMap::default(),
+        }
     }
 
     /// Returns the bytecode.
@@ -243,12 +325,49 @@ pub fn hits(&mut self, pc: u32, hits: u32) {
         *self.hits.entry(pc).or_default() += hits;
     }
 
+    /// Records a call to the function with the given selector.
+    #[inline]
+    pub fn call_function(&mut self, selector: Selector) {
+        self.functions.entry(selector).or_insert(false);
+    }
+
+    /// Records an assertion on the effects of the function with the given selector.
+    #[inline]
+    pub fn assert_function(&mut self, selector: Selector) {
+        self.functions.insert(selector, true);
+    }
+
+    /// Increase the hit counter by 1 for the given instrumentation counter.
+    #[inline]
+    pub fn hit_counter(&mut self, counter: Counter) {
+        *self.counters.entry(counter).or_default() += 1;
+    }
+
+    /// Returns an iterator over the instrumentation counters hit and their hit counts.
+    #[inline]
+    pub fn counters(&self) -> impl Iterator<Item = (Counter, u32)> + '_ {
+        self.counters.iter().map(|(&counter, &hits)| (counter, hits))
+    }
+
+    /// Returns an iterator over the selectors of the functions called, and whether their effects
+    /// were asserted on.
+    #[inline]
+    pub fn functions(&self) -> impl Iterator<Item = (Selector, bool)> + '_ {
+        self.functions.iter().map(|(&selector, &asserted)| (selector, asserted))
+    }
+
     /// Merge another hitmap into this, assuming the bytecode is consistent
     pub fn merge(&mut self, other: &Self) {
         self.hits.reserve(other.len());
         for (pc, hits) in other.iter() {
             self.hits(pc, hits);
         }
+        for (selector, asserted) in other.functions() {
+            *self.functions.entry(selector).or_default() |= asserted;
+        }
+        for (counter, hits) in other.counters() {
+            *self.counters.entry(counter).or_default() += hits;
+        }
     }
 
     /// Returns an iterator over all the program counters and their hit counts.
@@ -270,6 +389,43 @@ pub fn is_empty(&self) -> bool {
     }
 }
 
+/// A counter injected in the code of a contract by [instrumentation](instrument), which updates
+/// the hits of a coverage item when executed.
+///
+/// Counters are executed as calls to addresses made of [`Counter::ADDRESS_PREFIX`], whether the
+/// counter is a decrement, and the item ID, so that they are kept by the optimizer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Counter {
+    /// The ID of the coverage item.
+    pub item_id: u32,
+    /// Whether the counter decrements the hits of the item rather than incrementing them.
+    pub decrement: bool,
+}
+
+impl Counter {
+    /// The prefix of the addresses of the counters.
+    pub const ADDRESS_PREFIX: [u8; 15] = hex!("aab674c09aa53fb00055e4f3d64207");
+
+    /// Returns the address of the counter.
+    pub fn address(&self) -> Address {
+        let mut address = [0; 20];
+        address[..15].copy_from_slice(&Self::ADDRESS_PREFIX);
+        address[15] = self.decrement as u8;
+        address[16..].copy_from_slice(&self.item_id.to_be_bytes());
+        Address::from(address)
+    }
+
+    /// Returns the counter with the given address, if it is one.
+    pub fn from_address(address: &Address) -> Option<Self> {
+        let (prefix, rest) = address.split_first_chunk::<15>()?;
+        if *prefix != Self::ADDRESS_PREFIX || rest[0] > 1 {
+            return None
+        }
+        let item_id = u32::from_be_bytes(rest[1..].try_into().ok()?);
+        Some(Self { item_id, decrement: rest[0] == 1 })
+    }
+}
+
 /// A unique identifier for a contract
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct ContractId {
@@ -484,3 +640,14 @@ pub fn add_items<'a>(&mut self, items: impl IntoIterator<Item = &'a CoverageItem
         }
     }
 }
+
+/// Assertion coverage summary for a source file.
+#[derive(Clone, Debug, Default)]
+pub struct AssertionSummary {
+    /// The number of external functions executed by the tests.
+    pub executed: usize,
+    /// The number of executed functions whose effects were asserted on.
+    pub asserted: usize,
+    /// The executed functions whose effects were never asserted on, as `Contract.function`.
+    pub unchecked: Vec<String>,
+}
@@ -0,0 +1,434 @@
+//! Coverage analysis for Vyper sources.
+//!
+//! Vyper does not output an AST in the format the Solidity analysis walks, so coverage items are
+//! found by parsing the indentation-based structure of the source code directly. The resulting
+//! source ranges are matched against the source maps emitted by the Vyper compiler in the same
+//! way as for Solidity.
+
+use super::{CoverageItem, CoverageItemKind, SourceLocation};
+use std::{ops::Range, sync::Arc};
+
+/// A visitor that walks the source code of a single Vyper contract and finds coverage items.
+#[derive(Clone, Debug)]
+pub struct VyperVisitor<'a> {
+    /// The source ID of the contract.
+    source_id: usize,
+    /// The source code being walked.
+    source: &'a str,
+
+    /// The name of the contract being walked.
+    contract_name: &'a Arc<str>,
+
+    /// The current branch ID
+    branch_id: u32,
+    /// Stores the last line we put in the items collection to ensure we don't push duplicate lines
+    last_line: u32,
+
+    /// Coverage items
+    pub items: Vec<CoverageItem>,
+}
+
+impl<'a> VyperVisitor<'a> {
+    pub fn new(source_id: usize, source: &'a str, contract_name: &'a Arc<str>) -> Self {
+        Self { source_id, source, contract_name, branch_id: 0, last_line: 0, items: Vec::new() }
+    }
+
+    /// Walks all the functions defined in the source.
+    pub fn visit_source(&mut self) {
+        let lines = logical_lines(self.source);
+        let statements = parse_block(&lines, &mut 0, 0);
+        for statement in &statements {
+            if let Some(name) = statement.function_name() {
+                self.visit_function_definition(name, statement);
+            }
+        }
+    }
+
+    fn visit_function_definition(&mut self, name: &str, node: &Statement<'_>) {
+        // Interface definitions (`.vyi`) only contain `...` or `pass` as function bodies.
+        if !has_statements(&node.body) {
+            return;
+        }
+
+        // The constructor and the fallback function are named `__init__` and `__default__`.
+        let name = match name {
+            "__init__" => "constructor",
+            "__default__" => "fallback",
+            name => name,
+        };
+
+        self.push_item_kind(CoverageItemKind::Function { name: name.to_string() }, node.range());
+        self.visit_block(&node.body);
+    }
+
+    fn visit_block(&mut self, statements: &[Statement<'_>]) {
+        let mut statements = statements.iter().peekable();
+        while let Some(statement) = statements.next() {
+            match statement.keyword() {
+                // Skip statements that don't generate any code, like `pass` and docstrings.
+                _ if statement.is_noop() => {}
+                // If statement, along with its `elif` and `else` clauses.
+                Some("if") => {
+                    // We need to store the current branch ID here since visiting the bodies may
+                    // increase `self.branch_id` in the case of nested if statements.
+                    let branch_id = self.branch_id;
+                    self.branch_id += 1;
+
+                    self.visit_branch(branch_id, 0, statement);
+                    let mut path_id = 1;
+                    while let Some(clause) =
+                        statements.next_if(|next| matches!(next.keyword(), Some("elif" | "else")))
+                    {
+                        self.visit_branch(branch_id, path_id, clause);
+                        path_id += 1;
+                    }
+                }
+                // For loops
+                Some("for") => {
+                    self.push_item_kind(CoverageItemKind::Statement, statement.line.range());
+                    self.visit_block(&statement.body);
+                }
+                _ => self.push_item_kind(CoverageItemKind::Statement, statement.line.range()),
+            }
+        }
+    }
+
+    /// Visits a single clause of an if statement.
+    ///
+    /// The branch instruction is mapped to the first opcode within the body of the clause.
+    fn visit_branch(&mut self, branch_id: u32, path_id: u32, clause: &Statement<'_>) {
+        // The condition of `if` and `elif` clauses.
+        if clause.keyword() != Some("else") {
+            self.push_item_kind(CoverageItemKind::Statement, clause.line.range());
+        }
+
+        let (Some(first), Some(last)) = (clause.body.first(), clause.body.last()) else { return };
+        if !has_statements(&clause.body) {
+            return;
+        }
+        self.push_item_kind(
+            CoverageItemKind::Branch { branch_id, path_id, is_first_opcode: true },
+            first.line.start..last.range().end,
+        );
+        self.visit_block(&clause.body);
+    }
+
+    /// Creates a coverage item for a given kind and source range. Pushes item to the internal
+    /// collection (plus additional coverage line if item is a statement).
+    fn push_item_kind(&mut self, kind: CoverageItemKind, bytes: Range<usize>) {
+        let item = CoverageItem { kind, loc: self.source_location_for(bytes), hits: 0 };
+
+        // Push a line item if we haven't already.
+        debug_assert!(!matches!(item.kind, CoverageItemKind::Line));
+        if self.last_line < item.loc.lines.start {
+            self.items.push(CoverageItem {
+                kind: CoverageItemKind::Line,
+                loc: item.loc.clone(),
+                hits: 0,
+            });
+            self.last_line = item.loc.lines.start;
+        }
+
+        self.items.push(item);
+    }
+
+    fn source_location_for(&self, bytes: Range<usize>) -> SourceLocation {
+        let start_line = self.source[..bytes.start].matches('\n').count() as u32 + 1;
+        let n_lines = self.source[bytes.clone()].lines().count() as u32;
+        SourceLocation {
+            source_id: self.source_id,
+            contract_name: self.contract_name.clone(),
+            bytes: bytes.start as u32..bytes.end as u32,
+            lines: start_line..start_line + n_lines,
+        }
+    }
+}
+
+/// A logical line of source code, which may span multiple physical lines.
+#[derive(Clone, Debug)]
+struct Line<'a> {
+    /// The indentation of the first physical line.
+    indent: usize,
+    /// The byte offset of the first non-whitespace character.
+    start: usize,
+    /// The code of the line, without the trailing comment.
+    text: &'a str,
+}
+
+impl Line<'_> {
+    fn range(&self) -> Range<usize> {
+        self.start..self.start + self.text.len()
+    }
+}
+
+/// A statement, along with the statements of its indented body, if any.
+#[derive(Clone, Debug)]
+struct Statement<'a> {
+    line: Line<'a>,
+    body: Vec<Self>,
+}
+
+impl Statement<'_> {
+    /// Returns the source range of the statement, including its body.
+    fn range(&self) -> Range<usize> {
+        let end = self.body.last().map_or(self.line.range().end, |last| last.range().end);
+        self.line.start..end
+    }
+
+    /// Returns the leading keyword of the statement.
+    fn keyword(&self) -> Option<&str> {
+        let text = self.line.text;
+        let end = text.find(|c: char| !(c.is_alphanumeric() || c == '_'));
+        let keyword = &text[..end.unwrap_or(text.len())];
+        (!keyword.is_empty()).then_some(keyword)
+    }
+
+    /// Returns `true` if the statement does not generate any code.
+    fn is_noop(&self) -> bool {
+        let text = self.line.text;
+        text == "pass" || text == "..." || text.starts_with(['"', '\''])
+    }
+
+    /// Returns the name of the function if the statement is a function definition.
+    fn function_name(&self) -> Option<&str> {
+        let rest = self.line.text.strip_prefix("def ")?.trim_start();
+        let name = &rest[..rest.find('(')?];
+        Some(name.trim())
+    }
+}
+
+/// Returns `true` if any of the given statements generates code.
+fn has_statements(statements: &[Statement<'_>]) -> bool {
+    statements.iter().any(|statement| !statement.is_noop())
+}
+
+/// Parses the statements of a block whose lines are indented more than `parent_indent`, starting
+/// at line `*idx`.
+fn parse_block<'a>(
+    lines: &[Line<'a>],
+    idx: &mut usize,
+    parent_indent: usize,
+) -> Vec<Statement<'a>> {
+    let mut statements = Vec::new();
+    let Some(indent) = lines.get(*idx).map(|line| line.indent) else { return statements };
+    if *idx > 0 && indent <= parent_indent {
+        return statements;
+    }
+
+    while let Some(line) = lines.get(*idx) {
+        if line.indent < indent {
+            break;
+        }
+        *idx += 1;
+
+        // Compound statements end with a colon and are followed by an indented body.
+        let body = if line.text.ends_with(':') {
+            parse_block(lines, idx, line.indent)
+        } else {
+            Vec::new()
+        };
+        statements.push(Statement { line: line.clone(), body });
+    }
+    statements
+}
+
+/// Splits the source code into logical lines, skipping blank lines and comments.
+///
+/// Lines are joined while brackets are open, or when a line ends with a backslash, as well as
+/// inside multi-line strings.
+fn logical_lines(source: &str) -> Vec<Line<'_>> {
+    let mut lines = Vec::new();
+    let mut current: Option<(usize, usize, usize)> = None;
+    let mut depth = 0usize;
+    let mut string: Option<&str> = None;
+
+    let mut offset = 0;
+    for physical in source.split_inclusive('\n') {
+        let line_start = offset;
+        offset += physical.len();
+
+        let code = physical.trim_end_matches(['\n', '\r']);
+        let mut code_end = None;
+        let bytes = code.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            let rest = &code[i..];
+            if let Some(quote) = string {
+                if let Some(escaped) = rest.strip_prefix('\\') {
+                    i += 1 + escaped.chars().next().map_or(0, char::len_utf8);
+                    code_end = Some(i);
+                    continue;
+                }
+                if rest.starts_with(quote) {
+                    string = None;
+                    i += quote.len();
+                    code_end = Some(i);
+                    continue;
+                }
+            } else {
+                match bytes[i] {
+                    b'#' => break,
+                    b'(' | b'[' | b'{' => depth += 1,
+                    b')' | b']' | b'}' => depth = depth.saturating_sub(1),
+                    b'"' | b'\'' => {
+                        let quote = if rest.starts_with("\"\"\"") {
+                            "\"\"\""
+                        } else if rest.starts_with("'''") {
+                            "'''"
+                        } else {
+                            &rest[..1]
+                        };
+                        string = Some(quote);
+                        i += quote.len();
+                        code_end = Some(i);
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+            i += rest.chars().next().map_or(1, char::len_utf8);
+            if string.is_some() || !bytes[i - 1].is_ascii_whitespace() {
+                code_end = Some(i);
+            }
+        }
+
+        if let Some(end) = code_end {
+            match &mut current {
+                Some((_, _, current_end)) => *current_end = line_start + end,
+                None => {
+                    let indent = code.len() - code.trim_start().len();
+                    current = Some((indent, line_start + indent, line_start + end));
+                }
+            }
+        }
+
+        let continued = depth > 0 ||
+            string.is_some_and(|quote| quote.len() == 3) ||
+            code[..code_end.unwrap_or(0)].ends_with('\\');
+        if !continued {
+            // Single-quoted strings cannot span multiple lines.
+            string = None;
+            if let Some((indent, start, end)) = current.take() {
+                lines.push(Line { indent, start, text: &source[start..end] });
+            }
+        }
+    }
+    if let Some((indent, start, end)) = current {
+        lines.push(Line { indent, start, text: &source[start..end] });
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the kind, lines and first line of code of the items found in the source, except
+    /// for the line items.
+    fn items(source: &str) -> Vec<String> {
+        let contract_name = Arc::from("Test");
+        let mut visitor = VyperVisitor::new(0, source, &contract_name);
+        visitor.visit_source();
+        visitor
+            .items
+            .iter()
+            .filter_map(|item| {
+                let kind = match &item.kind {
+                    CoverageItemKind::Line => return None,
+                    CoverageItemKind::Statement => "statement".to_string(),
+                    CoverageItemKind::Branch { branch_id, path_id, .. } => {
+                        format!("branch {branch_id}.{path_id}")
+                    }
+                    CoverageItemKind::Function { name } => format!("function {name}"),
+                };
+                let code = &source[item.loc.bytes.start as usize..item.loc.bytes.end as usize];
+                let lines = &item.loc.lines;
+                let first_line = code.lines().next().unwrap();
+                Some(format!("{kind} {}-{}: {first_line}", lines.start, lines.end - 1))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn join_logical_lines() {
+        let source = concat!(
+            "x = (1 +\n  2)  # c\n\n",
+            "s = '''a\n# not a comment\n'''\n",
+            "t = \"a # b\" \\\n  + u\n",
+        );
+        let lines = logical_lines(source);
+        let texts = lines.iter().map(|line| line.text).collect::<Vec<_>>();
+        assert_eq!(
+            texts,
+            ["x = (1 +\n  2)", "s = '''a\n# not a comment\n'''", "t = \"a # b\" \\\n  + u"]
+        );
+        assert_eq!(lines[1].start, source.find("s = ").unwrap());
+    }
+
+    #[test]
+    fn find_functions_statements_and_branches() {
+        let source = r#"# @version ^0.4.0
+"""
+@title Test
+"""
+
+interface IERC20:
+    def transfer(to: address, amount: uint256) -> bool: nonpayable
+
+owner: public(address)
+
+@deploy
+def __init__():
+    self.owner = msg.sender
+
+@external
+def f(x: uint256) -> uint256:
+    """
+    @notice not code
+    """
+    y: uint256 = 0  # a comment
+    if x > 10:
+        y = 1
+    elif x > 5:
+        pass
+    else:
+        for i: uint256 in range(3):
+            y += i
+    assert x != 3, \
+        "three"
+    return self.g(
+        y,
+    )
+
+@internal
+def g(y: uint256) -> uint256:
+    return y * 2
+"#;
+        assert_eq!(
+            items(source),
+            [
+                "function constructor 12-13: def __init__():",
+                "statement 13-13: self.owner = msg.sender",
+                "function f 16-32: def f(x: uint256) -> uint256:",
+                "statement 20-20: y: uint256 = 0",
+                "statement 21-21: if x > 10:",
+                "branch 0.0 22-22: y = 1",
+                "statement 22-22: y = 1",
+                "statement 23-23: elif x > 5:",
+                "branch 0.2 26-27: for i: uint256 in range(3):",
+                "statement 26-26: for i: uint256 in range(3):",
+                "statement 27-27: y += i",
+                "statement 28-29: assert x != 3, \\",
+                "statement 30-32: return self.g(",
+                "function g 35-36: def g(y: uint256) -> uint256:",
+                "statement 36-36: return y * 2",
+            ]
+        );
+    }
+
+    #[test]
+    fn skip_interface_functions() {
+        let source = "def foo() -> uint256:\n    ...\n\ndef bar():\n    pass\n";
+        assert!(items(source).is_empty());
+    }
+}
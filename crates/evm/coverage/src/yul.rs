@@ -0,0 +1,524 @@
+//! Coverage analysis for standalone Yul sources.
+//!
+//! The compiler output contains no AST for Yul sources, so coverage items are found by parsing the
+//! source code directly. The items are the same as the ones found for inline assembly blocks in
+//! Solidity sources, and are matched against the Yul source maps emitted by the compiler.
+
+use super::{CoverageItem, CoverageItemKind, SourceLocation};
+use eyre::{bail, ensure};
+use std::{ops::Range, sync::Arc};
+
+/// A visitor that walks the source code of a single Yul object and finds coverage items.
+#[derive(Clone, Debug)]
+pub struct YulVisitor<'a> {
+    /// The source ID of the object.
+    source_id: usize,
+    /// The source code being walked.
+    source: &'a str,
+
+    /// The name of the object being walked.
+    contract_name: Arc<str>,
+
+    /// The current branch ID
+    branch_id: u32,
+    /// Stores the last line we put in the items collection to ensure we don't push duplicate lines
+    last_line: u32,
+
+    /// Coverage items
+    pub items: Vec<CoverageItem>,
+}
+
+impl<'a> YulVisitor<'a> {
+    pub fn new(source_id: usize, source: &'a str, contract_name: &Arc<str>) -> Self {
+        Self {
+            source_id,
+            source,
+            contract_name: contract_name.clone(),
+            branch_id: 0,
+            last_line: 0,
+            items: Vec::new(),
+        }
+    }
+
+    /// Walks all the code blocks defined in the source.
+    ///
+    /// The source is either a Yul object, whose name is used as the contract name, or a single
+    /// block of code.
+    pub fn visit_source(&mut self) -> eyre::Result<()> {
+        let mut parser = Parser::new(self.source)?;
+        let mut code = Vec::new();
+        if parser.peek() == Some("object") {
+            self.contract_name = parser.parse_object(&mut code)?.into();
+        } else {
+            code.push(parser.parse_block()?);
+        }
+        ensure!(parser.peek().is_none(), "unexpected trailing code after the top-level object");
+
+        for block in &code {
+            self.visit_block(block);
+        }
+        Ok(())
+    }
+
+    fn visit_block(&mut self, block: &Block<'_>) {
+        for statement in &block.statements {
+            self.visit_statement(statement);
+        }
+    }
+
+    fn visit_statement(&mut self, statement: &Statement<'_>) {
+        match statement {
+            Statement::Block(block) => self.visit_block(block),
+            Statement::Function { name, body, range } => {
+                self.push_item_kind(
+                    CoverageItemKind::Function { name: name.to_string() },
+                    range.clone(),
+                );
+                self.visit_block(body);
+            }
+            Statement::Simple(range) => {
+                self.push_item_kind(CoverageItemKind::Statement, range.clone());
+            }
+            Statement::Expression(expression) => self.visit_expression(expression),
+            Statement::If { condition, body, range } => {
+                self.visit_expression(condition);
+
+                // We need to store the current branch ID here since visiting the body may increase
+                // `self.branch_id` in the case of nested if statements.
+                let branch_id = self.branch_id;
+                self.branch_id += 1;
+
+                self.push_item_kind(
+                    CoverageItemKind::Branch { branch_id, path_id: 0, is_first_opcode: false },
+                    range.clone(),
+                );
+                self.visit_block(body);
+            }
+            Statement::Switch { cases, .. } => {
+                // Add coverage for each case statement and their bodies.
+                for case in cases {
+                    self.push_item_kind(CoverageItemKind::Statement, case.range.clone());
+                    self.push_item_kind(CoverageItemKind::Statement, case.body.range.clone());
+                    self.visit_block(&case.body);
+                }
+            }
+            Statement::For { pre, condition, post, body, .. } => {
+                self.visit_expression(condition);
+                self.visit_block(pre);
+                self.visit_block(post);
+
+                self.push_item_kind(CoverageItemKind::Statement, body.range.clone());
+                self.visit_block(body);
+            }
+        }
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        // Only function calls count towards coverage, not identifiers and literals.
+        if expression.is_call {
+            self.push_item_kind(CoverageItemKind::Statement, expression.range.clone());
+        }
+    }
+
+    /// Creates a coverage item for a given kind and source range. Pushes item to the internal
+    /// collection (plus additional coverage line if item is a statement).
+    fn push_item_kind(&mut self, kind: CoverageItemKind, bytes: Range<usize>) {
+        let item = CoverageItem { kind, loc: self.source_location_for(bytes), hits: 0 };
+
+        // Push a line item if we haven't already.
+        debug_assert!(!matches!(item.kind, CoverageItemKind::Line));
+        if self.last_line < item.loc.lines.start {
+            self.items.push(CoverageItem {
+                kind: CoverageItemKind::Line,
+                loc: item.loc.clone(),
+                hits: 0,
+            });
+            self.last_line = item.loc.lines.start;
+        }
+
+        self.items.push(item);
+    }
+
+    fn source_location_for(&self, bytes: Range<usize>) -> SourceLocation {
+        let start_line = self.source[..bytes.start].matches('\n').count() as u32 + 1;
+        let n_lines = self.source[bytes.clone()].lines().count() as u32;
+        SourceLocation {
+            source_id: self.source_id,
+            contract_name: self.contract_name.clone(),
+            bytes: bytes.start as u32..bytes.end as u32,
+            lines: start_line..start_line + n_lines,
+        }
+    }
+}
+
+/// A block of statements enclosed in braces.
+#[derive(Clone, Debug)]
+pub(crate) struct Block<'a> {
+    pub(crate) statements: Vec<Statement<'a>>,
+    pub(crate) range: Range<usize>,
+}
+
+/// A Yul statement, with the parts that are relevant for coverage.
+#[derive(Clone, Debug)]
+pub(crate) enum Statement<'a> {
+    Block(Block<'a>),
+    Function {
+        name: &'a str,
+        body: Block<'a>,
+        range: Range<usize>,
+    },
+    /// Variable declarations, assignments, `break`, `continue` and `leave`.
+    Simple(Range<usize>),
+    Expression(Expression),
+    If {
+        condition: Expression,
+        body: Block<'a>,
+        range: Range<usize>,
+    },
+    Switch {
+        cases: Vec<Case<'a>>,
+        range: Range<usize>,
+    },
+    For {
+        pre: Block<'a>,
+        condition: Expression,
+        post: Block<'a>,
+        body: Block<'a>,
+        range: Range<usize>,
+    },
+}
+
+impl Statement<'_> {
+    /// Returns the source range of the statement.
+    pub(crate) fn range(&self) -> Range<usize> {
+        match self {
+            Self::Block(Block { range, .. }) |
+            Self::Function { range, .. } |
+            Self::Simple(range) |
+            Self::Expression(Expression { range, .. }) |
+            Self::If { range, .. } |
+            Self::Switch { range, .. } |
+            Self::For { range, .. } => range.clone(),
+        }
+    }
+}
+
+/// A `case` or `default` clause of a switch statement.
+#[derive(Clone, Debug)]
+pub(crate) struct Case<'a> {
+    /// The value of the case, or `None` for the `default` clause.
+    pub(crate) value: Option<&'a str>,
+    pub(crate) body: Block<'a>,
+    pub(crate) range: Range<usize>,
+}
+
+/// A Yul expression, which is either a function call, an identifier or a literal.
+#[derive(Clone, Debug)]
+pub(crate) struct Expression {
+    pub(crate) is_call: bool,
+    pub(crate) range: Range<usize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TokenKind {
+    Identifier,
+    Number,
+    String,
+    Punctuation,
+}
+
+#[derive(Clone, Debug)]
+struct Token {
+    kind: TokenKind,
+    range: Range<usize>,
+}
+
+/// A recursive descent parser for Yul objects and code blocks.
+pub(crate) struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<Token>,
+    /// The ranges of the comments, in order.
+    pub(crate) comments: Vec<Range<usize>>,
+    /// The index of the next token.
+    pos: usize,
+    /// The end of the last consumed token.
+    last_end: usize,
+}
+
+impl<'a> Parser<'a> {
+    pub(crate) fn new(source: &'a str) -> eyre::Result<Self> {
+        let (tokens, comments) = tokenize(source)?;
+        Ok(Self { source, tokens, comments, pos: 0, last_end: 0 })
+    }
+
+    fn text(&self, token: &Token) -> &'a str {
+        &self.source[token.range.clone()]
+    }
+
+    /// Returns the text of the token `n` tokens ahead.
+    fn peek_nth(&self, n: usize) -> Option<&'a str> {
+        self.tokens.get(self.pos + n).map(|token| self.text(token))
+    }
+
+    pub(crate) fn peek(&self) -> Option<&'a str> {
+        self.peek_nth(0)
+    }
+
+    /// The start of the next token.
+    fn start(&self) -> eyre::Result<usize> {
+        match self.tokens.get(self.pos) {
+            Some(token) => Ok(token.range.start),
+            None => bail!("unexpected end of source"),
+        }
+    }
+
+    fn next(&mut self) -> eyre::Result<Token> {
+        let Some(token) = self.tokens.get(self.pos).cloned() else {
+            bail!("unexpected end of source")
+        };
+        self.pos += 1;
+        self.last_end = token.range.end;
+        Ok(token)
+    }
+
+    /// Consumes the next token if its text is `text`.
+    fn eat(&mut self, text: &str) -> bool {
+        let matches = self.peek() == Some(text);
+        if matches {
+            self.pos += 1;
+            self.last_end = self.tokens[self.pos - 1].range.end;
+        }
+        matches
+    }
+
+    fn expect(&mut self, text: &str) -> eyre::Result<()> {
+        let found = self.peek();
+        ensure!(self.eat(text), "expected `{text}` at byte {}, found {found:?}", self.last_end);
+        Ok(())
+    }
+
+    fn expect_kind(&mut self, kind: TokenKind) -> eyre::Result<&'a str> {
+        let token = self.next()?;
+        ensure!(
+            token.kind == kind,
+            "expected {kind:?} at byte {}, found `{}`",
+            token.range.start,
+            self.text(&token)
+        );
+        Ok(self.text(&token))
+    }
+
+    /// Parses an object along with its nested objects, and collects their code blocks.
+    ///
+    /// Returns the name of the object.
+    pub(crate) fn parse_object(&mut self, code: &mut Vec<Block<'a>>) -> eyre::Result<&'a str> {
+        self.expect("object")?;
+        let name = self.expect_kind(TokenKind::String)?;
+        self.expect("{")?;
+        while !self.eat("}") {
+            match self.peek() {
+                Some("code") => {
+                    self.next()?;
+                    code.push(self.parse_block()?);
+                }
+                Some("object") => {
+                    self.parse_object(code)?;
+                }
+                Some("data") => {
+                    self.next()?;
+                    self.expect_kind(TokenKind::String)?;
+                    self.eat("hex");
+                    self.expect_kind(TokenKind::String)?;
+                }
+                other => bail!("expected `code`, `object` or `data`, found {other:?}"),
+            }
+        }
+        Ok(&name[1..name.len() - 1])
+    }
+
+    pub(crate) fn parse_block(&mut self) -> eyre::Result<Block<'a>> {
+        let start = self.start()?;
+        self.expect("{")?;
+        let mut statements = Vec::new();
+        while !self.eat("}") {
+            statements.push(self.parse_statement()?);
+        }
+        Ok(Block { statements, range: start..self.last_end })
+    }
+
+    fn parse_statement(&mut self) -> eyre::Result<Statement<'a>> {
+        let start = self.start()?;
+        let statement = match self.peek() {
+            Some("{") => Statement::Block(self.parse_block()?),
+            Some("function") => {
+                self.next()?;
+                let name = self.expect_kind(TokenKind::Identifier)?;
+                self.expect("(")?;
+                if !self.eat(")") {
+                    self.parse_identifiers()?;
+                    self.expect(")")?;
+                }
+                if self.eat("->") {
+                    self.parse_identifiers()?;
+                }
+                let body = self.parse_block()?;
+                Statement::Function { name, range: start..body.range.end, body }
+            }
+            Some("let") => {
+                self.next()?;
+                self.parse_identifiers()?;
+                if self.eat(":=") {
+                    self.parse_expression()?;
+                }
+                Statement::Simple(start..self.last_end)
+            }
+            Some("if") => {
+                self.next()?;
+                let condition = self.parse_expression()?;
+                let body = self.parse_block()?;
+                Statement::If { condition, range: start..body.range.end, body }
+            }
+            Some("switch") => {
+                self.next()?;
+                self.parse_expression()?;
+                let mut cases = Vec::new();
+                while let Some(keyword @ ("case" | "default")) = self.peek() {
+                    let case_start = self.start()?;
+                    self.next()?;
+                    let value = if keyword == "case" {
+                        let value = self.parse_expression()?;
+                        Some(&self.source[value.range])
+                    } else {
+                        None
+                    };
+                    let body = self.parse_block()?;
+                    cases.push(Case { value, range: case_start..body.range.end, body });
+                }
+                ensure!(!cases.is_empty(), "switch statement at byte {start} has no cases");
+                Statement::Switch { cases, range: start..self.last_end }
+            }
+            Some("for") => {
+                self.next()?;
+                let pre = self.parse_block()?;
+                let condition = self.parse_expression()?;
+                let post = self.parse_block()?;
+                let body = self.parse_block()?;
+                Statement::For { pre, condition, post, range: start..body.range.end, body }
+            }
+            Some("break" | "continue" | "leave") => {
+                self.next()?;
+                Statement::Simple(start..self.last_end)
+            }
+            _ if matches!(self.peek_nth(1), Some("," | ":=")) => {
+                self.parse_identifiers()?;
+                self.expect(":=")?;
+                self.parse_expression()?;
+                Statement::Simple(start..self.last_end)
+            }
+            _ => Statement::Expression(self.parse_expression()?),
+        };
+        Ok(statement)
+    }
+
+    /// Parses a comma-separated list of identifiers, with optional type names.
+    fn parse_identifiers(&mut self) -> eyre::Result<()> {
+        loop {
+            self.expect_kind(TokenKind::Identifier)?;
+            if self.eat(":") {
+                self.expect_kind(TokenKind::Identifier)?;
+            }
+            if !self.eat(",") {
+                return Ok(())
+            }
+        }
+    }
+
+    fn parse_expression(&mut self) -> eyre::Result<Expression> {
+        let token = self.next()?;
+        let start = token.range.start;
+        let is_call = match token.kind {
+            TokenKind::Identifier if self.eat("(") => {
+                if !self.eat(")") {
+                    loop {
+                        self.parse_expression()?;
+                        if !self.eat(",") {
+                            break
+                        }
+                    }
+                    self.expect(")")?;
+                }
+                true
+            }
+            TokenKind::Identifier | TokenKind::Number | TokenKind::String => {
+                // `hex"..."` literals
+                if self.text(&token) == "hex" {
+                    self.expect_kind(TokenKind::String)?;
+                }
+                // typed literals, e.g. `1:u256`
+                if self.eat(":") {
+                    self.expect_kind(TokenKind::Identifier)?;
+                }
+                false
+            }
+            TokenKind::Punctuation => {
+                bail!("expected an expression at byte {start}, found `{}`", self.text(&token))
+            }
+        };
+        Ok(Expression { is_call, range: start..self.last_end })
+    }
+}
+
+/// Splits the source code into tokens, skipping whitespace, and returns them along with the
+/// ranges of the comments.
+fn tokenize(source: &str) -> eyre::Result<(Vec<Token>, Vec<Range<usize>>)> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut comments = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let rest = &source[i..];
+        let (kind, len) = match bytes[i] {
+            c if c.is_ascii_whitespace() => {
+                i += 1;
+                continue
+            }
+            _ if rest.starts_with("//") => {
+                let len = rest.find('\n').unwrap_or(rest.len());
+                comments.push(i..i + len);
+                i += len;
+                continue
+            }
+            _ if rest.starts_with("/*") => {
+                let Some(end) = rest[2..].find("*/") else { bail!("unterminated comment") };
+                comments.push(i..i + end + 4);
+                i += end + 4;
+                continue
+            }
+            quote @ (b'"' | b'\'') => {
+                let mut j = 1;
+                while j < rest.len() && rest.as_bytes()[j] != quote {
+                    j += if rest.as_bytes()[j] == b'\\' { 2 } else { 1 };
+                }
+                ensure!(j < rest.len(), "unterminated string at byte {i}");
+                (TokenKind::String, j + 1)
+            }
+            _ if rest.starts_with(":=") || rest.starts_with("->") => (TokenKind::Punctuation, 2),
+            b'{' | b'}' | b'(' | b')' | b',' | b':' => (TokenKind::Punctuation, 1),
+            c if c.is_ascii_alphanumeric() || c == b'_' || c == b'$' => {
+                let len = rest
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '$' | '.')))
+                    .unwrap_or(rest.len());
+                let kind =
+                    if c.is_ascii_digit() { TokenKind::Number } else { TokenKind::Identifier };
+                (kind, len)
+            }
+            _ => {
+                let c = rest.chars().next().unwrap_or_default();
+                bail!("unexpected character `{c}` at byte {i}")
+            }
+        };
+        tokens.push(Token { kind, range: i..i + len });
+        i += len;
+    }
+    Ok((tokens, comments))
+}
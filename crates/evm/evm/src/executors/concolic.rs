@@ -0,0 +1,511 @@
+//! Bounded concolic search for inputs violating a simple assertion.
+//!
+//! Inputs are executed concretely with step tracing enabled. The operands of every comparison
+//! that the execution encounters are then matched against the words of the calldata: when an
+//! input word flows unchanged into a comparison, new inputs are derived by replacing it with the
+//! other operand and its neighbours, which flips the outcome of the comparison on the next run.
+//! This input-to-state correspondence reaches most branches guarded by equality and range checks
+//! on function arguments without requiring a constraint solver.
+
+use crate::executors::Executor;
+use alloy_dyn_abi::{DynSolValue, FunctionExt, JsonAbiExt};
+use alloy_json_abi::Function;
+use alloy_primitives::{map::HashSet, Address, Bytes, I256, U256};
+use eyre::{bail, Result};
+use foundry_evm_fuzz::{strategies::fuzz_calldata, FuzzFixtures};
+use foundry_evm_traces::{SparsedTraceArena, TraceMode};
+use proptest::{
+    strategy::{Strategy, ValueTree},
+    test_runner::TestRunner,
+};
+use revm::interpreter::opcode;
+use std::{cmp::Ordering, collections::VecDeque, fmt, str::FromStr};
+
+/// The number of random inputs the search starts from, in addition to the zero input.
+const RANDOM_SEEDS: usize = 8;
+
+/// Wrapper around an [`Executor`] which searches for inputs of a function that violate an
+/// [`Assertion`].
+pub struct ConcolicExecutor {
+    /// The EVM executor, with step tracing enabled.
+    executor: Executor,
+    /// The account that calls the function.
+    sender: Address,
+    /// The maximum number of calls to execute.
+    max_runs: u32,
+}
+
+/// The outcome of a [`ConcolicExecutor::search`].
+#[derive(Clone, Debug)]
+pub struct ConcolicOutcome {
+    /// The first input found that violates the assertion, if any.
+    pub counterexample: Option<ConcolicCounterExample>,
+    /// The number of calls executed.
+    pub runs: u32,
+    /// The number of calls that reverted.
+    pub reverts: u32,
+}
+
+/// A concrete input violating an assertion.
+#[derive(Clone, Debug)]
+pub struct ConcolicCounterExample {
+    /// The calldata of the call.
+    pub calldata: Bytes,
+    /// The decoded arguments of the call.
+    pub args: Vec<DynSolValue>,
+    /// The decoded return values of the call.
+    pub returns: Vec<DynSolValue>,
+}
+
+impl ConcolicExecutor {
+    /// Creates a new concolic executor.
+    pub fn new(mut executor: Executor, sender: Address, max_runs: u32) -> Self {
+        executor.set_tracing(TraceMode::Debug);
+        Self { executor, sender, max_runs }
+    }
+
+    /// Searches for inputs of `func` on the contract at `address` for which the call succeeds and
+    /// `assertion` does not hold.
+    pub fn search(
+        &self,
+        address: Address,
+        func: &Function,
+        assertion: &Assertion,
+    ) -> Result<ConcolicOutcome> {
+        // Fail early on assertions referring to unknown identifiers.
+        for name in assertion.identifiers() {
+            let is_param = func.inputs.iter().any(|param| param.name == name);
+            let is_return = (0..func.outputs.len()).any(|i| return_name(func, i) == name);
+            if !is_param && !is_return {
+                bail!("unknown identifier `{name}` in assertion");
+            }
+        }
+
+        let mut queue = VecDeque::new();
+        let mut seen = HashSet::<Bytes>::default();
+        let mut runner = TestRunner::deterministic();
+        let strategy = fuzz_calldata(func.clone(), &FuzzFixtures::default());
+        let zero = func.abi_encode_input(
+            &func.inputs.iter().map(|param| zero_value(&param.selector_type())).collect::<Vec<_>>(),
+        );
+        if let Ok(zero) = zero {
+            queue.push_back(Bytes::from(zero));
+        }
+        for _ in 0..RANDOM_SEEDS {
+            let Ok(tree) = strategy.new_tree(&mut runner) else { continue };
+            queue.push_back(tree.current());
+        }
+
+        // Seed the search with the literals of the assertion.
+        let literals = assertion.literals().flat_map(neighbours).collect::<Vec<_>>();
+        for seed in queue.clone() {
+            for word in 0..words(&seed) {
+                for literal in &literals {
+                    queue.push_back(replace_word(&seed, word, *literal));
+                }
+            }
+        }
+
+        let mut outcome = ConcolicOutcome { counterexample: None, runs: 0, reverts: 0 };
+        while outcome.runs < self.max_runs {
+            let Some(calldata) = queue.pop_front() else { break };
+            if !seen.insert(calldata.clone()) {
+                continue;
+            }
+            outcome.runs += 1;
+
+            let result =
+                self.executor.call_raw(self.sender, address, calldata.clone(), U256::ZERO)?;
+            if result.reverted {
+                outcome.reverts += 1;
+            } else if let (Ok(args), Ok(returns)) = (
+                func.abi_decode_input(&calldata[4..], false),
+                func.abi_decode_output(&result.result, false),
+            ) {
+                if !assertion.eval(|name| lookup(func, &args, &returns, name))? {
+                    outcome.counterexample =
+                        Some(ConcolicCounterExample { calldata, args, returns });
+                    break;
+                }
+            }
+
+            // Derive new inputs from the comparisons of this execution.
+            let Some(traces) = &result.traces else { continue };
+            for (a, b) in comparisons(traces) {
+                for word in 0..words(&calldata) {
+                    let value = read_word(&calldata, word);
+                    let target = if value == a {
+                        b
+                    } else if value == b {
+                        a
+                    } else {
+                        continue
+                    };
+                    for candidate in neighbours(target) {
+                        let candidate = replace_word(&calldata, word, candidate);
+                        if !seen.contains(&candidate) {
+                            queue.push_back(candidate);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(outcome)
+    }
+}
+
+/// Returns the operands of all the comparisons executed in the traces.
+///
+/// `ISZERO` is treated as a comparison with zero.
+fn comparisons(traces: &SparsedTraceArena) -> Vec<(U256, U256)> {
+    let mut comparisons = Vec::new();
+    for node in traces.arena.nodes() {
+        for step in &node.trace.steps {
+            let Some(stack) = step.stack.as_deref() else { continue };
+            match (step.op.get(), stack) {
+                (opcode::EQ | opcode::LT | opcode::GT | opcode::SLT | opcode::SGT, [.., b, a]) => {
+                    comparisons.push((*a, *b))
+                }
+                (opcode::ISZERO, [.., a]) => comparisons.push((*a, U256::ZERO)),
+                _ => {}
+            }
+        }
+    }
+    comparisons.sort_unstable();
+    comparisons.dedup();
+    comparisons
+}
+
+/// Returns the value and its direct neighbours, which are needed to flip strict and non-strict
+/// comparisons.
+fn neighbours(value: U256) -> [U256; 3] {
+    [value, value.wrapping_add(U256::from(1)), value.wrapping_sub(U256::from(1))]
+}
+
+/// Returns the number of 32 byte words in the calldata, excluding the selector.
+fn words(calldata: &[u8]) -> usize {
+    calldata.len().saturating_sub(4) / 32
+}
+
+fn read_word(calldata: &[u8], word: usize) -> U256 {
+    let start = 4 + word * 32;
+    U256::from_be_slice(&calldata[start..start + 32])
+}
+
+fn replace_word(calldata: &[u8], word: usize, value: U256) -> Bytes {
+    let start = 4 + word * 32;
+    let mut calldata = calldata.to_vec();
+    calldata[start..start + 32].copy_from_slice(&value.to_be_bytes::<32>());
+    calldata.into()
+}
+
+/// Returns the zero value of the given type, used as the first input of the search.
+fn zero_value(ty: &str) -> DynSolValue {
+    alloy_dyn_abi::DynSolType::parse(ty)
+        .map(|ty| zero_of(&ty))
+        .unwrap_or(DynSolValue::Uint(U256::ZERO, 256))
+}
+
+fn zero_of(ty: &alloy_dyn_abi::DynSolType) -> DynSolValue {
+    use alloy_dyn_abi::DynSolType;
+    match ty {
+        DynSolType::Bool => DynSolValue::Bool(false),
+        DynSolType::Int(size) => DynSolValue::Int(I256::ZERO, *size),
+        DynSolType::Uint(size) => DynSolValue::Uint(U256::ZERO, *size),
+        DynSolType::FixedBytes(size) => DynSolValue::FixedBytes(Default::default(), *size),
+        DynSolType::Address => DynSolValue::Address(Address::ZERO),
+        DynSolType::Function => DynSolValue::Function(Default::default()),
+        DynSolType::Bytes => DynSolValue::Bytes(Vec::new()),
+        DynSolType::String => DynSolValue::String(String::new()),
+        DynSolType::Array(_) => DynSolValue::Array(Vec::new()),
+        DynSolType::FixedArray(ty, size) => DynSolValue::FixedArray(vec![zero_of(ty); *size]),
+        DynSolType::Tuple(tys) => DynSolValue::Tuple(tys.iter().map(zero_of).collect()),
+        _ => DynSolValue::Uint(U256::ZERO, 256),
+    }
+}
+
+/// Returns the name by which the return value at `index` can be referred to in assertions.
+///
+/// Return values without a name are named `ret0`, `ret1`, and so on.
+fn return_name(func: &Function, index: usize) -> String {
+    let name = &func.outputs[index].name;
+    if name.is_empty() {
+        format!("ret{index}")
+    } else {
+        name.clone()
+    }
+}
+
+fn lookup<'a>(
+    func: &Function,
+    args: &'a [DynSolValue],
+    returns: &'a [DynSolValue],
+    name: &str,
+) -> Option<&'a DynSolValue> {
+    if let Some(i) = func.inputs.iter().position(|param| param.name == name) {
+        return args.get(i);
+    }
+    (0..func.outputs.len()).find(|i| return_name(func, *i) == name).and_then(|i| returns.get(i))
+}
+
+/// A simple assertion comparing function parameters, named return values and literals, e.g.
+/// `x > 0` or `balance <= limit`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Assertion {
+    pub lhs: Operand,
+    pub op: ComparisonOp,
+    pub rhs: Operand,
+}
+
+/// An operand of an [`Assertion`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Operand {
+    /// The name of a parameter or return value.
+    Ident(String),
+    /// An integer or boolean literal.
+    Literal(I256),
+}
+
+/// A comparison operator of an [`Assertion`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ComparisonOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl ComparisonOp {
+    const ALL: [(&'static str, Self); 6] = [
+        ("==", Self::Eq),
+        ("!=", Self::Ne),
+        ("<=", Self::Le),
+        (">=", Self::Ge),
+        ("<", Self::Lt),
+        (">", Self::Gt),
+    ];
+
+    fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Self::Eq => ordering.is_eq(),
+            Self::Ne => ordering.is_ne(),
+            Self::Lt => ordering.is_lt(),
+            Self::Le => ordering.is_le(),
+            Self::Gt => ordering.is_gt(),
+            Self::Ge => ordering.is_ge(),
+        }
+    }
+}
+
+impl fmt::Display for ComparisonOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (s, _) = Self::ALL.iter().find(|(_, op)| op == self).unwrap();
+        f.write_str(s)
+    }
+}
+
+impl FromStr for Assertion {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let Some((i, (token, op))) = ComparisonOp::ALL
+            .iter()
+            .filter_map(|(token, op)| s.find(token).map(|i| (i, (*token, *op))))
+            .min_by_key(|(i, (token, _))| (*i, std::cmp::Reverse(token.len())))
+        else {
+            bail!("assertion `{s}` must compare two operands with one of ==, !=, <, <=, >, >=")
+        };
+        let lhs = s[..i].parse()?;
+        let rhs = s[i + token.len()..].parse()?;
+        Ok(Self { lhs, op, rhs })
+    }
+}
+
+impl FromStr for Operand {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        match s {
+            "" => bail!("missing operand"),
+            "true" => return Ok(Self::Literal(I256::ONE)),
+            "false" => return Ok(Self::Literal(I256::ZERO)),
+            _ => {}
+        }
+        if s.starts_with(|c: char| c.is_ascii_digit() || c == '-') {
+            return I256::from_dec_str(s)
+                .or_else(|_| I256::from_hex_str(s))
+                .map(Self::Literal)
+                .map_err(|_| eyre::eyre!("invalid literal `{s}`"));
+        }
+        if !s.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$') {
+            bail!("invalid identifier `{s}`");
+        }
+        Ok(Self::Ident(s.to_string()))
+    }
+}
+
+impl fmt::Display for Assertion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.lhs, self.op, self.rhs)
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ident(name) => f.write_str(name),
+            Self::Literal(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+impl Assertion {
+    /// Returns the identifiers the assertion refers to.
+    pub fn identifiers(&self) -> impl Iterator<Item = &str> {
+        [&self.lhs, &self.rhs].into_iter().filter_map(|operand| match operand {
+            Operand::Ident(name) => Some(name.as_str()),
+            Operand::Literal(_) => None,
+        })
+    }
+
+    /// Returns the literals of the assertion as EVM words.
+    fn literals(&self) -> impl Iterator<Item = U256> + '_ {
+        [&self.lhs, &self.rhs].into_iter().filter_map(|operand| match operand {
+            Operand::Ident(_) => None,
+            Operand::Literal(value) => Some(value.into_raw()),
+        })
+    }
+
+    /// Evaluates the assertion, resolving identifiers with `lookup`.
+    pub fn eval<'a>(&self, lookup: impl Fn(&str) -> Option<&'a DynSolValue>) -> Result<bool> {
+        let value = |operand: &Operand| -> Result<Number> {
+            match operand {
+                Operand::Literal(value) => Ok(Number::from_signed(*value)),
+                Operand::Ident(name) => {
+                    let value =
+                        lookup(name).ok_or_else(|| eyre::eyre!("unknown identifier `{name}`"))?;
+                    Number::from_value(value).ok_or_else(|| {
+                        eyre::eyre!("`{name}` must be an integer, boolean or address")
+                    })
+                }
+            }
+        };
+        Ok(self.op.holds(value(&self.lhs)?.cmp(&value(&self.rhs)?)))
+    }
+}
+
+/// An integer that can be compared regardless of its signedness.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Number {
+    Negative(U256),
+    NonNegative(U256),
+}
+
+impl Number {
+    fn from_signed(value: I256) -> Self {
+        if value.is_negative() {
+            Self::Negative(value.unsigned_abs())
+        } else {
+            Self::NonNegative(value.into_raw())
+        }
+    }
+
+    fn from_value(value: &DynSolValue) -> Option<Self> {
+        match value {
+            DynSolValue::Bool(b) => Some(Self::NonNegative(U256::from(*b as u8))),
+            DynSolValue::Uint(value, _) => Some(Self::NonNegative(*value)),
+            DynSolValue::Int(value, _) => Some(Self::from_signed(*value)),
+            DynSolValue::Address(address) => Some(Self::NonNegative(address.into_word().into())),
+            _ => None,
+        }
+    }
+}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Number {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Negative(a), Self::Negative(b)) => b.cmp(a),
+            (Self::Negative(_), Self::NonNegative(_)) => Ordering::Less,
+            (Self::NonNegative(_), Self::Negative(_)) => Ordering::Greater,
+            (Self::NonNegative(a), Self::NonNegative(b)) => a.cmp(b),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_assertion() {
+        let assertion: Assertion = "x > 0".parse().unwrap();
+        assert_eq!(
+            assertion,
+            Assertion {
+                lhs: Operand::Ident("x".to_string()),
+                op: ComparisonOp::Gt,
+                rhs: Operand::Literal(I256::ZERO),
+            }
+        );
+        assert_eq!(assertion.to_string(), "x > 0");
+
+        let assertion: Assertion = "ret0>=-5".parse().unwrap();
+        assert_eq!(assertion.op, ComparisonOp::Ge);
+        assert_eq!(assertion.rhs, Operand::Literal(I256::try_from(-5).unwrap()));
+
+        assert!("x".parse::<Assertion>().is_err());
+        assert!("x + 1 > 0".parse::<Assertion>().is_err());
+    }
+
+    #[test]
+    fn eval_assertion() {
+        let x = DynSolValue::Int(I256::try_from(-3).unwrap(), 256);
+        let y = DynSolValue::Uint(U256::from(7), 256);
+        let lookup = |name: &str| match name {
+            "x" => Some(&x),
+            "y" => Some(&y),
+            _ => None,
+        };
+        assert!("x < y".parse::<Assertion>().unwrap().eval(lookup).unwrap());
+        assert!(!"x >= 0".parse::<Assertion>().unwrap().eval(lookup).unwrap());
+        assert!("y == 7".parse::<Assertion>().unwrap().eval(lookup).unwrap());
+        assert!("z == 7".parse::<Assertion>().unwrap().eval(lookup).is_err());
+    }
+
+    #[test]
+    fn replace_calldata_words() {
+        let func = Function::parse("f(uint256 a, bool b)").unwrap();
+        let args = [DynSolValue::Uint(U256::from(5), 256), DynSolValue::Bool(true)];
+        let calldata = func.abi_encode_input(&args).unwrap();
+        assert_eq!(words(&calldata), 2);
+        assert_eq!(read_word(&calldata, 0), U256::from(5));
+
+        let replaced = replace_word(&calldata, 0, U256::from(31337));
+        assert_eq!(replaced.len(), calldata.len());
+        assert_eq!(replaced[..4], calldata[..4]);
+        assert_eq!(read_word(&replaced, 0), U256::from(31337));
+        assert_eq!(read_word(&replaced, 1), U256::from(1));
+        assert_eq!(neighbours(U256::ZERO), [U256::ZERO, U256::from(1), U256::MAX]);
+    }
+
+    #[test]
+    fn lookup_params_and_returns() {
+        let func = Function::parse("f(uint256 a) returns (uint256 fee, bool)").unwrap();
+        let args = [DynSolValue::Uint(U256::from(1), 256)];
+        let returns = [DynSolValue::Uint(U256::from(2), 256), DynSolValue::Bool(false)];
+        assert_eq!(lookup(&func, &args, &returns, "a"), Some(&args[0]));
+        assert_eq!(lookup(&func, &args, &returns, "fee"), Some(&returns[0]));
+        assert_eq!(lookup(&func, &args, &returns, "ret1"), Some(&returns[1]));
+        assert_eq!(lookup(&func, &args, &returns, "ret0"), None);
+    }
+}
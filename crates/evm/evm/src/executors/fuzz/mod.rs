@@ -1,4 +1,4 @@
-use crate::executors::{Executor, FuzzTestTimer, RawCallResult};
+use crate::executors::{Executor, FailFast, FuzzTestTimer, RawCallResult};
 use alloy_dyn_abi::JsonAbiExt;
 use alloy_json_abi::Function;
 use alloy_primitives::{map::HashMap, Address, Bytes, Log, U256};
@@ -6,17 +6,23 @@
 use foundry_common::evm::Breakpoints;
 use foundry_config::FuzzConfig;
 use foundry_evm_core::{
-    constants::{MAGIC_ASSUME, TEST_TIMEOUT},
+    constants::{MAGIC_ASSUME, TEST_CANCELLED, TEST_TIMEOUT},
     decode::{RevertDecoder, SkipReason},
 };
 use foundry_evm_coverage::HitMaps;
 use foundry_evm_fuzz::{
-    strategies::{fuzz_calldata, fuzz_calldata_from_state, EvmFuzzState},
+    strategies::{
+        fuzz_calldata, fuzz_calldata_from_corpus, fuzz_calldata_from_state, EvmFuzzState,
+        FuzzCorpus, FuzzDictionaryFile, SmallDomains,
+    },
     BaseCounterExample, CounterExample, FuzzCase, FuzzError, FuzzFixtures, FuzzTestResult,
 };
 use foundry_evm_traces::SparsedTraceArena;
 use indicatif::ProgressBar;
-use proptest::test_runner::{TestCaseError, TestError, TestRunner};
+use proptest::{
+    strategy::{Strategy, ValueTree},
+    test_runner::{TestCaseError, TestError, TestRunner},
+};
 use std::{cell::RefCell, collections::BTreeMap};
 
 mod types;
@@ -63,12 +69,17 @@ pub struct FuzzedExecutor {
 
 impl FuzzedExecutor {
     /// Instantiates a fuzzed executor given a testrunner
+    ///
+    /// If fuzzing is coverage-guided, the executor collects the edge coverage of every call.
     pub fn new(
-        executor: Executor,
+        mut executor: Executor,
         runner: TestRunner,
         sender: Address,
         config: FuzzConfig,
     ) -> Self {
+        if config.coverage_guided {
+            executor.inspector_mut().collect_edge_coverage(true);
+        }
         Self { executor, runner, sender, config }
     }
 
@@ -76,6 +87,9 @@ pub fn new(
     /// If `should_fail` is set to `true`, then it will stop only when there's a success
     /// test case.
     ///
+    /// If another test fails while `fail_fast` is enabled, it stops after the current case and the
+    /// test is reported as skipped.
+    ///
     /// Returns a list of all the consumed gas and calldata of every fuzz case
     #[allow(clippy::too_many_arguments)]
     pub fn fuzz(
@@ -86,14 +100,20 @@ pub fn fuzz(
         address: Address,
         rd: &RevertDecoder,
         progress: Option<&ProgressBar>,
+        fail_fast: &FailFast,
     ) -> FuzzTestResult {
         // Stores the fuzz test execution data.
         let execution_data = RefCell::new(FuzzTestData::default());
         let state = self.build_fuzz_state(deployed_libs);
         let dictionary_weight = self.config.dictionary.dictionary_weight.min(100);
+        // When fuzzing is coverage-guided, half of the inputs are mutations of the inputs that
+        // increased the coverage.
+        let corpus = FuzzCorpus::default();
+        let corpus_weight = if self.config.coverage_guided { 100 } else { 0 };
         let strategy = proptest::prop_oneof![
             100 - dictionary_weight => fuzz_calldata(func.clone(), fuzz_fixtures),
             dictionary_weight => fuzz_calldata_from_state(func.clone(), &state),
+            corpus_weight => fuzz_calldata_from_corpus(func.clone(), fuzz_fixtures, &corpus),
         ];
         // We want to collect at least one trace which will be displayed to user.
         let max_traces_to_collect = std::cmp::max(1, self.config.gas_report_samples) as usize;
@@ -102,12 +122,18 @@ pub fn fuzz(
         // Start timer for this fuzz test.
         let timer = FuzzTestTimer::new(self.config.timeout);
 
-        let run_result = self.runner.clone().run(&strategy, |calldata| {
+        let run_case = |calldata: Bytes| {
             // Check if the timeout has been reached.
             if timer.is_timed_out() {
                 return Err(TestCaseError::fail(TEST_TIMEOUT));
             }
 
+            // Stop the run if another test failed. This is not a failure of this test, so it's
+            // reported as skipped and not persisted as a failure below.
+            if fail_fast.should_stop() {
+                return Err(TestCaseError::fail(TEST_CANCELLED));
+            }
+
             let fuzz_res = self.single_fuzz(address, calldata)?;
 
             // If running with progress then increment current run.
@@ -117,6 +143,10 @@ pub fn fuzz(
 
             match fuzz_res {
                 FuzzOutcome::Case(case) => {
+                    if let Some(edges) = &case.edge_coverage {
+                        corpus.add(&case.case.calldata, edges);
+                    }
+
                     let mut data = execution_data.borrow_mut();
                     data.gas_by_case.push((case.case.gas, case.case.stipend));
 
@@ -158,7 +188,12 @@ pub fn fuzz(
                     Err(TestCaseError::fail(reason.unwrap_or_default()))
                 }
             }
-        });
+        };
+
+        let mut runner = self.runner.clone();
+        let run_result = self
+            .run_small_domains(func, fuzz_fixtures, &strategy, &mut runner, run_case)
+            .and_then(|()| runner.run(&strategy, run_case));
 
         let fuzz_result = execution_data.into_inner();
         let (calldata, call) = fuzz_result.counterexample;
@@ -184,10 +219,17 @@ pub fn fuzz(
             gas_report_traces: traces.into_iter().map(|a| a.arena).collect(),
             coverage: fuzz_result.coverage,
             deprecated_cheatcodes: fuzz_result.deprecated_cheatcodes,
+            dictionary: self.config.dictionary_export.is_some().then(|| state.export()),
         };
 
         match run_result {
             Ok(()) => {}
+            // The cases that were run passed, but the test is not complete.
+            Err(TestError::Fail(reason, _)) if reason.message() == TEST_CANCELLED => {
+                result.success = true;
+                result.skipped = true;
+                result.reason = Some(FailFast::CANCELLED.to_string());
+            }
             Err(TestError::Abort(reason)) => {
                 let msg = reason.message();
                 // Currently the only operation that can trigger proptest global rejects is the
@@ -228,10 +270,44 @@ pub fn fuzz(
         }
 
         state.log_stats();
+        if self.config.coverage_guided {
+            trace!(corpus_size = corpus.len(), "coverage-guided fuzzing corpus");
+        }
 
         result
     }
 
+    /// Runs the combinations of the values of the parameters with small domains, if enabled with
+    /// `exhaustive_small_domains`. The other parameters are sampled from `strategy`.
+    fn run_small_domains(
+        &self,
+        func: &Function,
+        fuzz_fixtures: &FuzzFixtures,
+        strategy: &impl Strategy<Value = Bytes>,
+        runner: &mut TestRunner,
+        run_case: impl Fn(Bytes) -> Result<(), TestCaseError>,
+    ) -> Result<(), TestError<Bytes>> {
+        if !self.config.exhaustive_small_domains {
+            return Ok(())
+        }
+        let Some(domains) = SmallDomains::new(func, fuzz_fixtures, self.config.runs as usize)
+        else {
+            return Ok(())
+        };
+        for index in 0..domains.len() {
+            let sampled = strategy.new_tree(runner).map_err(TestError::Abort)?.current();
+            let Ok(mut args) = func.abi_decode_input(&sampled[4..], false) else { continue };
+            domains.apply(index, &mut args);
+            let Ok(calldata) = func.abi_encode_input(&args) else { continue };
+            let calldata = Bytes::from(calldata);
+            match run_case(calldata.clone()) {
+                Ok(()) | Err(TestCaseError::Reject(_)) => {}
+                Err(TestCaseError::Fail(reason)) => return Err(TestError::Fail(reason, calldata)),
+            }
+        }
+        Ok(())
+    }
+
     /// Granular and single-step function that runs only one fuzz and returns either a `CaseOutcome`
     /// or a `CounterExampleOutcome`
     pub fn single_fuzz(
@@ -260,6 +336,7 @@ pub fn single_fuzz(
                 case: FuzzCase { calldata, gas: call.gas_used, stipend: call.stipend },
                 traces: call.traces,
                 coverage: call.coverage,
+                edge_coverage: call.edge_coverage,
                 breakpoints,
                 logs: call.logs,
                 deprecated_cheatcodes,
@@ -275,7 +352,7 @@ pub fn single_fuzz(
 
     /// Stores fuzz state for use with [fuzz_calldata_from_state]
     pub fn build_fuzz_state(&self, deployed_libs: &[Address]) -> EvmFuzzState {
-        if let Some(fork_db) = self.executor.backend().active_fork_db() {
+        let state = if let Some(fork_db) = self.executor.backend().active_fork_db() {
             EvmFuzzState::new(fork_db, self.config.dictionary, deployed_libs)
         } else {
             EvmFuzzState::new(
@@ -283,6 +360,16 @@ pub fn build_fuzz_state(&self, deployed_libs: &[Address]) -> EvmFuzzState {
                 self.config.dictionary,
                 deployed_libs,
             )
+        };
+
+        // Import the configured dictionary files.
+        for path in &self.config.dictionary_files {
+            match FuzzDictionaryFile::load(path) {
+                Ok(file) => state.import(&file),
+                Err(err) => warn!(?path, %err, "failed to import fuzz dictionary"),
+            }
         }
+
+        state
     }
 }
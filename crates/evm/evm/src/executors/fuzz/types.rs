@@ -15,6 +15,8 @@ pub struct CaseOutcome {
     pub traces: Option<SparsedTraceArena>,
     /// The coverage info collected during the call.
     pub coverage: Option<HitMaps>,
+    /// The edge coverage collected during the call, if fuzzing is coverage-guided.
+    pub edge_coverage: Option<Vec<(u32, u8)>>,
     /// Breakpoints char pc map.
     pub breakpoints: Breakpoints,
     /// logs of a single fuzz test case.
@@ -10,7 +10,7 @@
 use foundry_evm_core::{
     constants::{
         CALLER, CHEATCODE_ADDRESS, DEFAULT_CREATE2_DEPLOYER, HARDHAT_CONSOLE_ADDRESS, MAGIC_ASSUME,
-        TEST_TIMEOUT,
+        TEST_CANCELLED, TEST_TIMEOUT,
     },
     precompiles::PRECOMPILES,
 };
@@ -33,7 +33,7 @@
 use revm::primitives::HashMap;
 use shrink::shrink_sequence;
 use std::{
-    cell::RefCell,
+    cell::{Cell, RefCell},
     collections::{btree_map::Entry, HashMap as Map},
     sync::Arc,
 };
@@ -50,7 +50,7 @@
 use serde::{Deserialize, Serialize};
 
 mod shrink;
-use crate::executors::{EvmError, FuzzTestTimer};
+use crate::executors::{EvmError, FailFast, FuzzTestTimer};
 pub use shrink::check_sequence;
 
 sol! {
@@ -319,12 +319,16 @@ pub fn new(
     }
 
     /// Fuzzes any deployed contract and checks any broken invariant at `invariant_address`.
+    ///
+    /// If another test fails while `fail_fast` is enabled, the campaign stops after the current
+    /// call and the result is marked as cancelled.
     pub fn invariant_fuzz(
         &mut self,
         invariant_contract: InvariantContract<'_>,
         fuzz_fixtures: &FuzzFixtures,
         deployed_libs: &[Address],
         progress: Option<&ProgressBar>,
+        fail_fast: &FailFast,
     ) -> Result<InvariantFuzzTestResult> {
         // Throw an error to abort test run if the invariant function accepts input params
         if !invariant_contract.invariant_function.inputs.is_empty() {
@@ -336,6 +340,7 @@ pub fn invariant_fuzz(
 
         // Start timer for this invariant test.
         let timer = FuzzTestTimer::new(self.config.timeout);
+        let cancelled = Cell::new(false);
 
         let _ = self.runner.run(&invariant_strategy, |first_input| {
             // Create current invariant run data.
@@ -361,6 +366,12 @@ pub fn invariant_fuzz(
                     return Err(TestCaseError::fail(TEST_TIMEOUT));
                 }
 
+                // Check if another test failed and the campaign should be stopped.
+                if fail_fast.should_stop() {
+                    cancelled.set(true);
+                    return Err(TestCaseError::fail(TEST_CANCELLED));
+                }
+
                 let tx = current_run.inputs.last().ok_or_else(|| {
                     TestCaseError::fail("no input generated to called fuzz target")
                 })?;
@@ -503,6 +514,7 @@ pub fn invariant_fuzz(
             gas_report_traces: result.gas_report_traces,
             coverage: result.coverage,
             metrics: result.metrics,
+            cancelled: cancelled.get(),
         })
     }
 
@@ -32,6 +32,8 @@ pub struct InvariantFuzzTestResult {
     pub coverage: Option<HitMaps>,
     /// Fuzzed selectors metrics collected during the invariant test runs.
     pub metrics: HashMap<String, InvariantMetrics>,
+    /// Whether the campaign was stopped before completing all runs because another test failed.
+    pub cancelled: bool,
 }
 
 /// Enriched results of an invariant run check.
@@ -38,12 +38,19 @@
 };
 use std::{
     borrow::Cow,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     time::{Duration, Instant},
 };
 
 mod builder;
 pub use builder::ExecutorBuilder;
 
+pub mod concolic;
+pub use concolic::ConcolicExecutor;
+
 pub mod fuzz;
 pub use fuzz::FuzzedExecutor;
 
@@ -787,6 +794,8 @@ pub struct RawCallResult {
     pub traces: Option<SparsedTraceArena>,
     /// The coverage info collected during the call
     pub coverage: Option<HitMaps>,
+    /// The edge coverage collected during the call, for coverage-guided fuzzing
+    pub edge_coverage: Option<Vec<(u32, u8)>>,
     /// Scripted transactions generated from this call
     pub transactions: Option<BroadcastableTransactions>,
     /// The changeset of the state.
@@ -815,6 +824,7 @@ fn default() -> Self {
             labels: HashMap::default(),
             traces: None,
             coverage: None,
+            edge_coverage: None,
             transactions: None,
             state_changeset: HashMap::default(),
             env: EnvWithHandlerCfg::new_with_spec_id(Box::default(), SpecId::LATEST),
@@ -945,8 +955,15 @@ fn convert_executed_result(
         _ => Bytes::new(),
     };
 
-    let InspectorData { mut logs, labels, traces, coverage, cheatcodes, chisel_state } =
-        inspector.collect();
+    let InspectorData {
+        mut logs,
+        labels,
+        traces,
+        coverage,
+        edge_coverage,
+        cheatcodes,
+        chisel_state,
+    } = inspector.collect();
 
     if logs.is_empty() {
         logs = exec_logs;
@@ -969,6 +986,7 @@ fn convert_executed_result(
         labels,
         traces,
         coverage,
+        edge_coverage,
         transactions,
         state_changeset,
         env,
@@ -994,3 +1012,39 @@ pub fn is_timed_out(&self) -> bool {
         self.inner.is_some_and(|(start, duration)| start.elapsed() > duration)
     }
 }
+
+/// Stops the remaining tests after the first failure, used by `forge test --fail-fast`.
+///
+/// Once a failure is recorded, tests that have not started yet are not run, and fuzz and invariant
+/// tests stop after their current case.
+#[derive(Clone, Debug, Default)]
+pub struct FailFast {
+    /// Set once a test failed, `None` if fail-fast is disabled.
+    inner: Option<Arc<AtomicBool>>,
+}
+
+impl FailFast {
+    /// The reason reported for the tests that were stopped or not run.
+    pub const CANCELLED: &'static str = "cancelled after the first failure";
+
+    pub fn new(enabled: bool) -> Self {
+        Self { inner: enabled.then(Default::default) }
+    }
+
+    /// Whether fail-fast is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.inner.is_some()
+    }
+
+    /// Records a test failure, signaling all other tests to stop.
+    pub fn record_fail(&self) {
+        if let Some(failed) = &self.inner {
+            failed.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Whether a test failed and the current test should be stopped.
+    pub fn should_stop(&self) -> bool {
+        self.inner.as_ref().is_some_and(|failed| failed.load(Ordering::Relaxed))
+    }
+}
@@ -2,7 +2,7 @@
 
 pub use foundry_cheatcodes::{self as cheatcodes, Cheatcodes, CheatsConfig};
 pub use foundry_evm_coverage::CoverageCollector;
-pub use foundry_evm_fuzz::Fuzzer;
+pub use foundry_evm_fuzz::{EdgeCovInspector, Fuzzer};
 pub use foundry_evm_traces::{StackSnapshotType, TracingInspector, TracingInspectorConfig};
 
 pub use revm_inspectors::access_list::AccessListInspector;
@@ -1,16 +1,24 @@
 use super::{
-    Cheatcodes, CheatsConfig, ChiselState, CoverageCollector, Fuzzer, LogCollector,
-    TracingInspector,
+    Cheatcodes, CheatsConfig, ChiselState, CoverageCollector, EdgeCovInspector, Fuzzer,
+    LogCollector, TracingInspector,
+};
+use alloy_primitives::{
+    map::{AddressHashMap, HashSet},
+    Address, Bytes, Log, Selector, TxKind, B256, U256,
+};
+use foundry_cheatcodes::{CheatcodesExecutor, Vm, Wallets};
+use foundry_evm_core::{
+    backend::DatabaseExt,
+    constants::{CHEATCODE_ADDRESS, HARDHAT_CONSOLE_ADDRESS},
+    fork::{CachedCall, ForkCallCache},
+    InspectorExt,
 };
-use alloy_primitives::{map::AddressHashMap, Address, Bytes, Log, TxKind, U256};
-use foundry_cheatcodes::{CheatcodesExecutor, Wallets};
-use foundry_evm_core::{backend::DatabaseExt, InspectorExt};
 use foundry_evm_coverage::HitMaps;
 use foundry_evm_traces::{SparsedTraceArena, TraceMode};
 use revm::{
     inspectors::CustomPrintTracer,
     interpreter::{
-        CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome, EOFCreateInputs,
+        opcode, CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome, EOFCreateInputs,
         EOFCreateKind, Gas, InstructionResult, Interpreter, InterpreterResult,
     },
     primitives::{
@@ -21,9 +29,20 @@
 };
 use std::{
     ops::{Deref, DerefMut},
-    sync::Arc,
+    sync::{Arc, LazyLock},
 };
 
+/// The selectors of the assertion cheatcodes, e.g. `assertEq`.
+static ASSERTION_SELECTORS: LazyLock<Arc<HashSet<Selector>>> = LazyLock::new(|| {
+    Arc::new(
+        Vm::CHEATCODES
+            .iter()
+            .filter(|cheatcode| cheatcode.func.id.starts_with("assert"))
+            .map(|cheatcode| cheatcode.func.selector_bytes.into())
+            .collect(),
+    )
+});
+
 #[derive(Clone, Debug, Default)]
 #[must_use = "builders do nothing unless you call `build` on them"]
 pub struct InspectorStackBuilder {
@@ -47,6 +66,8 @@ pub struct InspectorStackBuilder {
     pub logs: Option<bool>,
     /// Whether coverage info should be collected.
     pub coverage: Option<bool>,
+    /// Whether to record if the effects of the functions covered are asserted on.
+    pub assertion_coverage: Option<bool>,
     /// Whether to print all opcode traces into the console. Useful for debugging the EVM.
     pub print: Option<bool>,
     /// The chisel state inspector.
@@ -61,6 +82,8 @@ pub struct InspectorStackBuilder {
     pub wallets: Option<Wallets>,
     /// The CREATE2 deployer address.
     pub create2_deployer: Address,
+    /// The cache of static calls made against forks.
+    pub fork_call_cache: Option<ForkCallCache>,
 }
 
 impl InspectorStackBuilder {
@@ -126,6 +149,13 @@ pub fn coverage(mut self, yes: bool) -> Self {
         self
     }
 
+    /// Set whether to record if the effects of the functions covered are asserted on.
+    #[inline]
+    pub fn assertion_coverage(mut self, yes: bool) -> Self {
+        self.assertion_coverage = Some(yes);
+        self
+    }
+
     /// Set whether to enable the trace printer.
     #[inline]
     pub fn print(mut self, yes: bool) -> Self {
@@ -164,6 +194,13 @@ pub fn create2_deployer(mut self, create2_deployer: Address) -> Self {
         self
     }
 
+    /// Set the cache used to answer static calls made against forks.
+    #[inline]
+    pub fn fork_call_cache(mut self, cache: Option<ForkCallCache>) -> Self {
+        self.fork_call_cache = cache;
+        self
+    }
+
     /// Builds the stack of inspectors to use when transacting/committing on the EVM.
     pub fn build(self) -> InspectorStack {
         let Self {
@@ -174,12 +211,14 @@ pub fn build(self) -> InspectorStack {
             trace_mode,
             logs,
             coverage,
+            assertion_coverage,
             print,
             chisel_state,
             enable_isolation,
             odyssey,
             wallets,
             create2_deployer,
+            fork_call_cache,
         } = self;
         let mut stack = InspectorStack::new();
 
@@ -200,6 +239,7 @@ pub fn build(self) -> InspectorStack {
             stack.set_chisel(chisel_state);
         }
         stack.collect_coverage(coverage.unwrap_or(false));
+        stack.collect_assertion_coverage(assertion_coverage.unwrap_or(false));
         stack.collect_logs(logs.unwrap_or(true));
         stack.print(print.unwrap_or(false));
         stack.tracing(trace_mode);
@@ -207,6 +247,7 @@ pub fn build(self) -> InspectorStack {
         stack.enable_isolation(enable_isolation);
         stack.odyssey(odyssey);
         stack.set_create2_deployer(create2_deployer);
+        stack.set_fork_call_cache(fork_call_cache);
 
         // environment, must come after all of the inspectors
         if let Some(block) = block {
@@ -248,6 +289,7 @@ pub struct InspectorData {
     pub labels: AddressHashMap<String>,
     pub traces: Option<SparsedTraceArena>,
     pub coverage: Option<HitMaps>,
+    pub edge_coverage: Option<Vec<(u32, u8)>>,
     pub cheatcodes: Option<Cheatcodes>,
     pub chisel_state: Option<(Vec<U256>, Vec<u8>, InstructionResult)>,
 }
@@ -286,6 +328,7 @@ pub struct InspectorStack {
 pub struct InspectorStackInner {
     pub chisel_state: Option<ChiselState>,
     pub coverage: Option<CoverageCollector>,
+    pub edge_coverage: Option<EdgeCovInspector>,
     pub fuzzer: Option<Fuzzer>,
     pub log_collector: Option<LogCollector>,
     pub printer: Option<CustomPrintTracer>,
@@ -298,6 +341,20 @@ pub struct InspectorStackInner {
     pub in_inner_context: bool,
     pub inner_context_data: Option<InnerContextData>,
     pub top_frame_journal: HashMap<Address, Account>,
+
+    /// The cache of static calls made against forks.
+    pub fork_call_cache: Option<ForkCallCache>,
+    /// The cacheable calls being executed, innermost last.
+    pending_fork_calls: Vec<PendingForkCall>,
+}
+
+/// A static call made against a fork whose result is cached when it returns.
+#[derive(Clone, Debug)]
+struct PendingForkCall {
+    key: B256,
+    depth: usize,
+    /// The accounts whose code ran, or whose balance or code was read, so far.
+    accounts: Vec<Address>,
 }
 
 /// Struct keeping mutable references to both parts of [InspectorStack] and implementing
@@ -342,7 +399,16 @@ macro_rules! push {
                     )*
                 };
             }
-            push!(cheatcodes, chisel_state, coverage, fuzzer, log_collector, printer, tracer);
+            push!(
+                cheatcodes,
+                chisel_state,
+                coverage,
+                edge_coverage,
+                fuzzer,
+                log_collector,
+                printer,
+                tracer
+            );
             if self.enable_isolation {
                 enabled.push("isolation");
             }
@@ -397,6 +463,24 @@ pub fn collect_coverage(&mut self, yes: bool) {
         self.coverage = yes.then(Default::default);
     }
 
+    /// Set whether the coverage collector records if the effects of the functions covered are
+    /// asserted on.
+    ///
+    /// Has no effect unless coverage is collected, so it must be called after
+    /// [`collect_coverage`](Self::collect_coverage).
+    #[inline]
+    pub fn collect_assertion_coverage(&mut self, yes: bool) {
+        if yes && self.coverage.is_some() {
+            self.coverage = Some(CoverageCollector::with_assertions(ASSERTION_SELECTORS.clone()));
+        }
+    }
+
+    /// Set whether to collect the edge coverage used by coverage-guided fuzzing.
+    #[inline]
+    pub fn collect_edge_coverage(&mut self, yes: bool) {
+        self.edge_coverage = yes.then(Default::default);
+    }
+
     /// Set whether to enable call isolation.
     #[inline]
     pub fn enable_isolation(&mut self, yes: bool) {
@@ -415,6 +499,12 @@ pub fn set_create2_deployer(&mut self, deployer: Address) {
         self.create2_deployer = deployer;
     }
 
+    /// Set the cache used to answer static calls made against forks.
+    #[inline]
+    pub fn set_fork_call_cache(&mut self, cache: Option<ForkCallCache>) {
+        self.fork_call_cache = cache;
+    }
+
     /// Set whether to enable the log collector.
     #[inline]
     pub fn collect_logs(&mut self, yes: bool) {
@@ -442,7 +532,10 @@ pub fn tracing(&mut self, mode: TraceMode) {
     pub fn collect(self) -> InspectorData {
         let Self {
             mut cheatcodes,
-            inner: InspectorStackInner { chisel_state, coverage, log_collector, tracer, .. },
+            inner:
+                InspectorStackInner {
+                    chisel_state, coverage, edge_coverage, log_collector, tracer, ..
+                },
         } = self;
 
         let traces = tracer.map(|tracer| tracer.into_traces()).map(|arena| {
@@ -471,6 +564,7 @@ pub fn collect(self) -> InspectorData {
                 .unwrap_or_default(),
             traces,
             coverage: coverage.map(|coverage| coverage.finish()),
+            edge_coverage: edge_coverage.map(|edge_coverage| edge_coverage.finish()),
             cheatcodes,
             chisel_state: chisel_state.and_then(|state| state.state),
         }
@@ -502,7 +596,13 @@ fn do_call_end(
         let result = outcome.result.result;
         call_inspectors!(
             #[ret]
-            [&mut self.fuzzer, &mut self.tracer, &mut self.cheatcodes, &mut self.printer],
+            [
+                &mut self.fuzzer,
+                &mut self.tracer,
+                &mut self.coverage,
+                &mut self.cheatcodes,
+                &mut self.printer,
+            ],
             |inspector| {
                 let new_outcome = inspector.call_end(ecx, inputs, outcome.clone());
 
@@ -527,7 +627,7 @@ fn do_create_end(
         let result = outcome.result.result;
         call_inspectors!(
             #[ret]
-            [&mut self.tracer, &mut self.cheatcodes, &mut self.printer],
+            [&mut self.tracer, &mut self.coverage, &mut self.cheatcodes, &mut self.printer],
             |inspector| {
                 let new_outcome = inspector.create_end(ecx, call, outcome.clone());
 
@@ -568,6 +668,99 @@ fn do_eofcreate_end(
         outcome
     }
 
+    /// Returns the cached result of a static call made against a fork, if it is cacheable and its
+    /// result is known. Otherwise, starts tracking it so its result is cached when it returns.
+    fn cached_fork_call(
+        &mut self,
+        ecx: &mut EvmContext<&mut dyn DatabaseExt>,
+        call: &CallInputs,
+    ) -> Option<CallOutcome> {
+        let cache = self.fork_call_cache.as_ref()?;
+        if call.scheme != CallScheme::StaticCall ||
+            self.in_inner_context ||
+            self.cheatcodes.as_ref().is_some_and(|cheatcodes| cheatcodes.observes_calls())
+        {
+            return None;
+        }
+        let fork = ecx.db.ensure_fork_id(ecx.db.active_fork_id()?).ok()?;
+        let key = ForkCallCache::key(
+            fork,
+            &ecx.env,
+            call.caller,
+            call.target_address,
+            call.bytecode_address,
+            &call.input,
+        );
+
+        // A hit skips the execution of the call, so its subcalls wouldn't be traced.
+        let cached = self.tracer.is_none().then(|| {
+            cache.get(&key, |cached| {
+                cached.gas_used <= call.gas_limit &&
+                    cached.accounts.iter().all(|account| {
+                        ecx.db.is_unmodified_fork_account(account, &ecx.journaled_state)
+                    })
+            })
+        });
+        let Some(cached) = cached.flatten() else {
+            self.pending_fork_calls.push(PendingForkCall {
+                key,
+                depth: ecx.journaled_state.depth,
+                accounts: vec![call.target_address, call.bytecode_address],
+            });
+            return None;
+        };
+
+        let mut gas = Gas::new(call.gas_limit);
+        let _ = gas.record_cost(cached.gas_used);
+        Some(CallOutcome {
+            result: InterpreterResult {
+                result: InstructionResult::Return,
+                output: cached.output,
+                gas,
+            },
+            memory_offset: call.return_memory_offset.clone(),
+        })
+    }
+
+    /// Caches the result of a static call made against a fork, if it was tracked by
+    /// [`Self::cached_fork_call`], succeeded, and only ran the code of unmodified fork accounts.
+    fn cache_fork_call(
+        &mut self,
+        ecx: &mut EvmContext<&mut dyn DatabaseExt>,
+        outcome: &CallOutcome,
+    ) {
+        let depth = ecx.journaled_state.depth;
+        if self.pending_fork_calls.last().is_none_or(|pending| pending.depth != depth) {
+            return;
+        }
+        let Some(PendingForkCall { key, mut accounts, .. }) = self.pending_fork_calls.pop() else {
+            return;
+        };
+        accounts.sort_unstable();
+        accounts.dedup();
+
+        // Calls to cheatcodes or to the console have effects beyond their return data.
+        let is_cacheable = outcome.result.is_ok() &&
+            !accounts.contains(&CHEATCODE_ADDRESS) &&
+            !accounts.contains(&HARDHAT_CONSOLE_ADDRESS) &&
+            accounts
+                .iter()
+                .all(|account| ecx.db.is_unmodified_fork_account(account, &ecx.journaled_state));
+        if let Some(cache) = self.fork_call_cache.as_ref().filter(|_| is_cacheable) {
+            cache.insert(
+                key,
+                CachedCall {
+                    output: outcome.result.output.clone(),
+                    gas_used: outcome.result.gas.spent(),
+                    accounts: accounts.clone(),
+                },
+            );
+        }
+        if let Some(parent) = self.pending_fork_calls.last_mut() {
+            parent.accounts.extend(accounts);
+        }
+    }
+
     fn transact_inner(
         &mut self,
         ecx: &mut EvmContext<&mut dyn DatabaseExt>,
@@ -715,6 +908,8 @@ fn with_stack<O>(&mut self, f: impl FnOnce(&mut InspectorStack) -> O) -> O {
 
     /// Invoked at the beginning of a new top-level (0 depth) frame.
     fn top_level_frame_start(&mut self, ecx: &mut EvmContext<&mut dyn DatabaseExt>) {
+        // Drop the calls of a previous transaction that was aborted before they returned.
+        self.pending_fork_calls.clear();
         if self.enable_isolation {
             // If we're in isolation mode, we need to keep track of the state at the beginning of
             // the frame to be able to roll back on revert
@@ -760,11 +955,30 @@ fn initialize_interp(
     }
 
     fn step(&mut self, interpreter: &mut Interpreter, ecx: &mut EvmContext<&mut dyn DatabaseExt>) {
+        if let Some(pending) = self.pending_fork_calls.last_mut() {
+            // The result of the call also depends on the balances and code it reads.
+            match interpreter.current_opcode() {
+                opcode::BALANCE |
+                opcode::EXTCODESIZE |
+                opcode::EXTCODECOPY |
+                opcode::EXTCODEHASH => {
+                    if let Ok(word) = interpreter.stack.peek(0) {
+                        pending.accounts.push(Address::from_word(word.into()));
+                    }
+                }
+                opcode::SELFBALANCE => {
+                    pending.accounts.push(interpreter.contract.target_address);
+                }
+                _ => {}
+            }
+        }
+
         call_inspectors!(
             [
                 &mut self.fuzzer,
                 &mut self.tracer,
                 &mut self.coverage,
+                &mut self.edge_coverage,
                 &mut self.cheatcodes,
                 &mut self.printer,
             ],
@@ -778,7 +992,13 @@ fn step_end(
         ecx: &mut EvmContext<&mut dyn DatabaseExt>,
     ) {
         call_inspectors!(
-            [&mut self.tracer, &mut self.cheatcodes, &mut self.chisel_state, &mut self.printer],
+            [
+                &mut self.coverage,
+                &mut self.tracer,
+                &mut self.cheatcodes,
+                &mut self.chisel_state,
+                &mut self.printer,
+            ],
             |inspector| inspector.step_end(interpreter, ecx),
         );
     }
@@ -809,9 +1029,19 @@ fn call(
             self.top_level_frame_start(ecx);
         }
 
+        if let Some(pending) = self.pending_fork_calls.last_mut() {
+            pending.accounts.extend([call.target_address, call.bytecode_address]);
+        }
+
         call_inspectors!(
             #[ret]
-            [&mut self.fuzzer, &mut self.tracer, &mut self.log_collector, &mut self.printer],
+            [
+                &mut self.fuzzer,
+                &mut self.tracer,
+                &mut self.coverage,
+                &mut self.log_collector,
+                &mut self.printer,
+            ],
             |inspector| {
                 let mut out = None;
                 if let Some(output) = inspector.call(ecx, call) {
@@ -843,6 +1073,10 @@ fn call(
             }
         }
 
+        if let Some(outcome) = self.cached_fork_call(ecx, call) {
+            return Some(outcome);
+        }
+
         if self.enable_isolation &&
             call.scheme == CallScheme::Call &&
             !self.in_inner_context &&
@@ -875,6 +1109,7 @@ fn call_end(
         }
 
         let outcome = self.do_call_end(ecx, inputs, outcome);
+        self.cache_fork_call(ecx, &outcome);
 
         if ecx.journaled_state.depth == 0 {
             self.top_level_frame_end(ecx, outcome.result.result);
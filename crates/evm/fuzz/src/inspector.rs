@@ -1,9 +1,13 @@
 use crate::{invariant::RandomCallGenerator, strategies::EvmFuzzState};
+use alloy_primitives::Address;
 use revm::{
-    interpreter::{CallInputs, CallOutcome, CallScheme, Interpreter},
+    interpreter::{opcode, CallInputs, CallOutcome, CallScheme, Interpreter},
     Database, EvmContext, Inspector,
 };
 
+/// The number of entries of the edge coverage maps collected by [`EdgeCovInspector`].
+pub const EDGE_MAP_SIZE: usize = 1 << 16;
+
 /// An inspector that can fuzz and collect data for that effect.
 #[derive(Clone, Debug)]
 pub struct Fuzzer {
@@ -94,3 +98,60 @@ fn override_call(&mut self, call: &mut CallInputs) {
         }
     }
 }
+
+/// An inspector that collects the edge coverage of the executed code, for coverage-guided fuzzing.
+///
+/// The edges are the jumps taken and not taken, identified by the address of the executed code
+/// and the program counters of the jump and of the instruction executed after it. Their hit counts
+/// are stored in a map of [`EDGE_MAP_SIZE`] saturating counters, indexed by a hash of the edge.
+#[derive(Clone, Debug, Default)]
+pub struct EdgeCovInspector {
+    /// The hit counts of the edges, allocated when the first edge is hit.
+    hits: Vec<u8>,
+    /// The indices of the edges hit, in the order they were first hit.
+    edges: Vec<u32>,
+    /// The program counter of the jump executed by the previous step, if any.
+    jump: Option<usize>,
+}
+
+impl<DB: Database> Inspector<DB> for EdgeCovInspector {
+    #[inline]
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        let pc = interp.program_counter();
+        if let Some(from) = self.jump.take() {
+            // Key the edges by the address of the code, so that the code of a delegate call is
+            // not confused with the code of the caller.
+            let contract = &interp.contract;
+            let address = contract.bytecode_address.unwrap_or(contract.target_address);
+            self.hit(edge_index(address, from, pc));
+        }
+        if matches!(interp.current_opcode(), opcode::JUMP | opcode::JUMPI) {
+            self.jump = Some(pc);
+        }
+    }
+}
+
+impl EdgeCovInspector {
+    /// Returns the indices and hit counts of the edges hit.
+    pub fn finish(self) -> Vec<(u32, u8)> {
+        self.edges.into_iter().map(|index| (index, self.hits[index as usize])).collect()
+    }
+
+    #[inline]
+    fn hit(&mut self, index: usize) {
+        if self.hits.is_empty() {
+            self.hits = vec![0; EDGE_MAP_SIZE];
+        }
+        if self.hits[index] == 0 {
+            self.edges.push(index as u32);
+        }
+        self.hits[index] = self.hits[index].saturating_add(1);
+    }
+}
+
+/// Returns the index of an edge in the edge coverage map.
+fn edge_index(address: Address, from: usize, to: usize) -> usize {
+    let address = u64::from_be_bytes(address[12..].try_into().unwrap());
+    let edge = address ^ ((from as u64) << 32) ^ to as u64;
+    (edge.wrapping_mul(0x9e37_79b9_7f4a_7c15) >> 48) as usize
+}
@@ -19,6 +19,7 @@
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use std::{fmt, sync::Arc};
+use strategies::FuzzDictionaryFile;
 
 pub use proptest::test_runner::{Config as FuzzConfig, Reason};
 
@@ -29,7 +30,7 @@
 pub mod strategies;
 
 mod inspector;
-pub use inspector::Fuzzer;
+pub use inspector::{EdgeCovInspector, Fuzzer, EDGE_MAP_SIZE};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum CounterExample {
@@ -189,6 +190,9 @@ pub struct FuzzTestResult {
 
     // Deprecated cheatcodes mapped to their replacements.
     pub deprecated_cheatcodes: HashMap<&'static str, Option<&'static str>>,
+
+    /// The learned fuzz dictionary, if it should be exported.
+    pub dictionary: Option<FuzzDictionaryFile>,
 }
 
 impl FuzzTestResult {
@@ -0,0 +1,223 @@
+use crate::{strategies::fuzz_calldata, FuzzFixtures, EDGE_MAP_SIZE};
+use alloy_dyn_abi::{DynSolValue, JsonAbiExt};
+use alloy_json_abi::Function;
+use alloy_primitives::{Bytes, I256, U256};
+use parking_lot::RwLock;
+use proptest::prelude::{any, Strategy};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::sync::Arc;
+
+/// The inputs of a fuzz test that increased its edge coverage, which are mutated to generate new
+/// inputs.
+///
+/// Wrapped in a shareable container.
+#[derive(Clone, Debug, Default)]
+pub struct FuzzCorpus {
+    inner: Arc<RwLock<CorpusInner>>,
+}
+
+#[derive(Debug, Default)]
+struct CorpusInner {
+    /// The buckets of hit counts seen for every edge, as bit sets.
+    seen: Vec<u8>,
+    /// The inputs that increased the coverage.
+    inputs: Vec<Bytes>,
+}
+
+impl FuzzCorpus {
+    /// Adds the input to the corpus if it increased the coverage, and returns whether it did.
+    ///
+    /// `edges` are the indices and hit counts of the edges collected by
+    /// [`EdgeCovInspector`](crate::EdgeCovInspector). The coverage increases when an edge is hit
+    /// for the first time, or a number of times in a new bucket. The buckets are the powers of
+    /// two, so that running a loop once more isn't new coverage, but running it twice as many
+    /// times is.
+    pub fn add(&self, input: &Bytes, edges: &[(u32, u8)]) -> bool {
+        // Most inputs don't increase the coverage, so check under the read lock first.
+        let is_new = |seen: &[u8]| {
+            edges.iter().any(|&(index, hits)| {
+                hits != 0 && seen.get(index as usize).is_none_or(|seen| seen & bucket(hits) == 0)
+            })
+        };
+        if !is_new(&self.inner.read().seen) {
+            return false
+        }
+
+        let mut inner = self.inner.write();
+        if !is_new(&inner.seen) {
+            return false
+        }
+        if inner.seen.len() < EDGE_MAP_SIZE {
+            inner.seen.resize(EDGE_MAP_SIZE, 0);
+        }
+        for &(index, hits) in edges {
+            if hits != 0 {
+                inner.seen[index as usize] |= bucket(hits);
+            }
+        }
+        inner.inputs.push(input.clone());
+        true
+    }
+
+    /// Returns the number of inputs in the corpus.
+    pub fn len(&self) -> usize {
+        self.inner.read().inputs.len()
+    }
+
+    /// Returns whether the corpus is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Mutates one argument of an input of the corpus, decoded as the calldata of `func`.
+    ///
+    /// Half of the time the input is the last one added, which reached the newest coverage, and
+    /// otherwise a random one.
+    ///
+    /// Returns `None` if the corpus is empty or the function has no parameters.
+    pub fn mutate(&self, func: &Function, rng: &mut impl Rng) -> Option<Bytes> {
+        let input = {
+            let inner = self.inner.read();
+            let input = if rng.gen() { inner.inputs.last() } else { inner.inputs.choose(rng) };
+            input?.clone()
+        };
+        let mut args = func.abi_decode_input(input.get(4..)?, false).ok()?;
+        mutate_value(args.choose_mut(rng)?, rng);
+        func.abi_encode_input(&args).ok().map(Into::into)
+    }
+}
+
+/// Given a function and a corpus, it returns a strategy which generates calldata by mutating the
+/// inputs of the corpus, or valid calldata for the function's input types, following declared
+/// test fixtures, while the corpus is empty.
+pub fn fuzz_calldata_from_corpus(
+    func: Function,
+    fuzz_fixtures: &FuzzFixtures,
+    corpus: &FuzzCorpus,
+) -> impl Strategy<Value = Bytes> {
+    let corpus = corpus.clone();
+    (fuzz_calldata(func.clone(), fuzz_fixtures), any::<u64>())
+        .prop_map(move |(calldata, seed)| {
+            corpus.mutate(&func, &mut StdRng::seed_from_u64(seed)).unwrap_or(calldata)
+        })
+        .no_shrink()
+}
+
+/// Returns the bucket of a non-zero hit count, as a bit set.
+fn bucket(hits: u8) -> u8 {
+    1 << (7 - hits.leading_zeros())
+}
+
+/// Mutates a value, or a random element of an array or a tuple, keeping it valid for its type.
+fn mutate_value(value: &mut DynSolValue, rng: &mut impl Rng) {
+    match value {
+        DynSolValue::Bool(b) => *b = !*b,
+        DynSolValue::Uint(n, bits) => *n = mutate_word(*n, *bits, rng),
+        DynSolValue::Int(n, bits) => {
+            // Sign-extend the mutated bits.
+            let word = mutate_word(n.into_raw(), *bits, rng);
+            let negative = *bits < 256 && word.bit(*bits - 1);
+            *n = I256::from_raw(if negative { word | (U256::MAX << *bits) } else { word });
+        }
+        DynSolValue::Address(address) => {
+            address[rng.gen_range(0..20)] ^= 1 << rng.gen_range(0..8);
+        }
+        DynSolValue::FixedBytes(word, size) => {
+            word[rng.gen_range(0..*size)] ^= 1 << rng.gen_range(0..8);
+        }
+        DynSolValue::Bytes(bytes) => mutate_bytes(bytes, rng),
+        DynSolValue::String(s) => {
+            let mut bytes = std::mem::take(s).into_bytes();
+            mutate_bytes(&mut bytes, rng);
+            *s = String::from_utf8_lossy(&bytes).into_owned();
+        }
+        DynSolValue::Array(values) |
+        DynSolValue::FixedArray(values) |
+        DynSolValue::Tuple(values) => {
+            if let Some(value) = values.choose_mut(rng) {
+                mutate_value(value, rng);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Mutates the low `bits` bits of a word: flips one of them, adds or subtracts a small value, or
+/// sets a boundary value.
+fn mutate_word(word: U256, bits: usize, rng: &mut impl Rng) -> U256 {
+    let mask = U256::MAX >> (256 - bits);
+    let mutated = match rng.gen_range(0..4) {
+        0 => word ^ (U256::from(1) << rng.gen_range(0..bits)),
+        1 => word.wrapping_add(U256::from(rng.gen_range(1..=16u64))),
+        2 => word.wrapping_sub(U256::from(rng.gen_range(1..=16u64))),
+        _ => *[U256::ZERO, U256::from(1), mask >> 1, mask].choose(rng).unwrap(),
+    };
+    mutated & mask
+}
+
+/// Flips a bit, removes a byte or inserts a random byte.
+fn mutate_bytes(bytes: &mut Vec<u8>, rng: &mut impl Rng) {
+    match rng.gen_range(0..3) {
+        0 if !bytes.is_empty() => {
+            let i = rng.gen_range(0..bytes.len());
+            bytes[i] ^= 1 << rng.gen_range(0..8);
+        }
+        1 if !bytes.is_empty() => {
+            bytes.remove(rng.gen_range(0..bytes.len()));
+        }
+        _ => {
+            let i = rng.gen_range(0..=bytes.len());
+            bytes.insert(i, rng.gen());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_inputs_that_increase_coverage() {
+        let corpus = FuzzCorpus::default();
+        let input = Bytes::from_static(b"input");
+
+        // No edge hit.
+        assert!(!corpus.add(&input, &[]));
+
+        assert!(corpus.add(&input, &[(1, 1)]));
+        assert!(!corpus.add(&input, &[(1, 1)]));
+
+        // The hit counts are bucketed by powers of two.
+        assert!(corpus.add(&input, &[(1, 3)]));
+        assert!(!corpus.add(&input, &[(1, 2)]));
+        assert!(corpus.add(&input, &[(1, 255)]));
+        assert!(!corpus.add(&input, &[(1, 1), (1, 2)]));
+
+        assert!(corpus.add(&input, &[(1, 1), (2, 1)]));
+        assert!(corpus.add(&input, &[(EDGE_MAP_SIZE as u32 - 1, 1)]));
+
+        assert_eq!(corpus.len(), 5);
+    }
+
+    #[test]
+    fn mutate_inputs() {
+        let func = Function::parse("test(uint8 a, int16 b, bool c)").unwrap();
+        let corpus = FuzzCorpus::default();
+        let args = [
+            DynSolValue::Uint(U256::from(200), 8),
+            DynSolValue::Int(I256::try_from(-300).unwrap(), 16),
+            DynSolValue::Bool(true),
+        ];
+        let input = Bytes::from(func.abi_encode_input(&args).unwrap());
+        assert!(corpus.mutate(&func, &mut StdRng::seed_from_u64(0)).is_none());
+        assert!(corpus.add(&input, &[(0, 1)]));
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let mutated = corpus.mutate(&func, &mut rng).unwrap();
+            let values = func.abi_decode_input(&mutated[4..], true).unwrap();
+            let changed = values.iter().zip(&args).filter(|(a, b)| a != b).count();
+            assert!(changed <= 1, "{values:?}");
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use crate::FuzzFixtures;
+use alloy_dyn_abi::{DynSolType, DynSolValue};
+use alloy_json_abi::Function;
+use alloy_primitives::{FixedBytes, I256, U256};
+
+/// The parameters of a function with small domains, whose values can be enumerated exhaustively
+/// instead of being sampled: `bool`, 8-bit integers, `bytes1` and parameters with fixtures.
+#[derive(Clone, Debug, Default)]
+pub struct SmallDomains {
+    /// The index of each enumerated parameter, and all its values.
+    params: Vec<(usize, Vec<DynSolValue>)>,
+}
+
+impl SmallDomains {
+    /// Selects the parameters of `func` with small domains, in order, as long as the number of
+    /// combinations of their values stays within `budget`.
+    ///
+    /// Returns `None` if no parameter can be enumerated.
+    pub fn new(func: &Function, fixtures: &FuzzFixtures, budget: usize) -> Option<Self> {
+        let mut params = Vec::new();
+        let mut combinations = 1usize;
+        for (i, input) in func.inputs.iter().enumerate() {
+            let values = match fixtures.param_fixtures(&input.name) {
+                Some(fixtures) => fixtures.to_vec(),
+                None => match input.selector_type().parse() {
+                    Ok(ty) => domain(&ty),
+                    Err(_) => continue,
+                },
+            };
+            if values.is_empty() {
+                continue;
+            }
+            if let Some(n) = combinations.checked_mul(values.len()).filter(|n| *n <= budget) {
+                combinations = n;
+                params.push((i, values));
+            }
+        }
+        (!params.is_empty()).then_some(Self { params })
+    }
+
+    /// Returns the number of combinations of the values of the enumerated parameters.
+    pub fn len(&self) -> usize {
+        self.params.iter().map(|(_, values)| values.len()).product()
+    }
+
+    /// Returns `true` if no parameter is enumerated.
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    /// Sets the enumerated parameters in `args` to their values in the combination at `index`.
+    pub fn apply(&self, mut index: usize, args: &mut [DynSolValue]) {
+        for (i, values) in &self.params {
+            if let Some(arg) = args.get_mut(*i) {
+                *arg = values[index % values.len()].clone();
+            }
+            index /= values.len();
+        }
+    }
+}
+
+/// Returns all the values of a type with a small domain, or an empty list otherwise.
+fn domain(ty: &DynSolType) -> Vec<DynSolValue> {
+    match ty {
+        DynSolType::Bool => vec![DynSolValue::Bool(false), DynSolValue::Bool(true)],
+        DynSolType::Uint(8) => (0..=u8::MAX).map(|v| DynSolValue::Uint(U256::from(v), 8)).collect(),
+        DynSolType::Int(8) => {
+            (i8::MIN..=i8::MAX).map(|v| DynSolValue::Int(I256::try_from(v).unwrap(), 8)).collect()
+        }
+        DynSolType::FixedBytes(1) => (0..=u8::MAX)
+            .map(|v| {
+                let mut word = FixedBytes::ZERO;
+                word[0] = v;
+                DynSolValue::FixedBytes(word, 1)
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::map::HashMap;
+
+    #[test]
+    fn enumerate_small_domains() {
+        let func = Function::parse("test(bool a, uint256 b, uint8 c, bool d, int8 e)").unwrap();
+        let domains = SmallDomains::new(&func, &FuzzFixtures::default(), 1024).unwrap();
+        // `e` would exceed the budget.
+        assert_eq!(domains.len(), 2 * 256 * 2);
+
+        let mut args = vec![
+            DynSolValue::Bool(false),
+            DynSolValue::Uint(U256::from(7), 256),
+            DynSolValue::Uint(U256::ZERO, 8),
+            DynSolValue::Bool(false),
+            DynSolValue::Int(I256::ZERO, 8),
+        ];
+        domains.apply(2 * 3 + 1, &mut args);
+        assert_eq!(args[0], DynSolValue::Bool(true));
+        assert_eq!(args[1], DynSolValue::Uint(U256::from(7), 256));
+        assert_eq!(args[2], DynSolValue::Uint(U256::from(3), 8));
+        assert_eq!(args[3], DynSolValue::Bool(false));
+
+        let mut fixtures = HashMap::default();
+        fixtures.insert(
+            "b".to_string(),
+            DynSolValue::Array(vec![DynSolValue::Uint(U256::from(1), 256)]),
+        );
+        let domains = SmallDomains::new(&func, &FuzzFixtures::new(fixtures), 4).unwrap();
+        assert_eq!(domains.len(), 2 * 2);
+        let func = Function::parse("test(uint256)").unwrap();
+        assert!(SmallDomains::new(&func, &FuzzFixtures::default(), 1024).is_none());
+    }
+}
@@ -10,8 +10,14 @@
 mod calldata;
 pub use calldata::{fuzz_calldata, fuzz_calldata_from_state};
 
+mod corpus;
+pub use corpus::{fuzz_calldata_from_corpus, FuzzCorpus};
+
+mod exhaustive;
+pub use exhaustive::SmallDomains;
+
 mod state;
-pub use state::EvmFuzzState;
+pub use state::{EvmFuzzState, FuzzDictionaryFile};
 
 mod invariants;
 pub use invariants::{fuzz_contract_with_calldata, invariant_strat, override_call_strat};
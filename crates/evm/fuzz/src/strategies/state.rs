@@ -2,8 +2,8 @@
 use alloy_dyn_abi::{DynSolType, DynSolValue, EventExt, FunctionExt};
 use alloy_json_abi::{Function, JsonAbi};
 use alloy_primitives::{
-    map::{AddressIndexSet, B256IndexSet, HashMap},
-    Address, Bytes, Log, B256, U256,
+    map::{AddressIndexSet, B256IndexSet, HashMap, SelectorIndexSet},
+    Address, Bytes, Log, Selector, B256, U256,
 };
 use foundry_config::FuzzDictionaryConfig;
 use foundry_evm_core::utils::StateChangeset;
@@ -13,7 +13,13 @@
     interpreter::opcode,
     primitives::AccountInfo,
 };
-use std::{collections::BTreeMap, fmt, sync::Arc};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    path::Path,
+    sync::Arc,
+};
 
 /// The maximum number of bytes we will look at in bytecodes to find push bytes (24 KiB).
 ///
@@ -83,6 +89,16 @@ pub fn revert(&self) {
         self.inner.write().revert();
     }
 
+    /// Imports the values of a dictionary file. Imported values are persisted across runs.
+    pub fn import(&self, file: &FuzzDictionaryFile) {
+        self.inner.write().import(file);
+    }
+
+    /// Exports the values of the dictionary, excluding the values collected during a run.
+    pub fn export(&self) -> FuzzDictionaryFile {
+        self.inner.read().export()
+    }
+
     pub fn dictionary_read(&self) -> RwLockReadGuard<'_, RawRwLock, FuzzDictionary> {
         self.inner.read()
     }
@@ -93,6 +109,51 @@ pub fn log_stats(&self) {
     }
 }
 
+/// A fuzz dictionary file, used to share the values learned while fuzzing one project with others.
+///
+/// Addresses and selectors are inserted into the dictionary as words, so they can be used as
+/// values of any matching parameter type.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FuzzDictionaryFile {
+    /// Raw 32 byte values.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub values: BTreeSet<B256>,
+    /// Addresses of contracts.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub addresses: BTreeSet<Address>,
+    /// Function selectors.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub selectors: BTreeSet<Selector>,
+}
+
+impl FuzzDictionaryFile {
+    /// Reads a dictionary file.
+    pub fn load(path: &Path) -> eyre::Result<Self> {
+        Ok(foundry_common::fs::read_json_file(path)?)
+    }
+
+    /// Writes the dictionary to a file, creating parent directories if needed.
+    pub fn save(&self, path: &Path) -> eyre::Result<()> {
+        if let Some(parent) = path.parent() {
+            foundry_common::fs::create_dir_all(parent)?;
+        }
+        foundry_common::fs::write_json_file(path, self)?;
+        Ok(())
+    }
+
+    /// Merges the values of another dictionary into this one.
+    pub fn extend(&mut self, other: Self) {
+        self.values.extend(other.values);
+        self.addresses.extend(other.addresses);
+        self.selectors.extend(other.selectors);
+    }
+
+    /// Returns `true` if the dictionary contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty() && self.addresses.is_empty() && self.selectors.is_empty()
+    }
+}
+
 // We're using `IndexSet` to have a stable element order when restoring persisted state, as well as
 // for performance when iterating over the sets.
 #[derive(Default)]
@@ -101,6 +162,9 @@ pub struct FuzzDictionary {
     state_values: B256IndexSet,
     /// Addresses that already had their PUSH bytes collected.
     addresses: AddressIndexSet,
+    /// The 4 byte values pushed by the collected code, which include the selectors of the
+    /// functions dispatched by the contracts, and the imported selectors.
+    selectors: SelectorIndexSet,
     /// Configuration for the dictionary.
     config: FuzzDictionaryConfig,
     /// Number of state values initially collected from db.
@@ -109,6 +173,9 @@ pub struct FuzzDictionary {
     /// Number of address values initially collected from db.
     /// Used to revert new collected addresses at the end of each run.
     db_addresses: usize,
+    /// Number of selectors initially collected from db.
+    /// Used to revert new collected selectors at the end of each run.
+    db_selectors: usize,
     /// Sample typed values that are collected from call result and used across invariant runs.
     sample_values: HashMap<DynSolType, B256IndexSet>,
 
@@ -166,6 +233,7 @@ fn insert_db_values(&mut self, db_state: Vec<(&Address, &DbAccount)>) {
         // end of each run.
         self.db_state_values = self.state_values.len();
         self.db_addresses = self.addresses.len();
+        self.db_selectors = self.selectors.len();
     }
 
     /// Insert values collected from call result into fuzz dictionary.
@@ -268,7 +336,12 @@ fn collect_push_bytes(&mut self, code: &[u8]) {
                     break;
                 }
 
-                let push_value = U256::try_from_be_slice(&code[push_start..push_end]).unwrap();
+                let push_bytes = &code[push_start..push_end];
+                if push_size == 4 {
+                    self.selectors.insert(Selector::from_slice(push_bytes));
+                }
+
+                let push_value = U256::try_from_be_slice(push_bytes).unwrap();
                 if push_value != U256::ZERO {
                     // Never add 0 to the dictionary as it's always present.
                     self.insert_value(push_value.into());
@@ -367,10 +440,38 @@ pub fn addresses(&self) -> &AddressIndexSet {
         &self.addresses
     }
 
+    /// Imports the values of a dictionary file as persistent values.
+    fn import(&mut self, file: &FuzzDictionaryFile) {
+        for value in &file.values {
+            self.insert_value(*value);
+        }
+        for address in &file.addresses {
+            self.insert_value(address.into_word());
+        }
+        for selector in &file.selectors {
+            self.selectors.insert(*selector);
+            self.insert_value(B256::right_padding_from(selector.as_slice()));
+        }
+        self.db_state_values = self.state_values.len();
+        self.db_selectors = self.selectors.len();
+    }
+
+    /// Exports the persistent values, addresses and selectors, and the collected samples.
+    fn export(&self) -> FuzzDictionaryFile {
+        let values = self.state_values.iter().take(self.db_state_values);
+        let samples = self.sample_values.values().flatten();
+        FuzzDictionaryFile {
+            values: values.chain(samples).copied().collect(),
+            addresses: self.addresses.iter().take(self.db_addresses).copied().collect(),
+            selectors: self.selectors.iter().take(self.db_selectors).copied().collect(),
+        }
+    }
+
     /// Revert values and addresses collected during the run by truncating to initial db len.
     pub fn revert(&mut self) {
         self.state_values.truncate(self.db_state_values);
         self.addresses.truncate(self.db_addresses);
+        self.selectors.truncate(self.db_selectors);
     }
 
     pub fn log_stats(&self) {
@@ -384,3 +485,45 @@ pub fn log_stats(&self) {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn import_export_dictionary() {
+        let mut dictionary = FuzzDictionary::new(FuzzDictionaryConfig::default());
+        let file = FuzzDictionaryFile {
+            values: [B256::with_last_byte(1)].into(),
+            addresses: [Address::repeat_byte(0x11)].into(),
+            selectors: [Selector::new([0xde, 0xad, 0xbe, 0xef])].into(),
+        };
+        dictionary.import(&file);
+
+        // Imported values persist across runs.
+        dictionary.insert_value(B256::with_last_byte(2));
+        dictionary.revert();
+
+        let exported = dictionary.export();
+        assert!(exported.values.contains(&B256::with_last_byte(1)));
+        assert!(exported.values.contains(&Address::repeat_byte(0x11).into_word()));
+        assert!(exported.values.contains(&B256::right_padding_from(&[0xde, 0xad, 0xbe, 0xef])));
+        assert!(!exported.values.contains(&B256::with_last_byte(2)));
+        assert_eq!(exported.selectors, file.selectors);
+    }
+
+    #[test]
+    fn export_pushed_selectors() {
+        let mut dictionary = FuzzDictionary::new(FuzzDictionaryConfig::default());
+        let selector = Selector::new([0xde, 0xad, 0xbe, 0xef]);
+        // PUSH4 0xdeadbeef EQ PUSH1 0x2a
+        dictionary.collect_push_bytes(&[0x63, 0xde, 0xad, 0xbe, 0xef, 0x14, 0x60, 0x2a]);
+        dictionary.db_selectors = dictionary.selectors.len();
+
+        // Selectors collected during a run are reverted.
+        dictionary.collect_push_bytes(&[0x63, 0xca, 0xfe, 0xba, 0xbe]);
+        dictionary.revert();
+
+        assert_eq!(dictionary.export().selectors, [selector].into());
+    }
+}
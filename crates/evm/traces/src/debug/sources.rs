@@ -27,11 +27,15 @@ pub struct SourceData {
     /// Maps contract name to (start, end) of the contract definition in the source code.
     /// This is useful for determining which contract contains given function definition.
     contract_definitions: Vec<(String, Range<usize>)>,
+    /// Maps function name to (start, end) of the function definitions in the source code, used
+    /// to jump to the definition of a called function.
+    function_definitions: Vec<(String, Range<usize>)>,
 }
 
 impl SourceData {
     pub fn new(source: Arc<String>, language: MultiCompilerLanguage, path: PathBuf) -> Self {
         let mut contract_definitions = Vec::new();
+        let mut function_definitions = Vec::new();
 
         match language {
             MultiCompilerLanguage::Vyper(_) => {
@@ -48,10 +52,23 @@ pub fn new(source: Arc<String>, language: MultiCompilerLanguage, path: PathBuf)
                     let mut parser =
                         Parser::from_source_code(&sess, &arena, filename, source.to_string())?;
                     let ast = parser.parse_file().map_err(|e| e.emit())?;
-                    for item in ast.items {
+                    let range = |span: solar_parse::interface::Span| {
+                        span.lo().to_usize()..span.hi().to_usize()
+                    };
+                    let mut push_function = |item: &solar_parse::ast::Item<'_>| {
+                        if let solar_parse::ast::ItemKind::Function(f) = &item.kind {
+                            if let Some(name) = f.header.name {
+                                function_definitions.push((name.to_string(), range(item.span)));
+                            }
+                        }
+                    };
+                    for item in ast.items.iter() {
                         if let solar_parse::ast::ItemKind::Contract(contract) = &item.kind {
-                            let range = item.span.lo().to_usize()..item.span.hi().to_usize();
-                            contract_definitions.push((contract.name.to_string(), range));
+                            contract_definitions
+                                .push((contract.name.to_string(), range(item.span)));
+                            contract.body.iter().for_each(&mut push_function);
+                        } else {
+                            push_function(item);
                         }
                     }
                     Ok(())
@@ -59,7 +76,7 @@ pub fn new(source: Arc<String>, language: MultiCompilerLanguage, path: PathBuf)
             }
         }
 
-        Self { source, language, path, contract_definitions }
+        Self { source, language, path, contract_definitions, function_definitions }
     }
 
     /// Finds name of contract that contains given loc.
@@ -69,6 +86,14 @@ pub fn find_contract_name(&self, start: usize, end: usize) -> Option<&str> {
             .find(|(_, r)| start >= r.start && end <= r.end)
             .map(|(name, _)| name.as_str())
     }
+
+    /// Returns the byte ranges of the definitions of the functions with the given name.
+    pub fn find_function_definitions<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = Range<usize>> + 'a {
+        self.function_definitions.iter().filter(move |(n, _)| n == name).map(|(_, r)| r.clone())
+    }
 }
 
 #[derive(Clone, Debug)]
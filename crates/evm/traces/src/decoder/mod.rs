@@ -3,11 +3,12 @@
     identifier::{
         AddressIdentity, LocalTraceIdentifier, SingleSignaturesIdentifier, TraceIdentifier,
     },
-    CallTrace, CallTraceArena, CallTraceNode, DecodedCallData,
+    CallKind, CallTrace, CallTraceArena, CallTraceNode, DecodedCallData,
 };
 use alloy_dyn_abi::{DecodedEvent, DynSolValue, EventExt, FunctionExt, JsonAbiExt};
 use alloy_json_abi::{Error, Event, Function, JsonAbi};
 use alloy_primitives::{
+    address,
     map::{hash_map::Entry, HashMap},
     Address, LogData, Selector, B256,
 };
@@ -123,6 +124,8 @@ pub struct CallTraceDecoder {
     pub receive_contracts: Vec<Address>,
     /// Contract addresses that have fallback functions, mapped to function sigs.
     pub fallback_contracts: HashMap<Address, Vec<String>>,
+    /// Proxy addresses, mapped to the implementation they delegate calls to.
+    pub proxies: HashMap<Address, Address>,
 
     /// All known functions.
     pub functions: HashMap<Selector, Vec<Function>>,
@@ -171,9 +174,26 @@ fn init() -> Self {
                 (EC_PAIRING, "ECPairing".to_string()),
                 (BLAKE_2F, "Blake2F".to_string()),
                 (POINT_EVALUATION, "PointEvaluation".to_string()),
+                // Deployers and protocols that live at the same address on most chains.
+                (address!("ba5Ed099633D3B313e4D5F7bdc1305d3c28ba5Ed"), "CreateX".to_string()),
+                (
+                    address!("914d7Fec6aaC8cd542e72Bca78B30650d45643d7"),
+                    "SafeSingletonFactory".to_string(),
+                ),
+                (
+                    address!("0000000000FFe8B47B3e2130213B802212439497"),
+                    "ImmutableCreate2Factory".to_string(),
+                ),
+                (
+                    address!("ce0042B868300000d44A59004Da54A005ffdcf9f"),
+                    "SingletonFactory".to_string(),
+                ),
+                (address!("cA11bde05977b3631167028862bE2a173976CA11"), "Multicall3".to_string()),
+                (address!("000000000022D473030F116dDEE9F6B43aC78BA3"), "Permit2".to_string()),
             ]),
             receive_contracts: Default::default(),
             fallback_contracts: Default::default(),
+            proxies: Default::default(),
 
             functions: console::hh::abi::functions()
                 .into_values()
@@ -206,15 +226,51 @@ pub fn clear_addresses(&mut self) {
 
         self.receive_contracts.clear();
         self.fallback_contracts.clear();
+        self.proxies.clear();
     }
 
     /// Identify unknown addresses in the specified call trace using the specified identifier.
     ///
     /// Unknown contracts are contracts that either lack a label or an ABI.
     pub fn identify(&mut self, trace: &CallTraceArena, identifier: &mut impl TraceIdentifier) {
+        self.collect_proxies(trace);
         self.collect_identities(identifier.identify_addresses(self.trace_addresses(trace)));
     }
 
+    /// Returns the label of the address, annotated with the label of its implementation if it is
+    /// a proxy, e.g. `ERC1967Proxy(Counter)`.
+    pub fn label(&self, address: &Address) -> Option<String> {
+        let label = self.labels.get(address);
+        let Some(implementation) = self.proxies.get(address).and_then(|i| self.labels.get(i))
+        else {
+            return label.cloned();
+        };
+        let proxy = label.map_or_else(|| address.to_string(), Clone::clone);
+        Some(format!("{proxy}({implementation})"))
+    }
+
+    /// Records the proxies called in the trace, which are the contracts that forward their
+    /// calldata to an implementation with `DELEGATECALL`.
+    ///
+    /// This covers EIP-1967, beacon, UUPS and minimal (EIP-1167) proxies without reading their
+    /// storage. If a proxy is upgraded, the last implementation is used.
+    fn collect_proxies(&mut self, arena: &CallTraceArena) {
+        let nodes = arena.nodes();
+        for node in nodes {
+            if node.trace.kind.is_any_create() {
+                continue;
+            }
+            let implementation = node.children.iter().map(|&i| &nodes[i].trace).find(|child| {
+                child.kind == CallKind::DelegateCall &&
+                    child.address != node.trace.address &&
+                    child.data == node.trace.data
+            });
+            if let Some(implementation) = implementation {
+                self.proxies.insert(node.trace.address, implementation.address);
+            }
+        }
+    }
+
     /// Adds a single event to the decoder.
     pub fn push_event(&mut self, event: Event) {
         self.events.entry((event.selector(), indexed_inputs(&event))).or_default().push(event);
@@ -336,7 +392,9 @@ pub async fn decode_function(&self, trace: &CallTrace) -> DecodedCallTrace {
             return trace;
         }
 
-        let label = self.labels.get(&trace.address).cloned();
+        let label = self.label(&trace.address);
+        // calls to proxies are decoded against their implementation
+        let target = self.proxies.get(&trace.address).unwrap_or(&trace.address);
 
         let cdata = &trace.data;
         if trace.address == DEFAULT_CREATE2_DEPLOYER {
@@ -374,7 +432,7 @@ pub async fn decode_function(&self, trace: &CallTrace) -> DecodedCallTrace {
             // If traced contract is a fallback contract, check if it has the decoded function.
             // If not, then replace call data signature with `fallback`.
             let mut call_data = self.decode_function_input(trace, func);
-            if let Some(fallback_functions) = self.fallback_contracts.get(&trace.address) {
+            if let Some(fallback_functions) = self.fallback_contracts.get(target) {
                 if !fallback_functions.contains(&func.signature()) {
                     call_data.signature = "fallback()".into();
                 }
@@ -386,7 +444,7 @@ pub async fn decode_function(&self, trace: &CallTrace) -> DecodedCallTrace {
                 return_data: self.decode_function_output(trace, functions),
             }
         } else {
-            let has_receive = self.receive_contracts.contains(&trace.address);
+            let has_receive = self.receive_contracts.contains(target);
             let signature =
                 if cdata.is_empty() && has_receive { "receive()" } else { "fallback()" }.into();
             let args = if cdata.is_empty() { Vec::new() } else { vec![cdata.to_string()] };
@@ -0,0 +1,308 @@
+//! Exporting call traces to the formats of external visualization tools.
+//!
+//! EVM executions have no notion of time, so the gas used by a call is used as its duration:
+//! calls are laid out one after another on a time axis measured in gas.
+
+use crate::folded_stack_trace::call_name;
+use alloy_primitives::{hex, B256};
+use revm_inspectors::tracing::{types::CallTraceNode, CallTraceArena};
+use serde_json::{json, Value};
+use std::{
+    fmt,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// A format call traces can be exported to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraceExportFormat {
+    /// OpenTelemetry spans, in the OTLP/JSON encoding. Every call is a span.
+    Otlp,
+    /// A [speedscope](https://www.speedscope.app) evented profile. Every call is a frame.
+    Speedscope,
+}
+
+impl TraceExportFormat {
+    /// Returns the extension of the files of this format.
+    pub const fn extension(&self) -> &'static str {
+        match self {
+            Self::Otlp => "otlp.json",
+            Self::Speedscope => "speedscope.json",
+        }
+    }
+
+    /// Exports the given arenas, each with a label such as `setup` or `execution`, to a single
+    /// document named `name`.
+    ///
+    /// The arenas should be decoded beforehand, so calls are named after their signatures.
+    pub fn export<'a>(
+        &self,
+        name: &str,
+        arenas: impl IntoIterator<Item = (&'a str, &'a CallTraceArena)>,
+    ) -> Value {
+        match self {
+            Self::Otlp => to_otlp(name, arenas),
+            Self::Speedscope => to_speedscope(name, arenas),
+        }
+    }
+}
+
+impl FromStr for TraceExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "otlp" | "otel" | "opentelemetry" => Ok(Self::Otlp),
+            "speedscope" => Ok(Self::Speedscope),
+            _ => Err(format!("unknown trace export format `{s}`, expected `otlp` or `speedscope`")),
+        }
+    }
+}
+
+impl fmt::Display for TraceExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Otlp => f.write_str("otlp"),
+            Self::Speedscope => f.write_str("speedscope"),
+        }
+    }
+}
+
+/// A call entered or exited while walking a call trace.
+enum CallEvent<'a> {
+    Enter { node: &'a CallTraceNode, idx: usize, parent: Option<usize>, start: u64, end: u64 },
+    Exit { idx: usize, end: u64 },
+}
+
+/// Walks the calls of an arena depth-first, starting at `start`.
+///
+/// The children of a call are laid out one after another from the start of the call, and are
+/// clamped to its end, so that calls are always nested within their parents.
+fn walk<'a>(arena: &'a CallTraceArena, start: u64, f: &mut impl FnMut(CallEvent<'a>)) -> u64 {
+    fn walk_node<'a>(
+        nodes: &'a [CallTraceNode],
+        idx: usize,
+        parent: Option<usize>,
+        start: u64,
+        limit: u64,
+        f: &mut impl FnMut(CallEvent<'a>),
+    ) -> u64 {
+        let node = &nodes[idx];
+        let end = start.saturating_add(node.trace.gas_used).min(limit);
+        f(CallEvent::Enter { node, idx, parent, start, end });
+        let mut child_start = start;
+        for &child in &node.children {
+            child_start = walk_node(nodes, child, Some(idx), child_start, end, f);
+        }
+        f(CallEvent::Exit { idx, end });
+        end
+    }
+
+    if arena.nodes().is_empty() {
+        return start;
+    }
+    walk_node(arena.nodes(), 0, None, start, u64::MAX, f)
+}
+
+/// Converts the arenas to an OTLP/JSON `ExportTraceServiceRequest`.
+///
+/// A root span named `name` contains a span for each arena, which contains the spans of its calls.
+/// Spans start at the time of the export, and last as many nanoseconds as the gas used.
+fn to_otlp<'a>(
+    name: &str,
+    arenas: impl IntoIterator<Item = (&'a str, &'a CallTraceArena)>,
+) -> Value {
+    let trace_id = hex::encode(&B256::random()[..16]);
+    let span_id = |id: u64| hex::encode(id.to_be_bytes());
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos() as u64;
+    let time = |gas: u64| now.saturating_add(gas).to_string();
+
+    let mut spans = Vec::new();
+    let mut next_id = 2;
+    let mut end = 0;
+    for (label, arena) in arenas {
+        let arena_id = next_id;
+        next_id += 1;
+        let first_id = next_id;
+        let start = end;
+        end = walk(arena, start, &mut |event| {
+            let CallEvent::Enter { node, idx, parent, start, end } = event else { return };
+            let trace = &node.trace;
+            let parent_id = parent.map_or(arena_id, |parent| first_id + parent as u64);
+            let mut attributes = vec![
+                attribute("evm.kind", json!({ "stringValue": trace.kind.to_string() })),
+                attribute("evm.address", json!({ "stringValue": trace.address.to_string() })),
+                attribute("evm.caller", json!({ "stringValue": trace.caller.to_string() })),
+                attribute("evm.value", json!({ "stringValue": trace.value.to_string() })),
+                attribute("evm.gas_used", json!({ "intValue": trace.gas_used.to_string() })),
+                attribute("evm.gas_limit", json!({ "intValue": trace.gas_limit.to_string() })),
+                attribute("evm.depth", json!({ "intValue": trace.depth.to_string() })),
+                attribute("evm.success", json!({ "boolValue": trace.success })),
+            ];
+            if let Some(label) = &trace.decoded.label {
+                attributes.push(attribute("evm.label", json!({ "stringValue": label })));
+            }
+            let status = if trace.success {
+                json!({ "code": 1 })
+            } else {
+                json!({ "code": 2, "message": format!("{:?}", trace.status) })
+            };
+            spans.push(json!({
+                "traceId": trace_id,
+                "spanId": span_id(first_id + idx as u64),
+                "parentSpanId": span_id(parent_id),
+                "name": call_name(node),
+                "kind": 1,
+                "startTimeUnixNano": time(start),
+                "endTimeUnixNano": time(end),
+                "attributes": attributes,
+                "status": status,
+            }));
+        });
+        next_id += arena.nodes().len() as u64;
+        spans.push(json!({
+            "traceId": trace_id,
+            "spanId": span_id(arena_id),
+            "parentSpanId": span_id(1),
+            "name": label,
+            "kind": 1,
+            "startTimeUnixNano": time(start),
+            "endTimeUnixNano": time(end),
+        }));
+    }
+    spans.push(json!({
+        "traceId": trace_id,
+        "spanId": span_id(1),
+        "name": name,
+        "kind": 1,
+        "startTimeUnixNano": time(0),
+        "endTimeUnixNano": time(end),
+    }));
+
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [attribute("service.name", json!({ "stringValue": "foundry" }))],
+            },
+            "scopeSpans": [{
+                "scope": { "name": "foundry-evm-traces" },
+                "spans": spans,
+            }],
+        }],
+    })
+}
+
+fn attribute(key: &str, value: Value) -> Value {
+    json!({ "key": key, "value": value })
+}
+
+/// Converts the arenas to a speedscope file, with an evented profile for each arena.
+///
+/// Profiles are measured in gas.
+fn to_speedscope<'a>(
+    name: &str,
+    arenas: impl IntoIterator<Item = (&'a str, &'a CallTraceArena)>,
+) -> Value {
+    let mut frames = Vec::<String>::new();
+    let mut profiles = Vec::new();
+    for (label, arena) in arenas {
+        let mut events = Vec::new();
+        let mut frame_ids = vec![0; arena.nodes().len()];
+        let end = walk(arena, 0, &mut |event| match event {
+            CallEvent::Enter { node, idx, start, .. } => {
+                let name = call_name(node);
+                let frame = frames.iter().position(|f| *f == name).unwrap_or_else(|| {
+                    frames.push(name);
+                    frames.len() - 1
+                });
+                frame_ids[idx] = frame;
+                events.push(json!({ "type": "O", "frame": frame, "at": start }));
+            }
+            CallEvent::Exit { idx, end } => {
+                events.push(json!({ "type": "C", "frame": frame_ids[idx], "at": end }));
+            }
+        });
+        profiles.push(json!({
+            "type": "evented",
+            "name": label,
+            "unit": "none",
+            "startValue": 0,
+            "endValue": end,
+            "events": events,
+        }));
+    }
+
+    let frames = frames.into_iter().map(|name| json!({ "name": name })).collect::<Vec<_>>();
+    json!({
+        "$schema": "https://www.speedscope.app/file-format-schema.json",
+        "name": name,
+        "exporter": "foundry",
+        "activeProfileIndex": 0,
+        "shared": { "frames": frames },
+        "profiles": profiles,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A call using 100 gas, with two children using 30 and 50 gas.
+    fn arena() -> CallTraceArena {
+        let mut arena = CallTraceArena::default();
+        let nodes = arena.nodes_mut();
+        nodes[0].trace.gas_used = 100;
+        nodes[0].trace.success = true;
+        nodes[0].children = vec![1, 2];
+        for (idx, gas_used) in [(1, 30), (2, 50)] {
+            let mut node = CallTraceNode { parent: Some(0), idx, ..Default::default() };
+            node.trace.gas_used = gas_used;
+            node.trace.depth = 1;
+            nodes.push(node);
+        }
+        arena
+    }
+
+    #[test]
+    fn lays_out_calls_by_gas() {
+        let arena = arena();
+        let mut calls = Vec::new();
+        let end = walk(&arena, 10, &mut |event| {
+            if let CallEvent::Enter { idx, start, end, .. } = event {
+                calls.push((idx, start, end));
+            }
+        });
+        assert_eq!(end, 110);
+        assert_eq!(calls, [(0, 10, 110), (1, 10, 40), (2, 40, 90)]);
+    }
+
+    #[test]
+    fn exports_speedscope_profiles() {
+        let arena = arena();
+        let profile = TraceExportFormat::Speedscope.export("test", [("execution", &arena)]);
+        assert_eq!(profile["shared"]["frames"].as_array().unwrap().len(), 1);
+        assert_eq!(profile["profiles"][0]["endValue"], 100);
+        let events = profile["profiles"][0]["events"].as_array().unwrap();
+        let events = events
+            .iter()
+            .map(|e| (e["type"].as_str().unwrap(), e["at"].as_u64().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(events, [("O", 0), ("O", 0), ("C", 30), ("O", 30), ("C", 80), ("C", 100)]);
+    }
+
+    #[test]
+    fn exports_otlp_spans() {
+        let arena = arena();
+        let request = TraceExportFormat::Otlp.export("test", [("execution", &arena)]);
+        let spans = request["resourceSpans"][0]["scopeSpans"][0]["spans"].as_array().unwrap();
+        // The calls, the execution and the root span.
+        assert_eq!(spans.len(), 5);
+        let root = &spans[4];
+        assert_eq!(root["name"], "test");
+        assert_eq!(spans[3]["parentSpanId"], root["spanId"]);
+        assert_eq!(spans[0]["parentSpanId"], spans[3]["spanId"]);
+        assert_eq!(spans[1]["parentSpanId"], spans[0]["spanId"]);
+        assert_eq!(spans[0]["status"]["code"], 1);
+        assert_eq!(spans[1]["status"]["code"], 2);
+    }
+}
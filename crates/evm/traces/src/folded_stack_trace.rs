@@ -11,6 +11,28 @@ pub fn build(arena: &CallTraceArena) -> Vec<String> {
     fst.build()
 }
 
+/// Returns the name of a call: `new <Contract>` for creations, and `<label>.<signature>` or
+/// `<signature>` for calls.
+pub(crate) fn call_name(node: &CallTraceNode) -> String {
+    if node.trace.kind.is_any_create() {
+        let contract_name = node.trace.decoded.label.as_deref().unwrap_or("Contract");
+        format!("new {contract_name}")
+    } else {
+        let selector = node
+            .selector()
+            .map(|selector| selector.encode_hex_with_prefix())
+            .unwrap_or_else(|| "fallback".to_string());
+        let signature =
+            node.trace.decoded.call_data.as_ref().map(|dc| &dc.signature).unwrap_or(&selector);
+
+        if let Some(label) = &node.trace.decoded.label {
+            format!("{label}.{signature}")
+        } else {
+            signature.clone()
+        }
+    }
+}
+
 /// Wrapper for building a folded stack trace using EVM call trace node.
 #[derive(Default)]
 pub struct EvmFoldedStackTraceBuilder {
@@ -29,25 +51,7 @@ pub fn build(self) -> Vec<String> {
     pub fn process_call_node(&mut self, nodes: &[CallTraceNode], idx: usize) {
         let node = &nodes[idx];
 
-        let func_name = if node.trace.kind.is_any_create() {
-            let contract_name = node.trace.decoded.label.as_deref().unwrap_or("Contract");
-            format!("new {contract_name}")
-        } else {
-            let selector = node
-                .selector()
-                .map(|selector| selector.encode_hex_with_prefix())
-                .unwrap_or_else(|| "fallback".to_string());
-            let signature =
-                node.trace.decoded.call_data.as_ref().map(|dc| &dc.signature).unwrap_or(&selector);
-
-            if let Some(label) = &node.trace.decoded.label {
-                format!("{label}.{signature}")
-            } else {
-                signature.clone()
-            }
-        };
-
-        self.fst.enter(func_name, node.trace.gas_used as i64);
+        self.fst.enter(call_name(node), node.trace.gas_used as i64);
 
         // Track internal function step exits to do in this call context.
         let mut step_exits = vec![];
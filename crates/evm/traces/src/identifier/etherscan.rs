@@ -1,20 +1,21 @@
-use super::{AddressIdentity, TraceIdentifier};
+use super::{compile_verified_sources, AddressIdentity, TraceIdentifier};
 use crate::debug::ContractSources;
 use alloy_primitives::Address;
 use foundry_block_explorers::{
     contract::{ContractMetadata, Metadata},
     errors::EtherscanError,
 };
-use foundry_common::compile::etherscan_project;
+use foundry_common::compile::{cached_etherscan_project, etherscan_project};
 use foundry_config::{Chain, Config};
 use futures::{
-    future::{join_all, Future},
+    future::Future,
     stream::{FuturesUnordered, Stream, StreamExt},
     task::{Context, Poll},
 };
 use std::{
     borrow::Cow,
     collections::BTreeMap,
+    path::Path,
     pin::Pin,
     sync::{
         atomic::{AtomicBool, Ordering},
@@ -32,6 +33,9 @@ pub struct EtherscanIdentifier {
     /// After the first [EtherscanError::InvalidApiKey] this will get set to true, so we can
     /// prevent any further attempts
     invalid_api_key: Arc<AtomicBool>,
+    /// The chain of the Etherscan client, used to find contracts compiled by
+    /// `cast source --recompile`.
+    chain: Option<Chain>,
     pub contracts: BTreeMap<Address, Metadata>,
     pub sources: BTreeMap<u32, String>,
 }
@@ -48,6 +52,7 @@ pub fn new(config: &Config, chain: Option<Chain>) -> eyre::Result<Option<Self>>
         };
         trace!(target: "traces::etherscan", chain=?config.chain, url=?config.api_url, "using etherscan identifier");
         Ok(Some(Self {
+            chain: config.chain,
             client: Arc::new(config.into_client()?),
             invalid_api_key: Arc::new(AtomicBool::new(false)),
             contracts: BTreeMap::new(),
@@ -57,40 +62,30 @@ pub fn new(config: &Config, chain: Option<Chain>) -> eyre::Result<Option<Self>>
 
     /// Goes over the list of contracts we have pulled from the traces, clones their source from
     /// Etherscan and compiles them locally, for usage in the debugger.
+    ///
+    /// Contracts compiled by `cast source --recompile` are reused instead.
     pub async fn get_compiled_contracts(&self) -> eyre::Result<ContractSources> {
         // TODO: Add caching so we dont double-fetch contracts.
-        let outputs_fut = self
+        let projects = self
             .contracts
             .iter()
             // filter out vyper files
             .filter(|(_, metadata)| !metadata.is_vyper())
-            .map(|(address, metadata)| async move {
-                sh_println!("Compiling: {} {address}", metadata.contract_name)?;
-                let root = tempfile::tempdir()?;
-                let root_path = root.path();
-                let project = etherscan_project(metadata, root_path)?;
-                let output = project.compile()?;
-
-                if output.has_compiler_errors() {
-                    eyre::bail!("{output}")
+            .map(|(address, metadata)| {
+                move |root: &Path| {
+                    let cached_root = self
+                        .chain
+                        .and_then(|chain| Config::foundry_etherscan_sources_dir(chain, *address))
+                        .filter(|dir| dir.is_dir());
+                    if let Some(cached_root) = &cached_root {
+                        cached_etherscan_project(metadata, cached_root)
+                    } else {
+                        sh_println!("Compiling: {} {address}", metadata.contract_name)?;
+                        etherscan_project(metadata, root)
+                    }
                 }
-
-                Ok((project, output, root))
-            })
-            .collect::<Vec<_>>();
-
-        // poll all the futures concurrently
-        let outputs = join_all(outputs_fut).await;
-
-        let mut sources: ContractSources = Default::default();
-
-        // construct the map
-        for res in outputs {
-            let (project, output, _root) = res?;
-            sources.insert(&output, project.root(), None)?;
-        }
-
-        Ok(sources)
+            });
+        compile_verified_sources(projects, false).await
     }
 }
 
@@ -1,9 +1,11 @@
+use crate::debug::ContractSources;
 use alloy_json_abi::JsonAbi;
 use alloy_primitives::Address;
 use foundry_common::ContractsByArtifact;
-use foundry_compilers::ArtifactId;
+use foundry_compilers::{solc::SolcCompiler, ArtifactId, Project};
 use foundry_config::{Chain, Config};
-use std::borrow::Cow;
+use futures::future::join_all;
+use std::{borrow::Cow, collections::HashSet, path::Path};
 
 mod local;
 pub use local::LocalTraceIdentifier;
@@ -11,6 +13,9 @@
 mod etherscan;
 pub use etherscan::EtherscanIdentifier;
 
+mod sourcify;
+pub use sourcify::{SourcifyContract, SourcifyIdentifier, SOURCIFY_URL};
+
 mod signatures;
 pub use signatures::{CachedSignatures, SignaturesIdentifier, SingleSignaturesIdentifier};
 
@@ -44,6 +49,8 @@ pub struct TraceIdentifiers<'a> {
     pub local: Option<LocalTraceIdentifier<'a>>,
     /// The optional Etherscan trace identifier.
     pub etherscan: Option<EtherscanIdentifier>,
+    /// The optional Sourcify trace identifier, used for addresses not identified otherwise.
+    pub sourcify: Option<SourcifyIdentifier>,
 }
 
 impl Default for TraceIdentifiers<'_> {
@@ -62,7 +69,13 @@ fn identify_addresses<'a, A>(&mut self, addresses: A) -> Vec<AddressIdentity<'_>
             identities.extend(local.identify_addresses(addresses.clone()));
         }
         if let Some(etherscan) = &mut self.etherscan {
-            identities.extend(etherscan.identify_addresses(addresses));
+            identities.extend(etherscan.identify_addresses(addresses.clone()));
+        }
+        if let Some(sourcify) = &mut self.sourcify {
+            let identified = identities.iter().map(|id| id.address).collect::<HashSet<_>>();
+            identities.extend(sourcify.identify_addresses(
+                addresses.filter(|(address, _, _)| !identified.contains(*address)),
+            ));
         }
         identities
     }
@@ -71,7 +84,7 @@ fn identify_addresses<'a, A>(&mut self, addresses: A) -> Vec<AddressIdentity<'_>
 impl<'a> TraceIdentifiers<'a> {
     /// Creates a new, empty instance.
     pub const fn new() -> Self {
-        Self { local: None, etherscan: None }
+        Self { local: None, etherscan: None, sourcify: None }
     }
 
     /// Sets the local identifier.
@@ -86,8 +99,51 @@ pub fn with_etherscan(mut self, config: &Config, chain: Option<Chain>) -> eyre::
         Ok(self)
     }
 
+    /// Sets the sourcify identifier.
+    pub fn with_sourcify(mut self, config: &Config, chain: Option<Chain>) -> eyre::Result<Self> {
+        self.sourcify = SourcifyIdentifier::new(config, chain)?;
+        Ok(self)
+    }
+
     /// Returns `true` if there are no set identifiers.
     pub fn is_empty(&self) -> bool {
-        self.local.is_none() && self.etherscan.is_none()
+        self.local.is_none() && self.etherscan.is_none() && self.sourcify.is_none()
+    }
+}
+
+/// Compiles the projects of verified contracts concurrently and collects their sources, for usage
+/// in the debugger.
+///
+/// Each project is created by calling its closure with a temporary directory to write the sources
+/// to. If `link` is set, the bytecode is linked with the libraries of the project's settings.
+async fn compile_verified_sources<F>(
+    projects: impl IntoIterator<Item = F>,
+    link: bool,
+) -> eyre::Result<ContractSources>
+where
+    F: FnOnce(&Path) -> eyre::Result<Project<SolcCompiler>>,
+{
+    let outputs_fut = projects.into_iter().map(|project| async move {
+        let root = tempfile::tempdir()?;
+        let project = project(root.path())?;
+        let output = project.compile()?;
+
+        if output.has_compiler_errors() {
+            eyre::bail!("{output}")
+        }
+
+        Ok((project, output, root))
+    });
+
+    // poll all the futures concurrently
+    let outputs = join_all(outputs_fut).await;
+
+    let mut sources: ContractSources = Default::default();
+    for res in outputs {
+        let (project, output, _root) = res?;
+        let libraries = link.then_some(&project.settings.settings.libraries);
+        sources.insert(&output, project.root(), libraries)?;
     }
+
+    Ok(sources)
 }
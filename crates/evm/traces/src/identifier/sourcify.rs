@@ -0,0 +1,362 @@
+use super::{compile_verified_sources, AddressIdentity, TraceIdentifier};
+use crate::debug::ContractSources;
+use alloy_json_abi::JsonAbi;
+use alloy_primitives::Address;
+use eyre::{Context, OptionExt};
+use foundry_compilers::{
+    artifacts::{Libraries, Settings},
+    solc::{Solc, SolcCompiler, SolcSettings},
+    Project, ProjectBuilder, ProjectPathsConfig, SolcConfig,
+};
+use foundry_config::{Chain, Config};
+use futures::stream::{self, StreamExt};
+use semver::Version;
+use serde::Deserialize;
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+/// The default Sourcify server URL.
+pub const SOURCIFY_URL: &str = "https://sourcify.dev/server/";
+
+/// The maximum amount of requests to send concurrently.
+const CONCURRENCY: usize = 5;
+
+/// A trace identifier that tries to identify addresses using contracts verified on Sourcify.
+///
+/// Unlike [`EtherscanIdentifier`](super::EtherscanIdentifier), this does not require an API key.
+pub struct SourcifyIdentifier {
+    client: reqwest::Client,
+    url: String,
+    chain_id: u64,
+    /// Verified contracts, by address.
+    pub contracts: BTreeMap<Address, SourcifyContract>,
+    /// Addresses that are not verified on Sourcify.
+    unverified: HashSet<Address>,
+}
+
+impl SourcifyIdentifier {
+    /// Creates a new Sourcify identifier for the given chain.
+    ///
+    /// Returns `None` in offline mode or if the chain is unknown.
+    pub fn new(config: &Config, chain: Option<Chain>) -> eyre::Result<Option<Self>> {
+        if config.offline {
+            return Ok(None);
+        }
+        let Some(chain) = chain.or(config.chain) else { return Ok(None) };
+        trace!(target: "traces::sourcify", ?chain, "using sourcify identifier");
+        Ok(Some(Self {
+            client: reqwest::Client::builder().build()?,
+            url: SOURCIFY_URL.to_string(),
+            chain_id: chain.id(),
+            contracts: BTreeMap::new(),
+            unverified: HashSet::new(),
+        }))
+    }
+
+    /// Goes over the list of contracts we have pulled from the traces, compiles their verified
+    /// sources locally, for usage in the debugger.
+    pub async fn get_compiled_contracts(&self) -> eyre::Result<ContractSources> {
+        let projects = self.contracts.iter().filter(|(_, contract)| contract.is_solidity()).map(
+            |(address, contract)| {
+                move |root: &Path| {
+                    sh_println!("Compiling: {} {address}", contract.name)?;
+                    contract.project(root)
+                }
+            },
+        );
+        compile_verified_sources(projects, true).await
+    }
+
+    /// Fetches the verified sources and metadata of a contract.
+    async fn fetch(&self, address: Address) -> eyre::Result<Option<SourcifyContract>> {
+        let url =
+            format!("{}/files/any/{}/{address}", self.url.trim_end_matches('/'), self.chain_id);
+        trace!(target: "traces::sourcify", ?address, "fetching info");
+        let response = self.client.get(url).send().await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let files: SourcifyFiles = response.error_for_status()?.json().await?;
+        SourcifyContract::from_files(files.files).map(Some)
+    }
+}
+
+impl TraceIdentifier for SourcifyIdentifier {
+    fn identify_addresses<'a, A>(&mut self, addresses: A) -> Vec<AddressIdentity<'_>>
+    where
+        A: Iterator<Item = (&'a Address, Option<&'a [u8]>, Option<&'a [u8]>)>,
+    {
+        let addresses = addresses.map(|(address, _, _)| *address).collect::<HashSet<_>>();
+        let to_fetch = addresses
+            .iter()
+            .copied()
+            .filter(|address| {
+                !self.contracts.contains_key(address) && !self.unverified.contains(address)
+            })
+            .collect::<Vec<_>>();
+
+        if !to_fetch.is_empty() {
+            let this = &*self;
+            let fetched = foundry_common::block_on(
+                stream::iter(to_fetch)
+                    .map(|address| async move { (address, this.fetch(address).await) })
+                    .buffer_unordered(CONCURRENCY)
+                    .collect::<Vec<_>>(),
+            );
+            for (address, res) in fetched {
+                match res {
+                    Ok(Some(contract)) => {
+                        self.contracts.insert(address, contract);
+                    }
+                    Ok(None) => {
+                        self.unverified.insert(address);
+                    }
+                    Err(err) => {
+                        warn!(target: "traces::sourcify", ?address, %err, "could not fetch contract");
+                        self.unverified.insert(address);
+                    }
+                }
+            }
+        }
+
+        self.contracts
+            .iter()
+            .filter(|(address, _)| addresses.contains(*address))
+            .map(|(address, contract)| AddressIdentity {
+                address: *address,
+                label: Some(contract.name.clone()),
+                contract: Some(contract.name.clone()),
+                abi: Some(Cow::Borrowed(&contract.abi)),
+                artifact_id: None,
+            })
+            .collect()
+    }
+}
+
+/// The response of the Sourcify `files/any` endpoint.
+#[derive(Deserialize)]
+struct SourcifyFiles {
+    files: Vec<SourcifyFile>,
+}
+
+#[derive(Deserialize)]
+struct SourcifyFile {
+    name: String,
+    path: String,
+    content: String,
+}
+
+/// The subset of the Solidity compiler metadata required to recompile a contract.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompilerMetadata {
+    #[serde(default)]
+    language: String,
+    compiler: CompilerInfo,
+    settings: serde_json::Value,
+    output: CompilerOutput,
+}
+
+#[derive(Deserialize)]
+struct CompilerInfo {
+    version: String,
+}
+
+#[derive(Deserialize)]
+struct CompilerOutput {
+    abi: JsonAbi,
+}
+
+/// A contract verified on Sourcify.
+#[derive(Clone, Debug)]
+pub struct SourcifyContract {
+    /// The contract name.
+    pub name: String,
+    /// The source unit name of the file that defines the contract.
+    pub path: String,
+    /// The contract ABI.
+    pub abi: JsonAbi,
+    /// The source language, as reported in the metadata.
+    pub language: String,
+    /// The compiler version.
+    pub compiler_version: Version,
+    /// The compiler settings from the metadata.
+    pub settings: serde_json::Value,
+    /// The verified sources, keyed by source unit name.
+    pub sources: BTreeMap<String, String>,
+}
+
+impl SourcifyContract {
+    fn from_files(files: Vec<SourcifyFile>) -> eyre::Result<Self> {
+        let metadata = files
+            .iter()
+            .find(|file| file.name == "metadata.json")
+            .ok_or_eyre("missing metadata.json")?;
+        let metadata: CompilerMetadata =
+            serde_json::from_str(&metadata.content).wrap_err("invalid metadata.json")?;
+
+        let (path, name) = metadata
+            .settings
+            .get("compilationTarget")
+            .and_then(|target| target.as_object())
+            .and_then(|target| target.iter().next())
+            .and_then(|(path, name)| Some((path.clone(), name.as_str()?.to_string())))
+            .ok_or_eyre("missing compilation target")?;
+
+        // Version strings look like `0.8.19+commit.7dd6d404`.
+        let compiler_version = metadata.compiler.version.trim_start_matches('v');
+        let compiler_version = Version::parse(compiler_version)
+            .map(|v| Version::new(v.major, v.minor, v.patch))
+            .wrap_err("invalid compiler version")?;
+
+        // Source files are stored under `<...>/sources/<source unit name>`.
+        let sources = files
+            .into_iter()
+            .filter_map(|file| {
+                let (_, name) = file.path.split_once("/sources/")?;
+                Some((name.to_string(), file.content))
+            })
+            .collect();
+
+        Ok(Self {
+            name,
+            path,
+            abi: metadata.output.abi,
+            language: metadata.language,
+            compiler_version,
+            settings: metadata.settings,
+            sources,
+        })
+    }
+
+    /// Returns `true` if the contract is written in Solidity.
+    pub fn is_solidity(&self) -> bool {
+        self.language.is_empty() || self.language == "Solidity"
+    }
+
+    /// Returns the solc settings from the metadata.
+    fn solc_settings(&self, root: &Path) -> eyre::Result<Settings> {
+        let mut settings = Settings::default();
+        let get = |key: &str| self.settings.get(key).cloned();
+        if let Some(optimizer) = get("optimizer") {
+            settings.optimizer = serde_json::from_value(optimizer)?;
+        }
+        if let Some(evm_version) = get("evmVersion").and_then(|v| v.as_str()?.parse().ok()) {
+            settings.evm_version = Some(evm_version);
+        }
+        if let Some(via_ir) = get("viaIR") {
+            settings.via_ir = serde_json::from_value(via_ir)?;
+        }
+        if let Some(remappings) = get("remappings") {
+            settings.remappings = serde_json::from_value(remappings)?;
+            // make remappings absolute with our root
+            for remapping in &mut settings.remappings {
+                remapping.path = root.join(&remapping.path).display().to_string();
+            }
+        }
+        if let Some(libraries) = get("libraries").and_then(|v| v.as_object().cloned()) {
+            // Metadata libraries are in the form of `<file>:<lib> -> <addr>`.
+            let libraries = libraries
+                .into_iter()
+                .filter_map(|(lib, address)| Some(format!("{lib}:{}", address.as_str()?)))
+                .collect::<Vec<_>>();
+            settings.libraries = Libraries::parse(&libraries)?;
+        }
+        Ok(settings)
+    }
+
+    /// Writes the sources to `root` and returns a project to compile them.
+    pub fn project(&self, root: &Path) -> eyre::Result<Project<SolcCompiler>> {
+        let root = dunce::canonicalize(root)?;
+        for (name, content) in &self.sources {
+            let path = root.join(sanitize_source_name(name));
+            if let Some(parent) = path.parent() {
+                foundry_common::fs::create_dir_all(parent)?;
+            }
+            foundry_common::fs::write(&path, content)?;
+        }
+
+        let settings = self.solc_settings(&root)?;
+        let paths = ProjectPathsConfig::builder()
+            .sources(&root)
+            .remappings(settings.remappings.clone())
+            .build_with_root(&root);
+
+        let solc = Solc::find_or_install(&self.compiler_version)?;
+
+        Ok(ProjectBuilder::<SolcCompiler>::default()
+            .settings(SolcSettings {
+                settings: SolcConfig::builder().settings(settings).build(),
+                ..Default::default()
+            })
+            .paths(paths)
+            .ephemeral()
+            .no_artifacts()
+            .build(SolcCompiler::Specific(solc))?)
+    }
+}
+
+/// Strips leading separators and parent directory components so sources can't escape the root.
+fn sanitize_source_name(name: &str) -> PathBuf {
+    Path::new(name)
+        .components()
+        .filter(|component| matches!(component, std::path::Component::Normal(_)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sourcify_files() {
+        let metadata = serde_json::json!({
+            "language": "Solidity",
+            "compiler": { "version": "0.8.19+commit.7dd6d404" },
+            "settings": {
+                "compilationTarget": { "src/Counter.sol": "Counter" },
+                "evmVersion": "paris",
+                "libraries": { "src/Lib.sol:Lib": "0x0000000000000000000000000000000000000001" },
+                "optimizer": { "enabled": true, "runs": 200 },
+                "remappings": ["forge-std/=lib/forge-std/src/"]
+            },
+            "output": { "abi": [] }
+        });
+        let files = vec![
+            SourcifyFile {
+                name: "metadata.json".to_string(),
+                path: "/data/full_match/1/0x01/metadata.json".to_string(),
+                content: metadata.to_string(),
+            },
+            SourcifyFile {
+                name: "Counter.sol".to_string(),
+                path: "/data/full_match/1/0x01/sources/src/Counter.sol".to_string(),
+                content: "contract Counter {}".to_string(),
+            },
+        ];
+
+        let contract = SourcifyContract::from_files(files).unwrap();
+        assert_eq!(contract.name, "Counter");
+        assert_eq!(contract.path, "src/Counter.sol");
+        assert_eq!(contract.compiler_version, Version::new(0, 8, 19));
+        assert!(contract.is_solidity());
+        assert_eq!(contract.sources.keys().collect::<Vec<_>>(), ["src/Counter.sol"]);
+
+        let settings = contract.solc_settings(Path::new("/root")).unwrap();
+        assert_eq!(settings.optimizer.runs, Some(200));
+        assert_eq!(settings.remappings[0].path, "/root/lib/forge-std/src/");
+        assert_eq!(
+            settings.libraries.libs[Path::new("src/Lib.sol")]["Lib"],
+            "0x0000000000000000000000000000000000000001"
+        );
+    }
+
+    #[test]
+    fn sanitizes_source_names() {
+        assert_eq!(sanitize_source_name("/abs/../src/A.sol"), Path::new("abs/src/A.sol"));
+        assert_eq!(sanitize_source_name("src/A.sol"), Path::new("src/A.sol"));
+    }
+}
@@ -52,6 +52,9 @@
 
 pub mod folded_stack_trace;
 
+pub mod export;
+pub use export::TraceExportFormat;
+
 pub type Traces = Vec<(TraceKind, SparsedTraceArena)>;
 
 /// Trace arena keeping track of ignored trace items.
@@ -68,7 +71,7 @@ pub struct SparsedTraceArena {
 
 impl SparsedTraceArena {
     /// Goes over entire trace arena and removes ignored trace items.
-    fn resolve_arena(&self) -> Cow<'_, CallTraceArena> {
+    pub fn resolve_arena(&self) -> Cow<'_, CallTraceArena> {
         if self.ignored.is_empty() {
             Cow::Borrowed(&self.arena)
         } else {
@@ -0,0 +1,303 @@
+use clap::{Parser, Subcommand};
+use eyre::Result;
+use foundry_cli::opts::BuildOpts;
+use foundry_common::{compile::ProjectCompiler, shell};
+use foundry_compilers::{
+    artifacts::{output_selection::ContractOutputSelection, Storage, StorageLayout},
+    info::ContractInfo,
+};
+use serde::Serialize;
+use similar::TextDiff;
+use std::{cmp::Reverse, collections::HashSet};
+
+/// The gas cost of setting a zero storage slot, saved once for every slot that isn't used.
+const SSTORE_SET_GAS: u64 = 20_000;
+
+/// CLI arguments for `forge analyze`.
+#[derive(Clone, Debug, Parser)]
+pub struct AnalyzeArgs {
+    #[command(subcommand)]
+    pub sub: AnalyzeSubcommands,
+}
+
+impl AnalyzeArgs {
+    pub fn run(self) -> Result<()> {
+        match self.sub {
+            AnalyzeSubcommands::StoragePacking(args) => args.run(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum AnalyzeSubcommands {
+    /// Suggest orders of the state variables and struct members that use fewer storage slots.
+    #[command(visible_alias = "sp")]
+    StoragePacking(StoragePackingArgs),
+}
+
+/// CLI arguments for `forge analyze storage-packing`.
+#[derive(Clone, Debug, Parser)]
+pub struct StoragePackingArgs {
+    /// The identifier of the contract to analyze in the form `(<path>:)?<contractname>`.
+    ///
+    /// By default, all the contracts of the project's sources are analyzed.
+    pub contract: Option<ContractInfo>,
+
+    /// All build arguments are supported
+    #[command(flatten)]
+    build: BuildOpts,
+}
+
+impl StoragePackingArgs {
+    pub fn run(self) -> Result<()> {
+        let Self { contract, mut build } = self;
+
+        let storage_layout = ContractOutputSelection::StorageLayout;
+        if !build.compiler.extra_output.contains(&storage_layout) {
+            build.compiler.extra_output.push(storage_layout);
+        }
+        let project = build.project()?;
+        let output = ProjectCompiler::new().quiet(true).compile(&project)?;
+
+        let mut found = false;
+        let mut suggestions = Vec::new();
+        let mut structs = HashSet::new();
+        for (id, artifact) in output.artifact_ids() {
+            let is_target = match &contract {
+                Some(contract) => {
+                    id.name == contract.name &&
+                        contract.path.as_ref().is_none_or(|path| id.source.ends_with(path))
+                }
+                None => id.source.starts_with(&project.paths.sources),
+            };
+            let Some(layout) = artifact.storage_layout.as_ref().filter(|_| is_target) else {
+                continue
+            };
+            found = true;
+
+            // Only the variables declared in the contract itself can be reordered, those of the
+            // base contracts come first.
+            let is_own = |storage: &&Storage| {
+                storage
+                    .contract
+                    .rsplit_once(':')
+                    .is_some_and(|(path, name)| name == id.name && id.source.ends_with(path))
+            };
+            let (own, inherited): (Vec<_>, Vec<_>) = layout.storage.iter().partition(is_own);
+            let name = id.with_stripped_file_prefixes(project.root()).identifier();
+            suggestions.extend(Suggestion::new(
+                name,
+                &variables(inherited, layout),
+                &variables(own, layout),
+            ));
+
+            for ty in layout.types.values() {
+                let Some(members) = ty.other.get("members") else { continue };
+                if !structs.insert(ty.label.clone()) {
+                    continue
+                }
+                let members: Vec<Storage> = serde_json::from_value(members.clone())?;
+                let members = variables(&members, layout);
+                suggestions.extend(Suggestion::new(ty.label.clone(), &[], &members));
+            }
+        }
+
+        if let Some(contract) = &contract {
+            if !found {
+                eyre::bail!("Could not find artifact `{contract}` in the compiled artifacts");
+            }
+        }
+
+        if shell::is_json() {
+            sh_println!("{}", serde_json::to_string_pretty(&suggestions)?)?;
+            return Ok(())
+        }
+        if suggestions.is_empty() {
+            sh_println!("No storage slots can be saved by reordering variables.")?;
+            return Ok(())
+        }
+        for suggestion in &suggestions {
+            sh_println!("{suggestion}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A state variable or a struct member.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Variable {
+    /// The declaration of the variable, e.g. `uint128 amount;`.
+    declaration: String,
+    /// The number of bytes of the variable in storage.
+    size: u64,
+    /// Whether the variable starts a new slot, and the next variable too.
+    ///
+    /// This is the case of structs and static arrays, and of the values that fill whole slots.
+    full: bool,
+}
+
+impl Variable {
+    fn new(storage: &Storage, layout: &StorageLayout) -> Option<Self> {
+        let ty = layout.types.get(&storage.storage_type)?;
+        let size = ty.number_of_bytes.parse().ok()?;
+        let is_aggregate = ty.encoding == "inplace" &&
+            (ty.other.contains_key("members") || ty.label.ends_with(']'));
+        let label = ty.label.replace("struct ", "").replace("contract ", "").replace("enum ", "");
+        Some(Self {
+            declaration: format!("{label} {};", storage.label),
+            size,
+            full: is_aggregate || size >= 32,
+        })
+    }
+}
+
+/// Returns the variables of the storage layout that have known types.
+fn variables<'a>(
+    storage: impl IntoIterator<Item = &'a Storage>,
+    layout: &StorageLayout,
+) -> Vec<Variable> {
+    storage.into_iter().filter_map(|storage| Variable::new(storage, layout)).collect()
+}
+
+/// Returns the number of slots used by the variables, placed in order as the compiler does.
+fn slots<'a>(variables: impl IntoIterator<Item = &'a Variable>) -> u64 {
+    let (mut slot, mut offset) = (0, 0);
+    for variable in variables {
+        if offset > 0 && (variable.full || offset + variable.size > 32) {
+            slot += 1;
+            offset = 0;
+        }
+        if variable.full {
+            slot += variable.size.div_ceil(32);
+        } else {
+            offset += variable.size;
+        }
+    }
+    slot + (offset > 0) as u64
+}
+
+/// Reorders the variables to use fewer slots.
+///
+/// The variables smaller than a slot are packed first-fit by decreasing size, and the slots are
+/// then ordered by the position of their first variable, keeping the order close to the original.
+fn pack(variables: &[Variable]) -> Vec<Variable> {
+    let (full, mut small): (Vec<usize>, Vec<usize>) =
+        (0..variables.len()).partition(|&i| variables[i].full);
+    small.sort_by_key(|&i| Reverse(variables[i].size));
+
+    // The indices of the variables of every slot, and the number of bytes they use.
+    let mut slots = full.into_iter().map(|i| (vec![i], 32)).collect::<Vec<_>>();
+    for i in small {
+        let size = variables[i].size;
+        match slots.iter_mut().find(|(_, used)| *used + size <= 32) {
+            Some((indices, used)) => {
+                indices.push(i);
+                *used += size;
+            }
+            None => slots.push((vec![i], size)),
+        }
+    }
+    for (indices, _) in &mut slots {
+        indices.sort_unstable();
+    }
+    slots.sort_by_key(|(indices, _)| indices[0]);
+    slots.into_iter().flat_map(|(indices, _)| indices).map(|i| variables[i].clone()).collect()
+}
+
+/// A reordering of the state variables of a contract, or of the members of a struct, that uses
+/// fewer storage slots.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Suggestion {
+    /// The identifier of the contract, or the name of the struct.
+    name: String,
+    /// The number of slots used with the current order.
+    current_slots: u64,
+    /// The number of slots used with the suggested order.
+    suggested_slots: u64,
+    /// The estimated gas saved when setting all the variables, as every slot saved is an
+    /// `SSTORE` of a zero slot less.
+    gas_saved: u64,
+    /// The declarations in the current order.
+    current: Vec<String>,
+    /// The declarations in the suggested order.
+    suggested: Vec<String>,
+}
+
+impl Suggestion {
+    /// Returns the suggested order of `variables`, placed after `fixed`, if it saves slots.
+    fn new(name: String, fixed: &[Variable], variables: &[Variable]) -> Option<Self> {
+        let suggested = pack(variables);
+        let current_slots = slots(fixed.iter().chain(variables));
+        let suggested_slots = slots(fixed.iter().chain(&suggested));
+        if suggested_slots >= current_slots {
+            return None
+        }
+        Some(Self {
+            name,
+            current_slots,
+            suggested_slots,
+            gas_saved: (current_slots - suggested_slots) * SSTORE_SET_GAS,
+            current: variables.iter().map(|v| v.declaration.clone()).collect(),
+            suggested: suggested.into_iter().map(|v| v.declaration).collect(),
+        })
+    }
+}
+
+impl std::fmt::Display for Suggestion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{}: {} slots instead of {}, saving ~{} gas",
+            self.name, self.suggested_slots, self.current_slots, self.gas_saved
+        )?;
+        let lines = |declarations: &[String]| {
+            declarations
+                .iter()
+                .map(|declaration| format!("    {declaration}\n"))
+                .collect::<String>()
+        };
+        let (current, suggested) = (lines(&self.current), lines(&self.suggested));
+        let diff = TextDiff::from_lines(&current, &suggested);
+        write!(f, "{}", diff.unified_diff().header(&self.name, &self.name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variable(size: u64) -> Variable {
+        Variable { declaration: format!("bytes{size} v;"), size, full: size >= 32 }
+    }
+
+    #[test]
+    fn count_slots() {
+        assert_eq!(slots(&[variable(16), variable(32), variable(16)]), 3);
+        assert_eq!(slots(&[variable(16), variable(16), variable(32)]), 2);
+        assert_eq!(slots(&[variable(20), variable(8), variable(4), variable(1)]), 2);
+
+        let mut array = variable(64);
+        array.full = true;
+        assert_eq!(slots(&[variable(1), array, variable(1)]), 4);
+    }
+
+    #[test]
+    fn pack_variables() {
+        let variables = [variable(8), variable(32), variable(16), variable(8)];
+        let packed = pack(&variables);
+        assert_eq!(packed, [variable(8), variable(16), variable(8), variable(32)]);
+        assert_eq!(slots(&packed), 2);
+
+        let suggestion = Suggestion::new("A".into(), &[], &variables).unwrap();
+        assert_eq!((suggestion.current_slots, suggestion.suggested_slots), (3, 2));
+        assert_eq!(suggestion.gas_saved, SSTORE_SET_GAS);
+
+        // Already packed.
+        assert!(Suggestion::new("A".into(), &[], &packed).is_none());
+
+        // The free bytes of the last slot of the base contracts are used.
+        let suggestion = Suggestion::new("A".into(), &[variable(8)], &[variable(32), variable(8)]);
+        assert_eq!(suggestion.unwrap().suggested_slots, 2);
+    }
+}
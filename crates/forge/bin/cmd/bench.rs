@@ -0,0 +1,212 @@
+use super::test;
+use clap::{Parser, ValueHint};
+use eyre::{Context, Result};
+use forge::result::{BenchStats, TestKind, TestOutcome};
+use foundry_common::fs;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt, path::PathBuf};
+use yansi::Paint;
+
+/// The results of all benchmarks, by contract name and function signature.
+type BenchResults = BTreeMap<String, BTreeMap<String, BenchResult>>;
+
+/// CLI arguments for `forge bench`.
+///
+/// Benchmarks are functions without parameters whose name starts with `bench_`. Each benchmark is
+/// run repeatedly on the state left by `setUp`, and the gas used and wall time of the runs are
+/// summarized with their median, 95th percentile and the 95% confidence interval of the median.
+#[derive(Clone, Debug, Parser)]
+pub struct BenchArgs {
+    /// The number of times to run each benchmark.
+    #[arg(
+        long,
+        default_value = "100",
+        value_parser = clap::value_parser!(u32).range(1..),
+        value_name = "RUNS"
+    )]
+    runs: u32,
+
+    /// The baseline to compare against, or to save the results to with `--save-baseline`.
+    #[arg(
+        long,
+        default_value = ".bench-baseline.json",
+        value_hint = ValueHint::FilePath,
+        value_name = "FILE"
+    )]
+    baseline: PathBuf,
+
+    /// Save the results as the new baseline instead of comparing against it.
+    #[arg(long)]
+    save_baseline: bool,
+
+    /// Tolerates increases of the median gas up to the specified percentage.
+    #[arg(long, default_value = "0", value_name = "PERCENT")]
+    gas_tolerance: f64,
+
+    /// Tolerates increases of the median time up to the specified percentage.
+    #[arg(long, default_value = "10", value_name = "PERCENT")]
+    time_tolerance: f64,
+
+    /// Only compare gas against the baseline.
+    ///
+    /// Wall time depends on the machine, so it can't be compared against a baseline recorded on
+    /// another one.
+    #[arg(long)]
+    ignore_time: bool,
+
+    /// All test arguments are supported
+    #[command(flatten)]
+    pub(crate) test: test::TestArgs,
+}
+
+/// The result of a benchmark, as stored in the baseline.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct BenchResult {
+    runs: usize,
+    gas: BenchStats,
+    /// In nanoseconds.
+    time: BenchStats,
+}
+
+impl BenchArgs {
+    pub async fn run(mut self) -> Result<()> {
+        self.test.bench_runs = Some(self.runs);
+
+        let outcome = self.test.clone().execute_tests().await?;
+        outcome.ensure_ok(false)?;
+        let results = bench_results(outcome);
+
+        if self.save_baseline {
+            fs::write_pretty_json_file(&self.baseline, &results)?;
+            sh_println!("Saved baseline to {}", self.baseline.display())?;
+            return Ok(())
+        }
+
+        if !self.baseline.exists() {
+            sh_warn!(
+                "No baseline found at {}, run with `--save-baseline` to record one",
+                self.baseline.display()
+            )?;
+            return Ok(())
+        }
+        let baseline: BenchResults = fs::read_json_file(&self.baseline)
+            .wrap_err_with(|| format!("failed to read baseline {}", self.baseline.display()))?;
+
+        let regressions = self.compare(&results, &baseline)?;
+        if regressions > 0 {
+            eyre::bail!("{regressions} benchmark(s) regressed from the baseline");
+        }
+        Ok(())
+    }
+
+    /// Prints the changes from the baseline, returning the number of regressed benchmarks.
+    fn compare(&self, results: &BenchResults, baseline: &BenchResults) -> Result<usize> {
+        sh_println!("\nComparing against {}:", self.baseline.display())?;
+        let mut regressions = 0;
+        for (contract, benches) in results {
+            for (signature, result) in benches {
+                let Some(base) = baseline.get(contract).and_then(|b| b.get(signature)) else {
+                    sh_println!("{contract}:{signature} {}", "(not in baseline)".dim())?;
+                    continue;
+                };
+
+                let gas = change(&result.gas, &base.gas, self.gas_tolerance);
+                let mut line = format!("{contract}:{signature} (gas: {gas}");
+                let mut regressed = gas.regressed;
+                if !self.ignore_time {
+                    let time = change(&result.time, &base.time, self.time_tolerance);
+                    line.push_str(&format!(", time: {time}"));
+                    regressed |= time.regressed;
+                }
+                line.push(')');
+
+                if regressed {
+                    regressions += 1;
+                    sh_println!("{} {line}", "[REGRESSED]".red())?;
+                } else {
+                    sh_println!("{} {line}", "[OK]".green())?;
+                }
+            }
+        }
+        Ok(regressions)
+    }
+}
+
+/// The change of the median of a benchmark from the baseline.
+struct Change {
+    percent: f64,
+    regressed: bool,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let percent = format!("{:+.2}%", self.percent);
+        if self.regressed {
+            write!(f, "{}", percent.red())
+        } else if self.percent < 0.0 {
+            write!(f, "{}", percent.green())
+        } else {
+            write!(f, "{percent}")
+        }
+    }
+}
+
+/// Returns the change of the median from the baseline.
+fn change(stats: &BenchStats, baseline: &BenchStats, tolerance: f64) -> Change {
+    let percent = if baseline.median == 0 {
+        0.0
+    } else {
+        (stats.median as f64 - baseline.median as f64) / baseline.median as f64 * 100.0
+    };
+    Change { percent, regressed: stats.is_regression(baseline, tolerance) }
+}
+
+/// Collects the results of all benchmarks of the outcome.
+fn bench_results(outcome: TestOutcome) -> BenchResults {
+    let mut results = BenchResults::new();
+    for test in outcome.into_tests() {
+        if let TestKind::Bench { runs, gas, time } = test.result.kind {
+            results
+                .entry(test.contract_name().to_string())
+                .or_default()
+                .insert(test.signature, BenchResult { runs, gas, time });
+        }
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_bench_stats() {
+        let mut samples = (1..=100).rev().collect::<Vec<u64>>();
+        let stats = BenchStats::new(&mut samples);
+        assert_eq!(stats.mean, 50);
+        assert_eq!(stats.median, 50);
+        assert_eq!(stats.p95, 95);
+        assert_eq!((stats.ci_low, stats.ci_high), (41, 61));
+
+        let stats = BenchStats::new(&mut [7; 10]);
+        assert_eq!(stats, BenchStats { mean: 7, median: 7, p95: 7, ci_low: 7, ci_high: 7 });
+    }
+
+    #[test]
+    fn detects_regressions() {
+        let baseline = BenchStats::new(&mut (100..200).collect::<Vec<u64>>());
+
+        // Overlapping confidence intervals are noise.
+        let noisy = BenchStats::new(&mut (105..205).collect::<Vec<u64>>());
+        assert!(!noisy.is_regression(&baseline, 0.0));
+
+        let slower = BenchStats::new(&mut (200..300).collect::<Vec<u64>>());
+        assert!(slower.is_regression(&baseline, 0.0));
+        assert!(!slower.is_regression(&baseline, 100.0));
+
+        // Deterministic samples regress on any increase past the tolerance.
+        let gas = BenchStats::new(&mut [1000; 10]);
+        assert!(BenchStats::new(&mut [1001; 10]).is_regression(&gas, 0.0));
+        assert!(!BenchStats::new(&mut [1001; 10]).is_regression(&gas, 1.0));
+    }
+}
@@ -1,6 +1,7 @@
 use super::{install, watch::WatchArgs};
 use clap::Parser;
 use eyre::Result;
+use forge::build_hooks::{build_hook_inputs, run_build_hooks, BuildStage};
 use foundry_cli::{opts::BuildOpts, utils::LoadConfig};
 use foundry_common::{compile::ProjectCompiler, shell};
 use foundry_compilers::{
@@ -18,7 +19,7 @@
     Config,
 };
 use serde::Serialize;
-use std::path::PathBuf;
+use std::{num::NonZeroUsize, path::PathBuf};
 
 foundry_config::merge_impl_figment_convert!(BuildArgs, build);
 
@@ -56,6 +57,24 @@ pub struct BuildArgs {
     #[serde(skip)]
     pub ignore_eip_3860: bool,
 
+    /// Print the time and peak memory spent compiling each file, slowest first.
+    ///
+    /// The files that changed are compiled one at a time, each with its imports, so that the peak
+    /// memory of every compiler is measured on its own. With `--max-unit-files`, the units are
+    /// then compiled one at a time too, instead of in parallel.
+    #[arg(long)]
+    #[serde(skip)]
+    pub timings: bool,
+
+    /// Split the sources into compilation units of at most this many files, imports included.
+    ///
+    /// The units are compiled in parallel by separate compiler processes, so that a few large
+    /// files don't serialize the whole build. Combine with `--timings` to time every unit, which
+    /// compiles the units one at a time.
+    #[arg(long, value_name = "FILES")]
+    #[serde(skip)]
+    pub max_unit_files: Option<NonZeroUsize>,
+
     #[command(flatten)]
     #[serde(flatten)]
     pub build: BuildOpts,
@@ -74,6 +93,9 @@ pub fn run(self) -> Result<ProjectCompileOutput> {
             config = self.load_config()?;
         }
 
+        // Run the pre-build hooks first, as they may generate sources.
+        run_build_hooks(&config, BuildStage::PreBuild)?;
+
         let project = config.project()?;
 
         // Collect sources to compile if build subdirectories specified.
@@ -95,11 +117,17 @@ pub fn run(self) -> Result<ProjectCompileOutput> {
             .print_names(self.names)
             .print_sizes(self.sizes)
             .ignore_eip_3860(self.ignore_eip_3860)
+            .timings(self.timings)
+            .max_unit_files(self.max_unit_files)
             .bail(!format_json);
 
         let output = compiler.compile(&project)?;
 
-        if format_json && !self.names && !self.sizes {
+        if !output.has_compiler_errors() {
+            run_build_hooks(&config, BuildStage::PostBuild)?;
+        }
+
+        if format_json && !self.names && !self.sizes && !self.timings {
             sh_println!("{}", serde_json::to_string_pretty(&output.output())?)?;
         }
 
@@ -124,11 +152,14 @@ pub fn is_watch(&self) -> bool {
     /// bootstrap a new [`watchexe::Watchexec`] loop.
     pub(crate) fn watchexec_config(&self) -> Result<watchexec::Config> {
         // Use the path arguments or if none where provided the `src`, `test` and `script`
-        // directories as well as the `foundry.toml` configuration file.
+        // directories, the `foundry.toml` configuration file and the inputs of the pre-build hooks.
         self.watch.watchexec_config(|| {
             let config = self.load_config()?;
             let foundry_toml: PathBuf = config.root.join(Config::FILE_NAME);
-            Ok([config.src, config.test, config.script, foundry_toml])
+            let hook_inputs = build_hook_inputs(&config, BuildStage::PreBuild)?;
+            Ok([config.src, config.test, config.script, foundry_toml]
+                .into_iter()
+                .chain(hook_inputs))
         })
     }
 }
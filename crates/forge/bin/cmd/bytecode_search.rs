@@ -0,0 +1,264 @@
+use alloy_primitives::{hex, Address, Bytes};
+use alloy_provider::Provider;
+use alloy_rpc_types::BlockId;
+use clap::Parser;
+use eyre::{Context, Result};
+use foundry_cli::{
+    opts::{BuildOpts, RpcOpts},
+    utils::{self, LoadConfig},
+};
+use foundry_common::{
+    compile::ProjectCompiler,
+    contracts::{bytecode_diff_score, BytecodeData, ContractsByArtifact},
+    shell,
+};
+use foundry_compilers::artifacts::{BytecodeObject, Offsets};
+use serde::Serialize;
+use std::str::FromStr;
+
+foundry_config::impl_figment_convert!(BytecodeSearchArgs, build, rpc);
+
+/// CLI arguments for `forge bytecode-search`.
+#[derive(Clone, Debug, Parser)]
+pub struct BytecodeSearchArgs {
+    /// The runtime bytecode to identify, or the address of a contract to fetch it from.
+    #[arg(value_name = "CODE_OR_ADDRESS")]
+    pub target: String,
+
+    /// The block to fetch the code at, when an address is given.
+    #[arg(long, short = 'B')]
+    pub block: Option<BlockId>,
+
+    #[command(flatten)]
+    pub rpc: RpcOpts,
+
+    #[command(flatten)]
+    pub build: BuildOpts,
+}
+
+impl BytecodeSearchArgs {
+    pub async fn run(self) -> Result<()> {
+        let config = self.load_config()?;
+        let code: Bytes = if let Ok(address) = Address::from_str(&self.target) {
+            let provider = utils::get_provider(&config)?;
+            provider.get_code_at(address).block_id(self.block.unwrap_or_default()).await?
+        } else {
+            hex::decode(self.target.trim()).wrap_err("invalid bytecode or address")?.into()
+        };
+        if code.is_empty() {
+            eyre::bail!("{} has no code", self.target);
+        }
+
+        let project = config.project()?;
+        let output = ProjectCompiler::new().quiet(true).compile(&project)?;
+        let contracts = ContractsByArtifact::new(
+            output.artifact_ids().map(|(id, artifact)| (id, artifact.clone().into())),
+        );
+
+        let mut matches = contracts
+            .iter()
+            .filter_map(|(id, contract)| {
+                let kind = match_deployed_code(contract.deployed_bytecode.as_ref()?, &code)?;
+                Some(BytecodeMatch {
+                    kind,
+                    contract: id.identifier(),
+                    compiler_version: id.version.to_string(),
+                })
+            })
+            .collect::<Vec<_>>();
+        matches.sort_by(|a, b| b.kind.cmp(&a.kind).then_with(|| a.contract.cmp(&b.contract)));
+
+        if shell::is_json() {
+            sh_println!("{}", serde_json::to_string_pretty(&matches)?)?;
+            return Ok(());
+        }
+
+        if matches.is_empty() {
+            let code = strip_metadata(&code);
+            let closest = contracts
+                .iter()
+                .filter_map(|(id, contract)| {
+                    let local = contract.deployed_bytecode()?;
+                    Some((bytecode_diff_score(strip_metadata(local), code), id))
+                })
+                .min_by(|(a, _), (b, _)| a.total_cmp(b));
+            sh_println!("No contract of the project matches the bytecode.")?;
+            if let Some((score, id)) = closest {
+                sh_println!(
+                    "The closest is {}, with {:.0}% of the bytecode differing.",
+                    id.identifier(),
+                    score * 100.0
+                )?;
+            }
+            return Ok(());
+        }
+
+        for m in &matches {
+            let kind = match m.kind {
+                MatchKind::Full => "Full match",
+                MatchKind::Partial => "Partial match (different metadata)",
+            };
+            sh_println!("{kind}: {} (solc {})", m.contract, m.compiler_version)?;
+        }
+        sh_println!(
+            "Compiler settings: optimizer {}, {} runs, EVM version {}, via-IR {}",
+            if config.optimizer.unwrap_or_default() { "enabled" } else { "disabled" },
+            config.optimizer_runs.unwrap_or(200),
+            config.evm_version,
+            config.via_ir
+        )?;
+        if let Some(version) = metadata_solc_version(&code) {
+            let mismatch = |m: &BytecodeMatch| !m.compiler_version.starts_with(version.as_str());
+            if matches.iter().any(mismatch) {
+                sh_warn!("the metadata of the bytecode was generated by solc {version}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A contract of the project whose runtime code matches the searched bytecode.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BytecodeMatch {
+    #[serde(rename = "match")]
+    kind: MatchKind,
+    contract: String,
+    compiler_version: String,
+}
+
+/// How closely a contract matches the searched bytecode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum MatchKind {
+    /// Only the metadata differs, e.g. because of different comments or source paths.
+    Partial,
+    /// The code is identical, except for immutables and library addresses.
+    Full,
+}
+
+/// Matches the runtime code of a contract against the searched bytecode, ignoring the values of
+/// immutables and linked libraries.
+fn match_deployed_code(bytecode: &BytecodeData, code: &[u8]) -> Option<MatchKind> {
+    let ignored = bytecode
+        .immutable_references
+        .values()
+        .chain(bytecode.link_references.values().flat_map(|v| v.values()))
+        .flatten()
+        .cloned()
+        .collect::<Vec<_>>();
+    let local = match bytecode.object.as_ref()? {
+        BytecodeObject::Bytecode(bytes) => bytes.to_vec(),
+        // Library placeholders are masked below, so replace them with anything that decodes.
+        BytecodeObject::Unlinked(s) => {
+            let mut s = s.strip_prefix("0x").unwrap_or(s).as_bytes().to_vec();
+            for offset in &ignored {
+                let start = offset.start as usize * 2;
+                let end = (start + offset.length as usize * 2).min(s.len());
+                s.get_mut(start..end)?.fill(b'0');
+            }
+            hex::decode(s).ok()?
+        }
+    };
+    match_code(&local, &ignored, code)
+}
+
+fn match_code(local: &[u8], ignored: &[Offsets], code: &[u8]) -> Option<MatchKind> {
+    if local.is_empty() {
+        return None;
+    }
+    let mut local = local.to_vec();
+    let mut code = code.to_vec();
+    // Libraries start with `PUSH20 <address>` to protect against calls to their functions.
+    let has_call_protection = local.starts_with(&CALL_PROTECTION_PREFIX);
+    let mut mask = |offset: &Offsets| {
+        let start = offset.start as usize;
+        let end = start + offset.length as usize;
+        for bytes in [&mut local, &mut code] {
+            if let Some(range) = bytes.get_mut(start..end) {
+                range.fill(0);
+            }
+        }
+    };
+    ignored.iter().for_each(&mut mask);
+    if has_call_protection {
+        mask(&Offsets { start: 1, length: 20 });
+    }
+
+    if local == code {
+        Some(MatchKind::Full)
+    } else if strip_metadata(&local) == strip_metadata(&code) {
+        Some(MatchKind::Partial)
+    } else {
+        None
+    }
+}
+
+/// `PUSH20 0x0000000000000000000000000000000000000000`
+const CALL_PROTECTION_PREFIX: [u8; 21] = hex!("730000000000000000000000000000000000000000");
+
+/// Removes the CBOR-encoded metadata appended by solc, if any.
+fn strip_metadata(code: &[u8]) -> &[u8] {
+    metadata(code).map_or(code, |metadata| &code[..code.len() - metadata.len() - 2])
+}
+
+/// Returns the CBOR-encoded metadata appended by solc, whose length is stored in the last two
+/// bytes of the code.
+fn metadata(code: &[u8]) -> Option<&[u8]> {
+    let [.., hi, lo] = *code else { return None };
+    let len = u16::from_be_bytes([hi, lo]) as usize;
+    let metadata = code.get(code.len().checked_sub(len + 2)?..code.len() - 2)?;
+    // The metadata is a CBOR map.
+    matches!(metadata.first(), Some(0xa0..=0xbf)).then_some(metadata)
+}
+
+/// Returns the solc version recorded in the metadata of the code, e.g. `0.8.24`.
+fn metadata_solc_version(code: &[u8]) -> Option<String> {
+    // The `solc` key, followed by a 3-byte string.
+    const KEY: &[u8] = b"\x64solc\x43";
+    let metadata = metadata(code)?;
+    let start = metadata.windows(KEY.len()).position(|w| w == KEY)? + KEY.len();
+    let [major, minor, patch] = *metadata.get(start..start + 3)? else { return None };
+    Some(format!("{major}.{minor}.{patch}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `PUSH1 0x2a PUSH32 <immutable> STOP`, followed by the metadata of solc 0.8.24.
+    const CODE: &str = concat!(
+        "602a7f0000000000000000000000000000000000000000000000000000000000000000",
+        "a2646970667358221220",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "64736f6c6343000818",
+        "0033",
+    );
+
+    #[test]
+    fn parse_metadata() {
+        let code = hex::decode(CODE).unwrap();
+        assert_eq!(strip_metadata(&code).len(), 35);
+        assert_eq!(metadata_solc_version(&code).as_deref(), Some("0.8.24"));
+        assert_eq!(strip_metadata(&code[..35]).len(), 35);
+        assert_eq!(metadata_solc_version(&code[..35]), None);
+    }
+
+    #[test]
+    fn match_runtime_code() {
+        let local = hex::decode(CODE).unwrap();
+        let immutable = [Offsets { start: 3, length: 32 }];
+
+        let mut deployed = local.clone();
+        deployed[34] = 1;
+        assert_eq!(match_code(&local, &immutable, &deployed), Some(MatchKind::Full));
+        assert_eq!(match_code(&local, &[], &deployed), None);
+
+        let metadata_start = deployed.len() - 40;
+        deployed[metadata_start] = 0xff;
+        assert_eq!(match_code(&local, &immutable, &deployed), Some(MatchKind::Partial));
+
+        deployed[1] = 0x2b;
+        assert_eq!(match_code(&local, &immutable, &deployed), None);
+    }
+}
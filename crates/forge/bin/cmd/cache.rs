@@ -22,6 +22,9 @@ pub enum CacheSubcommands {
 
     /// Shows cached data from the global foundry directory.
     Ls(LsArgs),
+
+    /// Evicts the cached RPC data exceeding the limits of `rpc_storage_caching`.
+    Gc(GcArgs),
 }
 
 /// CLI arguments for `forge clean`.
@@ -106,6 +109,49 @@ pub fn run(self) -> Result<()> {
     }
 }
 
+/// CLI arguments for `forge cache gc`.
+#[derive(Debug, Parser)]
+pub struct GcArgs {
+    /// The maximum total size of the cached blocks in bytes.
+    ///
+    /// Overrides `rpc_storage_caching.max_size`.
+    #[arg(long, value_name = "BYTES")]
+    max_size: Option<u64>,
+
+    /// The number of days after which unused cached blocks are evicted.
+    ///
+    /// Overrides `rpc_storage_caching.max_age_days`.
+    #[arg(long, value_name = "DAYS")]
+    max_age_days: Option<u64>,
+}
+
+impl GcArgs {
+    pub fn run(self) -> Result<()> {
+        let mut caching = Config::load()?.rpc_storage_caching;
+        if let Some(max_size) = self.max_size {
+            caching.max_size = Some(max_size);
+        }
+        if let Some(max_age_days) = self.max_age_days {
+            caching.max_age_days = Some(max_age_days);
+        }
+        if !caching.has_eviction_policy() {
+            sh_warn!(
+                "No cache limits are set, set `rpc_storage_caching.max_size` or \
+                 `rpc_storage_caching.max_age_days`"
+            )?;
+            return Ok(())
+        }
+
+        let evicted = Config::evict_rpc_cache(&caching)?;
+        for block in &evicted {
+            sh_println!("- {} block {} ({} B)", block.chain, block.block, block.size)?;
+        }
+        let size = evicted.iter().map(|block| block.size).sum::<u64>();
+        sh_println!("Evicted {} cached block(s), freeing {size} B", evicted.len())?;
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum ChainOrAll {
     NamedChain(NamedChain),
@@ -185,4 +231,10 @@ fn can_parse_cache_ls() {
         let args: CacheArgs = CacheArgs::parse_from(["cache", "ls"]);
         assert!(matches!(args.sub, CacheSubcommands::Ls(_)));
     }
+
+    #[test]
+    fn can_parse_cache_gc() {
+        let args: CacheArgs = CacheArgs::parse_from(["cache", "gc", "--max-age-days", "30"]);
+        assert!(matches!(args.sub, CacheSubcommands::Gc(GcArgs { max_age_days: Some(30), .. })));
+    }
 }
@@ -3,7 +3,8 @@
 use eyre::Result;
 use foundry_cli::utils::LoadConfig;
 use foundry_common::{evm::EvmArgs, shell};
-use foundry_config::fix::fix_tomls;
+use foundry_config::{find_project_root, fix::fix_tomls, Dotenv, Provenance};
+use std::fmt::Write;
 
 foundry_config::impl_figment_convert!(ConfigArgs, build, evm);
 
@@ -18,6 +19,18 @@ pub struct ConfigArgs {
     #[arg(long)]
     fix: bool,
 
+    /// Print the loaded `.env` files and which file supplied each variable.
+    #[arg(long, conflicts_with_all = ["basic", "fix"])]
+    env: bool,
+
+    /// Print only the values that differ from the defaults, and where each value was set.
+    #[arg(long, conflicts_with_all = ["basic", "fix", "env"])]
+    diff: bool,
+
+    /// Print the final value of a key, e.g. `fuzz.runs`, and every provider that set it.
+    #[arg(long, value_name = "KEY", conflicts_with_all = ["basic", "fix", "env", "diff"])]
+    explain: Option<String>,
+
     // support nested build arguments
     #[command(flatten)]
     build: BuildArgs,
@@ -35,8 +48,34 @@ pub fn run(self) -> Result<()> {
             return Ok(())
         }
 
-        let config = self
-            .load_config_unsanitized()?
+        if self.env {
+            return print_dotenv();
+        }
+
+        if let Some(key) = &self.explain {
+            let (figment, provenance) = Provenance::record(|| self.figment());
+            let explanation = provenance.explain(&figment, key);
+            if shell::is_json() {
+                sh_println!("{}", serde_json::to_string_pretty(&explanation)?)?;
+            } else {
+                sh_print!("{explanation}")?;
+            }
+            return Ok(());
+        }
+
+        let config = self.load_config_unsanitized()?;
+
+        if self.diff {
+            let diff = config.diff_from_default().with_sources(&self.figment());
+            if shell::is_json() {
+                sh_println!("{}", serde_json::to_string_pretty(&diff)?)?;
+            } else {
+                sh_print!("{diff}")?;
+            }
+            return Ok(());
+        }
+
+        let config = config
             .normalized_optimizer_settings()
             // we explicitly normalize the version, so mimic the behavior when invoking solc
             .normalized_evm_version();
@@ -58,3 +97,47 @@ pub fn run(self) -> Result<()> {
         Ok(())
     }
 }
+
+/// Prints the loaded `.env` layers, without revealing the values of the variables.
+fn print_dotenv() -> Result<()> {
+    let dotenv = match Dotenv::loaded() {
+        Some(dotenv) => dotenv.clone(),
+        None => {
+            let cwd = std::env::current_dir()?;
+            Dotenv::resolve(&find_project_root(None)?, &cwd, |name| std::env::var(name).ok())
+        }
+    };
+
+    if shell::is_json() {
+        sh_println!("{}", serde_json::to_string_pretty(&dotenv)?)?;
+        return Ok(());
+    }
+
+    sh_println!("Profile: {}", dotenv.profile)?;
+    sh_println!("Files (lowest to highest precedence):")?;
+    for file in &dotenv.files {
+        let status = if file.loaded { "loaded" } else { "not found" };
+        sh_println!("  {} ({status})", dotenv.display_path(&file.path).display())?;
+    }
+
+    if dotenv.vars.is_empty() {
+        return Ok(());
+    }
+    sh_println!("Variables:")?;
+    for (name, var) in &dotenv.vars {
+        let mut line = format!("  {name}: {}", dotenv.display_path(&var.source).display());
+        if !var.overrides.is_empty() {
+            let overrides = var
+                .overrides
+                .iter()
+                .map(|path| dotenv.display_path(path).display().to_string())
+                .collect::<Vec<_>>();
+            let _ = write!(line, " (overrides {})", overrides.join(", "));
+        }
+        if var.from_process_env {
+            line.push_str(" [ignored, set in process environment]");
+        }
+        sh_println!("{line}")?;
+    }
+    Ok(())
+}
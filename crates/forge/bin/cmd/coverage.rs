@@ -1,13 +1,17 @@
 use super::{install, test::TestArgs, watch::WatchArgs};
-use alloy_primitives::{map::HashMap, Address, Bytes, U256};
+use alloy_primitives::{
+    map::{HashMap, HashSet},
+    Address, Bytes, U256,
+};
 use clap::{Parser, ValueEnum, ValueHint};
 use eyre::{Context, Result};
 use forge::{
     coverage::{
         analysis::{SourceAnalysis, SourceFile, SourceFiles},
         anchors::find_anchors,
-        BytecodeReporter, ContractId, CoverageReport, CoverageReporter, CoverageSummaryReporter,
-        DebugReporter, ItemAnchor, LcovReporter,
+        instrument::Instrumenter,
+        AssertionReporter, BytecodeReporter, ContractId, CoverageReport, CoverageReporter,
+        CoverageSummaryReporter, DebugReporter, HtmlReporter, ItemAnchor, LcovReporter,
     },
     opts::EvmOpts,
     utils::IcPcMap,
@@ -17,15 +21,23 @@
 use foundry_common::compile::ProjectCompiler;
 use foundry_compilers::{
     artifacts::{
-        sourcemap::SourceMap, CompactBytecode, CompactDeployedBytecode, SolcLanguage, Source,
+        output_selection::ContractOutputSelection, sourcemap::SourceMap,
+        vyper::VyperOptimizationMode, CompactBytecode, CompactContractBytecode,
+        CompactDeployedBytecode, SolcLanguage, Source, Sources,
+    },
+    compilers::{
+        multi::MultiCompiler,
+        solc::{SolcCompiler, SolcVersionedInput},
+        Compiler, CompilerInput,
     },
-    compilers::multi::MultiCompiler,
-    Artifact, ArtifactId, Project, ProjectCompileOutput, ProjectPathsConfig,
+    solc::Solc,
+    Artifact, ArtifactId, Project, ProjectCompileOutput,
 };
 use foundry_config::Config;
 use rayon::prelude::*;
 use semver::{Version, VersionReq};
 use std::{
+    collections::BTreeMap,
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -60,9 +72,19 @@ pub struct CoverageArgs {
     #[arg(long)]
     ir_minimum: bool,
 
-    /// The path to output the report.
+    /// Instrument the IR of the contracts with coverage counters.
     ///
-    /// If not specified, the report will be stored in the root of the project.
+    /// The contracts are compiled with `viaIR` and the optimizer settings of the project, and the
+    /// hits of the lines, statements, branches and functions are counted where their code starts
+    /// in the IR. This results in accurate reports whatever the optimizer settings, at the cost of
+    /// compiling every contract a second time.
+    #[arg(long, conflicts_with = "ir_minimum")]
+    instrument: bool,
+
+    /// The path to output the report, a directory for the HTML report.
+    ///
+    /// If not specified, the report will be stored in the root of the project, in `lcov.info` for
+    /// the LCOV report and in the `coverage` directory for the HTML report.
     #[arg(
         long,
         short,
@@ -85,6 +107,15 @@ pub struct CoverageArgs {
 
 impl CoverageArgs {
     pub async fn run(mut self) -> Result<()> {
+        let num_file_reports = self
+            .report
+            .iter()
+            .filter(|kind| matches!(kind, CoverageReportKind::Lcov | CoverageReportKind::Html))
+            .count();
+        if self.report_file.is_some() && num_file_reports > 1 {
+            eyre::bail!("`--report-file` can only be used with a single LCOV or HTML report");
+        }
+
         let (mut config, evm_opts) = self.load_config_and_evm_opts()?;
 
         // install missing dependencies
@@ -99,18 +130,29 @@ pub async fn run(mut self) -> Result<()> {
         // Coverage analysis requires the Solc AST output.
         config.ast = true;
 
-        let (paths, output) = {
-            let (project, output) = self.build(&config)?;
-            (project.paths, output)
-        };
+        // Instrumentation requires the IR, which is only emitted with `viaIR`.
+        if self.instrument {
+            config.via_ir = true;
+            config.extra_output.push(ContractOutputSelection::Ir);
+        }
+
+        let (project, output) = self.build(&config)?;
 
-        self.populate_reporters(&paths.root);
+        self.populate_reporters(&project.paths.root);
 
         sh_println!("Analysing contracts...")?;
-        let report = self.prepare(&paths, &output)?;
+        let (report, instrumented_code) = self.prepare(&project, &output)?;
 
         sh_println!("Running tests...")?;
-        self.collect(&paths.root, &output, report, Arc::new(config), evm_opts).await
+        self.collect(
+            &project.paths.root,
+            &output,
+            report,
+            instrumented_code,
+            Arc::new(config),
+            evm_opts,
+        )
+        .await
     }
 
     fn populate_reporters(&mut self, root: &Path) {
@@ -126,11 +168,16 @@ fn populate_reporters(&mut self, root: &Path) {
                         root.join(self.report_file.as_deref().unwrap_or("lcov.info".as_ref()));
                     Box::new(LcovReporter::new(path, self.lcov_version.clone()))
                 }
+                CoverageReportKind::Html => {
+                    let dir = root.join(self.report_file.as_deref().unwrap_or("coverage".as_ref()));
+                    Box::new(HtmlReporter::new(root.to_path_buf(), dir))
+                }
                 CoverageReportKind::Bytecode => Box::new(BytecodeReporter::new(
                     root.to_path_buf(),
                     root.join("bytecode-coverage"),
                 )),
                 CoverageReportKind::Debug => Box::new(DebugReporter),
+                CoverageReportKind::Assertions => Box::<AssertionReporter>::default(),
             })
             .collect::<Vec<_>>();
     }
@@ -139,7 +186,11 @@ fn populate_reporters(&mut self, root: &Path) {
     fn build(&self, config: &Config) -> Result<(Project, ProjectCompileOutput)> {
         let mut project = config.ephemeral_project()?;
 
-        if self.ir_minimum {
+        if self.instrument {
+            // The IR is compiled with the optimizer settings of the project, but Vyper sources
+            // are still analyzed from their source maps.
+            project.settings.vyper.optimize = Some(VyperOptimizationMode::None);
+        } else if self.ir_minimum {
             // print warning message
             sh_warn!(
                 "`--ir-minimum` enables `viaIR` with minimum optimization, \
@@ -171,6 +222,7 @@ fn build(&self, config: &Config) -> Result<(Project, ProjectCompileOutput)> {
             project.settings.solc.optimizer.runs = None;
             project.settings.solc.optimizer.details = None;
             project.settings.solc.via_ir = None;
+            project.settings.vyper.optimize = Some(VyperOptimizationMode::None);
         }
 
         let output = ProjectCompiler::default()
@@ -180,14 +232,17 @@ fn build(&self, config: &Config) -> Result<(Project, ProjectCompileOutput)> {
         Ok((project, output))
     }
 
-    /// Builds the coverage report.
+    /// Builds the coverage report, and the instrumented bytecode of the contracts if
+    /// instrumenting.
     #[instrument(name = "prepare", skip_all)]
     fn prepare(
         &self,
-        project_paths: &ProjectPathsConfig,
+        project: &Project,
         output: &ProjectCompileOutput,
-    ) -> Result<CoverageReport> {
+    ) -> Result<(CoverageReport, BTreeMap<ArtifactId, CompactContractBytecode>)> {
+        let project_paths = &project.paths;
         let mut report = CoverageReport::default();
+        let mut instrumented_code = BTreeMap::new();
 
         // Collect source files.
         let mut versioned_sources = HashMap::<Version, SourceFiles<'_>>::default();
@@ -199,21 +254,28 @@ fn prepare(
                 continue;
             }
 
-            if let Some(ast) = &source_file.ast {
-                let file = project_paths.root.join(path);
-                trace!(root=?project_paths.root, ?file, "reading source file");
-
-                let source = SourceFile {
-                    ast,
-                    source: Source::read(&file)
-                        .wrap_err("Could not read source code for analysis")?,
-                };
-                versioned_sources
-                    .entry(version.clone())
-                    .or_default()
-                    .sources
-                    .insert(source_file.id as usize, source);
+            // Vyper and Yul sources have no AST in the compiler output and are analyzed from their
+            // source code instead. Interfaces are skipped as they have no implementation.
+            let is_vyper = path.extension().is_some_and(|ext| ext == "vy");
+            let is_yul = path.extension().is_some_and(|ext| ext == "yul");
+            if source_file.ast.is_none() && !is_vyper && !is_yul {
+                continue;
             }
+
+            let file = project_paths.root.join(path);
+            trace!(root=?project_paths.root, ?file, "reading source file");
+
+            let source = SourceFile {
+                ast: source_file.ast.as_ref(),
+                source: Source::read(&file).wrap_err("Could not read source code for analysis")?,
+                name: path.file_stem().unwrap_or_default().to_string_lossy().into(),
+                is_yul,
+            };
+            versioned_sources
+                .entry(version.clone())
+                .or_default()
+                .sources
+                .insert(source_file.id as usize, source);
         }
 
         // Get source maps and bytecodes.
@@ -229,9 +291,29 @@ fn prepare(
         // Add coverage items.
         for (version, sources) in &versioned_sources {
             let source_analysis = SourceAnalysis::new(sources)?;
+
+            // Instrumented contracts have no anchors, their items are hit by their counters.
+            let mut instrumented = HashSet::<ContractId>::default();
+            if self.instrument {
+                for (id, code) in instrument(project, output, version, &source_analysis)? {
+                    if let Some(source_id) =
+                        report.get_source_id(id.version.clone(), id.source.clone())
+                    {
+                        instrumented.insert(ContractId {
+                            version: id.version.clone(),
+                            source_id,
+                            contract_name: id.name.as_str().into(),
+                        });
+                    }
+                    instrumented_code
+                        .insert(id.with_stripped_file_prefixes(&project_paths.root), code);
+                }
+            }
+
             let anchors = artifacts
                 .par_iter()
                 .filter(|artifact| artifact.contract_id.version == *version)
+                .filter(|artifact| !instrumented.contains(&artifact.contract_id))
                 .map(|artifact| {
                     let creation_code_anchors = artifact.creation.find_anchors(&source_analysis);
                     let deployed_code_anchors = artifact.deployed.find_anchors(&source_analysis);
@@ -248,7 +330,7 @@ fn prepare(
             }));
         }
 
-        Ok(report)
+        Ok((report, instrumented_code))
     }
 
     /// Runs tests, collects coverage data and generates the final report.
@@ -257,6 +339,7 @@ async fn collect(
         root: &Path,
         output: &ProjectCompileOutput,
         mut report: CoverageReport,
+        instrumented_code: BTreeMap<ArtifactId, CompactContractBytecode>,
         config: Arc<Config>,
         evm_opts: EvmOpts,
     ) -> Result<()> {
@@ -270,12 +353,14 @@ async fn collect(
             .sender(evm_opts.sender)
             .with_fork(evm_opts.get_fork(&config, env.clone()))
             .set_coverage(true)
+            .set_assertion_coverage(self.report.contains(&CoverageReportKind::Assertions))
+            .instrumented_code(instrumented_code)
             .build::<MultiCompiler>(root, output, env, evm_opts)?;
 
         let known_contracts = runner.known_contracts.clone();
 
         let filter = self.test.filter(&config);
-        let outcome = self.test.run_tests(runner, config, verbosity, &filter, output).await?;
+        let outcome = self.test.run_tests(runner, config, verbosity, &filter, output, None).await?;
 
         outcome.ensure_ok(false)?;
 
@@ -285,31 +370,40 @@ async fn collect(
             for result in suite.test_results.values() {
                 let Some(hit_maps) = result.coverage.as_ref() else { continue };
                 for map in hit_maps.0.values() {
-                    if let Some((id, _)) = known_contracts.find_by_deployed_code(map.bytecode()) {
-                        hits.push((id, map, true));
-                    } else if let Some((id, _)) =
+                    if let Some((id, contract)) =
+                        known_contracts.find_by_deployed_code(map.bytecode())
+                    {
+                        hits.push((id, contract, map, true));
+                    } else if let Some((id, contract)) =
                         known_contracts.find_by_creation_code(map.bytecode())
                     {
-                        hits.push((id, map, false));
+                        hits.push((id, contract, map, false));
                     }
                 }
             }
             hits
         });
 
-        for (artifact_id, map, is_deployed_code) in data {
+        for (artifact_id, contract, map, is_deployed_code) in data {
             if let Some(source_id) =
                 report.get_source_id(artifact_id.version.clone(), artifact_id.source.clone())
             {
-                report.add_hit_map(
-                    &ContractId {
-                        version: artifact_id.version.clone(),
-                        source_id,
-                        contract_name: artifact_id.name.as_str().into(),
-                    },
-                    map,
-                    is_deployed_code,
-                )?;
+                let contract_id = ContractId {
+                    version: artifact_id.version.clone(),
+                    source_id,
+                    contract_name: artifact_id.name.as_str().into(),
+                };
+                report.add_hit_map(&contract_id, map, is_deployed_code)?;
+
+                // Only the functions of the deployed code are called with a selector.
+                if is_deployed_code {
+                    let functions = map.functions().filter_map(|(selector, asserted)| {
+                        let function =
+                            contract.abi.functions().find(|func| func.selector() == selector)?;
+                        Some((function.name.clone(), asserted))
+                    });
+                    report.add_function_assertions(&contract_id, functions);
+                }
             }
         }
 
@@ -340,13 +434,71 @@ pub(crate) fn watch(&self) -> &WatchArgs {
 }
 
 /// Coverage reports to generate.
-#[derive(Clone, Debug, Default, ValueEnum)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
 pub enum CoverageReportKind {
     #[default]
     Summary,
     Lcov,
+    /// HTML pages of the annotated sources, in the `coverage` directory of the project.
+    Html,
     Debug,
     Bytecode,
+    /// Experimental: whether the tests asserted on the return values or storage writes of the
+    /// external functions they executed.
+    Assertions,
+}
+
+/// Instruments the IR of the contracts compiled with the given compiler version, and compiles it.
+///
+/// Returns the instrumented bytecode of the contracts, by artifact ID. Contracts that can't be
+/// instrumented keep their compiled bytecode, and are covered using their source maps.
+fn instrument(
+    project: &Project,
+    output: &ProjectCompileOutput,
+    version: &Version,
+    analysis: &SourceAnalysis,
+) -> Result<BTreeMap<ArtifactId, CompactContractBytecode>> {
+    let solc = match &project.compiler.solc {
+        Some(SolcCompiler::Specific(solc)) => solc.clone(),
+        _ => Solc::find_or_install(&Version::new(version.major, version.minor, version.patch))?,
+    };
+    let mut settings = project.settings.solc.clone();
+    settings.via_ir = None;
+
+    let instrumenter = Instrumenter::new(analysis);
+    let artifacts = output
+        .artifact_ids()
+        .filter(|(id, artifact)| id.version == *version && artifact.ir.is_some())
+        .collect::<Vec<_>>();
+    Ok(artifacts
+        .into_par_iter()
+        .filter_map(|(id, artifact)| {
+            let ir = artifact.ir.as_deref()?;
+            let code = instrumenter.instrument(ir).and_then(|ir| {
+                let path = PathBuf::from(format!("{}.yul", id.name));
+                let sources = Sources::from([(path, Source::new(ir))]);
+                let input = SolcVersionedInput::build(
+                    sources,
+                    settings.clone(),
+                    SolcLanguage::Yul,
+                    solc.version.clone(),
+                );
+                let output = SolcCompiler::Specific(solc.clone()).compile(&input)?;
+                if let Some(err) = output.errors.iter().find(|err| err.is_error()) {
+                    eyre::bail!("{err}");
+                }
+                let contract = output.contracts.into_values().flat_map(|c| c.into_values()).next();
+                contract.map(Into::into).ok_or_else(|| eyre::eyre!("no compiled contract"))
+            });
+            match code {
+                Ok(code) => Some((id, code)),
+                Err(err) => {
+                    let _ = sh_warn!("could not instrument {}: {err}", id.identifier());
+                    None
+                }
+            }
+        })
+        .collect())
 }
 
 /// Helper function that will link references in unlinked bytecode to the 0 address.
@@ -3,6 +3,7 @@
 use eyre::Result;
 use forge_doc::{
     ContractInheritance, Deployments, DocBuilder, GitSource, InferInlineHyperlinks, Inheritdoc,
+    TestCoverage,
 };
 use foundry_cli::opts::GH_REPO_PREFIX_REGEX;
 use foundry_common::compile::ProjectCompiler;
@@ -63,6 +64,14 @@ pub struct DocArgs {
     /// Whether to create docs for external libraries.
     #[arg(long, short)]
     include_libraries: bool,
+
+    /// Whether to create docs for the scripts.
+    #[arg(long)]
+    include_scripts: bool,
+
+    /// Whether to create docs for the tests, linked to the contracts they cover.
+    #[arg(long)]
+    include_tests: bool,
 }
 
 impl DocArgs {
@@ -77,6 +86,8 @@ pub async fn run(self) -> Result<()> {
         if let Some(out) = self.out {
             doc_config.out = out;
         }
+        doc_config.include_scripts |= self.include_scripts;
+        doc_config.include_tests |= self.include_tests;
         if doc_config.repository.is_none() {
             // Attempt to read repo from git
             if let Ok(output) = Command::new("git").args(["remote", "get-url", "origin"]).output() {
@@ -115,6 +126,15 @@ pub async fn run(self) -> Result<()> {
             repository: doc_config.repository.clone(),
         });
 
+        if doc_config.include_scripts {
+            builder = builder.with_extra_sources(project.paths.scripts);
+        }
+        if doc_config.include_tests {
+            builder = builder
+                .with_extra_sources(project.paths.tests.clone())
+                .with_preprocessor(TestCoverage { root: root.clone(), tests: project.paths.tests });
+        }
+
         // If deployment docgen is enabled, add the [Deployments] preprocessor
         if let Some(deployments) = self.deployments {
             builder = builder.with_preprocessor(Deployments { root: root.clone(), deployments });
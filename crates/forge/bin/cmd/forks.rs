@@ -0,0 +1,71 @@
+use alloy_provider::Provider;
+use clap::{Parser, Subcommand};
+use eyre::Result;
+use foundry_cli::{opts::ProjectPathOpts, utils::LoadConfig};
+use foundry_common::provider::ProviderBuilder;
+use foundry_evm::fork::{ForkPins, FORK_PINS_FILE};
+
+/// CLI arguments for `forge forks`.
+#[derive(Clone, Debug, Parser)]
+pub struct ForksArgs {
+    #[command(subcommand)]
+    pub sub: ForksSubcommands,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum ForksSubcommands {
+    /// Refreshes the recorded fork pins to the latest block of each chain.
+    ///
+    /// The latest block is fetched from the configured `rpc_endpoints` and `eth_rpc_url`. Only
+    /// chains that already have a pin in `<cache>/fork-pins.toml` are updated.
+    Update(UpdateForksArgs),
+}
+
+/// CLI arguments for `forge forks update`.
+#[derive(Clone, Debug, Parser)]
+pub struct UpdateForksArgs {
+    #[command(flatten)]
+    project_paths: ProjectPathOpts,
+}
+
+foundry_config::impl_figment_convert!(UpdateForksArgs, project_paths);
+
+impl UpdateForksArgs {
+    pub async fn run(self) -> Result<()> {
+        let config = self.load_config()?;
+        let path = config.cache_path.join(FORK_PINS_FILE);
+        let pins = ForkPins::load(&path, false)?;
+        let mut pending = pins.pins().pins;
+        if pending.is_empty() {
+            sh_println!("No fork pins recorded in {}", path.display())?;
+            return Ok(());
+        }
+
+        let mut urls = config
+            .rpc_endpoints
+            .clone()
+            .resolved()
+            .values()
+            .filter_map(|endpoint| endpoint.url().ok())
+            .collect::<Vec<_>>();
+        if let Some(Ok(url)) = config.get_rpc_url() {
+            urls.push(url.into_owned());
+        }
+
+        for url in urls {
+            let Ok(provider) = ProviderBuilder::new(&url).build() else { continue };
+            let Ok(chain_id) = provider.get_chain_id().await else { continue };
+            let Some(previous) = pending.remove(&chain_id.to_string()) else { continue };
+            let block = provider.get_block_number().await?;
+            pins.record(chain_id, block);
+            sh_println!("chain {chain_id}: {previous} -> {block}")?;
+        }
+
+        for chain_id in pending.keys() {
+            sh_warn!("no RPC endpoint configured for chain {chain_id}, keeping its pin")?;
+        }
+
+        pins.save(&path)?;
+        Ok(())
+    }
+}
@@ -1,7 +1,14 @@
+use alloy_json_abi::{Function, InternalType, JsonAbi, Param, StateMutability};
 use clap::{Parser, Subcommand};
-use eyre::Result;
-use foundry_common::fs;
-use std::path::Path;
+use eyre::{Context, Result};
+use forge_fmt::{format_to, parse, FormatterConfig};
+use foundry_cli::{opts::BuildOpts, utils::LoadConfig};
+use foundry_common::{compile::ProjectCompiler, fs};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Write,
+    path::{Component, Path, PathBuf},
+};
 use yansi::Paint;
 
 /// CLI arguments for `forge generate`.
@@ -20,35 +27,267 @@ pub enum GenerateSubcommands {
 #[derive(Debug, Parser)]
 pub struct GenerateTestArgs {
     /// Contract name for test generation.
-    #[arg(long, short, value_name = "CONTRACT_NAME")]
+    #[arg(long, short, visible_alias = "contract", value_name = "CONTRACT_NAME")]
     pub contract_name: String,
+
+    #[command(flatten)]
+    build: BuildOpts,
 }
 
+foundry_config::impl_figment_convert!(GenerateTestArgs, build);
+
 impl GenerateTestArgs {
     pub fn run(self) -> Result<()> {
+        let config = self.load_config()?;
+        let project = config.project()?;
+
         let contract_name = format_identifier(&self.contract_name, true);
         let instance_name = format_identifier(&self.contract_name, false);
 
-        // Create the test file content.
-        let test_content = include_str!("../../../assets/generated/TestTemplate.t.sol");
-        let test_content = test_content
-            .replace("{contract_name}", &contract_name)
-            .replace("{instance_name}", &instance_name);
+        // Compile the contract to get its ABI.
+        let source_path = project.find_contract_path(&contract_name)?;
+        let mut output =
+            ProjectCompiler::new().quiet(true).files([source_path.clone()]).compile(&project)?;
+        let abi = output
+            .remove(&source_path, &contract_name)
+            .and_then(|artifact| artifact.abi)
+            .ok_or_else(|| eyre::eyre!("could not find ABI of contract `{contract_name}`"))?;
+
+        // Resolve the sources of the contract types used in the ABI so they can be imported.
+        let test_dir = config.root.join(&config.test);
+        let mut imports = HashMap::new();
+        for name in contract_types(&abi) {
+            if name == contract_name {
+                continue;
+            }
+            if let Ok(path) = project.find_contract_path(&name) {
+                imports.insert(name, import_path(&test_dir, &config.root, &path));
+            }
+        }
+
+        let scaffold = TestScaffold {
+            contract_name: &contract_name,
+            instance_name: &instance_name,
+            import_path: import_path(&test_dir, &config.root, &source_path),
+            imports,
+            abi: &abi,
+        };
+        let test_content = format_source(&scaffold.render(), config.fmt.clone())?;
 
         // Create the test directory if it doesn't exist.
-        fs::create_dir_all("test")?;
+        fs::create_dir_all(&test_dir)?;
 
         // Define the test file path
-        let test_file_path = Path::new("test").join(format!("{contract_name}.t.sol"));
+        let test_file_path = test_dir.join(format!("{contract_name}.t.sol"));
 
         // Write the test content to the test file.
         fs::write(&test_file_path, test_content)?;
 
-        sh_println!("{} test file: {}", "Generated".green(), test_file_path.to_str().unwrap())?;
+        let test_file_path = test_file_path.strip_prefix(&config.root).unwrap_or(&test_file_path);
+        sh_println!("{} test file: {}", "Generated".green(), test_file_path.display())?;
         Ok(())
     }
 }
 
+/// Test file skeleton for a contract, rendered from its ABI.
+struct TestScaffold<'a> {
+    contract_name: &'a str,
+    instance_name: &'a str,
+    /// Import path of the contract's source file, relative to the test file.
+    import_path: String,
+    /// Import paths of the other contract types used in the ABI.
+    imports: HashMap<String, String>,
+    abi: &'a JsonAbi,
+}
+
+impl TestScaffold<'_> {
+    fn render(&self) -> String {
+        let Self { contract_name, instance_name, .. } = *self;
+        let constructor_inputs =
+            self.abi.constructor.as_ref().map(|c| c.inputs.as_slice()).unwrap_or_default();
+
+        let mut out = String::new();
+        let _ = writeln!(out, "// SPDX-License-Identifier: UNLICENSED");
+        let _ = writeln!(out, "pragma solidity ^0.8.13;\n");
+        let _ = writeln!(out, r#"import {{Test, console}} from "forge-std/Test.sol";"#);
+        let _ = writeln!(out, r#"import {{{contract_name}}} from "{}";"#, self.import_path);
+        let imports = self.imports.iter().collect::<BTreeSet<_>>();
+        for (name, path) in imports {
+            let _ = writeln!(out, r#"import {{{name}}} from "{path}";"#);
+        }
+
+        let _ = writeln!(out, "\ncontract {contract_name}Test is Test {{");
+        let _ = writeln!(out, "{contract_name} public {instance_name};");
+
+        // Mocks for constructor dependencies.
+        let dependencies = constructor_inputs
+            .iter()
+            .enumerate()
+            .filter_map(|(i, param)| {
+                Some((param_name(param, i), param.internal_type()?.as_contract()?))
+            })
+            .collect::<Vec<_>>();
+        for (name, ty) in &dependencies {
+            let _ = writeln!(out, "{ty} public {name};");
+        }
+
+        let _ = writeln!(out, "\nfunction setUp() public {{");
+        for (name, ty) in &dependencies {
+            let _ = writeln!(out, r#"{name} = {ty}(makeAddr("{name}"));"#);
+            let _ = writeln!(out, r#"vm.etch(address({name}), hex"00");"#);
+            let _ = writeln!(out, "// TODO: mock calls to `{name}` with `vm.mockCall`.");
+        }
+        let mut args = Vec::with_capacity(constructor_inputs.len());
+        for (i, param) in constructor_inputs.iter().enumerate() {
+            let name = param_name(param, i);
+            if param.internal_type().and_then(InternalType::as_contract).is_none() {
+                let _ = writeln!(
+                    out,
+                    "{} {name}; // TODO: set constructor argument",
+                    solidity_type(param)
+                );
+            }
+            args.push(name);
+        }
+        let _ = writeln!(out, "{instance_name} = new {contract_name}({});", args.join(", "));
+        let _ = writeln!(out, "}}");
+
+        let mut test_names = HashMap::<&str, usize>::new();
+        for function in self.abi.functions() {
+            // Disambiguate overloaded functions.
+            let count = test_names.entry(&function.name).or_default();
+            let suffix = if *count == 0 { String::new() } else { format!("_{count}") };
+            *count += 1;
+            self.render_tests(&mut out, function, &suffix);
+        }
+
+        let _ = writeln!(out, "}}");
+        out
+    }
+
+    /// Renders an empty unit test and, for functions that take arguments or ether, a fuzz test.
+    fn render_tests(&self, out: &mut String, function: &Function, suffix: &str) {
+        let name = &function.name;
+        let _ = writeln!(out, "\nfunction test_{name}{suffix}() public {{");
+        let _ = writeln!(out, "// TODO: test `{}`", function.signature());
+        let _ = writeln!(out, "}}");
+
+        let payable = function.state_mutability == StateMutability::Payable;
+        if function.inputs.is_empty() && !payable {
+            return;
+        }
+
+        let mut params = function
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(i, param)| format!("{} {}", solidity_type(param), param_name(param, i)))
+            .collect::<Vec<_>>();
+        let args = function
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(i, param)| param_name(param, i))
+            .collect::<Vec<_>>()
+            .join(", ");
+        if payable {
+            params.push("uint256 msgValue".to_string());
+        }
+
+        let mutability = match function.state_mutability {
+            StateMutability::Pure | StateMutability::View => " view",
+            _ => "",
+        };
+        let _ = writeln!(
+            out,
+            "\nfunction testFuzz_{name}{suffix}({}) public{mutability} {{",
+            params.join(", ")
+        );
+        let _ = writeln!(out, "// TODO: constrain inputs with `vm.assume` or `bound`.");
+        let instance_name = self.instance_name;
+        if payable {
+            let _ = writeln!(out, "vm.deal(address(this), msgValue);");
+            let _ = writeln!(out, "{instance_name}.{name}{{value: msgValue}}({args});");
+        } else {
+            let _ = writeln!(out, "{instance_name}.{name}({args});");
+        }
+        let _ = writeln!(out, "}}");
+    }
+}
+
+/// Returns the names of all contract types used in the inputs of the ABI.
+fn contract_types(abi: &JsonAbi) -> BTreeSet<String> {
+    abi.constructor
+        .iter()
+        .flat_map(|c| &c.inputs)
+        .chain(abi.functions().flat_map(|f| &f.inputs))
+        .filter_map(|param| param.internal_type()?.as_contract())
+        .map(|name| name.split('[').next().unwrap_or(name).to_string())
+        .collect()
+}
+
+/// Returns the name of a parameter, or a placeholder for unnamed parameters.
+fn param_name(param: &Param, index: usize) -> String {
+    if param.name.is_empty() {
+        format!("arg{index}")
+    } else {
+        param.name.clone()
+    }
+}
+
+/// Returns the Solidity type of a parameter, including its data location for reference types.
+fn solidity_type(param: &Param) -> String {
+    let ty = match param.internal_type() {
+        Some(InternalType::AddressPayable(ty)) | Some(InternalType::Contract(ty)) => ty.clone(),
+        Some(
+            InternalType::Struct { contract, ty } |
+            InternalType::Enum { contract, ty } |
+            InternalType::Other { contract, ty },
+        ) => match contract {
+            Some(contract) => format!("{contract}.{ty}"),
+            None => ty.clone(),
+        },
+        None => param.ty.clone(),
+    };
+    let is_reference = param.ty == "string" ||
+        param.ty == "bytes" ||
+        param.ty.ends_with(']') ||
+        param.ty.starts_with("tuple");
+    if is_reference {
+        format!("{ty} memory")
+    } else {
+        ty
+    }
+}
+
+/// Returns the import path of `source` relative to the test directory.
+fn import_path(test_dir: &Path, root: &Path, source: &Path) -> String {
+    let source = source.strip_prefix(root).unwrap_or(source);
+    let depth = test_dir
+        .strip_prefix(root)
+        .map(|dir| dir.components().filter(|c| matches!(c, Component::Normal(_))).count())
+        .unwrap_or_default();
+    let mut path = PathBuf::new();
+    for _ in 0..depth {
+        path.push("..");
+    }
+    path.push(source);
+    let path = path.to_string_lossy().replace('\\', "/");
+    if path.starts_with('.') {
+        path
+    } else {
+        format!("./{path}")
+    }
+}
+
+/// Formats the generated source with the project's formatter config.
+fn format_source(source: &str, config: FormatterConfig) -> Result<String> {
+    let parsed = parse(source).map_err(|_| eyre::eyre!("failed to parse generated test"))?;
+    let mut output = String::new();
+    format_to(&mut output, parsed, config).wrap_err("failed to format generated test")?;
+    Ok(output)
+}
+
 /// Utility function to convert an identifier to pascal or camel case.
 fn format_identifier(input: &str, is_pascal_case: bool) -> String {
     let mut result = String::new();
@@ -68,3 +307,86 @@ fn format_identifier(input: &str, is_pascal_case: bool) -> String {
     }
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_test_scaffold() {
+        let abi: JsonAbi = serde_json::from_str(
+            r#"[
+                {"type":"constructor","stateMutability":"nonpayable","inputs":[
+                    {"name":"token","type":"address","internalType":"contract IERC20"},
+                    {"name":"name","type":"string","internalType":"string"}
+                ]},
+                {"type":"function","name":"deposit","stateMutability":"payable","inputs":[
+                    {"name":"amounts","type":"uint256[]","internalType":"uint256[]"}
+                ],"outputs":[]},
+                {"type":"function","name":"total","stateMutability":"view","inputs":[],"outputs":[
+                    {"name":"","type":"uint256","internalType":"uint256"}
+                ]}
+            ]"#,
+        )
+        .unwrap();
+        let scaffold = TestScaffold {
+            contract_name: "Vault",
+            instance_name: "vault",
+            import_path: "../src/Vault.sol".to_string(),
+            imports: HashMap::from([("IERC20".to_string(), "../src/IERC20.sol".to_string())]),
+            abi: &abi,
+        };
+        let rendered = format_source(&scaffold.render(), FormatterConfig::default()).unwrap();
+        assert_eq!(
+            rendered,
+            r#"// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^0.8.13;
+
+import {Test, console} from "forge-std/Test.sol";
+import {Vault} from "../src/Vault.sol";
+import {IERC20} from "../src/IERC20.sol";
+
+contract VaultTest is Test {
+    Vault public vault;
+    IERC20 public token;
+
+    function setUp() public {
+        token = IERC20(makeAddr("token"));
+        vm.etch(address(token), hex"00");
+        // TODO: mock calls to `token` with `vm.mockCall`.
+        string memory name; // TODO: set constructor argument
+        vault = new Vault(token, name);
+    }
+
+    function test_deposit() public {
+        // TODO: test `deposit(uint256[])`
+    }
+
+    function testFuzz_deposit(uint256[] memory amounts, uint256 msgValue) public {
+        // TODO: constrain inputs with `vm.assume` or `bound`.
+        vm.deal(address(this), msgValue);
+        vault.deposit{value: msgValue}(amounts);
+    }
+
+    function test_total() public {
+        // TODO: test `total()`
+    }
+}
+"#
+        );
+    }
+
+    #[test]
+    fn relative_import_path() {
+        let root = Path::new("/project");
+        assert_eq!(
+            import_path(&root.join("test"), root, &root.join("src/Foo.sol")),
+            "../src/Foo.sol"
+        );
+        assert_eq!(
+            import_path(&root.join("test/unit"), root, &root.join("src/Foo.sol")),
+            "../../src/Foo.sol"
+        );
+        assert_eq!(import_path(root, root, &root.join("src/Foo.sol")), "./src/Foo.sol");
+    }
+}
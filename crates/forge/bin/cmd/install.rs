@@ -1,11 +1,15 @@
 use clap::{Parser, ValueHint};
 use eyre::{Context, Result};
+use forge::package::{PackageLock, PackageSpec, Registry, PACKAGE_LOCK, PACKAGE_MANIFEST};
 use foundry_cli::{
     opts::Dependency,
     utils::{CommandUtils, Git, LoadConfig},
 };
 use foundry_common::fs;
-use foundry_config::{impl_figment_convert_basic, Config};
+use foundry_config::{
+    dependency_remappings, impl_figment_convert_basic, soldeer::RemappingsLocation, Config,
+    RemappingConflict, RemappingsUpdate,
+};
 use regex::Regex;
 use semver::Version;
 use std::{
@@ -25,7 +29,8 @@
     forge install [OPTIONS] <github username>/<github project>@<tag>...
     forge install [OPTIONS] <alias>=<github username>/<github project>@<tag>...
     forge install [OPTIONS] <https://<github token>@git url>...)]
-    forge install [OPTIONS] <https:// git url>...")]
+    forge install [OPTIONS] <https:// git url>...
+    forge install [OPTIONS] --registry <REGISTRY> <package>[@<version>]...")]
 pub struct InstallArgs {
     /// The dependencies to install.
     ///
@@ -42,8 +47,20 @@ pub struct InstallArgs {
     ///
     /// Target installation directory can be added via `<alias>=` suffix.
     /// The dependency will installed to `lib/<alias>`.
+    ///
+    /// With `--registry`, a dependency is the name of a package with an optional version,
+    /// e.g. `my-lib@1.0.0`. The version pinned in `forge-packages.lock` is installed if none is
+    /// provided, or the latest one if the package isn't pinned.
     dependencies: Vec<Dependency>,
 
+    /// Install packages published with `forge publish` from this registry, either a directory or
+    /// an HTTP(S) URL.
+    ///
+    /// Packages are installed as ordinary folders, after verifying their checksum. The checksum of
+    /// each installed version is pinned in `forge-packages.lock` and checked on later installs.
+    #[arg(long, env = "FORGE_REGISTRY", value_name = "REGISTRY", requires = "dependencies")]
+    pub registry: Option<Registry>,
+
     /// The project's root path.
     ///
     /// By default root of the Git repository, if in one,
@@ -51,6 +68,10 @@ pub struct InstallArgs {
     #[arg(long, value_hint = ValueHint::DirPath, value_name = "PATH")]
     pub root: Option<PathBuf>,
 
+    /// Preview the remappings the dependencies would add, without installing them.
+    #[arg(long)]
+    pub dry_run: bool,
+
     #[command(flatten)]
     opts: DependencyInstallOpts,
 }
@@ -60,6 +81,12 @@ pub struct InstallArgs {
 impl InstallArgs {
     pub fn run(self) -> Result<()> {
         let mut config = self.load_config()?;
+        if let Some(registry) = &self.registry {
+            return install_packages(&mut config, registry, &self.dependencies, self.dry_run)
+        }
+        if self.dry_run {
+            return self.opts.preview_remappings(&config, self.dependencies)
+        }
         self.opts.install(&mut config, self.dependencies)
     }
 }
@@ -138,6 +165,7 @@ pub fn install(self, config: &mut Config, dependencies: Vec<Dependency>) -> Resu
 
         fs::create_dir_all(&libs)?;
 
+        let mut remappings = RemappingsUpdate::new(config)?;
         let installer = Installer { git, no_commit };
         for dep in dependencies {
             let path = libs.join(dep.name());
@@ -192,14 +220,25 @@ pub fn install(self, config: &mut Config, dependencies: Vec<Dependency>) -> Resu
                 }
             }
 
+            let version = dep.tag.or(installed_tag);
             let mut msg = format!("    {} {}", "Installed".green(), dep.name);
-            if let Some(tag) = dep.tag.or(installed_tag) {
+            if let Some(tag) = &version {
                 msg.push(' ');
                 msg.push_str(tag.as_str());
             }
             sh_println!("{msg}")?;
+
+            remappings.add_dependency(
+                dependency_remappings(&config.root, &path),
+                version.as_deref(),
+                |conflict| resolve_remapping_conflict(conflict, true),
+            );
         }
 
+        // only update the remappings once all dependencies were installed
+        remappings.apply(config)?;
+        print_remappings_update(&remappings, false)?;
+
         // update `libs` in config if not included yet
         if !config.libs.iter().any(|p| p == install_lib_dir) {
             config.libs.push(install_lib_dir.to_path_buf());
@@ -207,6 +246,162 @@ pub fn install(self, config: &mut Config, dependencies: Vec<Dependency>) -> Resu
         }
         Ok(())
     }
+
+    /// Clones the dependencies into a temporary directory and prints the remappings they would
+    /// add to the project.
+    pub fn preview_remappings(self, config: &Config, dependencies: Vec<Dependency>) -> Result<()> {
+        let tmp = tempfile::tempdir()?;
+        let install_lib_dir = config.install_lib_dir();
+        let libs =
+            tmp.path().join(install_lib_dir.strip_prefix(&config.root).unwrap_or(install_lib_dir));
+        fs::create_dir_all(&libs)?;
+
+        let mut remappings = RemappingsUpdate::new(config)?;
+        let installer = Installer { git: self.git(config), no_commit: true };
+        for dep in dependencies {
+            let path = libs.join(dep.name());
+            sh_println!("Resolving {} (url: {:?}, tag: {:?})", dep.name, dep.url, dep.tag)?;
+            let installed_tag = installer.install_as_folder(&dep, &path)?;
+            remappings.add_dependency(
+                dependency_remappings(tmp.path(), &path),
+                dep.tag.as_deref().or(installed_tag.as_deref()),
+                |conflict| resolve_remapping_conflict(conflict, false),
+            );
+        }
+
+        if remappings.is_empty() {
+            sh_println!("No changes to the remappings")?;
+        }
+        print_remappings_update(&remappings, true)
+    }
+}
+
+/// Installs packages from a registry in the library directory and adds their remappings.
+///
+/// If `dry_run` is set, the packages are installed in a temporary directory and the remappings
+/// they would add are printed instead.
+fn install_packages(
+    config: &mut Config,
+    registry: &Registry,
+    dependencies: &[Dependency],
+    dry_run: bool,
+) -> Result<()> {
+    let specs = dependencies
+        .iter()
+        .map(|dep| dep.name.parse::<PackageSpec>())
+        .collect::<Result<Vec<_>>>()?;
+
+    let tmp = tempfile::tempdir()?;
+    let install_lib_dir = config.install_lib_dir().to_path_buf();
+    let root = if dry_run { tmp.path().to_path_buf() } else { config.root.clone() };
+    let libs = root.join(install_lib_dir.strip_prefix(&config.root).unwrap_or(&install_lib_dir));
+
+    // Install the pinned versions of the packages by default, and verify their checksums.
+    let lock_path = config.root.join(PACKAGE_LOCK);
+    let mut lock = PackageLock::load(&lock_path)?;
+
+    let mut remappings = RemappingsUpdate::new(config)?;
+    for mut spec in specs {
+        if spec.version.is_none() {
+            spec.version = lock.version(&spec.name).cloned();
+        }
+        sh_println!("Installing {spec} from {registry}")?;
+        let package = foundry_cli::utils::block_on(registry.fetch(&spec))?;
+        lock.pin(&package)?;
+
+        let path = libs.join(&package.name);
+        if path.exists() {
+            // never overwrite a dependency that was not installed from a registry
+            if !path.join(PACKAGE_MANIFEST).is_file() {
+                eyre::bail!(
+                    "{} already exists and was not installed from a registry",
+                    path.display()
+                );
+            }
+            fs::remove_dir_all(&path)?;
+        }
+        for (file, source) in &package.sources {
+            let file = path.join(file);
+            if let Some(parent) = file.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(file, source)?;
+        }
+        fs::write_pretty_json_file(&path.join(PACKAGE_MANIFEST), &package.manifest())?;
+
+        let version = package.version.to_string();
+        if !dry_run {
+            sh_println!(
+                "    {} {} {version} (checksum: {})",
+                "Installed".green(),
+                package.name,
+                package.checksum
+            )?;
+        }
+        remappings.add_dependency(
+            dependency_remappings(&root, &path),
+            Some(&version),
+            |conflict| resolve_remapping_conflict(conflict, !dry_run),
+        );
+    }
+
+    if dry_run {
+        if remappings.is_empty() {
+            sh_println!("No changes to the remappings")?;
+        }
+        return print_remappings_update(&remappings, true)
+    }
+
+    lock.save(&lock_path)?;
+    remappings.apply(config)?;
+    print_remappings_update(&remappings, false)?;
+
+    // update `libs` in config if not included yet
+    if !config.libs.contains(&install_lib_dir) {
+        config.libs.push(install_lib_dir);
+        config.update_libs()?;
+    }
+    Ok(())
+}
+
+/// Prints the changes to the remappings declared by the project.
+pub fn print_remappings_update(update: &RemappingsUpdate, dry_run: bool) -> Result<()> {
+    let file = match update.location {
+        RemappingsLocation::Txt => "remappings.txt",
+        RemappingsLocation::Config => "foundry.toml",
+    };
+    let (added, removed) =
+        if dry_run { ("Would add", "Would remove") } else { ("Added", "Removed") };
+    for remapping in &update.added {
+        sh_println!("    {} remapping {remapping} in {file}", added.green())?;
+    }
+    for remapping in &update.removed {
+        sh_println!("    {} remapping {remapping} in {file}", removed.red())?;
+    }
+    Ok(())
+}
+
+/// Decides whether to add the version-suffixed remapping of a conflicting remapping.
+///
+/// Asks the user if `prompt` is set and the current terminal is a tty, otherwise accepts it.
+fn resolve_remapping_conflict(conflict: &RemappingConflict, prompt: bool) -> bool {
+    let RemappingConflict { existing, remapping, suffixed } = conflict;
+    let Some(suffixed) = suffixed else {
+        let _ = sh_warn!(
+            "Skipping remapping `{remapping}`, it conflicts with `{existing}` and the version of \
+             the dependency is unknown"
+        );
+        return false
+    };
+    if prompt && std::io::stdout().is_terminal() {
+        return prompt!(
+            "Remapping `{remapping}` conflicts with `{existing}`, add `{suffixed}` instead? [Y/n] "
+        )
+        .is_ok_and(match_yn)
+    }
+    let _ =
+        sh_warn!("Remapping `{remapping}` conflicts with `{existing}`, using `{suffixed}` instead");
+    true
 }
 
 pub fn install_missing_dependencies(config: &mut Config) -> bool {
@@ -0,0 +1,439 @@
+use clap::{Parser, ValueHint};
+use eyre::{Result, WrapErr};
+use foundry_cli::utils::{FoundryPathExt, LoadConfig};
+use foundry_common::fs;
+use foundry_compilers::{compilers::solc::SolcLanguage, solc::SOLC_EXTENSIONS};
+use foundry_config::{filter::expand_globs, impl_figment_convert_basic, LintSeverity};
+use regex::Regex;
+use solang_parser::pt::{
+    CatchClause, CodeLocation, ContractDefinition, ContractPart, Expression, Identifier, Import,
+    Loc, Parameter, SourceUnit, SourceUnitPart, Statement,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    path::PathBuf,
+};
+use yansi::Paint;
+
+/// CLI arguments for `forge lint`.
+#[derive(Clone, Debug, Parser)]
+pub struct LintArgs {
+    /// Paths to files or directories to lint.
+    #[arg(value_hint = ValueHint::FilePath, value_name = "PATH", num_args(1..))]
+    paths: Vec<PathBuf>,
+
+    /// The project's root path.
+    ///
+    /// By default root of the Git repository, if in one,
+    /// or the current working directory.
+    #[arg(long, value_hint = ValueHint::DirPath, value_name = "PATH")]
+    root: Option<PathBuf>,
+
+    /// The minimum severity of the reported lints: info, low, medium or high.
+    #[arg(long, value_name = "SEVERITY")]
+    severity: Option<LintSeverity>,
+
+    /// Only run the given lints.
+    #[arg(long, value_name = "LINT", num_args(1..))]
+    only: Vec<String>,
+
+    /// Skip the given lints.
+    #[arg(long, value_name = "LINT", num_args(1..))]
+    skip: Vec<String>,
+}
+
+impl_figment_convert_basic!(LintArgs);
+
+impl LintArgs {
+    /// Lints the sources, and returns the number of reported lints.
+    pub fn run(self) -> Result<usize> {
+        let mut config = self.load_config()?;
+        if let Some(severity) = self.severity {
+            config.lint.severity = severity;
+        }
+        if !self.only.is_empty() {
+            config.lint.include_lints = self.only;
+        }
+        config.lint.exclude_lints.extend(self.skip);
+        for id in config.lint.include_lints.iter().chain(&config.lint.exclude_lints) {
+            if !LintKind::ALL.iter().any(|kind| kind.id() == id) {
+                sh_warn!("Unknown lint `{id}`")?;
+            }
+        }
+
+        // Expand ignore globs and canonicalize from the get go
+        let ignored = expand_globs(&config.root, config.lint.ignore.iter())?
+            .iter()
+            .flat_map(fs::canonicalize_path)
+            .collect::<Vec<_>>();
+
+        let mut sources = Vec::new();
+        if self.paths.is_empty() {
+            sources.extend(config.project_paths::<SolcLanguage>().input_files_iter());
+        } else {
+            for path in &self.paths {
+                if path.is_dir() {
+                    sources
+                        .extend(foundry_compilers::utils::source_files_iter(path, SOLC_EXTENSIONS));
+                } else if path.is_sol() {
+                    sources.push(path.clone());
+                } else {
+                    warn!("Cannot process path {}", path.display());
+                }
+            }
+        }
+        sources.retain(|path| {
+            fs::canonicalize_path(path).map_or(true, |path| !ignored.contains(&path))
+        });
+
+        let mut count = 0;
+        for path in sources {
+            let source = fs::read_to_string(&path)?;
+            let name = path.strip_prefix(&config.root).unwrap_or(&path).display().to_string();
+            let lints = lint_source(&source).wrap_err_with(|| format!("Failed to parse {name}"))?;
+            for lint in lints {
+                if !config.lint.is_enabled(lint.kind.id(), lint.kind.severity()) {
+                    continue
+                }
+                let (line, column) = line_column(&source, lint.offset);
+                sh_println!("{lint}\n  --> {name}:{line}:{column}\n")?;
+                count += 1;
+            }
+        }
+
+        if count > 0 {
+            sh_println!("Found {count} lint(s)")?;
+        }
+        Ok(count)
+    }
+}
+
+/// The lints checked by `forge lint`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LintKind {
+    /// An imported symbol that is never used.
+    UnusedImport,
+    /// A local variable or parameter with the same name as a state variable.
+    ShadowedVariable,
+    /// A low-level call whose success is not checked.
+    UncheckedCall,
+}
+
+impl LintKind {
+    pub const ALL: [Self; 3] = [Self::UnusedImport, Self::ShadowedVariable, Self::UncheckedCall];
+
+    /// Returns the id of the lint, used to enable or disable it.
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::UnusedImport => "unused-import",
+            Self::ShadowedVariable => "shadowed-variable",
+            Self::UncheckedCall => "unchecked-call",
+        }
+    }
+
+    /// Returns the severity of the lint.
+    pub fn severity(self) -> LintSeverity {
+        match self {
+            Self::UnusedImport => LintSeverity::Info,
+            Self::ShadowedVariable => LintSeverity::Low,
+            Self::UncheckedCall => LintSeverity::High,
+        }
+    }
+}
+
+/// A lint found in a source file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lint {
+    pub kind: LintKind,
+    /// The byte offset of the linted code in the source.
+    pub offset: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = self.kind.severity();
+        let severity = match severity {
+            LintSeverity::Info => severity.cyan(),
+            LintSeverity::Low => severity.green(),
+            LintSeverity::Medium => severity.yellow(),
+            LintSeverity::High => severity.red(),
+        };
+        write!(f, "{}[{}]: {}", severity.bold(), self.kind.id(), self.message)
+    }
+}
+
+/// Lints a Solidity source file, returning the lints in the order they were found.
+pub fn lint_source(source: &str) -> Result<Vec<Lint>> {
+    let (unit, comments) = solang_parser::parse(source, 0)
+        .map_err(|diags| eyre::eyre!("Failed to parse Solidity code: {diags:?}"))?;
+
+    let mut lints = unused_imports(source, &unit, comments.iter().map(|c| c.loc()));
+
+    let mut visitor = Visitor { lints: Vec::new(), state_vars: HashSet::new() };
+    let contracts = unit
+        .0
+        .iter()
+        .filter_map(|part| match part {
+            SourceUnitPart::ContractDefinition(contract) => Some(contract.as_ref()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let by_name = contracts
+        .iter()
+        .filter_map(|contract| Some((contract.name.as_ref()?.name.as_str(), *contract)))
+        .collect::<HashMap<_, _>>();
+    for part in &unit.0 {
+        match part {
+            SourceUnitPart::ContractDefinition(contract) => {
+                visitor.state_vars = state_variables(contract, &by_name);
+                for part in &contract.parts {
+                    if let ContractPart::FunctionDefinition(func) = part {
+                        visitor.visit_function(&func.params, &func.returns, func.body.as_ref());
+                    }
+                }
+            }
+            SourceUnitPart::FunctionDefinition(func) => {
+                visitor.state_vars = HashSet::new();
+                visitor.visit_function(&func.params, &func.returns, func.body.as_ref());
+            }
+            _ => {}
+        }
+    }
+    lints.extend(visitor.lints);
+    lints.sort_by_key(|lint| lint.offset);
+
+    Ok(lints)
+}
+
+/// Returns the lints of the symbols imported by name or alias that are never used.
+fn unused_imports(
+    source: &str,
+    unit: &SourceUnit,
+    comments: impl Iterator<Item = Loc>,
+) -> Vec<Lint> {
+    let imports = unit
+        .0
+        .iter()
+        .filter_map(|part| match part {
+            SourceUnitPart::ImportDirective(import) => Some(import),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    // Blank out the imports and comments, so that only the uses of the symbols remain.
+    let mut code = source.as_bytes().to_vec();
+    for loc in imports.iter().map(|import| import.loc()).chain(comments) {
+        if let Some(range) = code.get_mut(loc.start()..loc.end()) {
+            range.fill(b' ');
+        }
+    }
+    let code = String::from_utf8_lossy(&code);
+
+    let mut lints = Vec::new();
+    let mut check = |ident: &Identifier| {
+        let re = Regex::new(&format!(r"\b{}\b", regex::escape(&ident.name))).unwrap();
+        if !re.is_match(&code) {
+            lints.push(Lint {
+                kind: LintKind::UnusedImport,
+                offset: ident.loc.start(),
+                message: format!("`{}` is imported but never used", ident.name),
+            });
+        }
+    };
+    for import in imports {
+        match import {
+            Import::Plain(..) => {}
+            Import::GlobalSymbol(_, alias, _) => check(alias),
+            Import::Rename(_, symbols, _) => {
+                for (symbol, alias) in symbols {
+                    check(alias.as_ref().unwrap_or(symbol));
+                }
+            }
+        }
+    }
+    lints
+}
+
+/// Returns the names of the state variables of a contract, including the ones inherited from the
+/// contracts declared in the same file.
+fn state_variables(
+    contract: &ContractDefinition,
+    by_name: &HashMap<&str, &ContractDefinition>,
+) -> HashSet<String> {
+    let mut vars = HashSet::new();
+    let mut stack = vec![contract];
+    let mut visited = HashSet::new();
+    while let Some(contract) = stack.pop() {
+        if !visited.insert(std::ptr::from_ref(contract)) {
+            continue
+        }
+        for part in &contract.parts {
+            if let ContractPart::VariableDefinition(var) = part {
+                vars.extend(var.name.as_ref().map(|name| name.name.clone()));
+            }
+        }
+        for base in &contract.base {
+            if let Some(base) =
+                base.name.identifiers.last().and_then(|name| by_name.get(name.name.as_str()))
+            {
+                stack.push(base);
+            }
+        }
+    }
+    vars
+}
+
+/// Collects the lints of the function bodies.
+struct Visitor {
+    lints: Vec<Lint>,
+    /// The state variables of the contract being visited.
+    state_vars: HashSet<String>,
+}
+
+impl Visitor {
+    fn visit_function(
+        &mut self,
+        params: &[(Loc, Option<Parameter>)],
+        returns: &[(Loc, Option<Parameter>)],
+        body: Option<&Statement>,
+    ) {
+        for (_, param) in params.iter().chain(returns) {
+            self.check_declaration(param.as_ref().and_then(|param| param.name.as_ref()));
+        }
+        if let Some(body) = body {
+            self.visit_statement(body);
+        }
+    }
+
+    fn visit_statement(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::Block { statements, .. } => {
+                statements.iter().for_each(|stmt| self.visit_statement(stmt))
+            }
+            Statement::If(_, _, then, otherwise) => {
+                self.visit_statement(then);
+                if let Some(otherwise) = otherwise {
+                    self.visit_statement(otherwise);
+                }
+            }
+            Statement::While(_, _, body) | Statement::DoWhile(_, body, _) => {
+                self.visit_statement(body)
+            }
+            Statement::For(_, init, _, _, body) => {
+                init.iter().chain(body).for_each(|stmt| self.visit_statement(stmt))
+            }
+            Statement::Try(_, _, returns, clauses) => {
+                if let Some((params, body)) = returns {
+                    self.visit_function(params, &[], Some(body));
+                }
+                for clause in clauses {
+                    let (param, body) = match clause {
+                        CatchClause::Simple(_, param, body) => (param.as_ref(), body),
+                        CatchClause::Named(_, _, param, body) => (Some(param), body),
+                    };
+                    self.check_declaration(param.and_then(|param| param.name.as_ref()));
+                    self.visit_statement(body);
+                }
+            }
+            Statement::VariableDefinition(_, decl, _) => self.check_declaration(decl.name.as_ref()),
+            Statement::Expression(_, expr) => self.check_call(expr),
+            _ => {}
+        }
+    }
+
+    /// Checks whether a declaration shadows a state variable.
+    fn check_declaration(&mut self, name: Option<&Identifier>) {
+        let Some(name) = name else { return };
+        if self.state_vars.contains(&name.name) {
+            self.lints.push(Lint {
+                kind: LintKind::ShadowedVariable,
+                offset: name.loc.start(),
+                message: format!("`{}` shadows a state variable", name.name),
+            });
+        }
+    }
+
+    /// Checks whether an expression statement is a low-level call, whose result is discarded.
+    fn check_call(&mut self, expr: &Expression) {
+        let Expression::FunctionCall(_, callee, _) = expr else { return };
+        let callee = match callee.as_ref() {
+            Expression::FunctionCallBlock(_, callee, _) => callee,
+            callee => callee,
+        };
+        if let Expression::MemberAccess(_, _, member) = callee {
+            if matches!(member.name.as_str(), "call" | "delegatecall" | "staticcall" | "send") {
+                self.lints.push(Lint {
+                    kind: LintKind::UncheckedCall,
+                    offset: member.loc.start(),
+                    message: format!(
+                        "the return value of the low-level `{}` is not checked",
+                        member.name
+                    ),
+                });
+            }
+        }
+    }
+}
+
+/// Returns the 1-based line and column of a byte offset in a source.
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lint_sources() {
+        let source = r#"
+            import {A, B as C} from "./A.sol";
+            import "./D.sol" as D;
+
+            contract Base {
+                uint256 owner;
+            }
+
+            contract E is Base {
+                // B is used in a comment only
+                function f(uint256 owner) public returns (A a) {
+                    payable(msg.sender).call{value: 1}("");
+                    (bool ok, ) = msg.sender.call("");
+                    D.g();
+                }
+            }
+        "#;
+        let lints = lint_source(source).unwrap();
+        let kinds = lints.iter().map(|lint| (lint.kind, lint.message.as_str())).collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                (LintKind::UnusedImport, "`C` is imported but never used"),
+                (LintKind::ShadowedVariable, "`owner` shadows a state variable"),
+                (
+                    LintKind::UncheckedCall,
+                    "the return value of the low-level `call` is not checked"
+                ),
+            ]
+        );
+        assert_eq!(line_column(source, lints[0].offset), (2, 29));
+    }
+
+    #[test]
+    fn free_functions_have_no_state_variables() {
+        let source = r#"
+            contract A {
+                uint256 owner;
+            }
+
+            function f(uint256 owner) pure returns (uint256) {
+                return owner;
+            }
+        "#;
+        assert!(lint_source(source).unwrap().is_empty());
+    }
+}
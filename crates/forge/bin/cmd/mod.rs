@@ -5,9 +5,12 @@
 //! implement `figment::Provider` which allows the subcommand to override the config's defaults, see
 //! [`foundry_config::Config`].
 
+pub mod analyze;
+pub mod bench;
 pub mod bind;
 pub mod bind_json;
 pub mod build;
+pub mod bytecode_search;
 pub mod cache;
 pub mod clone;
 pub mod compiler;
@@ -18,11 +21,16 @@
 pub mod eip712;
 pub mod flatten;
 pub mod fmt;
+pub mod forks;
 pub mod geiger;
 pub mod generate;
 pub mod init;
 pub mod inspect;
 pub mod install;
+pub mod lint;
+pub mod nonces;
+pub mod prove;
+pub mod publish;
 pub mod remappings;
 pub mod remove;
 pub mod selectors;
@@ -0,0 +1,108 @@
+use alloy_chains::Chain;
+use alloy_primitives::Address;
+use clap::{Parser, Subcommand};
+use eyre::Result;
+use forge_script::NonceStore;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// CLI arguments for `forge nonces`.
+#[derive(Clone, Debug, Parser)]
+pub struct NoncesArgs {
+    #[command(subcommand)]
+    pub sub: NoncesSubcommands,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum NoncesSubcommands {
+    /// Lists the nonces reserved by `forge script --nonce-strategy`.
+    #[command(visible_alias = "ls")]
+    List(ListNoncesArgs),
+
+    /// Releases the nonces reserved by scripts that won't finish, for example because they were
+    /// killed.
+    ///
+    /// Scripts waiting for these nonces, or reusing them, can then proceed. Transactions that
+    /// were already sent with these nonces are not affected.
+    Release(ReleaseNoncesArgs),
+}
+
+/// CLI arguments for `forge nonces list`.
+#[derive(Clone, Debug, Parser)]
+pub struct ListNoncesArgs {
+    /// Only list the nonces of this sender.
+    #[arg(value_name = "ADDRESS")]
+    sender: Option<Address>,
+
+    /// Only list the nonces reserved on this chain.
+    #[arg(long)]
+    chain: Option<Chain>,
+}
+
+impl ListNoncesArgs {
+    pub fn run(self) -> Result<()> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let mut found = false;
+        for (chain_id, sender, store) in filter_stores(self.chain, self.sender)? {
+            for reservation in store.reservations()? {
+                let nonces = match reservation.count {
+                    Some(0) => "none".to_string(),
+                    Some(count) => {
+                        format!("{}..={}", reservation.start, reservation.start + count - 1)
+                    }
+                    None => format!("{}..", reservation.start),
+                };
+                let expires_in = reservation.expires.saturating_sub(now);
+                sh_println!(
+                    "chain {chain_id} {sender}: nonces {nonces} reserved by {} [{}], \
+                     expires in {expires_in}s",
+                    reservation.owner,
+                    reservation.id,
+                )?;
+                found = true;
+            }
+        }
+        if !found {
+            sh_println!("No reserved nonces")?;
+        }
+        Ok(())
+    }
+}
+
+/// CLI arguments for `forge nonces release`.
+#[derive(Clone, Debug, Parser)]
+pub struct ReleaseNoncesArgs {
+    /// The sender whose nonces to release.
+    #[arg(value_name = "ADDRESS")]
+    sender: Address,
+
+    /// Only release the nonces reserved on this chain.
+    #[arg(long)]
+    chain: Option<Chain>,
+
+    /// Only release the reservation with this id, as shown by `forge nonces list`.
+    #[arg(long)]
+    id: Option<String>,
+}
+
+impl ReleaseNoncesArgs {
+    pub fn run(self) -> Result<()> {
+        let mut released = 0;
+        for (_, _, store) in filter_stores(self.chain, Some(self.sender))? {
+            released += store.release(self.id.as_deref())?;
+        }
+        sh_println!("Released {released} reservation(s) of {}", self.sender)?;
+        Ok(())
+    }
+}
+
+fn filter_stores(
+    chain: Option<Chain>,
+    sender: Option<Address>,
+) -> Result<Vec<(u64, Address, NonceStore)>> {
+    let mut stores = NonceStore::all()?;
+    stores.retain(|(chain_id, address, _)| {
+        chain.is_none_or(|chain| chain.id() == *chain_id) &&
+            sender.is_none_or(|sender| sender == *address)
+    });
+    Ok(stores)
+}
@@ -0,0 +1,191 @@
+use alloy_json_abi::Function;
+use alloy_primitives::{B256, U256};
+use clap::{Parser, ValueHint};
+use eyre::{Context, Result};
+use foundry_cli::{opts::BuildOpts, utils::LoadConfig};
+use foundry_common::{
+    compile::ProjectCompiler, evm::EvmArgs, fmt::format_tokens, shell, ContractData,
+    ContractsByArtifact,
+};
+use foundry_compilers::ArtifactId;
+use foundry_evm::{
+    backend::Backend,
+    executors::{concolic::Assertion, ConcolicExecutor, ExecutorBuilder},
+    fuzz::strategies::FuzzDictionaryFile,
+    traces::TraceMode,
+};
+use std::path::PathBuf;
+
+foundry_config::impl_figment_convert!(ProveArgs, build, evm);
+
+/// CLI arguments for `forge prove`.
+///
+/// This is an experimental, bounded search: not finding a counterexample does not prove that the
+/// assertion holds.
+#[derive(Clone, Debug, Parser)]
+pub struct ProveArgs {
+    /// The name or signature of the function to call.
+    #[arg(long, value_name = "FUNCTION")]
+    function: String,
+
+    /// The assertion that must hold after every successful call, e.g. `x > 0`.
+    ///
+    /// Compares two operands with one of `==`, `!=`, `<`, `<=`, `>`, `>=`. Operands are integer
+    /// or boolean literals, or the names of the function's parameters and return values. Return
+    /// values without a name are referred to as `ret0`, `ret1`, and so on.
+    #[arg(long = "assert", value_name = "ASSERTION")]
+    assertion: Assertion,
+
+    /// The name or identifier of the contract that defines the function.
+    ///
+    /// Required if more than one contract defines the function.
+    #[arg(long, value_name = "CONTRACT")]
+    contract: Option<String>,
+
+    /// The maximum number of calls to execute.
+    #[arg(long, default_value = "1000", value_name = "RUNS")]
+    runs: u32,
+
+    /// Write the counterexample to a fuzz dictionary file at the given path, so it can be used to
+    /// seed fuzz tests through `fuzz.dictionary_files`.
+    #[arg(long, value_hint = ValueHint::FilePath, value_name = "PATH")]
+    export: Option<PathBuf>,
+
+    #[command(flatten)]
+    build: BuildOpts,
+
+    #[command(flatten)]
+    evm: EvmArgs,
+}
+
+impl ProveArgs {
+    /// Runs the search, returning `true` if a counterexample was found.
+    pub async fn run(self) -> Result<bool> {
+        let (config, evm_opts) = self.load_config_and_evm_opts()?;
+
+        let project = self.build.project()?;
+        let output = ProjectCompiler::new().quiet(shell::is_json()).compile(&project)?;
+        let contracts = ContractsByArtifact::new(
+            output.artifact_ids().map(|(id, artifact)| (id, artifact.clone().into())),
+        );
+
+        let (id, contract, func) = self.find_function(&contracts)?;
+        let code = contract.bytecode().ok_or_else(|| {
+            eyre::eyre!("{} has no bytecode or links against libraries", id.identifier())
+        })?;
+        if contract.abi.constructor.as_ref().is_some_and(|c| !c.inputs.is_empty()) {
+            eyre::bail!("{} has constructor arguments, which are not supported", id.identifier());
+        }
+
+        let env = evm_opts.evm_env().await?;
+        let fork = evm_opts.get_fork(&config, env.clone());
+        let mut executor = ExecutorBuilder::new()
+            .inspectors(|stack| {
+                stack
+                    .trace_mode(TraceMode::Debug)
+                    .odyssey(evm_opts.odyssey)
+                    .create2_deployer(evm_opts.create2_deployer)
+            })
+            .spec_id(config.evm_spec_id())
+            .gas_limit(evm_opts.gas_limit())
+            .legacy_assertions(config.legacy_assertions)
+            .build(env, Backend::spawn(fork));
+
+        executor.set_balance(evm_opts.sender, U256::MAX)?;
+        let address = executor
+            .deploy(evm_opts.sender, code.clone(), U256::ZERO, None)
+            .map_err(|err| eyre::eyre!("failed to deploy {}: {err}", id.identifier()))?
+            .address;
+
+        sh_println!(
+            "Searching for a counterexample to `{}` in {}...",
+            self.assertion,
+            func.signature()
+        )?;
+        let outcome = ConcolicExecutor::new(executor, evm_opts.sender, self.runs).search(
+            address,
+            func,
+            &self.assertion,
+        )?;
+
+        let Some(counterexample) = outcome.counterexample else {
+            sh_println!(
+                "No counterexample found after {} runs ({} reverted).",
+                outcome.runs,
+                outcome.reverts
+            )?;
+            sh_println!("The search is bounded, so this does not prove that the assertion holds.")?;
+            return Ok(false);
+        };
+
+        sh_println!("Counterexample found after {} runs:", outcome.runs)?;
+        sh_println!(
+            "  args: [{}]",
+            format_tokens(&counterexample.args).collect::<Vec<_>>().join(", ")
+        )?;
+        sh_println!(
+            "  returns: [{}]",
+            format_tokens(&counterexample.returns).collect::<Vec<_>>().join(", ")
+        )?;
+        sh_println!("  calldata: {}", counterexample.calldata)?;
+
+        if let Some(path) = &self.export {
+            let dictionary = FuzzDictionaryFile {
+                values: counterexample.calldata[4..]
+                    .chunks_exact(32)
+                    .map(B256::from_slice)
+                    .collect(),
+                ..Default::default()
+            };
+            dictionary.save(path).wrap_err("failed to export counterexample")?;
+            sh_println!("Exported counterexample to {}", path.display())?;
+        }
+
+        Ok(true)
+    }
+
+    /// Finds the function to search and the contract defining it.
+    fn find_function<'a>(
+        &self,
+        contracts: &'a ContractsByArtifact,
+    ) -> Result<(&'a ArtifactId, &'a ContractData, &'a Function)> {
+        let matches =
+            |func: &Function| func.name == self.function || func.signature() == self.function;
+
+        let candidates = if let Some(contract) = &self.contract {
+            let (id, data) = contracts
+                .find_by_name_or_identifier(contract)?
+                .ok_or_else(|| eyre::eyre!("could not find contract `{contract}`"))?;
+            vec![(id, data)]
+        } else {
+            contracts
+                .iter()
+                .filter(|(_, data)| data.bytecode().is_some())
+                .filter(|(_, data)| data.abi.functions().any(matches))
+                .collect()
+        };
+
+        let [(id, data)] = candidates[..] else {
+            if candidates.is_empty() {
+                eyre::bail!("no deployable contract defines `{}`", self.function);
+            }
+            eyre::bail!(
+                "multiple contracts define `{}`, use --contract to select one of: {}",
+                self.function,
+                candidates.iter().map(|(id, _)| id.identifier()).collect::<Vec<_>>().join(", ")
+            );
+        };
+
+        let funcs = data.abi.functions().filter(|func| matches(func)).collect::<Vec<_>>();
+        let func = match funcs[..] {
+            [func] => func,
+            [] => eyre::bail!("{} does not define `{}`", id.identifier(), self.function),
+            _ => eyre::bail!(
+                "`{}` is overloaded in {}, use the function signature instead",
+                self.function,
+                id.identifier()
+            ),
+        };
+        Ok((id, data, func))
+    }
+}
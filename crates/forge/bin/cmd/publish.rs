@@ -0,0 +1,140 @@
+use clap::Parser;
+use eyre::{Context, Result};
+use forge::package::{Package, PackageBuild, Registry, PACKAGE_METADATA_FILES};
+use foundry_cli::{opts::BuildOpts, utils::LoadConfig};
+use foundry_common::{compile::ProjectCompiler, fs, shell};
+use foundry_compilers::{
+    artifacts::Source,
+    compilers::{multi::MultiCompilerParsedSource, solc::SOLC_EXTENSIONS},
+    resolver::Graph,
+    utils::source_files_iter,
+};
+use semver::Version;
+use std::collections::{BTreeMap, BTreeSet};
+use yansi::Paint;
+
+foundry_config::impl_figment_convert!(PublishArgs, build);
+
+/// CLI arguments for `forge publish`.
+#[derive(Clone, Debug, Parser)]
+pub struct PublishArgs {
+    /// The version of the package.
+    #[arg(value_name = "VERSION")]
+    pub version: Version,
+
+    /// The registry to publish the package to, either a directory or an HTTP(S) URL.
+    #[arg(long, env = "FORGE_REGISTRY", value_name = "REGISTRY")]
+    pub registry: Registry,
+
+    /// The name of the package.
+    ///
+    /// By default the name of the project's root directory.
+    #[arg(long, value_name = "NAME")]
+    pub name: Option<String>,
+
+    /// The bearer token used to authenticate with an HTTP(S) registry.
+    #[arg(long, env = "FORGE_REGISTRY_TOKEN", value_name = "TOKEN")]
+    pub token: Option<String>,
+
+    /// Build and print the package, without publishing it.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    #[command(flatten)]
+    build: BuildOpts,
+}
+
+impl PublishArgs {
+    pub async fn run(self) -> Result<()> {
+        let config = self.load_config()?;
+        let name = match self.name {
+            Some(name) => name,
+            None => config
+                .root
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map(|name| name.to_lowercase())
+                .ok_or_else(|| eyre::eyre!("could not infer the package name, use `--name`"))?,
+        };
+
+        let files = source_files_iter(&config.src, SOLC_EXTENSIONS).collect::<Vec<_>>();
+        if files.is_empty() {
+            eyre::bail!("no Solidity sources found in {}", config.src.display());
+        }
+
+        // the package must build with the settings it is published with
+        let project = config.project()?;
+        let output = ProjectCompiler::new().quiet(true).files(files.clone()).compile(&project)?;
+        let solc = output.artifact_ids().map(|(id, _)| id.version).collect::<BTreeSet<_>>();
+
+        // Package the sources along with the files of the project they import, libraries
+        // excluded, and the metadata files of the project.
+        let graph = Graph::<MultiCompilerParsedSource>::resolve_sources(
+            &project.paths,
+            Source::read_all_files(files)?,
+        )?;
+        let mut files = graph
+            .files()
+            .keys()
+            .filter(|file| {
+                file.starts_with(&config.root) &&
+                    !config.libs.iter().any(|lib| file.starts_with(config.root.join(lib)))
+            })
+            .cloned()
+            .collect::<BTreeSet<_>>();
+        files.extend(
+            PACKAGE_METADATA_FILES
+                .iter()
+                .map(|file| config.root.join(file))
+                .filter(|file| file.is_file()),
+        );
+
+        let mut sources = BTreeMap::new();
+        for file in files {
+            let path = file
+                .strip_prefix(&config.root)
+                .wrap_err("Source directory is not relative to the project root")?;
+            // always use `/` separators, the package may be installed on another platform
+            let path = path.iter().map(|c| c.to_string_lossy()).collect::<Vec<_>>().join("/");
+            sources.insert(path, fs::read_to_string(&file)?);
+        }
+
+        let build = PackageBuild {
+            solc: solc.into_iter().collect(),
+            evm_version: config.evm_version,
+            optimizer: config.optimizer.unwrap_or_default(),
+            optimizer_runs: config.optimizer_runs,
+            via_ir: config.via_ir,
+        };
+        let package = Package::new(name, self.version, build, sources)?;
+
+        if self.dry_run {
+            if shell::is_json() {
+                sh_println!("{}", serde_json::to_string_pretty(&package.manifest())?)?;
+            } else {
+                sh_println!(
+                    "Would publish {}@{} to {}",
+                    package.name,
+                    package.version,
+                    self.registry
+                )?;
+                for path in package.sources.keys() {
+                    sh_println!("    {path}")?;
+                }
+                sh_println!("Checksum: {}", package.checksum)?;
+            }
+            return Ok(())
+        }
+
+        self.registry.publish(&package, self.token.as_deref()).await?;
+        sh_println!(
+            "{} {}@{} to {} (checksum: {})",
+            "Published".green(),
+            package.name,
+            package.version,
+            self.registry,
+            package.checksum
+        )?;
+        Ok(())
+    }
+}
@@ -1,10 +1,11 @@
+use super::install::print_remappings_update;
 use clap::{Parser, ValueHint};
 use eyre::Result;
 use foundry_cli::{
     opts::Dependency,
     utils::{Git, LoadConfig},
 };
-use foundry_config::impl_figment_convert_basic;
+use foundry_config::{impl_figment_convert_basic, RemappingsUpdate};
 use std::path::PathBuf;
 
 /// CLI arguments for `forge remove`.
@@ -24,6 +25,10 @@ pub struct RemoveArgs {
     /// Override the up-to-date check.
     #[arg(short, long)]
     force: bool,
+
+    /// Preview the remappings that would be removed, without removing the dependencies.
+    #[arg(long)]
+    dry_run: bool,
 }
 impl_figment_convert_basic!(RemoveArgs);
 
@@ -33,6 +38,18 @@ pub fn run(self) -> Result<()> {
         let (root, paths) = super::update::dependencies_paths(&self.dependencies, &config)?;
         let git_modules = root.join(".git/modules");
 
+        let mut remappings = RemappingsUpdate::new(&config)?;
+        for path in &paths {
+            remappings.remove_dependency(&root.join(path));
+        }
+
+        if self.dry_run {
+            for (Dependency { name, .. }, path) in self.dependencies.iter().zip(&paths) {
+                sh_println!("Would remove '{name}' in {}", path.display())?;
+            }
+            return print_remappings_update(&remappings, true)
+        }
+
         // remove all the dependencies by invoking `git rm` only once with all the paths
         Git::new(&root).rm(self.force, &paths)?;
 
@@ -42,6 +59,7 @@ pub fn run(self) -> Result<()> {
             std::fs::remove_dir_all(git_modules.join(path))?;
         }
 
-        Ok(())
+        remappings.apply(&config)?;
+        print_remappings_update(&remappings, false)
     }
 }
@@ -8,11 +8,23 @@
 };
 use foundry_common::{
     compile::{compile_target, ProjectCompiler},
-    selectors::{import_selectors, SelectorImportData},
+    fs,
+    selectors::{import_selectors, OpenChainClient, SelectorImportData, SelectorType},
+};
+use foundry_compilers::{
+    artifacts::output_selection::ContractOutputSelection, info::ContractInfo, Artifact,
+    ProjectCompileOutput,
 };
-use foundry_compilers::{artifacts::output_selection::ContractOutputSelection, info::ContractInfo};
 use foundry_config::Config;
-use std::fs::canonicalize;
+use foundry_evm::traces::identifier::CachedSignatures;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::canonicalize,
+    path::PathBuf,
+};
+
+/// Maximum number of selectors to resolve in a single OpenChain request.
+const DOWNLOAD_BATCH_SIZE: usize = 100;
 
 /// CLI arguments for `forge selectors`.
 #[derive(Clone, Debug, Parser)]
@@ -70,8 +82,21 @@ pub enum SelectorsSubcommands {
     },
 
     /// Cache project selectors (enables trace with local contracts functions and events).
+    ///
+    /// The cache is stored in the foundry cache directory and is consulted by all trace decoding
+    /// before querying the OpenChain signature database.
     #[command(visible_alias = "c")]
     Cache {
+        /// Upload the selectors of the project contracts to the OpenChain signature database.
+        #[arg(long)]
+        upload: bool,
+
+        /// Resolve the function selectors dispatched and the events emitted by the project
+        /// bytecode that are not defined in the project using the OpenChain signature database,
+        /// and store them in the cache for offline decoding.
+        #[arg(long)]
+        download: bool,
+
         #[command(flatten)]
         project_paths: ProjectPathOpts,
     },
@@ -80,7 +105,7 @@ pub enum SelectorsSubcommands {
 impl SelectorsSubcommands {
     pub async fn run(self) -> Result<()> {
         match self {
-            Self::Cache { project_paths } => {
+            Self::Cache { upload, download, project_paths } => {
                 sh_println!("Caching selectors for contracts in the project...")?;
                 let build_args = BuildOpts {
                     project_paths,
@@ -94,7 +119,29 @@ pub async fn run(self) -> Result<()> {
                 // compile the project to get the artifacts/abis
                 let project = build_args.project()?;
                 let outcome = ProjectCompiler::new().quiet(true).compile(&project)?;
-                cache_local_signatures(&outcome, Config::foundry_cache_dir().unwrap())?
+                let cache_path = Config::foundry_cache_dir().unwrap();
+                cache_local_signatures(&outcome, cache_path.clone())?;
+
+                if upload {
+                    let abis = outcome
+                        .artifacts()
+                        .filter_map(|(_, artifact)| artifact.abi.clone())
+                        .filter(|abi| {
+                            !abi.functions.is_empty() ||
+                                !abi.events.is_empty() ||
+                                !abi.errors.is_empty()
+                        })
+                        .collect::<Vec<_>>();
+                    if !abis.is_empty() {
+                        sh_println!("Uploading selectors to OpenChain...")?;
+                        import_selectors(SelectorImportData::Abi(abis)).await?.describe();
+                    }
+                }
+
+                if download {
+                    sh_println!("Downloading unknown selectors from OpenChain...")?;
+                    download_signatures(&outcome, cache_path).await?;
+                }
             }
             Self::Upload { contract, all, project_paths } => {
                 let build_args = BuildOpts {
@@ -377,3 +424,142 @@ pub async fn run(self) -> Result<()> {
         Ok(())
     }
 }
+
+/// Resolves the function selectors dispatched by the project bytecode and the 32-byte values it
+/// pushes that are not yet in the signatures cache using OpenChain, and merges the results into
+/// the cache file.
+///
+/// Only the selectors compared against the calldata by the function dispatchers are sent, while
+/// the 32-byte values include the topics of emitted events.
+async fn download_signatures(output: &ProjectCompileOutput, cache_path: PathBuf) -> Result<()> {
+    let mut cached_signatures = CachedSignatures::load(cache_path.clone());
+
+    let mut selectors = BTreeSet::new();
+    let mut topics = BTreeSet::new();
+    for (_, artifact) in output.artifacts() {
+        if let Some(code) = artifact.get_deployed_bytecode_bytes() {
+            let instructions = instructions(&code);
+            selectors.extend(dispatcher_selectors(&instructions).map(hex::encode_prefixed));
+        }
+        let codes = [artifact.get_bytecode_bytes(), artifact.get_deployed_bytecode_bytes()];
+        for code in codes.iter().flatten() {
+            topics.extend(
+                instructions(code)
+                    .into_iter()
+                    .filter(|(op, _)| *op == PUSH32)
+                    .map(|(_, value)| hex::encode_prefixed(value)),
+            );
+        }
+    }
+    selectors.retain(|selector| !cached_signatures.functions.contains_key(selector));
+    topics.retain(|topic| !cached_signatures.events.contains_key(topic));
+
+    let client = OpenChainClient::new()?;
+    let functions = resolve(&client, SelectorType::Function, selectors).await?;
+    let events = resolve(&client, SelectorType::Event, topics).await?;
+    sh_println!("Resolved {} selectors and {} events.", functions.len(), events.len())?;
+
+    for (selector, signature) in functions {
+        cached_signatures.functions.entry(selector).or_insert(signature);
+    }
+    for (topic, signature) in events {
+        cached_signatures.events.entry(topic).or_insert(signature);
+    }
+
+    fs::write_json_file(&cache_path.join("signatures"), &cached_signatures)?;
+    Ok(())
+}
+
+/// Resolves the given selectors in batches, returning the first signature found for each.
+async fn resolve(
+    client: &OpenChainClient,
+    selector_type: SelectorType,
+    selectors: BTreeSet<String>,
+) -> Result<BTreeMap<String, String>> {
+    let selectors = selectors.into_iter().collect::<Vec<_>>();
+    let mut resolved = BTreeMap::new();
+    for chunk in selectors.chunks(DOWNLOAD_BATCH_SIZE) {
+        let decoded = client.decode_selectors(selector_type, chunk.iter().cloned()).await?;
+        for (selector, signatures) in chunk.iter().zip(decoded) {
+            if let Some(signature) = signatures.and_then(|s| s.into_iter().next()) {
+                resolved.insert(selector.clone(), signature);
+            }
+        }
+    }
+    Ok(resolved)
+}
+
+const PUSH1: u8 = 0x60;
+const PUSH4: u8 = 0x63;
+const PUSH32: u8 = 0x7f;
+const DUP2: u8 = 0x81;
+const EQ: u8 = 0x14;
+
+/// Returns the opcodes of the given bytecode with their immediate values, which are empty for
+/// the opcodes other than `PUSH1`-`PUSH32`. A truncated push at the end is skipped.
+fn instructions(code: &[u8]) -> Vec<(u8, &[u8])> {
+    let mut instructions = Vec::new();
+    let mut pc = 0;
+    while pc < code.len() {
+        let op = code[pc];
+        pc += 1;
+        let len = if (PUSH1..=PUSH32).contains(&op) { (op - PUSH1 + 1) as usize } else { 0 };
+        if pc + len > code.len() {
+            break
+        }
+        instructions.push((op, &code[pc..pc + len]));
+        pc += len;
+    }
+    instructions
+}
+
+/// Returns the selectors that the function dispatcher compares against the calldata, i.e. the
+/// `PUSH4` values followed by `EQ`, or by `DUP2 EQ` when compiled with the IR pipeline.
+fn dispatcher_selectors<'a>(
+    instructions: &'a [(u8, &'a [u8])],
+) -> impl Iterator<Item = &'a [u8]> + 'a {
+    instructions.iter().enumerate().filter_map(|(i, &(op, value))| {
+        let is_compared = matches!(instructions[i + 1..], [(EQ, _), ..] | [(DUP2, _), (EQ, _), ..]);
+        (op == PUSH4 && is_compared).then_some(value)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_instructions() {
+        // PUSH4 0x12345678 PUSH1 0x63 PUSH32 0x11..11 EQ PUSH4 (truncated)
+        let mut code = hex::decode("631234567860637f").unwrap();
+        code.extend([0x11; 32]);
+        code.extend([0x14, 0x63, 0xaa]);
+        assert_eq!(
+            instructions(&code),
+            vec![
+                (PUSH4, &[0x12, 0x34, 0x56, 0x78][..]),
+                (PUSH1, &[0x63][..]),
+                (PUSH32, &[0x11; 32][..]),
+                (EQ, &[][..]),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_dispatcher_selectors() {
+        let code = hex::decode(concat!(
+            // Legacy dispatcher: DUP1 PUSH4 0xa9059cbb EQ PUSH2 0x0040 JUMPI
+            "8063a9059cbb1461004057",
+            // IR dispatcher: PUSH4 0x70a08231 DUP2 EQ PUSH2 0x0050 JUMPI
+            "6370a08231811461005057",
+            // Custom error: PUSH4 0x08c379a0 PUSH1 0xe0 SHL
+            "6308c379a060e01b",
+            // External call: PUSH4 0x23b872dd PUSH1 0xe0 SHL DUP2 MSTORE
+            "6323b872dd60e01b8152",
+        ))
+        .unwrap();
+        let instructions = instructions(&code);
+        let selectors = dispatcher_selectors(&instructions).map(hex::encode).collect::<Vec<_>>();
+        assert_eq!(selectors, ["a9059cbb", "70a08231"]);
+    }
+}
@@ -93,7 +93,7 @@ pub(crate) fn watchexec_config(&self) -> Result<watchexec::Config> {
 
     pub async fn run(mut self) -> Result<()> {
         // Set fuzz seed so gas snapshots are deterministic
-        self.test.fuzz_seed = Some(U256::from_be_bytes(STATIC_FUZZ_SEED));
+        self.test.seed = Some(U256::from_be_bytes(STATIC_FUZZ_SEED));
 
         let outcome = self.test.execute_tests().await?;
         outcome.ensure_ok(false)?;
@@ -0,0 +1,137 @@
+//! Debugging artifacts written for failed tests.
+
+use eyre::Result;
+use forge::{
+    decode::decode_console_logs,
+    result::TestResult,
+    traces::{
+        debug::ContractSources, decode_trace_arena, CallTraceDecoder, CallTraceNode,
+        SparsedTraceArena, TraceKind,
+    },
+};
+use foundry_common::fs;
+use foundry_config::DebuggerConfig;
+use foundry_debugger::Debugger;
+use revm_inspectors::{tracing::TraceWriter, ColorChoice};
+use serde::Serialize;
+use std::{
+    fmt::Write,
+    path::{Component, Path, PathBuf},
+};
+
+/// The name of the directory, relative to the project's `out` directory, that the artifacts are
+/// written to.
+pub const FAILURE_ARTIFACTS_DIR: &str = "test-artifacts";
+
+/// Writes a bundle of artifacts for each failed test to `<root>/<file>/<contract>/<signature>/`, so
+/// failures can be inspected without running the test again:
+/// - `trace.txt`: the decoded setup and execution traces
+/// - `logs.txt`: the decoded console logs
+/// - `gas.json`: the gas used by every call of the traces
+/// - `debugger.json`: the debugger dump of the execution, as written by `forge test --dump`
+pub struct FailureArtifacts {
+    root: PathBuf,
+    sources: ContractSources,
+    debugger: DebuggerConfig,
+}
+
+/// The gas used by a single call.
+#[derive(Serialize)]
+struct CallGas {
+    kind: &'static str,
+    depth: usize,
+    contract: Option<String>,
+    function: String,
+    gas_used: u64,
+    success: bool,
+}
+
+impl FailureArtifacts {
+    /// Creates a new writer, removing the artifacts of previous runs.
+    pub fn new(root: PathBuf, sources: ContractSources, debugger: DebuggerConfig) -> Result<Self> {
+        if root.exists() {
+            fs::remove_dir_all(&root)?;
+        }
+        Ok(Self { root, sources, debugger })
+    }
+
+    /// Writes the artifacts of a failed test, returning the directory they were written to.
+    pub async fn write(
+        &self,
+        suite_name: &str,
+        test_name: &str,
+        result: &TestResult,
+        decoder: &CallTraceDecoder,
+    ) -> Result<PathBuf> {
+        // Key by the full suite identifier and signature, so that contracts with the same name in
+        // different files and overloaded tests don't overwrite each other.
+        let (file, contract) = suite_name.rsplit_once(':').unwrap_or(("", suite_name));
+        let file = Path::new(file)
+            .components()
+            .filter(|c| matches!(c, Component::Normal(_)))
+            .collect::<PathBuf>();
+        let dir = self.root.join(file).join(contract).join(test_name);
+        fs::create_dir_all(&dir)?;
+
+        let mut trace = String::new();
+        let mut gas = Vec::new();
+        for (kind, arena) in &result.traces {
+            let label = match kind {
+                TraceKind::Deployment => continue,
+                TraceKind::Setup => "setup",
+                TraceKind::Execution => "execution",
+            };
+            let mut arena = arena.clone();
+            decode_trace_arena(&mut arena, decoder).await?;
+            writeln!(trace, "{label}:\n{}", render_plain(&arena))?;
+            gas.extend(arena.arena.nodes().iter().map(|node| CallGas::new(label, node)));
+        }
+        fs::write(dir.join("trace.txt"), trace)?;
+        fs::write(dir.join("logs.txt"), decode_console_logs(&result.logs).join("\n"))?;
+        fs::write_pretty_json_file(&dir.join("gas.json"), &gas)?;
+
+        let traces =
+            result.traces.iter().filter(|(kind, _)| kind.is_execution()).cloned().collect();
+        let mut debugger = Debugger::builder()
+            .traces(traces)
+            .sources(self.sources.clone())
+            .breakpoints(result.breakpoints.clone())
+            .decoder(decoder)
+            .config(self.debugger.clone())
+            .build();
+        // The dump is only available if execution steps were recorded.
+        let _ = debugger.dump_to_file(&dir.join("debugger.json"));
+
+        Ok(dir)
+    }
+}
+
+impl CallGas {
+    fn new(kind: &'static str, node: &CallTraceNode) -> Self {
+        let decoded = &node.trace.decoded;
+        let function = if node.trace.kind.is_any_create() {
+            "constructor".to_string()
+        } else if let Some(call_data) = &decoded.call_data {
+            call_data.signature.clone()
+        } else {
+            node.selector().map_or_else(|| "fallback".to_string(), |selector| selector.to_string())
+        };
+        Self {
+            kind,
+            depth: node.trace.depth,
+            contract: decoded.label.clone(),
+            function,
+            gas_used: node.trace.gas_used,
+            success: node.trace.success,
+        }
+    }
+}
+
+/// Renders a trace arena without colors.
+fn render_plain(arena: &SparsedTraceArena) -> String {
+    let mut w = TraceWriter::new(Vec::<u8>::new()).use_colors(ColorChoice::Never);
+    if w.write_arena(&arena.resolve_arena()).is_err() {
+        return String::new();
+    }
+    String::from_utf8_lossy(&w.into_writer()).into_owned()
+}
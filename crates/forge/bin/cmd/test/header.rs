@@ -0,0 +1,58 @@
+use alloy_primitives::{keccak256, B256, U256};
+use eyre::Result;
+use foundry_compilers::ProjectCompileOutput;
+use foundry_config::Config;
+use serde::Serialize;
+use std::{collections::BTreeSet, fmt};
+
+/// The parameters a test run depends on, reported before running the tests so that the run can
+/// be reproduced from its output.
+#[derive(Clone, Debug, Serialize)]
+pub struct TestRunHeader {
+    /// The seed of the fuzz and invariant runs, and of the randomness cheatcodes, if set.
+    pub seed: Option<U256>,
+    /// The number of threads the test suites are run on.
+    pub threads: usize,
+    /// The compiler versions of the compiled contracts.
+    pub solc_versions: BTreeSet<String>,
+    /// The hash of the configuration, excluding the seed and the project root.
+    pub config_hash: B256,
+}
+
+impl TestRunHeader {
+    pub fn new(config: &Config, output: &ProjectCompileOutput) -> Result<Self> {
+        let seed = config.fuzz.seed;
+        let solc_versions = output.artifact_ids().map(|(id, _)| id.version.to_string()).collect();
+
+        // The seed is reported separately, and the root differs between machines.
+        let mut config = config.clone();
+        config.fuzz.seed = None;
+        let config = config.to_string_pretty()?.replace(&config.root.display().to_string(), ".");
+
+        Ok(Self {
+            seed,
+            threads: rayon::current_num_threads(),
+            solc_versions,
+            config_hash: keccak256(config),
+        })
+    }
+}
+
+impl fmt::Display for TestRunHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let solc = if self.solc_versions.is_empty() {
+            "-".to_string()
+        } else {
+            self.solc_versions.iter().cloned().collect::<Vec<_>>().join(", ")
+        };
+        let seed = match self.seed {
+            Some(seed) => format!("{seed:#x}"),
+            None => "random".to_string(),
+        };
+        write!(
+            f,
+            "Test run: seed {seed}, {} threads, solc {solc}, config {}",
+            self.threads, self.config_hash
+        )
+    }
+}
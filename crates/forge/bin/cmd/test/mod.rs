@@ -1,18 +1,18 @@
 use super::{install, test::filter::ProjectPathsAwareFilter, watch::WatchArgs};
 use alloy_primitives::U256;
-use chrono::Utc;
 use clap::{Parser, ValueHint};
 use eyre::{Context, OptionExt, Result};
 use forge::{
     decode::decode_console_logs,
     gas_report::GasReport,
-    multi_runner::matches_contract,
-    result::{SuiteResult, TestOutcome, TestStatus},
+    multi_runner::{matches_bench_contract, matches_contract},
+    result::{SuiteResult, TestOutcome, TestResult, TestStatus},
+    shard::{TestShard, TestShardOutput},
     traces::{
         debug::{ContractSources, DebugTraceIdentifier},
         decode_trace_arena, folded_stack_trace,
         identifier::SignaturesIdentifier,
-        CallTraceDecoderBuilder, InternalTraceMode, TraceKind,
+        CallTraceDecoderBuilder, InternalTraceMode, TraceExportFormat, TraceKind,
     },
     MultiContractRunner, MultiContractRunnerBuilder, TestFilter,
 };
@@ -37,25 +37,33 @@
         Metadata, Profile, Provider,
     },
     filter::GlobMatcher,
-    Config,
+    Config, SolcReq,
+};
+use foundry_debugger::{ContractDebugInfo, Debugger, DumpFormat};
+use foundry_evm::{
+    fork::{ForkCallCache, ForkPins, FORK_CALL_CACHE_FILE, FORK_PINS_FILE},
+    fuzz::strategies::FuzzDictionaryFile,
+    traces::identifier::TraceIdentifiers,
 };
-use foundry_debugger::Debugger;
-use foundry_evm::traces::identifier::TraceIdentifiers;
 use regex::Regex;
+use semver::Version;
 use std::{
     collections::{BTreeMap, BTreeSet},
-    fmt::Write,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{mpsc::channel, Arc},
-    time::{Duration, Instant},
+    time::Instant,
 };
 use yansi::Paint;
 
+mod artifacts;
 mod filter;
+mod header;
+mod report;
 mod summary;
+use artifacts::{FailureArtifacts, FAILURE_ARTIFACTS_DIR};
 pub use filter::FilterArgs;
 use forge::{result::TestKind, traces::render_trace_arena_inner};
-use quick_junit::{NonSuccessKind, Report, TestCase, TestCaseStatus, TestSuite};
+pub use header::TestRunHeader;
 use summary::{format_invariant_metrics_table, TestSummaryReport};
 
 // Loads project's figment and merges the build cli arguments into it
@@ -96,6 +104,14 @@ pub struct TestArgs {
     #[arg(long, conflicts_with = "flamegraph")]
     flamechart: bool,
 
+    /// Export the setup and execution traces of every test to
+    /// `<cache>/traces/<contract>/<test>.<format>.json`.
+    ///
+    /// `otlp` writes OpenTelemetry spans in the OTLP/JSON encoding, `speedscope` writes profiles
+    /// that can be opened in https://www.speedscope.app. Gas is used as the duration of calls.
+    #[arg(long, value_name = "FORMAT")]
+    export_traces: Option<TraceExportFormat>,
+
     /// Identify internal functions in traces.
     ///
     /// This will trace internal functions and decode stack parameters.
@@ -114,6 +130,18 @@ pub struct TestArgs {
     )]
     dump: Option<PathBuf>,
 
+    /// The format of the debugger dump: `debugger` for the debugger data, or `json` or `csv`
+    /// for the executed steps, with their stack, memory and storage writes, and source locations.
+    #[arg(long, requires = "dump", value_name = "FORMAT", default_value_t)]
+    dump_format: DumpFormat,
+
+    /// Write the traces, console logs, call gas usage and debugger dump of every failed test to
+    /// `<out>/test-artifacts/<contract>/<test>/`.
+    ///
+    /// This records every execution step for the debugger dump, which slows down the test run.
+    #[arg(long, env = "FORGE_FAILURE_ARTIFACTS")]
+    failure_artifacts: bool,
+
     /// Print a gas report.
     #[arg(long, env = "FORGE_GAS_REPORT")]
     gas_report: bool,
@@ -127,10 +155,26 @@ pub struct TestArgs {
     allow_failure: bool,
 
     /// Output test results as JUnit XML report.
-    #[arg(long, conflicts_with_all = ["quiet", "json", "gas_report", "summary", "list", "show_progress"], help_heading = "Display options")]
-    pub junit: bool,
+    ///
+    /// If a path is given, the report is written to it in addition to the other outputs.
+    /// Otherwise, it is printed instead of the test results.
+    #[arg(
+        long,
+        num_args = 0..=1,
+        value_hint = ValueHint::FilePath,
+        value_name = "PATH",
+        help_heading = "Display options"
+    )]
+    pub junit: Option<Option<PathBuf>>,
+
+    /// Print a GitHub Actions annotation for each failed test, at the line it failed at.
+    #[arg(long, conflicts_with_all = ["json", "list"], help_heading = "Display options")]
+    pub github_annotations: bool,
 
     /// Stop running tests after the first failure.
+    ///
+    /// Running fuzz and invariant tests stop after their current case, and they are reported as
+    /// skipped along with the tests that were not run.
     #[arg(long)]
     pub fail_fast: bool,
 
@@ -138,13 +182,42 @@ pub struct TestArgs {
     #[arg(long, env = "ETHERSCAN_API_KEY", value_name = "KEY")]
     etherscan_api_key: Option<String>,
 
+    /// Run only the test contracts of the given shard, in the form `index/count`, e.g. `2/8`.
+    ///
+    /// Test contracts are assigned to shards by a hash of their identifier, so every machine
+    /// running the same shard runs the same contracts.
+    #[arg(long, value_name = "INDEX/COUNT")]
+    pub shard: Option<TestShard>,
+
+    /// Write the results of the shard, and its gas report with `--gas-report`, to the given file.
+    ///
+    /// The outputs of all the shards can then be merged with `--merge-shards`.
+    #[arg(long, requires = "shard", value_hint = ValueHint::FilePath, value_name = "PATH")]
+    pub shard_output: Option<PathBuf>,
+
+    /// Merge the outputs of all the shards of a test run, written with `--shard-output`, instead
+    /// of running tests.
+    ///
+    /// The merged results are reported as a single run, including the gas report with
+    /// `--gas-report` and the summary with `--summary`.
+    #[arg(
+        long,
+        num_args = 1..,
+        value_hint = ValueHint::FilePath,
+        value_name = "PATHS",
+        conflicts_with_all = ["shard", "list", "debug", "flamegraph", "flamechart"]
+    )]
+    pub merge_shards: Vec<PathBuf>,
+
     /// List tests instead of running them.
     #[arg(long, short, conflicts_with_all = ["show_progress", "decode_internal", "summary"], help_heading = "Display options")]
     list: bool,
 
-    /// Set seed used to generate randomness during your fuzz runs.
-    #[arg(long)]
-    pub fuzz_seed: Option<U256>,
+    /// Set the seed of the fuzz and invariant runs, and of the randomness cheatcodes.
+    ///
+    /// If not set, each test uses a random seed.
+    #[arg(long, visible_alias = "fuzz-seed")]
+    pub seed: Option<U256>,
 
     #[arg(long, env = "FOUNDRY_FUZZ_RUNS", value_name = "RUNS")]
     pub fuzz_runs: Option<u64>,
@@ -157,15 +230,59 @@ pub struct TestArgs {
     #[arg(long)]
     pub fuzz_input_file: Option<String>,
 
+    /// Fail tests whose Solidity contracts corrupt the reserved memory slots or read memory that
+    /// was never written.
+    ///
+    /// Catches inline assembly that doesn't follow the memory model of Solidity, at the cost of
+    /// slower execution.
+    #[arg(long)]
+    pub memory_safety: bool,
+
+    /// Report the peak bytes of memory and stack used by the interpreter in each unit test.
+    #[arg(long, help_heading = "Display options")]
+    pub memory_report: bool,
+
+    /// Fail tests whose call frames use more than this many bytes of memory and stack in total.
+    ///
+    /// Unlike the EVM `memory_limit`, the failure reason names the call frame and the opcode that
+    /// exceeded the limit.
+    #[arg(long, value_name = "BYTES")]
+    pub test_memory_limit: Option<u64>,
+
     /// Show test execution progress.
     #[arg(long, conflicts_with_all = ["quiet", "json"], help_heading = "Display options")]
     pub show_progress: bool,
 
+    /// Print the header of the run as a separate JSON object before the results, with `--json`.
+    #[arg(long, help_heading = "Display options")]
+    pub json_header: bool,
+
     /// Re-run recorded test failures from last run.
     /// If no failure recorded then regular test run is performed.
     #[arg(long)]
     pub rerun: bool,
 
+    /// Run forks of `latest` at the block numbers recorded by a previous run.
+    ///
+    /// Block numbers used by forks of `latest` are recorded to `<cache>/fork-pins.toml`. Use
+    /// `forge forks update` to refresh them.
+    #[arg(long)]
+    pub pin_forks: bool,
+
+    /// Reuse the results of static calls made against forks across the tests of this run.
+    ///
+    /// A result is only reused while the contracts that produced it are unmodified compared to
+    /// the fork, so calls to contracts altered by a test are still executed.
+    #[arg(long)]
+    pub cache_fork_calls: bool,
+
+    /// Persist the cached results of fork calls to `<cache>/fork-calls.json`, and reuse them in
+    /// later runs.
+    ///
+    /// Best combined with `--pin-forks`, as results are only reused for the same fork block.
+    #[arg(long, requires = "cache_fork_calls")]
+    pub persist_fork_calls: bool,
+
     /// Print test summary table.
     #[arg(long, help_heading = "Display options")]
     pub summary: bool,
@@ -174,6 +291,10 @@ pub struct TestArgs {
     #[arg(long, help_heading = "Display options", requires = "summary")]
     pub detailed: bool,
 
+    /// The number of times to run each benchmark. If set, only benchmarks are run.
+    #[arg(skip)]
+    pub bench_runs: Option<u32>,
+
     #[command(flatten)]
     filter: FilterArgs,
 
@@ -187,12 +308,128 @@ pub struct TestArgs {
     pub watch: WatchArgs,
 }
 
+/// The output selection of the ethdebug information of the creation and runtime bytecode.
+const ETHDEBUG_OUTPUT_SELECTION: [&str; 2] =
+    ["evm.bytecode.ethdebug", "evm.deployedBytecode.ethdebug"];
+
+/// Returns whether solc can emit the ethdebug output with the project settings, which requires
+/// the IR pipeline and solc 0.8.29 or newer.
+fn emits_ethdebug(config: &Config) -> bool {
+    let min_version = Version::new(0, 8, 29);
+    config.via_ir &&
+        matches!(&config.solc, Some(SolcReq::Version(version)) if *version >= min_version)
+}
+
 impl TestArgs {
     pub async fn run(self) -> Result<TestOutcome> {
         trace!(target: "forge::test", "executing test command");
+        if self.junit_stdout() &&
+            (shell::is_json() ||
+                shell::is_quiet() ||
+                self.gas_report ||
+                self.summary ||
+                self.list ||
+                self.show_progress ||
+                self.github_annotations)
+        {
+            eyre::bail!(
+                "`--junit` without a path prints the report instead of the test results, and can't \
+                 be combined with other outputs; pass the path to write the report to instead"
+            );
+        }
+        if !self.merge_shards.is_empty() {
+            return self.merge_shard_outputs();
+        }
         self.execute_tests().await
     }
 
+    /// Merges the outputs of the shards of a test run, and reports them as a single run.
+    fn merge_shard_outputs(&self) -> Result<TestOutcome> {
+        let config = self.load_config()?;
+        let outputs = self
+            .merge_shards
+            .iter()
+            .map(|path| {
+                fs::read_json_file::<TestShardOutput>(path)
+                    .wrap_err_with(|| format!("failed to read shard output {}", path.display()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let duration = outputs.iter().map(|output| output.duration).max().unwrap_or_default();
+
+        let gas_report = self.gas_report.then(|| {
+            GasReport::new(
+                config.gas_reports.clone(),
+                config.gas_reports_ignore.clone(),
+                config.gas_reports_include_tests,
+            )
+        });
+        let (results, gas_report) = TestShardOutput::merge(outputs, gas_report)?;
+        let mut outcome = TestOutcome::new(results, self.allow_failure);
+
+        if !self.gas_report && !self.summary && shell::is_json() {
+            sh_println!("{}", serde_json::to_string(&outcome.results)?)?;
+            return Ok(outcome);
+        }
+
+        if self.junit_stdout() {
+            let report = report::junit_xml_report(&outcome.results, shell::verbosity());
+            sh_println!("{}", report.to_string()?)?;
+            return Ok(outcome);
+        }
+
+        if let Some(gas_report) = gas_report {
+            sh_println!("{gas_report}")?;
+            outcome.gas_report = Some(gas_report);
+        }
+
+        if !self.summary && !shell::is_json() {
+            sh_println!("{}", outcome.summary(duration))?;
+        }
+
+        if self.summary && !outcome.results.is_empty() {
+            let summary_report = TestSummaryReport::new(self.detailed, outcome.clone());
+            sh_println!("{}", &summary_report)?;
+        }
+
+        // The traces of the shards don't record the steps the failures are located with.
+        self.report_to_ci(&config, &outcome, shell::verbosity(), None)?;
+
+        Ok(outcome)
+    }
+
+    /// Returns whether the JUnit report is printed instead of the test results.
+    pub fn junit_stdout(&self) -> bool {
+        matches!(self.junit, Some(None))
+    }
+
+    /// Writes the JUnit report to its file, and prints the GitHub annotations of the failed tests,
+    /// if requested.
+    ///
+    /// The failures are annotated at their location in the `sources`, if any.
+    fn report_to_ci(
+        &self,
+        config: &Config,
+        outcome: &TestOutcome,
+        verbosity: u8,
+        sources: Option<&ContractSources>,
+    ) -> Result<()> {
+        if let Some(Some(path)) = &self.junit {
+            let report = report::junit_xml_report(&outcome.results, verbosity);
+            fs::write(path, report.to_string()?)?;
+        }
+        if self.github_annotations {
+            let libs = config
+                .libs
+                .iter()
+                .map(|lib| lib.strip_prefix(&config.root).unwrap_or(lib).to_path_buf())
+                .collect::<Vec<_>>();
+            for annotation in report::github_annotations(sources, &libs, &outcome.results) {
+                sh_println!("{annotation}")?;
+            }
+        }
+        Ok(())
+    }
+
     /// Returns sources which include any tests to be executed.
     /// If no filters are provided, sources are filtered by existence of test/invariant methods in
     /// them, If filters are provided, sources are additionally filtered by them.
@@ -222,12 +459,23 @@ pub fn get_sources_to_compile(
         // Filter sources by their abis and contract names.
         let mut test_sources = abis
             .iter()
-            .filter(|(id, abi)| matches_contract(id, abi, filter))
+            .filter(|(id, abi)| {
+                if self.bench_runs.is_some() {
+                    matches_bench_contract(id, abi, filter)
+                } else {
+                    matches_contract(id, abi, filter)
+                }
+            })
             .map(|(id, _)| id.source.clone())
             .collect::<BTreeSet<_>>();
 
         if test_sources.is_empty() {
-            if filter.is_empty() {
+            if filter.is_empty() && self.bench_runs.is_some() {
+                sh_println!(
+                    "No benchmarks found in project! \
+                        Forge looks for functions that starts with `bench_`."
+                )?;
+            } else if filter.is_empty() {
                 sh_println!(
                     "No tests found in project! \
                         Forge looks for functions that starts with `test`."
@@ -283,6 +531,30 @@ pub async fn execute_tests(mut self) -> Result<TestOutcome> {
             config.invariant.gas_report_samples = 0;
         }
 
+        // Record the blocks that forks of `latest` resolve to, and reuse them if pinning.
+        let fork_pins_path = config.cache_path.join(FORK_PINS_FILE);
+        if config.cache || self.pin_forks {
+            evm_opts.fork_pins = Some(ForkPins::load(&fork_pins_path, self.pin_forks)?);
+        }
+        let fork_pins = evm_opts.fork_pins.clone();
+
+        // Share the results of static fork calls between tests, and optionally between runs.
+        let fork_calls_path = config.cache_path.join(FORK_CALL_CACHE_FILE);
+        if self.cache_fork_calls {
+            evm_opts.fork_call_cache = Some(if self.persist_fork_calls {
+                ForkCallCache::load(&fork_calls_path)?
+            } else {
+                ForkCallCache::new()
+            });
+        }
+        let fork_call_cache = evm_opts.fork_call_cache.clone();
+
+        // Ensure the fuzz dictionaries to import are valid before running any test.
+        for path in &config.fuzz.dictionary_files {
+            FuzzDictionaryFile::load(path)
+                .wrap_err_with(|| format!("failed to import fuzz dictionary {}", path.display()))?;
+        }
+
         // Install missing dependencies.
         if install::install_missing_dependencies(&mut config) && config.auto_detect_remappings {
             // need to re-configure here to also catch additional remappings
@@ -290,17 +562,28 @@ pub async fn execute_tests(mut self) -> Result<TestOutcome> {
         }
 
         // Set up the project.
-        let project = config.project()?;
+        let mut project = config.project()?;
+        if self.debug && emits_ethdebug(&config) {
+            // Request the ethdebug output used to name stack values in the debugger.
+            project.build_info = true;
+            for outputs in project.settings.solc.settings.output_selection.0.values_mut() {
+                for selection in outputs.values_mut() {
+                    selection.extend(ETHDEBUG_OUTPUT_SELECTION.map(String::from));
+                }
+            }
+        }
 
         let filter = self.filter(&config);
         trace!(target: "forge::test", ?filter, "using filter");
 
         let sources_to_compile = self.get_sources_to_compile(&config, &filter)?;
 
-        let compiler =
-            ProjectCompiler::new().quiet(shell::is_json() || self.junit).files(sources_to_compile);
+        let compiler = ProjectCompiler::new()
+            .quiet(shell::is_json() || self.junit_stdout())
+            .files(sources_to_compile);
 
         let output = compiler.compile(&project)?;
+        let header = TestRunHeader::new(&config, &output)?;
 
         // Create test options from general project settings and compiler output.
         let project_root = &project.paths.root;
@@ -310,7 +593,10 @@ pub async fn execute_tests(mut self) -> Result<TestOutcome> {
 
         // Determine print verbosity and executor verbosity.
         let verbosity = evm_opts.verbosity;
-        if (self.gas_report && evm_opts.verbosity < 3) || self.flamegraph || self.flamechart {
+        if ((self.gas_report || self.export_traces.is_some()) && evm_opts.verbosity < 3) ||
+            self.flamegraph ||
+            self.flamechart
+        {
             evm_opts.verbosity = 3;
         }
 
@@ -322,7 +608,9 @@ pub async fn execute_tests(mut self) -> Result<TestOutcome> {
         }
 
         // Choose the internal function tracing mode, if --decode-internal is provided.
-        let decode_internal = if self.decode_internal {
+        // The steps recorded by the internal tracing also locate the failures of the GitHub
+        // annotations.
+        let decode_internal = if self.decode_internal || self.github_annotations {
             // If more than one function matched, we enable simple tracing.
             // If only one function matched, we enable full tracing. This is done in `run_tests`.
             InternalTraceMode::Simple
@@ -333,7 +621,7 @@ pub async fn execute_tests(mut self) -> Result<TestOutcome> {
         // Prepare the test builder.
         let config = Arc::new(config);
         let runner = MultiContractRunnerBuilder::new(config.clone())
-            .set_debug(should_debug)
+            .set_debug(should_debug || self.failure_artifacts)
             .set_decode_internal(decode_internal)
             .initial_balance(evm_opts.initial_balance)
             .evm_spec(config.evm_spec_id())
@@ -341,10 +629,54 @@ pub async fn execute_tests(mut self) -> Result<TestOutcome> {
             .with_fork(evm_opts.get_fork(&config, env.clone()))
             .enable_isolation(evm_opts.isolate)
             .odyssey(evm_opts.odyssey)
+            .bench_runs(self.bench_runs)
+            .fail_fast(self.fail_fast)
+            .shard(self.shard)
             .build::<MultiCompiler>(project_root, &output, env, evm_opts)?;
 
         let libraries = runner.libraries.clone();
-        let mut outcome = self.run_tests(runner, config, verbosity, &filter, &output).await?;
+        let timer = Instant::now();
+        let mut outcome = self
+            .run_tests(runner, config.clone(), verbosity, &filter, &output, Some(&header))
+            .await?;
+        // The failures are annotated at the steps recorded by the tracer.
+        let annotation_sources = if self.github_annotations {
+            Some(ContractSources::from_project_output(&output, &config.root, Some(&libraries))?)
+        } else {
+            None
+        };
+        self.report_to_ci(&config, &outcome, verbosity, annotation_sources.as_ref())?;
+
+        if let (Some(shard), Some(path)) = (self.shard, &self.shard_output) {
+            let shard_output = TestShardOutput {
+                shard,
+                results: outcome.results.clone(),
+                gas_report: outcome.gas_report.clone(),
+                duration: timer.elapsed(),
+            };
+            fs::write_json_file(path, &shard_output)?;
+        }
+
+        if let Some(pins) = fork_pins {
+            if !pins.pins().pins.is_empty() {
+                pins.save(&fork_pins_path)?;
+            }
+        }
+
+        if let Some(cache) = fork_call_cache {
+            if self.persist_fork_calls {
+                cache.save(&fork_calls_path)?;
+            }
+            if !shell::is_json() && !self.junit_stdout() {
+                let stats = cache.stats();
+                sh_println!(
+                    "Fork call cache: reused {} of {} static fork calls, {} results cached",
+                    stats.hits,
+                    stats.hits + stats.misses,
+                    stats.entries
+                )?;
+            }
+        }
 
         if should_draw {
             let (suite_name, test_name, mut test_result) =
@@ -401,15 +733,28 @@ pub async fn execute_tests(mut self) -> Result<TestOutcome> {
                     test_result.traces.iter().filter(|(t, _)| t.is_execution()).cloned().collect(),
                 )
                 .sources(sources)
-                .breakpoints(test_result.breakpoints.clone());
+                .storage_layouts(
+                    output.artifact_ids().filter_map(|(id, artifact)| {
+                        Some((id.name, artifact.storage_layout.clone()?))
+                    }),
+                )
+                .breakpoints(test_result.breakpoints.clone())
+                .config(config.debugger.clone())
+                .root(project.root());
 
             if let Some(decoder) = &outcome.last_run_decoder {
                 builder = builder.decoder(decoder);
             }
 
+            // Name stack values using the ethdebug output of the build info files, if any.
+            if project.paths.build_infos.is_dir() {
+                builder = builder
+                    .debug_info(ContractDebugInfo::load_build_info_dir(&project.paths.build_infos));
+            }
+
             let mut debugger = builder.build();
             if let Some(dump_path) = self.dump {
-                debugger.dump_to_file(&dump_path)?;
+                debugger.dump_to_file_as(&dump_path, self.dump_format)?;
             } else {
                 debugger.try_run_tui()?;
             }
@@ -419,6 +764,8 @@ pub async fn execute_tests(mut self) -> Result<TestOutcome> {
     }
 
     /// Run all tests that matches the filter predicate from a test runner
+    ///
+    /// The `header` of the run, if any, is printed before the results.
     pub async fn run_tests(
         &self,
         mut runner: MultiContractRunner,
@@ -426,6 +773,7 @@ pub async fn run_tests(
         verbosity: u8,
         filter: &ProjectPathsAwareFilter,
         output: &ProjectCompileOutput,
+        header: Option<&TestRunHeader>,
     ) -> eyre::Result<TestOutcome> {
         if self.list {
             return list(runner, filter);
@@ -475,13 +823,16 @@ pub async fn run_tests(
                     }
                 }
             });
+            if let Some(header) = header.filter(|_| self.json_header) {
+                sh_println!("{}", serde_json::json!({ "run": header }))?;
+            }
             sh_println!("{}", serde_json::to_string(&results)?)?;
             return Ok(TestOutcome::new(results, self.allow_failure));
         }
 
-        if self.junit {
+        if self.junit_stdout() {
             let results = runner.test_collect(filter);
-            sh_println!("{}", junit_xml_report(&results, verbosity).to_string()?)?;
+            sh_println!("{}", report::junit_xml_report(&results, verbosity).to_string()?)?;
             return Ok(TestOutcome::new(results, self.allow_failure));
         }
 
@@ -490,6 +841,12 @@ pub async fn run_tests(
 
         let libraries = runner.libraries.clone();
 
+        let num_suites = runner.matching_contracts(filter).count();
+
+        if let Some(header) = header.filter(|_| !silent) {
+            sh_println!("\n{header}")?;
+        }
+
         // Run tests in a streaming fashion.
         let (tx, rx) = channel::<(String, SuiteResult)>();
         let timer = Instant::now();
@@ -527,6 +884,25 @@ pub async fn run_tests(
         }
         let mut decoder = builder.build();
 
+        let failure_artifacts = if self.failure_artifacts {
+            let sources =
+                ContractSources::from_project_output(output, &config.root, Some(&libraries))?;
+            Some(FailureArtifacts::new(
+                config.out.join(FAILURE_ARTIFACTS_DIR),
+                sources,
+                config.debugger.clone(),
+            )?)
+        } else {
+            None
+        };
+
+        // Used to point memory safety violations to their source.
+        let memory_safety_sources = if config.memory_safety {
+            Some(ContractSources::from_project_output(output, &config.root, Some(&libraries))?)
+        } else {
+            None
+        };
+
         let mut gas_report = self.gas_report.then(|| {
             GasReport::new(
                 config.gas_reports.clone(),
@@ -540,8 +916,15 @@ pub async fn run_tests(
         let mut outcome = TestOutcome::empty(self.allow_failure);
 
         let mut any_test_failed = false;
-        for (contract_name, suite_result) in rx {
+        for (contract_name, mut suite_result) in rx {
+            if let Some(sources) = &memory_safety_sources {
+                for result in suite_result.test_results.values_mut() {
+                    locate_memory_safety_violation(sources, result);
+                }
+            }
+
             let tests = &suite_result.test_results;
+            let mut artifact_dirs = Vec::new();
 
             // Clear the addresses and labels from previous test.
             decoder.clear_addresses();
@@ -551,7 +934,8 @@ pub async fn run_tests(
                 self.gas_report ||
                 self.debug ||
                 self.flamegraph ||
-                self.flamechart;
+                self.flamechart ||
+                self.export_traces.is_some();
 
             // Print suite header.
             if !silent {
@@ -603,9 +987,11 @@ pub async fn run_tests(
                     .extend(result.labeled_addresses.iter().map(|(k, v)| (*k, v.clone())));
 
                 // Identify addresses and decode traces.
+                let write_artifacts = failure_artifacts.is_some() && result.status.is_failure();
                 let mut decoded_traces = Vec::with_capacity(result.traces.len());
+                let mut exported_traces = Vec::new();
                 for (kind, arena) in &mut result.traces.clone() {
-                    if identify_addresses {
+                    if identify_addresses || write_artifacts {
                         decoder.identify(arena, &mut identifier);
                     }
 
@@ -624,10 +1010,35 @@ pub async fn run_tests(
                         TraceKind::Deployment => false,
                     };
 
-                    if should_include {
+                    let should_export =
+                        self.export_traces.is_some() && !matches!(kind, TraceKind::Deployment);
+
+                    if should_include || should_export {
                         decode_trace_arena(arena, &decoder).await?;
+                    }
+                    if should_include {
                         decoded_traces.push(render_trace_arena_inner(arena, false, verbosity > 4));
                     }
+                    if should_export {
+                        let label = if kind.is_execution() { "execution" } else { "setup" };
+                        exported_traces.push((label, arena.resolve_arena().into_owned()));
+                    }
+                }
+
+                if let Some(format) = self.export_traces {
+                    let contract = contract_name.split(':').next_back().unwrap();
+                    let test_name = name.split('(').next().unwrap();
+                    let path = config
+                        .cache_path
+                        .join("traces")
+                        .join(contract)
+                        .join(format!("{test_name}.{}", format.extension()));
+                    let exported = format.export(
+                        &format!("{contract}::{test_name}"),
+                        exported_traces.iter().map(|(label, arena)| (*label, arena)),
+                    );
+                    fs::create_dir_all(path.parent().unwrap())?;
+                    fs::write_pretty_json_file(&path, &exported)?;
                 }
 
                 if !silent && !decoded_traces.is_empty() {
@@ -637,6 +1048,11 @@ pub async fn run_tests(
                     }
                 }
 
+                if let Some(artifacts) = failure_artifacts.as_ref().filter(|_| write_artifacts) {
+                    let dir = artifacts.write(&contract_name, name, result, &decoder).await?;
+                    artifact_dirs.push((name.clone(), dir));
+                }
+
                 if let Some(gas_report) = &mut gas_report {
                     gas_report.analyze(result.traces.iter().map(|(_, a)| &a.arena), &decoder).await;
 
@@ -750,12 +1166,23 @@ pub async fn run_tests(
                 sh_println!("{}", suite_result.summary())?;
             }
 
+            for (name, dir) in artifact_dirs {
+                if let Some(result) = suite_result.test_results.get_mut(&name) {
+                    result.failure_artifacts = Some(dir);
+                }
+            }
+
             // Add the suite result to the outcome.
             outcome.results.insert(contract_name, suite_result);
+        }
 
-            // Stop processing the remaining suites if any test failed and `fail_fast` is set.
-            if self.fail_fast && any_test_failed {
-                break;
+        // With `fail_fast`, the runner stops the running suites at the first failure and doesn't
+        // start the remaining ones.
+        if self.fail_fast && any_test_failed && !silent {
+            let not_run = num_suites.saturating_sub(outcome.results.len());
+            if not_run > 0 {
+                let suites = if not_run > 1 { "suites" } else { "suite" };
+                sh_warn!("{not_run} test {suites} not run after the first failure")?;
             }
         }
         outcome.last_run_decoder = Some(decoder);
@@ -789,6 +1216,11 @@ pub async fn run_tests(
         // Persist test run failures to enable replaying.
         persist_run_failures(&config, &outcome);
 
+        // Export the fuzz dictionaries learned by all fuzz tests.
+        if let Some(path) = &config.fuzz.dictionary_export {
+            export_fuzz_dictionary(path, &outcome)?;
+        }
+
         Ok(outcome)
     }
 
@@ -833,8 +1265,8 @@ fn data(&self) -> Result<Map<Profile, Dict>, figment::Error> {
         let mut dict = Dict::default();
 
         let mut fuzz_dict = Dict::default();
-        if let Some(fuzz_seed) = self.fuzz_seed {
-            fuzz_dict.insert("seed".to_string(), fuzz_seed.to_string().into());
+        if let Some(seed) = self.seed {
+            fuzz_dict.insert("seed".to_string(), seed.to_string().into());
         }
         if let Some(fuzz_runs) = self.fuzz_runs {
             fuzz_dict.insert("runs".to_string(), fuzz_runs.into());
@@ -857,6 +1289,18 @@ fn data(&self) -> Result<Map<Profile, Dict>, figment::Error> {
             dict.insert("show_progress".to_string(), true.into());
         }
 
+        if self.memory_safety {
+            dict.insert("memory_safety".to_string(), true.into());
+        }
+
+        if self.memory_report {
+            dict.insert("memory_report".to_string(), true.into());
+        }
+
+        if let Some(limit) = self.test_memory_limit {
+            dict.insert("test_memory_limit".to_string(), limit.into());
+        }
+
         Ok(Map::from([(Config::selected_profile(), dict)]))
     }
 }
@@ -906,47 +1350,35 @@ fn persist_run_failures(config: &Config, outcome: &TestOutcome) {
     }
 }
 
-/// Generate test report in JUnit XML report format.
-fn junit_xml_report(results: &BTreeMap<String, SuiteResult>, verbosity: u8) -> Report {
-    let mut total_duration = Duration::default();
-    let mut junit_report = Report::new("Test run");
-    junit_report.set_timestamp(Utc::now());
-    for (suite_name, suite_result) in results {
-        let mut test_suite = TestSuite::new(suite_name);
-        total_duration += suite_result.duration;
-        test_suite.set_time(suite_result.duration);
-        test_suite.set_system_out(suite_result.summary());
-        for (test_name, test_result) in &suite_result.test_results {
-            let mut test_status = match test_result.status {
-                TestStatus::Success => TestCaseStatus::success(),
-                TestStatus::Failure => TestCaseStatus::non_success(NonSuccessKind::Failure),
-                TestStatus::Skipped => TestCaseStatus::skipped(),
-            };
-            if let Some(reason) = &test_result.reason {
-                test_status.set_message(reason);
-            }
-
-            let mut test_case = TestCase::new(test_name, test_status);
-            test_case.set_time(test_result.duration);
-
-            let mut sys_out = String::new();
-            let result_report = test_result.kind.report();
-            write!(sys_out, "{test_result} {test_name} {result_report}").unwrap();
-            if verbosity >= 2 && !test_result.logs.is_empty() {
-                write!(sys_out, "\\nLogs:\\n").unwrap();
-                let console_logs = decode_console_logs(&test_result.logs);
-                for log in console_logs {
-                    write!(sys_out, "  {log}\\n").unwrap();
-                }
-            }
+/// Appends the source location of the memory safety violation of a test, if any, to its reason.
+fn locate_memory_safety_violation(sources: &ContractSources, result: &mut TestResult) {
+    let Some(violation) = &result.memory_safety_violation else { return };
+    let Some(name) = &violation.name else { return };
+    let Some((element, source)) =
+        sources.find_source_mapping(name, violation.pc as u32, violation.init_code)
+    else {
+        return
+    };
+    let offset = (element.offset() as usize).min(source.source.len());
+    let before = &source.source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = offset - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+    result.reason = Some(format!("{violation} ({}:{line}:{column})", source.path.display()));
+}
 
-            test_case.set_system_out(sys_out);
-            test_suite.add_test_case(test_case);
+/// Merges the fuzz dictionaries of all tests and writes them to the given path.
+fn export_fuzz_dictionary(path: &Path, outcome: &TestOutcome) -> Result<()> {
+    let mut dictionary = FuzzDictionaryFile::default();
+    for (_, result) in outcome.tests() {
+        if let Some(other) = &result.fuzz_dictionary {
+            dictionary.extend(other.clone());
         }
-        junit_report.add_test_suite(test_suite);
     }
-    junit_report.set_time(total_duration);
-    junit_report
+    if !dictionary.is_empty() {
+        dictionary.save(path)?;
+        sh_println!("Exported fuzz dictionary to {}", path.display())?;
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -963,7 +1395,10 @@ fn watch_parse() {
     #[test]
     fn fuzz_seed() {
         let args: TestArgs = TestArgs::parse_from(["foundry-cli", "--fuzz-seed", "0x10"]);
-        assert!(args.fuzz_seed.is_some());
+        assert_eq!(args.seed, Some(U256::from(0x10)));
+
+        let args: TestArgs = TestArgs::parse_from(["foundry-cli", "--seed", "0x10"]);
+        assert_eq!(args.seed, Some(U256::from(0x10)));
     }
 
     // <https://github.com/foundry-rs/foundry/issues/5913>
@@ -971,7 +1406,7 @@ fn fuzz_seed() {
     fn fuzz_seed_exists() {
         let args: TestArgs =
             TestArgs::parse_from(["foundry-cli", "-vvv", "--gas-report", "--fuzz-seed", "0x10"]);
-        assert!(args.fuzz_seed.is_some());
+        assert!(args.seed.is_some());
     }
 
     #[test]
@@ -0,0 +1,199 @@
+//! Test reports for CI systems: JUnit XML and GitHub Actions annotations.
+
+use alloy_primitives::Address;
+use chrono::Utc;
+use forge::{
+    decode::decode_console_logs,
+    result::{SuiteResult, TestResult, TestStatus},
+    traces::{debug::ContractSources, TraceKind},
+};
+use foundry_evm::constants::CHEATCODE_ADDRESS;
+use quick_junit::{NonSuccessKind, Report, TestCase, TestCaseStatus, TestSuite};
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write},
+    path::PathBuf,
+    time::Duration,
+};
+
+/// Generate test report in JUnit XML report format.
+pub fn junit_xml_report(results: &BTreeMap<String, SuiteResult>, verbosity: u8) -> Report {
+    let mut total_duration = Duration::default();
+    let mut junit_report = Report::new("Test run");
+    junit_report.set_timestamp(Utc::now());
+    for (suite_name, suite_result) in results {
+        let mut test_suite = TestSuite::new(suite_name);
+        total_duration += suite_result.duration;
+        test_suite.set_time(suite_result.duration);
+        test_suite.set_system_out(suite_result.summary());
+        for (test_name, test_result) in &suite_result.test_results {
+            let mut test_status = match test_result.status {
+                TestStatus::Success => TestCaseStatus::success(),
+                TestStatus::Failure => TestCaseStatus::non_success(NonSuccessKind::Failure),
+                TestStatus::Skipped => TestCaseStatus::skipped(),
+            };
+            if let Some(reason) = &test_result.reason {
+                test_status.set_message(reason);
+            }
+
+            let mut test_case = TestCase::new(test_name, test_status);
+            test_case.set_time(test_result.duration);
+
+            let mut sys_out = String::new();
+            let result_report = test_result.kind.report();
+            write!(sys_out, "{test_result} {test_name} {result_report}").unwrap();
+            if verbosity >= 2 && !test_result.logs.is_empty() {
+                write!(sys_out, "\\nLogs:\\n").unwrap();
+                let console_logs = decode_console_logs(&test_result.logs);
+                for log in console_logs {
+                    write!(sys_out, "  {log}\\n").unwrap();
+                }
+            }
+
+            test_case.set_system_out(sys_out);
+            test_suite.add_test_case(test_case);
+        }
+        junit_report.add_test_suite(test_suite);
+    }
+    junit_report.set_time(total_duration);
+    junit_report
+}
+
+/// A GitHub Actions `error` workflow command, annotating the source of a failed test.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GithubAnnotation {
+    /// The path of the file the test failed in, relative to the project root.
+    pub file: String,
+    /// The line the test failed at, if it could be found.
+    pub line: Option<usize>,
+    pub title: String,
+    pub message: String,
+}
+
+impl GithubAnnotation {
+    /// Returns the annotation of a failed test.
+    ///
+    /// The location is the one of the last instruction executed by the test contract, in the call
+    /// that failed, that is mapped to a source of the project which isn't in one of the `libs`, so
+    /// the annotation is in the file of the base contract of inherited tests. It defaults to the
+    /// file of the suite. The message is the failure reason, along with the contract the test
+    /// reverted in when it isn't the test contract itself, as found in the execution trace.
+    pub fn new(
+        sources: Option<&ContractSources>,
+        libs: &[PathBuf],
+        suite_name: &str,
+        test_name: &str,
+        result: &TestResult,
+    ) -> Self {
+        let (file, contract) = suite_name.rsplit_once(':').unwrap_or((suite_name, ""));
+        let location =
+            sources.and_then(|sources| failure_location(sources, libs, contract, result));
+        let (file, line) = match location {
+            Some((file, line)) => (file.display().to_string(), Some(line)),
+            None => (file.to_string(), None),
+        };
+
+        let mut message = result.reason.clone().unwrap_or_else(|| "test failed".to_string());
+        if let Some(address) = reverted_in(result) {
+            let label = result.labeled_addresses.get(&address).cloned();
+            let label = label.unwrap_or_else(|| address.to_string());
+            write!(message, "\nReverted in a call to {label}").unwrap();
+        }
+
+        Self { file, line, title: format!("{contract}::{test_name}"), message }
+    }
+}
+
+impl fmt::Display for GithubAnnotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "::error file={}", escape_property(&self.file))?;
+        if let Some(line) = self.line {
+            write!(f, ",line={line}")?;
+        }
+        write!(f, ",title={}::{}", escape_property(&self.title), escape_data(&self.message))
+    }
+}
+
+/// Returns the annotations of the failed tests.
+///
+/// The failures are located with the `sources` of the project, if any, see
+/// [`GithubAnnotation::new`].
+pub fn github_annotations(
+    sources: Option<&ContractSources>,
+    libs: &[PathBuf],
+    results: &BTreeMap<String, SuiteResult>,
+) -> Vec<GithubAnnotation> {
+    results
+        .iter()
+        .flat_map(|(suite_name, suite)| {
+            suite.failures().map(|(test_name, result)| {
+                GithubAnnotation::new(sources, libs, suite_name, test_name, result)
+            })
+        })
+        .collect()
+}
+
+/// Returns the file and the 1-based line of the last instruction of the test `contract` that was
+/// recorded in the failed call, i.e. the execution of the test, or the setup if it failed, and
+/// that is mapped to a source outside of the `libs`.
+fn failure_location(
+    sources: &ContractSources,
+    libs: &[PathBuf],
+    contract: &str,
+    result: &TestResult,
+) -> Option<(PathBuf, usize)> {
+    let (_, arena) = result.traces.iter().rev().find(|(kind, _)| *kind != TraceKind::Deployment)?;
+    let node = arena.arena.nodes().first()?;
+    node.trace.steps.iter().rev().find_map(|step| {
+        let (element, source) = sources.find_source_mapping(contract, step.pc as u32, false)?;
+        if libs.iter().any(|lib| source.path.starts_with(lib)) {
+            return None
+        }
+        let offset = (element.offset() as usize).min(source.source.len());
+        let line = source.source[..offset].matches('\n').count() + 1;
+        Some((source.path.clone(), line))
+    })
+}
+
+/// Returns the address of the deepest call of the execution trace that reverted, if it isn't
+/// the test contract or the cheatcodes.
+fn reverted_in(result: &TestResult) -> Option<Address> {
+    let (_, arena) = result.traces.iter().find(|(kind, _)| *kind == TraceKind::Execution)?;
+    let node = arena
+        .arena
+        .nodes()
+        .iter()
+        .filter(|node| !node.trace.success && node.trace.address != CHEATCODE_ADDRESS)
+        .max_by_key(|node| node.trace.depth)?;
+    (node.trace.depth > 0).then_some(node.trace.address)
+}
+
+/// Escapes the data of a workflow command.
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Escapes a property of a workflow command.
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_github_annotation() {
+        let annotation = GithubAnnotation {
+            file: "test/Counter.t.sol".to_string(),
+            line: Some(12),
+            title: "CounterTest::test_Increment()".to_string(),
+            message: "assertion failed: 1 != 2\nReverted in a call to Counter".to_string(),
+        };
+        assert_eq!(
+            annotation.to_string(),
+            "::error file=test/Counter.t.sol,line=12,title=CounterTest%3A%3Atest_Increment()::\
+             assertion failed: 1 != 2%0AReverted in a call to Counter"
+        );
+    }
+}
@@ -5,6 +5,10 @@
     resolver::{parse::SolData, Charset, TreeOptions},
     Graph,
 };
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    path::{Component, Path, PathBuf},
+};
 
 /// CLI arguments for `forge tree`.
 #[derive(Clone, Debug, Parser)]
@@ -19,6 +23,18 @@ pub struct TreeArgs {
     #[arg(long, default_value = "utf8")]
     charset: Charset,
 
+    /// Print the shortest import chain from every root file to the given file or dependency.
+    ///
+    /// Matches every resolved file whose path contains the given value, e.g. `SafeERC20.sol` or
+    /// `openzeppelin-contracts`.
+    #[arg(long, value_name = "FILE", conflicts_with = "duplicates")]
+    why: Option<String>,
+
+    /// Print library files that are reachable through multiple distinct paths, e.g. because of
+    /// conflicting remappings or nested dependencies.
+    #[arg(long)]
+    duplicates: bool,
+
     #[command(flatten)]
     project_paths: ProjectPathOpts,
 }
@@ -29,9 +45,186 @@ impl TreeArgs {
     pub fn run(self) -> Result<()> {
         let config = self.load_config()?;
         let graph = Graph::<SolData>::resolve(&config.project_paths())?;
+
+        if let Some(target) = &self.why {
+            return print_why(&graph, &config.root, target, self.charset);
+        }
+
+        if self.duplicates {
+            return print_duplicates(&graph, &config.root, &config.libs);
+        }
+
         let opts = TreeOptions { charset: self.charset, no_dedupe: self.no_dedupe };
         graph.print_with_options(opts);
 
         Ok(())
     }
 }
+
+/// Prints the shortest import chain from every root file to the files matching `target`.
+fn print_why(graph: &Graph<SolData>, root: &Path, target: &str, charset: Charset) -> Result<()> {
+    let targets = graph
+        .files()
+        .iter()
+        .filter(|(path, _)| display_path(path, root).contains(target))
+        .map(|(_, idx)| *idx)
+        .collect::<BTreeSet<_>>();
+    if targets.is_empty() {
+        eyre::bail!("no file matching `{target}` is part of the build");
+    }
+
+    let arrow = match charset {
+        Charset::Utf8 => " → ",
+        Charset::Ascii => " -> ",
+    };
+    for target in targets {
+        sh_println!("{} is included by:", display_path(graph.node(target).path(), root))?;
+        let chains = import_chains(graph, target);
+        if chains.is_empty() {
+            sh_println!("  (root file)")?;
+        }
+        for chain in chains {
+            let chain = chain
+                .iter()
+                .map(|idx| display_path(graph.node(*idx).path(), root))
+                .collect::<Vec<_>>()
+                .join(arrow);
+            sh_println!("  {chain}")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the shortest import chain from every file without importers that imports `target`.
+///
+/// The importers are walked breadth-first from `target`, visiting every file once, since the
+/// number of distinct chains grows exponentially with the shared imports.
+fn import_chains(graph: &Graph<SolData>, target: usize) -> Vec<Vec<usize>> {
+    let mut importers = vec![Vec::new(); graph.nodes.len()];
+    for idx in 0..graph.nodes.len() {
+        for imported in graph.imported_nodes(idx) {
+            importers[*imported].push(idx);
+        }
+    }
+    shortest_chains(&importers, target)
+}
+
+/// Returns the shortest chain from every node without importers to `target`, given the
+/// importers of every node.
+fn shortest_chains(importers: &[Vec<usize>], target: usize) -> Vec<Vec<usize>> {
+    // The node each visited node imports on its shortest chain to `target`.
+    let mut next = vec![None; importers.len()];
+    let mut visited = vec![false; importers.len()];
+    visited[target] = true;
+    let mut queue = VecDeque::from([target]);
+    let mut roots = Vec::new();
+    while let Some(current) = queue.pop_front() {
+        if importers[current].is_empty() && current != target {
+            roots.push(current);
+        }
+        for &parent in &importers[current] {
+            if !visited[parent] {
+                visited[parent] = true;
+                next[parent] = Some(current);
+                queue.push_back(parent);
+            }
+        }
+    }
+
+    let mut chains = roots
+        .into_iter()
+        .map(|root| {
+            let mut chain = vec![root];
+            while let Some(child) = next[*chain.last().unwrap()] {
+                chain.push(child);
+            }
+            chain
+        })
+        .collect::<Vec<_>>();
+    chains.sort();
+    chains
+}
+
+/// Prints library files that resolve to the same dependency file from multiple locations.
+fn print_duplicates(graph: &Graph<SolData>, root: &Path, libs: &[PathBuf]) -> Result<()> {
+    let mut by_key = BTreeMap::<PathBuf, BTreeSet<String>>::new();
+    let libs = libs.iter().map(|lib| lib.strip_prefix(root).unwrap_or(lib)).collect::<Vec<_>>();
+    for path in graph.files().keys() {
+        if let Some(key) = dependency_key(path.strip_prefix(root).unwrap_or(path), &libs) {
+            by_key.entry(key).or_default().insert(display_path(path, root));
+        }
+    }
+
+    let duplicates = by_key.into_iter().filter(|(_, paths)| paths.len() > 1).collect::<Vec<_>>();
+    if duplicates.is_empty() {
+        sh_println!("No duplicate dependency files found.")?;
+        return Ok(());
+    }
+    for (key, paths) in duplicates {
+        sh_println!("{} is included from {} locations:", key.display(), paths.len())?;
+        for path in paths {
+            sh_println!("  {path}")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the path of a file relative to the innermost library directory it belongs to,
+/// including the dependency name, e.g. `lib/a/lib/oz/contracts/Foo.sol` -> `oz/contracts/Foo.sol`.
+fn dependency_key(path: &Path, libs: &[&Path]) -> Option<PathBuf> {
+    let lib_names = libs.iter().filter_map(|lib| lib.file_name()).collect::<Vec<_>>();
+    let components = path.components().collect::<Vec<_>>();
+    let pos = components.iter().rposition(|c| match c {
+        Component::Normal(name) => lib_names.contains(name),
+        _ => false,
+    })?;
+    let rest = &components[pos + 1..];
+    (rest.len() > 1).then(|| rest.iter().collect())
+}
+
+fn display_path(path: &Path, root: &Path) -> String {
+    path.strip_prefix(root).unwrap_or(path).display().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dependency_key_uses_innermost_lib() {
+        let libs = [Path::new("lib"), Path::new("node_modules")];
+        assert_eq!(
+            dependency_key(Path::new("lib/a/lib/oz/contracts/Foo.sol"), &libs),
+            Some(PathBuf::from("oz/contracts/Foo.sol"))
+        );
+        assert_eq!(
+            dependency_key(Path::new("lib/oz/contracts/Foo.sol"), &libs),
+            Some(PathBuf::from("oz/contracts/Foo.sol"))
+        );
+        assert_eq!(dependency_key(Path::new("src/Foo.sol"), &libs), None);
+    }
+
+    #[test]
+    fn shortest_chains_visit_every_file_once() {
+        // A ladder of 64 diamonds: 2^64 distinct chains lead from the root to the last file.
+        let n = 64;
+        let mut importers = vec![Vec::new(); 3 * n + 1];
+        for i in 0..n {
+            let (top, left, right, bottom) = (3 * i, 3 * i + 1, 3 * i + 2, 3 * i + 3);
+            importers[left].push(top);
+            importers[right].push(top);
+            importers[bottom].extend([left, right]);
+        }
+        let chains = shortest_chains(&importers, 3 * n);
+        assert_eq!(chains.len(), 1);
+        assert_eq!(chains[0].len(), 2 * n + 1);
+        assert_eq!(chains[0].first(), Some(&0));
+
+        // Two roots, one of which also imports the other, and an import cycle.
+        let importers = vec![vec![], vec![0, 2], vec![1], vec![1, 4], vec![]];
+        assert_eq!(shortest_chains(&importers, 3), vec![vec![0, 1, 3], vec![4, 3]]);
+        assert_eq!(shortest_chains(&importers, 0), Vec::<Vec<usize>>::new());
+    }
+}
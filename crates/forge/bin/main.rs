@@ -6,7 +6,10 @@
 use foundry_evm::inspectors::cheatcodes::{set_execution_context, ForgeContext};
 
 mod cmd;
-use cmd::{cache::CacheSubcommands, generate::GenerateSubcommands, watch};
+use cmd::{
+    cache::CacheSubcommands, forks::ForksSubcommands, generate::GenerateSubcommands,
+    nonces::NoncesSubcommands, watch,
+};
 
 mod opts;
 use opts::{Forge, ForgeSubcommand};
@@ -43,7 +46,7 @@ fn run() -> Result<()> {
             if cmd.is_watch() {
                 utils::block_on(watch::watch_test(cmd))
             } else {
-                let silent = cmd.junit || shell::is_json();
+                let silent = cmd.junit_stdout() || shell::is_json();
                 let outcome = utils::block_on(cmd.run())?;
                 outcome.ensure_ok(silent)
             }
@@ -56,6 +59,13 @@ fn run() -> Result<()> {
                 utils::block_on(cmd.run())
             }
         }
+        ForgeSubcommand::Bench(cmd) => utils::block_on(cmd.run()),
+        ForgeSubcommand::Prove(cmd) => {
+            if utils::block_on(cmd.run())? {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
         ForgeSubcommand::Bind(cmd) => cmd.run(),
         ForgeSubcommand::Build(cmd) => {
             if cmd.is_watch() {
@@ -71,11 +81,20 @@ fn run() -> Result<()> {
         ForgeSubcommand::Cache(cmd) => match cmd.sub {
             CacheSubcommands::Clean(cmd) => cmd.run(),
             CacheSubcommands::Ls(cmd) => cmd.run(),
+            CacheSubcommands::Gc(cmd) => cmd.run(),
+        },
+        ForgeSubcommand::Forks(cmd) => match cmd.sub {
+            ForksSubcommands::Update(cmd) => utils::block_on(cmd.run()),
+        },
+        ForgeSubcommand::Nonces(cmd) => match cmd.sub {
+            NoncesSubcommands::List(cmd) => cmd.run(),
+            NoncesSubcommands::Release(cmd) => cmd.run(),
         },
         ForgeSubcommand::Create(cmd) => utils::block_on(cmd.run()),
         ForgeSubcommand::Update(cmd) => cmd.run(),
         ForgeSubcommand::Install(cmd) => cmd.run(),
         ForgeSubcommand::Remove(cmd) => cmd.run(),
+        ForgeSubcommand::Publish(cmd) => utils::block_on(cmd.run()),
         ForgeSubcommand::Remappings(cmd) => cmd.run(),
         ForgeSubcommand::Init(cmd) => cmd.run(),
         ForgeSubcommand::Completions { shell } => {
@@ -107,7 +126,9 @@ fn run() -> Result<()> {
         ForgeSubcommand::Fmt(cmd) => cmd.run(),
         ForgeSubcommand::Config(cmd) => cmd.run(),
         ForgeSubcommand::Flatten(cmd) => cmd.run(),
+        ForgeSubcommand::BytecodeSearch(cmd) => utils::block_on(cmd.run()),
         ForgeSubcommand::Inspect(cmd) => cmd.run(),
+        ForgeSubcommand::Analyze(cmd) => cmd.run(),
         ForgeSubcommand::Tree(cmd) => cmd.run(),
         ForgeSubcommand::Geiger(cmd) => {
             let n = cmd.run()?;
@@ -116,6 +137,12 @@ fn run() -> Result<()> {
             }
             Ok(())
         }
+        ForgeSubcommand::Lint(cmd) => {
+            if cmd.run()? > 0 {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
         ForgeSubcommand::Doc(cmd) => {
             if cmd.is_watch() {
                 utils::block_on(watch::watch_doc(cmd))
@@ -143,6 +170,7 @@ fn init_execution_context(subcommand: &ForgeSubcommand) {
         ForgeSubcommand::Test(_) => ForgeContext::Test,
         ForgeSubcommand::Coverage(_) => ForgeContext::Coverage,
         ForgeSubcommand::Snapshot(_) => ForgeContext::Snapshot,
+        ForgeSubcommand::Bench(_) => ForgeContext::Test,
         ForgeSubcommand::Script(cmd) => {
             if cmd.broadcast {
                 ForgeContext::ScriptBroadcast
@@ -1,9 +1,11 @@
 use crate::cmd::{
-    bind::BindArgs, bind_json, build::BuildArgs, cache::CacheArgs, clone::CloneArgs,
+    analyze::AnalyzeArgs, bench::BenchArgs, bind::BindArgs, bind_json, build::BuildArgs,
+    bytecode_search::BytecodeSearchArgs, cache::CacheArgs, clone::CloneArgs,
     compiler::CompilerArgs, config, coverage, create::CreateArgs, doc::DocArgs, eip712, flatten,
-    fmt::FmtArgs, geiger, generate, init::InitArgs, inspect, install::InstallArgs,
-    remappings::RemappingArgs, remove::RemoveArgs, selectors::SelectorsSubcommands, snapshot,
-    soldeer, test, tree, update,
+    fmt::FmtArgs, forks::ForksArgs, geiger, generate, init::InitArgs, inspect,
+    install::InstallArgs, lint::LintArgs, nonces::NoncesArgs, prove::ProveArgs,
+    publish::PublishArgs, remappings::RemappingArgs, remove::RemoveArgs,
+    selectors::SelectorsSubcommands, snapshot, soldeer, test, tree, update,
 };
 use clap::{Parser, Subcommand, ValueHint};
 use forge_script::ScriptArgs;
@@ -43,6 +45,12 @@ pub enum ForgeSubcommand {
     /// Generate coverage reports.
     Coverage(coverage::CoverageArgs),
 
+    /// Run the project's benchmarks and compare them against a baseline.
+    Bench(BenchArgs),
+
+    /// Search for inputs of a function that violate an assertion (experimental).
+    Prove(ProveArgs),
+
     /// Generate Rust bindings for smart contracts.
     #[command(alias = "bi")]
     Bind(BindArgs),
@@ -70,6 +78,11 @@ pub enum ForgeSubcommand {
     #[command(visible_alias = "rm")]
     Remove(RemoveArgs),
 
+    /// Publish the project's sources as a package to a registry.
+    ///
+    /// Packages can be installed with `forge install --registry`.
+    Publish(PublishArgs),
+
     /// Get the automatically inferred remappings for the project.
     #[command(visible_alias = "re")]
     Remappings(RemappingArgs),
@@ -118,6 +131,12 @@ pub enum ForgeSubcommand {
     /// Manage the Foundry cache.
     Cache(CacheArgs),
 
+    /// Manage the block numbers that fork tests against `latest` are pinned to.
+    Forks(ForksArgs),
+
+    /// Manage the nonces reserved by scripts broadcasting from the same key.
+    Nonces(NoncesArgs),
+
     /// Create a gas snapshot of each test's gas usage.
     #[command(visible_alias = "s")]
     Snapshot(snapshot::GasSnapshotArgs),
@@ -133,10 +152,19 @@ pub enum ForgeSubcommand {
     /// Format Solidity source files.
     Fmt(FmtArgs),
 
+    /// Find the contracts of the project whose runtime code matches deployed bytecode.
+    ///
+    /// Immutables, library addresses and the metadata hash are ignored, so contracts compiled
+    /// from the same sources can be identified from the address of a deployment alone.
+    BytecodeSearch(BytecodeSearchArgs),
+
     /// Get specialized information about a smart contract.
     #[command(visible_alias = "in")]
     Inspect(inspect::InspectArgs),
 
+    /// Analyze the project's contracts, e.g. to find storage slots that can be saved.
+    Analyze(AnalyzeArgs),
+
     /// Display a tree visualization of the project's dependency graph.
     #[command(visible_alias = "tr")]
     Tree(tree::TreeArgs),
@@ -147,6 +175,9 @@ pub enum ForgeSubcommand {
     /// Generate documentation for the project.
     Doc(DocArgs),
 
+    /// Run static checks over the project's sources.
+    Lint(LintArgs),
+
     /// Function selector utilities.
     #[command(visible_alias = "se")]
     Selectors {
@@ -0,0 +1,162 @@
+//! Running the commands configured in `[build.hooks]`.
+
+use alloy_primitives::{Keccak256, B256};
+use eyre::{Context, Result};
+use foundry_common::{fs, shell};
+use foundry_config::{filter::expand_globs, BuildHook, Config};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// The file in the cache directory that records the last run of every hook.
+const BUILD_HOOKS_CACHE_FILE: &str = "build-hooks.json";
+
+/// The stage of a build at which hooks run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BuildStage {
+    /// Before compiling.
+    PreBuild,
+    /// After a successful compilation.
+    PostBuild,
+}
+
+impl fmt::Display for BuildStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PreBuild => f.write_str("pre_build"),
+            Self::PostBuild => f.write_str("post_build"),
+        }
+    }
+}
+
+/// The fingerprints of the inputs and outputs of a hook after its last successful run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct HookFingerprint {
+    inputs: B256,
+    outputs: B256,
+}
+
+/// Runs the hooks of the given stage whose command or inputs changed since their last run, or
+/// whose outputs are missing or were modified.
+///
+/// Returns the names of the hooks that ran.
+pub fn run_build_hooks(config: &Config, stage: BuildStage) -> Result<Vec<String>> {
+    let hooks = match stage {
+        BuildStage::PreBuild => &config.build.hooks.pre_build,
+        BuildStage::PostBuild => &config.build.hooks.post_build,
+    };
+    if hooks.is_empty() {
+        return Ok(Vec::new())
+    }
+
+    let cache_file = config.cache_path.join(BUILD_HOOKS_CACHE_FILE);
+    let mut fingerprints: BTreeMap<String, HookFingerprint> =
+        fs::read_json_file(&cache_file).unwrap_or_default();
+
+    let mut ran = Vec::new();
+    for hook in hooks {
+        let name = hook.name();
+        let key = format!("{stage}:{name}");
+        let inputs = inputs_fingerprint(&config.root, hook)?;
+        let is_fresh = !hook.inputs.is_empty() &&
+            fingerprints.get(&key).is_some_and(|last| {
+                last.inputs == inputs &&
+                    outputs_fingerprint(&config.root, hook).is_ok_and(|o| o == Some(last.outputs))
+            });
+        if is_fresh {
+            continue
+        }
+
+        if !shell::is_json() {
+            sh_println!("Running {stage} hook `{name}`")?;
+        }
+        run_hook(&config.root, hook, &name)?;
+
+        let outputs = outputs_fingerprint(&config.root, hook)?.ok_or_else(|| {
+            let missing = hook.outputs.iter().find(|path| !config.root.join(path).is_file());
+            eyre::eyre!(
+                "{stage} hook `{name}` did not write `{}`",
+                missing.map(|path| path.display().to_string()).unwrap_or_default()
+            )
+        })?;
+        fingerprints.insert(key, HookFingerprint { inputs, outputs });
+        ran.push(name);
+    }
+
+    if config.cache && !ran.is_empty() {
+        fs::create_dir_all(&config.cache_path)?;
+        fs::write_json_file(&cache_file, &fingerprints)?;
+    }
+    Ok(ran)
+}
+
+/// Returns the input files of the hooks of the given stage, which can be watched for changes.
+pub fn build_hook_inputs(config: &Config, stage: BuildStage) -> Result<Vec<PathBuf>> {
+    let hooks = match stage {
+        BuildStage::PreBuild => &config.build.hooks.pre_build,
+        BuildStage::PostBuild => &config.build.hooks.post_build,
+    };
+    let mut inputs = Vec::new();
+    for hook in hooks {
+        inputs.extend(expand_globs(&config.root, &hook.inputs)?);
+    }
+    inputs.sort();
+    inputs.dedup();
+    Ok(inputs)
+}
+
+fn run_hook(root: &Path, hook: &BuildHook, name: &str) -> Result<()> {
+    let Some((program, args)) = hook.command.split_first() else {
+        eyre::bail!("build hook `{name}` has no command")
+    };
+    let output = Command::new(program)
+        .args(args)
+        .current_dir(root)
+        .output()
+        .wrap_err_with(|| format!("failed to run build hook `{name}`"))?;
+    if !output.status.success() {
+        let logs = [&output.stdout, &output.stderr]
+            .into_iter()
+            .map(|log| String::from_utf8_lossy(log).trim_end().to_string())
+            .filter(|log| !log.is_empty())
+            .collect::<Vec<_>>();
+        eyre::bail!("build hook `{name}` failed with {}:\n{}", output.status, logs.join("\n"));
+    }
+    Ok(())
+}
+
+/// Hashes the command and the contents of the input files of a hook.
+fn inputs_fingerprint(root: &Path, hook: &BuildHook) -> Result<B256> {
+    let mut hasher = Keccak256::new();
+    for arg in &hook.command {
+        hasher.update(arg.len().to_be_bytes());
+        hasher.update(arg.as_bytes());
+    }
+    let mut inputs = expand_globs(root, &hook.inputs)?;
+    inputs.sort();
+    inputs.dedup();
+    for path in inputs.iter().filter(|path| path.is_file()) {
+        let relative = path.strip_prefix(root).unwrap_or(path).to_string_lossy();
+        hasher.update(relative.len().to_be_bytes());
+        hasher.update(relative.as_bytes());
+        hasher.update(fs::read(path)?);
+    }
+    Ok(hasher.finalize())
+}
+
+/// Hashes the contents of the output files of a hook, returning `None` if one of them is missing.
+fn outputs_fingerprint(root: &Path, hook: &BuildHook) -> Result<Option<B256>> {
+    let mut hasher = Keccak256::new();
+    for path in &hook.outputs {
+        let path = root.join(path);
+        if !path.is_file() {
+            return Ok(None)
+        }
+        hasher.update(fs::read(&path)?);
+    }
+    Ok(Some(hasher.finalize()))
+}
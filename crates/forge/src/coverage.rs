@@ -6,9 +6,10 @@
 use foundry_common::fs;
 use semver::Version;
 use std::{
-    collections::hash_map,
+    collections::{hash_map, BTreeMap},
+    fmt::Write as _,
     io::Write,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
 };
 
 pub use foundry_evm::coverage::*;
@@ -74,6 +75,52 @@ fn report(&mut self, report: &CoverageReport) -> eyre::Result<()> {
     }
 }
 
+/// An experimental reporter that prints, for every file, how many of the external functions
+/// executed by the tests had their effects asserted on, and which ones never did.
+pub struct AssertionReporter {
+    /// The assertion table.
+    table: Table,
+    /// The totals of the entire project.
+    total: AssertionSummary,
+}
+
+impl Default for AssertionReporter {
+    fn default() -> Self {
+        let mut table = Table::new();
+        table.apply_modifier(UTF8_ROUND_CORNERS);
+
+        table.set_header(vec![
+            Cell::new("File"),
+            Cell::new("% Asserted Funcs"),
+            Cell::new("Executed But Never Checked"),
+        ]);
+
+        Self { table, total: AssertionSummary::default() }
+    }
+}
+
+impl CoverageReporter for AssertionReporter {
+    fn report(&mut self, report: &CoverageReport) -> eyre::Result<()> {
+        for (path, summary) in report.assertions_by_file() {
+            self.total.executed += summary.executed;
+            self.total.asserted += summary.asserted;
+            let mut row = Row::new();
+            row.add_cell(Cell::new(path.display()))
+                .add_cell(format_cell(summary.asserted, summary.executed))
+                .add_cell(Cell::new(summary.unchecked.join("\n")));
+            self.table.add_row(row);
+        }
+
+        let mut row = Row::new();
+        row.add_cell(Cell::new("Total"))
+            .add_cell(format_cell(self.total.asserted, self.total.executed))
+            .add_cell(Cell::new(""));
+        self.table.add_row(row);
+        sh_println!("\n{}", self.table)?;
+        Ok(())
+    }
+}
+
 fn format_cell(hits: usize, total: usize) -> Cell {
     let percentage = if total == 0 { 1. } else { hits as f64 / total as f64 };
 
@@ -178,6 +225,235 @@ fn report(&mut self, report: &CoverageReport) -> eyre::Result<()> {
     }
 }
 
+/// Writes the coverage report as HTML pages, which don't need any external tool to be generated.
+///
+/// The report is made of an index with the summary of every directory, the summary of the files of
+/// every directory, and the sources of every file annotated with their hits.
+pub struct HtmlReporter {
+    root: PathBuf,
+    destdir: PathBuf,
+}
+
+impl HtmlReporter {
+    /// Create a new HTML reporter, writing the pages in `destdir`.
+    pub fn new(root: PathBuf, destdir: PathBuf) -> Self {
+        Self { root, destdir }
+    }
+
+    /// Writes a page at the given path relative to the destination directory.
+    fn write_page(&self, path: &Path, title: &str, body: &str) -> eyre::Result<()> {
+        // Links are relative to the page, so that the report can be moved around.
+        let depth = path.components().count() - 1;
+        let root = "../".repeat(depth);
+        let page = format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{title} - Coverage report</title>
+<style>{HTML_STYLE}</style>
+</head>
+<body>
+<h1><a href="{root}index.html">Coverage report</a> - {title}</h1>
+{body}
+</body>
+</html>
+"#,
+            title = html_escape(title),
+        );
+        let path = self.destdir.join(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, page)?;
+        Ok(())
+    }
+}
+
+impl CoverageReporter for HtmlReporter {
+    fn report(&mut self, report: &CoverageReport) -> eyre::Result<()> {
+        // The summaries of the files, by directory.
+        let mut dirs = BTreeMap::<PathBuf, Vec<(PathBuf, CoverageSummary)>>::new();
+        for (path, items) in report.items_by_file() {
+            let summary = CoverageSummary::from_items(items.iter().copied());
+            let source = fs::read_to_string(self.root.join(path))?;
+            let page = html_page_path(path);
+            let title = path.display().to_string();
+            let body = format!(
+                "{}\n{}",
+                html_summary_table("File", &title, &[], &summary),
+                html_source(&source, &items)
+            );
+            let mut page_file = page.clone().into_os_string();
+            page_file.push(".html");
+            self.write_page(page_file.as_ref(), &title, &body)?;
+
+            let dir = page.parent().map(Path::to_path_buf).unwrap_or_default();
+            dirs.entry(dir).or_default().push((page, summary));
+        }
+
+        let mut rows = Vec::new();
+        let mut total = CoverageSummary::default();
+        for (dir, files) in &dirs {
+            let mut dir_total = CoverageSummary::default();
+            let mut file_rows = Vec::new();
+            for (page, summary) in files {
+                dir_total.merge(summary);
+                let name = page.file_name().unwrap_or_default().to_string_lossy();
+                file_rows.push((name.to_string(), format!("{name}.html"), summary.clone()));
+            }
+            total.merge(&dir_total);
+
+            // Files at the root of the project are listed in the index directly.
+            if dir.as_os_str().is_empty() {
+                rows.extend(file_rows);
+                continue
+            }
+            let title = dir.display().to_string();
+            let body = html_summary_table("File", &title, &file_rows, &dir_total);
+            self.write_page(&dir.join("index.html"), &title, &body)?;
+            rows.push((format!("{title}/"), format!("{title}/index.html"), dir_total));
+        }
+        let body = html_summary_table("Directory", "Total", &rows, &total);
+        self.write_page("index.html".as_ref(), "All files", &body)?;
+
+        sh_println!("Wrote HTML report to {}.", self.destdir.display())?;
+        Ok(())
+    }
+}
+
+const HTML_STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; }
+th, td { padding: 2px 8px; text-align: left; }
+.summary td, .summary th { border: 1px solid #ccc; }
+.high { background: #c8f0c8; }
+.medium { background: #fff0b0; }
+.low { background: #f8c8c8; }
+.none { color: #888; }
+.source { font-family: monospace; white-space: pre; margin-top: 1em; }
+.source td { padding: 0 8px; }
+.source .num, .source .hits { color: #888; text-align: right; }
+.source .hit { background: #dff5df; }
+.source .partial { background: #fff5cc; }
+.source .miss { background: #fbdcdc; }
+.taken { color: #080; }
+.not-taken { color: #c00; font-weight: bold; }
+";
+
+/// Returns the path of the page of a source file relative to the root of the report, without the
+/// `.html` extension.
+fn html_page_path(path: &Path) -> PathBuf {
+    // Don't write pages outside of the report, for sources outside of the project.
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+            Component::ParentDir => Some("_".to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns a table of the summaries of the given rows, each with a name, a link, and a summary,
+/// followed by the total.
+fn html_summary_table(
+    header: &str,
+    total_name: &str,
+    rows: &[(String, String, CoverageSummary)],
+    total: &CoverageSummary,
+) -> String {
+    let mut html = format!(
+        "<table class=\"summary\">\n<tr><th>{header}</th><th>Lines</th><th>Statements</th>\
+         <th>Branches</th><th>Functions</th></tr>\n"
+    );
+    let row = |html: &mut String, name: &str, summary: &CoverageSummary| {
+        let _ = writeln!(
+            html,
+            "<tr><td>{name}</td>{}{}{}{}</tr>",
+            html_summary_cell(summary.line_hits, summary.line_count),
+            html_summary_cell(summary.statement_hits, summary.statement_count),
+            html_summary_cell(summary.branch_hits, summary.branch_count),
+            html_summary_cell(summary.function_hits, summary.function_count),
+        );
+    };
+    for (name, link, summary) in rows {
+        row(&mut html, &format!("<a href=\"{link}\">{}</a>", html_escape(name)), summary);
+    }
+    row(&mut html, &format!("<b>{}</b>", html_escape(total_name)), total);
+    html.push_str("</table>");
+    html
+}
+
+/// Returns a cell of a summary table, colored like the cells of [`CoverageSummaryReporter`].
+fn html_summary_cell(hits: usize, total: usize) -> String {
+    let percentage = if total == 0 { 1. } else { hits as f64 / total as f64 };
+    let class = match percentage {
+        _ if total == 0 => "none",
+        _ if percentage < 0.5 => "low",
+        _ if percentage < 0.75 => "medium",
+        _ => "high",
+    };
+    format!("<td class=\"{class}\">{:.2}% ({hits}/{total})</td>", percentage * 100.)
+}
+
+/// Returns the source annotated with the hits of its lines and branches.
+///
+/// Lines with items that were never hit are highlighted as missed, and lines with branches that
+/// were never taken as partially covered.
+fn html_source(source: &str, items: &[&CoverageItem]) -> String {
+    let mut html = String::from("<table class=\"source\">\n");
+    for (i, text) in source.lines().enumerate() {
+        let line = i as u32 + 1;
+        let items = items.iter().filter(|item| item.loc.lines.start == line);
+
+        let mut hits = None::<u32>;
+        let mut missed = false;
+        let mut branches = String::new();
+        for item in items {
+            match item.kind {
+                CoverageItemKind::Line => hits = Some(hits.unwrap_or(0).max(item.hits)),
+                CoverageItemKind::Branch { path_id, .. } => {
+                    let (class, sign) =
+                        if item.hits > 0 { ("taken", "+") } else { ("not-taken", "-") };
+                    let _ = write!(
+                        branches,
+                        "<span class=\"{class}\" title=\"Path {path_id} taken {} times\">{sign}\
+                         </span>",
+                        item.hits
+                    );
+                    missed |= item.hits == 0;
+                }
+                CoverageItemKind::Statement | CoverageItemKind::Function { .. } => {
+                    missed |= item.hits == 0;
+                }
+            }
+        }
+
+        let class = match hits {
+            Some(0) => "miss",
+            _ if missed => "partial",
+            Some(_) => "hit",
+            None => "",
+        };
+        let hits = hits.map(|hits| hits.to_string()).unwrap_or_default();
+        if !branches.is_empty() {
+            branches = format!("[ {branches} ]");
+        }
+        let _ = writeln!(
+            html,
+            "<tr class=\"{class}\"><td class=\"num\">{line}</td><td class=\"hits\">{hits}</td>\
+             <td>{branches}</td><td>{}</td></tr>",
+            html_escape(text)
+        );
+    }
+    html.push_str("</table>");
+    html
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
 /// A super verbose reporter for debugging coverage while it is still unstable.
 pub struct DebugReporter;
 
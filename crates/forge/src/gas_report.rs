@@ -134,6 +134,23 @@ async fn analyze_node(&mut self, node: &CallTraceNode, decoder: &CallTraceDecode
         }
     }
 
+    /// Merges the calls recorded in another report, e.g. of another shard of the test run.
+    ///
+    /// The report must be [finalized](Self::finalize) afterwards.
+    pub fn merge(&mut self, other: Self) {
+        for (name, other) in other.contracts {
+            let contract = self.contracts.entry(name).or_default();
+            contract.gas = contract.gas.max(other.gas);
+            contract.size = contract.size.max(other.size);
+            for (function, sigs) in other.functions {
+                let function = contract.functions.entry(function).or_default();
+                for (sig, gas_info) in sigs {
+                    function.entry(sig).or_default().frames.extend(gas_info.frames);
+                }
+            }
+        }
+    }
+
     /// Finalizes the gas report by calculating the min, max, mean, and median for each function.
     #[must_use]
     pub fn finalize(mut self) -> Self {
@@ -8,6 +8,8 @@
 #[macro_use]
 extern crate tracing;
 
+pub mod build_hooks;
+
 pub mod coverage;
 
 pub mod gas_report;
@@ -18,9 +20,13 @@
 mod runner;
 pub use runner::ContractRunner;
 
+pub mod package;
+
 mod progress;
 pub mod result;
 
+pub mod shard;
+
 // TODO: remove
 pub use foundry_common::traits::TestFilter;
 pub use foundry_evm::*;
@@ -1,15 +1,15 @@
 //! Forge test runner for multiple contracts.
 
 use crate::{
-    progress::TestsProgress, result::SuiteResult, runner::LIBRARY_DEPLOYER, ContractRunner,
-    TestFilter,
+    progress::TestsProgress, result::SuiteResult, runner::LIBRARY_DEPLOYER, shard::TestShard,
+    ContractRunner, TestFilter,
 };
 use alloy_json_abi::{Function, JsonAbi};
 use alloy_primitives::{Address, Bytes, U256};
 use eyre::Result;
 use foundry_common::{get_contract_name, shell::verbosity, ContractsByArtifact, TestFunctionExt};
 use foundry_compilers::{
-    artifacts::{Contract, Libraries},
+    artifacts::{CompactContractBytecode, CompactContractBytecodeCow, Contract, Libraries},
     compilers::Compiler,
     Artifact, ArtifactId, ProjectCompileOutput,
 };
@@ -17,8 +17,8 @@
 use foundry_evm::{
     backend::Backend,
     decode::RevertDecoder,
-    executors::{Executor, ExecutorBuilder},
-    fork::CreateFork,
+    executors::{Executor, ExecutorBuilder, FailFast},
+    fork::{CreateFork, ForkCallCache},
     inspectors::CheatsConfig,
     opts::EvmOpts,
     revm,
@@ -28,7 +28,7 @@
 use rayon::prelude::*;
 use revm::primitives::SpecId;
 use std::{
-    borrow::Borrow,
+    borrow::{Borrow, Cow},
     collections::BTreeMap,
     fmt::Debug,
     path::Path,
@@ -61,6 +61,8 @@ pub struct MultiContractRunner {
 
     /// The fork to use at launch
     pub fork: Option<CreateFork>,
+    /// The shard of the test contracts to run, if sharding.
+    pub shard: Option<TestShard>,
 
     /// The base configuration for the test runner.
     pub tcfg: TestRunnerConfig,
@@ -86,7 +88,12 @@ pub fn matching_contracts<'a: 'b, 'b>(
         &'a self,
         filter: &'b dyn TestFilter,
     ) -> impl Iterator<Item = (&'a ArtifactId, &'a TestContract)> + 'b {
-        self.contracts.iter().filter(|&(id, c)| matches_contract(id, &c.abi, filter))
+        self.contracts.iter().filter(|&(id, c)| {
+            self.shard.is_none_or(|shard| shard.contains(id)) &&
+                filter.matches_path(&id.source) &&
+                filter.matches_contract(&id.name) &&
+                c.abi.functions().any(|func| self.is_matching_function(func, filter))
+        })
     }
 
     /// Returns an iterator over all test functions that match the filter.
@@ -96,7 +103,7 @@ pub fn matching_test_functions<'a: 'b, 'b>(
     ) -> impl Iterator<Item = &'a Function> + 'b {
         self.matching_contracts(filter)
             .flat_map(|(_, c)| c.abi.functions())
-            .filter(|func| is_matching_test(func, filter))
+            .filter(|func| self.is_matching_function(func, filter))
     }
 
     /// Returns an iterator over all test functions in contracts that match the filter.
@@ -120,7 +127,7 @@ pub fn list(&self, filter: &dyn TestFilter) -> BTreeMap<String, BTreeMap<String,
                 let tests = c
                     .abi
                     .functions()
-                    .filter(|func| is_matching_test(func, filter))
+                    .filter(|func| self.is_matching_function(func, filter))
                     .map(|func| func.name.clone())
                     .collect::<Vec<_>>();
                 (source, name, tests)
@@ -187,6 +194,8 @@ pub fn test(
             // Collect test suite results to stream at the end of test run.
             let results: Vec<(String, SuiteResult)> = contracts
                 .par_iter()
+                // Don't start new suites once a test failed with `--fail-fast`.
+                .filter(|_| !self.tcfg.fail_fast.should_stop())
                 .map(|&(id, contract)| {
                     let _guard = tokio_handle.enter();
                     tests_progress.inner.lock().start_suite_progress(&id.identifier());
@@ -216,6 +225,10 @@ pub fn test(
             });
         } else {
             contracts.par_iter().for_each(|&(id, contract)| {
+                // Don't start new suites once a test failed with `--fail-fast`.
+                if self.tcfg.fail_fast.should_stop() {
+                    return
+                }
                 let _guard = tokio_handle.enter();
                 let result = self.run_test_suite(id, contract, &db, filter, &tokio_handle, None);
                 let _ = tx.send((id.identifier(), result));
@@ -255,6 +268,11 @@ fn run_test_suite(
         );
         let r = runner.run_tests(filter);
 
+        // Also covers suites that failed before running any test, e.g. in `setUp`.
+        if r.failed() > 0 {
+            self.tcfg.fail_fast.record_fail();
+        }
+
         debug!(duration=?r.duration, "executed all tests in contract");
 
         r
@@ -282,6 +300,8 @@ pub struct TestRunnerConfig {
 
     /// Whether to collect coverage info
     pub coverage: bool,
+    /// Whether to record if the effects of the functions covered are asserted on
+    pub assertion_coverage: bool,
     /// Whether to collect debug info
     pub debug: bool,
     /// Whether to enable steps tracking in the tracer.
@@ -290,6 +310,10 @@ pub struct TestRunnerConfig {
     pub isolation: bool,
     /// Whether to enable Odyssey features.
     pub odyssey: bool,
+    /// The number of times to run each benchmark. If set, only benchmarks are run.
+    pub bench_runs: Option<u32>,
+    /// Stops the remaining tests after the first failure.
+    pub fail_fast: FailFast,
 }
 
 impl TestRunnerConfig {
@@ -302,9 +326,12 @@ pub fn reconfigure_with(&mut self, config: Arc<Config>) {
         self.spec_id = config.evm_spec_id();
         self.sender = config.sender;
         // self.coverage = N/A;
+        // self.assertion_coverage = N/A;
         // self.debug = N/A;
         // self.decode_internal = N/A;
         // self.isolation = N/A;
+        // self.bench_runs = N/A;
+        // self.fail_fast = N/A;
         self.odyssey = config.odyssey;
 
         self.config = config;
@@ -322,8 +349,10 @@ pub fn configure_executor(&self, executor: &mut Executor) {
         }
         inspector.tracing(self.trace_mode());
         inspector.collect_coverage(self.coverage);
+        inspector.collect_assertion_coverage(self.assertion_coverage);
         inspector.enable_isolation(self.isolation);
         inspector.odyssey(self.odyssey);
+        inspector.set_fork_call_cache(self.fork_call_cache());
         // inspector.set_create2_deployer(self.evm_opts.create2_deployer);
 
         // executor.env_mut().clone_from(&self.env);
@@ -351,9 +380,11 @@ pub fn executor(
                     .cheatcodes(cheats_config)
                     .trace_mode(self.trace_mode())
                     .coverage(self.coverage)
+                    .assertion_coverage(self.assertion_coverage)
                     .enable_isolation(self.isolation)
                     .odyssey(self.odyssey)
                     .create2_deployer(self.evm_opts.create2_deployer)
+                    .fork_call_cache(self.fork_call_cache())
             })
             .spec_id(self.spec_id)
             .gas_limit(self.evm_opts.gas_limit())
@@ -361,6 +392,16 @@ pub fn executor(
             .build(self.env.clone(), db)
     }
 
+    /// Returns `true` if the function should be run with this configuration: a benchmark when
+    /// benchmarking, a test otherwise.
+    pub fn is_matching_function(&self, func: &Function, filter: &dyn TestFilter) -> bool {
+        if self.bench_runs.is_some() {
+            is_matching_bench(func, filter)
+        } else {
+            is_matching_test(func, filter)
+        }
+    }
+
     fn trace_mode(&self) -> TraceMode {
         TraceMode::default()
             .with_debug(self.debug)
@@ -368,6 +409,12 @@ fn trace_mode(&self) -> TraceMode {
             .with_verbosity(self.evm_opts.verbosity)
             .with_state_changes(verbosity() > 4)
     }
+
+    /// Returns the cache of fork calls, unless every call must be executed to collect coverage or
+    /// debug information.
+    fn fork_call_cache(&self) -> Option<ForkCallCache> {
+        self.evm_opts.fork_call_cache.clone().filter(|_| !self.coverage && !self.debug)
+    }
 }
 
 /// Builder used for instantiating the multi-contract runner
@@ -387,6 +434,8 @@ pub struct MultiContractRunnerBuilder {
     pub config: Arc<Config>,
     /// Whether or not to collect coverage info
     pub coverage: bool,
+    /// Whether or not to record if the effects of the functions covered are asserted on
+    pub assertion_coverage: bool,
     /// Whether or not to collect debug info
     pub debug: bool,
     /// Whether to enable steps tracking in the tracer.
@@ -395,6 +444,14 @@ pub struct MultiContractRunnerBuilder {
     pub isolation: bool,
     /// Whether to enable Odyssey features.
     pub odyssey: bool,
+    /// The number of times to run each benchmark, if benchmarking.
+    pub bench_runs: Option<u32>,
+    /// Whether to stop the remaining tests after the first failure.
+    pub fail_fast: bool,
+    /// The shard of the test contracts to run, if sharding.
+    pub shard: Option<TestShard>,
+    /// The bytecode of the contracts instrumented for coverage, replacing the compiled one.
+    pub instrumented_code: BTreeMap<ArtifactId, CompactContractBytecode>,
 }
 
 impl MultiContractRunnerBuilder {
@@ -406,10 +463,15 @@ pub fn new(config: Arc<Config>) -> Self {
             evm_spec: Default::default(),
             fork: Default::default(),
             coverage: Default::default(),
+            assertion_coverage: Default::default(),
             debug: Default::default(),
             isolation: Default::default(),
             decode_internal: Default::default(),
             odyssey: Default::default(),
+            bench_runs: Default::default(),
+            fail_fast: Default::default(),
+            shard: Default::default(),
+            instrumented_code: Default::default(),
         }
     }
 
@@ -438,6 +500,11 @@ pub fn set_coverage(mut self, enable: bool) -> Self {
         self
     }
 
+    pub fn set_assertion_coverage(mut self, enable: bool) -> Self {
+        self.assertion_coverage = enable;
+        self
+    }
+
     pub fn set_debug(mut self, enable: bool) -> Self {
         self.debug = enable;
         self
@@ -458,6 +525,34 @@ pub fn odyssey(mut self, enable: bool) -> Self {
         self
     }
 
+    /// Runs only benchmarks, each `runs` times, instead of tests.
+    pub fn bench_runs(mut self, runs: Option<u32>) -> Self {
+        self.bench_runs = runs;
+        self
+    }
+
+    /// Stops the remaining tests after the first failure, see [`FailFast`].
+    pub fn fail_fast(mut self, enable: bool) -> Self {
+        self.fail_fast = enable;
+        self
+    }
+
+    /// Runs only the test contracts of the given shard.
+    pub fn shard(mut self, shard: Option<TestShard>) -> Self {
+        self.shard = shard;
+        self
+    }
+
+    /// Runs the given instrumented bytecode instead of the compiled one of the contracts, see
+    /// [`Instrumenter`](crate::coverage::instrument::Instrumenter).
+    pub fn instrumented_code(
+        mut self,
+        code: BTreeMap<ArtifactId, CompactContractBytecode>,
+    ) -> Self {
+        self.instrumented_code = code;
+        self
+    }
+
     /// Given an EVM, proceeds to return a runner which is able to execute all tests
     /// against that evm
     pub fn build<C: Compiler<CompilerContract = Contract>>(
@@ -469,7 +564,15 @@ pub fn build<C: Compiler<CompilerContract = Contract>>(
     ) -> Result<MultiContractRunner> {
         let contracts = output
             .artifact_ids()
-            .map(|(id, v)| (id.with_stripped_file_prefixes(root), v))
+            .map(|(id, v)| {
+                let id = id.with_stripped_file_prefixes(root);
+                let mut contract = CompactContractBytecodeCow::from(v);
+                if let Some(code) = self.instrumented_code.get(&id) {
+                    contract.bytecode = code.bytecode.clone().map(Cow::Owned);
+                    contract.deployed_bytecode = code.deployed_bytecode.clone().map(Cow::Owned);
+                }
+                (id, contract)
+            })
             .collect();
         let linker = Linker::new(root, contracts);
 
@@ -495,9 +598,9 @@ pub fn build<C: Compiler<CompilerContract = Contract>>(
         for (id, contract) in linked_contracts.iter() {
             let Some(abi) = &contract.abi else { continue };
 
-            // if it's a test, link it and add to deployable contracts
+            // if it's a test or a benchmark, link it and add to deployable contracts
             if abi.constructor.as_ref().map(|c| c.inputs.is_empty()).unwrap_or(true) &&
-                abi.functions().any(|func| func.name.is_any_test())
+                abi.functions().any(|func| func.name.is_any_test() || func.is_bench())
             {
                 let Some(bytecode) =
                     contract.get_bytecode_bytes().map(|b| b.into_owned()).filter(|b| !b.is_empty())
@@ -520,6 +623,7 @@ pub fn build<C: Compiler<CompilerContract = Contract>>(
             libraries,
 
             fork: self.fork,
+            shard: self.shard,
 
             tcfg: TestRunnerConfig {
                 evm_opts,
@@ -528,11 +632,14 @@ pub fn build<C: Compiler<CompilerContract = Contract>>(
                 sender: self.sender.unwrap_or(self.config.sender),
 
                 coverage: self.coverage,
+                assertion_coverage: self.assertion_coverage,
                 debug: self.debug,
                 decode_internal: self.decode_internal,
                 inline_config: Arc::new(InlineConfig::new_parsed(output, &self.config)?),
                 isolation: self.isolation,
                 odyssey: self.odyssey,
+                bench_runs: self.bench_runs,
+                fail_fast: FailFast::new(self.fail_fast),
 
                 config: self.config,
             },
@@ -545,7 +652,18 @@ pub fn matches_contract(id: &ArtifactId, abi: &JsonAbi, filter: &dyn TestFilter)
         abi.functions().any(|func| is_matching_test(func, filter))
 }
 
+/// Returns `true` if the contract matches the given filter and has benchmarks that match it.
+pub fn matches_bench_contract(id: &ArtifactId, abi: &JsonAbi, filter: &dyn TestFilter) -> bool {
+    (filter.matches_path(&id.source) && filter.matches_contract(&id.name)) &&
+        abi.functions().any(|func| is_matching_bench(func, filter))
+}
+
 /// Returns `true` if the function is a test function that matches the given filter.
 pub(crate) fn is_matching_test(func: &Function, filter: &dyn TestFilter) -> bool {
     func.is_any_test() && filter.matches_test(&func.signature())
 }
+
+/// Returns `true` if the function is a benchmark that matches the given filter.
+pub(crate) fn is_matching_bench(func: &Function, filter: &dyn TestFilter) -> bool {
+    func.is_bench() && filter.matches_test(&func.signature())
+}
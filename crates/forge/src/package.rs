@@ -0,0 +1,588 @@
+//! Contract packages, published with `forge publish` and installed with
+//! `forge install --registry`.
+//!
+//! A package bundles the Solidity sources of a project, the other files of the project they import
+//! and its [metadata files](PACKAGE_METADATA_FILES) with the compiler settings they were built
+//! with, and a checksum of all of them that is verified when the package is installed.
+//!
+//! A registry is either a local directory or an HTTP(S) URL with the following layout:
+//!
+//! ```text
+//! <registry>/<name>/index.json        # the published versions of the package
+//! <registry>/<name>/<version>.json    # the package
+//! ```
+//!
+//! The checksums of the installed packages are pinned in the [`PACKAGE_LOCK`] file of the project
+//! on first install, so that a package changed in the registry is detected on later installs.
+
+use alloy_primitives::{keccak256, B256};
+use eyre::{Context, Result};
+use foundry_common::fs;
+use foundry_compilers::artifacts::EvmVersion;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+/// The name of the file that records the installed package in the dependency directory.
+pub const PACKAGE_MANIFEST: &str = "forge-package.json";
+
+/// The name of the file, in the project root, that pins the checksums of the installed packages.
+pub const PACKAGE_LOCK: &str = "forge-packages.lock";
+
+/// The files of the project root that are published with its sources, if they exist.
+pub const PACKAGE_METADATA_FILES: &[&str] =
+    &["foundry.toml", "remappings.txt", "README.md", "LICENSE", "LICENSE-MIT", "LICENSE-APACHE"];
+
+/// How many times the index of a remote registry is updated again after a concurrent publish.
+const INDEX_UPDATE_RETRIES: usize = 5;
+
+/// How long to wait for a concurrent publish to a local registry to finish.
+const LOCAL_LOCK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A published contract package.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Package {
+    /// The name of the package, also the name of the directory it is installed in.
+    pub name: String,
+    /// The version of the package.
+    pub version: Version,
+    /// The settings the sources were built with when the package was published.
+    pub build: PackageBuild,
+    /// The sources of the package, keyed by their path relative to the package root.
+    pub sources: BTreeMap<String, String>,
+    /// The checksum of the package, see [`Package::compute_checksum`].
+    pub checksum: B256,
+}
+
+/// The settings a package was built with.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageBuild {
+    /// The versions of solc the sources were compiled with.
+    pub solc: Vec<Version>,
+    pub evm_version: EvmVersion,
+    pub optimizer: bool,
+    pub optimizer_runs: Option<usize>,
+    pub via_ir: bool,
+}
+
+impl Package {
+    /// Creates a package and computes its checksum.
+    pub fn new(
+        name: String,
+        version: Version,
+        build: PackageBuild,
+        sources: BTreeMap<String, String>,
+    ) -> Result<Self> {
+        validate_name(&name)?;
+        let mut package = Self { name, version, build, sources, checksum: B256::ZERO };
+        package.checksum = package.compute_checksum();
+        Ok(package)
+    }
+
+    /// Returns the keccak256 hash of the canonical JSON encoding of the package without its
+    /// checksum.
+    pub fn compute_checksum(&self) -> B256 {
+        #[derive(Serialize)]
+        struct Unsigned<'a> {
+            name: &'a str,
+            version: &'a Version,
+            build: &'a PackageBuild,
+            sources: &'a BTreeMap<String, String>,
+        }
+        let unsigned = Unsigned {
+            name: &self.name,
+            version: &self.version,
+            build: &self.build,
+            sources: &self.sources,
+        };
+        keccak256(serde_json::to_vec(&unsigned).expect("package is serializable"))
+    }
+
+    /// Verifies the checksum of the package, and that all its sources are relative paths inside
+    /// of the package.
+    pub fn verify(&self) -> Result<()> {
+        validate_name(&self.name)?;
+        let checksum = self.compute_checksum();
+        if checksum != self.checksum {
+            eyre::bail!(
+                "checksum mismatch for package {}@{}: expected {}, got {checksum}",
+                self.name,
+                self.version,
+                self.checksum
+            );
+        }
+        for path in self.sources.keys() {
+            let path = std::path::Path::new(path);
+            if !path.components().all(|c| matches!(c, std::path::Component::Normal(_))) {
+                eyre::bail!(
+                    "package {}@{} contains an invalid source path: {}",
+                    self.name,
+                    self.version,
+                    path.display()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the manifest of the package, which is the package without its sources.
+    pub fn manifest(&self) -> Self {
+        Self { sources: BTreeMap::new(), ..self.clone() }
+    }
+}
+
+/// Returns an error if `name` is not a valid package name.
+///
+/// Package names are made of lowercase alphanumeric characters, `-` and `_`.
+pub fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty() &&
+        name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if !valid {
+        eyre::bail!(
+            "invalid package name `{name}`, only lowercase alphanumeric characters, `-` and `_` \
+             are allowed"
+        );
+    }
+    Ok(())
+}
+
+/// The checksums of the packages installed in a project, pinned on first install.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackageLock(BTreeMap<String, LockedPackage>);
+
+/// A package pinned in the [`PackageLock`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedPackage {
+    pub version: Version,
+    pub checksum: B256,
+}
+
+impl PackageLock {
+    /// Loads the lock from the given file, or starts empty if it does not exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default())
+        }
+        fs::read_json_file(path).wrap_err_with(|| format!("invalid lock file {}", path.display()))
+    }
+
+    /// Writes the lock to the given file.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        Ok(fs::write_pretty_json_file(path, self)?)
+    }
+
+    /// Returns the version of the package that is pinned, if any.
+    pub fn version(&self, name: &str) -> Option<&Version> {
+        self.0.get(name).map(|locked| &locked.version)
+    }
+
+    /// Pins the checksum of the package if its version wasn't installed before, otherwise
+    /// verifies that it matches the pinned checksum.
+    pub fn pin(&mut self, package: &Package) -> Result<()> {
+        if let Some(locked) = self.0.get(&package.name) {
+            if locked.version == package.version && locked.checksum != package.checksum {
+                eyre::bail!(
+                    "checksum of package {}@{} changed since it was first installed: expected {}, \
+                     got {}; if the change is expected, remove it from {PACKAGE_LOCK}",
+                    package.name,
+                    package.version,
+                    locked.checksum,
+                    package.checksum
+                );
+            }
+        }
+        self.0.insert(
+            package.name.clone(),
+            LockedPackage { version: package.version.clone(), checksum: package.checksum },
+        );
+        Ok(())
+    }
+}
+
+/// A package requested from a registry: `<name>` or `<name>@<version>`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PackageSpec {
+    pub name: String,
+    /// The version, the latest published version if not set.
+    pub version: Option<Version>,
+}
+
+impl FromStr for PackageSpec {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, version) = match s.split_once('@') {
+            Some((name, version)) => {
+                let version = version.strip_prefix('v').unwrap_or(version);
+                let version = Version::parse(version)
+                    .wrap_err_with(|| format!("invalid version of package `{s}`"))?;
+                (name, Some(version))
+            }
+            None => (s, None),
+        };
+        validate_name(name)?;
+        Ok(Self { name: name.to_string(), version })
+    }
+}
+
+impl fmt::Display for PackageSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)?;
+        if let Some(version) = &self.version {
+            write!(f, "@{version}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A package registry, see the [module docs](self) for its layout.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Registry {
+    /// A local directory.
+    Local(PathBuf),
+    /// An HTTP(S) server, packages are published with `PUT` requests.
+    Remote(String),
+}
+
+impl FromStr for Registry {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.starts_with("http://") || s.starts_with("https://") {
+            Ok(Self::Remote(s.trim_end_matches('/').to_string()))
+        } else {
+            Ok(Self::Local(PathBuf::from(s)))
+        }
+    }
+}
+
+impl fmt::Display for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Local(path) => path.display().fmt(f),
+            Self::Remote(url) => url.fmt(f),
+        }
+    }
+}
+
+impl Registry {
+    /// Returns the published versions of the package `name`, sorted in ascending order.
+    pub async fn versions(&self, name: &str) -> Result<Vec<Version>> {
+        let Some(index) = self.read(&format!("{name}/index.json")).await? else {
+            return Ok(Vec::new())
+        };
+        let mut versions: Vec<Version> = serde_json::from_slice(&index)
+            .wrap_err_with(|| format!("invalid index of package `{name}` in {self}"))?;
+        versions.sort();
+        Ok(versions)
+    }
+
+    /// Fetches the package matching `spec` and verifies its integrity.
+    pub async fn fetch(&self, spec: &PackageSpec) -> Result<Package> {
+        let version = match &spec.version {
+            Some(version) => version.clone(),
+            None => self
+                .versions(&spec.name)
+                .await?
+                .pop()
+                .ok_or_else(|| eyre::eyre!("package `{}` not found in {self}", spec.name))?,
+        };
+        let data = self
+            .read(&format!("{}/{version}.json", spec.name))
+            .await?
+            .ok_or_else(|| eyre::eyre!("package `{}@{version}` not found in {self}", spec.name))?;
+        let package: Package = serde_json::from_slice(&data)
+            .wrap_err_with(|| format!("invalid package `{}@{version}`", spec.name))?;
+        if package.name != spec.name || package.version != version {
+            eyre::bail!(
+                "registry returned package `{}@{}` instead of `{}@{version}`",
+                package.name,
+                package.version,
+                spec.name
+            );
+        }
+        package.verify()?;
+        Ok(package)
+    }
+
+    /// Publishes the package, failing if its version was already published.
+    ///
+    /// The package file is only created if it doesn't exist, and the index is only replaced if it
+    /// didn't change since it was read: local registries are locked while publishing, and remote
+    /// ones are sent conditional requests. Concurrent publishes therefore don't drop each other's
+    /// versions from the index.
+    pub async fn publish(&self, package: &Package, token: Option<&str>) -> Result<()> {
+        let name = &package.name;
+        let _lock = match self {
+            Self::Local(dir) => Some(LocalLock::acquire(&dir.join(name))?),
+            Self::Remote(_) => None,
+        };
+
+        let already_published =
+            || eyre::eyre!("package `{name}@{}` is already published in {self}", package.version);
+        if self.versions(name).await?.contains(&package.version) {
+            return Err(already_published())
+        }
+        let data = serde_json::to_vec_pretty(package)?;
+        let path = format!("{name}/{}.json", package.version);
+        if !self.write(&path, data, token, Precondition::Absent).await? {
+            return Err(already_published())
+        }
+
+        let path = format!("{name}/index.json");
+        for _ in 0..INDEX_UPDATE_RETRIES {
+            let (index, etag) = self.read_with_etag(&path).await?;
+            let mut versions: Vec<Version> = match &index {
+                Some(index) => serde_json::from_slice(index)
+                    .wrap_err_with(|| format!("invalid index of package `{name}` in {self}"))?,
+                None => Vec::new(),
+            };
+            if !versions.contains(&package.version) {
+                versions.push(package.version.clone());
+                versions.sort();
+            }
+            let precondition = match (index, etag) {
+                (None, _) => Precondition::Absent,
+                (Some(_), Some(etag)) => Precondition::Matches(etag),
+                (Some(_), None) => Precondition::None,
+            };
+            let data = serde_json::to_vec_pretty(&versions)?;
+            if self.write(&path, data, token, precondition).await? {
+                return Ok(())
+            }
+        }
+        eyre::bail!(
+            "the index of package `{name}` in {self} is being updated concurrently, try again"
+        )
+    }
+
+    /// Reads the file at `path` in the registry, returns `None` if it does not exist.
+    async fn read(&self, path: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self.read_with_etag(path).await?.0)
+    }
+
+    /// Reads the file at `path` in the registry along with its `ETag` if the registry is remote
+    /// and returned one.
+    async fn read_with_etag(&self, path: &str) -> Result<(Option<Vec<u8>>, Option<String>)> {
+        match self {
+            Self::Local(dir) => {
+                let path = dir.join(path);
+                if !path.is_file() {
+                    return Ok((None, None))
+                }
+                Ok((Some(fs::read(path)?), None))
+            }
+            Self::Remote(url) => {
+                let res = reqwest::get(format!("{url}/{path}")).await?;
+                if res.status() == reqwest::StatusCode::NOT_FOUND {
+                    return Ok((None, None))
+                }
+                let res = res.error_for_status()?;
+                let etag = res
+                    .headers()
+                    .get(reqwest::header::ETAG)
+                    .and_then(|etag| etag.to_str().ok())
+                    .map(str::to_string);
+                Ok((Some(res.bytes().await?.to_vec()), etag))
+            }
+        }
+    }
+
+    /// Writes `data` to the file at `path` in the registry if the precondition holds, returns
+    /// whether it was written.
+    async fn write(
+        &self,
+        path: &str,
+        data: Vec<u8>,
+        token: Option<&str>,
+        precondition: Precondition,
+    ) -> Result<bool> {
+        match self {
+            Self::Local(dir) => {
+                // Local registries are locked while publishing, only creation is checked.
+                let path = dir.join(path);
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                if matches!(precondition, Precondition::Absent) && path.exists() {
+                    return Ok(false)
+                }
+                // Replace the file atomically, so that readers never see it partially written.
+                let tmp = path.with_extension("json.tmp");
+                fs::write(&tmp, data)?;
+                std::fs::rename(&tmp, &path)
+                    .wrap_err_with(|| format!("failed to write {}", path.display()))?;
+            }
+            Self::Remote(url) => {
+                let mut req = reqwest::Client::new().put(format!("{url}/{path}")).body(data);
+                if let Some(token) = token {
+                    req = req.bearer_auth(token);
+                }
+                req = match precondition {
+                    Precondition::Absent => req.header(reqwest::header::IF_NONE_MATCH, "*"),
+                    Precondition::Matches(etag) => req.header(reqwest::header::IF_MATCH, etag),
+                    Precondition::None => req,
+                };
+                let res = req.send().await?;
+                if res.status() == reqwest::StatusCode::PRECONDITION_FAILED {
+                    return Ok(false)
+                }
+                res.error_for_status()?;
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// The condition for a write to the registry to happen.
+enum Precondition {
+    /// The file must not exist.
+    Absent,
+    /// The file must have the given `ETag`.
+    Matches(String),
+    /// The file is written unconditionally.
+    None,
+}
+
+/// A lock on a package of a local registry, released when dropped.
+struct LocalLock {
+    path: PathBuf,
+}
+
+impl LocalLock {
+    fn acquire(dir: &Path) -> Result<Self> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(".lock");
+        let deadline = Instant::now() + LOCAL_LOCK_TIMEOUT;
+        loop {
+            match std::fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => return Ok(Self { path }),
+                Err(err) if err.kind() == ErrorKind::AlreadyExists && Instant::now() < deadline => {
+                    std::thread::sleep(Duration::from_millis(50));
+                }
+                Err(err) => {
+                    return Err(err).wrap_err_with(|| {
+                        format!(
+                            "failed to lock {}; if no other publish is running, remove the file",
+                            path.display()
+                        )
+                    })
+                }
+            }
+        }
+    }
+}
+
+impl Drop for LocalLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package() -> Package {
+        Package::new(
+            "my-lib".to_string(),
+            Version::new(1, 0, 0),
+            PackageBuild {
+                solc: vec![Version::new(0, 8, 28)],
+                evm_version: EvmVersion::Cancun,
+                optimizer: false,
+                optimizer_runs: None,
+                via_ir: false,
+            },
+            BTreeMap::from([("src/Lib.sol".to_string(), "contract Lib {}".to_string())]),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn verify_checksum() {
+        let mut package = package();
+        package.verify().unwrap();
+
+        package.sources.insert("src/Lib.sol".to_string(), "contract Evil {}".to_string());
+        assert!(package.verify().unwrap_err().to_string().contains("checksum mismatch"));
+
+        let mut package = Package::new(
+            package.name,
+            package.version,
+            package.build,
+            BTreeMap::from([("../Lib.sol".to_string(), String::new())]),
+        )
+        .unwrap();
+        assert!(package.verify().unwrap_err().to_string().contains("invalid source path"));
+        package.checksum = B256::ZERO;
+        assert!(package.verify().is_err());
+    }
+
+    #[test]
+    fn pin_checksum() {
+        let mut lock = PackageLock::default();
+        let mut package = package();
+        lock.pin(&package).unwrap();
+        assert_eq!(lock.version("my-lib"), Some(&Version::new(1, 0, 0)));
+
+        // The same version with other sources.
+        let original = package.checksum;
+        package.sources.insert("src/Lib.sol".to_string(), "contract Evil {}".to_string());
+        package.checksum = package.compute_checksum();
+        let err = lock.pin(&package).unwrap_err().to_string();
+        assert!(err.contains("changed since it was first installed"), "{err}");
+        assert_eq!(lock.0["my-lib"].checksum, original);
+
+        // Another version is pinned on install.
+        package.version = Version::new(1, 1, 0);
+        package.checksum = package.compute_checksum();
+        lock.pin(&package).unwrap();
+        assert_eq!(lock.version("my-lib"), Some(&Version::new(1, 1, 0)));
+    }
+
+    #[test]
+    fn publish_concurrently() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = Registry::Local(dir.path().to_path_buf());
+        let publishes = (0..8)
+            .map(|minor| {
+                let registry = registry.clone();
+                std::thread::spawn(move || {
+                    let mut package = package();
+                    package.version = Version::new(1, minor, 0);
+                    package.checksum = package.compute_checksum();
+                    tokio::runtime::Runtime::new()
+                        .unwrap()
+                        .block_on(registry.publish(&package, None))
+                })
+            })
+            .collect::<Vec<_>>();
+        for publish in publishes {
+            publish.join().unwrap().unwrap();
+        }
+
+        let versions =
+            tokio::runtime::Runtime::new().unwrap().block_on(registry.versions("my-lib")).unwrap();
+        assert_eq!(versions, (0..8).map(|minor| Version::new(1, minor, 0)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn parse_spec() {
+        let spec: PackageSpec = "my-lib@v1.2.3".parse().unwrap();
+        assert_eq!(spec.name, "my-lib");
+        assert_eq!(spec.version, Some(Version::new(1, 2, 3)));
+        assert_eq!(spec.to_string(), "my-lib@1.2.3");
+        assert_eq!("my-lib".parse::<PackageSpec>().unwrap().version, None);
+        assert!("My/Lib".parse::<PackageSpec>().is_err());
+        assert!("my-lib@latest".parse::<PackageSpec>().is_err());
+    }
+}
@@ -13,14 +13,18 @@
 use foundry_evm::{
     coverage::HitMaps,
     decode::SkipReason,
-    executors::{invariant::InvariantMetrics, RawCallResult},
-    fuzz::{CounterExample, FuzzCase, FuzzFixtures, FuzzTestResult},
+    executors::{invariant::InvariantMetrics, FailFast, RawCallResult},
+    fuzz::{
+        strategies::FuzzDictionaryFile, CounterExample, FuzzCase, FuzzFixtures, FuzzTestResult,
+    },
+    inspectors::cheatcodes::MemorySafetyViolation,
     traces::{CallTraceArena, CallTraceDecoder, TraceKind, Traces},
 };
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, HashMap as Map},
     fmt::{self, Write},
+    path::PathBuf,
     time::Duration,
 };
 use yansi::Paint;
@@ -174,6 +178,9 @@ pub fn ensure_ok(&self, silent: bool) -> eyre::Result<()> {
             sh_println!("Encountered {failed} failing {term} in {suite_name}")?;
             for (name, result) in suite.failures() {
                 sh_println!("{}", result.short_result(name))?;
+                if let Some(dir) = &result.failure_artifacts {
+                    sh_println!("  Artifacts: {}", dir.display())?;
+                }
             }
             sh_println!()?;
         }
@@ -202,7 +209,7 @@ pub fn remove_first(&mut self) -> Option<(String, String, TestResult)> {
 }
 
 /// A set of test results for a single test suite, which is all the tests in a single contract.
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SuiteResult {
     /// Wall clock time it took to execute all tests in this suite.
     #[serde(with = "humantime_serde")]
@@ -420,6 +427,27 @@ pub struct TestResult {
     /// Deprecated cheatcodes (mapped to their replacements, if any) used in current test.
     #[serde(skip)]
     pub deprecated_cheatcodes: HashMap<&'static str, Option<&'static str>>,
+
+    /// The learned fuzz dictionary, if it should be exported.
+    #[serde(skip)]
+    pub fuzz_dictionary: Option<FuzzDictionaryFile>,
+
+    /// The directory the debugging artifacts of a failed test were written to, if any.
+    #[serde(skip)]
+    pub failure_artifacts: Option<PathBuf>,
+
+    /// The peak bytes of memory and stack used by the interpreter, if they were recorded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peak_memory: Option<usize>,
+
+    /// The number of times the test was run again after a transient RPC error, see
+    /// `vm.retryOnRpcError`.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub rpc_retries: u32,
+
+    /// The first violation of the memory model of solc, when memory safety checks are enabled.
+    #[serde(skip)]
+    pub memory_safety_violation: Option<MemorySafetyViolation>,
 }
 
 impl fmt::Display for TestResult {
@@ -488,6 +516,14 @@ pub fn fail(reason: String) -> Self {
         Self { status: TestStatus::Failure, reason: Some(reason), ..Default::default() }
     }
 
+    /// Creates the result of a test that was not run because another test failed, see
+    /// [`FailFast`].
+    pub fn cancelled() -> Self {
+        let mut result = Self::default();
+        result.cancel();
+        result
+    }
+
     /// Creates a test setup result.
     pub fn setup_result(setup: TestSetup) -> Self {
         Self {
@@ -542,6 +578,20 @@ pub fn single_result(
             self.breakpoints = cheatcodes.breakpoints;
             self.gas_snapshots = cheatcodes.gas_snapshots;
             self.deprecated_cheatcodes = cheatcodes.deprecated;
+            if let Some(memory_usage) = cheatcodes.memory_usage {
+                self.peak_memory = Some(memory_usage.peak);
+                // The limit fails the test even if the reverted frame was caught.
+                if let Some(reason) = memory_usage.exceeded {
+                    self.status = TestStatus::Failure;
+                    self.reason = Some(reason);
+                }
+            }
+            // Violations fail the test even if the reverted frame was caught.
+            if let Some(violation) = cheatcodes.memory_safety.violation {
+                self.status = TestStatus::Failure;
+                self.reason = Some(violation.to_string());
+                self.memory_safety_violation = Some(violation);
+            }
         }
     }
 
@@ -574,6 +624,23 @@ pub fn fuzz_result(&mut self, result: FuzzTestResult) {
         self.gas_report_traces = result.gas_report_traces.into_iter().map(|t| vec![t]).collect();
         self.breakpoints = result.breakpoints.unwrap_or_default();
         self.deprecated_cheatcodes = result.deprecated_cheatcodes;
+        self.fuzz_dictionary = result.dictionary;
+    }
+
+    /// Returns the result for a benchmark, from the gas used and the wall time, in nanoseconds, of
+    /// every run. Merges the execution results of the last run in initial setup results.
+    pub fn bench_result(
+        &mut self,
+        gas_samples: &mut [u64],
+        time_samples: &mut [u64],
+        raw_call_result: RawCallResult,
+    ) {
+        self.single_result(true, None, raw_call_result);
+        self.kind = TestKind::Bench {
+            runs: gas_samples.len(),
+            gas: BenchStats::new(gas_samples),
+            time: BenchStats::new(time_samples),
+        };
     }
 
     /// Returns the skipped result for invariant test.
@@ -637,6 +704,13 @@ pub fn invariant_result(
         self.gas_report_traces = gas_report_traces;
     }
 
+    /// Marks the test as skipped because it was stopped after another test failed, see
+    /// [`FailFast`].
+    pub fn cancel(&mut self) {
+        self.status = TestStatus::Skipped;
+        self.reason = Some(FailFast::CANCELLED.to_string());
+    }
+
     /// Returns `true` if this is the result of a fuzz test
     pub fn is_fuzz(&self) -> bool {
         matches!(self.kind, TestKind::Fuzz { .. })
@@ -644,7 +718,14 @@ pub fn is_fuzz(&self) -> bool {
 
     /// Formats the test result into a string (for printing).
     pub fn short_result(&self, name: &str) -> String {
-        format!("{self} {name} {}", self.kind.report())
+        let mut s = format!("{self} {name} {}", self.kind.report());
+        if let Some(peak) = self.peak_memory {
+            write!(s, " (peak memory: {peak} bytes)").unwrap();
+        }
+        if self.rpc_retries > 0 {
+            write!(s, " (rpc retries: {})", self.rpc_retries).unwrap();
+        }
+        s
     }
 
     /// Merges the given raw call result into `self`.
@@ -667,6 +748,7 @@ pub enum TestKindReport {
     Unit { gas: u64 },
     Fuzz { runs: usize, mean_gas: u64, median_gas: u64 },
     Invariant { runs: usize, calls: usize, reverts: usize, metrics: Map<String, InvariantMetrics> },
+    Bench { runs: usize, gas: BenchStats, time: BenchStats },
 }
 
 impl fmt::Display for TestKindReport {
@@ -681,6 +763,22 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             Self::Invariant { runs, calls, reverts, metrics: _ } => {
                 write!(f, "(runs: {runs}, calls: {calls}, reverts: {reverts})")
             }
+            Self::Bench { runs, gas, time } => {
+                let ns = Duration::from_nanos;
+                write!(
+                    f,
+                    "(runs: {runs}, gas ~: {} [{}, {}], p95: {}, ",
+                    gas.median, gas.ci_low, gas.ci_high, gas.p95
+                )?;
+                write!(
+                    f,
+                    "time ~: {:?} [{:?}, {:?}], p95: {:?})",
+                    ns(time.median),
+                    ns(time.ci_low),
+                    ns(time.ci_high),
+                    ns(time.p95)
+                )
+            }
         }
     }
 }
@@ -694,6 +792,7 @@ pub fn gas(&self) -> u64 {
             Self::Fuzz { median_gas, .. } => median_gas,
             // We return 0 since it's not applicable
             Self::Invariant { .. } => 0,
+            Self::Bench { gas, .. } => gas.median,
         }
     }
 }
@@ -713,6 +812,8 @@ pub enum TestKind {
     },
     /// An invariant test.
     Invariant { runs: usize, calls: usize, reverts: usize, metrics: Map<String, InvariantMetrics> },
+    /// A benchmark.
+    Bench { runs: usize, gas: BenchStats, time: BenchStats },
 }
 
 impl Default for TestKind {
@@ -735,10 +836,62 @@ pub fn report(&self) -> TestKindReport {
                 reverts: *reverts,
                 metrics: HashMap::default(),
             },
+            Self::Bench { runs, gas, time } => {
+                TestKindReport::Bench { runs: *runs, gas: *gas, time: *time }
+            }
         }
     }
 }
 
+/// Summary statistics of the samples of a benchmark.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BenchStats {
+    pub mean: u64,
+    pub median: u64,
+    /// The 95th percentile.
+    pub p95: u64,
+    /// The bounds of the 95% confidence interval of the median.
+    pub ci_low: u64,
+    pub ci_high: u64,
+}
+
+impl BenchStats {
+    /// Computes the statistics of the given samples, sorting them.
+    ///
+    /// The confidence interval of the median doesn't assume any distribution of the samples: its
+    /// bounds are the samples ranked `n/2 ± 1.96·√n/2`.
+    pub fn new(samples: &mut [u64]) -> Self {
+        let n = samples.len();
+        if n == 0 {
+            return Self::default();
+        }
+        samples.sort_unstable();
+
+        let rank = |rank: f64| samples[(rank.max(0.0) as usize).min(n - 1)];
+        let half_width = 1.96 * (n as f64).sqrt() / 2.0;
+        let median = if n % 2 == 0 {
+            ((samples[n / 2 - 1] as u128 + samples[n / 2] as u128) / 2) as u64
+        } else {
+            samples[n / 2]
+        };
+        Self {
+            mean: (samples.iter().map(|&s| s as u128).sum::<u128>() / n as u128) as u64,
+            median,
+            p95: rank((n as f64 * 0.95).ceil() - 1.0),
+            ci_low: rank((n as f64 / 2.0 - half_width).floor()),
+            ci_high: rank((n as f64 / 2.0 + half_width).ceil()),
+        }
+    }
+
+    /// Returns `true` if these statistics are a statistically significant regression from the
+    /// baseline: the confidence intervals of the medians don't overlap, and the median increased
+    /// by more than `tolerance` percent.
+    pub fn is_regression(&self, baseline: &Self, tolerance: f64) -> bool {
+        self.ci_low > baseline.ci_high &&
+            self.median as f64 > baseline.median as f64 * (1.0 + tolerance / 100.0)
+    }
+}
+
 /// The result of a test setup.
 ///
 /// Includes the deployment of the required libraries and the test contract itself, and the call to
@@ -783,3 +936,7 @@ pub fn extend(&mut self, raw: RawCallResult, trace_kind: TraceKind) {
         HitMaps::merge_opt(&mut self.coverage, raw.coverage);
     }
 }
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
@@ -2,7 +2,7 @@
 
 use crate::{
     fuzz::{invariant::BasicTxDetails, BaseCounterExample},
-    multi_runner::{is_matching_test, TestContract, TestRunnerConfig},
+    multi_runner::{TestContract, TestRunnerConfig},
     progress::{start_fuzz_progress, TestsProgress},
     result::{SuiteResult, TestResult, TestSetup},
     MultiContractRunner, TestFilter,
@@ -14,6 +14,7 @@
 use foundry_common::{contracts::ContractsByAddress, TestFunctionExt, TestFunctionKind};
 use foundry_config::Config;
 use foundry_evm::{
+    backend::{with_rpc_error_scope, RpcErrorScope},
     constants::CALLER,
     decode::RevertDecoder,
     executors::{
@@ -34,7 +35,13 @@
     FailurePersistence, FileFailurePersistence, RngAlgorithm, TestError, TestRng, TestRunner,
 };
 use rayon::prelude::*;
-use std::{borrow::Cow, cmp::min, collections::BTreeMap, sync::Arc, time::Instant};
+use std::{
+    borrow::Cow,
+    cmp::min,
+    collections::BTreeMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tracing::Span;
 
 /// When running tests, we deploy all external libraries present in the project. To avoid additional
@@ -44,6 +51,10 @@
 /// `address(uint160(uint256(keccak256("foundry library deployer"))))`
 pub const LIBRARY_DEPLOYER: Address = address!("1F95D37F27EA0dEA9C252FC09D5A6eaA97647353");
 
+/// The delay before running a test again after a transient RPC error, multiplied by the number of
+/// the retry.
+const RPC_RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
 /// A type that executes all tests of a contract
 pub struct ContractRunner<'a> {
     /// The name of the contract.
@@ -333,16 +344,30 @@ pub fn run_tests(mut self, filter: &dyn TestFilter) -> SuiteResult {
         }
 
         let setup_time = Instant::now();
-        let setup = self.setup(call_setup);
+        // Deploy and set up from scratch again if `setUp` fails because of a flaky RPC endpoint.
+        let executor = self.executor.clone();
+        let (setup, setup_scope, setup_retries) = retry_on_rpc_error(
+            None,
+            |setup: &TestSetup| setup.reason.is_some(),
+            |retries| {
+                if retries > 0 {
+                    self.executor = executor.clone();
+                }
+                self.setup(call_setup)
+            },
+        );
+        drop(executor);
         debug!("finished setting up in {:?}", setup_time.elapsed());
 
         self.executor.inspector_mut().tracer = prev_tracer;
 
         if setup.reason.is_some() {
             // The setup failed, so we return a single test result for `setUp`
+            let mut res = TestResult::setup_result(setup);
+            res.rpc_retries = setup_retries;
             return SuiteResult::new(
                 start.elapsed(),
-                [("setUp()".to_string(), TestResult::setup_result(setup))].into(),
+                [("setUp()".to_string(), res)].into(),
                 warnings,
             )
         }
@@ -354,7 +379,7 @@ pub fn run_tests(mut self, filter: &dyn TestFilter) -> SuiteResult {
             .contract
             .abi
             .functions()
-            .filter(|func| is_matching_test(func, filter))
+            .filter(|func| self.tcfg.is_matching_function(func, filter))
             .collect::<Vec<_>>();
         debug!(
             "Found {} test functions out of {} in {:?}",
@@ -402,6 +427,11 @@ pub fn run_tests(mut self, filter: &dyn TestFilter) -> SuiteResult {
                 let sig = func.signature();
                 let kind = func.test_function_kind();
 
+                // Don't start new tests once a test failed with `--fail-fast`.
+                if self.fail_fast.should_stop() {
+                    return (sig, TestResult::cancelled());
+                }
+
                 let _guard = debug_span!(
                     "test",
                     %kind,
@@ -409,13 +439,25 @@ pub fn run_tests(mut self, filter: &dyn TestFilter) -> SuiteResult {
                 )
                 .entered();
 
-                let mut res = FunctionRunner::new(&self, &setup).run(
-                    func,
-                    kind,
-                    call_after_invariant,
-                    identified_contracts.as_ref(),
+                // Run the test again while it fails because of a flaky RPC endpoint, if it or
+                // `setUp` opted in with `vm.retryOnRpcError`.
+                let (mut res, _, rpc_retries) = retry_on_rpc_error(
+                    setup_scope.max_retries,
+                    |res: &TestResult| res.status.is_failure(),
+                    |_| {
+                        FunctionRunner::new(&self, &setup).run(
+                            func,
+                            kind,
+                            call_after_invariant,
+                            identified_contracts.as_ref(),
+                        )
+                    },
                 );
+                res.rpc_retries = rpc_retries;
                 res.duration = start.elapsed();
+                if res.status.is_failure() {
+                    self.fail_fast.record_fail();
+                }
 
                 (sig, res)
             })
@@ -426,6 +468,30 @@ pub fn run_tests(mut self, filter: &dyn TestFilter) -> SuiteResult {
     }
 }
 
+/// Runs `run` again, up to the number of retries requested with `vm.retryOnRpcError`, or
+/// `default_max_retries` if none were, while its output is a failure and a request to an RPC
+/// endpoint failed because of a transient error.
+///
+/// `run` is called with the number of retries so far. Returns the last output along with its
+/// [`RpcErrorScope`] and the number of retries.
+fn retry_on_rpc_error<T>(
+    default_max_retries: Option<u32>,
+    is_failure: impl Fn(&T) -> bool,
+    mut run: impl FnMut(u32) -> T,
+) -> (T, RpcErrorScope, u32) {
+    let mut retries = 0;
+    loop {
+        let (output, scope) = with_rpc_error_scope(|| run(retries));
+        let max_retries = scope.max_retries.or(default_max_retries).unwrap_or(0);
+        if retries >= max_retries || !scope.transient_error || !is_failure(&output) {
+            return (output, scope, retries)
+        }
+        retries += 1;
+        debug!(retries, "retrying after RPC error");
+        std::thread::sleep(RPC_RETRY_BACKOFF * retries);
+    }
+}
+
 /// Executes a single test function, returning a [`TestResult`].
 struct FunctionRunner<'a> {
     /// The function-level configuration.
@@ -498,6 +564,10 @@ fn run(
             TestFunctionKind::InvariantTest => {
                 self.run_invariant_test(func, call_after_invariant, identified_contracts.unwrap())
             }
+            TestFunctionKind::Bench => {
+                let runs = self.bench_runs.unwrap_or(1);
+                self.run_bench(func, runs)
+            }
             _ => unreachable!(),
         }
     }
@@ -543,6 +613,58 @@ fn run_unit_test(mut self, func: &Function) -> TestResult {
         self.result
     }
 
+    /// Runs a single benchmark.
+    ///
+    /// The benchmark is called `runs` times on the state left by `setUp` and before test txes,
+    /// recording the gas used and the wall time of every call. Stops at the first failing call.
+    fn run_bench(mut self, func: &Function, runs: u32) -> TestResult {
+        // Prepare benchmark execution.
+        if self.prepare_test(func).is_err() {
+            return self.result;
+        }
+
+        let runs = runs.max(1) as usize;
+        let mut gas_samples = Vec::with_capacity(runs);
+        let mut time_samples = Vec::with_capacity(runs);
+        let mut last_call_result = None;
+        for _ in 0..runs {
+            let start = Instant::now();
+            let call = self.executor.call(
+                self.sender,
+                self.address,
+                func,
+                &[],
+                U256::ZERO,
+                Some(self.revert_decoder()),
+            );
+            let elapsed = start.elapsed();
+
+            let (mut raw_call_result, reason) = match call {
+                Ok(res) => (res.raw, None),
+                Err(EvmError::Execution(err)) => (err.raw, Some(err.reason)),
+                Err(EvmError::Skip(reason)) => {
+                    self.result.single_skip(reason);
+                    return self.result;
+                }
+                Err(err) => {
+                    self.result.single_fail(Some(err.to_string()));
+                    return self.result;
+                }
+            };
+
+            if !self.executor.is_raw_call_mut_success(self.address, &mut raw_call_result, false) {
+                self.result.single_result(false, reason, raw_call_result);
+                return self.result;
+            }
+            gas_samples.push(raw_call_result.gas_used.wrapping_sub(raw_call_result.stipend));
+            time_samples.push(elapsed.as_nanos() as u64);
+            last_call_result = Some(raw_call_result);
+        }
+
+        self.result.bench_result(&mut gas_samples, &mut time_samples, last_call_result.unwrap());
+        self.result
+    }
+
     fn run_invariant_test(
         mut self,
         func: &Function,
@@ -642,6 +764,7 @@ fn run_invariant_test(
             &self.setup.fuzz_fixtures,
             &self.setup.deployed_libs,
             progress.as_ref(),
+            &self.tcfg.fail_fast,
         ) {
             Ok(x) => x,
             Err(e) => {
@@ -734,6 +857,9 @@ fn run_invariant_test(
             invariant_result.reverts,
             invariant_result.metrics,
         );
+        if success && invariant_result.cancelled {
+            self.result.cancel();
+        }
         self.result
     }
 
@@ -768,6 +894,7 @@ fn run_fuzz_test(mut self, func: &Function) -> TestResult {
             self.address,
             &self.cr.mcr.revert_decoder,
             progress.as_ref(),
+            &self.tcfg.fail_fast,
         );
         self.result.fuzz_result(result);
         self.result
@@ -0,0 +1,267 @@
+//! Deterministic partitioning of test contracts across machines.
+
+use crate::{gas_report::GasReport, result::SuiteResult};
+use alloy_primitives::keccak256;
+use eyre::Result;
+use foundry_compilers::ArtifactId;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt, str::FromStr, time::Duration};
+
+/// A shard of the test contracts, in the form `index/count`, with `index` starting at 1.
+///
+/// Contracts are assigned to shards by a hash of their identifier, so that every shard runs the
+/// same contracts regardless of the machine or of the other contracts of the project.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestShard {
+    /// The 1-based index of the shard.
+    pub index: u64,
+    /// The total number of shards.
+    pub count: u64,
+}
+
+impl TestShard {
+    /// Returns whether the contract with the given artifact id belongs to this shard.
+    pub fn contains(&self, id: &ArtifactId) -> bool {
+        self.contains_identifier(&id.identifier())
+    }
+
+    /// Returns whether the contract with the given identifier (`path:name`) belongs to this shard.
+    pub fn contains_identifier(&self, identifier: &str) -> bool {
+        let hash = keccak256(identifier);
+        let hash = u64::from_be_bytes(hash[..8].try_into().unwrap());
+        hash % self.count == self.index - 1
+    }
+}
+
+impl FromStr for TestShard {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (index, count) = s
+            .split_once('/')
+            .and_then(|(index, count)| Some((index.parse().ok()?, count.parse().ok()?)))
+            .ok_or_else(|| format!("invalid shard `{s}`, expected `index/count`, e.g. `2/8`"))?;
+        if count == 0 || index == 0 || index > count {
+            return Err(format!("invalid shard `{s}`, the index must be between 1 and {count}"));
+        }
+        Ok(Self { index, count })
+    }
+}
+
+impl fmt::Display for TestShard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.index, self.count)
+    }
+}
+
+/// The results of a shard of a test run, to be merged with the other shards afterwards.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TestShardOutput {
+    /// The shard these results are for.
+    pub shard: TestShard,
+    /// The results of the test suites of the shard.
+    pub results: BTreeMap<String, SuiteResult>,
+    /// The gas report of the shard, if requested.
+    #[serde(with = "shard_gas_report")]
+    pub gas_report: Option<GasReport>,
+    /// Wall clock time it took to run the shard.
+    #[serde(with = "humantime_serde")]
+    pub duration: Duration,
+}
+
+impl TestShardOutput {
+    /// Merges the outputs of all the shards of a test run, and their gas reports into
+    /// `gas_report` if any.
+    ///
+    /// Returns an error if the outputs don't cover every shard exactly once, or if a gas report is
+    /// requested and a shard was run without one.
+    pub fn merge(
+        outputs: Vec<Self>,
+        mut gas_report: Option<GasReport>,
+    ) -> Result<(BTreeMap<String, SuiteResult>, Option<GasReport>)> {
+        let Some(count) = outputs.first().map(|output| output.shard.count) else {
+            eyre::bail!("no shard outputs to merge");
+        };
+
+        let mut seen = vec![false; count as usize];
+        let mut results = BTreeMap::new();
+        for output in outputs {
+            if output.shard.count != count || !(1..=count).contains(&output.shard.index) {
+                eyre::bail!(
+                    "shard {} is not one of {count} shards, outputs of different runs can't be \
+                     merged",
+                    output.shard
+                );
+            }
+            if std::mem::replace(&mut seen[output.shard.index as usize - 1], true) {
+                eyre::bail!("shard {} is duplicated", output.shard);
+            }
+            results.extend(output.results);
+            if let Some(gas_report) = &mut gas_report {
+                let Some(shard_report) = output.gas_report else {
+                    eyre::bail!("shard {} was run without `--gas-report`", output.shard);
+                };
+                gas_report.merge(shard_report);
+            }
+        }
+
+        let missing = seen
+            .iter()
+            .enumerate()
+            .filter(|(_, seen)| !**seen)
+            .map(|(i, _)| TestShard { index: i as u64 + 1, count }.to_string())
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            eyre::bail!("missing shards: {}", missing.join(", "));
+        }
+
+        Ok((results, gas_report.map(GasReport::finalize)))
+    }
+}
+
+/// (De)serializes the gas report of a shard as the gas used by every call, which is needed to
+/// merge the reports of the shards but is not part of a serialized [`GasReport`].
+mod shard_gas_report {
+    use crate::gas_report::{ContractInfo, GasInfo, GasReport};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::BTreeMap;
+
+    /// The calls recorded for a contract.
+    #[derive(Serialize, Deserialize)]
+    struct ContractCalls {
+        gas: u64,
+        size: usize,
+        /// Function name -> Function signature -> gas used by every call
+        functions: BTreeMap<String, BTreeMap<String, Vec<u64>>>,
+    }
+
+    pub fn serialize<S: Serializer>(
+        report: &Option<GasReport>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let contracts = report.as_ref().map(|report| {
+            report
+                .contracts
+                .iter()
+                .map(|(name, contract)| {
+                    let functions = contract
+                        .functions
+                        .iter()
+                        .map(|(function, sigs)| {
+                            let sigs = sigs
+                                .iter()
+                                .map(|(sig, gas_info)| (sig.clone(), gas_info.frames.clone()))
+                                .collect();
+                            (function.clone(), sigs)
+                        })
+                        .collect();
+                    (name, ContractCalls { gas: contract.gas, size: contract.size, functions })
+                })
+                .collect::<BTreeMap<_, _>>()
+        });
+        contracts.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<GasReport>, D::Error> {
+        let Some(contracts) = Option::<BTreeMap<String, ContractCalls>>::deserialize(deserializer)?
+        else {
+            return Ok(None)
+        };
+        let mut report = GasReport::default();
+        for (name, calls) in contracts {
+            let functions = calls
+                .functions
+                .into_iter()
+                .map(|(function, sigs)| {
+                    let sigs = sigs
+                        .into_iter()
+                        .map(|(sig, frames)| (sig, GasInfo { frames, ..Default::default() }))
+                        .collect();
+                    (function, sigs)
+                })
+                .collect();
+            report
+                .contracts
+                .insert(name, ContractInfo { gas: calls.gas, size: calls.size, functions });
+        }
+        Ok(Some(report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gas_report::GasInfo;
+
+    #[test]
+    fn parse_shard() {
+        assert_eq!("2/8".parse(), Ok(TestShard { index: 2, count: 8 }));
+        assert_eq!("1/1".parse::<TestShard>().unwrap().to_string(), "1/1");
+        assert!("0/8".parse::<TestShard>().is_err());
+        assert!("9/8".parse::<TestShard>().is_err());
+        assert!("1/0".parse::<TestShard>().is_err());
+        assert!("2".parse::<TestShard>().is_err());
+    }
+
+    #[test]
+    fn shards_partition_contracts() {
+        let count = 4;
+        let shards = (1..=count).map(|index| TestShard { index, count }).collect::<Vec<_>>();
+        for i in 0..100 {
+            let identifier = format!("test/Contract{i}.t.sol:Contract{i}Test");
+            let containing = shards.iter().filter(|s| s.contains_identifier(&identifier)).count();
+            assert_eq!(containing, 1);
+        }
+    }
+
+    #[test]
+    fn merge_requires_every_shard() {
+        let output = |index| TestShardOutput {
+            shard: TestShard { index, count: 2 },
+            results: BTreeMap::new(),
+            gas_report: None,
+            duration: Duration::ZERO,
+        };
+        let merge = |outputs| TestShardOutput::merge(outputs, None).map_err(|e| e.to_string());
+        assert!(merge(vec![output(1), output(2)]).is_ok());
+        assert_eq!(merge(vec![output(1)]).unwrap_err(), "missing shards: 2/2");
+        assert_eq!(merge(vec![output(1), output(1)]).unwrap_err(), "shard 1/2 is duplicated");
+
+        let err = TestShardOutput::merge(vec![output(1), output(2)], Some(GasReport::default()))
+            .unwrap_err();
+        assert_eq!(err.to_string(), "shard 1/2 was run without `--gas-report`");
+    }
+
+    #[test]
+    fn serializes_gas_report_calls() {
+        let mut report = GasReport::default();
+        let contract = report.contracts.entry("src/Counter.sol:Counter".to_string()).or_default();
+        contract.functions.entry("increment".to_string()).or_default().insert(
+            "increment()".to_string(),
+            GasInfo { frames: vec![43_000, 21_000], ..Default::default() },
+        );
+        let output = TestShardOutput {
+            shard: TestShard { index: 1, count: 1 },
+            results: BTreeMap::new(),
+            gas_report: Some(report.clone()),
+            duration: Duration::ZERO,
+        };
+
+        let json = serde_json::to_value(&output).unwrap();
+        assert_eq!(
+            json["gas_report"]["src/Counter.sol:Counter"]["functions"]["increment"]["increment()"],
+            serde_json::json!([43_000, 21_000])
+        );
+        // the gas report itself doesn't serialize the calls
+        assert!(!serde_json::to_string(&report).unwrap().contains("43000"));
+
+        let output: TestShardOutput = serde_json::from_value(json).unwrap();
+        let (_, report) = TestShardOutput::merge(vec![output], Some(GasReport::default())).unwrap();
+        let report = report.unwrap();
+        let info =
+            &report.contracts["src/Counter.sol:Counter"].functions["increment"]["increment()"];
+        assert_eq!((info.calls, info.min, info.max), (2, 21_000, 43_000));
+    }
+}
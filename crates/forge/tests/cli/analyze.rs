@@ -0,0 +1,37 @@
+forgetest!(storage_packing, |prj, cmd| {
+    prj.add_source(
+        "A.sol",
+        r#"
+contract A {
+    struct S {
+        uint8 x;
+        uint256 y;
+        uint8 z;
+    }
+
+    uint128 a;
+    uint256 b;
+    uint128 c;
+    S s;
+}
+    "#,
+    )
+    .unwrap();
+
+    cmd.args(["analyze", "storage-packing"]).assert_success().stdout_eq(str![[r#"
+...
+src/A.sol:A: 5 slots instead of 6, saving ~20000 gas
+--- src/A.sol:A
++++ src/A.sol:A
+...
+struct A.S: 2 slots instead of 3, saving ~20000 gas
+--- struct A.S
++++ struct A.S
+...
+"#]]);
+
+    cmd.forge_fuse().args(["analyze", "sp", "B"]).assert_failure().stderr_eq(str![[r#"
+Error: Could not find artifact `B` in the compiled artifacts
+
+"#]]);
+});
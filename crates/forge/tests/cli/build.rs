@@ -1,5 +1,5 @@
 use crate::utils::generate_large_init_contract;
-use foundry_test_utils::{forgetest, snapbox::IntoData, str};
+use foundry_test_utils::{forgetest, snapbox::IntoData, str, util::OutputExt};
 use globset::Glob;
 
 forgetest_init!(can_parse_build_filters, |prj, cmd| {
@@ -203,3 +203,88 @@
 
     cmd.args(["build"]).assert_success();
 });
+
+// tests that pre-build hooks only run when their inputs change
+#[cfg(unix)]
+forgetest!(runs_build_hooks_on_changed_inputs, |prj, cmd| {
+    prj.create_file("data/Constants.sol", "contract Constants { uint256 constant X = 1; }");
+    prj.update_config(|config| {
+        config.build.hooks.pre_build = vec![foundry_config::BuildHook {
+            name: Some("constants".to_string()),
+            command: ["cp", "data/Constants.sol", "src/Constants.sol"].map(String::from).to_vec(),
+            inputs: vec!["data/*.sol".to_string()],
+            outputs: vec!["src/Constants.sol".into()],
+        }];
+    });
+
+    cmd.args(["build"]).assert_success().stdout_eq(str![[r#"
+Running pre_build hook `constants`
+[COMPILING_FILES] with [SOLC_VERSION]
+[SOLC_VERSION] [ELAPSED]
+Compiler run successful!
+
+"#]]);
+
+    // Unchanged inputs don't run the hook nor invalidate the compilation cache.
+    cmd.forge_fuse().args(["build"]).assert_success().stdout_eq(str![[r#"
+No files changed, compilation skipped
+
+"#]]);
+
+    prj.create_file("data/Constants.sol", "contract Constants { uint256 constant X = 2; }");
+    cmd.forge_fuse().args(["build"]).assert_success().stdout_eq(str![[r#"
+Running pre_build hook `constants`
+[COMPILING_FILES] with [SOLC_VERSION]
+[SOLC_VERSION] [ELAPSED]
+Compiler run successful!
+
+"#]]);
+
+    // Missing outputs are regenerated.
+    std::fs::remove_file(prj.root().join("src/Constants.sol")).unwrap();
+    cmd.forge_fuse().args(["build"]).assert_success().stdout_eq(str![[r#"
+Running pre_build hook `constants`
+...
+"#]]);
+});
+
+// tests that the sources can be compiled in units, which populate the output and the cache
+forgetest_init!(build_in_compilation_units, |prj, cmd| {
+    cmd.args(["build", "--max-unit-files", "20", "--timings"]).assert_success().stdout_eq(str![[
+        r#"
+...
+Compiler run successful!
+
+╭[..]
+| File [..]| Contracts [..]| Compiled Files [..]| Time [..]| Peak Memory [..]|
+...
+"#
+    ]]);
+    assert!(!prj.root().join("cache/units").exists());
+
+    cmd.forge_fuse().args(["build"]).assert_success().stdout_eq(str![[r#"
+No files changed, compilation skipped
+
+"#]]);
+
+    // Only the files that changed are compiled, and timed one at a time.
+    prj.add_source("Other.sol", "contract Other {}").unwrap();
+    let out =
+        cmd.forge_fuse().args(["build", "--timings"]).assert_success().get_output().stdout_lossy();
+    assert!(out.contains("src/Other.sol"), "{out}");
+    assert!(!out.contains("src/Counter.sol"), "{out}");
+
+    cmd.forge_fuse().args(["build", "--force", "--max-unit-files", "20"]).assert_success();
+    cmd.forge_fuse().args(["test"]).assert_success();
+
+    // The errors of a unit are reported.
+    prj.add_source("Broken.sol", "contract Broken { function f() public { x; } }").unwrap();
+    cmd.forge_fuse().args(["build", "--max-unit-files", "20"]).assert_failure().stderr_eq(str![[
+        r#"
+Error: Compiler run failed:
+...
+Error (7576): Undeclared identifier.
+...
+"#
+    ]]);
+});
@@ -1015,6 +1015,8 @@ struct Bar {
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for src/ATest.t.sol:ATest
 [PASS] testExample() ([GAS])
 Suite result: ok. 1 passed; 0 failed; 0 skipped; [ELAPSED]
@@ -1026,6 +1028,8 @@ struct Bar {
     cmd.arg("--check").assert_success().stdout_eq(str![[r#"
 No files changed, compilation skipped
 
+Test run: [..]
+
 Ran 1 test for src/ATest.t.sol:ATest
 [PASS] testExample() ([GAS])
 Suite result: ok. 1 passed; 0 failed; 0 skipped; [ELAPSED]
@@ -1347,6 +1351,107 @@ struct Bar {
     remove(&mut cmd, "lib/forge-std");
 });
 
+// checks that declared remappings are kept in sync when installing and removing dependencies
+forgetest!(can_update_remappings_on_install_and_remove, |prj, cmd| {
+    cmd.git_init();
+
+    let remappings_txt = prj.root().join("remappings.txt");
+    fs::write(&remappings_txt, "@custom/=src/custom/\n").unwrap();
+
+    cmd.forge_fuse()
+        .args(["install", "foundry-rs/forge-std", "--no-commit"])
+        .assert_success()
+        .stdout_eq(str![[r#"
+Installing forge-std in [..] (url: Some("https://github.com/foundry-rs/forge-std"), tag: None)
+    Installed forge-std[..]
+    Added remapping forge-std/=lib/forge-std/src/ in remappings.txt
+
+"#]]);
+    assert_eq!(
+        read_string(&remappings_txt),
+        "@custom/=src/custom/\nforge-std/=lib/forge-std/src/\n"
+    );
+
+    cmd.forge_fuse()
+        .args(["remove", "--force", "--dry-run", "forge-std"])
+        .assert_success()
+        .stdout_eq(str![[r#"
+Would remove 'forge-std' in lib/forge-std
+    Would remove remapping forge-std/=lib/forge-std/src/ in remappings.txt
+
+"#]]);
+    assert!(prj.root().join("lib/forge-std").exists());
+
+    cmd.forge_fuse().args(["remove", "--force", "forge-std"]).assert_success().stdout_eq(str![[
+        r#"
+Removing 'forge-std' in [..], (url: [..], tag: None)
+    Removed remapping forge-std/=lib/forge-std/src/ in remappings.txt
+
+"#
+    ]]);
+    assert_eq!(read_string(&remappings_txt), "@custom/=src/custom/\n");
+});
+
+// checks that packages can be published to and installed from a registry
+forgetest!(can_publish_and_install_package, |prj, cmd| {
+    prj.add_source("Lib", "library Lib {}").unwrap();
+    let registry = tempfile::tempdir().unwrap();
+    let registry = registry.path().to_str().unwrap();
+
+    cmd.forge_fuse()
+        .args(["publish", "1.0.0", "--name", "my-lib", "--registry", registry])
+        .assert_success()
+        .stdout_eq(str![[r#"
+Published my-lib@1.0.0 to [..] (checksum: 0x[..])
+
+"#]]);
+    assert!(Path::new(registry).join("my-lib/1.0.0.json").is_file());
+
+    // versions can't be published twice
+    cmd.forge_fuse()
+        .args(["publish", "1.0.0", "--name", "my-lib", "--registry", registry])
+        .assert_failure()
+        .stderr_eq(str![[r#"
+Error: package `my-lib@1.0.0` is already published in [..]
+
+"#]]);
+
+    cmd.forge_fuse()
+        .args(["install", "--registry", registry, "my-lib"])
+        .assert_success()
+        .stdout_eq(str![[r#"
+Installing my-lib from [..]
+    Installed my-lib 1.0.0 (checksum: 0x[..])
+    Added remapping my-lib/=lib/my-lib/src/ in [..]
+
+"#]]);
+    assert!(prj.root().join("lib/my-lib/src/Lib.sol").is_file());
+
+    // tampered packages are rejected
+    let package = Path::new(registry).join("my-lib/1.0.0.json");
+    fs::write(&package, read_string(&package).replace("library Lib", "library Evil")).unwrap();
+    cmd.forge_fuse()
+        .args(["install", "--registry", registry, "my-lib@1.0.0"])
+        .assert_failure()
+        .stderr_eq(str![[r#"
+Error: checksum mismatch for package my-lib@1.0.0: expected 0x[..], got 0x[..]
+
+"#]]);
+
+    // packages replaced in the registry are rejected, even with a valid checksum
+    let mut replaced: forge::package::Package =
+        serde_json::from_str(&read_string(&package)).unwrap();
+    replaced.checksum = replaced.compute_checksum();
+    fs::write(&package, serde_json::to_string(&replaced).unwrap()).unwrap();
+    cmd.forge_fuse()
+        .args(["install", "--registry", registry, "my-lib"])
+        .assert_failure()
+        .stderr_eq(str![[r#"
+Error: checksum of package my-lib@1.0.0 changed since it was first installed: expected 0x[..], got 0x[..]; if the change is expected, remove it from forge-packages.lock
+
+"#]]);
+});
+
 // test to check we can run `forge install` in an empty dir <https://github.com/foundry-rs/foundry/issues/6519>
 forgetest!(can_install_empty, |prj, cmd| {
     // create
@@ -2998,6 +3103,8 @@ struct Bar {
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 2 tests for test/Counter.t.sol:CounterTest
 [PASS] testFuzz_SetNumber(uint256) (runs: 256, [AVG_GAS])
 [PASS] test_Increment() ([GAS])
@@ -3572,3 +3679,184 @@ enum MyEnum { A, B, C }
 Bindings have been generated to [..]"#
     ]]);
 });
+
+forgetest!(can_print_tree_why, |prj, cmd| {
+    prj.add_lib("dep/Dep.sol", "contract Dep {}").unwrap();
+    prj.add_source(
+        "Counter.sol",
+        r#"
+import "../lib/dep/Dep.sol";
+contract Counter {}
+    "#,
+    )
+    .unwrap();
+
+    cmd.args(["tree", "--why", "Dep.sol", "--charset", "ascii"]);
+    cmd.assert_success().stdout_eq(str![[r#"
+lib/dep/Dep.sol is included by:
+  src/Counter.sol -> lib/dep/Dep.sol
+
+"#]]);
+
+    cmd.forge_fuse().args(["tree", "--why", "Missing.sol"]).assert_failure().stderr_eq(str![[r#"
+Error: no file matching `Missing.sol` is part of the build
+
+"#]]);
+});
+
+forgetest!(can_prove_function, |prj, cmd| {
+    prj.add_source(
+        "Vault.sol",
+        r#"
+contract Vault {
+    function withdraw(uint256 amount) public pure returns (uint256 fee) {
+        if (amount == 31337) {
+            return 0;
+        }
+        require(amount < 2 ** 128, "too large");
+        return amount / 100 + 1;
+    }
+}
+    "#,
+    )
+    .unwrap();
+
+    cmd.args(["prove", "--function", "withdraw", "--assert", "fee > 0", "--export", "ce.json"]);
+    cmd.assert_failure().stdout_eq(str![[r#"
+...
+Searching for a counterexample to `fee > 0` in withdraw(uint256)...
+Counterexample found after [..] runs:
+  args: [31337 [3.133e4]]
+  returns: [0]
+  calldata: 0x2e1a7d4d0000000000000000000000000000000000000000000000000000000000007a69
+Exported counterexample to ce.json
+
+"#]]);
+    let exported: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(prj.root().join("ce.json")).unwrap())
+            .unwrap();
+    assert_eq!(
+        exported["values"][0],
+        "0x0000000000000000000000000000000000000000000000000000000000007a69"
+    );
+
+    cmd.forge_fuse().args(["prove", "--function", "withdraw", "--assert", "fee >= 0"]);
+    cmd.assert_success().stdout_eq(str![[r#"
+...
+Searching for a counterexample to `fee >= 0` in withdraw(uint256)...
+No counterexample found after [..] runs ([..] reverted).
+The search is bounded, so this does not prove that the assertion holds.
+
+"#]]);
+
+    cmd.forge_fuse().args(["prove", "--function", "withdraw", "--assert", "x > 0"]);
+    cmd.assert_failure().stderr_eq(str![[r#"
+Error: unknown identifier `x` in assertion
+
+"#]]);
+});
+
+// Finds the contracts of the project matching a runtime bytecode.
+forgetest!(bytecode_search, |prj, cmd| {
+    prj.add_source("Counter.sol", CUSTOM_COUNTER).unwrap();
+    prj.add_source("Other.sol", "contract Other { uint256 public x; }").unwrap();
+
+    let code = cmd
+        .args(["inspect", "Counter", "deployedBytecode"])
+        .assert_success()
+        .get_output()
+        .stdout_lossy();
+    let code = code.trim();
+
+    cmd.forge_fuse().args(["bytecode-search", code]).assert_success().stdout_eq(str![[r#"
+Full match: src/Counter.sol:Counter (solc [..])
+Compiler settings: optimizer disabled, 200 runs, EVM version cancun, via-IR false
+
+"#]]);
+
+    cmd.forge_fuse().args(["bytecode-search", code, "--json"]).assert_success().stdout_eq(
+        str![[r#"
+[
+  {
+    "match": "full",
+    "contract": "src/Counter.sol:Counter",
+    "compilerVersion": "[..]"
+  }
+]
+"#]]
+        .is_json(),
+    );
+
+    cmd.forge_fuse().args(["bytecode-search", "0x6080604052"]).assert_success().stdout_eq(str![[
+        r#"
+No contract of the project matches the bytecode.
+The closest is [..], with [..]% of the bytecode differing.
+
+"#
+    ]]);
+});
+
+// checks that `forge generate test` scaffolds a test file from the contract's ABI
+forgetest_init!(can_generate_test, |prj, cmd| {
+    prj.wipe_contracts();
+    prj.add_source(
+        "Counter.sol",
+        r#"
+contract Counter {
+    uint256 public number;
+
+    function setNumber(uint256 newNumber) public {
+        number = newNumber;
+    }
+
+    function increment() public {
+        number++;
+    }
+}
+   "#,
+    )
+    .unwrap();
+
+    cmd.args(["generate", "test", "--contract", "Counter"]).assert_success().stdout_eq(str![[r#"
+Generated test file: test/Counter.t.sol
+
+"#]]);
+
+    assert_eq!(
+        fs::read_to_string(prj.root().join("test/Counter.t.sol")).unwrap(),
+        r#"// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^0.8.13;
+
+import {Test, console} from "forge-std/Test.sol";
+import {Counter} from "../src/Counter.sol";
+
+contract CounterTest is Test {
+    Counter public counter;
+
+    function setUp() public {
+        counter = new Counter();
+    }
+
+    function test_increment() public {
+        // TODO: test `increment()`
+    }
+
+    function test_number() public {
+        // TODO: test `number()`
+    }
+
+    function test_setNumber() public {
+        // TODO: test `setNumber(uint256)`
+    }
+
+    function testFuzz_setNumber(uint256 newNumber) public {
+        // TODO: constrain inputs with `vm.assume` or `bound`.
+        counter.setNumber(newNumber);
+    }
+}
+"#
+    );
+
+    // The generated tests compile and pass.
+    cmd.forge_fuse().args(["test", "--mc", "CounterTest"]).assert_success();
+});
@@ -88,7 +88,10 @@
             ..Default::default()
         },
         ffi: true,
+        ffi_sandbox: Default::default(),
         allow_internal_expect_revert: false,
+        memory_safety: false,
+        strict_config: false,
         always_use_create_2_factory: false,
         prompt_timeout: 0,
         sender: "00a329c0648769A73afAc7F9381D08FB43dBEA72".parse().unwrap(),
@@ -108,6 +111,8 @@
         block_gas_limit: Some(100u64.into()),
         disable_block_gas_limit: false,
         memory_limit: 1 << 27,
+        test_memory_limit: None,
+        memory_report: false,
         eth_rpc_url: Some("localhost".to_string()),
         eth_rpc_jwt: None,
         eth_rpc_timeout: None,
@@ -127,6 +132,7 @@
         rpc_storage_caching: StorageCachingConfig {
             chains: CachedChains::None,
             endpoints: CachedEndpoints::Remote,
+            ..Default::default()
         },
         no_storage_caching: true,
         no_rpc_rate_limit: true,
@@ -142,9 +148,14 @@
         build_info_path: None,
         fmt: Default::default(),
         doc: Default::default(),
+        lint: Default::default(),
         bind_json: Default::default(),
+        debugger: Default::default(),
+        build: Default::default(),
         fs_permissions: Default::default(),
         labels: Default::default(),
+        label_files: Default::default(),
+        env: Default::default(),
         isolate: true,
         unchecked_cheatcode_artifacts: false,
         create2_library_salt: Config::DEFAULT_CREATE2_LIBRARY_SALT,
@@ -154,14 +165,17 @@
         dependencies: Default::default(),
         soldeer: Default::default(),
         warnings: vec![],
+        secrets: vec![],
         assertions_revert: true,
         legacy_assertions: false,
         extra_args: vec![],
         eof_version: None,
         odyssey: false,
         transaction_timeout: 120,
+        transaction_watch: Default::default(),
         additional_compiler_profiles: Default::default(),
         compilation_restrictions: Default::default(),
+        overrides: Default::default(),
         eof: false,
         _non_exhaustive: (),
     };
@@ -994,6 +1008,8 @@
 transaction_timeout = 120
 ffi = false
 allow_internal_expect_revert = false
+memory_safety = false
+strict_config = false
 always_use_create_2_factory = false
 prompt_timeout = 120
 sender = "0x1804c8ab1f12e6bbf3894d4083f33e07309d1f38"
@@ -1007,6 +1023,7 @@
 block_difficulty = 0
 block_prevrandao = "0x0000000000000000000000000000000000000000000000000000000000000000"
 memory_limit = 134217728
+memory_report = false
 extra_output = []
 extra_output_files = []
 names = false
@@ -1035,10 +1052,17 @@
 access = "read"
 path = "out"
 
+[profile.default.ffi_sandbox]
+allowed_commands = []
+
 [profile.default.rpc_storage_caching]
 chains = "all"
 endpoints = "all"
 
+[profile.default.transaction_watch]
+poll_interval = 1000
+max_poll_interval = 12000
+
 [fmt]
 line_length = 120
 tab_width = 4
@@ -1061,6 +1085,15 @@
 book = "book.toml"
 homepage = "README.md"
 ignore = []
+include_scripts = false
+include_tests = false
+languages = []
+
+[lint]
+severity = "info"
+include_lints = []
+exclude_lints = []
+ignore = []
 
 [fuzz]
 runs = 256
@@ -1074,6 +1107,9 @@
 failure_persist_dir = "cache/fuzz"
 failure_persist_file = "failures"
 show_logs = false
+dictionary_files = []
+exhaustive_small_domains = false
+coverage_guided = false
 
 [invariant]
 runs = 256
@@ -1100,6 +1136,16 @@
 include = []
 exclude = []
 
+[debugger]
+layout = "auto"
+horizontal_min_width = 200
+horizontal_split = 50
+hide = []
+
+[build.hooks]
+pre_build = []
+post_build = []
+
 
 "#]]);
 
@@ -1176,7 +1222,11 @@
     "failure_persist_dir": "cache/fuzz",
     "failure_persist_file": "failures",
     "show_logs": false,
-    "timeout": null
+    "timeout": null,
+    "dictionary_files": [],
+    "dictionary_export": null,
+    "exhaustive_small_domains": false,
+    "coverage_guided": false
   },
   "invariant": {
     "runs": 256,
@@ -1196,7 +1246,12 @@
     "timeout": null
   },
   "ffi": false,
+  "ffi_sandbox": {
+    "allowed_commands": []
+  },
   "allow_internal_expect_revert": false,
+  "memory_safety": false,
+  "strict_config": false,
   "always_use_create_2_factory": false,
   "prompt_timeout": 120,
   "sender": "0x1804c8ab1f12e6bbf3894d4083f33e07309d1f38",
@@ -1215,6 +1270,8 @@
   "block_prevrandao": "0x0000000000000000000000000000000000000000000000000000000000000000",
   "block_gas_limit": null,
   "memory_limit": 134217728,
+  "test_memory_limit": null,
+  "memory_report": false,
   "extra_output": [],
   "extra_output_files": [],
   "names": false,
@@ -1255,6 +1312,15 @@
     "title": "",
     "book": "book.toml",
     "homepage": "README.md",
+    "ignore": [],
+    "include_scripts": false,
+    "include_tests": false,
+    "languages": []
+  },
+  "lint": {
+    "severity": "info",
+    "include_lints": [],
+    "exclude_lints": [],
     "ignore": []
   },
   "bind_json": {
@@ -1262,6 +1328,18 @@
     "include": [],
     "exclude": []
   },
+  "debugger": {
+    "layout": "auto",
+    "horizontal_min_width": 200,
+    "horizontal_split": 50,
+    "hide": []
+  },
+  "build": {
+    "hooks": {
+      "pre_build": [],
+      "post_build": []
+    }
+  },
   "fs_permissions": [
     {
       "access": "read",
@@ -1281,6 +1359,10 @@
   "legacy_assertions": false,
   "odyssey": false,
   "transaction_timeout": 120,
+  "transaction_watch": {
+    "poll_interval": 1000,
+    "max_poll_interval": 12000
+  },
   "eof": false,
   "additional_compiler_profiles": [],
   "compilation_restrictions": []
@@ -1423,6 +1505,8 @@
     prj.add_source("GasSnapshotCheckTest.sol", &test_contract(1)).unwrap();
     cmd.forge_fuse().args(["test"]).assert_success().stdout_eq(str![[r#"
 ...
+Test run: [..]
+
 Ran 1 test for src/GasSnapshotCheckTest.sol:GasSnapshotCheckTest
 [PASS] testSnapshotGasSectionExternal() ([GAS])
 Suite result: ok. 1 passed; 0 failed; 0 skipped; [ELAPSED]
@@ -1453,6 +1537,8 @@
     prj.update_config(|config| config.gas_snapshot_check = false);
     cmd.forge_fuse().args(["test"]).assert_success().stdout_eq(str![[r#"
 ...
+Test run: [..]
+
 Ran 1 test for src/GasSnapshotCheckTest.sol:GasSnapshotCheckTest
 [PASS] testSnapshotGasSectionExternal() ([GAS])
 Suite result: ok. 1 passed; 0 failed; 0 skipped; [ELAPSED]
@@ -1464,6 +1550,8 @@
     prj.update_config(|config| config.gas_snapshot_check = true);
     cmd.forge_fuse().args(["test"]).assert_success().stdout_eq(str![[r#"
 ...
+Test run: [..]
+
 Ran 1 test for src/GasSnapshotCheckTest.sol:GasSnapshotCheckTest
 [PASS] testSnapshotGasSectionExternal() ([GAS])
 Suite result: ok. 1 passed; 0 failed; 0 skipped; [ELAPSED]
@@ -1527,9 +1615,116 @@
     cmd.env("FORGE_SNAPSHOT_CHECK", "true");
     cmd.args(["test", "--gas-snapshot-check=false"]).assert_success().stdout_eq(str![[r#"
 ...
+Test run: [..]
+
 Ran 1 test for src/GasSnapshotCheckTest.sol:GasSnapshotCheckTest
 [PASS] testSnapshotGasSectionExternal() ([GAS])
 Suite result: ok. 1 passed; 0 failed; 0 skipped; [ELAPSED]
 ...
 "#]]);
 });
+
+// tests that `forge config --diff` prints the values that differ from the defaults
+forgetest!(can_show_config_diff, |prj, cmd| {
+    let toml = r"[profile.default]
+optimizer_runs = 1000
+
+[fuzz]
+runs = 1
+";
+    fs::write(prj.root().join("foundry.toml"), toml).unwrap();
+
+    cmd.env("FOUNDRY_SPARSE_MODE", "true");
+    cmd.args(["config", "--diff"]).assert_success().stdout_eq(str![[r#"
+...
+fuzz.runs = 1 (default: 256) from TOML file `[..]foundry.toml`
+...
+optimizer_runs = 1000 (default: null) from TOML file `[..]foundry.toml`
+...
+sparse_mode = true (default: false) from [..]
+...
+"#]]);
+});
+
+// tests that `forge config --explain` prints every provider that set a key
+forgetest!(can_explain_config_key, |prj, cmd| {
+    let toml = r"[profile.default]
+optimizer_runs = 1000
+";
+    fs::write(prj.root().join("foundry.toml"), toml).unwrap();
+
+    cmd.env("FOUNDRY_OPTIMIZER_RUNS", "2000");
+    cmd.args(["config", "--explain", "optimizer_runs"]).assert_success().stdout_eq(str![[r#"
+optimizer_runs = 2000
+Contributions (lowest to highest precedence):
+...
+  1000 from TOML file `[..]foundry.toml` [default]
+...
+  2000 from [..] [global]
+Set by [..]
+
+"#]]);
+
+    cmd.forge_fuse().args(["config", "--explain", "unknown_key"]).assert_success().stdout_eq(str![
+        [r#"
+`unknown_key` is not set
+
+"#]
+    ]);
+});
+
+// tests that contradictory settings are reported when the config is loaded
+forgetest!(can_validate_config, |prj, cmd| {
+    let toml = r#"[profile.default]
+eof_version = 1
+"#;
+    fs::write(prj.root().join("foundry.toml"), toml).unwrap();
+
+    cmd.args(["config"]).assert_failure().stderr_eq(str![[r#"
+Error: failed to extract foundry config:
+foundry config error: `eof_version` requires `via_ir`; set `via_ir = true` or remove `eof_version`
+
+"#]]);
+
+    let toml = r#"[profile.default]
+solc = "0.8.12"
+via_ir = true
+"#;
+    fs::write(prj.root().join("foundry.toml"), toml).unwrap();
+
+    cmd.forge_fuse().args(["config"]).assert_success().stderr_eq(str![[r#"
+Warning: `via_ir` is experimental before solc 0.8.13, but `solc` is set to 0.8.12; set `solc` to a newer version or disable `via_ir`.
+
+"#]]);
+});
+
+// tests that `--config-override` takes precedence over the other sources of the config
+forgetest!(can_override_config_from_cli, |prj, cmd| {
+    let toml = r"[profile.default]
+optimizer_runs = 1000
+
+[profile.default.fuzz]
+runs = 500
+";
+    fs::write(prj.root().join("foundry.toml"), toml).unwrap();
+
+    cmd.env("FOUNDRY_OPTIMIZER_RUNS", "2000");
+    let output = cmd
+        .args(["config", "--json", "--config-override", "optimizer_runs=3000"])
+        .args(["--config-override", "fuzz.runs=10000"])
+        .assert_success()
+        .get_output()
+        .stdout_lossy();
+    let config: Config = serde_json::from_str(&output).unwrap();
+    assert_eq!(config.optimizer_runs, Some(3000));
+    assert_eq!(config.fuzz.runs, 10000);
+
+    cmd.forge_fuse().args(["config", "--config-override", "fuzz.run=1"]).assert_failure().stderr_eq(
+        str![[r#"
+error: invalid value 'fuzz.run=1' for '--config-override <KEY=VALUE>': unknown config key `fuzz.run`, did you mean `fuzz.runs`?
+
+For more information, try '--help'.
+
+"#]],
+    );
+});
@@ -170,6 +170,47 @@ fn basic_base(prj: TestProject, mut cmd: TestCommand) {
     basic_base(prj, cmd);
 });
 
+forgetest_init!(html_report, |prj, cmd| {
+    cmd.args(["coverage", "--report=html"]).assert_success().stdout_eq(str![[r#"
+...
+Wrote HTML report to [..]coverage.
+
+"#]]);
+
+    let report = prj.root().join("coverage");
+    let index = fs::read_to_string(report.join("index.html")).unwrap();
+    assert!(index.contains(r#"<a href="src/index.html">src/</a>"#), "{index}");
+    let dir = fs::read_to_string(report.join("src/index.html")).unwrap();
+    assert!(dir.contains(r#"<a href="Counter.sol.html">Counter.sol</a>"#), "{dir}");
+
+    let file = fs::read_to_string(report.join("src/Counter.sol.html")).unwrap();
+    assert!(file.contains(r#"<td class="high">100.00% (4/4)</td>"#), "{file}");
+    let increment = r#"<tr class="hit"><td class="num">12</td><td class="hits">1</td>"#;
+    assert!(file.contains(increment), "{file}");
+    let script = fs::read_to_string(report.join("script/Counter.s.sol.html")).unwrap();
+    assert!(script.contains(r#"<tr class="miss">"#), "{script}");
+});
+
+forgetest_init!(html_report_file, |prj, cmd| {
+    cmd.args(["coverage", "--report=html", "--report-file", "report/html"])
+        .assert_success()
+        .stdout_eq(str![[r#"
+...
+Wrote HTML report to [..]html.
+
+"#]]);
+    assert!(prj.root().join("report/html/src/Counter.sol.html").exists());
+    assert!(!prj.root().join("coverage").exists());
+
+    cmd.forge_fuse()
+        .args(["coverage", "--report=html", "--report=lcov", "--report-file", "report/html"])
+        .assert_failure()
+        .stderr_eq(str![[r#"
+Error: `--report-file` can only be used with a single LCOV or HTML report
+
+"#]]);
+});
+
 forgetest_init!(basic_crlf, |prj, cmd| {
     // Manually replace `\n` with `\r\n` in the source file.
     let make_crlf = |path: &Path| {
@@ -1687,6 +1728,108 @@ struct Custom {
 "#]]);
 });
 
+// Test coverage with `--instrument` is accurate with the optimizer enabled.
+forgetest!(instrument, |prj, cmd| {
+    prj.insert_ds_test();
+    prj.update_config(|config| config.optimizer = Some(true));
+    prj.add_source(
+        "AContract.sol",
+        r#"
+contract AContract {
+    uint256 public x;
+
+    function set(uint256 a) external {
+        require(a != 0, "zero");
+        if (a > 10) {
+            x = 10;
+        } else {
+            x = a;
+        }
+    }
+}
+    "#,
+    )
+    .unwrap();
+
+    prj.add_source(
+        "AContractTest.sol",
+        r#"
+import "./test.sol";
+import {AContract} from "./AContract.sol";
+
+contract AContractTest is DSTest {
+    function test_set() external {
+        AContract a = new AContract();
+        a.set(5);
+        a.set(20);
+        try a.set(0) {} catch {}
+    }
+}
+    "#,
+    )
+    .unwrap();
+
+    cmd.arg("coverage").arg("--instrument").assert_success().stdout_eq(str![[r#"
+...
+| src/AContract.sol | 100.00% [..] | 100.00% [..] | 100.00% [..] | 100.00% (1/1) |
+...
+"#]]);
+
+    // The `require` passed twice and reverted once, and each path of the `if` was taken once.
+    assert_lcov(
+        cmd.forge_fuse().args(["coverage", "--instrument"]),
+        str![[r#"
+...
+BRDA:8,0,0,2
+...
+BRDA:8,0,1,1
+...
+BRDA:9,1,0,1
+...
+BRDA:9,1,1,1
+...
+"#]],
+    );
+
+    // Without the revert, the failing branch of the `require` isn't covered.
+    prj.add_source(
+        "AContractTest.sol",
+        r#"
+import "./test.sol";
+import {AContract} from "./AContract.sol";
+
+contract AContractTest is DSTest {
+    function test_set() external {
+        AContract a = new AContract();
+        a.set(5);
+        a.set(20);
+    }
+}
+    "#,
+    )
+    .unwrap();
+
+    cmd.forge_fuse().arg("coverage").arg("--instrument").assert_success().stdout_eq(str![[r#"
+...
+| src/AContract.sol | 100.00% [..] | 100.00% [..] | 75.00% (3/4) | 100.00% (1/1) |
+...
+"#]]);
+    assert_lcov(
+        cmd.forge_fuse().args(["coverage", "--instrument"]),
+        str![[r#"
+...
+BRDA:8,0,0,2
+...
+BRDA:8,0,1,-
+...
+BRDA:9,1,0,1
+...
+BRDA:9,1,1,1
+...
+"#]],
+    );
+});
+
 forgetest!(no_artifacts_written, |prj, cmd| {
     prj.insert_ds_test();
     prj.add_source(
@@ -1747,6 +1890,204 @@ struct Custom {
     assert!(files.is_empty());
 });
 
+forgetest!(vyper, |prj, cmd| {
+    prj.insert_ds_test();
+    prj.add_raw_source(
+        "Counter.vy",
+        r#"
+# pragma version >=0.4.0
+
+number: public(uint256)
+
+@external
+def set_number(new_number: uint256):
+    self.number = new_number
+
+@external
+def increment():
+    if self.number > 10:
+        self.number = 0
+    else:
+        self.number += 1
+    "#,
+    )
+    .unwrap();
+
+    prj.add_source(
+        "CounterTest.sol",
+        r#"
+import "./test.sol";
+
+interface Vm {
+    function deployCode(string calldata artifactPath) external returns (address);
+}
+
+interface ICounter {
+    function number() external view returns (uint256);
+    function set_number(uint256 newNumber) external;
+    function increment() external;
+}
+
+contract CounterTest is DSTest {
+    Vm constant vm = Vm(HEVM_ADDRESS);
+
+    function testIncrement() external {
+        ICounter counter = ICounter(vm.deployCode("Counter.vy"));
+        counter.set_number(5);
+        counter.increment();
+        assertEq(counter.number(), 6);
+    }
+}
+    "#,
+    )
+    .unwrap();
+
+    // Assert that the Vyper source is included in the report, with the `if` branch not covered.
+    cmd.arg("coverage").assert_success().stdout_eq(str![[r#"
+...
+╭----------------+--------------+--------------+--------------+---------------╮
+| File           | % Lines      | % Statements | % Branches   | % Funcs       |
++=============================================================================+
+| src/Counter.vy | 83.33% (5/6) | 75.00% (3/4) | 50.00% (1/2) | 100.00% (2/2) |
+|----------------+--------------+--------------+--------------+---------------|
+| Total          | 83.33% (5/6) | 75.00% (3/4) | 50.00% (1/2) | 100.00% (2/2) |
+╰----------------+--------------+--------------+--------------+---------------╯
+
+"#]]);
+});
+
+forgetest!(yul_source, |prj, cmd| {
+    prj.insert_ds_test();
+    prj.add_raw_source(
+        "Counter.yul",
+        r#"
+object "Counter" {
+    code {
+        datacopy(0, dataoffset("runtime"), datasize("runtime"))
+        return(0, datasize("runtime"))
+    }
+    object "runtime" {
+        code {
+            switch shr(224, calldataload(0))
+            // number()
+            case 0x8381f58a {
+                mstore(0, sload(0))
+                return(0, 0x20)
+            }
+            // increment()
+            case 0xd09de08a {
+                sstore(0, add(sload(0), 1))
+            }
+            default {
+                revert(0, 0)
+            }
+        }
+    }
+}
+    "#,
+    )
+    .unwrap();
+
+    prj.add_source(
+        "CounterTest.sol",
+        r#"
+import "./test.sol";
+
+interface Vm {
+    function deployCode(string calldata artifactPath) external returns (address);
+}
+
+interface ICounter {
+    function number() external view returns (uint256);
+    function increment() external;
+}
+
+contract CounterTest is DSTest {
+    Vm constant vm = Vm(HEVM_ADDRESS);
+
+    function testIncrement() external {
+        ICounter counter = ICounter(vm.deployCode("Counter.yul:Counter"));
+        counter.increment();
+        assertEq(counter.number(), 1);
+    }
+}
+    "#,
+    )
+    .unwrap();
+
+    // Assert that both the creation and runtime code of the Yul source are covered, with the
+    // `default` case not covered.
+    cmd.arg("coverage").assert_success().stdout_eq(str![[r#"
+...
+╭-----------------+--------------+---------------+---------------+---------------╮
+| File            | % Lines      | % Statements  | % Branches    | % Funcs       |
++================================================================================+
+| src/Counter.yul | 77.78% (7/9) | 75.00% (9/12) | 100.00% (0/0) | 100.00% (0/0) |
+|-----------------+--------------+---------------+---------------+---------------|
+| Total           | 77.78% (7/9) | 75.00% (9/12) | 100.00% (0/0) | 100.00% (0/0) |
+╰-----------------+--------------+---------------+---------------+---------------╯
+
+"#]]);
+});
+
+// Assert that functions whose effects are never asserted on are reported.
+forgetest!(assertions, |prj, cmd| {
+    prj.insert_ds_test();
+    prj.add_source(
+        "Counter.sol",
+        r#"
+contract Counter {
+    uint256 public number;
+
+    function setNumber(uint256 newNumber) public {
+        number = newNumber;
+    }
+
+    function increment() public {
+        number++;
+    }
+}
+    "#,
+    )
+    .unwrap();
+
+    prj.add_source(
+        "CounterTest.sol",
+        r#"
+import "./test.sol";
+import {Counter} from "./Counter.sol";
+
+interface Vm {
+    function assertEq(uint256 left, uint256 right) external pure;
+}
+
+contract CounterTest is DSTest {
+    Vm constant vm = Vm(HEVM_ADDRESS);
+
+    function testIncrement() external {
+        Counter counter = new Counter();
+        counter.setNumber(41);
+        counter.increment();
+        vm.assertEq(counter.number(), 42);
+    }
+}
+    "#,
+    )
+    .unwrap();
+
+    cmd.args(["coverage", "--report=assertions"]).assert_success().stdout_eq(str![[r#"
+...
+╭-----------------+------------------+----------------------------╮
+| File            | % Asserted Funcs | Executed But Never Checked |
++=================================================================+
+| src/Counter.sol | 66.67% (2/3)     | Counter.setNumber          |
+|-----------------+------------------+----------------------------|
+| Total           | 66.67% (2/3)     |                            |
+╰-----------------+------------------+----------------------------╯
+
+"#]]);
+});
+
 #[track_caller]
 fn assert_lcov(cmd: &mut TestCommand, data: impl IntoData) {
     cmd.args(["--report=lcov", "--report-file"]).assert_file(data.into_data());
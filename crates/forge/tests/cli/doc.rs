@@ -6,3 +6,45 @@ fn can_generate_solmate_docs() {
         setup_forge_remote(RemoteProject::new("transmissions11/solmate").set_build(false));
     prj.forge_command().args(["doc", "--build"]).assert_success();
 }
+
+// tests that the pages are rendered with the template, in each language
+forgetest!(can_generate_localized_docs_with_template, |prj, cmd| {
+    prj.add_source(
+        "Greeter.sol",
+        r#"
+/// @notice Greets people.
+/// @custom:fr-notice Salue les gens.
+contract Greeter {
+    /// @notice Returns a greeting.
+    /// @custom:fr-notice Renvoie une salutation.
+    function greet() external pure returns (string memory) {
+        return "hello";
+    }
+}
+"#,
+    )
+    .unwrap();
+    std::fs::write(
+        prj.root().join("page.hbs"),
+        concat!(
+            "{{title}}: {{natspec.notice}}\n",
+            "{{#each functions}}- {{name}}: {{natspec.notice}}\n{{/each}}",
+        ),
+    )
+    .unwrap();
+    prj.update_config(|config| {
+        config.doc.template = Some("page.hbs".into());
+        config.doc.languages = vec!["fr".to_string()];
+    });
+    cmd.arg("doc").assert_success();
+
+    let page = "src/src/Greeter.sol/contract.Greeter.md";
+    assert_eq!(
+        std::fs::read_to_string(prj.root().join("docs").join(page)).unwrap(),
+        "Greeter: Greets people.\n- greet: Returns a greeting.\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(prj.root().join("docs/fr").join(page)).unwrap(),
+        "Greeter: Salue les gens.\n- greet: Renvoie une salutation.\n"
+    );
+});
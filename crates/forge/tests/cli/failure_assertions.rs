@@ -99,6 +99,63 @@
 "#);
 });
 
+forgetest!(expect_valid_signature_tests_should_fail, |prj, cmd| {
+    prj.insert_ds_test();
+    prj.insert_vm();
+
+    let expect_signature_failure_tests =
+        include_str!("../fixtures/ExpectValidSignatureFailures.t.sol");
+
+    prj.add_source("ExpectValidSignatureFailures.sol", expect_signature_failure_tests).unwrap();
+
+    cmd.forge_fuse()
+        .args(["test", "--mc", "ExpectValidSignatureFailureTest"])
+        .assert_failure()
+        .stdout_eq(r#"[COMPILING_FILES] with [SOLC_VERSION]
+[SOLC_VERSION] [ELAPSED]
+...
+[FAIL: expected `isValidSignature` of 0x5615dEB798BB3E4dFa0139dFa1b3D433Cc23b72f to accept a signature of 0x0000000000000000000000000000000000000000000000000000000000000001, but it was never called] testShouldFailExpectValidSignatureNotCalled() ([GAS])
+[FAIL: expected `isValidSignature` of 0x5615dEB798BB3E4dFa0139dFa1b3D433Cc23b72f to accept a signature of 0x0000000000000000000000000000000000000000000000000000000000000001, but it returned 0xffffffff00000000000000000000000000000000000000000000000000000000] testShouldFailExpectValidSignatureRejected() ([GAS])
+Suite result: FAILED. 0 passed; 2 failed; 0 skipped; [ELAPSED]
+...
+"#);
+});
+
+forgetest!(expect_gas_used_tests_should_fail, |prj, cmd| {
+    prj.insert_ds_test();
+    prj.insert_vm();
+
+    let expect_gas_used_failure_tests = include_str!("../fixtures/ExpectGasUsedFailures.t.sol");
+
+    prj.add_source("ExpectGasUsedFailures.sol", expect_gas_used_failure_tests).unwrap();
+    std::fs::create_dir_all(prj.root().join("snapshots")).unwrap();
+    std::fs::write(prj.root().join("snapshots/ExpectGasUsed.json"), r#"{ "set": "1" }"#).unwrap();
+
+    cmd.forge_fuse()
+        .args(["test", "--mc", "ExpectGasUsedFailureTest"])
+        .assert_failure()
+        .stdout_eq(r#"[COMPILING_FILES] with [SOLC_VERSION]
+[SOLC_VERSION] [ELAPSED]
+...
+[FAIL: expected call to use 1 gas, but it used [..]] testShouldFailExpectGasUsedExact() ([GAS])
+[FAIL: vm.expectGasUsed: minimum gas 2 is greater than maximum gas 1] testShouldFailExpectGasUsedInvalidRange() ([GAS])
+[FAIL: vm.expectGasUsed: no gas snapshot `missing` stored in [..]ExpectGasUsedFailureTest.json] testShouldFailExpectGasUsedMissingSnapshot() ([GAS])
+[FAIL: expected a call to use a specific amount of gas, but no call was made afterwards] testShouldFailExpectGasUsedNotCalled() ([GAS])
+[FAIL: expected call to use between 1 and 2 gas, but it used [..]] testShouldFailExpectGasUsedRange() ([GAS])
+[FAIL: expected call to use 1 gas, but it used [..] (snapshot `ExpectGasUsed/set`)] testShouldFailExpectGasUsedSnapshot() ([GAS])
+Suite result: FAILED. 0 passed; 6 failed; 0 skipped; [ELAPSED]
+...
+"#);
+
+    cmd.forge_fuse()
+        .args(["test", "--mt", "testShouldFailExpectGasUsedRange", "--isolate"])
+        .assert_failure()
+        .stdout_eq(r#"...
+[FAIL: expected call to use between 1 and 2 gas, but it used [..]; gas includes the transaction's intrinsic cost in isolation mode] testShouldFailExpectGasUsedRange() ([GAS])
+...
+"#);
+});
+
 forgetest!(expect_call_tests_should_fail, |prj, cmd| {
     prj.insert_ds_test();
     prj.insert_vm();
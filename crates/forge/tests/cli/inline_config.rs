@@ -1,3 +1,5 @@
+use foundry_config::figment::value::Value;
+
 forgetest!(runs, |prj, cmd| {
     prj.add_test(
         "inline.sol",
@@ -19,6 +21,8 @@
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 2 tests for test/inline.sol:Inline
 [PASS] test1(bool) (runs: 2, [AVG_GAS])
 [PASS] test2(bool) (runs: 3, [AVG_GAS])
@@ -36,6 +40,8 @@
 Analysing contracts...
 Running tests...
 
+Test run: [..]
+
 Ran 2 tests for test/inline.sol:Inline
 [PASS] test1(bool) (runs: 2, [AVG_GAS])
 [PASS] test2(bool) (runs: 3, [AVG_GAS])
@@ -89,6 +95,8 @@
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for test/inline.sol:Inline
 [FAIL: failed to get inline configuration: unknown config section `default`] test(bool) ([GAS])
 Suite result: FAILED. 0 passed; 1 failed; 0 skipped; [ELAPSED]
@@ -121,6 +129,8 @@
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for test/inline.sol:Inline
 [FAIL: failed to get inline configuration: unknown config section `default`] test(bool) ([GAS])
 Suite result: FAILED. 0 passed; 1 failed; 0 skipped; [ELAPSED]
@@ -154,6 +164,8 @@
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for test/inline.sol:Inline
 [FAIL: invalid type: found sequence, expected u32 for key "default.fuzz.runs" in inline config] setUp() ([GAS])
 Suite result: FAILED. 0 passed; 1 failed; 0 skipped; [ELAPSED]
@@ -186,6 +198,8 @@
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for test/inline.sol:Inline
 [FAIL: invalid type: found string "2", expected u32 for key "default.fuzz.runs" in inline config] setUp() ([GAS])
 Suite result: FAILED. 0 passed; 1 failed; 0 skipped; [ELAPSED]
@@ -256,6 +270,8 @@
 
     cmd.args(["test", "--evm-version=cancun", "-j1"]).assert_success().stdout_eq(str![[r#"
 ...
+Test run: [..]
+
 Ran 2 tests for test/inline.sol:ContractConfig
 [PASS] test_new() ([GAS])
 [PASS] test_old() ([GAS])
@@ -270,3 +286,54 @@
 
 "#]]);
 });
+
+forgetest!(overrides, |prj, cmd| {
+    prj.add_test(
+        "integration/Integration.t.sol",
+        "
+        contract Integration {
+            function test1(bool) public {}
+
+            /// forge-config: default.fuzz.runs = 3
+            function test2(bool) public {}
+        }
+    ",
+    )
+    .unwrap();
+    prj.add_test(
+        "unit/Unit.t.sol",
+        "
+        contract Unit {
+            function test(bool) public {}
+        }
+    ",
+    )
+    .unwrap();
+    prj.update_config(|config| {
+        config.fuzz.runs = 2;
+        let fuzz = [("runs".to_string(), Value::serialize(5).unwrap())].into_iter().collect();
+        config.overrides.insert(
+            "test/integration/**".to_string(),
+            [("fuzz".to_string(), Value::Dict(Default::default(), fuzz))].into_iter().collect(),
+        );
+    });
+
+    cmd.args(["test", "--mc", "Integration"]).assert_success().stdout_eq(str![[r#"
+...
+Test run: [..]
+
+Ran 2 tests for test/integration/Integration.t.sol:Integration
+[PASS] test1(bool) (runs: 5, [AVG_GAS])
+[PASS] test2(bool) (runs: 3, [AVG_GAS])
+...
+"#]]);
+
+    cmd.forge_fuse().args(["test", "--mc", "Unit"]).assert_success().stdout_eq(str![[r#"
+...
+Test run: [..]
+
+Ran 1 test for test/unit/Unit.t.sol:Unit
+[PASS] test(bool) (runs: 2, [AVG_GAS])
+...
+"#]]);
+});
@@ -0,0 +1,44 @@
+forgetest!(lints, |prj, cmd| {
+    prj.add_source(
+        "Lint.sol",
+        r#"
+import {Vm} from "./Vm.sol";
+
+contract Lint {
+    address owner;
+
+    function pay(address owner) public {
+        payable(owner).send(1);
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    cmd.args(["lint", "src"]).assert_code(1).stdout_eq(str![[r#"
+info[unused-import]: `Vm` is imported but never used
+  --> src/Lint.sol:2:9
+
+low[shadowed-variable]: `owner` shadows a state variable
+  --> src/Lint.sol:7:26
+
+high[unchecked-call]: the return value of the low-level `send` is not checked
+  --> src/Lint.sol:8:24
+
+Found 3 lint(s)
+
+"#]]);
+
+    cmd.forge_fuse().args(["lint", "src", "--severity", "medium"]);
+    cmd.assert_code(1).stdout_eq(str![[r#"
+high[unchecked-call]: the return value of the low-level `send` is not checked
+  --> src/Lint.sol:8:24
+
+Found 1 lint(s)
+
+"#]]);
+
+    prj.update_config(|config| config.lint.exclude_lints = vec!["unchecked-call".to_string()]);
+    cmd.forge_fuse().args(["lint", "src", "--only", "unchecked-call"]);
+    cmd.assert_empty_stdout();
+});
@@ -4,6 +4,7 @@
 pub mod constants;
 pub mod utils;
 
+mod analyze;
 mod bind_json;
 mod build;
 mod cache;
@@ -19,6 +20,7 @@
 mod failure_assertions;
 mod geiger;
 mod inline_config;
+mod lint;
 mod multi_script;
 mod odyssey;
 mod script;
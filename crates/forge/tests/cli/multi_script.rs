@@ -22,6 +22,41 @@
     assert_eq!(api2.transaction_count(tester.accounts_pub[1], None).await.unwrap().to::<u32>(), 3);
 });
 
+// tests that the transactions of every chain are broadcast together and saved to its logs, even
+// if the script switches back and forth between the chains
+forgetest_async!(can_deploy_interleaved_multi_chain_script, |prj, cmd| {
+    let (api1, handle1) = spawn(NodeConfig::test().with_chain_id(Some(1337u64))).await;
+    let (api2, handle2) = spawn(NodeConfig::test().with_chain_id(Some(1338u64))).await;
+    let mut tester = ScriptTester::new_broadcast_without_endpoint(cmd, prj.root());
+
+    tester
+        .load_private_keys(&[0])
+        .await
+        .add_sig("MultiChainBroadcastNoLink", "deployInterleaved(string memory,string memory)")
+        .args(&[&handle1.http_endpoint(), &handle2.http_endpoint()])
+        .broadcast(ScriptOutcome::OkBroadcast);
+
+    assert_eq!(api1.transaction_count(tester.accounts_pub[0], None).await.unwrap().to::<u32>(), 2);
+    assert_eq!(api2.transaction_count(tester.accounts_pub[0], None).await.unwrap().to::<u32>(), 1);
+
+    let broadcast = prj.root().join("broadcast");
+    let multi: serde_json::Value = foundry_common::fs::read_json_file(
+        &broadcast.join("multi/Broadcast.t.sol-latest/deployInterleaved.json"),
+    )
+    .unwrap();
+    let deployments = multi["deployments"].as_array().unwrap();
+    assert_eq!(deployments.len(), 2);
+    for (deployment, (chain, txs)) in deployments.iter().zip([(1337, 2), (1338, 1)]) {
+        assert_eq!(deployment["chain"], chain);
+        assert_eq!(deployment["transactions"].as_array().unwrap().len(), txs);
+
+        let path = broadcast.join(format!("Broadcast.t.sol/{chain}/deployInterleaved-latest.json"));
+        let log: serde_json::Value = foundry_common::fs::read_json_file(&path).unwrap();
+        assert_eq!(log["chain"], chain);
+        assert_eq!(log["receipts"].as_array().unwrap().len(), txs);
+    }
+});
+
 forgetest_async!(can_not_deploy_multi_chain_script_with_lib, |prj, cmd| {
     let (_, handle1) = spawn(NodeConfig::test()).await;
     let (_, handle2) = spawn(NodeConfig::test()).await;
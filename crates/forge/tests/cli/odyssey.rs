@@ -11,6 +11,8 @@
 Compiler run successful with warnings:
 Warning (3805): This is a pre-release compiler version, please do not use it in production.
 
+Test run: [..]
+
 Ran 2 tests for test/Counter.t.sol:CounterTest
 [PASS] testFuzz_SetNumber(uint256) (runs: 256, [AVG_GAS])
 [PASS] test_Increment() ([GAS])
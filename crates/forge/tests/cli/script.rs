@@ -2,6 +2,7 @@
 
 use crate::constants::TEMPLATE_CONTRACT;
 use alloy_primitives::{address, hex, Address, Bytes};
+use alloy_provider::Provider;
 use anvil::{spawn, NodeConfig};
 use forge_script_sequence::ScriptSequence;
 use foundry_test_utils::{
@@ -785,6 +786,122 @@
         .await;
 });
 
+// Resuming a finished broadcast must not send any of its transactions again.
+forgetest_async!(can_resume_finished_broadcast, |prj, cmd| {
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let mut tester = ScriptTester::new_broadcast(cmd, &handle.http_endpoint(), prj.root());
+
+    tester
+        .load_private_keys(&[0, 1])
+        .await
+        .add_sig("BroadcastTest", "deploy()")
+        .broadcast(ScriptOutcome::OkBroadcast)
+        .assert_nonce_increment(&[(0, 2), (1, 1)])
+        .await
+        .resume(ScriptOutcome::OkBroadcast)
+        .assert_nonce_increment(&[(0, 2), (1, 1)])
+        .await;
+});
+
+/// Rewrites the broadcast log of `deployDoesntPanic()` as if its transactions were signed and
+/// recorded as pending, but the broadcast was interrupted before any of them was sent.
+fn mark_broadcast_unsent(root: &std::path::Path) {
+    let path = root.join("broadcast/Broadcast.t.sol/31337/deployDoesntPanic-latest.json");
+    let mut log: Value = foundry_common::fs::read_json_file(&path).unwrap();
+    let mut pending = vec![];
+    for tx in log["transactions"].as_array_mut().unwrap() {
+        tx["status"] = "pending".into();
+        pending.push(tx["hash"].clone());
+    }
+    log["pending"] = pending.into();
+    log["receipts"] = Value::Array(vec![]);
+    foundry_common::fs::write_json_file(&path, &log).unwrap();
+}
+
+// Resuming a broadcast whose transactions were recorded as pending but never sent must send each
+// of them exactly once.
+forgetest_async!(can_resume_recorded_unsent_broadcast, |prj, cmd| {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let mut tester = ScriptTester::new_broadcast(cmd, &handle.http_endpoint(), prj.root());
+
+    tester
+        .load_private_keys(&[0, 1])
+        .await
+        .add_sig("BroadcastTestNoLinking", "deployDoesntPanic()")
+        .args(&["--timeout", "1"]);
+
+    // Broadcast, then undo it on chain so that the recorded hashes are unknown to the node.
+    let snapshot = api.evm_snapshot().await.unwrap();
+    tester.broadcast(ScriptOutcome::OkBroadcast);
+    assert!(api.evm_revert(snapshot).await.unwrap());
+    mark_broadcast_unsent(prj.root());
+
+    tester
+        .resume(ScriptOutcome::OkBroadcast)
+        .assert_nonce_increment(&[(0, 1), (1, 2)])
+        .await
+        // Resuming again doesn't send anything.
+        .run(ScriptOutcome::OkBroadcast)
+        .assert_nonce_increment(&[(0, 1), (1, 2)])
+        .await;
+
+    let log: ScriptSequence = foundry_common::fs::read_json_file(
+        &prj.root().join("broadcast/Broadcast.t.sol/31337/deployDoesntPanic-latest.json"),
+    )
+    .unwrap();
+    assert_eq!(log.receipts.len(), 3);
+    assert!(log.pending.is_empty());
+});
+
+// Resuming a broadcast whose pending transaction was replaced by another one with the same nonce
+// must not send it again, but must still send the other ones.
+forgetest_async!(can_resume_replaced_broadcast, |prj, cmd| {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let mut tester = ScriptTester::new_broadcast(cmd, &handle.http_endpoint(), prj.root());
+
+    tester
+        .load_private_keys(&[0, 1])
+        .await
+        .add_sig("BroadcastTestNoLinking", "deployDoesntPanic()")
+        .args(&["--timeout", "1"]);
+
+    let snapshot = api.evm_snapshot().await.unwrap();
+    tester.broadcast(ScriptOutcome::OkBroadcast);
+    assert!(api.evm_revert(snapshot).await.unwrap());
+    mark_broadcast_unsent(prj.root());
+
+    // Use the first nonce of the second sender for another transaction.
+    let sender = tester.accounts_pub[1];
+    let tx = alloy_rpc_types::TransactionRequest::default().from(sender).to(sender).nonce(0);
+    api.send_transaction(alloy_serde::WithOtherFields::new(tx)).await.unwrap();
+
+    tester.cmd.arg("--resume");
+    let out = tester.cmd.execute();
+    let err = String::from_utf8_lossy(&out.stderr);
+    assert!(!out.status.success());
+    assert!(
+        err.contains("Transaction replaced by another transaction with the same nonce"),
+        "{err}"
+    );
+    tester.assert_nonce_increment(&[(0, 0), (1, 1)]).await;
+
+    // The replaced transaction is skipped, the others are sent.
+    tester.run(ScriptOutcome::OkBroadcast).assert_nonce_increment(&[(0, 1), (1, 2)]).await;
+
+    let log: Value = foundry_common::fs::read_json_file(
+        &prj.root().join("broadcast/Broadcast.t.sol/31337/deployDoesntPanic-latest.json"),
+    )
+    .unwrap();
+    let statuses = log["transactions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tx| tx["status"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(statuses, ["confirmed", "replaced", "confirmed"]);
+    assert_eq!(log["receipts"].as_array().unwrap().len(), 2);
+});
+
 forgetest_async!(can_deploy_broadcast_wrap, |prj, cmd| {
     let (_api, handle) = spawn(NodeConfig::test()).await;
     let mut tester = ScriptTester::new_broadcast(cmd, &handle.http_endpoint(), prj.root());
@@ -2616,3 +2733,170 @@ struct Point {
 
 "#]]);
 });
+
+// Asserts that `--simulate-at` reports transactions whose outcome depends on the block.
+forgetest_async!(simulate_at_reports_block_sensitive_txs, |prj, cmd| {
+    foundry_test_utils::util::initialize(prj.root());
+    prj.add_script(
+        "Stamp",
+        r#"
+import "forge-std/Script.sol";
+
+contract Stamp {
+    uint256 public at = block.number;
+}
+
+contract StampScript is Script {
+    function run() external {
+        vm.startBroadcast();
+        new Stamp();
+    }
+}
+   "#,
+    )
+    .unwrap();
+
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    api.mine_one().await;
+    api.mine_one().await;
+
+    cmd.args([
+        "script",
+        "StampScript",
+        "--fork-url",
+        &handle.http_endpoint(),
+        "--sender",
+        "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266",
+        "--simulate-at",
+        "head-1,head,next",
+    ])
+    .assert_success()
+    .stdout_eq(str![[r#"
+...
+## Block variance (head-1, head, next)
+
+[0] CREATE Stamp
+  head-1: state changes differ
+  next: state changes differ
+...
+"#]]);
+});
+
+// tests that concurrent scripts broadcasting from the same sender queue behind each other
+forgetest_async!(concurrent_scripts_use_sequential_nonces, |prj, cmd| {
+    foundry_test_utils::util::initialize(prj.root());
+    prj.add_script(
+        "Deploy.s.sol",
+        r#"
+import {Script} from "forge-std/Script.sol";
+
+contract Counter {
+    uint256 public number;
+}
+
+contract DeployScript is Script {
+    function run() public {
+        vm.startBroadcast();
+        new Counter();
+        new Counter();
+        new Counter();
+        vm.stopBroadcast();
+    }
+}
+   "#,
+    )
+    .unwrap();
+
+    // A chain of its own, so that the nonces reserved by the test don't leak into the others.
+    let (_api, handle) = spawn(NodeConfig::test().with_chain_id(Some(7_357_001u64))).await;
+    let rpc = handle.http_endpoint();
+    let dev = handle.dev_accounts().next().unwrap();
+    let provider = handle.http_provider();
+    let nonce = provider.get_transaction_count(dev).await.unwrap();
+
+    // Compile upfront so that the scripts don't race on the build.
+    cmd.args(["build"]).assert_success();
+
+    let scripts = (0..2)
+        .map(|_| {
+            prj.forge_bin()
+                .args([
+                    "script",
+                    "DeployScript",
+                    "--rpc-url",
+                    rpc.as_str(),
+                    "--sender",
+                    format!("{dev:?}").as_str(),
+                    "--unlocked",
+                    "--broadcast",
+                    "--nonce-strategy",
+                    "sequential",
+                ])
+                .stdout(std::process::Stdio::null())
+                .spawn()
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    for mut script in scripts {
+        assert!(script.wait().unwrap().success());
+    }
+
+    assert_eq!(provider.get_transaction_count(dev).await.unwrap(), nonce + 6);
+});
+
+// Asserts that `--barrier-on` simulates each batch on top of the previous one before sending it.
+forgetest_async!(can_broadcast_with_batch_barrier, |prj, cmd| {
+    foundry_test_utils::util::initialize(prj.root());
+    prj.add_script(
+        "Counter.s.sol",
+        r#"
+import {Script} from "forge-std/Script.sol";
+
+contract Counter {
+    uint256 public number;
+
+    function increment() public {
+        number++;
+    }
+}
+
+contract CounterScript is Script {
+    function run() public {
+        vm.startBroadcast();
+        Counter counter = new Counter();
+        counter.increment();
+        counter.increment();
+        vm.stopBroadcast();
+    }
+}
+   "#,
+    )
+    .unwrap();
+
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let dev = handle.dev_accounts().next().unwrap();
+    let provider = handle.http_provider();
+
+    cmd.args([
+        "script",
+        "CounterScript",
+        "--rpc-url",
+        &handle.http_endpoint(),
+        "--sender",
+        &format!("{dev:?}"),
+        "--unlocked",
+        "--broadcast",
+        "--batch-size",
+        "1",
+        "--barrier-on",
+        "receipts",
+    ])
+    .assert_success()
+    .stdout_eq(str![[r#"
+...
+ONCHAIN EXECUTION COMPLETE & SUCCESSFUL.
+...
+"#]]);
+
+    assert_eq!(provider.get_transaction_count(dev).await.unwrap(), 3);
+});
@@ -59,6 +59,8 @@ async fn ensure_latest_release(platform: Platform) {
     prj.add_test("Counter", &src).unwrap();
     cmd.arg("test").assert_success().stdout_eq(str![[r#"
 ...
+Test run: [..]
+
 Ran 1 test for test/Counter.sol:CounterTest
 [PASS] testAssert() ([GAS])
 Suite result: ok. 1 passed; 0 failed; 0 skipped; [ELAPSED]
@@ -139,6 +139,8 @@
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for src/ATest.t.sol:ATest
 [PASS] testArray(uint64[2]) (runs: 256, [AVG_GAS])
 Suite result: ok. 1 passed; 0 failed; 0 skipped; [ELAPSED]
@@ -172,6 +174,8 @@
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for src/ATest.t.sol:ATest
 [PASS] testArray(uint64[2]) (runs: 256, [AVG_GAS])
 Suite result: ok. 1 passed; 0 failed; 0 skipped; [ELAPSED]
@@ -216,6 +220,8 @@
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for src/ATest.t.sol:ATest
 [PASS] testPass() ([GAS])
 Suite result: ok. 1 passed; 0 failed; 0 skipped; [ELAPSED]
@@ -263,6 +269,8 @@
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for src/ATest.t.sol:ATest
 [PASS] testPass() ([GAS])
 Suite result: ok. 1 passed; 0 failed; 0 skipped; [ELAPSED]
@@ -345,6 +353,8 @@
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for src/nested/forge-tests/MyTest.t.sol:MyTest
 [PASS] testTrue() ([GAS])
 Suite result: ok. 1 passed; 0 failed; 0 skipped; [ELAPSED]
@@ -364,6 +374,8 @@
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 2 tests for test/Counter.t.sol:CounterTest
 [PASS] testFuzz_SetNumber(uint256) (runs: 256, [AVG_GAS])
 [PASS] test_Increment() ([GAS])
@@ -377,6 +389,8 @@
         cmd.assert_success().stdout_eq(str![[r#"
 No files changed, compilation skipped
 
+Test run: [..]
+
 Ran 2 tests for test/Counter.t.sol:CounterTest
 [PASS] testFuzz_SetNumber(uint256) (runs: 256, [AVG_GAS])
 [PASS] test_Increment() ([GAS])
@@ -420,6 +434,8 @@
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for src/Contract.t.sol:ContractTest
 [PASS] testExample() ([GAS])
 Suite result: ok. 1 passed; 0 failed; 0 skipped; [ELAPSED]
@@ -438,6 +454,8 @@
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for src/Contract.t.sol:ContractTest
 [PASS] testExample() ([GAS])
 Suite result: ok. 1 passed; 0 failed; 0 skipped; [ELAPSED]
@@ -499,6 +517,8 @@
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for test/Contract.t.sol:ContractTest
 [PASS] test() ([GAS])
 Suite result: ok. 1 passed; 0 failed; 0 skipped; [ELAPSED]
@@ -540,6 +560,40 @@
     cmd.assert_empty_stderr();
 });
 
+// Tests that `--fail-fast` stops the running fuzz tests after the first failure.
+forgetest_init!(fail_fast_cancels_running_tests, |prj, cmd| {
+    prj.wipe_contracts();
+    prj.add_test(
+        "FailFast.t.sol",
+        r#"
+import "forge-std/Test.sol";
+
+contract FailFastTest is Test {
+    /// forge-config: default.fuzz.runs = 100000000
+    function testFuzz_Slow(uint256 x) public pure {
+        assertGe(x, 0);
+    }
+
+    function test_Fail() public pure {
+        revert("failed");
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    cmd.args(["test", "--fail-fast"]).assert_failure().stdout_eq(str![[r#"
+...
+Test run: [..]
+
+Ran 2 tests for test/FailFast.t.sol:FailFastTest
+[SKIP: cancelled after the first failure] testFuzz_Slow(uint256) [..]
+[FAIL: revert: failed] test_Fail() ([GAS])
+Suite result: FAILED. 0 passed; 1 failed; 1 skipped; [ELAPSED]
+...
+"#]]);
+});
+
 // https://github.com/foundry-rs/foundry/pull/6531
 forgetest_init!(fork_traces, |prj, cmd| {
     prj.wipe_contracts();
@@ -571,6 +625,8 @@
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for test/Contract.t.sol:USDTCallingTest
 [PASS] test() ([GAS])
 Traces:
@@ -617,6 +673,8 @@
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 2 tests for test/Contract.t.sol:CustomTypesTest
 [FAIL: PoolNotInitialized()] testErr() ([GAS])
 Traces:
@@ -792,6 +850,8 @@
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for test/CounterFuzz.t.sol:CounterTest
 [FAIL: panic: arithmetic underflow or overflow (0x11); counterexample: calldata=0xa76d58f5ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff args=[115792089237316195423570985008687907853269984665640564039457584007913129639935 [1.157e77]]] testAddOne(uint256) (runs: 61, [AVG_GAS])
 Suite result: FAILED. 0 passed; 1 failed; 0 skipped; [ELAPSED]
@@ -843,6 +903,8 @@
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for test/CounterInvariant.t.sol:CounterTest
 [FAIL: failed to set up invariant testing environment: wrong count] invariant_early_exit() (runs: 0, calls: 0, reverts: 0)
 Suite result: FAILED. 0 passed; 1 failed; 0 skipped; [ELAPSED]
@@ -891,6 +953,8 @@
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 4 tests for test/ReplayFailures.t.sol:ReplayFailuresTest
 [PASS] testA() ([GAS])
 [FAIL: revert: testB failed] testB() ([GAS])
@@ -916,6 +980,8 @@
     cmd.forge_fuse().args(["test", "--rerun"]).assert_failure().stdout_eq(str![[r#"
 No files changed, compilation skipped
 
+Test run: [..]
+
 Ran 2 tests for test/ReplayFailures.t.sol:ReplayFailuresTest
 [FAIL: revert: testB failed] testB() ([GAS])
 [FAIL: revert: testD failed] testD() ([GAS])
@@ -994,6 +1060,8 @@
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for test/Contract.t.sol:PrecompileLabelsTest
 [PASS] testPrecompileLabels() ([GAS])
 Traces:
@@ -1068,6 +1136,8 @@
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for test/ContractFuzz.t.sol:ContractFuzz
 [PASS] testFuzzConsoleLog(uint256) (runs: 3, [AVG_GAS])
 Logs:
@@ -1113,6 +1183,8 @@
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for test/ContractFuzz.t.sol:ContractFuzz
 [PASS] testFuzzConsoleLog(uint256) (runs: 3, [AVG_GAS])
 Logs:
@@ -1158,6 +1230,8 @@
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for test/ContractFuzz.t.sol:ContractFuzz
 [PASS] testFuzzConsoleLog(uint256) (runs: 3, [AVG_GAS])
 Suite result: ok. 1 passed; 0 failed; 0 skipped; [ELAPSED]
@@ -1198,6 +1272,8 @@
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for test/ContractFuzz.t.sol:ContractFuzz
 [PASS] testFuzzConsoleLog(uint256) (runs: 3, [AVG_GAS])
 Suite result: ok. 1 passed; 0 failed; 0 skipped; [ELAPSED]
@@ -1257,6 +1333,8 @@
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for test/Simple.sol:SimpleContractTest
 [PASS] test() ([GAS])
 Traces:
@@ -1409,6 +1487,26 @@ fn extract_test_result(out: &str) -> &str {
     let res4 = extract_test_result(&out4);
     assert_ne!(res4, res1);
     assert_ne!(res4, res3);
+
+    // The header of the run reports the seed, if any.
+    assert!(out4.contains("Test run: seed random,"), "{out4}");
+    assert!(out1.contains(&format!("Test run: seed {seed1},")), "{out1}");
+
+    // With `--json-header`, the header is printed as a separate JSON object before the results.
+    let out5 = cmd
+        .forge_fuse()
+        .args(["test", "--seed", seed1, "--json", "--json-header"])
+        .assert_success()
+        .get_output()
+        .stdout_lossy();
+    let mut lines = out5.lines();
+    let header: serde_json::Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+    assert_eq!(header["run"]["seed"], seed1);
+    let results: serde_json::Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+    assert!(results.get("run").is_none());
+    assert!(results
+        .get("test/DeterministicRandomnessTest.t.sol:DeterministicRandomnessTest")
+        .is_some());
 });
 
 // Tests that `pauseGasMetering` used at the end of test does not produce meaningless values.
@@ -1488,6 +1586,8 @@ fn extract_test_result(out: &str) -> &str {
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for test/ATest.t.sol:ATest
 [PASS] testSelfMeteringRevert() ([GAS])
 Suite result: ok. 1 passed; 0 failed; 0 skipped; [ELAPSED]
@@ -2015,6 +2115,8 @@ fn extract_test_result(out: &str) -> &str {
 
     cmd.arg("test").assert_success().stdout_eq(str![[r#"
 ...
+Test run: [..]
+
 Ran 6 tests for src/Counter.t.sol:Skips
 [SKIP] invariant_skipInvariant() (runs: 1, calls: 1, reverts: 1)
 [SKIP: invariant] invariant_skipInvariantReason() (runs: 1, calls: 1, reverts: 1)
@@ -2062,6 +2164,8 @@ fn extract_test_result(out: &str) -> &str {
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for test/Counter.t.sol:SkipCounterSetup
 [SKIP: skipped: skip counter test] setUp() ([GAS])
 Suite result: ok. 0 passed; 0 failed; 1 skipped; [ELAPSED]
@@ -2184,6 +2288,67 @@ fn extract_test_result(out: &str) -> &str {
 "#]]);
 });
 
+forgetest_init!(should_write_junit_report_and_github_annotations, |prj, cmd| {
+    prj.wipe_contracts();
+    prj.add_source(
+        "AnnotationsTest.t.sol",
+        r#"
+import "forge-std/Test.sol";
+import "./AnnotationsBase.sol";
+
+contract Target {
+    function fail() public pure { revert("Target failed"); }
+}
+
+contract AnnotationsTest is AnnotationsBase {
+    function test_fail() public pure {
+        require(1 > 2, "Revert");
+    }
+
+    function test_fail_in_call() public {
+        Target target = new Target();
+        vm.label(address(target), "Target");
+        target.fail();
+    }
+}
+   "#,
+    )
+    .unwrap();
+    prj.add_source(
+        "AnnotationsBase.sol",
+        r#"
+import "forge-std/Test.sol";
+
+abstract contract AnnotationsBase is Test {
+    function test_inherited() public pure {
+        revert("Inherited");
+    }
+}
+   "#,
+    )
+    .unwrap();
+
+    // The failures are annotated where they happened, in the base contract for inherited tests.
+    cmd.args(["test", "--junit", "report.xml", "--github-annotations"]).assert_failure().stdout_eq(
+        str![[r#"
+...
+Ran 3 tests for src/AnnotationsTest.t.sol:AnnotationsTest
+[FAIL: revert: Revert] test_fail() ([GAS])
+[FAIL: revert: Target failed] test_fail_in_call() ([GAS])
+[FAIL: revert: Inherited] test_inherited() ([GAS])
+...
+::error file=src/AnnotationsTest.t.sol,line=13,title=AnnotationsTest%3A%3Atest_fail()::revert: Revert
+::error file=src/AnnotationsTest.t.sol,line=19,title=AnnotationsTest%3A%3Atest_fail_in_call()::revert: Target failed%0AReverted in a call to Target
+::error file=src/AnnotationsBase.sol,line=8,title=AnnotationsTest%3A%3Atest_inherited()::revert: Inherited
+...
+"#]],
+    );
+
+    let report = std::fs::read_to_string(prj.root().join("report.xml")).unwrap();
+    assert!(report.contains(r#"<testsuites name="Test run" tests="3" failures="3""#), "{report}");
+    assert!(report.contains(r#"<failure message="revert: Target failed"/>"#), "{report}");
+});
+
 forgetest_init!(
     // Enable this if no cheatcodes are deprecated.
     // #[ignore = "no cheatcodes are deprecated"]
@@ -2325,6 +2490,8 @@ fn extract_test_result(out: &str) -> &str {
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for src/Foo.sol:FooTest
 [PASS] testWalletScript() ([GAS])
 Logs:
@@ -2392,6 +2559,8 @@ fn extract_test_result(out: &str) -> &str {
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for test/MetadataTraceTest.t.sol:MetadataTraceTest
 [PASS] test_proxy_trace() ([GAS])
 Traces:
@@ -2417,6 +2586,8 @@ fn extract_test_result(out: &str) -> &str {
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for test/MetadataTraceTest.t.sol:MetadataTraceTest
 [PASS] test_proxy_trace() ([GAS])
 Traces:
@@ -2630,6 +2801,8 @@ struct PotentialRevert {
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 8 tests for src/AssumeNoRevertTest.t.sol:ReverterTest
 [FAIL: expected 0 reverts with reason: 0x92fa317b, but got one; counterexample: [..]] testAssumeThenExpectCountZeroFails(uint256) (runs: [..], [AVG_GAS])
 [FAIL: MyRevert(); counterexample: calldata=[..]] testAssumeWithReverter_fails(uint256) (runs: [..], [AVG_GAS])
@@ -2884,6 +3057,8 @@ struct PotentialRevert {
 
     cmd.args(["test", "--mt", "test_fork_err_message"]).assert_failure().stdout_eq(str![[r#"
 ...
+Test run: [..]
+
 Ran 1 test for test/ForkTest.t.sol:ForkTest
 [FAIL: vm.createSelectFork: could not instantiate forked environment with provider eth-mainnet.g.alchemy.com; failed to get latest block number; [..]] test_fork_err_message() ([GAS])
 Suite result: FAILED. 0 passed; 1 failed; 0 skipped; [ELAPSED]
@@ -2897,6 +3072,8 @@ struct PotentialRevert {
 forgetest_init!(should_show_state_changes, |prj, cmd| {
     cmd.args(["test", "--mt", "test_Increment", "-vvvvv"]).assert_success().stdout_eq(str![[r#"
 ...
+Test run: [..]
+
 Ran 1 test for test/Counter.t.sol:CounterTest
 [PASS] test_Increment() ([GAS])
 Traces:
@@ -2978,6 +3155,8 @@ struct PotentialRevert {
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for test/DebugTraceRecordingTest.t.sol:DebugTraceRecordingTest
 [PASS] test_start_stop_recording() ([GAS])
 Traces:
@@ -2998,3 +3177,562 @@ struct PotentialRevert {
         .assert_success()
         .stdout_eq(file!["../fixtures/colored_traces.svg": TermSvg]);
 });
+
+forgetest_init!(writes_failure_artifacts, |prj, cmd| {
+    prj.wipe_contracts();
+
+    prj.add_test(
+        "Contract.t.sol",
+        r#"
+import {Test, console} from "forge-std/Test.sol";
+
+contract ArtifactsTest is Test {
+    function testPass() public pure {}
+
+    function test_RevertWhen_Fails() public pure {
+        console.log("about to fail");
+        revert("failure");
+    }
+}
+   "#,
+    )
+    .unwrap();
+
+    cmd.args(["test", "--failure-artifacts"]).assert_failure().stdout_eq(str![[r#"
+...
+Failing tests:
+Encountered 1 failing test in test/Contract.t.sol:ArtifactsTest
+[FAIL: failure] test_RevertWhen_Fails() ([GAS])
+  Artifacts: [..]/out/test-artifacts/test/Contract.t.sol/ArtifactsTest/test_RevertWhen_Fails()
+
+Encountered a total of 1 failing tests, 1 tests succeeded
+
+"#]]);
+
+    let artifacts = prj.root().join("out/test-artifacts/test/Contract.t.sol/ArtifactsTest");
+    assert!(!artifacts.join("testPass()").exists());
+
+    let dir = artifacts.join("test_RevertWhen_Fails()");
+    let trace = std::fs::read_to_string(dir.join("trace.txt")).unwrap();
+    assert!(trace.contains("ArtifactsTest::test_RevertWhen_Fails()"), "{trace}");
+    assert_eq!(std::fs::read_to_string(dir.join("logs.txt")).unwrap(), "about to fail");
+    let gas: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.join("gas.json")).unwrap()).unwrap();
+    let gas = gas.as_array().unwrap();
+    assert!(gas.iter().any(|call| call["function"] == "test_RevertWhen_Fails()"));
+    assert!(dir.join("debugger.json").exists());
+});
+
+forgetest!(runs_benchmarks_against_baseline, |prj, cmd| {
+    let bench = |iterations: u32| {
+        format!(
+            r#"
+contract SumBench {{
+    uint256 public sum;
+
+    function testSum() public {{}}
+
+    function benchmarkIterations() public pure returns (uint256) {{
+        return {iterations};
+    }}
+
+    function bench_sum() public {{
+        for (uint256 i; i < benchmarkIterations(); i++) {{
+            sum += i;
+        }}
+    }}
+}}
+   "#
+        )
+    };
+    prj.add_test("Sum.t.sol", &bench(10)).unwrap();
+
+    cmd.args(["bench", "--runs", "20", "--save-baseline"]).assert_success().stdout_eq(str![[r#"
+...
+[PASS] bench_sum() (runs: 20, gas ~: [..] [[..], [..]], p95: [..], time ~: [..] [[..], [..]], p95: [..])
+...
+Saved baseline to .bench-baseline.json
+
+"#]]);
+    let baseline: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(prj.root().join(".bench-baseline.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(baseline["SumBench"]["bench_sum()"]["runs"], 20);
+    assert!(baseline["SumBench"].get("testSum()").is_none());
+    assert!(baseline["SumBench"].get("benchmarkIterations()").is_none());
+
+    cmd.forge_fuse().args(["bench", "--runs", "20", "--ignore-time"]).assert_success().stdout_eq(
+        str![[r#"
+...
+Comparing against .bench-baseline.json:
+[OK] SumBench:bench_sum() (gas: +0.00%)
+
+"#]],
+    );
+
+    prj.add_test("Sum.t.sol", &bench(20)).unwrap();
+    cmd.forge_fuse().args(["bench", "--runs", "20", "--ignore-time"]).assert_failure().stdout_eq(
+        str![[r#"
+...
+[REGRESSED] SumBench:bench_sum() (gas: [..])
+
+"#]],
+    );
+});
+
+forgetest_init!(exports_traces, |prj, cmd| {
+    prj.wipe_contracts();
+    prj.add_test(
+        "Counter.t.sol",
+        r#"
+import {Test} from "forge-std/Test.sol";
+
+contract Counter {
+    uint256 public number;
+
+    function increment() public {
+        number++;
+    }
+}
+
+contract CounterTest is Test {
+    Counter counter;
+
+    function setUp() public {
+        counter = new Counter();
+    }
+
+    function testIncrement() public {
+        counter.increment();
+    }
+}
+   "#,
+    )
+    .unwrap();
+
+    cmd.args(["test", "--export-traces", "speedscope"]).assert_success();
+    let path = prj.root().join("cache/traces/CounterTest/testIncrement.speedscope.json");
+    let profile: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    let profiles = profile["profiles"].as_array().unwrap();
+    assert_eq!(profiles.iter().map(|p| &p["name"]).collect::<Vec<_>>(), ["setup", "execution"]);
+    let frames = profile["shared"]["frames"].as_array().unwrap();
+    assert!(frames.iter().any(|f| f["name"] == "new Counter"), "{frames:?}");
+    assert!(frames.iter().any(|f| f["name"] == "Counter.increment()"), "{frames:?}");
+
+    cmd.forge_fuse().args(["test", "--export-traces", "otlp"]).assert_success();
+    let path = prj.root().join("cache/traces/CounterTest/testIncrement.otlp.json");
+    let request: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    let spans = request["resourceSpans"][0]["scopeSpans"][0]["spans"].as_array().unwrap();
+    assert!(spans.iter().any(|s| s["name"] == "CounterTest::testIncrement"), "{spans:?}");
+});
+
+// tests that `memory_safety` fails the tests breaking the memory model of solc, even in a caught
+// call, and points to the source of the violation
+forgetest_init!(test_memory_safety, |prj, cmd| {
+    prj.wipe_contracts();
+    prj.update_config(|config| config.memory_safety = true);
+    prj.add_test(
+        "MemorySafetyTest.t.sol",
+        r#"
+import {Test} from "forge-std/Test.sol";
+
+contract Unsafe {
+    function dirtyZeroSlot() external pure {
+        assembly {
+            mstore(0x60, 1)
+        }
+    }
+}
+
+contract Scratch {
+    function readScratch() external pure returns (uint256 value) {
+        assembly {
+            value := mload(add(mload(0x40), 0x20))
+        }
+    }
+}
+
+contract MemorySafetyTest is Test {
+    function testCaughtViolation() public {
+        Unsafe unsafe = new Unsafe();
+        try unsafe.dirtyZeroSlot() {} catch {}
+    }
+
+    function testScratchSpace() public {
+        assertEq(new Scratch().readScratch(), 0);
+    }
+}
+   "#,
+    )
+    .unwrap();
+
+    let out = cmd.arg("test").assert_failure().get_output().stdout_lossy();
+    assert!(out.contains("[PASS] testScratchSpace() (gas: "), "{out}");
+    assert!(
+        out.contains("[FAIL: memory safety violation in test/MemorySafetyTest.t.sol:Unsafe at pc "),
+        "{out}"
+    );
+    assert!(
+        out.contains(
+            ": non-zero value written to the zero slot at 0x60 (test/MemorySafetyTest.t.sol:9:"
+        ),
+        "{out}"
+    );
+});
+
+// tests that `--test-memory-limit` fails the tests exceeding it and `--memory-report` shows the
+// peak memory of each test
+forgetest_init!(test_memory_limit, |prj, cmd| {
+    prj.wipe_contracts();
+    prj.add_test(
+        "MemoryTest.t.sol",
+        r#"
+import {Test} from "forge-std/Test.sol";
+
+contract Expander {
+    fallback() external {
+        assembly {
+            mstore(0xffffffffff, 1)
+        }
+    }
+}
+
+contract MemoryTest is Test {
+    function testSmall() public pure {
+        new uint256[](4);
+    }
+
+    function testLarge() public pure {
+        new uint256[](1024);
+    }
+
+    function testCaughtOutOfMemory() public {
+        (bool success,) = address(new Expander()).call("");
+        assertFalse(success);
+    }
+}
+   "#,
+    )
+    .unwrap();
+
+    let out = cmd
+        .args(["test", "--memory-report", "--test-memory-limit", "4096"])
+        .assert_failure()
+        .get_output()
+        .stdout_lossy();
+    assert!(out.contains("[PASS] testSmall() (gas: "), "{out}");
+    assert!(out.contains("[FAIL: test memory limit of 4096 bytes exceeded: "), "{out}");
+    assert!(out.contains("(peak memory: "), "{out}");
+
+    cmd.forge_fuse().args(["test", "--mt", "testSmall"]).assert_success();
+
+    // Without a limit, running out of memory in a caught call doesn't fail the test.
+    cmd.forge_fuse()
+        .args(["test", "--memory-report", "--mt", "testCaughtOutOfMemory"])
+        .assert_success();
+});
+
+// tests that calls to proxies are labeled and decoded with their implementation
+forgetest_init!(decodes_proxy_calls, |prj, cmd| {
+    prj.wipe_contracts();
+    prj.add_test(
+        "ProxyTest.t.sol",
+        r#"
+import {Test} from "forge-std/Test.sol";
+
+contract Counter {
+    uint256 public number;
+
+    function increment() public {
+        number++;
+    }
+}
+
+contract Proxy {
+    address immutable implementation;
+
+    constructor(address _implementation) {
+        implementation = _implementation;
+    }
+
+    fallback() external payable {
+        address impl = implementation;
+        assembly {
+            calldatacopy(0, 0, calldatasize())
+            let ok := delegatecall(gas(), impl, 0, calldatasize(), 0, 0)
+            returndatacopy(0, 0, returndatasize())
+            if iszero(ok) { revert(0, returndatasize()) }
+            return(0, returndatasize())
+        }
+    }
+}
+
+contract ProxyTest is Test {
+    function test_proxy() public {
+        Counter counter = Counter(address(new Proxy(address(new Counter()))));
+        counter.increment();
+    }
+}
+   "#,
+    )
+    .unwrap();
+
+    cmd.args(["test", "-vvvv"]).assert_success().stdout_eq(str![[r#"
+...
+[PASS] test_proxy() ([GAS])
+Traces:
+  [..] ProxyTest::test_proxy()
+    ├─ [..] → new Counter@[..]
+    │   └─ ← [Return] [..] bytes of code
+    ├─ [..] → new Proxy@[..]
+    │   └─ ← [Return] [..] bytes of code
+    ├─ [..] Proxy(Counter)::increment()
+    │   ├─ [..] Counter::increment() [delegatecall]
+    │   │   └─ ← [Stop]
+    │   └─ ← [Return]
+    └─ ← [Stop]
+...
+"#]]);
+});
+
+// Tests that `--shard` partitions the test contracts, and that the outputs of all the shards can be
+// merged.
+forgetest_init!(can_shard_and_merge_tests, |prj, cmd| {
+    prj.wipe_contracts();
+    for i in 0..8 {
+        prj.add_test(
+            &format!("Shard{i}.t.sol"),
+            &format!(
+                r#"
+import {{Test}} from "forge-std/Test.sol";
+
+contract Shard{i}Test is Test {{
+    function test_Shard() public pure {{}}
+}}
+"#
+            ),
+        )
+        .unwrap();
+    }
+
+    let mut suites = std::collections::BTreeSet::new();
+    let mut outputs = Vec::new();
+    for index in 1..=3 {
+        let path = prj.root().join(format!("shard-{index}.json"));
+        let path = path.to_str().unwrap().to_string();
+        cmd.forge_fuse()
+            .args(["test", "--shard", &format!("{index}/3"), "--shard-output", &path])
+            .assert_success();
+
+        let output: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        for suite in output["results"].as_object().unwrap().keys() {
+            assert!(suites.insert(suite.clone()), "{suite} is in several shards");
+        }
+        outputs.push(path);
+    }
+    assert_eq!(suites.len(), 8);
+
+    cmd.forge_fuse().args(["test", "--merge-shards"]).args(&outputs).assert_success().stdout_eq(
+        str![[r#"
+
+Ran 8 test suites [ELAPSED]: 8 tests passed, 0 failed, 0 skipped (8 total tests)
+
+"#]],
+    );
+
+    cmd.forge_fuse().args(["test", "--merge-shards", &outputs[0]]).assert_failure().stderr_eq(
+        str![[r#"
+Error: missing shards: 2/3, 3/3
+
+"#]],
+    );
+});
+
+// tests that fork calls are reused across runs, and executed again once an account whose balance
+// they read is modified
+forgetest_async!(reuses_and_invalidates_fork_calls, |prj, cmd| {
+    foundry_test_utils::util::initialize(prj.root());
+    prj.wipe_contracts();
+
+    let (api, handle) = spawn(NodeConfig::test().silent()).await;
+    let oracle = alloy_primitives::address!("0x00000000000000000000000000000000000000aa");
+    let holder = alloy_primitives::address!("0x00000000000000000000000000000000000000bb");
+    // Returns the balance of `holder`.
+    let code =
+        [&[0x73][..], holder.as_slice(), &[0x31, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3]]
+            .concat();
+    api.anvil_set_code(oracle, code.into()).await.unwrap();
+    api.anvil_set_balance(holder, U256::from(10).pow(U256::from(18))).await.unwrap();
+
+    prj.add_test(
+        "ForkCallCache.t.sol",
+        &format!(
+            r#"
+import {{Test}} from "forge-std/Test.sol";
+
+interface IOracle {{
+    function read() external view returns (uint256);
+}}
+
+contract ForkCallCacheTest is Test {{
+    IOracle constant ORACLE = IOracle({oracle});
+    address constant HOLDER = {holder};
+
+    function setUp() public {{
+        vm.createSelectFork("{rpc}");
+    }}
+
+    function testRead() public view {{
+        require(ORACLE.read() == 1 ether, "stale read");
+    }}
+
+    function testReadAfterDeal() public {{
+        vm.deal(HOLDER, 2 ether);
+        require(ORACLE.read() == 2 ether, "stale read");
+    }}
+}}
+   "#,
+            oracle = oracle.to_checksum(None),
+            holder = holder.to_checksum(None),
+            rpc = handle.http_endpoint(),
+        ),
+    )
+    .unwrap();
+
+    cmd.args(["test", "--cache-fork-calls", "--persist-fork-calls"]).assert_success();
+
+    // `testRead` is answered from the cache, `testReadAfterDeal` reads a modified balance.
+    let out = cmd.assert_success().get_output().stdout_lossy();
+    assert!(
+        out.contains("Fork call cache: reused 1 of 2 static fork calls, 1 results cached"),
+        "{out}"
+    );
+});
+
+// tests that the blocks forks of `latest` resolve to are recorded, reused with `--pin-forks` and
+// refreshed with `forge forks update`
+forgetest_async!(pins_and_updates_fork_blocks, |prj, cmd| {
+    foundry_test_utils::util::initialize(prj.root());
+    prj.wipe_contracts();
+
+    let (api, handle) = spawn(NodeConfig::test().silent()).await;
+    api.anvil_mine(Some(U256::from(3)), None).await.unwrap();
+
+    prj.update_config(|config| config.eth_rpc_url = Some(handle.http_endpoint()));
+    prj.add_test(
+        "ForkPins.t.sol",
+        r#"
+import {Test} from "forge-std/Test.sol";
+
+contract ForkPinsTest is Test {
+    function testPinnedBlock() public view {
+        assertEq(block.number, 3);
+    }
+}
+   "#,
+    )
+    .unwrap();
+
+    let pins = prj.root().join("cache").join("fork-pins.toml");
+    cmd.args(["test"]).assert_success();
+    assert!(std::fs::read_to_string(&pins).unwrap().contains("31337 = 3"));
+
+    // The pinned block is used even though the chain moved on.
+    api.anvil_mine(Some(U256::from(2)), None).await.unwrap();
+    cmd.forge_fuse().args(["test", "--pin-forks"]).assert_success();
+    assert!(std::fs::read_to_string(&pins).unwrap().contains("31337 = 3"));
+
+    cmd.forge_fuse().args(["forks", "update"]).assert_success().stdout_eq(str![[r#"
+chain 31337: 3 -> 5
+
+"#]]);
+    assert!(std::fs::read_to_string(&pins).unwrap().contains("31337 = 5"));
+});
+
+// Tests that tests are run again after a transient RPC error if they opt in with
+// `vm.retryOnRpcError`, either in the test or in `setUp`.
+forgetest_async!(retries_tests_on_transient_rpc_errors, |prj, cmd| {
+    foundry_test_utils::util::initialize(prj.root());
+    prj.wipe_contracts();
+
+    let (_api, handle) = spawn(NodeConfig::test().silent()).await;
+
+    // Proxies requests to anvil, but fails the first request to each path with a 502.
+    let failed = std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashSet::new()));
+    let rpc = handle.http_endpoint();
+    let client = reqwest::Client::new();
+    let proxy = axum::Router::new().fallback(move |uri: axum::http::Uri, body: String| {
+        let (failed, client, rpc) = (failed.clone(), client.clone(), rpc.clone());
+        async move {
+            if failed.lock().unwrap().insert(uri.path().to_string()) {
+                return (axum::http::StatusCode::BAD_GATEWAY, String::new());
+            }
+            let res = client
+                .post(rpc)
+                .header("content-type", "application/json")
+                .body(body)
+                .send()
+                .await
+                .unwrap();
+            (axum::http::StatusCode::OK, res.text().await.unwrap())
+        }
+    });
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let proxy_url = format!("http://{}", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        axum::serve(listener, proxy.into_make_service()).await.unwrap();
+    });
+
+    prj.add_test(
+        "RetryOnRpcError.t.sol",
+        &format!(
+            r#"
+import {{Test}} from "forge-std/Test.sol";
+
+contract RetryInTest is Test {{
+    function test_retryInTest() public {{
+        vm.retryOnRpcError(3);
+        vm.createSelectFork("{proxy_url}/test");
+        assertEq(block.chainid, 31337);
+    }}
+}}
+
+contract RetryInSetUp is Test {{
+    function setUp() public {{
+        vm.retryOnRpcError(3);
+        vm.createSelectFork("{proxy_url}/setup");
+    }}
+
+    function test_retryFromSetUp() public {{
+        vm.createSelectFork("{proxy_url}/setup-test");
+        assertEq(block.chainid, 31337);
+    }}
+}}
+
+contract NoRetry is Test {{
+    function test_noRetry() public {{
+        vm.createSelectFork("{proxy_url}/no-retry");
+    }}
+}}
+   "#
+        ),
+    )
+    .unwrap();
+
+    let out = cmd.args(["test"]).assert_failure().get_output().stdout_lossy();
+    let result = |name: &str| {
+        out.lines().find(|line| line.contains(name)).unwrap_or_else(|| panic!("{name}: {out}"))
+    };
+    let res = result("test_retryInTest()");
+    assert!(res.starts_with("[PASS]") && res.ends_with("(rpc retries: 1)"), "{res}");
+    let res = result("test_retryFromSetUp()");
+    assert!(res.starts_with("[PASS]") && res.ends_with("(rpc retries: 1)"), "{res}");
+    let res = result("test_noRetry()");
+    assert!(res.starts_with("[FAIL") && !res.contains("rpc retries"), "{res}");
+
+    server.abort();
+});
@@ -0,0 +1,51 @@
+// Note Used in forge-cli tests to assert failures.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity ^0.8.18;
+
+import "./test.sol";
+import "./Vm.sol";
+
+contract Target {
+    uint256 public value;
+
+    function set(uint256 _value) public {
+        value = _value;
+    }
+}
+
+contract ExpectGasUsedFailureTest is DSTest {
+    Vm constant vm = Vm(HEVM_ADDRESS);
+
+    Target target;
+
+    function setUp() public {
+        target = new Target();
+    }
+
+    function testShouldFailExpectGasUsedRange() public {
+        vm.expectGasUsed(1, 2);
+        target.set(1);
+    }
+
+    function testShouldFailExpectGasUsedExact() public {
+        vm.expectGasUsed(1, 1);
+        target.set(1);
+    }
+
+    function testShouldFailExpectGasUsedInvalidRange() public {
+        vm.expectGasUsed(2, 1);
+    }
+
+    function testShouldFailExpectGasUsedNotCalled() public {
+        vm.expectGasUsed(1, 2);
+    }
+
+    function testShouldFailExpectGasUsedSnapshot() public {
+        vm.expectGasUsed("ExpectGasUsed", "set", 0);
+        target.set(1);
+    }
+
+    function testShouldFailExpectGasUsedMissingSnapshot() public {
+        vm.expectGasUsed("missing", 0);
+    }
+}
@@ -0,0 +1,40 @@
+// Note Used in forge-cli tests to assert failures.
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity ^0.8.18;
+
+import "./test.sol";
+import "./Vm.sol";
+
+contract EcdsaAccount {
+    address public owner;
+
+    constructor(address _owner) {
+        owner = _owner;
+    }
+
+    function isValidSignature(bytes32 hash, bytes calldata signature) external view returns (bytes4) {
+        (bytes32 r, bytes32 s) = abi.decode(signature, (bytes32, bytes32));
+        bool valid = ecrecover(hash, uint8(signature[64]), r, s) == owner;
+        return valid ? bytes4(0x1626ba7e) : bytes4(0xffffffff);
+    }
+}
+
+contract ExpectValidSignatureFailureTest is DSTest {
+    Vm constant vm = Vm(HEVM_ADDRESS);
+
+    uint256 constant PRIVATE_KEY = 0xabc;
+    bytes32 constant DIGEST = bytes32(uint256(1));
+
+    function testShouldFailExpectValidSignatureRejected() public {
+        EcdsaAccount account = new EcdsaAccount(vm.addr(PRIVATE_KEY));
+
+        vm.expectValidSignature(address(account), DIGEST);
+        account.isValidSignature(DIGEST, vm.signErc1271(PRIVATE_KEY + 1, DIGEST));
+    }
+
+    function testShouldFailExpectValidSignatureNotCalled() public {
+        EcdsaAccount account = new EcdsaAccount(vm.addr(PRIVATE_KEY));
+
+        vm.expectValidSignature(address(account), DIGEST);
+    }
+}
@@ -65,6 +65,15 @@ async fn test_rpc_fork() {
     TestConfig::with_filter(runner, filter).run().await;
 }
 
+/// Executes the blockAt and rollForkTo cheatcodes
+#[tokio::test(flavor = "multi_thread")]
+async fn test_block_at_fork() {
+    let runner = TEST_DATA_DEFAULT.runner();
+    let filter =
+        Filter::new("test(BlockAt|RollForkTo)", ".*", &format!(".*cheats{RE_PATH_SEPARATOR}Fork"));
+    TestConfig::with_filter(runner, filter).run().await;
+}
+
 /// Tests that we can launch in forking mode
 #[tokio::test(flavor = "multi_thread")]
 async fn test_launch_fork() {
@@ -13,7 +13,7 @@
 #[tokio::test(flavor = "multi_thread")]
 async fn test_fuzz() {
     let filter = Filter::new(".*", ".*", ".*fuzz/")
-        .exclude_tests(r"invariantCounter|testIncrement\(address\)|testNeedle\(uint256\)|testSuccessChecker\(uint256\)|testSuccessChecker2\(int256\)|testSuccessChecker3\(uint32\)|testStorageOwner\(address\)|testImmutableOwner\(address\)")
+        .exclude_tests(r"invariantCounter|testIncrement\(address\)|testNeedle\(uint256\)|testSuccessChecker\(uint256\)|testSuccessChecker2\(int256\)|testSuccessChecker3\(uint32\)|testStorageOwner\(address\)|testImmutableOwner\(address\)|testMagicBits\(uint24\)")
         .exclude_paths("invariant");
     let mut runner = TEST_DATA_DEFAULT.runner();
     let suite_result = runner.test_collect(&filter);
@@ -76,6 +76,59 @@ async fn test_successful_fuzz_cases() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_coverage_guided_fuzz() {
+    let filter = Filter::new(".*", ".*", ".*fuzz/Fuzz.t.sol");
+    let mut runner = TEST_DATA_DEFAULT.runner_with(|config| {
+        config.fuzz.coverage_guided = true;
+        config.fuzz.seed = Some(U256::from(1u32));
+    });
+    let results = runner.test_collect(&filter);
+
+    assert_multiple(
+        &results,
+        BTreeMap::from([(
+            "default/fuzz/Fuzz.t.sol:FuzzTest",
+            vec![
+                ("testShouldFailFuzz(uint8)", false, Some("should revert".into()), None, None),
+                ("testSuccessfulFuzz(uint128,uint128)", true, None, None, None),
+                ("testToStringFuzz(bytes32)", true, None, None, None),
+            ],
+        )]),
+    );
+}
+
+// Tests that coverage-guided fuzzing finds an input that plain fuzzing doesn't.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_coverage_guided_fuzz_magic_bits() {
+    let filter = Filter::new(".*", ".*", ".*fuzz/FuzzCoverageGuided.t.sol");
+    let suite = "default/fuzz/FuzzCoverageGuided.t.sol:FuzzCoverageGuidedTest";
+
+    let mut runner = TEST_DATA_DEFAULT.runner_with(|config| {
+        config.fuzz.runs = 30_000;
+        config.fuzz.seed = Some(U256::from(1u32));
+    });
+    let results = runner.test_collect(&filter);
+    assert_multiple(
+        &results,
+        BTreeMap::from([(suite, vec![("testMagicBits(uint24)", true, None, None, None)])]),
+    );
+
+    let mut runner = TEST_DATA_DEFAULT.runner_with(|config| {
+        config.fuzz.runs = 30_000;
+        config.fuzz.seed = Some(U256::from(1u32));
+        config.fuzz.coverage_guided = true;
+    });
+    let results = runner.test_collect(&filter);
+    assert_multiple(
+        &results,
+        BTreeMap::from([(
+            suite,
+            vec![("testMagicBits(uint24)", false, Some("magic bits found".into()), None, None)],
+        )]),
+    );
+}
+
 /// Test that showcases PUSH collection on normal fuzzing. Ignored until we collect them in a
 /// smarter way.
 #[tokio::test(flavor = "multi_thread")]
@@ -268,6 +321,8 @@ macro_rules! run_fail {
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for test/Contract.t.sol:FuzzTimeoutTest
 [PASS] test_fuzz_bound(uint256) (runs: [..], [AVG_GAS])
 Suite result: ok. 1 passed; 0 failed; 0 skipped; [ELAPSED]
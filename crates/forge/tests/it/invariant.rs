@@ -980,6 +980,8 @@ async fn test_no_reverts_in_counterexample() {
 [SOLC_VERSION] [ELAPSED]
 Compiler run successful!
 
+Test run: [..]
+
 Ran 1 test for test/TimeoutTest.t.sol:TimeoutTest
 [PASS] invariant_counter_timeout() (runs: 0, calls: 0, reverts: 0)
 Suite result: ok. 1 passed; 0 failed; 0 skipped; [ELAPSED]
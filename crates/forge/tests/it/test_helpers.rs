@@ -124,6 +124,10 @@ pub fn config(&self) -> Config {
             failure_persist_file: Some("testfailure".to_string()),
             show_logs: false,
             timeout: None,
+            dictionary_files: Vec::new(),
+            dictionary_export: None,
+            exhaustive_small_domains: false,
+            coverage_guided: false,
         };
         config.invariant = InvariantConfig {
             runs: 256,
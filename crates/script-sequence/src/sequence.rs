@@ -1,4 +1,4 @@
-use crate::transaction::TransactionWithMetadata;
+use crate::transaction::{TransactionWithMetadata, TxBroadcastStatus};
 use alloy_network::AnyTransactionReceipt;
 use alloy_primitives::{hex, map::HashMap, TxHash};
 use eyre::{ContextCompat, Result, WrapErr};
@@ -83,6 +83,7 @@ pub fn load(
         .wrap_err(format!("Deployment's sensitive details not found for chain `{chain_id}`."))?;
 
         script_sequence.fill_sensitive(&sensitive_script_sequence);
+        script_sequence.fill_statuses();
 
         script_sequence.paths = Some((path, sensitive_path));
 
@@ -145,7 +146,9 @@ pub fn save(&mut self, silent: bool, save_ts: bool) -> Result<()> {
         Ok(())
     }
 
+    /// Records the receipt of a successful transaction.
     pub fn add_receipt(&mut self, receipt: AnyTransactionReceipt) {
+        self.set_status(receipt.transaction_hash, TxBroadcastStatus::Confirmed);
         self.receipts.push(receipt);
     }
 
@@ -157,6 +160,7 @@ pub fn sort_receipts(&mut self) {
     pub fn add_pending(&mut self, index: usize, tx_hash: TxHash) {
         if !self.pending.contains(&tx_hash) {
             self.transactions[index].hash = Some(tx_hash);
+            self.transactions[index].status = TxBroadcastStatus::Pending;
             self.pending.push(tx_hash);
         }
     }
@@ -165,6 +169,64 @@ pub fn remove_pending(&mut self, tx_hash: TxHash) {
         self.pending.retain(|element| element != &tx_hash);
     }
 
+    /// Records that the pending transaction reverted.
+    pub fn mark_reverted(&mut self, tx_hash: TxHash) {
+        self.remove_pending(tx_hash);
+        self.set_status(tx_hash, TxBroadcastStatus::Reverted);
+    }
+
+    /// Records that the pending transaction was dropped from the mempool, so that it is sent
+    /// again.
+    pub fn mark_dropped(&mut self, tx_hash: TxHash) {
+        self.remove_pending(tx_hash);
+        if let Some(tx) = self.transactions.iter_mut().find(|tx| tx.hash == Some(tx_hash)) {
+            tx.hash = None;
+            tx.status = TxBroadcastStatus::Unsent;
+        }
+    }
+
+    /// Records that the nonce of the pending transaction was used by another transaction.
+    pub fn mark_replaced(&mut self, tx_hash: TxHash) {
+        self.remove_pending(tx_hash);
+        self.set_status(tx_hash, TxBroadcastStatus::Replaced);
+    }
+
+    fn set_status(&mut self, tx_hash: TxHash, status: TxBroadcastStatus) {
+        if let Some(tx) = self.transactions.iter_mut().find(|tx| tx.hash == Some(tx_hash)) {
+            tx.status = status;
+        }
+    }
+
+    /// Returns the indices of the transactions that still have to be sent.
+    pub fn unsent(&self) -> impl Iterator<Item = usize> + '_ {
+        self.transactions
+            .iter()
+            .enumerate()
+            .filter(|(_, tx)| tx.status == TxBroadcastStatus::Unsent)
+            .map(|(i, _)| i)
+    }
+
+    /// Returns the number of transactions that won't be sent again.
+    pub fn finished(&self) -> usize {
+        self.transactions.iter().filter(|tx| tx.status.is_final()).count()
+    }
+
+    /// Derives the statuses of the transactions of a log written before they were recorded, from
+    /// its receipts and pending transactions.
+    pub fn fill_statuses(&mut self) {
+        for tx in &mut self.transactions {
+            let Some(hash) = tx.hash else { continue };
+            if tx.status != TxBroadcastStatus::Unsent {
+                continue
+            }
+            if self.receipts.iter().any(|receipt| receipt.transaction_hash == hash) {
+                tx.status = TxBroadcastStatus::Confirmed;
+            } else if self.pending.contains(&hash) {
+                tx.status = TxBroadcastStatus::Pending;
+            }
+        }
+    }
+
     /// Gets paths in the formats
     /// `./broadcast/[contract_filename]/[chain_id]/[sig]-[timestamp].json` and
     /// `./cache/[contract_filename]/[chain_id]/[sig]-[timestamp].json`.
@@ -256,4 +318,48 @@ fn can_convert_sig() {
             "522bb704"
         );
     }
+
+    #[test]
+    fn tracks_broadcast_status() {
+        let tx = TransactionWithMetadata::from_tx_request(TransactionMaybeSigned::Unsigned(
+            Default::default(),
+        ));
+        let mut sequence =
+            ScriptSequence { transactions: vec![tx; 3].into(), ..Default::default() };
+        let (hash0, hash1) = (TxHash::with_last_byte(1), TxHash::with_last_byte(2));
+
+        sequence.add_pending(0, hash0);
+        sequence.add_pending(1, hash1);
+        assert_eq!(sequence.unsent().collect::<Vec<_>>(), [2]);
+
+        // Dropped transactions are sent again, reverted ones are not.
+        sequence.mark_dropped(hash0);
+        sequence.mark_reverted(hash1);
+        assert!(sequence.pending.is_empty());
+        assert_eq!(sequence.transactions[0].hash, None);
+        assert_eq!(sequence.transactions[1].status, TxBroadcastStatus::Reverted);
+        assert_eq!(sequence.unsent().collect::<Vec<_>>(), [0, 2]);
+        assert_eq!(sequence.finished(), 1);
+    }
+
+    #[test]
+    fn fills_statuses_of_old_logs() {
+        let mut tx = TransactionWithMetadata::from_tx_request(TransactionMaybeSigned::Unsigned(
+            Default::default(),
+        ));
+        tx.hash = Some(TxHash::with_last_byte(1));
+        let mut json = serde_json::to_value(&tx).unwrap();
+        json.as_object_mut().unwrap().remove("status");
+        let tx: TransactionWithMetadata = serde_json::from_value(json).unwrap();
+        assert_eq!(tx.status, TxBroadcastStatus::Unsent);
+
+        let mut sequence = ScriptSequence {
+            transactions: vec![tx].into(),
+            pending: vec![TxHash::with_last_byte(1)],
+            ..Default::default()
+        };
+        sequence.fill_statuses();
+        assert_eq!(sequence.transactions[0].status, TxBroadcastStatus::Pending);
+        assert_eq!(sequence.unsent().count(), 0);
+    }
 }
@@ -12,10 +12,38 @@ pub struct AdditionalContract {
     pub init_code: Bytes,
 }
 
+/// The broadcast status of a transaction, checkpointed to the broadcast log so that resuming a
+/// broadcast never sends a transaction twice.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TxBroadcastStatus {
+    /// The transaction wasn't sent yet, or it was dropped from the mempool.
+    #[default]
+    Unsent,
+    /// The transaction was sent, or is about to be, with the recorded hash.
+    Pending,
+    /// The transaction was included and succeeded.
+    Confirmed,
+    /// The transaction was included and reverted.
+    Reverted,
+    /// Its nonce was used by another transaction of the sender, or by this one before its hash
+    /// could be recorded.
+    Replaced,
+}
+
+impl TxBroadcastStatus {
+    /// Returns `true` if the transaction must not be sent again.
+    pub fn is_final(self) -> bool {
+        matches!(self, Self::Confirmed | Self::Reverted | Self::Replaced)
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionWithMetadata {
     pub hash: Option<B256>,
+    #[serde(default)]
+    pub status: TxBroadcastStatus,
     #[serde(rename = "transactionType")]
     pub opcode: CallKind,
     #[serde(default = "default_string")]
@@ -50,6 +78,7 @@ pub fn from_tx_request(transaction: TransactionMaybeSigned) -> Self {
         Self {
             transaction,
             hash: Default::default(),
+            status: Default::default(),
             opcode: Default::default(),
             contract_name: Default::default(),
             contract_address: Default::default(),
@@ -1,11 +1,16 @@
 use crate::{
-    build::LinkedBuildData, progress::ScriptProgress, sequence::ScriptSequenceKind,
-    verify::BroadcastedState, ScriptArgs, ScriptConfig,
+    build::LinkedBuildData,
+    nonce,
+    progress::ScriptProgress,
+    sequence::ScriptSequenceKind,
+    variance::{self, SimulationBlock},
+    verify::BroadcastedState,
+    ScriptArgs, ScriptConfig,
 };
 use alloy_chains::Chain;
 use alloy_consensus::TxEnvelope;
 use alloy_eips::{eip2718::Encodable2718, BlockId};
-use alloy_network::{AnyNetwork, EthereumWallet, TransactionBuilder};
+use alloy_network::{AnyNetwork, AnyTxEnvelope, EthereumWallet, TransactionBuilder};
 use alloy_primitives::{
     map::{AddressHashMap, AddressHashSet},
     utils::format_units,
@@ -15,6 +20,7 @@
 use alloy_rpc_types::TransactionRequest;
 use alloy_serde::WithOtherFields;
 use eyre::{bail, Context, Result};
+use forge_script_sequence::{ScriptSequence, TxBroadcastStatus};
 use forge_verify::provider::VerificationProviderType;
 use foundry_cheatcodes::Wallets;
 use foundry_cli::utils::{has_batch_support, has_different_gas_calc};
@@ -25,7 +31,7 @@
 use foundry_config::Config;
 use futures::{future::join_all, StreamExt};
 use itertools::Itertools;
-use std::{cmp::Ordering, sync::Arc};
+use std::{cmp::Ordering, str::FromStr, sync::Arc, time::Duration};
 
 pub async fn estimate_gas<P: Provider<AnyNetwork>>(
     tx: &mut WithOtherFields<TransactionRequest>,
@@ -56,14 +62,16 @@ pub async fn next_nonce(
     Ok(provider.get_transaction_count(caller).block_id(block_id).await?)
 }
 
-pub async fn send_transaction(
-    provider: Arc<RetryProvider>,
+/// Checks the nonce of the transaction, estimates its gas and signs it if needed, so that its hash
+/// can be recorded before it is submitted.
+pub async fn prepare_transaction(
+    provider: &RetryProvider,
     mut kind: SendTransactionKind<'_>,
     sequential_broadcast: bool,
     is_fixed_gas_limit: bool,
     estimate_via_rpc: bool,
     estimate_multiplier: u64,
-) -> Result<TxHash> {
+) -> Result<PreparedTransaction> {
     if let SendTransactionKind::Raw(tx, _) | SendTransactionKind::Unlocked(tx) = &mut kind {
         if sequential_broadcast {
             let from = tx.from.expect("no sender");
@@ -93,31 +101,156 @@ pub async fn send_transaction(
         // Chains which use `eth_estimateGas` are being sent sequentially and require their
         // gas to be re-estimated right before broadcasting.
         if !is_fixed_gas_limit && estimate_via_rpc {
-            estimate_gas(tx, &provider, estimate_multiplier).await?;
+            estimate_gas(tx, provider, estimate_multiplier).await?;
         }
     }
 
-    let pending = match kind {
-        SendTransactionKind::Unlocked(tx) => {
-            debug!("sending transaction from unlocked account {:?}", tx);
+    Ok(match kind {
+        SendTransactionKind::Unlocked(tx) => PreparedTransaction::Unlocked(tx),
+        SendTransactionKind::Raw(tx, signer) => {
+            PreparedTransaction::Signed(tx.build(signer).await?)
+        }
+        SendTransactionKind::Signed(tx) => PreparedTransaction::Signed(AnyTxEnvelope::Ethereum(tx)),
+    })
+}
+
+/// A transaction ready to be submitted.
+pub enum PreparedTransaction {
+    /// Sent via `eth_sendTransaction`, the node signs it.
+    Unlocked(WithOtherFields<TransactionRequest>),
+    /// Sent via `eth_sendRawTransaction`.
+    Signed(AnyTxEnvelope),
+}
 
-            // Submit the transaction
-            provider.send_transaction(tx).await?
+impl PreparedTransaction {
+    /// Returns the hash of the transaction, if it's known before it is submitted.
+    pub fn hash(&self) -> Option<TxHash> {
+        match self {
+            Self::Unlocked(_) => None,
+            Self::Signed(tx) => Some(tx.trie_hash()),
         }
-        SendTransactionKind::Raw(tx, signer) => {
-            debug!("sending transaction: {:?}", tx);
-            let signed = tx.build(signer).await?;
+    }
 
-            // Submit the raw transaction
-            provider.send_raw_transaction(signed.encoded_2718().as_ref()).await?
+    /// Submits the transaction, returning its hash.
+    pub async fn submit(self, provider: &RetryProvider) -> Result<TxHash> {
+        let pending = match self {
+            Self::Unlocked(tx) => {
+                debug!("sending transaction from unlocked account {:?}", tx);
+
+                // Submit the transaction
+                provider.send_transaction(tx).await?
+            }
+            Self::Signed(tx) => {
+                debug!("sending transaction: {:?}", tx);
+
+                // Submit the raw transaction
+                provider.send_raw_transaction(tx.encoded_2718().as_ref()).await?
+            }
+        };
+
+        Ok(*pending.tx_hash())
+    }
+}
+
+/// Adds the commands to recover from a stuck nonce to the errors caused by the nonce of a
+/// transaction.
+fn with_recovery_hint(
+    err: eyre::Report,
+    chain_id: u64,
+    tx: &TransactionMaybeSigned,
+) -> eyre::Report {
+    let is_nonce_error = err.chain().any(|cause| {
+        let cause = cause.to_string().to_lowercase();
+        cause.contains("nonce") || cause.contains("replacement transaction underpriced")
+    });
+    match (tx.from(), tx.nonce()) {
+        (Some(from), Some(nonce)) if is_nonce_error => {
+            err.wrap_err(nonce::recovery_hint(chain_id, from, nonce))
         }
-        SendTransactionKind::Signed(tx) => {
-            debug!("sending transaction: {:?}", tx);
-            provider.send_raw_transaction(tx.encoded_2718().as_ref()).await?
+        _ => err,
+    }
+}
+
+/// Marks the unsent transactions whose nonce was already used on chain as replaced, so that they
+/// aren't sent again when resuming, e.g. if the broadcast was interrupted after submitting one of
+/// them but before its hash was recorded.
+async fn skip_used_nonces(sequence: &mut ScriptSequence, provider: &RetryProvider) -> Result<()> {
+    let mut onchain_nonces = AddressHashMap::<u64>::default();
+    for index in sequence.unsent().collect::<Vec<_>>() {
+        let tx = &mut sequence.transactions[index];
+        let (Some(from), Some(nonce)) = (tx.tx().from(), tx.tx().nonce()) else { continue };
+        let onchain_nonce = if let Some(&nonce) = onchain_nonces.get(&from) {
+            nonce
+        } else {
+            let nonce = provider.get_transaction_count(from).await?;
+            onchain_nonces.insert(from, nonce);
+            nonce
+        };
+        if nonce < onchain_nonce {
+            sh_warn!(
+                "Skipping transaction {index} of chain {}: nonce {nonce} of {from} was already \
+                 used",
+                sequence.chain
+            )?;
+            tx.status = TxBroadcastStatus::Replaced;
         }
-    };
+    }
+    Ok(())
+}
+
+/// What to wait for after sending a batch of transactions, before sending the next one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BatchBarrier {
+    /// Wait for the receipts of the batch, and for the RPC to serve the block of the last one,
+    /// so that the next batch is simulated on top of the batch.
+    Receipts,
+    /// Wait for the receipts of the batch, and for the given number of blocks on top of the
+    /// last one.
+    Blocks(u64),
+}
+
+impl FromStr for BatchBarrier {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "receipts" => Ok(Self::Receipts),
+            Some(("blocks", blocks)) => blocks
+                .parse()
+                .map(Self::Blocks)
+                .map_err(|e| format!("invalid number of blocks `{blocks}`: {e}")),
+            _ => Err(format!("invalid barrier `{s}`, expected `receipts` or `blocks:<N>`")),
+        }
+    }
+}
+
+impl BatchBarrier {
+    /// Returns the number of blocks to wait for on top of the block of the batch's last receipt.
+    pub fn confirmations(self) -> u64 {
+        match self {
+            Self::Receipts => 0,
+            Self::Blocks(confirmations) => confirmations,
+        }
+    }
+}
 
-    Ok(*pending.tx_hash())
+/// Waits until the chain is `confirmations` blocks past `block_number`.
+async fn wait_for_confirmations(
+    provider: &RetryProvider,
+    block_number: u64,
+    confirmations: u64,
+    timeout: u64,
+) -> Result<()> {
+    let target = block_number + confirmations;
+    let wait = async {
+        while provider.get_block_number().await? < target {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+        Ok::<_, eyre::Report>(())
+    };
+    tokio::time::timeout(Duration::from_secs(timeout), wait)
+        .await
+        .wrap_err_with(|| format!("Timed out waiting for block {target}"))?
 }
 
 /// How to send a single transaction
@@ -192,6 +325,7 @@ pub async fn wait_for_pending(mut self) -> Result<Self> {
                         sequence,
                         &provider,
                         self.script_config.config.transaction_timeout,
+                        true,
                     )
                     .await
             })
@@ -228,6 +362,8 @@ pub async fn broadcast(mut self) -> Result<BroadcastedState> {
             );
         }
 
+        self.commit_nonces().await?;
+
         let send_kind = if self.args.unlocked {
             SendTransactionsKind::Unlocked(required_addresses.clone())
         } else {
@@ -262,11 +398,30 @@ pub async fn broadcast(mut self) -> Result<BroadcastedState> {
             let mut sequence = self.sequence.sequences_mut().get_mut(i).unwrap();
 
             let provider = Arc::new(try_get_http_provider(sequence.rpc_url())?);
-            let already_broadcasted = sequence.receipts.len();
+
+            if self.args.resume {
+                skip_used_nonces(sequence, &provider).await?;
+                self.sequence.save(true, false)?;
+                sequence = self.sequence.sequences_mut().get_mut(i).unwrap();
+            }
+            let unsent = sequence.unsent().collect::<Vec<_>>();
+
+            // Queue behind the other scripts holding the nonces before ours.
+            if let Some(nonce_manager) = &self.script_config.nonce_manager {
+                let senders = unsent
+                    .iter()
+                    .filter_map(|&index| sequence.transactions[index].tx().from())
+                    .collect::<AddressHashSet>();
+                for lease in self.script_config.nonce_leases.iter().filter(|lease| {
+                    lease.chain_id == sequence.chain && senders.contains(&lease.sender)
+                }) {
+                    nonce_manager.wait_for_turn(&provider, lease).await?;
+                }
+            }
 
             let seq_progress = progress.get_sequence_progress(i, sequence);
 
-            if already_broadcasted < sequence.transactions.len() {
+            if !unsent.is_empty() {
                 let is_legacy = Chain::from(sequence.chain).is_legacy() || self.args.legacy;
                 // Make a one-time gas price estimation
                 let (gas_price, eip1559_fees) = match (
@@ -300,11 +455,10 @@ pub async fn broadcast(mut self) -> Result<BroadcastedState> {
 
                 // Iterate through transactions, matching the `from` field with the associated
                 // wallet. Then send the transaction. Panics if we find a unknown `from`
-                let transactions = sequence
-                    .transactions
+                let transactions = unsent
                     .iter()
-                    .skip(already_broadcasted)
-                    .map(|tx_with_metadata| {
+                    .map(|&index| {
+                        let tx_with_metadata = &sequence.transactions[index];
                         let is_fixed_gas_limit = tx_with_metadata.is_fixed_gas_limit;
 
                         let kind = match tx_with_metadata.tx().clone() {
@@ -336,7 +490,7 @@ pub async fn broadcast(mut self) -> Result<BroadcastedState> {
                             }
                         };
 
-                        Ok((kind, is_fixed_gas_limit))
+                        Ok((index, kind, is_fixed_gas_limit))
                     })
                     .collect::<Result<Vec<_>>>()?;
 
@@ -355,33 +509,94 @@ pub async fn broadcast(mut self) -> Result<BroadcastedState> {
 
                 // We send transactions and wait for receipts in batches.
                 let batch_size = if sequential_broadcast { 1 } else { self.args.batch_size };
-                let mut index = already_broadcasted;
+                let estimate_multiplier = self.args.gas_estimate_multiplier;
 
                 for (batch_number, batch) in transactions.chunks(batch_size).enumerate() {
-                    let mut pending_transactions = vec![];
+                    // The state may have changed since the script was simulated, so check that
+                    // the batch still succeeds on top of the latest block. The transactions are
+                    // simulated in order, as later ones may depend on earlier ones.
+                    if batch_number > 0 && self.args.barrier_on.is_some() {
+                        seq_progress.inner.write().set_status("Simulating the next batch");
+                        let batch_txs = batch
+                            .iter()
+                            .map(|(index, ..)| sequence.transactions[*index].clone())
+                            .collect::<Vec<_>>();
+                        let outcomes = variance::simulate_at(
+                            &self.script_config,
+                            SimulationBlock::Head(0),
+                            &batch_txs,
+                            self.args.slow,
+                        )
+                        .await?;
+                        if let Some(((index, ..), _)) =
+                            batch.iter().zip(&outcomes).find(|(_, outcome)| !outcome.success)
+                        {
+                            bail!(
+                                "Transaction {index} fails against the current state of the \
+                                 chain.\n\nAdd `--resume` to your command to continue \
+                                 broadcasting the transactions."
+                            );
+                        }
+                    }
 
                     seq_progress.inner.write().set_status(&format!(
                         "Sending transactions [{} - {}]",
                         batch_number * batch_size,
                         batch_number * batch_size + std::cmp::min(batch_size, batch.len()) - 1
                     ));
-                    for (kind, is_fixed_gas_limit) in batch {
-                        let fut = send_transaction(
-                            provider.clone(),
-                            kind.clone(),
-                            sequential_broadcast,
-                            *is_fixed_gas_limit,
-                            estimate_via_rpc,
-                            self.args.gas_estimate_multiplier,
-                        );
-                        pending_transactions.push(fut);
+                    let prepared = batch.iter().map(|(index, kind, is_fixed_gas_limit)| {
+                        let provider = &provider;
+                        async move {
+                            let tx = prepare_transaction(
+                                provider,
+                                kind.clone(),
+                                sequential_broadcast,
+                                *is_fixed_gas_limit,
+                                estimate_via_rpc,
+                                estimate_multiplier,
+                            )
+                            .await;
+                            (*index, tx)
+                        }
+                    });
+                    let mut prepared = futures::stream::iter(prepared).buffered(7);
+
+                    let mut submissions = Vec::with_capacity(batch.len());
+                    while let Some((index, tx)) = prepared.next().await {
+                        let tx = tx.map_err(|err| {
+                            with_recovery_hint(
+                                err.wrap_err("Failed to send transaction"),
+                                sequence.chain,
+                                sequence.transactions[index].tx(),
+                            )
+                        })?;
+                        // Record the hash before submitting the transaction, so that it's looked
+                        // up instead of being sent again if the broadcast is interrupted.
+                        if let Some(tx_hash) = tx.hash() {
+                            sequence.add_pending(index, tx_hash);
+                        }
+                        submissions.push((index, tx));
                     }
 
-                    if !pending_transactions.is_empty() {
-                        let mut buffer = futures::stream::iter(pending_transactions).buffered(7);
+                    // Checkpoint save
+                    self.sequence.save(true, false)?;
+                    sequence = self.sequence.sequences_mut().get_mut(i).unwrap();
 
-                        while let Some(tx_hash) = buffer.next().await {
-                            let tx_hash = tx_hash.wrap_err("Failed to send transaction")?;
+                    if !submissions.is_empty() {
+                        let submissions = submissions.into_iter().map(|(index, tx)| {
+                            let provider = &provider;
+                            async move { (index, tx.submit(provider).await) }
+                        });
+                        let mut buffer = futures::stream::iter(submissions).buffered(7);
+
+                        while let Some((index, tx_hash)) = buffer.next().await {
+                            let tx_hash = tx_hash.map_err(|err| {
+                                with_recovery_hint(
+                                    err.wrap_err("Failed to send transaction"),
+                                    sequence.chain,
+                                    sequence.transactions[index].tx(),
+                                )
+                            })?;
                             sequence.add_pending(index, tx_hash);
 
                             // Checkpoint save
@@ -389,21 +604,37 @@ pub async fn broadcast(mut self) -> Result<BroadcastedState> {
                             sequence = self.sequence.sequences_mut().get_mut(i).unwrap();
 
                             seq_progress.inner.write().tx_sent(tx_hash);
-                            index += 1;
                         }
 
-                        // Checkpoint save
-                        self.sequence.save(true, false)?;
-                        sequence = self.sequence.sequences_mut().get_mut(i).unwrap();
-
                         progress
                             .wait_for_pending(
                                 i,
                                 sequence,
                                 &provider,
                                 self.script_config.config.transaction_timeout,
+                                false,
+                            )
+                            .await?;
+
+                        if let Some(barrier) = self.args.barrier_on {
+                            let confirmations = barrier.confirmations();
+                            let last_block = sequence
+                                .receipts
+                                .iter()
+                                .filter_map(|receipt| receipt.block_number)
+                                .max()
+                                .unwrap_or_default();
+                            seq_progress.inner.write().set_status(&format!(
+                                "Waiting for {confirmations} confirmations of block {last_block}"
+                            ));
+                            wait_for_confirmations(
+                                &provider,
+                                last_block,
+                                confirmations,
+                                self.script_config.config.transaction_timeout,
                             )
-                            .await?
+                            .await?;
+                        }
                     }
                     // Checkpoint save
                     self.sequence.save(true, false)?;
@@ -418,8 +649,10 @@ pub async fn broadcast(mut self) -> Result<BroadcastedState> {
                     (acc.0 + gas_used, acc.1 + gas_price, acc.2 + gas_used * gas_price)
                 });
             let paid = format_units(total_paid, 18).unwrap_or_else(|_| "N/A".to_string());
-            let avg_gas_price = format_units(total_gas_price / sequence.receipts.len() as u64, 9)
-                .unwrap_or_else(|_| "N/A".to_string());
+            let avg_gas_price = total_gas_price
+                .checked_div(sequence.receipts.len() as u64)
+                .and_then(|price| format_units(price, 9).ok())
+                .unwrap_or_else(|| "N/A".to_string());
 
             seq_progress.inner.write().set_status(&format!(
                 "Total Paid: {} ETH ({} gas * avg {} gwei)\n",
@@ -430,6 +663,12 @@ pub async fn broadcast(mut self) -> Result<BroadcastedState> {
             seq_progress.inner.write().finish();
         }
 
+        if let Some(nonce_manager) = &self.script_config.nonce_manager {
+            for lease in std::mem::take(&mut self.script_config.nonce_leases) {
+                nonce_manager.release(&lease).await?;
+            }
+        }
+
         if !shell::is_json() {
             sh_println!("\n\n==========================")?;
             sh_println!("\nONCHAIN EXECUTION COMPLETE & SUCCESSFUL.")?;
@@ -443,6 +682,60 @@ pub async fn broadcast(mut self) -> Result<BroadcastedState> {
         })
     }
 
+    /// Records how many of the reserved nonces the transactions of each sender use, so that other
+    /// scripts can reserve the following ones while this one broadcasts.
+    ///
+    /// The nonces of the senders and chains that weren't reserved before the script ran are
+    /// reserved here, and must start at the nonces the transactions were simulated with.
+    async fn commit_nonces(&mut self) -> Result<()> {
+        let Some(nonce_manager) = self.script_config.nonce_manager.clone() else {
+            return Ok(());
+        };
+        for sequence in self.sequence.sequences() {
+            // The range of nonces used by each sender.
+            let mut used = AddressHashMap::<(u64, u64)>::default();
+            for tx in sequence.transactions() {
+                let (Some(from), Some(nonce)) = (tx.from(), tx.nonce()) else { continue };
+                let (first, end) = used.entry(from).or_insert((nonce, nonce + 1));
+                *first = (*first).min(nonce);
+                *end = (*end).max(nonce + 1);
+            }
+
+            let leases = &mut self.script_config.nonce_leases;
+            for (&sender, &(first, end)) in &used {
+                let lease = match leases
+                    .iter()
+                    .find(|lease| lease.chain_id == sequence.chain && lease.sender == sender)
+                {
+                    Some(lease) => lease.clone(),
+                    None => {
+                        let lease = nonce_manager.acquire(sequence.rpc_url(), sender).await?;
+                        leases.push(lease.clone());
+                        if lease.nonce != first {
+                            eyre::bail!(
+                                "the transactions of {sender} on chain {} start at nonce {first}, \
+                                 but nonce {} is the first one that isn't reserved by other \
+                                 scripts.\nRun the script again once they finish",
+                                sequence.chain,
+                                lease.nonce
+                            );
+                        }
+                        lease
+                    }
+                };
+                nonce_manager.commit(&lease, end.saturating_sub(lease.nonce)).await?;
+            }
+
+            // Senders reserved upfront without transactions on this chain don't use any nonce.
+            for lease in leases.iter().filter(|lease| {
+                lease.chain_id == sequence.chain && !used.contains_key(&lease.sender)
+            }) {
+                nonce_manager.commit(lease, 0).await?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn verify_preflight_check(&self) -> Result<()> {
         for sequence in self.sequence.sequences() {
             if self.args.verifier.verifier == VerificationProviderType::Etherscan &&
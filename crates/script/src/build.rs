@@ -286,7 +286,7 @@ pub async fn resume(self) -> Result<BundledState> {
             let mut froms = sequence.sequences().iter().flat_map(|s| {
                 s.transactions
                     .iter()
-                    .skip(s.receipts.len())
+                    .filter(|t| !t.status.is_final())
                     .map(|t| t.transaction.from().expect("from is missing in script artifact"))
             });
 
@@ -21,7 +21,7 @@
     ContractsByArtifact,
 };
 use foundry_config::{Config, NamedChain};
-use foundry_debugger::Debugger;
+use foundry_debugger::{Debugger, DumpFormat};
 use foundry_evm::{
     decode::decode_console_logs,
     inspectors::cheatcodes::BroadcastableTransactions,
@@ -495,8 +495,8 @@ pub fn run_debugger(self) -> Result<()> {
         Ok(())
     }
 
-    pub fn dump_debugger(self, path: &Path) -> Result<()> {
-        self.create_debugger().dump_to_file(path)?;
+    pub fn dump_debugger(self, path: &Path, format: DumpFormat) -> Result<()> {
+        self.create_debugger().dump_to_file_as(path, format)?;
         Ok(())
     }
 
@@ -510,8 +510,17 @@ fn create_debugger(self) -> Debugger {
                     .collect(),
             )
             .decoder(&self.execution_artifacts.decoder)
+            .storage_layouts(
+                self.build_data
+                    .build_data
+                    .output
+                    .artifact_ids()
+                    .filter_map(|(id, artifact)| Some((id.name, artifact.storage_layout.clone()?))),
+            )
             .sources(self.build_data.sources)
             .breakpoints(self.execution_result.breakpoints)
+            .config(self.script_config.config.debugger)
+            .root(self.script_config.config.root)
             .build()
     }
 }
@@ -44,6 +44,7 @@
     },
     Config,
 };
+use foundry_debugger::DumpFormat;
 use foundry_evm::{
     backend::Backend,
     executors::ExecutorBuilder,
@@ -53,6 +54,7 @@
     },
     opts::EvmOpts,
     traces::{TraceMode, Traces},
+    utils::StateChangeset,
 };
 use foundry_wallets::MultiWalletOpts;
 use serde::Serialize;
@@ -62,15 +64,22 @@
 mod build;
 mod execute;
 mod multi_sequence;
+mod nonce;
 mod progress;
 mod providers;
 mod receipts;
+mod review;
 mod runner;
 mod sequence;
 mod simulate;
 mod transaction;
+mod variance;
 mod verify;
 
+pub use broadcast::BatchBarrier;
+pub use nonce::{NonceArgs, NonceLease, NonceManager, NonceReservation, NonceStore, NonceStrategy};
+pub use variance::SimulationBlock;
+
 // Loads project's figment and merges the build cli arguments into it
 foundry_config::merge_impl_figment_convert!(ScriptArgs, build, evm);
 
@@ -124,6 +133,16 @@ pub struct ScriptArgs {
     #[arg(long, default_value = "100")]
     pub batch_size: usize,
 
+    /// What to wait for between batches of transactions, either `receipts` or `blocks:<N>` to
+    /// also wait for N confirmations of the previous batch.
+    ///
+    /// With `receipts`, the next batch is sent once the RPC serves the block of the last receipt
+    /// of the previous batch. When set, the transactions of each batch are simulated in order
+    /// against that state before sending the batch, so that the broadcast stops if the state
+    /// changed since the script was simulated.
+    #[arg(long, value_name = "BARRIER")]
+    pub barrier_on: Option<BatchBarrier>,
+
     /// Skips on-chain simulation.
     #[arg(long)]
     pub skip_simulation: bool,
@@ -167,6 +186,11 @@ pub struct ScriptArgs {
     )]
     pub dump: Option<PathBuf>,
 
+    /// The format of the debugger dump: `debugger` for the debugger data, or `json` or `csv`
+    /// for the executed steps, with their stack, memory and storage writes, and source locations.
+    #[arg(long, requires = "dump", value_name = "FORMAT", default_value_t)]
+    pub dump_format: DumpFormat,
+
     /// Makes sure a transaction is sent,
     /// only after its previous one has been confirmed and succeeded.
     #[arg(long)]
@@ -178,6 +202,28 @@ pub struct ScriptArgs {
     #[arg(long)]
     pub non_interactive: bool,
 
+    /// Review the simulated transactions in an interactive TUI before broadcasting.
+    ///
+    /// Shows each transaction with its decoded calldata, simulated state changes and gas cost,
+    /// and allows approving or skipping individual transactions.
+    #[arg(long, conflicts_with_all = &["skip_simulation", "non_interactive", "resume"])]
+    pub review: bool,
+
+    /// Also simulates the transactions against each of the given blocks, and reports the
+    /// transactions whose success, gas usage or state changes differ from the main simulation.
+    ///
+    /// Blocks are relative to the latest block: `head`, `head-N`, or `next` for a new block on
+    /// top of the latest one.
+    ///
+    /// Example: --simulate-at head-1,head,next
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_name = "BLOCKS",
+        conflicts_with_all = &["skip_simulation", "resume"]
+    )]
+    pub simulate_at: Vec<SimulationBlock>,
+
     /// The Etherscan (or equivalent) API key
     #[arg(long, env = "ETHERSCAN_API_KEY", value_name = "KEY")]
     pub etherscan_api_key: Option<String>,
@@ -216,6 +262,9 @@ pub struct ScriptArgs {
 
     #[command(flatten)]
     pub retry: RetryArgs,
+
+    #[command(flatten)]
+    pub nonce: NonceArgs,
 }
 
 impl ScriptArgs {
@@ -228,7 +277,11 @@ pub async fn preprocess(self) -> Result<PreprocessedState> {
             evm_opts.sender = sender;
         }
 
-        let script_config = ScriptConfig::new(config, evm_opts).await?;
+        let mut script_config = ScriptConfig::new(config, evm_opts).await?;
+        if self.broadcast && !self.resume {
+            script_config.nonce_manager = NonceManager::new(&self.nonce, &self.path);
+            script_config.reserve_nonces().await?;
+        }
 
         Ok(PreprocessedState { args: self, script_config, script_wallets })
     }
@@ -238,6 +291,21 @@ pub async fn run_script(self) -> Result<()> {
         trace!(target: "script", "executing script command");
 
         let state = self.preprocess().await?;
+        let nonce_manager = state.script_config.nonce_manager.clone();
+        let result = Self::run_preprocessed(state).await;
+
+        // Release the nonces reserved by a script that stopped before broadcasting.
+        if let Some(nonce_manager) = nonce_manager {
+            if let Err(err) = nonce_manager.release_open().await {
+                sh_warn!("Failed to release reserved nonces: {err}")?;
+            }
+        }
+
+        result
+    }
+
+    /// Executes the script once its config is loaded.
+    async fn run_preprocessed(state: PreprocessedState) -> Result<()> {
         let create2_deployer = state.script_config.evm_opts.create2_deployer;
         let compiled = state.compile()?;
 
@@ -260,7 +328,10 @@ pub async fn run_script(self) -> Result<()> {
 
             if pre_simulation.args.debug {
                 return match pre_simulation.args.dump.clone() {
-                    Some(path) => pre_simulation.dump_debugger(&path),
+                    Some(path) => {
+                        let format = pre_simulation.args.dump_format;
+                        pre_simulation.dump_debugger(&path, format)
+                    }
                     None => pre_simulation.run_debugger(),
                 };
             }
@@ -513,6 +584,8 @@ pub struct ScriptResult {
     pub address: Option<Address>,
     #[serde(skip)]
     pub breakpoints: Breakpoints,
+    #[serde(skip)]
+    pub state_changeset: StateChangeset,
 }
 
 impl ScriptResult {
@@ -550,6 +623,10 @@ pub struct ScriptConfig {
     pub sender_nonce: u64,
     /// Maps a rpc url to a backend
     pub backends: HashMap<String, Backend>,
+    /// Reserves the nonces of the sender when broadcasting, if enabled.
+    pub nonce_manager: Option<NonceManager>,
+    /// The nonces reserved for the sender, on every chain it broadcasts to.
+    pub nonce_leases: Vec<NonceLease>,
 }
 
 impl ScriptConfig {
@@ -561,7 +638,31 @@ pub async fn new(config: Config, evm_opts: EvmOpts) -> Result<Self> {
             1
         };
 
-        Ok(Self { config, evm_opts, sender_nonce, backends: HashMap::default() })
+        Ok(Self {
+            config,
+            evm_opts,
+            sender_nonce,
+            backends: HashMap::default(),
+            nonce_manager: None,
+            nonce_leases: Vec::new(),
+        })
+    }
+
+    /// Reserves the nonces of the sender on the main chain, starting the script from the first
+    /// reserved one.
+    pub async fn reserve_nonces(&mut self) -> Result<()> {
+        let (Some(nonce_manager), Some(fork_url)) =
+            (self.nonce_manager.as_ref(), self.evm_opts.fork_url.as_ref())
+        else {
+            return Ok(());
+        };
+        if self.evm_opts.sender == Config::DEFAULT_SENDER {
+            return Ok(());
+        }
+        let lease = nonce_manager.acquire(fork_url, self.evm_opts.sender).await?;
+        self.sender_nonce = lease.nonce;
+        self.nonce_leases.push(lease);
+        Ok(())
     }
 
     pub async fn update_sender(&mut self, sender: Address) -> Result<()> {
@@ -572,6 +673,12 @@ pub async fn update_sender(&mut self, sender: Address) -> Result<()> {
             1
         };
         self.evm_opts.sender = sender;
+        if let Some(nonce_manager) = &self.nonce_manager {
+            for lease in std::mem::take(&mut self.nonce_leases) {
+                nonce_manager.release(&lease).await?;
+            }
+            self.reserve_nonces().await?;
+        }
         Ok(())
     }
 
@@ -685,6 +792,26 @@ fn can_parse_unlocked() {
         assert!(args.is_err());
     }
 
+    #[test]
+    fn can_parse_batch_barrier() {
+        let args = ScriptArgs::parse_from(["foundry-cli", "Contract.sol"]);
+        assert_eq!(args.barrier_on, None);
+
+        let args =
+            ScriptArgs::parse_from(["foundry-cli", "Contract.sol", "--barrier-on", "blocks:3"]);
+        assert_eq!(args.barrier_on, Some(BatchBarrier::Blocks(3)));
+
+        let args =
+            ScriptArgs::parse_from(["foundry-cli", "Contract.sol", "--barrier-on", "receipts"]);
+        assert_eq!(args.barrier_on, Some(BatchBarrier::Receipts));
+        assert_eq!(BatchBarrier::Receipts.confirmations(), 0);
+        assert_eq!(BatchBarrier::Blocks(3).confirmations(), 3);
+
+        let args =
+            ScriptArgs::try_parse_from(["foundry-cli", "Contract.sol", "--barrier-on", "blocks"]);
+        assert!(args.is_err());
+    }
+
     #[test]
     fn can_merge_script_config() {
         let args = ScriptArgs::parse_from([
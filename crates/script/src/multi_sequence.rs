@@ -99,9 +99,12 @@ pub fn load(config: &Config, sig: &str, target: &ArtifactId, dry_run: bool) -> R
             foundry_compilers::utils::read_json_file(&sensitive_path)
                 .wrap_err("Multi-chain deployment sensitive details not found.")?;
 
-        sequence.deployments.iter_mut().enumerate().for_each(|(i, sequence)| {
-            sequence.fill_sensitive(&sensitive_sequence.deployments[i]);
-        });
+        for (i, deployment) in sequence.deployments.iter_mut().enumerate() {
+            deployment.fill_sensitive(&sensitive_sequence.deployments[i]);
+            deployment.fill_statuses();
+            deployment.paths =
+                Some(ScriptSequence::get_paths(config, sig, target, deployment.chain, dry_run)?);
+        }
 
         sequence.path = path;
         sequence.sensitive_path = sensitive_path;
@@ -109,9 +112,28 @@ pub fn load(config: &Config, sig: &str, target: &ArtifactId, dry_run: bool) -> R
         Ok(sequence)
     }
 
-    /// Saves the transactions as file if it's a standalone deployment.
+    /// Updates the paths of the sequence and of its deployments to not be under the dry-run
+    /// directories.
+    pub fn update_paths_to_broadcasted(
+        &mut self,
+        config: &Config,
+        sig: &str,
+        target: &ArtifactId,
+    ) -> Result<()> {
+        (self.path, self.sensitive_path) = Self::get_paths(config, sig, target, false)?;
+        for deployment in &mut self.deployments {
+            deployment.paths =
+                Some(ScriptSequence::get_paths(config, sig, target, deployment.chain, false)?);
+        }
+        Ok(())
+    }
+
+    /// Saves the transactions as file, and the deployment of every chain to the logs of its
+    /// chain.
     pub fn save(&mut self, silent: bool, save_ts: bool) -> Result<()> {
-        self.deployments.iter_mut().for_each(|sequence| sequence.sort_receipts());
+        for deployment in &mut self.deployments {
+            deployment.save(true, save_ts)?;
+        }
 
         self.timestamp = now().as_secs();
 
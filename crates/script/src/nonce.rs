@@ -0,0 +1,585 @@
+//! Reserving the nonces of a sender, so that scripts broadcasting from the same key at the same
+//! time don't use the same nonces.
+
+use alloy_eips::BlockId;
+use alloy_primitives::Address;
+use alloy_provider::Provider;
+use clap::{Parser, ValueEnum};
+use eyre::{bail, Context, Result};
+use foundry_common::{
+    fs,
+    provider::{try_get_http_provider, RetryProvider},
+    shell,
+};
+use foundry_config::Config;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// How long reserved nonces are kept if the script holding them doesn't release them.
+const RESERVATION_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// How long to wait for other scripts to release their nonces, by default.
+const DEFAULT_NONCE_WAIT: u64 = 600;
+
+/// How often to check whether other scripts released their nonces.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How long a lock on the reservations of a sender can be held before it's considered abandoned.
+const STALE_LOCK: Duration = Duration::from_secs(30);
+
+/// How nonces are chosen for the transactions of a script.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum NonceStrategy {
+    /// Use the latest nonce of the sender, without reserving it.
+    #[default]
+    Latest,
+    /// Wait for the other scripts broadcasting from the sender to finish, and use the nonces
+    /// that follow theirs.
+    Sequential,
+    /// Use the lowest nonces that aren't reserved by other scripts, without waiting for them.
+    ///
+    /// Fails before broadcasting if the transactions of the script don't fit in the gap.
+    GapFilling,
+    /// Reserve the nonces from an external allocator, shared by all the machines broadcasting
+    /// from the sender.
+    ///
+    /// The allocator is a JSON-RPC server implementing:
+    /// - `nonce_acquire(chainId, address)`, returning `{ "id": string, "nonce": quantity }`
+    /// - `nonce_commit(id, count)`, called with the number of nonces used before broadcasting
+    /// - `nonce_release(id)`, called once the transactions are included
+    External,
+}
+
+/// CLI arguments for reserving the nonces of the sender.
+#[derive(Clone, Debug, Default, Parser)]
+#[command(next_help_heading = "Nonce management")]
+pub struct NonceArgs {
+    /// How nonces are chosen when broadcasting.
+    ///
+    /// Strategies other than `latest` reserve the nonces of the sender in
+    /// `~/.foundry/cache/nonces`, so that scripts broadcasting from the same key at the same time
+    /// don't collide.
+    #[arg(long, value_enum, default_value_t, value_name = "STRATEGY")]
+    pub nonce_strategy: NonceStrategy,
+
+    /// The JSON-RPC endpoint of the nonce allocator used by the `external` strategy.
+    #[arg(
+        long,
+        value_name = "URL",
+        required_if_eq("nonce_strategy", "external"),
+        env = "FOUNDRY_NONCE_ALLOCATOR"
+    )]
+    pub nonce_allocator: Option<String>,
+
+    /// How long to wait for other scripts broadcasting from the sender, in seconds.
+    ///
+    /// Default: 600
+    #[arg(long, value_name = "SECONDS")]
+    pub nonce_wait: Option<u64>,
+}
+
+/// Nonces reserved by a script.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NonceReservation {
+    /// Identifies the reservation.
+    pub id: String,
+    /// The first reserved nonce.
+    pub start: u64,
+    /// The number of reserved nonces, or `None` until the script knows how many it needs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+    /// The script that made the reservation.
+    pub owner: String,
+    /// When the reservation expires, as a UNIX timestamp.
+    pub expires: u64,
+}
+
+impl NonceReservation {
+    /// Returns the nonce following the reserved ones.
+    ///
+    /// Reservations whose size isn't known yet hold at least their first nonce.
+    fn end(&self) -> u64 {
+        self.start + self.count.unwrap_or(1)
+    }
+
+    fn overlaps(&self, start: u64, end: u64) -> bool {
+        self.start < end && start < self.end()
+    }
+}
+
+/// The nonce reservations of a sender on a chain, stored in
+/// `~/.foundry/cache/nonces/<chain>/<address>.json`.
+#[derive(Clone, Debug)]
+pub struct NonceStore {
+    path: PathBuf,
+}
+
+impl NonceStore {
+    /// Returns the reservations of the given sender on the given chain.
+    pub fn new(chain_id: u64, sender: Address) -> Result<Self> {
+        let dir = Self::dir()?.join(chain_id.to_string());
+        Ok(Self::at(dir.join(format!("{sender}.json"))))
+    }
+
+    /// Returns the reservations stored in the given file.
+    pub fn at(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Returns the directory containing the reservations of all the senders.
+    pub fn dir() -> Result<PathBuf> {
+        Config::foundry_cache_dir()
+            .map(|dir| dir.join("nonces"))
+            .ok_or_else(|| eyre::eyre!("failed to resolve the home directory"))
+    }
+
+    /// Returns the stores of all the senders that have reservations, with their chain and
+    /// address.
+    pub fn all() -> Result<Vec<(u64, Address, Self)>> {
+        let dir = Self::dir()?;
+        let mut stores = Vec::new();
+        let Ok(chains) = std::fs::read_dir(&dir) else { return Ok(stores) };
+        for chain in chains.flatten() {
+            let Some(chain_id) = chain.file_name().to_str().and_then(|s| s.parse().ok()) else {
+                continue
+            };
+            for file in std::fs::read_dir(chain.path())?.flatten() {
+                let path = file.path();
+                if path.extension().is_none_or(|ext| ext != "json") {
+                    continue
+                }
+                let Some(sender) = path.file_stem().and_then(|s| s.to_str()?.parse().ok()) else {
+                    continue
+                };
+                stores.push((chain_id, sender, Self::at(path)));
+            }
+        }
+        stores.sort_by_key(|(chain_id, sender, _)| (*chain_id, *sender));
+        Ok(stores)
+    }
+
+    /// Returns the path of the file storing the reservations.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the reservations that haven't expired.
+    pub fn reservations(&self) -> Result<Vec<NonceReservation>> {
+        let now = unix_now();
+        Ok(self.load()?.into_iter().filter(|reservation| reservation.expires > now).collect())
+    }
+
+    /// Releases the reservation with the given id, or all of them. Returns the number of
+    /// released reservations.
+    pub fn release(&self, id: Option<&str>) -> Result<usize> {
+        self.update(|reservations| {
+            let before = reservations.len();
+            reservations.retain(|reservation| id.is_some_and(|id| reservation.id != id));
+            Ok(before - reservations.len())
+        })
+    }
+
+    fn load(&self) -> Result<Vec<NonceReservation>> {
+        if !self.path.exists() {
+            return Ok(Vec::new())
+        }
+        Ok(fs::read_json_file(&self.path)?)
+    }
+
+    /// Updates the reservations while holding the lock on them.
+    fn update<R>(&self, f: impl FnOnce(&mut Vec<NonceReservation>) -> Result<R>) -> Result<R> {
+        let _lock = self.lock()?;
+        let mut reservations = self.load()?;
+        let ret = f(&mut reservations)?;
+        fs::write_json_file(&self.path, &reservations)?;
+        Ok(ret)
+    }
+
+    fn lock(&self) -> Result<StoreLock> {
+        let path = self.path.with_extension("lock");
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let deadline = Instant::now() + STALE_LOCK;
+        loop {
+            match std::fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => return Ok(StoreLock { path }),
+                Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+                    // Locks are only held for a few milliseconds, so an old one was left behind by
+                    // a process that was killed.
+                    let is_stale = path
+                        .metadata()
+                        .and_then(|metadata| metadata.modified())
+                        .is_ok_and(|modified| modified.elapsed().is_ok_and(|age| age > STALE_LOCK));
+                    if is_stale {
+                        let _ = std::fs::remove_file(&path);
+                        continue
+                    }
+                    if Instant::now() > deadline {
+                        bail!(
+                            "timed out waiting for the lock on the nonce reservations at {}",
+                            path.display()
+                        );
+                    }
+                    std::thread::sleep(Duration::from_millis(50));
+                }
+                Err(err) => {
+                    return Err(err).wrap_err_with(|| format!("failed to lock {}", path.display()))
+                }
+            }
+        }
+    }
+}
+
+/// Removes the lock file of a [`NonceStore`] when dropped.
+struct StoreLock {
+    path: PathBuf,
+}
+
+impl Drop for StoreLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Removes the expired reservations and the ones whose nonces were all used on chain.
+fn prune(reservations: &mut Vec<NonceReservation>, confirmed: u64, now: u64) {
+    reservations.retain(|reservation| {
+        reservation.expires > now &&
+            reservation.count.is_none_or(|count| reservation.start + count > confirmed)
+    });
+}
+
+/// Returns the first nonce the given strategy can reserve, or `None` if it has to wait for other
+/// scripts first.
+fn first_free_nonce(
+    reservations: &[NonceReservation],
+    pending: u64,
+    strategy: NonceStrategy,
+) -> Option<u64> {
+    match strategy {
+        NonceStrategy::Sequential => {
+            if reservations.iter().any(|reservation| reservation.count.is_none()) {
+                return None
+            }
+            Some(reservations.iter().map(NonceReservation::end).fold(pending, u64::max))
+        }
+        _ => {
+            let mut reservations = reservations.iter().collect::<Vec<_>>();
+            reservations.sort_by_key(|reservation| reservation.start);
+            let mut nonce = pending;
+            for reservation in reservations {
+                if reservation.overlaps(nonce, nonce + 1) {
+                    nonce = reservation.end();
+                }
+            }
+            Some(nonce)
+        }
+    }
+}
+
+/// Reserves the nonces of the senders of a script.
+///
+/// Cloning is cheap and all clones track the same reservations, so that the ones that are still
+/// open when the script stops can be released.
+#[derive(Clone, Debug)]
+pub struct NonceManager {
+    args: NonceArgs,
+    owner: String,
+    open: Arc<Mutex<Vec<NonceLease>>>,
+}
+
+impl NonceManager {
+    /// Returns the manager for the given arguments, or `None` if nonces aren't reserved.
+    pub fn new(args: &NonceArgs, owner: &str) -> Option<Self> {
+        (args.nonce_strategy != NonceStrategy::Latest).then(|| Self {
+            args: args.clone(),
+            owner: format!("{owner} (pid {})", std::process::id()),
+            open: Default::default(),
+        })
+    }
+
+    /// Reserves the nonces of the sender, starting from its pending nonce, and returns the lease
+    /// holding them.
+    pub async fn acquire(&self, rpc_url: &str, sender: Address) -> Result<NonceLease> {
+        let provider = try_get_http_provider(rpc_url)?;
+        let chain_id = provider.get_chain_id().await?;
+        let lease = match self.args.nonce_strategy {
+            NonceStrategy::External => {
+                let url = self.args.nonce_allocator.as_deref().ok_or_else(|| {
+                    eyre::eyre!("the `external` nonce strategy requires `--nonce-allocator`")
+                })?;
+                let allocator = try_get_http_provider(url)?;
+                let allocated: AllocatedNonces = allocator
+                    .raw_request("nonce_acquire".into(), (chain_id, sender))
+                    .await
+                    .wrap_err_with(|| format!("failed to acquire a nonce from {url}"))?;
+                NonceLease {
+                    chain_id,
+                    sender,
+                    nonce: allocated.nonce,
+                    id: allocated.id,
+                    allocator: Some(url.to_string()),
+                }
+            }
+            strategy => {
+                let store = NonceStore::new(chain_id, sender)?;
+                let id = format!("{:x}", SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos());
+                let nonce = self.reserve(&provider, &store, sender, &id, strategy).await?;
+                NonceLease { chain_id, sender, nonce, id, allocator: None }
+            }
+        };
+        self.open.lock().push(lease.clone());
+        Ok(lease)
+    }
+
+    async fn reserve(
+        &self,
+        provider: &RetryProvider,
+        store: &NonceStore,
+        sender: Address,
+        id: &str,
+        strategy: NonceStrategy,
+    ) -> Result<u64> {
+        let wait = self.wait();
+        let deadline = Instant::now() + wait;
+        let mut waiting = false;
+        loop {
+            let confirmed = provider.get_transaction_count(sender).await?;
+            let pending =
+                provider.get_transaction_count(sender).block_id(BlockId::pending()).await?;
+            let now = unix_now();
+            let reserved = store.update(|reservations| {
+                prune(reservations, confirmed, now);
+                let start = first_free_nonce(reservations, pending, strategy);
+                if let Some(start) = start {
+                    reservations.push(NonceReservation {
+                        id: id.to_string(),
+                        start,
+                        count: None,
+                        owner: self.owner.clone(),
+                        expires: now + RESERVATION_TTL.as_secs(),
+                    });
+                }
+                Ok(start)
+            })?;
+            if let Some(nonce) = reserved {
+                return Ok(nonce)
+            }
+
+            if Instant::now() > deadline {
+                bail!(
+                    "timed out after {}s waiting for other scripts broadcasting from {sender}.\n\
+                     If none are running, list their reservations with `forge nonces list` and \
+                     release them with `forge nonces release {sender}`",
+                    wait.as_secs()
+                );
+            }
+            if !waiting && !shell::is_json() {
+                sh_println!("Waiting for other scripts broadcasting from {sender} to finish...")?;
+                waiting = true;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Waits until the transactions using the nonces before the lease are included, so that the
+    /// script queues behind the other scripts broadcasting from the sender instead of failing the
+    /// nonce checks of the broadcast.
+    pub async fn wait_for_turn(&self, provider: &RetryProvider, lease: &NonceLease) -> Result<()> {
+        let wait = self.wait();
+        let deadline = Instant::now() + wait;
+        let mut waiting = false;
+        loop {
+            let nonce = provider.get_transaction_count(lease.sender).await?;
+            if nonce >= lease.nonce {
+                return Ok(())
+            }
+            if Instant::now() > deadline {
+                bail!(
+                    "timed out after {}s waiting for the transactions of {} before nonce {} to be \
+                     included.\n{}",
+                    wait.as_secs(),
+                    lease.sender,
+                    lease.nonce,
+                    recovery_hint(lease.chain_id, lease.sender, nonce)
+                );
+            }
+            if !waiting && !shell::is_json() {
+                sh_println!(
+                    "Waiting for the transactions of other scripts from {} to be included...",
+                    lease.sender
+                )?;
+                waiting = true;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    fn wait(&self) -> Duration {
+        Duration::from_secs(self.args.nonce_wait.unwrap_or(DEFAULT_NONCE_WAIT))
+    }
+
+    /// Records the number of nonces used by the script, before broadcasting its transactions.
+    pub async fn commit(&self, lease: &NonceLease, count: u64) -> Result<()> {
+        self.open.lock().retain(|open| open.id != lease.id);
+        if let Some(url) = &lease.allocator {
+            try_get_http_provider(url)?
+                .raw_request::<_, serde_json::Value>(
+                    "nonce_commit".into(),
+                    (lease.id.clone(), count),
+                )
+                .await
+                .wrap_err_with(|| format!("failed to commit nonces to {url}"))?;
+            return Ok(())
+        }
+
+        let store = NonceStore::new(lease.chain_id, lease.sender)?;
+        let (start, end) = (lease.nonce, lease.nonce + count);
+        store.update(|reservations| {
+            let Some(index) = reservations.iter().position(|r| r.id == lease.id) else {
+                bail!(
+                    "the reservation of nonces {start}.. of {} was released while the script was \
+                     running",
+                    lease.sender
+                )
+            };
+            if let Some(other) =
+                reservations.iter().find(|r| r.id != lease.id && r.overlaps(start, end))
+            {
+                bail!(
+                    "the {count} nonces used by the script, from {start}, overlap with nonces \
+                     reserved by {}.\n\
+                     Run the script again, or use `--nonce-strategy sequential` to wait for the \
+                     other scripts to finish",
+                    other.owner
+                )
+            }
+            reservations[index].count = Some(count);
+            reservations[index].expires = unix_now() + RESERVATION_TTL.as_secs();
+            Ok(())
+        })
+    }
+
+    /// Releases the nonces of a lease once its transactions are included.
+    pub async fn release(&self, lease: &NonceLease) -> Result<()> {
+        self.open.lock().retain(|open| open.id != lease.id);
+        if let Some(url) = &lease.allocator {
+            try_get_http_provider(url)?
+                .raw_request::<_, serde_json::Value>("nonce_release".into(), (lease.id.clone(),))
+                .await
+                .wrap_err_with(|| format!("failed to release nonces to {url}"))?;
+            return Ok(())
+        }
+        NonceStore::new(lease.chain_id, lease.sender)?.release(Some(&lease.id))?;
+        Ok(())
+    }
+
+    /// Releases the leases whose size was never committed, because the script stopped before
+    /// broadcasting.
+    pub async fn release_open(&self) -> Result<()> {
+        let open = std::mem::take(&mut *self.open.lock());
+        for lease in &open {
+            self.release(lease).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Nonces of a sender reserved by a script.
+#[derive(Clone, Debug)]
+pub struct NonceLease {
+    /// The chain the nonces are reserved on.
+    pub chain_id: u64,
+    /// The sender whose nonces are reserved.
+    pub sender: Address,
+    /// The first reserved nonce.
+    pub nonce: u64,
+    id: String,
+    allocator: Option<String>,
+}
+
+/// The response of `nonce_acquire`.
+#[derive(Debug, Deserialize)]
+struct AllocatedNonces {
+    id: String,
+    #[serde(with = "alloy_serde::quantity")]
+    nonce: u64,
+}
+
+/// Returns the commands to recover from a transaction that can't be sent because of its nonce.
+pub fn recovery_hint(chain_id: u64, sender: Address, nonce: u64) -> String {
+    format!(
+        "If a transaction with nonce {nonce} of {sender} is stuck, replace it with one paying a \
+         higher gas price:\n    \
+         cast send {sender} --chain {chain_id} --nonce {nonce} --gas-price <PRICE> [WALLET]\n\
+         then run the script again with `--resume`. Nonces reserved by scripts that won't finish \
+         can be released with `forge nonces release --chain {chain_id} {sender}`"
+    )
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reservation(id: &str, start: u64, count: Option<u64>) -> NonceReservation {
+        NonceReservation { id: id.into(), start, count, owner: id.into(), expires: u64::MAX }
+    }
+
+    #[test]
+    fn first_free_nonce_by_strategy() {
+        let mut reservations = vec![reservation("a", 10, Some(3)), reservation("b", 15, Some(2))];
+        assert_eq!(first_free_nonce(&reservations, 9, NonceStrategy::Sequential), Some(17));
+        assert_eq!(first_free_nonce(&reservations, 9, NonceStrategy::GapFilling), Some(9));
+        assert_eq!(first_free_nonce(&reservations, 10, NonceStrategy::GapFilling), Some(13));
+        assert_eq!(first_free_nonce(&reservations, 20, NonceStrategy::Sequential), Some(20));
+
+        reservations.push(reservation("c", 13, None));
+        assert_eq!(first_free_nonce(&reservations, 10, NonceStrategy::Sequential), None);
+        assert_eq!(first_free_nonce(&reservations, 10, NonceStrategy::GapFilling), Some(14));
+    }
+
+    #[test]
+    fn prune_used_and_expired() {
+        let mut reservations = vec![
+            reservation("used", 10, Some(3)),
+            reservation("pending", 13, Some(2)),
+            reservation("open", 15, None),
+            NonceReservation { expires: 5, ..reservation("expired", 17, None) },
+        ];
+        prune(&mut reservations, 13, 10);
+        let ids = reservations.iter().map(|r| r.id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids, ["pending", "open"]);
+    }
+
+    #[test]
+    fn store_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = NonceStore::at(dir.path().join("1").join(format!("{}.json", Address::ZERO)));
+        assert!(store.reservations().unwrap().is_empty());
+
+        store
+            .update(|reservations| {
+                reservations.push(reservation("a", 0, Some(2)));
+                reservations.push(reservation("b", 2, None));
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(store.reservations().unwrap().len(), 2);
+        assert!(!store.path().with_extension("lock").exists());
+
+        assert_eq!(store.release(Some("a")).unwrap(), 1);
+        assert_eq!(store.reservations().unwrap(), [reservation("b", 2, None)]);
+        assert_eq!(store.release(None).unwrap(), 1);
+        assert!(store.reservations().unwrap().is_empty());
+    }
+}
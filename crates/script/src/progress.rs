@@ -4,6 +4,7 @@
     map::{B256HashMap, HashMap},
     B256,
 };
+use alloy_provider::Provider;
 use eyre::Result;
 use forge_script_sequence::ScriptSequence;
 use foundry_cli::utils::init_progress;
@@ -62,8 +63,8 @@ pub fn new(sequence_idx: usize, sequence: &ScriptSequence, multi: MultiProgress)
             txs.enable_steady_tick(Duration::from_millis(1000));
             receipts.enable_steady_tick(Duration::from_millis(1000));
 
-            txs.set_position(sequence.receipts.len() as u64);
-            receipts.set_position(sequence.receipts.len() as u64);
+            txs.set_position(sequence.finished() as u64);
+            receipts.set_position(sequence.finished() as u64);
 
             Self { top_spinner, txs, receipts, tx_spinners: Default::default(), multi }
         };
@@ -180,13 +181,17 @@ pub fn get_sequence_progress(
     /// node's mempool, we wait for its receipt to be available. If the transaction
     /// has not confirmed, and cannot be found in the mempool, we remove it from
     /// the `deploy_sequence.pending` vector so that it will be rebroadcast in
-    /// later steps.
+    /// later steps, unless its nonce was used by another transaction.
+    ///
+    /// Dropped transactions are reported as errors unless `resend_dropped` is set, i.e. the caller
+    /// sends them again.
     pub async fn wait_for_pending(
         &self,
         sequence_idx: usize,
         deployment_sequence: &mut ScriptSequence,
         provider: &RetryProvider,
         timeout: u64,
+        resend_dropped: bool,
     ) -> Result<()> {
         if deployment_sequence.pending.is_empty() {
             return Ok(());
@@ -216,9 +221,21 @@ pub async fn wait_for_pending(
                     seq_progress.inner.write().finish_tx_spinner(tx_hash);
                 }
                 Ok(TxStatus::Dropped) => {
-                    // We want to remove it from pending so it will be re-broadcast.
-                    deployment_sequence.remove_pending(tx_hash);
-                    errors.push(format!("Transaction dropped from the mempool: {tx_hash:?}"));
+                    if is_nonce_used(deployment_sequence, tx_hash, provider).await? {
+                        // The nonce can't be used again, so the transaction is never re-broadcast.
+                        deployment_sequence.mark_replaced(tx_hash);
+                        errors.push(format!(
+                            "Transaction replaced by another transaction with the same nonce: \
+                             {tx_hash:?}"
+                        ));
+                    } else {
+                        // We want to remove it from pending so it will be re-broadcast.
+                        deployment_sequence.mark_dropped(tx_hash);
+                        if !resend_dropped {
+                            errors
+                                .push(format!("Transaction dropped from the mempool: {tx_hash:?}"));
+                        }
+                    }
 
                     seq_progress.inner.write().finish_tx_spinner(tx_hash);
                 }
@@ -232,11 +249,9 @@ pub async fn wait_for_pending(
                     deployment_sequence.add_receipt(receipt);
                 }
                 Ok(TxStatus::Revert(receipt)) => {
-                    // consider:
-                    // if this is not removed from pending, then the script becomes
-                    // un-resumable. Is this desirable on reverts?
+                    // The reverted transaction used its nonce, so it's not sent again on resume.
                     warn!(tx_hash=?tx_hash, "Transaction Failure");
-                    deployment_sequence.remove_pending(receipt.transaction_hash);
+                    deployment_sequence.mark_reverted(receipt.transaction_hash);
 
                     let msg = format_receipt(deployment_sequence.chain.into(), &receipt);
                     seq_progress.inner.write().finish_tx_spinner_with_msg(tx_hash, &msg)?;
@@ -249,7 +264,7 @@ pub async fn wait_for_pending(
         // print any errors
         if !errors.is_empty() {
             let mut error_msg = errors.join("\n");
-            if !deployment_sequence.pending.is_empty() {
+            if deployment_sequence.finished() < deployment_sequence.transactions.len() {
                 error_msg += "\n\n Add `--resume` to your command to try and continue broadcasting
         the transactions."
             }
@@ -259,3 +274,16 @@ pub async fn wait_for_pending(
         Ok(())
     }
 }
+
+/// Returns `true` if the sender of the transaction already used its nonce on chain.
+async fn is_nonce_used(
+    sequence: &ScriptSequence,
+    tx_hash: B256,
+    provider: &RetryProvider,
+) -> Result<bool> {
+    let Some(tx) = sequence.transactions.iter().find(|tx| tx.hash == Some(tx_hash)) else {
+        return Ok(false)
+    };
+    let (Some(from), Some(nonce)) = (tx.tx().from(), tx.tx().nonce()) else { return Ok(false) };
+    Ok(provider.get_transaction_count(from).await? > nonce)
+}
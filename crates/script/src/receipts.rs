@@ -39,7 +39,7 @@ pub async fn check_tx_status(
             {
                 Ok(receipt) => Ok(receipt.into()),
                 Err(e) => match provider.get_transaction_by_hash(hash).await {
-                    Ok(_) => match e {
+                    Ok(Some(_)) => match e {
                         PendingTransactionError::TxWatcher(WatchTxError::Timeout) => {
                             Err(RetryError::Continue(eyre!(
                                 "tx is still known to the node, waiting for receipt"
@@ -47,7 +47,7 @@ pub async fn check_tx_status(
                         }
                         _ => Err(RetryError::Retry(e.into())),
                     },
-                    Err(_) => Ok(TxStatus::Dropped),
+                    Ok(None) | Err(_) => Ok(TxStatus::Dropped),
                 },
             }
         })
@@ -0,0 +1,425 @@
+//! Interactive review of the simulated transactions before broadcasting.
+
+use alloy_primitives::{map::HashMap, utils::format_units, Address, U256};
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use eyre::Result;
+use forge_script_sequence::TransactionWithMetadata;
+use foundry_evm::{
+    traces::{SparsedTraceArena, TraceKind},
+    utils::StateChangeset,
+};
+use ratatui::{
+    backend::{Backend, CrosstermBackend},
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame, Terminal,
+};
+use revm_inspectors::{tracing::TraceWriter, ColorChoice};
+use std::{collections::VecDeque, io, ops::ControlFlow};
+
+/// A simulated transaction, as presented in the review screen.
+#[derive(Clone, Debug, Default)]
+pub struct ReviewEntry {
+    /// Short description of the transaction.
+    pub title: String,
+    /// Sender, target, decoded calldata and gas cost.
+    pub details: Vec<String>,
+    /// Contract creations, value transfers and storage changes observed during simulation.
+    pub state_changes: Vec<String>,
+    /// The rendered, decoded simulation trace.
+    pub trace: String,
+}
+
+impl ReviewEntry {
+    /// Creates a new entry from a simulated transaction.
+    ///
+    /// `traces` must already be decoded. `gas_price` is used to compute the maximum cost of the
+    /// transaction, if known.
+    pub fn new(
+        tx: &TransactionWithMetadata,
+        gas_used: u64,
+        traces: &[(TraceKind, SparsedTraceArena)],
+        state_changeset: &StateChangeset,
+        labels: &HashMap<Address, String>,
+        gas_price: Option<U256>,
+    ) -> Self {
+        let name = |address: Address| match labels.get(&address) {
+            Some(label) => format!("{label} ({address})"),
+            None => address.to_string(),
+        };
+
+        let contract = tx.contract_name.as_deref().unwrap_or("<unknown>");
+        let title = match (&tx.function, tx.opcode.is_any_create()) {
+            (_, true) => format!("{} {contract}", tx.opcode),
+            (Some(function), false) => format!("{contract}::{function}"),
+            (None, false) => format!("{contract}::fallback"),
+        };
+
+        let mut details = Vec::new();
+        let inner = tx.tx();
+        if let Some(from) = inner.from() {
+            details.push(format!("From:      {}", name(from)));
+        }
+        if let Some(address) = tx.contract_address {
+            let label = if tx.opcode.is_any_create() { "Creates:" } else { "To:" };
+            details.push(format!("{label:<10} {}", name(address)));
+        }
+        if let Some(nonce) = inner.nonce() {
+            details.push(format!("Nonce:     {nonce}"));
+        }
+        let value = inner.value().unwrap_or_default();
+        if !value.is_zero() {
+            details.push(format!("Value:     {} ETH", format_ether(value)));
+        }
+        if let Some(function) = &tx.function {
+            details.push(format!("Function:  {function}"));
+        }
+        for (i, arg) in tx.arguments.iter().flatten().enumerate() {
+            details.push(format!("  [{i}]:    {arg}"));
+        }
+        details.push(format!("RPC:       {}", tx.rpc));
+
+        let gas_limit = inner.gas().unwrap_or_default();
+        details.push(format!("Gas used:  {gas_used} (limit {gas_limit})"));
+        if let Some(gas_price) = gas_price {
+            let cost = gas_price.saturating_mul(U256::from(gas_limit));
+            details.push(format!("Max cost:  {} ETH", format_ether(cost)));
+        }
+
+        let mut state_changes = Vec::new();
+        for (_, arena) in traces {
+            for node in arena.arena.nodes() {
+                let trace = &node.trace;
+                if !trace.success {
+                    continue;
+                }
+                if trace.kind.is_any_create() {
+                    state_changes.push(format!("Created {}", name(trace.address)));
+                }
+                if !trace.value.is_zero() {
+                    state_changes.push(format!(
+                        "Transferred {} ETH from {} to {}",
+                        format_ether(trace.value),
+                        name(trace.caller),
+                        name(trace.address),
+                    ));
+                }
+            }
+        }
+        let mut accounts = state_changeset.iter().collect::<Vec<_>>();
+        accounts.sort_by_key(|(address, _)| **address);
+        for (address, account) in accounts {
+            let mut slots = account.changed_storage_slots().collect::<Vec<_>>();
+            if slots.is_empty() {
+                continue;
+            }
+            slots.sort_by_key(|(slot, _)| **slot);
+            state_changes.push(format!("Storage of {}:", name(*address)));
+            for (slot, value) in slots {
+                state_changes.push(format!(
+                    "  {slot:#x}: {:#x} → {:#x}",
+                    value.original_value, value.present_value
+                ));
+            }
+        }
+
+        let trace = traces.iter().map(|(_, arena)| render_plain(arena)).collect();
+
+        Self { title, details, state_changes, trace }
+    }
+}
+
+/// The result of a review.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReviewOutcome {
+    /// The user confirmed the selection. Contains whether each transaction was approved.
+    Confirmed(Vec<bool>),
+    /// The user aborted the script.
+    Aborted,
+}
+
+/// The state of the review screen.
+#[derive(Debug)]
+pub struct Review {
+    entries: Vec<ReviewEntry>,
+    approved: Vec<bool>,
+    selected: usize,
+    show_trace: bool,
+    scroll: u16,
+}
+
+impl Review {
+    /// Creates a new review with all transactions approved.
+    pub fn new(entries: Vec<ReviewEntry>) -> Self {
+        let approved = vec![true; entries.len()];
+        Self { entries, approved, selected: 0, show_trace: false, scroll: 0 }
+    }
+
+    /// Runs the review TUI until the user confirms or aborts.
+    pub fn run(mut self) -> Result<ReviewOutcome> {
+        let backend = CrosstermBackend::new(io::stdout());
+        let terminal = Terminal::new(backend)?;
+        TerminalGuard::with(terminal, |terminal| loop {
+            terminal.draw(|f| self.draw(f))?;
+            if let Event::Key(key) = event::read()? {
+                if let ControlFlow::Break(outcome) = self.handle_key(key) {
+                    return Ok(outcome);
+                }
+            }
+        })
+    }
+
+    /// Handles a key press.
+    fn handle_key(&mut self, key: KeyEvent) -> ControlFlow<ReviewOutcome> {
+        if key.kind != KeyEventKind::Press {
+            return ControlFlow::Continue(());
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return ControlFlow::Break(ReviewOutcome::Aborted),
+            KeyCode::Enter => {
+                return ControlFlow::Break(ReviewOutcome::Confirmed(self.approved.clone()))
+            }
+            KeyCode::Char('k') | KeyCode::Up => self.select(self.selected.saturating_sub(1)),
+            KeyCode::Char('j') | KeyCode::Down => self.select(self.selected + 1),
+            KeyCode::Char(' ') => {
+                if let Some(approved) = self.approved.get_mut(self.selected) {
+                    *approved = !*approved;
+                }
+            }
+            KeyCode::Char('a') => self.approved.fill(true),
+            KeyCode::Char('n') => self.approved.fill(false),
+            KeyCode::Char('t') => {
+                self.show_trace = !self.show_trace;
+                self.scroll = 0;
+            }
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(10),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn select(&mut self, index: usize) {
+        let index = index.min(self.entries.len().saturating_sub(1));
+        if index != self.selected {
+            self.selected = index;
+            self.scroll = 0;
+        }
+    }
+
+    fn draw(&self, f: &mut Frame<'_>) {
+        let [main, help] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .areas(f.area());
+        let [list, details] = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+            .areas(main);
+
+        let n_approved = self.approved.iter().filter(|approved| **approved).count();
+        let items = self
+            .entries
+            .iter()
+            .zip(&self.approved)
+            .enumerate()
+            .map(|(i, (entry, approved))| {
+                let (mark, color) =
+                    if *approved { ("[x]", Color::Green) } else { ("[ ]", Color::Red) };
+                ListItem::new(Line::from(vec![
+                    Span::styled(mark, Style::new().fg(color)),
+                    Span::raw(format!(" {i}. {}", entry.title)),
+                ]))
+            })
+            .collect::<Vec<_>>();
+        let title = format!(" Transactions ({n_approved}/{} approved) ", self.entries.len());
+        let list_widget = List::new(items)
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        f.render_stateful_widget(list_widget, list, &mut state);
+
+        let entry = &self.entries[self.selected];
+        let (title, lines) = if self.show_trace {
+            (" Trace ", entry.trace.lines().map(|l| Line::raw(l.to_string())).collect())
+        } else {
+            let mut lines = entry.details.iter().map(|l| Line::raw(l.as_str())).collect::<Vec<_>>();
+            lines.push(Line::raw(""));
+            lines.push(Line::styled("State changes:", Style::new().add_modifier(Modifier::BOLD)));
+            if entry.state_changes.is_empty() {
+                lines.push(Line::raw("  none"));
+            }
+            lines.extend(entry.state_changes.iter().map(|l| Line::raw(format!("  {l}"))));
+            (" Details ", lines)
+        };
+        let paragraph = Paragraph::new(lines)
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0));
+        f.render_widget(paragraph, details);
+
+        let help_text = "[↑/↓] select | [space] approve/skip | [a] approve all | [n] skip all | \
+                         [t] toggle trace | [pgup/pgdown] scroll | [enter] confirm | [q] abort";
+        f.render_widget(Paragraph::new(help_text).style(Style::new().fg(Color::DarkGray)), help);
+    }
+}
+
+/// Removes the skipped transactions and lowers the nonces of the following transactions from the
+/// same sender on the same chain, so that they can still be broadcast in sequence.
+///
+/// Fails if a pre-signed transaction would need a different nonce.
+pub fn apply_review(
+    transactions: VecDeque<TransactionWithMetadata>,
+    approved: &[bool],
+) -> Result<VecDeque<TransactionWithMetadata>> {
+    let mut skipped = HashMap::<(String, Address), u64>::default();
+    let mut kept = VecDeque::with_capacity(transactions.len());
+    for (mut tx, approved) in transactions.into_iter().zip(approved.iter().copied()) {
+        let from = tx.tx().from().unwrap_or_default();
+        let key = (tx.rpc.clone(), from);
+        if !approved {
+            *skipped.entry(key).or_default() += 1;
+            continue;
+        }
+
+        let n_skipped = skipped.get(&key).copied().unwrap_or_default();
+        if n_skipped > 0 {
+            let Some(unsigned) = tx.tx_mut().as_unsigned_mut() else {
+                eyre::bail!(
+                    "cannot skip transactions of {from} that precede a pre-signed transaction"
+                );
+            };
+            if let Some(nonce) = unsigned.nonce.as_mut() {
+                *nonce -= n_skipped;
+            }
+        }
+        kept.push_back(tx);
+    }
+    Ok(kept)
+}
+
+/// Renders a trace arena without colors.
+fn render_plain(arena: &SparsedTraceArena) -> String {
+    let mut w = TraceWriter::new(Vec::<u8>::new()).use_colors(ColorChoice::Never);
+    if w.write_arena(&arena.resolve_arena()).is_err() {
+        return String::new();
+    }
+    String::from_utf8_lossy(&w.into_writer()).into_owned()
+}
+
+fn format_ether(value: U256) -> String {
+    format_units(value, 18).unwrap_or_else(|_| value.to_string())
+}
+
+/// Handles terminal state.
+#[must_use]
+struct TerminalGuard<B: Backend + io::Write> {
+    terminal: Terminal<B>,
+}
+
+impl<B: Backend + io::Write> TerminalGuard<B> {
+    fn with<T>(terminal: Terminal<B>, f: impl FnOnce(&mut Terminal<B>) -> T) -> T {
+        let mut guard = Self { terminal };
+        let _ = enable_raw_mode();
+        let _ = execute!(*guard.terminal.backend_mut(), EnterAlternateScreen);
+        let _ = guard.terminal.hide_cursor();
+        let _ = guard.terminal.clear();
+        f(&mut guard.terminal)
+    }
+}
+
+impl<B: Backend + io::Write> Drop for TerminalGuard<B> {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(*self.terminal.backend_mut(), LeaveAlternateScreen);
+        let _ = self.terminal.show_cursor();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_rpc_types::TransactionRequest;
+    use alloy_serde::WithOtherFields;
+    use crossterm::event::KeyModifiers;
+    use foundry_common::TransactionMaybeSigned;
+
+    fn tx(rpc: &str, from: Address, nonce: u64) -> TransactionWithMetadata {
+        let request = TransactionRequest::default().from(from).nonce(nonce);
+        let mut tx = TransactionWithMetadata::from_tx_request(TransactionMaybeSigned::new(
+            WithOtherFields::new(request),
+        ));
+        tx.rpc = rpc.to_string();
+        tx
+    }
+
+    fn press(review: &mut Review, code: KeyCode) -> ControlFlow<ReviewOutcome> {
+        review.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn toggles_transactions() {
+        let mut review = Review::new(vec![ReviewEntry::default(); 3]);
+        assert!(press(&mut review, KeyCode::Down).is_continue());
+        assert!(press(&mut review, KeyCode::Char(' ')).is_continue());
+        // Selection is clamped to the last transaction.
+        for _ in 0..5 {
+            let _ = press(&mut review, KeyCode::Char('j'));
+        }
+        let _ = press(&mut review, KeyCode::Char(' '));
+        assert_eq!(
+            press(&mut review, KeyCode::Enter),
+            ControlFlow::Break(ReviewOutcome::Confirmed(vec![true, false, false]))
+        );
+
+        let _ = press(&mut review, KeyCode::Char('n'));
+        assert_eq!(
+            press(&mut review, KeyCode::Enter),
+            ControlFlow::Break(ReviewOutcome::Confirmed(vec![false; 3]))
+        );
+        let _ = press(&mut review, KeyCode::Char('a'));
+        assert_eq!(
+            press(&mut review, KeyCode::Enter),
+            ControlFlow::Break(ReviewOutcome::Confirmed(vec![true; 3]))
+        );
+        assert_eq!(
+            press(&mut review, KeyCode::Char('q')),
+            ControlFlow::Break(ReviewOutcome::Aborted)
+        );
+    }
+
+    #[test]
+    fn shifts_nonces_of_skipped_transactions() {
+        let alice = Address::repeat_byte(0xa);
+        let bob = Address::repeat_byte(0xb);
+        let txs = VecDeque::from([
+            tx("mainnet", alice, 0),
+            tx("mainnet", bob, 5),
+            tx("mainnet", alice, 1),
+            tx("optimism", alice, 0),
+            tx("mainnet", alice, 2),
+            tx("mainnet", bob, 6),
+        ]);
+
+        let kept = apply_review(txs, &[false, true, false, true, true, true]).unwrap();
+        let nonces = kept
+            .iter()
+            .map(|tx| (tx.rpc.as_str(), tx.tx().from().unwrap(), tx.tx().nonce().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            nonces,
+            [
+                ("mainnet", bob, 5),
+                ("optimism", alice, 0),
+                ("mainnet", alice, 0),
+                ("mainnet", bob, 6)
+            ]
+        );
+    }
+}
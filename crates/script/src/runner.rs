@@ -252,7 +252,8 @@ pub fn simulate(
                 value.unwrap_or(U256::ZERO),
                 None,
             );
-            let (address, RawCallResult { gas_used, logs, traces, .. }) = match res {
+            let (address, RawCallResult { gas_used, logs, traces, state_changeset, .. }) = match res
+            {
                 Ok(DeployResult { address, raw }) => (address, raw),
                 Err(EvmError::Execution(err)) => {
                     let ExecutionErr { raw, reason } = *err;
@@ -272,6 +273,7 @@ pub fn simulate(
                     .map(|traces| vec![(TraceKind::Execution, traces)])
                     .unwrap_or_default(),
                 address: Some(address),
+                state_changeset,
                 ..Default::default()
             })
         } else {
@@ -317,7 +319,16 @@ fn call(
             res = self.executor.transact_raw(from, to, calldata, value)?;
         }
 
-        let RawCallResult { result, reverted, logs, traces, labels, transactions, .. } = res;
+        let RawCallResult {
+            result,
+            reverted,
+            logs,
+            traces,
+            labels,
+            transactions,
+            state_changeset,
+            ..
+        } = res;
         let breakpoints = res.cheatcodes.map(|cheats| cheats.breakpoints).unwrap_or_default();
 
         Ok(ScriptResult {
@@ -336,6 +347,7 @@ fn call(
             transactions,
             address: None,
             breakpoints,
+            state_changeset,
         })
     }
 
@@ -83,10 +83,7 @@ pub fn update_paths_to_broadcasted(
                 sequence.paths =
                     Some(ScriptSequence::get_paths(config, sig, target, sequence.chain, false)?);
             }
-            Self::Multi(sequence) => {
-                (sequence.path, sequence.sensitive_path) =
-                    MultiChainSequence::get_paths(config, sig, target, false)?;
-            }
+            Self::Multi(sequence) => sequence.update_paths_to_broadcasted(config, sig, target)?,
         };
 
         Ok(())
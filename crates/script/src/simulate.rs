@@ -6,7 +6,9 @@
     broadcast::{estimate_gas, BundledState},
     build::LinkedBuildData,
     execute::{ExecutionArtifacts, ExecutionData},
+    review::{apply_review, Review, ReviewEntry, ReviewOutcome},
     sequence::get_commit_hash,
+    variance::{self, SimulationOutcome},
     ScriptArgs, ScriptConfig, ScriptResult,
 };
 use alloy_network::TransactionBuilder;
@@ -133,7 +135,7 @@ pub async fn simulate_and_fill(
                     .wrap_err("Internal EVM error during simulation")?;
 
                 if !result.success {
-                    return Ok((None, false, result.traces));
+                    return Ok((None, false, result));
                 }
 
                 // Simulate mining the transaction if the user passes `--slow`.
@@ -151,7 +153,7 @@ pub async fn simulate_and_fill(
                     .with_execution_result(&result, self.args.gas_estimate_multiplier)
                     .build();
 
-                eyre::Ok((Some(transaction), is_noop_tx, result.traces))
+                eyre::Ok((Some(transaction), is_noop_tx, result))
             })
             .collect::<Vec<_>>();
 
@@ -160,18 +162,44 @@ pub async fn simulate_and_fill(
             sh_println!("Simulated On-chain Traces:\n")?;
         }
 
+        let review = self.args.review && self.args.should_broadcast();
+        let mut review_entries = Vec::new();
+        let mut outcomes = Vec::new();
+
         let mut abort = false;
         for res in join_all(futs).await {
-            let (tx, is_noop_tx, mut traces) = res?;
+            let (tx, is_noop_tx, mut result) = res?;
 
             // Transaction will be `None`, if execution didn't pass.
-            if tx.is_none() || self.script_config.evm_opts.verbosity > 3 {
-                for (_, trace) in &mut traces {
+            let print_traces = tx.is_none() || self.script_config.evm_opts.verbosity > 3;
+            if print_traces || review {
+                for (_, trace) in &mut result.traces {
                     decode_trace_arena(trace, &self.execution_artifacts.decoder).await?;
-                    sh_println!("{}", render_trace_arena(trace))?;
+                    if print_traces {
+                        sh_println!("{}", render_trace_arena(trace))?;
+                    }
                 }
             }
 
+            if !self.args.simulate_at.is_empty() {
+                outcomes.push(SimulationOutcome::new(
+                    result.success,
+                    result.gas_used,
+                    &result.state_changeset,
+                ));
+            }
+
+            if let (true, Some(tx)) = (review, &tx) {
+                review_entries.push(ReviewEntry::new(
+                    tx,
+                    result.gas_used,
+                    &result.traces,
+                    &result.state_changeset,
+                    &self.execution_artifacts.decoder.labels,
+                    self.args.with_gas_price,
+                ));
+            }
+
             if let Some(tx) = tx {
                 if is_noop_tx {
                     let to = tx.contract_address.unwrap();
@@ -200,9 +228,72 @@ pub async fn simulate_and_fill(
             eyre::bail!("Simulated execution failed.")
         }
 
+        if !self.args.simulate_at.is_empty() {
+            self.simulate_variance(final_txs.make_contiguous(), &outcomes).await?;
+        }
+
+        if review {
+            final_txs = self.review(final_txs, review_entries)?;
+        }
+
         Ok(final_txs)
     }
 
+    /// Simulates the transactions against each of the `--simulate-at` blocks, and reports the
+    /// transactions whose outcome differs from the main simulation.
+    async fn simulate_variance(
+        &self,
+        transactions: &[TransactionWithMetadata],
+        reference: &[SimulationOutcome],
+    ) -> Result<()> {
+        let blocks = &self.args.simulate_at;
+        if !shell::is_json() {
+            sh_println!("\n## Simulating at {} more blocks.", blocks.len())?;
+        }
+
+        let futs = blocks.iter().map(|block| {
+            variance::simulate_at(&self.script_config, *block, transactions, self.args.slow)
+        });
+        let outcomes = try_join_all(futs).await?;
+
+        variance::report(transactions, reference, blocks, &outcomes)
+    }
+
+    /// Lets the user review the simulated transactions and approve or skip each of them.
+    ///
+    /// Skipped transactions are removed, and the nonces of the following transactions from the
+    /// same sender are adjusted accordingly.
+    fn review(
+        &self,
+        transactions: VecDeque<TransactionWithMetadata>,
+        entries: Vec<ReviewEntry>,
+    ) -> Result<VecDeque<TransactionWithMetadata>> {
+        let approved = match Review::new(entries).run()? {
+            ReviewOutcome::Confirmed(approved) => approved,
+            ReviewOutcome::Aborted => eyre::bail!("User canceled the script."),
+        };
+
+        let n_skipped = approved.iter().filter(|approved| !**approved).count();
+        if n_skipped == approved.len() {
+            eyre::bail!("No transactions were approved for broadcasting.");
+        }
+        if n_skipped > 0 {
+            let skips_create = transactions
+                .iter()
+                .zip(&approved)
+                .any(|(tx, approved)| !approved && tx.opcode.is_any_create());
+            if skips_create {
+                sh_warn!(
+                    "Skipped transactions include contract creations; the addresses of contracts \
+                     created later by the same sender will differ from the simulation."
+                )?;
+            }
+            sh_println!("\nSkipping {n_skipped} of {} transactions.", approved.len())?;
+        }
+
+        apply_review(transactions, &approved)
+    }
+
     /// Build mapping from contract address to its ABI, code and contract name.
     fn build_address_to_abi_map(&self) -> BTreeMap<Address, &ContractData> {
         self.execution_artifacts
@@ -267,16 +358,15 @@ pub async fn bundle(self) -> Result<BundledState> {
 
         let mut total_gas_per_rpc: HashMap<String, u128> = HashMap::default();
 
-        // Batches sequence of transactions from different rpcs.
-        let mut new_sequence = VecDeque::new();
+        // The transactions sent to every chain, in the order the chains were first used. Each
+        // chain's transactions are broadcast together, even if the script switched forks in
+        // between, so the order of transactions across chains is not kept.
         let mut manager = ProvidersManager::default();
-        let mut sequences = vec![];
-
-        // Peeking is used to check if the next rpc url is different. If so, it creates a
-        // [`ScriptSequence`] from all the collected transactions up to this point.
-        let mut txes_iter = self.transactions.clone().into_iter().peekable();
+        let mut transactions_per_chain: Vec<(u64, VecDeque<TransactionWithMetadata>)> = vec![];
+        let mut last_chain = None;
+        let mut reordered = false;
 
-        while let Some(mut tx) = txes_iter.next() {
+        for mut tx in self.transactions.clone() {
             let tx_rpc = tx.rpc.to_owned();
             let provider_info = manager.get_or_init_provider(&tx.rpc, self.args.legacy).await?;
 
@@ -320,27 +410,34 @@ pub async fn bundle(self) -> Result<BundledState> {
                     }
                 }
 
-                let total_gas = total_gas_per_rpc.entry(tx_rpc.clone()).or_insert(0);
+                let total_gas = total_gas_per_rpc.entry(tx_rpc).or_insert(0);
                 *total_gas += tx.gas().expect("gas is set");
             }
 
-            new_sequence.push_back(tx);
-            // We only create a [`ScriptSequence`] object when we collect all the rpc related
-            // transactions.
-            if let Some(next_tx) = txes_iter.peek() {
-                if next_tx.rpc == tx_rpc {
-                    continue;
+            let chain = provider_info.chain;
+            match transactions_per_chain.iter_mut().find(|(c, _)| *c == chain) {
+                Some((_, transactions)) => {
+                    reordered |= last_chain != Some(chain);
+                    transactions.push_back(tx);
                 }
+                None => transactions_per_chain.push((chain, VecDeque::from([tx]))),
             }
+            last_chain = Some(chain);
+        }
 
-            let sequence =
-                self.create_sequence(is_multi_deployment, provider_info.chain, new_sequence)?;
-
-            sequences.push(sequence);
-
-            new_sequence = VecDeque::new();
+        if reordered {
+            sh_warn!(
+                "The script switches back to a chain it already sent transactions to. \
+                 The transactions of every chain are broadcast together, so transactions on \
+                 different chains will not be sent in the order the script created them."
+            )?;
         }
 
+        let mut sequences = transactions_per_chain
+            .into_iter()
+            .map(|(chain, transactions)| self.create_sequence(chain, transactions))
+            .collect::<Result<Vec<_>>>()?;
+
         if !self.args.skip_simulation {
             // Present gas information on a per RPC basis.
             for (rpc, total_gas) in total_gas_per_rpc {
@@ -409,23 +506,17 @@ pub async fn bundle(self) -> Result<BundledState> {
     /// Creates a [ScriptSequence] object from the given transactions.
     fn create_sequence(
         &self,
-        multi: bool,
         chain: u64,
         transactions: VecDeque<TransactionWithMetadata>,
     ) -> Result<ScriptSequence> {
-        // Paths are set to None for multi-chain sequences parts, because they don't need to be
-        // saved to a separate file.
-        let paths = if multi {
-            None
-        } else {
-            Some(ScriptSequence::get_paths(
-                &self.script_config.config,
-                &self.args.sig,
-                &self.build_data.build_data.target,
-                chain,
-                !self.args.broadcast,
-            )?)
-        };
+        // The sequences of multi-chain deployments are also saved to the logs of their chain.
+        let paths = Some(ScriptSequence::get_paths(
+            &self.script_config.config,
+            &self.args.sig,
+            &self.build_data.build_data.target,
+            chain,
+            !self.args.broadcast,
+        )?);
 
         let commit = get_commit_hash(&self.script_config.config.root);
 
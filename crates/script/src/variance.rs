@@ -0,0 +1,255 @@
+//! Simulation of the script transactions against several base blocks, to find transactions whose
+//! outcome depends on the block they are included in.
+
+use crate::ScriptConfig;
+use alloy_primitives::{Address, Bytes, TxKind, U256};
+use alloy_provider::Provider;
+use eyre::{Context, Result};
+use forge_script_sequence::TransactionWithMetadata;
+use foundry_cli::utils::now;
+use foundry_common::{provider::try_get_http_provider, shell};
+use foundry_evm::utils::StateChangeset;
+use itertools::Itertools;
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+/// A block to simulate the script transactions against, relative to the latest block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SimulationBlock {
+    /// The latest block, or the block this many blocks before it.
+    Head(u64),
+    /// A new block on top of the latest one, with the current time as timestamp.
+    Next,
+}
+
+impl FromStr for SimulationBlock {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "head" => Ok(Self::Head(0)),
+            "next" => Ok(Self::Next),
+            _ => match s.strip_prefix("head-").and_then(|n| n.parse().ok()) {
+                Some(n) => Ok(Self::Head(n)),
+                None => Err(format!("invalid block `{s}`, expected `head`, `head-N` or `next`")),
+            },
+        }
+    }
+}
+
+impl fmt::Display for SimulationBlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Head(0) => f.write_str("head"),
+            Self::Head(n) => write!(f, "head-{n}"),
+            Self::Next => f.write_str("next"),
+        }
+    }
+}
+
+/// The outcome of a simulated transaction, as compared across blocks.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SimulationOutcome {
+    pub success: bool,
+    pub gas_used: u64,
+    /// The balance and changed storage slots of every touched account.
+    pub state: BTreeMap<Address, (U256, BTreeMap<U256, U256>)>,
+}
+
+impl SimulationOutcome {
+    pub fn new(success: bool, gas_used: u64, state_changeset: &StateChangeset) -> Self {
+        let state = state_changeset
+            .iter()
+            .filter(|(_, account)| account.is_touched())
+            .map(|(address, account)| {
+                let slots = account
+                    .changed_storage_slots()
+                    .map(|(slot, value)| (*slot, value.present_value))
+                    .collect();
+                (*address, (account.info.balance, slots))
+            })
+            .collect();
+        Self { success, gas_used, state }
+    }
+
+    /// Describes how this outcome differs from the reference one, if at all.
+    fn diff(&self, reference: &Self) -> Option<String> {
+        let mut diffs = Vec::new();
+        if self.success != reference.success {
+            diffs.push(if self.success { "succeeds" } else { "reverts" }.to_string());
+        }
+        if self.gas_used != reference.gas_used {
+            let delta = self.gas_used as i128 - reference.gas_used as i128;
+            diffs.push(format!("gas used {} ({delta:+})", self.gas_used));
+        }
+        if self.state != reference.state {
+            diffs.push("state changes differ".to_string());
+        }
+        (!diffs.is_empty()).then(|| diffs.join(", "))
+    }
+}
+
+/// Simulates the transactions against the given block of each RPC used in the script, in order.
+///
+/// Returns the outcome of each transaction.
+pub async fn simulate_at(
+    script_config: &ScriptConfig,
+    block: SimulationBlock,
+    transactions: &[TransactionWithMetadata],
+    slow: bool,
+) -> Result<Vec<SimulationOutcome>> {
+    let mut outcomes = vec![SimulationOutcome::default(); transactions.len()];
+
+    for rpc in transactions.iter().map(|tx| tx.rpc.as_str()).unique() {
+        let provider = try_get_http_provider(rpc)?;
+        let head = provider.get_block_number().await.wrap_err("failed to get latest block")?;
+        let number = match block {
+            SimulationBlock::Head(n) => head.checked_sub(n).ok_or_else(|| {
+                eyre::eyre!("cannot simulate at {block}, the latest block is {head}")
+            })?,
+            SimulationBlock::Next => head,
+        };
+
+        // Every block needs its own backend, so none of the cached ones can be reused.
+        let mut script_config = script_config.clone();
+        script_config.backends.clear();
+        script_config.evm_opts.fork_url = Some(rpc.to_string());
+        script_config.evm_opts.fork_block_number = Some(number);
+        let mut runner = script_config.get_runner().await?;
+
+        if block == SimulationBlock::Next {
+            let env = runner.executor.env_mut();
+            env.block.number += U256::from(1);
+            env.block.timestamp =
+                U256::from(now().as_secs()).max(env.block.timestamp + U256::from(1));
+        }
+
+        for (i, transaction) in transactions.iter().enumerate().filter(|(_, tx)| tx.rpc == rpc) {
+            let tx = transaction.tx();
+            let to = if let Some(TxKind::Call(to)) = tx.to() { Some(to) } else { None };
+            let result = runner
+                .simulate(
+                    tx.from().expect("transaction doesn't have a `from` address at execution time"),
+                    to,
+                    tx.input().map(Bytes::copy_from_slice),
+                    tx.value(),
+                    tx.authorization_list(),
+                )
+                .wrap_err_with(|| format!("Internal EVM error during simulation at {block}"))?;
+
+            if slow {
+                runner.executor.env_mut().block.number += U256::from(1);
+            }
+
+            outcomes[i] =
+                SimulationOutcome::new(result.success, result.gas_used, &result.state_changeset);
+        }
+    }
+
+    Ok(outcomes)
+}
+
+/// Reports the transactions whose outcome at any of the `blocks` differs from the `reference`
+/// simulation.
+///
+/// `outcomes` contains the outcome of every transaction for each of the `blocks`.
+pub fn report(
+    transactions: &[TransactionWithMetadata],
+    reference: &[SimulationOutcome],
+    blocks: &[SimulationBlock],
+    outcomes: &[Vec<SimulationOutcome>],
+) -> Result<()> {
+    let mut sensitive = Vec::new();
+    for (i, (tx, reference)) in transactions.iter().zip(reference).enumerate() {
+        let diffs = blocks
+            .iter()
+            .zip(outcomes)
+            .filter_map(|(block, outcomes)| {
+                outcomes[i].diff(reference).map(|diff| (block.to_string(), diff))
+            })
+            .collect::<Vec<_>>();
+        if !diffs.is_empty() {
+            sensitive.push((i, tx, diffs));
+        }
+    }
+
+    if shell::is_json() {
+        let sensitive = sensitive
+            .iter()
+            .map(|(i, tx, diffs)| {
+                let diffs = diffs
+                    .iter()
+                    .map(|(block, diff)| serde_json::json!({ "block": block, "difference": diff }))
+                    .collect::<Vec<_>>();
+                serde_json::json!({
+                    "index": i,
+                    "transaction": title(tx),
+                    "rpc": tx.rpc,
+                    "differences": diffs,
+                })
+            })
+            .collect::<Vec<_>>();
+        sh_println!("{}", serde_json::json!({ "block_sensitive_transactions": sensitive }))?;
+        return Ok(())
+    }
+
+    let blocks = blocks.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+    sh_println!("\n## Block variance ({blocks})")?;
+    if sensitive.is_empty() {
+        sh_println!("\nNo block-sensitive transactions found.")?;
+        return Ok(())
+    }
+
+    for (i, tx, diffs) in &sensitive {
+        sh_println!("\n[{i}] {}", title(tx))?;
+        for (block, diff) in diffs {
+            sh_println!("  {block}: {diff}")?;
+        }
+    }
+    sh_warn!(
+        "{} of {} transactions have a different outcome depending on the block they are \
+         included in.",
+        sensitive.len(),
+        transactions.len()
+    )?;
+    Ok(())
+}
+
+/// Returns a short description of the transaction.
+fn title(tx: &TransactionWithMetadata) -> String {
+    let contract = tx.contract_name.as_deref().unwrap_or("<unknown>");
+    match (&tx.function, tx.opcode.is_any_create()) {
+        (_, true) => format!("{} {contract}", tx.opcode),
+        (Some(function), false) => format!("{contract}::{function}"),
+        (None, false) => format!("{contract}::fallback"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_simulation_block() {
+        assert_eq!("head".parse(), Ok(SimulationBlock::Head(0)));
+        assert_eq!("head-2".parse(), Ok(SimulationBlock::Head(2)));
+        assert_eq!("next".parse(), Ok(SimulationBlock::Next));
+        assert!("head+1".parse::<SimulationBlock>().is_err());
+        assert!("latest".parse::<SimulationBlock>().is_err());
+
+        assert_eq!(SimulationBlock::Head(1).to_string(), "head-1");
+        assert_eq!(SimulationBlock::Head(0).to_string(), "head");
+    }
+
+    #[test]
+    fn diffs_outcomes() {
+        let reference = SimulationOutcome { success: true, gas_used: 50_000, ..Default::default() };
+        assert_eq!(reference.diff(&reference), None);
+
+        let reverted = SimulationOutcome { success: false, gas_used: 30_000, ..Default::default() };
+        assert_eq!(reverted.diff(&reference).as_deref(), Some("reverts, gas used 30000 (-20000)"));
+
+        let mut changed = reference.clone();
+        changed.state.insert(Address::ZERO, (U256::ZERO, BTreeMap::new()));
+        assert_eq!(changed.diff(&reference).as_deref(), Some("state changes differ"));
+    }
+}
@@ -77,6 +77,7 @@ pub fn new(
             lib_paths: project.paths.libraries.clone(),
             hardhat: config.profile == Config::HARDHAT_PROFILE,
             config_path: if config_path.exists() { Some(config_path) } else { None },
+            strict_config: config.strict_config,
         };
 
         let via_ir = config.via_ir;
@@ -188,10 +189,16 @@ async fn verify_contracts(
         let mut future_verifications = Vec::with_capacity(sequence.receipts.len());
         let mut unverifiable_contracts = vec![];
 
-        // Make sure the receipts have the right order first.
-        sequence.sort_receipts();
+        for tx in &sequence.transactions {
+            // Transactions that reverted or were replaced have no receipt.
+            let Some(receipt) = sequence
+                .receipts
+                .iter_mut()
+                .find(|receipt| tx.hash == Some(receipt.transaction_hash))
+            else {
+                continue
+            };
 
-        for (receipt, tx) in sequence.receipts.iter_mut().zip(sequence.transactions.iter()) {
             // create2 hash offset
             let mut offset = 0;
 
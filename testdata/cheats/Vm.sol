@@ -155,6 +155,7 @@ interface Vm {
     function attachDelegation(SignedDelegation calldata signedDelegation) external;
     function blobBaseFee(uint256 newBlobBaseFee) external;
     function blobhashes(bytes32[] calldata hashes) external;
+    function blockAt(uint256 timestamp) external returns (uint256 blockNumber);
     function breakpoint(string calldata char) external pure;
     function breakpoint(string calldata char, bool value) external pure;
     function broadcastRawTransaction(bytes calldata data) external;
@@ -180,6 +181,7 @@ interface Vm {
     function createSelectFork(string calldata urlOrAlias) external returns (uint256 forkId);
     function createSelectFork(string calldata urlOrAlias, uint256 blockNumber) external returns (uint256 forkId);
     function createSelectFork(string calldata urlOrAlias, bytes32 txHash) external returns (uint256 forkId);
+    function createTempDir() external returns (string memory path);
     function createWallet(string calldata walletLabel) external returns (Wallet memory wallet);
     function createWallet(uint256 privateKey) external returns (Wallet memory wallet);
     function createWallet(uint256 privateKey, string calldata walletLabel) external returns (Wallet memory wallet);
@@ -188,6 +190,8 @@ interface Vm {
     function deleteSnapshots() external;
     function deleteStateSnapshot(uint256 snapshotId) external returns (bool success);
     function deleteStateSnapshots() external;
+    function deployCodeTo(string calldata artifactPath, address target) external;
+    function deployCodeTo(string calldata artifactPath, bytes calldata constructorArgs, address target) external;
     function deployCode(string calldata artifactPath) external returns (address deployedAddress);
     function deployCode(string calldata artifactPath, bytes calldata constructorArgs) external returns (address deployedAddress);
     function deriveKey(string calldata mnemonic, uint32 index) external pure returns (uint256 privateKey);
@@ -249,6 +253,9 @@ interface Vm {
     function expectEmit(bool checkTopic1, bool checkTopic2, bool checkTopic3, bool checkData, address emitter, uint64 count) external;
     function expectEmit(uint64 count) external;
     function expectEmit(address emitter, uint64 count) external;
+    function expectGasUsed(uint64 minGas, uint64 maxGas) external;
+    function expectGasUsed(string calldata name, uint64 tolerance) external;
+    function expectGasUsed(string calldata group, string calldata name, uint64 tolerance) external;
     function expectPartialRevert(bytes4 revertData) external;
     function expectPartialRevert(bytes4 revertData, address reverter) external;
     function expectRevert() external;
@@ -265,6 +272,7 @@ interface Vm {
     function expectRevert(address reverter, uint64 count) external;
     function expectSafeMemory(uint64 min, uint64 max) external;
     function expectSafeMemoryCall(uint64 min, uint64 max) external;
+    function expectValidSignature(address account, bytes32 digest) external;
     function fee(uint256 newBasefee) external;
     function ffi(string[] calldata commandInput) external returns (bytes memory result);
     function fsMetadata(string calldata path) external view returns (FsMetadata memory metadata);
@@ -317,6 +325,8 @@ interface Vm {
     function mockCall(address callee, uint256 msgValue, bytes calldata data, bytes calldata returnData) external;
     function mockCall(address callee, bytes4 data, bytes calldata returnData) external;
     function mockCall(address callee, uint256 msgValue, bytes4 data, bytes calldata returnData) external;
+    function mockCallsRevert(address callee, bytes calldata data, bytes[] calldata revertData) external;
+    function mockCallsRevert(address callee, uint256 msgValue, bytes calldata data, bytes[] calldata revertData) external;
     function mockCalls(address callee, bytes calldata data, bytes[] calldata returnData) external;
     function mockCalls(address callee, uint256 msgValue, bytes calldata data, bytes[] calldata returnData) external;
     function mockFunction(address callee, address target, bytes calldata data) external;
@@ -345,6 +355,7 @@ interface Vm {
     function parseJsonUintArray(string calldata json, string calldata key) external pure returns (uint256[] memory);
     function parseJson(string calldata json) external pure returns (bytes memory abiEncodedData);
     function parseJson(string calldata json, string calldata key) external pure returns (bytes memory abiEncodedData);
+    function parseTimestamp(string calldata timestamp) external view returns (uint256 unixTimestamp);
     function parseTomlAddress(string calldata toml, string calldata key) external pure returns (address);
     function parseTomlAddressArray(string calldata toml, string calldata key) external pure returns (address[] memory);
     function parseTomlBool(string calldata toml, string calldata key) external pure returns (bool);
@@ -411,6 +422,7 @@ interface Vm {
     function resetNonce(address account) external;
     function resumeGasMetering() external;
     function resumeTracing() external view;
+    function retryOnRpcError(uint256 maxRetries) external;
     function revertTo(uint256 snapshotId) external returns (bool success);
     function revertToAndDelete(uint256 snapshotId) external returns (bool success);
     function revertToState(uint256 snapshotId) external returns (bool success);
@@ -418,15 +430,19 @@ interface Vm {
     function revokePersistent(address account) external;
     function revokePersistent(address[] calldata accounts) external;
     function roll(uint256 newHeight) external;
+    function rollForkTo(string calldata timestamp) external;
     function rollFork(uint256 blockNumber) external;
     function rollFork(bytes32 txHash) external;
     function rollFork(uint256 forkId, uint256 blockNumber) external;
     function rollFork(uint256 forkId, bytes32 txHash) external;
+    function rpcJson(string calldata method, string calldata params) external returns (string memory json);
+    function rpcJson(string calldata urlOrAlias, string calldata method, string calldata params) external returns (string memory json);
     function rpcUrl(string calldata rpcAlias) external view returns (string memory json);
     function rpcUrlStructs() external view returns (Rpc[] memory urls);
     function rpcUrls() external view returns (string[2][] memory urls);
     function rpc(string calldata method, string calldata params) external returns (bytes memory data);
     function rpc(string calldata urlOrAlias, string calldata method, string calldata params) external returns (bytes memory data);
+    function scratchDir() external returns (string memory path);
     function selectFork(uint256 forkId) external;
     function serializeAddress(string calldata objectKey, string calldata valueKey, address value) external returns (string memory json);
     function serializeAddress(string calldata objectKey, string calldata valueKey, address[] calldata values) external returns (string memory json);
@@ -457,11 +473,14 @@ interface Vm {
     function signCompact(bytes32 digest) external pure returns (bytes32 r, bytes32 vs);
     function signCompact(address signer, bytes32 digest) external pure returns (bytes32 r, bytes32 vs);
     function signDelegation(address implementation, uint256 privateKey) external returns (SignedDelegation memory signedDelegation);
+    function signErc1271(uint256 privateKey, bytes32 digest) external pure returns (bytes memory signature);
+    function signErc1271(uint256 privateKey, address account, bytes32 digest, string calldata accountType) external view returns (bytes memory signature);
     function signP256(uint256 privateKey, bytes32 digest) external pure returns (bytes32 r, bytes32 s);
     function sign(Wallet calldata wallet, bytes32 digest) external returns (uint8 v, bytes32 r, bytes32 s);
     function sign(uint256 privateKey, bytes32 digest) external pure returns (uint8 v, bytes32 r, bytes32 s);
     function sign(bytes32 digest) external pure returns (uint8 v, bytes32 r, bytes32 s);
     function sign(address signer, bytes32 digest) external pure returns (uint8 v, bytes32 r, bytes32 s);
+    function skipUnlessEnv(string calldata name) external;
     function skip(bool skipTest) external;
     function skip(bool skipTest, string calldata reason) external;
     function sleep(uint256 duration) external;
@@ -513,6 +532,7 @@ interface Vm {
     function txGasPrice(uint256 newGasPrice) external;
     function unixTime() external view returns (uint256 milliseconds);
     function warp(uint256 newTimestamp) external;
+    function warpTo(string calldata timestamp) external;
     function writeFile(string calldata path, string calldata data) external;
     function writeFileBinary(string calldata path, bytes calldata data) external;
     function writeJson(string calldata json, string calldata path) external;
@@ -431,6 +431,20 @@ contract MultiChainBroadcastNoLink is DSTest {
         vm.broadcast(address(ACCOUNT_B));
         new NoLink();
     }
+
+    function deployInterleaved(string memory sforkA, string memory sforkB) public {
+        uint256 forkA = vm.createSelectFork(sforkA);
+        vm.broadcast(address(ACCOUNT_A));
+        new NoLink();
+
+        vm.createSelectFork(sforkB);
+        vm.broadcast(address(ACCOUNT_A));
+        new NoLink();
+
+        vm.selectFork(forkA);
+        vm.broadcast(address(ACCOUNT_A));
+        new NoLink();
+    }
 }
 
 contract MultiChainBroadcastLink is DSTest {
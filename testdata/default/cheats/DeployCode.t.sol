@@ -16,6 +16,19 @@ contract TestContractWithArgs {
     }
 }
 
+contract TestContractWithImmutables {
+    address public immutable self;
+    uint256 public immutable value;
+    address public deployer;
+
+    constructor(uint256 _value) {
+        require(_value != 0, "zero value");
+        self = address(this);
+        value = _value;
+        deployer = msg.sender;
+    }
+}
+
 contract DeployCodeTest is DSTest {
     Vm constant vm = Vm(HEVM_ADDRESS);
 
@@ -39,4 +52,30 @@ contract DeployCodeTest is DSTest {
         assertEq(withDeployCode.a(), 3);
         assertEq(withDeployCode.b(), 4);
     }
+
+    function testDeployCodeTo() public {
+        address target = address(0xC0FFEE);
+        vm.deployCodeTo("cheats/DeployCode.t.sol:TestContract", target);
+
+        assertEq(target.code, address(new TestContract()).code);
+    }
+
+    function testDeployCodeToRunsConstructorAtTarget() public {
+        address target = address(0xC0FFEE);
+        vm.deployCodeTo("cheats/DeployCode.t.sol:TestContractWithImmutables", abi.encode(42), target);
+
+        TestContractWithImmutables deployed = TestContractWithImmutables(target);
+        assertEq(deployed.self(), target);
+        assertEq(deployed.value(), 42);
+        assertEq(deployed.deployer(), address(this));
+        assertEq(vm.getNonce(target), 1);
+    }
+
+    function testDeployCodeToRevertsWithConstructorError() public {
+        address target = address(0xC0FFEE);
+        vm._expectCheatcodeRevert("zero value");
+        vm.deployCodeTo("cheats/DeployCode.t.sol:TestContractWithImmutables", abi.encode(0), target);
+
+        assertEq(target.code.length, 0);
+    }
 }
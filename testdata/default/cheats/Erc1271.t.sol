@@ -0,0 +1,117 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity ^0.8.18;
+
+import "ds-test/test.sol";
+import "cheats/Vm.sol";
+
+abstract contract OwnedAccount {
+    address public owner;
+
+    constructor(address _owner) {
+        owner = _owner;
+    }
+
+    function recover(bytes32 hash, bytes memory signature) internal pure returns (address) {
+        (bytes32 r, bytes32 s) = abi.decode(signature, (bytes32, bytes32));
+        return ecrecover(hash, uint8(signature[64]), r, s);
+    }
+
+    function result(bool valid) internal pure returns (bytes4) {
+        return valid ? bytes4(0x1626ba7e) : bytes4(0xffffffff);
+    }
+}
+
+contract EcdsaAccount is OwnedAccount {
+    constructor(address _owner) OwnedAccount(_owner) {}
+
+    function isValidSignature(bytes32 hash, bytes calldata signature) external view returns (bytes4) {
+        return result(recover(hash, signature) == owner);
+    }
+}
+
+contract SafeAccount is OwnedAccount {
+    constructor(address _owner) OwnedAccount(_owner) {}
+
+    function isValidSignature(bytes32 hash, bytes calldata signature) external view returns (bytes4) {
+        bytes32 domainSeparator = keccak256(
+            abi.encode(keccak256("EIP712Domain(uint256 chainId,address verifyingContract)"), block.chainid, this)
+        );
+        bytes32 safeMessageHash =
+            keccak256(abi.encode(keccak256("SafeMessage(bytes message)"), keccak256(abi.encode(hash))));
+        bytes32 messageHash = keccak256(abi.encodePacked(bytes1(0x19), bytes1(0x01), domainSeparator, safeMessageHash));
+        return result(recover(messageHash, signature) == owner);
+    }
+}
+
+contract CoinbaseAccount is OwnedAccount {
+    struct SignatureWrapper {
+        uint256 ownerIndex;
+        bytes signatureData;
+    }
+
+    constructor(address _owner) OwnedAccount(_owner) {}
+
+    function isValidSignature(bytes32 hash, bytes calldata signature) external view returns (bytes4) {
+        bytes32 domainSeparator = keccak256(
+            abi.encode(
+                keccak256("EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)"),
+                keccak256("Coinbase Smart Wallet"),
+                keccak256("1"),
+                block.chainid,
+                this
+            )
+        );
+        bytes32 structHash = keccak256(abi.encode(keccak256("CoinbaseSmartWalletMessage(bytes32 hash)"), hash));
+        bytes32 replaySafeHash = keccak256(abi.encodePacked(bytes1(0x19), bytes1(0x01), domainSeparator, structHash));
+        SignatureWrapper memory wrapper = abi.decode(signature, (SignatureWrapper));
+        return result(wrapper.ownerIndex == 0 && recover(replaySafeHash, wrapper.signatureData) == owner);
+    }
+}
+
+contract Erc1271Test is DSTest {
+    Vm constant vm = Vm(HEVM_ADDRESS);
+
+    uint256 constant PRIVATE_KEY = 0xa11ce;
+    bytes32 constant DIGEST = keccak256("message");
+
+    function testSignErc1271Ecdsa() public {
+        EcdsaAccount account = new EcdsaAccount(vm.addr(PRIVATE_KEY));
+
+        bytes memory signature = vm.signErc1271(PRIVATE_KEY, DIGEST);
+        assertEq(signature.length, 65);
+        assertEq(account.isValidSignature(DIGEST, signature), bytes4(0x1626ba7e));
+
+        (uint8 v, bytes32 r, bytes32 s) = vm.sign(PRIVATE_KEY, DIGEST);
+        assertEq(signature, abi.encodePacked(r, s, v));
+        assertEq(vm.signErc1271(PRIVATE_KEY, address(account), DIGEST, "ecdsa"), signature);
+    }
+
+    function testSignErc1271Safe() public {
+        SafeAccount account = new SafeAccount(vm.addr(PRIVATE_KEY));
+
+        bytes memory signature = vm.signErc1271(PRIVATE_KEY, address(account), DIGEST, "safe");
+        assertEq(account.isValidSignature(DIGEST, signature), bytes4(0x1626ba7e));
+
+        // The signature is bound to the chain.
+        vm.chainId(10);
+        assertEq(account.isValidSignature(DIGEST, signature), bytes4(0xffffffff));
+    }
+
+    function testSignErc1271Coinbase() public {
+        CoinbaseAccount account = new CoinbaseAccount(vm.addr(PRIVATE_KEY));
+
+        bytes memory signature = vm.signErc1271(PRIVATE_KEY, address(account), DIGEST, "coinbase");
+        assertEq(account.isValidSignature(DIGEST, signature), bytes4(0x1626ba7e));
+
+        // The signature is bound to the account.
+        CoinbaseAccount other = new CoinbaseAccount(vm.addr(PRIVATE_KEY));
+        assertEq(other.isValidSignature(DIGEST, signature), bytes4(0xffffffff));
+    }
+
+    function testExpectValidSignature() public {
+        EcdsaAccount account = new EcdsaAccount(vm.addr(PRIVATE_KEY));
+
+        vm.expectValidSignature(address(account), DIGEST);
+        account.isValidSignature(DIGEST, vm.signErc1271(PRIVATE_KEY, DIGEST));
+    }
+}
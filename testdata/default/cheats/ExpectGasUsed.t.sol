@@ -0,0 +1,61 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity ^0.8.18;
+
+import "ds-test/test.sol";
+import "cheats/Vm.sol";
+
+contract Target {
+    uint256 public value;
+
+    function set(uint256 _value) public {
+        value = _value;
+    }
+
+    function get() public view returns (uint256) {
+        return value;
+    }
+}
+
+contract ExpectGasUsedTest is DSTest {
+    Vm constant vm = Vm(HEVM_ADDRESS);
+
+    Target target;
+
+    function setUp() public {
+        target = new Target();
+    }
+
+    function testExpectGasUsedRange() public {
+        vm.expectGasUsed(1, 100_000);
+        target.set(1);
+    }
+
+    function testExpectGasUsedExact() public {
+        target.get();
+        uint64 gasUsed = vm.lastCallGas().gasTotalUsed;
+
+        vm.expectGasUsed(gasUsed, gasUsed);
+        target.get();
+    }
+
+    function testExpectGasUsedOnlyNextCall() public {
+        target.set(1);
+        uint64 gasUsed = vm.lastCallGas().gasTotalUsed;
+
+        vm.expectGasUsed(gasUsed, gasUsed);
+        target.set(2);
+        // The expectation is consumed by the previous call.
+        target.set(0);
+    }
+}
+
+contract ExpectGasUsedIsolatedTest is DSTest {
+    Vm constant vm = Vm(HEVM_ADDRESS);
+
+    function testExpectGasUsedIncludesIntrinsicCost() public {
+        Target target = new Target();
+        // Each call is executed as a separate transaction, which costs at least 21000 gas.
+        vm.expectGasUsed(21_000, 100_000);
+        target.get();
+    }
+}
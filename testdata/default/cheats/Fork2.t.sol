@@ -240,6 +240,43 @@ contract ForkTest is DSTest {
         string memory param = string.concat('["0xe1a0fba63292976050b2fbf4379a1901691355ed138784b4e0d1854b4cf9193e"]');
         vm.rpc("sepolia", "eth_getTransactionByHash", param);
     }
+
+    function testRpcJson() public {
+        vm.selectFork(mainnetFork);
+        string memory file = vm.readFile("fixtures/Rpc/balance_params.json");
+        assertEq(vm.rpcJson("eth_getBalance", file), '"0x10b7c11bcb51e6"');
+
+        string memory json = vm.rpcJson("mainnet", "eth_getBlockByNumber", '["0x112a880", false]');
+        assertEq(vm.parseJsonUint(json, ".number"), 18_000_000);
+    }
+
+    function testBlockAt() public {
+        uint256 forkedBlock = 14608400;
+        vm.createSelectFork("mainnet", forkedBlock);
+        uint256 timestamp = block.timestamp;
+
+        assertEq(vm.blockAt(timestamp), forkedBlock);
+        assertEq(vm.blockAt(timestamp - 1), forkedBlock - 1);
+        // The search doesn't go past the block the fork is pinned to.
+        assertEq(vm.blockAt(timestamp + 365 days), forkedBlock);
+        vm.roll(forkedBlock + 100);
+        assertEq(vm.blockAt(timestamp + 365 days), forkedBlock);
+    }
+
+    function testRollForkTo() public {
+        vm.createSelectFork("mainnet", 14608400);
+        uint256 timestamp = block.timestamp;
+        uint256 expected = vm.blockAt(timestamp - 1 hours);
+
+        vm.rollForkTo("-1 hour");
+        assertEq(block.number, expected);
+        assertLe(block.timestamp, timestamp - 1 hours);
+
+        // Unlike `blockAt`, rolling can go past the block the fork was pinned to.
+        vm.rollForkTo("+1 day");
+        assertGt(block.number, 14608400);
+        assertLe(block.timestamp, timestamp + 1 days - 1 hours);
+    }
 }
 
 contract DummyContract {
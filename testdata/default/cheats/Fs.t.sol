@@ -202,6 +202,33 @@ contract FsTest is DSTest {
         vm.fsMetadata(child);
     }
 
+    function testCreateTempDir() public {
+        string memory dir = vm.createTempDir();
+        assertTrue(vm.isDir(dir));
+
+        string memory path = string.concat(dir, "/file.txt");
+        vm.writeFile(path, "hello");
+        assertEq(vm.readFile(path), "hello");
+
+        // Each call creates a new directory.
+        string memory other = vm.createTempDir();
+        assertTrue(keccak256(bytes(other)) != keccak256(bytes(dir)));
+        assertFalse(vm.exists(string.concat(other, "/file.txt")));
+    }
+
+    function testScratchDir() public {
+        string memory dir = vm.scratchDir();
+        assertTrue(vm.isDir(dir));
+
+        string memory path = string.concat(dir, "/file.txt");
+        vm.writeFile(path, "hello");
+        assertEq(vm.readFile(path), "hello");
+
+        // Every call of a test returns the same directory.
+        assertEq(vm.scratchDir(), dir);
+        assertTrue(keccak256(bytes(vm.createTempDir())) != keccak256(bytes(dir)));
+    }
+
     function testFsMetadata() public {
         Vm.FsMetadata memory metadata = vm.fsMetadata("fixtures/File");
         assertEq(metadata.isDir, true);
@@ -56,4 +56,22 @@ contract MockCallsTest is DSTest {
         (, bytes memory ret3) = mockErc20.call(data);
         assertEq(abi.decode(ret3, (uint256)), 6.423 ether);
     }
+
+    function testMockCallsRevert() public {
+        address mockErc20 = vm.addr(vm.randomUint());
+        bytes memory data = abi.encodeWithSignature("transfer(address,uint256)", address(this), 1 ether);
+        bytes[] memory reverts = new bytes[](2);
+        reverts[0] = "first";
+        reverts[1] = "second";
+        vm.mockCallsRevert(mockErc20, data, reverts);
+        (bool success1, bytes memory ret1) = mockErc20.call(data);
+        assertTrue(!success1);
+        assertEq(ret1, bytes("first"));
+        (bool success2, bytes memory ret2) = mockErc20.call(data);
+        assertTrue(!success2);
+        assertEq(ret2, bytes("second"));
+        (bool success3, bytes memory ret3) = mockErc20.call(data);
+        assertTrue(!success3);
+        assertEq(ret3, bytes("second"));
+    }
 }
@@ -31,6 +31,25 @@ contract SkipTest is DSTest {
         revert("This test should fail");
     }
 
+    function testSkipUnlessEnv() public {
+        vm.skipUnlessEnv("FOUNDRY_SKIP_TEST_UNSET_VAR");
+        revert("Should not reach this revert");
+    }
+
+    function testRetryOnRpcError() public {
+        vm.retryOnRpcError(3);
+        assertTrue(true);
+    }
+
+    function testRetryOnRpcErrorNotTopLevel() public {
+        vm._expectCheatcodeRevert("`retryOnRpcError` can only be used at test level");
+        this.retryOnRpcError();
+    }
+
+    function retryOnRpcError() external {
+        vm.retryOnRpcError(3);
+    }
+
     function statefulFuzzSkip() public {
         vm.skip(true);
         require(true == false, "Test should not reach invariant");
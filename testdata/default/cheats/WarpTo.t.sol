@@ -0,0 +1,38 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity ^0.8.18;
+
+import "ds-test/test.sol";
+import "cheats/Vm.sol";
+
+contract WarpToTest is DSTest {
+    Vm constant vm = Vm(HEVM_ADDRESS);
+
+    function testWarpToDateTime() public {
+        vm.warpTo("2025-06-01T00:00:00Z");
+        assertEq(vm.getBlockTimestamp(), 1748736000, "warpTo date-time failed");
+
+        vm.warpTo("2025-06-01");
+        assertEq(vm.getBlockTimestamp(), 1748736000, "warpTo date failed");
+    }
+
+    function testWarpToRelative() public {
+        vm.warp(1000000);
+        vm.warpTo("+30 days");
+        assertEq(vm.getBlockTimestamp(), 1000000 + 30 days, "warpTo forward failed");
+
+        vm.warpTo("-1 week 2h");
+        assertEq(vm.getBlockTimestamp(), 1000000 + 30 days - 1 weeks - 2 hours, "warpTo backward failed");
+    }
+
+    function testParseTimestamp() public {
+        vm.warp(100);
+        assertEq(vm.parseTimestamp("+1 minute"), 160);
+        assertEq(vm.parseTimestamp("1970-01-02T00:00:00Z"), 1 days);
+    }
+
+    /// forge-config: default.allow_internal_expect_revert = true
+    function testRevertIfInvalidTimestamp() public {
+        vm.expectRevert();
+        vm.warpTo("next tuesday");
+    }
+}
@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity ^0.8.18;
+
+import "ds-test/test.sol";
+
+contract FuzzCoverageGuidedTest is DSTest {
+    // Reverts only for 0xb59c4d, which is checked one bit at a time: random inputs reach it
+    // once in 2^24 runs, but every bit that matches increases the edge coverage.
+    function testMagicBits(uint24 x) public pure {
+        if (x & 0x000001 == 0) return;
+        if (x & 0x000002 != 0) return;
+        if (x & 0x000004 == 0) return;
+        if (x & 0x000008 == 0) return;
+        if (x & 0x000010 != 0) return;
+        if (x & 0x000020 != 0) return;
+        if (x & 0x000040 == 0) return;
+        if (x & 0x000080 != 0) return;
+        if (x & 0x000100 != 0) return;
+        if (x & 0x000200 != 0) return;
+        if (x & 0x000400 == 0) return;
+        if (x & 0x000800 == 0) return;
+        if (x & 0x001000 == 0) return;
+        if (x & 0x002000 != 0) return;
+        if (x & 0x004000 != 0) return;
+        if (x & 0x008000 == 0) return;
+        if (x & 0x010000 == 0) return;
+        if (x & 0x020000 != 0) return;
+        if (x & 0x040000 == 0) return;
+        if (x & 0x080000 != 0) return;
+        if (x & 0x100000 == 0) return;
+        if (x & 0x200000 == 0) return;
+        if (x & 0x400000 != 0) return;
+        if (x & 0x800000 == 0) return;
+        revert("magic bits found");
+    }
+}